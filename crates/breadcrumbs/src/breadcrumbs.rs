@@ -1,12 +1,15 @@
 use editor::Editor;
 use gpui::{
-    Element, EventEmitter, FocusableView, IntoElement, ParentElement, Render, StyledText,
-    Subscription, ViewContext,
+    AnchorCorner, Element, EventEmitter, FocusableView, IntoElement, ParentElement, Render,
+    StyledText, Subscription, View, ViewContext,
 };
+use image_viewer::ImageView;
 use itertools::Itertools;
 use std::cmp;
 use theme::ActiveTheme;
-use ui::{prelude::*, ButtonLike, ButtonStyle, Label, Tooltip};
+use ui::{
+    prelude::*, ButtonLike, ButtonStyle, ContextMenu, IconPosition, Label, PopoverMenu, Tooltip,
+};
 use workspace::{
     item::{BreadcrumbText, ItemEvent, ItemHandle},
     ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView,
@@ -82,6 +85,9 @@ impl Render for Breadcrumbs {
         });
 
         let breadcrumbs_stack = h_flex().gap_1().children(breadcrumbs);
+        if let Some(image_view) = active_item.downcast::<ImageView>() {
+            return element.child(render_sibling_images_menu(image_view, breadcrumbs_stack));
+        }
         match active_item
             .downcast::<Editor>()
             .map(|editor| editor.downgrade())
@@ -124,6 +130,53 @@ impl Render for Breadcrumbs {
     }
 }
 
+/// Wraps an image's breadcrumbs in a dropdown for switching to the other images in its
+/// directory.
+fn render_sibling_images_menu(
+    image_view: View<ImageView>,
+    breadcrumbs_stack: Div,
+) -> impl IntoElement {
+    PopoverMenu::new("sibling-images")
+        .trigger(
+            ButtonLike::new("toggle sibling images")
+                .child(breadcrumbs_stack)
+                .style(ButtonStyle::Transparent)
+                .tooltip(|cx| Tooltip::text("Show images in this directory", cx)),
+        )
+        .menu(move |cx| {
+            let image_view = image_view.clone();
+            let current = image_view.project_path(cx);
+            let siblings = image_view.read(cx).sibling_images(cx);
+            Some(ContextMenu::build(cx, move |mut menu, _| {
+                for sibling in siblings {
+                    let file_name = sibling
+                        .path
+                        .file_name()
+                        .map(|file_name| file_name.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                    let toggled = current.as_ref() == Some(&sibling);
+                    let image_view = image_view.downgrade();
+                    menu = menu.toggleable_entry(
+                        file_name,
+                        toggled,
+                        IconPosition::Start,
+                        None,
+                        move |cx| {
+                            image_view
+                                .update(cx, |image_view, cx| {
+                                    image_view.activate_image(sibling.clone(), cx)
+                                })
+                                .ok();
+                        },
+                    );
+                }
+                menu
+            }))
+        })
+        .anchor(AnchorCorner::TopLeft)
+        .attach(AnchorCorner::BottomLeft)
+}
+
 impl ToolbarItemView for Breadcrumbs {
     fn set_active_pane_item(
         &mut self,
@@ -308,6 +308,7 @@ impl Server {
             .add_request_handler(forward_read_only_project_request::<proto::InlayHints>)
             .add_request_handler(forward_read_only_project_request::<proto::ResolveInlayHint>)
             .add_request_handler(forward_read_only_project_request::<proto::OpenBufferByPath>)
+            .add_request_handler(forward_read_only_project_request::<proto::OpenImageByPath>)
             .add_request_handler(forward_read_only_project_request::<proto::GitBranches>)
             .add_request_handler(forward_mutating_project_request::<proto::UpdateGitBranch>)
             .add_request_handler(forward_mutating_project_request::<proto::GetCompletions>)
@@ -336,6 +337,7 @@ impl Server {
             .add_request_handler(forward_mutating_project_request::<proto::RestartLanguageServers>)
             .add_request_handler(forward_mutating_project_request::<proto::LinkedEditingRange>)
             .add_message_handler(create_buffer_for_peer)
+            .add_message_handler(create_image_for_peer)
             .add_request_handler(update_buffer)
             .add_message_handler(broadcast_project_message_from_host::<proto::RefreshInlayHints>)
             .add_message_handler(broadcast_project_message_from_host::<proto::UpdateBufferFile>)
@@ -386,6 +388,7 @@ impl Server {
             .add_request_handler(forward_mutating_project_request::<proto::SynchronizeContexts>)
             .add_message_handler(broadcast_project_message_from_host::<proto::AdvertiseContexts>)
             .add_message_handler(update_context)
+            .add_message_handler(update_image_annotations)
             .add_request_handler({
                 let app_state = app_state.clone();
                 move |request, response, session| {
@@ -2175,6 +2178,23 @@ async fn create_buffer_for_peer(
     Ok(())
 }
 
+/// Send an image opened by a guest to them, in chunks.
+async fn create_image_for_peer(request: proto::CreateImageForPeer, session: Session) -> Result<()> {
+    session
+        .db()
+        .await
+        .check_user_is_project_host(
+            ProjectId::from_proto(request.project_id),
+            session.connection_id,
+        )
+        .await?;
+    let peer_id = request.peer_id.ok_or_else(|| anyhow!("invalid peer id"))?;
+    session
+        .peer
+        .forward_send(session.connection_id, peer_id.into(), request)?;
+    Ok(())
+}
+
 /// Notify other participants that a buffer has been updated. This is
 /// allowed for guests as long as the update is limited to selections.
 async fn update_buffer(
@@ -2267,6 +2287,33 @@ async fn update_context(message: proto::UpdateContext, session: Session) -> Resu
     Ok(())
 }
 
+/// Relay a participant's pointer and annotations over an image to everyone else in the project.
+async fn update_image_annotations(
+    message: proto::UpdateImageAnnotations,
+    session: Session,
+) -> Result<()> {
+    let project_id = ProjectId::from_proto(message.project_id);
+    let guard = session
+        .db()
+        .await
+        .connections_for_buffer_update(project_id, session.connection_id, Capability::ReadOnly)
+        .await?;
+
+    let (host, guests) = &*guard;
+
+    broadcast(
+        Some(session.connection_id),
+        guests.iter().chain([host]).copied(),
+        |connection_id| {
+            session
+                .peer
+                .forward_send(session.connection_id, connection_id, message.clone())
+        },
+    );
+
+    Ok(())
+}
+
 /// Notify other participants that a project has been updated.
 async fn broadcast_project_message_from_host<T: EntityMessage<Entity = ShareProject>>(
     request: T,
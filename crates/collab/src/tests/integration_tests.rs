@@ -30,8 +30,8 @@ use lsp::LanguageServerId;
 use parking_lot::Mutex;
 use project::lsp_store::FormatTarget;
 use project::{
-    lsp_store::FormatTrigger, search::SearchQuery, search::SearchResult, DiagnosticSummary,
-    HoverBlockKind, Project, ProjectPath,
+    image_store::TEST_PNG, lsp_store::FormatTrigger, search::SearchQuery, search::SearchResult,
+    DiagnosticSummary, HoverBlockKind, Project, ProjectPath,
 };
 use rand::prelude::*;
 use serde_json::json;
@@ -3536,6 +3536,65 @@ async fn test_buffer_reloading(
     });
 }
 
+#[gpui::test(iterations = 10)]
+async fn test_image_opening_and_reloading(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    // Pad the image so that it is sent to the guest in several chunks.
+    let mut content = TEST_PNG.to_vec();
+    content.extend((0..600_000).map(|ix| ix as u8));
+    client_a.fs().insert_tree("/dir", json!({})).await;
+    client_a
+        .fs()
+        .insert_file("/dir/image.png", content.clone())
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project("/dir", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.join_remote_project(project_id, cx_b).await;
+
+    // Open the image as client B
+    let image_b = project_b
+        .update(cx_b, |p, cx| p.open_image((worktree_id, "image.png"), cx))
+        .await
+        .unwrap();
+    image_b.read_with(cx_b, |image, _| {
+        assert_eq!(image.image.format, gpui::ImageFormat::Png);
+        assert_eq!(image.image.bytes, content);
+    });
+
+    // Reload it as client B after the host's file changes
+    let mut new_content = TEST_PNG.to_vec();
+    new_content.extend((0..400_000).map(|ix| (ix / 3) as u8));
+    client_a
+        .fs()
+        .insert_file("/dir/image.png", new_content.clone())
+        .await;
+    executor.run_until_parked();
+
+    project_b
+        .update(cx_b, |p, cx| {
+            p.reload_images([image_b.clone()].into_iter().collect(), cx)
+        })
+        .await
+        .unwrap();
+    image_b.read_with(cx_b, |image, _| {
+        assert_eq!(image.image.bytes, new_content);
+    });
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_editing_while_guest_opens_buffer(
     executor: BackgroundExecutor,
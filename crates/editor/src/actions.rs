@@ -232,6 +232,7 @@ gpui::actions!(
         CopyPath,
         CopyPermalinkToLine,
         CopyRelativePath,
+        CopySelectionAsImage,
         Cut,
         CutToEndOfLine,
         Delete,
@@ -317,6 +318,7 @@ gpui::actions!(
         ReloadFile,
         RevertSelectedHunks,
         Rewrap,
+        SaveSelectionAsImage,
         ScrollCursorBottom,
         ScrollCursorCenter,
         ScrollCursorCenterTopBottom,
@@ -0,0 +1,219 @@
+use std::io::Cursor;
+
+use anyhow::Result;
+use gpui::{
+    point, px, ClipboardItem, Image, ImageFormat, Pixels, Rgba, ShapedLine, TextRun, ViewContext,
+};
+use language::{HighlightId, Point};
+use util::ResultExt;
+
+use crate::{CopySelectionAsImage, Editor, SaveSelectionAsImage};
+
+/// The space left around the code.
+const PADDING: Pixels = px(32.);
+
+/// Images are rendered at twice their logical size, so that they stay crisp on high density
+/// displays.
+const SCALE_FACTOR: f32 = 2.;
+
+pub(crate) fn copy_selection_as_image(
+    editor: &mut Editor,
+    _: &CopySelectionAsImage,
+    cx: &mut ViewContext<Editor>,
+) {
+    if let Some(image) = render_selection(editor, cx).log_err().flatten() {
+        cx.write_to_clipboard(ClipboardItem::new_image(&image));
+    }
+}
+
+pub(crate) fn save_selection_as_image(
+    editor: &mut Editor,
+    _: &SaveSelectionAsImage,
+    cx: &mut ViewContext<Editor>,
+) {
+    let Some(project) = editor.project.clone() else {
+        return;
+    };
+    let Some(image) = render_selection(editor, cx).log_err().flatten() else {
+        return;
+    };
+    let fs = project.read(cx).fs().clone();
+    let directory = editor
+        .target_file(cx)
+        .and_then(|file| Some(file.abs_path(cx).parent()?.to_path_buf()))
+        .unwrap_or_else(|| util::paths::home_dir().clone());
+    let abs_path = cx.prompt_for_new_path(&directory);
+
+    cx.spawn(|_, _| async move {
+        let Some(mut abs_path) = abs_path.await?? else {
+            return Ok(());
+        };
+        if abs_path.extension().is_none() {
+            abs_path.set_extension("png");
+        }
+        let content = image.bytes.as_slice();
+        futures::pin_mut!(content);
+        fs.create_file_with(&abs_path, content).await
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Renders the lines touched by the newest selection with the editor's font and syntax theme
+/// into a PNG, with their common indentation removed.
+fn render_selection(editor: &Editor, cx: &mut ViewContext<Editor>) -> Result<Option<Image>> {
+    let Some(buffer) = editor.buffer.read(cx).as_singleton() else {
+        return Ok(None);
+    };
+    let Some(style) = editor.style.as_ref() else {
+        return Ok(None);
+    };
+    let snapshot = buffer.read(cx).snapshot();
+    let selection = editor.selections.newest::<Point>(cx);
+    let start_row = selection.start.row;
+    let mut end_row = selection.end.row;
+    if end_row > start_row && selection.end.column == 0 {
+        end_row -= 1;
+    }
+    let range = Point::new(start_row, 0)..Point::new(end_row, snapshot.line_len(end_row));
+
+    let tab_size = snapshot.settings_at(range.start, cx).tab_size.get() as usize;
+    let mut lines = vec![Vec::new()];
+    for chunk in snapshot.chunks(range, true) {
+        for (ix, text) in chunk.text.split('\n').enumerate() {
+            if ix > 0 {
+                lines.push(Vec::new());
+            }
+            if !text.is_empty() {
+                let text = text.replace('\t', &" ".repeat(tab_size));
+                lines
+                    .last_mut()
+                    .unwrap()
+                    .push((text, chunk.syntax_highlight_id));
+            }
+        }
+    }
+    remove_common_indentation(&mut lines);
+
+    let font = style.text.font();
+    let font_size = style.text.font_size.to_pixels(cx.rem_size());
+    let line_height = style.text.line_height_in_pixels(cx.rem_size());
+    let text_system = cx.text_system().clone();
+    let shaped_lines = lines
+        .into_iter()
+        .map(|segments| {
+            let mut text = String::new();
+            let mut runs = Vec::new();
+            for (segment, highlight_id) in segments {
+                let highlight = highlight_id.and_then(|id| id.style(&style.syntax));
+                let mut font = font.clone();
+                if let Some(highlight) = highlight {
+                    font.weight = highlight.font_weight.unwrap_or(font.weight);
+                    font.style = highlight.font_style.unwrap_or(font.style);
+                }
+                runs.push(TextRun {
+                    len: segment.len(),
+                    font,
+                    color: highlight
+                        .and_then(|highlight| highlight.color)
+                        .unwrap_or(style.text.color),
+                    background_color: None,
+                    underline: None,
+                    strikethrough: None,
+                });
+                text.push_str(&segment);
+            }
+            text_system.shape_line(text.into(), font_size, &runs)
+        })
+        .collect::<Result<Vec<ShapedLine>>>()?;
+
+    let width = shaped_lines
+        .iter()
+        .map(|line| line.width)
+        .max()
+        .unwrap_or_default()
+        + PADDING * 2.;
+    let height = line_height * shaped_lines.len() as f32 + PADDING * 2.;
+    let background = Rgba::from(style.background);
+    let mut canvas = image::RgbaImage::from_pixel(
+        (width.0 * SCALE_FACTOR).ceil() as u32,
+        (height.0 * SCALE_FACTOR).ceil() as u32,
+        image::Rgba([
+            (background.r * 255.).round() as u8,
+            (background.g * 255.).round() as u8,
+            (background.b * 255.).round() as u8,
+            255,
+        ]),
+    );
+    for (ix, line) in shaped_lines.iter().enumerate() {
+        line.rasterize(
+            point(PADDING, PADDING + line_height * ix as f32),
+            line_height,
+            SCALE_FACTOR,
+            &text_system,
+            &mut canvas,
+        )?;
+    }
+
+    let mut bytes = Vec::new();
+    image::DynamicImage::ImageRgba8(canvas)
+        .write_to(&mut Cursor::new(&mut bytes), image::ImageFormat::Png)?;
+    Ok(Some(Image {
+        format: ImageFormat::Png,
+        id: gpui::hash(&bytes),
+        bytes,
+    }))
+}
+
+/// Strips the indentation shared by all non-blank lines, which only consists of spaces once
+/// tabs have been expanded.
+fn remove_common_indentation(lines: &mut [Vec<(String, Option<HighlightId>)>]) {
+    let indentation = |line: &Vec<(String, Option<HighlightId>)>| {
+        let mut indentation = 0;
+        for (text, _) in line {
+            let spaces = text.len() - text.trim_start_matches(' ').len();
+            indentation += spaces;
+            if spaces < text.len() {
+                return Some(indentation);
+            }
+        }
+        None
+    };
+    let Some(common_indentation) = lines.iter().filter_map(indentation).min() else {
+        return;
+    };
+
+    for line in lines {
+        let mut remaining = common_indentation;
+        line.retain_mut(|(text, _)| {
+            let removed = remaining.min(text.len() - text.trim_start_matches(' ').len());
+            text.drain(..removed);
+            remaining -= removed;
+            !text.is_empty()
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remove_common_indentation() {
+        let mut lines = vec![
+            vec![("    ".to_string(), None), ("fn a() {".to_string(), None)],
+            vec![],
+            vec![("        body".to_string(), None)],
+            vec![("    }".to_string(), None)],
+        ];
+        remove_common_indentation(&mut lines);
+        let text = lines
+            .iter()
+            .map(|line| {
+                line.iter()
+                    .map(|(text, _)| text.as_str())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(text, ["fn a() {", "", "    body", "}"]);
+    }
+}
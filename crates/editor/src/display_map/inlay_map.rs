@@ -69,6 +69,14 @@ impl Inlay {
             text: text.into(),
         }
     }
+
+    pub fn color<T: Into<Rope>>(id: usize, position: Anchor, text: T) -> Self {
+        Self {
+            id: InlayId::Color(id),
+            position,
+            text: text.into(),
+        }
+    }
 }
 
 impl sum_tree::Item for Transform {
@@ -348,6 +356,7 @@ impl<'a> Iterator for InlayChunks<'a> {
                 let mut highlight_style = match inlay.id {
                     InlayId::Suggestion(_) => self.highlight_styles.suggestion,
                     InlayId::Hint(_) => self.highlight_styles.inlay_hint,
+                    InlayId::Color(_) => None,
                 };
                 let next_inlay_highlight_endpoint;
                 let offset_in_inlay = self.output_offset - self.transforms.start().0;
@@ -16,6 +16,7 @@ pub mod actions;
 mod blame_entry_tooltip;
 mod blink_manager;
 mod clangd_ext;
+mod code_image;
 mod debounced_delay;
 pub mod display_map;
 mod editor_settings;
@@ -26,6 +27,7 @@ mod highlight_matching_bracket;
 mod hover_links;
 mod hover_popover;
 mod hunk_diff;
+mod image_paste;
 mod indent_guides;
 mod inlay_hint_cache;
 pub mod items;
@@ -38,6 +40,7 @@ mod proposed_changes_editor;
 mod rust_analyzer_ext;
 pub mod scroll;
 mod selections_collection;
+mod stylesheet_previews;
 pub mod tasks;
 
 #[cfg(test)]
@@ -71,14 +74,15 @@ use futures::{future, FutureExt};
 use fuzzy::{StringMatch, StringMatchCandidate};
 use git::blame::GitBlame;
 use gpui::{
-    div, impl_actions, point, prelude::*, px, relative, size, uniform_list, Action, AnyElement,
-    AppContext, AsyncWindowContext, AvailableSpace, BackgroundExecutor, Bounds, ClipboardEntry,
-    ClipboardItem, Context, DispatchPhase, ElementId, EventEmitter, FocusHandle, FocusOutEvent,
-    FocusableView, FontId, FontWeight, Global, HighlightStyle, Hsla, InteractiveText, KeyContext,
-    ListSizingBehavior, Model, ModelContext, MouseButton, PaintQuad, ParentElement, Pixels, Render,
-    ScrollStrategy, SharedString, Size, StrikethroughStyle, Styled, StyledText, Subscription, Task,
-    TextStyle, TextStyleRefinement, UTF16Selection, UnderlineStyle, UniformListScrollHandle, View,
-    ViewContext, ViewInputHandler, VisualContext, WeakFocusHandle, WeakView, WindowContext,
+    div, img, impl_actions, point, prelude::*, px, relative, size, uniform_list, Action,
+    AnyElement, AppContext, AsyncWindowContext, AvailableSpace, BackgroundExecutor, Bounds,
+    ClipboardEntry, ClipboardItem, Context, DispatchPhase, ElementId, EventEmitter, FocusHandle,
+    FocusOutEvent, FocusableView, FontId, FontWeight, Global, HighlightStyle, Hsla,
+    InteractiveText, KeyContext, ListSizingBehavior, Model, ModelContext, MouseButton, ObjectFit,
+    PaintQuad, ParentElement, Pixels, Render, ScrollStrategy, SharedString, Size,
+    StrikethroughStyle, Styled, StyledText, Subscription, Task, TextStyle, TextStyleRefinement,
+    UTF16Selection, UnderlineStyle, UniformListScrollHandle, View, ViewContext, ViewInputHandler,
+    VisualContext, WeakFocusHandle, WeakView, WindowContext,
 };
 use highlight_matching_bracket::refresh_matching_bracket_highlights;
 use hover_popover::{hide_hover, HoverState};
@@ -123,6 +127,7 @@ use multi_buffer::{
 use ordered_float::OrderedFloat;
 use parking_lot::{Mutex, RwLock};
 use project::{
+    image_store::is_image_path,
     lsp_store::{FormatTarget, FormatTrigger},
     project_settings::{GitGutterSetting, ProjectSettings},
     CodeAction, Completion, CompletionIntent, DocumentHighlight, InlayHint, Item, Location,
@@ -257,6 +262,7 @@ pub fn render_parsed_markdown(
 pub(crate) enum InlayId {
     Suggestion(usize),
     Hint(usize),
+    Color(usize),
 }
 
 impl InlayId {
@@ -264,6 +270,7 @@ impl InlayId {
         match self {
             Self::Suggestion(id) => *id,
             Self::Hint(id) => *id,
+            Self::Color(id) => *id,
         }
     }
 }
@@ -601,6 +608,7 @@ pub struct Editor {
     document_highlights_task: Option<Task<()>>,
     linked_editing_range_task: Option<Task<Option<()>>>,
     linked_edit_ranges: linked_editing_ranges::LinkedEditingRanges,
+    stylesheet_previews: stylesheet_previews::StylesheetPreviews,
     pending_rename: Option<RenameState>,
     searchable: bool,
     cursor_shape: CursorShape,
@@ -1006,6 +1014,15 @@ struct CompletionsMenu {
     selected_item: usize,
     scroll_handle: UniformListScrollHandle,
     selected_completion_resolve_debounce: Option<Arc<Mutex<DebouncedDelay>>>,
+    image_previews: Arc<RwLock<HashMap<usize, CompletionImagePreview>>>,
+}
+
+/// A thumbnail shown in the documentation pane for file completions that
+/// resolve to an image.
+#[derive(Clone, Debug)]
+struct CompletionImagePreview {
+    image: Arc<gpui::Image>,
+    dimensions: Option<(u32, u32)>,
 }
 
 impl CompletionsMenu {
@@ -1038,6 +1055,7 @@ impl CompletionsMenu {
             selected_item: 0,
             scroll_handle: UniformListScrollHandle::new(),
             selected_completion_resolve_debounce: Some(Arc::new(Mutex::new(DebouncedDelay::new()))),
+            image_previews: Default::default(),
         }
     }
 
@@ -1091,6 +1109,7 @@ impl CompletionsMenu {
             selected_item: 0,
             scroll_handle: UniformListScrollHandle::new(),
             selected_completion_resolve_debounce: Some(Arc::new(Mutex::new(DebouncedDelay::new()))),
+            image_previews: Default::default(),
         }
     }
 
@@ -1160,6 +1179,8 @@ impl CompletionsMenu {
         provider: Option<&dyn CompletionProvider>,
         cx: &mut ViewContext<Editor>,
     ) {
+        self.load_selected_image_preview(cx);
+
         let completion_index = self.matches[self.selected_item].candidate_id;
         let Some(provider) = provider else {
             return;
@@ -1188,6 +1209,72 @@ impl CompletionsMenu {
         });
     }
 
+    /// Loads a thumbnail for the selected completion if it is a file completion
+    /// that resolves to an image relative to the buffer.
+    fn load_selected_image_preview(&self, cx: &mut ViewContext<Editor>) {
+        let completion_index = self.matches[self.selected_item].candidate_id;
+        if self.image_previews.read().contains_key(&completion_index) {
+            return;
+        }
+        let Some(candidate_path) = self.image_path_candidate(completion_index, cx) else {
+            return;
+        };
+
+        let buffer = self.buffer.clone();
+        let image_previews = self.image_previews.clone();
+        cx.spawn(move |editor, mut cx| async move {
+            let Some(project) = editor.update(&mut cx, |editor, _| editor.project.clone())? else {
+                return anyhow::Ok(());
+            };
+            let resolved_path = project
+                .update(&mut cx, |project, cx| {
+                    project.resolve_path_in_buffer(&candidate_path, &buffer, cx)
+                })?
+                .await;
+            let Some(project_path) = resolved_path
+                .filter(|resolved_path| resolved_path.is_file())
+                .and_then(|resolved_path| resolved_path.project_path().cloned())
+            else {
+                return anyhow::Ok(());
+            };
+
+            let image_item = project
+                .update(&mut cx, |project, cx| project.open_image(project_path, cx))?
+                .await?;
+            let preview = image_item.update(&mut cx, |image_item, _| CompletionImagePreview {
+                image: image_item.image.clone(),
+                dimensions: image_item.dimensions(),
+            })?;
+            image_previews.write().insert(completion_index, preview);
+            editor.update(&mut cx, |_, cx| cx.notify())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Builds the path a file completion would produce, by prepending the part
+    /// of the path that was already typed before the completion range.
+    fn image_path_candidate(&self, completion_index: usize, cx: &AppContext) -> Option<String> {
+        let completions = self.completions.read();
+        let completion = &completions[completion_index];
+        if completion.lsp_completion.kind != Some(CompletionItemKind::FILE)
+            || !is_image_path(Path::new(&completion.new_text))
+        {
+            return None;
+        }
+
+        let buffer = self.buffer.read(cx);
+        let start = buffer.summary_for_anchor::<usize>(&completion.old_range.start);
+        let mut prefix = buffer
+            .reversed_chars_at(start)
+            .take_while(|ch| !ch.is_whitespace() && !matches!(ch, '"' | '\'' | '(' | '`'))
+            .collect::<Vec<_>>();
+        prefix.reverse();
+
+        let mut candidate_path = prefix.into_iter().collect::<String>();
+        candidate_path.push_str(&completion.new_text);
+        Some(candidate_path)
+    }
+
     fn visible(&self) -> bool {
         !self.matches.is_empty()
     }
@@ -1229,20 +1316,43 @@ impl CompletionsMenu {
 
         let multiline_docs = if show_completion_documentation {
             let mat = &self.matches[selected_item];
-            let multiline_docs = match &self.completions.read()[mat.candidate_id].documentation {
-                Some(Documentation::MultiLinePlainText(text)) => {
-                    Some(div().child(SharedString::from(text.clone())))
-                }
-                Some(Documentation::MultiLineMarkdown(parsed)) if !parsed.text.is_empty() => {
-                    Some(div().child(render_parsed_markdown(
-                        "completions_markdown",
-                        parsed,
-                        &style,
-                        workspace,
-                        cx,
-                    )))
+            let image_preview = self.image_previews.read().get(&mat.candidate_id).cloned();
+            let multiline_docs = if let Some(preview) = image_preview {
+                Some(
+                    div()
+                        .flex()
+                        .flex_col()
+                        .gap_1()
+                        .child(
+                            img(preview.image)
+                                .max_w(px(240.))
+                                .max_h(px(240.))
+                                .object_fit(ObjectFit::ScaleDown),
+                        )
+                        .when_some(preview.dimensions, |this, (width, height)| {
+                            this.child(
+                                Label::new(format!("{width} × {height}"))
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                        }),
+                )
+            } else {
+                match &self.completions.read()[mat.candidate_id].documentation {
+                    Some(Documentation::MultiLinePlainText(text)) => {
+                        Some(div().child(SharedString::from(text.clone())))
+                    }
+                    Some(Documentation::MultiLineMarkdown(parsed)) if !parsed.text.is_empty() => {
+                        Some(div().child(render_parsed_markdown(
+                            "completions_markdown",
+                            parsed,
+                            &style,
+                            workspace,
+                            cx,
+                        )))
+                    }
+                    _ => None,
                 }
-                _ => None,
             };
             multiline_docs.map(|div| {
                 div.id("multiline_docs")
@@ -2147,6 +2257,7 @@ impl Editor {
             ],
             tasks_update_task: None,
             linked_edit_ranges: Default::default(),
+            stylesheet_previews: Default::default(),
             previous_search_ranges: None,
             breadcrumb_header: None,
             focused_block: None,
@@ -2169,6 +2280,8 @@ impl Editor {
                 this.git_blame_inline_enabled = true;
                 this.start_git_blame_inline(false, cx);
             }
+
+            stylesheet_previews::refresh_stylesheet_previews(&mut this, cx);
         }
 
         this.report_editor_event("open", None, cx);
@@ -7491,6 +7604,12 @@ impl Editor {
         if let Some(item) = cx.read_from_clipboard() {
             let entries = item.entries();
 
+            if let Some(ClipboardEntry::Image(image)) = entries.first() {
+                if image_paste::paste_image(self, image, cx) {
+                    return;
+                }
+            }
+
             match entries.first() {
                 // For now, we only support applying metadata if there's one string. In the future, we can incorporate all the selections
                 // of all the pasted entries.
@@ -12524,6 +12643,7 @@ impl Editor {
                 if self.has_active_inline_completion(cx) {
                     self.update_visible_inline_completion(cx);
                 }
+                stylesheet_previews::refresh_stylesheet_previews(self, cx);
                 cx.emit(EditorEvent::BufferEdited);
                 cx.emit(SearchEvent::MatchesInvalidated);
                 if *singleton_buffer_edited {
@@ -12596,6 +12716,7 @@ impl Editor {
             }
             multi_buffer::Event::LanguageChanged(buffer_id) => {
                 linked_editing_ranges::refresh_linked_ranges(self, cx);
+                stylesheet_previews::refresh_stylesheet_previews(self, cx);
                 cx.emit(EditorEvent::Reparsed(*buffer_id));
                 cx.notify();
             }
@@ -12652,6 +12773,8 @@ impl Editor {
             cx.emit(EditorEvent::CursorShapeChanged);
         }
 
+        stylesheet_previews::refresh_stylesheet_previews(self, cx);
+
         let project_settings = ProjectSettings::get_global(cx);
         self.serialize_dirty_buffers = project_settings.session.restore_unsaved_buffers;
 
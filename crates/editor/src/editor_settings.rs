@@ -27,6 +27,8 @@ pub struct EditorSettings {
     pub redact_private_values: bool,
     pub expand_excerpt_lines: u32,
     pub middle_click_paste: bool,
+    pub pasted_images_directory: String,
+    pub stylesheet_previews: bool,
     #[serde(default)]
     pub double_click_in_multibuffer: DoubleClickInMultibuffer,
     pub search_wrap: bool,
@@ -262,6 +264,18 @@ pub struct EditorSettingsContent {
     /// Default: true
     pub middle_click_paste: Option<bool>,
 
+    /// Directory, relative to the worktree root, where images pasted into
+    /// Markdown and AsciiDoc buffers are saved.
+    ///
+    /// Default: "assets"
+    pub pasted_images_directory: Option<String>,
+
+    /// Whether to show color swatches and thumbnails of the images referenced
+    /// with `url(...)` in CSS, SCSS, Sass and Less buffers.
+    ///
+    /// Default: true
+    pub stylesheet_previews: Option<bool>,
+
     /// What to do when multibuffer is double clicked in some of its excerpts
     /// (parts of singleton buffers).
     ///
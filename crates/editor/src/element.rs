@@ -367,6 +367,8 @@ impl EditorElement {
         register_action(view, cx, Editor::copy_path);
         register_action(view, cx, Editor::copy_relative_path);
         register_action(view, cx, Editor::copy_highlight_json);
+        register_action(view, cx, crate::code_image::copy_selection_as_image);
+        register_action(view, cx, crate::code_image::save_selection_as_image);
         register_action(view, cx, Editor::copy_permalink_to_line);
         register_action(view, cx, Editor::open_permalink_to_line);
         register_action(view, cx, Editor::copy_file_location);
@@ -1,34 +1,36 @@
 use crate::{
     display_map::{invisibles::is_invisible, InlayOffset, ToDisplayPoint},
-    hover_links::{InlayHighlight, RangeInEditor},
+    hover_links::{find_file, InlayHighlight, RangeInEditor},
     scroll::ScrollAmount,
     Anchor, AnchorRangeExt, DisplayPoint, DisplayRow, Editor, EditorSettings, EditorSnapshot,
     Hover, RangeToAnchorExt,
 };
 use gpui::{
-    div, px, AnyElement, AsyncWindowContext, FontWeight, Hsla, InteractiveElement, IntoElement,
-    MouseButton, ParentElement, Pixels, ScrollHandle, Size, Stateful, StatefulInteractiveElement,
-    StyleRefinement, Styled, Task, TextStyleRefinement, View, ViewContext,
+    div, img, px, AnyElement, AsyncWindowContext, FontWeight, Hsla, Image, InteractiveElement,
+    IntoElement, Model, MouseButton, ObjectFit, ParentElement, Pixels, ScrollHandle, Size,
+    Stateful, StatefulInteractiveElement, StyleRefinement, Styled, Task, TextStyleRefinement, View,
+    ViewContext,
 };
 use itertools::Itertools;
 use language::{Diagnostic, DiagnosticEntry, Language, LanguageRegistry};
 use lsp::DiagnosticSeverity;
 use markdown::{Markdown, MarkdownStyle};
-use multi_buffer::ToOffset;
-use project::{HoverBlock, InlayHintLabelPart};
+use multi_buffer::{ExcerptId, ToOffset};
+use project::{image_store::is_image_path, HoverBlock, InlayHintLabelPart, Project, ResolvedPath};
 use settings::Settings;
 use std::rc::Rc;
 use std::{borrow::Cow, cell::RefCell};
 use std::{ops::Range, sync::Arc, time::Duration};
 use theme::ThemeSettings;
 use ui::{prelude::*, window_is_transparent, Scrollbar, ScrollbarState};
-use util::TryFutureExt;
+use util::{ResultExt, TryFutureExt};
 pub const HOVER_DELAY_MILLIS: u64 = 350;
 pub const HOVER_REQUEST_DELAY_MILLIS: u64 = 200;
 
 pub const MIN_POPOVER_CHARACTER_WIDTH: f32 = 20.;
 pub const MIN_POPOVER_LINE_HEIGHT: Pixels = px(4.);
 pub const HOVER_POPOVER_GAP: Pixels = px(10.);
+pub const IMAGE_POPOVER_MAX_SIZE: Pixels = px(256.);
 
 /// Bindable action which uses the most recent selection head to trigger a hover
 pub fn hover(editor: &mut Editor, _: &Hover, cx: &mut ViewContext<Editor>) {
@@ -175,7 +177,9 @@ pub fn hover_at_inlay(editor: &mut Editor, inlay_hover: InlayHover, cx: &mut Vie
 pub fn hide_hover(editor: &mut Editor, cx: &mut ViewContext<Editor>) -> bool {
     let info_popovers = editor.hover_state.info_popovers.drain(..);
     let diagnostics_popover = editor.hover_state.diagnostic_popover.take();
-    let did_hide = info_popovers.count() > 0 || diagnostics_popover.is_some();
+    let image_popover = editor.hover_state.image_popover.take();
+    let did_hide =
+        info_popovers.count() > 0 || diagnostics_popover.is_some() || image_popover.is_some();
 
     editor.hover_state.info_task = None;
     editor.hover_state.triggered_from = None;
@@ -211,12 +215,14 @@ fn show_hover(
 
     let (excerpt_id, _, _) = editor.buffer().read(cx).excerpt_containing(anchor, cx)?;
 
-    let language_registry = editor.project.as_ref()?.read(cx).languages().clone();
+    let project = editor.project.clone();
+    let language_registry = project.as_ref()?.read(cx).languages().clone();
     let provider = editor.semantics_provider.clone()?;
 
     if !ignore_timeout {
         if same_info_hover(editor, &snapshot, anchor)
             || same_diagnostic_hover(editor, &snapshot, anchor)
+            || same_image_hover(editor, &snapshot, anchor)
             || editor.hover_state.diagnostic_popover.is_some()
         {
             // Hover triggered from same location as last time. Don't show again.
@@ -395,8 +401,19 @@ fn show_hover(
                 None
             };
 
+            let image_popover = image_popover_for_position(
+                &buffer,
+                project,
+                buffer_position,
+                excerpt_id,
+                &snapshot,
+                &mut cx,
+            )
+            .await;
+
             this.update(&mut cx, |this, _| {
                 this.hover_state.diagnostic_popover = diagnostic_popover;
+                this.hover_state.image_popover = image_popover;
             })?;
 
             let hovers_response = if let Some(hover_request) = hover_request {
@@ -481,6 +498,47 @@ fn show_hover(
     None
 }
 
+/// Resolves the path under the cursor and, if it points at an image in the
+/// worktree, loads it through the project's image store.
+async fn image_popover_for_position(
+    buffer: &Model<language::Buffer>,
+    project: Option<Model<Project>>,
+    buffer_position: text::Anchor,
+    excerpt_id: ExcerptId,
+    snapshot: &EditorSnapshot,
+    cx: &mut AsyncWindowContext,
+) -> Option<ImagePopover> {
+    let project = project?;
+    let (range, resolved_path) =
+        find_file(buffer, Some(project.clone()), buffer_position, cx).await?;
+    let ResolvedPath::ProjectPath { project_path, .. } = resolved_path else {
+        return None;
+    };
+    if !is_image_path(&project_path.path) {
+        return None;
+    }
+
+    let image_item = project
+        .update(cx, |project, cx| project.open_image(project_path, cx))
+        .ok()?
+        .await
+        .log_err()?;
+    let (image, dimensions) = image_item
+        .update(cx, |image_item, _| {
+            (image_item.image.clone(), image_item.dimensions())
+        })
+        .ok()?;
+
+    let buffer_snapshot = &snapshot.buffer_snapshot;
+    let start = buffer_snapshot.anchor_in_excerpt(excerpt_id, range.start)?;
+    let end = buffer_snapshot.anchor_in_excerpt(excerpt_id, range.end)?;
+    Some(ImagePopover {
+        symbol_range: start..end,
+        image,
+        dimensions,
+    })
+}
+
 fn same_info_hover(editor: &Editor, snapshot: &EditorSnapshot, anchor: Anchor) -> bool {
     editor
         .hover_state
@@ -602,17 +660,35 @@ async fn parse_blocks(
     rendered_block
 }
 
+fn same_image_hover(editor: &Editor, snapshot: &EditorSnapshot, anchor: Anchor) -> bool {
+    editor
+        .hover_state
+        .image_popover
+        .as_ref()
+        .map(|image_popover| {
+            let hover_range = image_popover
+                .symbol_range
+                .to_offset(&snapshot.buffer_snapshot);
+            let offset = anchor.to_offset(&snapshot.buffer_snapshot);
+            (hover_range.start..=hover_range.end).contains(&offset)
+        })
+        .unwrap_or(false)
+}
+
 #[derive(Default, Debug)]
 pub struct HoverState {
     pub info_popovers: Vec<InfoPopover>,
     pub diagnostic_popover: Option<DiagnosticPopover>,
+    pub(crate) image_popover: Option<ImagePopover>,
     pub triggered_from: Option<Anchor>,
     pub info_task: Option<Task<Option<()>>>,
 }
 
 impl HoverState {
     pub fn visible(&self) -> bool {
-        !self.info_popovers.is_empty() || self.diagnostic_popover.is_some()
+        !self.info_popovers.is_empty()
+            || self.diagnostic_popover.is_some()
+            || self.image_popover.is_some()
     }
 
     pub(crate) fn render(
@@ -643,6 +719,11 @@ impl HoverState {
                         RangeInEditor::Inlay(range) => Some(&range.inlay_position),
                     }
                 })
+            })
+            .or_else(|| {
+                self.image_popover
+                    .as_ref()
+                    .map(|image_popover| &image_popover.symbol_range.start)
             })?;
         let point = anchor.to_display_point(&snapshot.display_snapshot);
 
@@ -659,6 +740,9 @@ impl HoverState {
         for info_popover in &mut self.info_popovers {
             elements.push(info_popover.render(max_size, cx));
         }
+        if let Some(image_popover) = self.image_popover.as_ref() {
+            elements.push(image_popover.render(cx));
+        }
 
         Some((point, elements))
     }
@@ -772,6 +856,43 @@ impl InfoPopover {
     }
 }
 
+#[derive(Debug, Clone)]
+pub(crate) struct ImagePopover {
+    pub(crate) symbol_range: Range<Anchor>,
+    pub(crate) image: Arc<Image>,
+    pub(crate) dimensions: Option<(u32, u32)>,
+}
+
+impl ImagePopover {
+    pub(crate) fn render(&self, cx: &mut ViewContext<Editor>) -> AnyElement {
+        div()
+            .id("image_popover")
+            .elevation_2(cx)
+            .p_1()
+            .flex()
+            .flex_col()
+            .gap_1()
+            // Prevent a mouse down/move on the popover from being propagated to the editor,
+            // because that would dismiss the popover.
+            .on_mouse_move(|_, cx| cx.stop_propagation())
+            .on_mouse_down(MouseButton::Left, |_, cx| cx.stop_propagation())
+            .child(
+                img(self.image.clone())
+                    .max_w(IMAGE_POPOVER_MAX_SIZE)
+                    .max_h(IMAGE_POPOVER_MAX_SIZE)
+                    .object_fit(ObjectFit::ScaleDown),
+            )
+            .when_some(self.dimensions, |this, (width, height)| {
+                this.child(
+                    Label::new(format!("{width} × {height}"))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+            .into_any_element()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct DiagnosticPopover {
     local_diagnostic: DiagnosticEntry<Anchor>,
@@ -0,0 +1,102 @@
+use std::path::Path;
+
+use gpui::{Image, ViewContext};
+use project::ProjectPath;
+use settings::Settings;
+use util::ResultExt;
+
+use crate::{
+    items::{relative_path_between, ImageReferenceStyle},
+    Editor, EditorSettings,
+};
+
+/// Saves a pasted bitmap into the configured assets directory of the buffer's worktree and
+/// inserts a link to it. Returns `false` when the paste should be handled as text instead.
+pub(crate) fn paste_image(
+    editor: &mut Editor,
+    image: &Image,
+    cx: &mut ViewContext<Editor>,
+) -> bool {
+    if editor.read_only(cx) {
+        return false;
+    }
+    let Some(project) = editor.project.clone() else {
+        return false;
+    };
+    if !project.read(cx).is_local() {
+        return false;
+    }
+    let Some(buffer) = editor.buffer.read(cx).as_singleton() else {
+        return false;
+    };
+    let Some(file) = buffer.read(cx).file().cloned() else {
+        return false;
+    };
+
+    let cursor = editor.selections.newest_anchor().head();
+    let language = editor.buffer.read(cx).language_at(cursor, cx);
+    let style = ImageReferenceStyle::for_language(language.as_ref().map(|l| l.name().0));
+    if !matches!(
+        style,
+        ImageReferenceStyle::Markdown | ImageReferenceStyle::AsciiDoc
+    ) {
+        return false;
+    }
+
+    let worktree_id = file.worktree_id(cx);
+    let Some(worktree) = project.read(cx).worktree_for_id(worktree_id, cx) else {
+        return false;
+    };
+    let directory = Path::new(&EditorSettings::get_global(cx).pasted_images_directory).to_owned();
+    let file_stem = format!(
+        "pasted-image-{}",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    );
+    let extension = image.format.extension();
+    let snapshot = worktree.read(cx).snapshot();
+    let image_path = (0..)
+        .map(|ix| {
+            let file_name = if ix == 0 {
+                format!("{file_stem}.{extension}")
+            } else {
+                format!("{file_stem}-{ix}.{extension}")
+            };
+            directory.join(file_name)
+        })
+        .find(|path| snapshot.entry_for_path(path).is_none())
+        .unwrap();
+    let project_path = ProjectPath {
+        worktree_id,
+        path: image_path.as_path().into(),
+    };
+    let Some(abs_path) = project.read(cx).absolute_path(&project_path, cx) else {
+        return false;
+    };
+
+    let reference_path = relative_path_between(
+        file.path().parent().unwrap_or(Path::new("")),
+        &project_path.path,
+    );
+    editor.insert(&style.format(&reference_path, "image"), cx);
+
+    let fs = project.read(cx).fs().clone();
+    let bytes = image.bytes.clone();
+    cx.spawn(|_, mut cx| async move {
+        if let Some(parent) = abs_path.parent() {
+            fs.create_dir(parent).await?;
+        }
+        let content = bytes.as_slice();
+        futures::pin_mut!(content);
+        fs.create_file_with(&abs_path, content).await?;
+
+        // Opening the image refreshes the worktree entry and loads it into the image store, so
+        // the new file can be opened in the image viewer straight away.
+        project
+            .update(&mut cx, |project, cx| project.open_image(project_path, cx))?
+            .await
+            .log_err();
+        anyhow::Ok(())
+    })
+    .detach_and_log_err(cx);
+    true
+}
@@ -22,8 +22,8 @@ use language::{
 use lsp::DiagnosticSeverity;
 use multi_buffer::AnchorRangeExt;
 use project::{
-    lsp_store::FormatTrigger, project_settings::ProjectSettings, search::SearchQuery, Item as _,
-    Project, ProjectPath,
+    image_store::is_image_path, lsp_store::FormatTrigger, project_settings::ProjectSettings,
+    search::SearchQuery, Item as _, Project, ProjectPath,
 };
 use rpc::proto::{self, update_view, PeerId};
 use settings::Settings;
@@ -31,7 +31,7 @@ use workspace::item::{Dedup, ItemSettings, SerializableItem, TabContentParams};
 
 use project::lsp_store::FormatTarget;
 use std::{
-    any::TypeId,
+    any::{Any, TypeId},
     borrow::Cow,
     cmp::{self, Ordering},
     iter,
@@ -46,12 +46,65 @@ use util::{paths::PathExt, ResultExt, TryFutureExt};
 use workspace::item::{BreadcrumbText, FollowEvent};
 use workspace::{
     item::{FollowableItem, Item, ItemEvent, ProjectItem},
+    pane::DraggedSelection,
     searchable::{Direction, SearchEvent, SearchableItem, SearchableItemHandle},
     ItemId, ItemNavHistory, ToolbarItemLocation, ViewId, Workspace, WorkspaceId,
 };
 
 pub const MAX_TAB_TITLE_LEN: usize = 24;
 
+/// How a reference to an image file is written into a buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ImageReferenceStyle {
+    Markdown,
+    AsciiDoc,
+    Html,
+    PathString,
+}
+
+impl ImageReferenceStyle {
+    pub(crate) fn for_language(language_name: Option<Arc<str>>) -> Self {
+        match language_name.as_deref() {
+            Some("Markdown" | "MDX") => Self::Markdown,
+            Some("AsciiDoc") => Self::AsciiDoc,
+            Some("HTML" | "Vue.js" | "Svelte" | "Astro") => Self::Html,
+            _ => Self::PathString,
+        }
+    }
+
+    pub(crate) fn format(&self, path: &str, alt: &str) -> String {
+        match self {
+            Self::Markdown if path.contains(' ') => format!("![{alt}](<{path}>)"),
+            Self::Markdown => format!("![{alt}]({path})"),
+            Self::AsciiDoc => format!("image::{path}[{alt}]"),
+            Self::Html => format!("<img src=\"{path}\" alt=\"{alt}\">"),
+            Self::PathString => format!("\"{path}\""),
+        }
+    }
+}
+
+/// Returns the `/`-separated path from the directory `from_dir` to `to`, both relative to the
+/// same worktree root.
+pub(crate) fn relative_path_between(from_dir: &Path, to: &Path) -> String {
+    let from_components = from_dir.components().collect::<Vec<_>>();
+    let to_components = to.components().collect::<Vec<_>>();
+    let common_len = from_components
+        .iter()
+        .zip(&to_components)
+        .take_while(|(from, to)| from == to)
+        .count();
+
+    iter::repeat("..".to_string())
+        .take(from_components.len() - common_len)
+        .chain(
+            to_components[common_len..]
+                .iter()
+                .map(|component| component.as_os_str().to_string_lossy().to_string()),
+        )
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
 impl FollowableItem for Editor {
     fn remote_id(&self) -> Option<ViewId> {
         self.remote_id
@@ -297,6 +350,7 @@ impl FollowableItem for Editor {
                 }
                 _ => false,
             },
+            _ => false,
         }
     }
 
@@ -306,7 +360,9 @@ impl FollowableItem for Editor {
         message: update_view::Variant,
         cx: &mut ViewContext<Self>,
     ) -> Task<Result<()>> {
-        let update_view::Variant::Editor(message) = message;
+        let update_view::Variant::Editor(message) = message else {
+            return Task::ready(Err(anyhow!("received a non-editor update for an editor")));
+        };
         let project = project.clone();
         cx.spawn(|this, mut cx| async move {
             update_editor_from_message(this, project, message, &mut cx).await
@@ -571,6 +627,71 @@ impl Item for Editor {
         }
     }
 
+    /// Dropping image files from the project panel inserts a reference to them at the cursor,
+    /// formatted for the language under the cursor, instead of opening them.
+    fn handle_drop(&mut self, dropped: &dyn Any, cx: &mut ViewContext<Self>) -> bool {
+        let Some(selection) = dropped.downcast_ref::<DraggedSelection>() else {
+            return false;
+        };
+        if self.read_only(cx) {
+            return false;
+        }
+        let Some(project) = self.project.clone() else {
+            return false;
+        };
+        let Some(buffer) = self.buffer.read(cx).as_singleton() else {
+            return false;
+        };
+
+        let project = project.read(cx);
+        let Some(image_paths) = selection
+            .items()
+            .map(|entry| project.path_for_entry(entry.entry_id, cx))
+            .collect::<Option<Vec<_>>>()
+        else {
+            return false;
+        };
+        if image_paths.is_empty() || !image_paths.iter().all(|path| is_image_path(&path.path)) {
+            return false;
+        }
+
+        let buffer_path = buffer.read(cx).file().map(|file| ProjectPath {
+            worktree_id: file.worktree_id(cx),
+            path: file.path().clone(),
+        });
+        let cursor = self.selections.newest_anchor().head();
+        let language = self.buffer.read(cx).language_at(cursor, cx);
+        let style = ImageReferenceStyle::for_language(language.as_ref().map(|l| l.name().0));
+        let references = image_paths
+            .iter()
+            .map(|image_path| {
+                let path = match &buffer_path {
+                    Some(buffer_path) if buffer_path.worktree_id == image_path.worktree_id => {
+                        relative_path_between(
+                            buffer_path.path.parent().unwrap_or(Path::new("")),
+                            &image_path.path,
+                        )
+                    }
+                    _ => project
+                        .absolute_path(image_path, cx)
+                        .unwrap_or_else(|| image_path.path.to_path_buf())
+                        .to_string_lossy()
+                        .to_string(),
+                };
+                let alt = image_path
+                    .path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                style.format(&path, &alt)
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        self.insert(&references, cx);
+        true
+    }
+
     fn tab_tooltip_text(&self, cx: &AppContext) -> Option<SharedString> {
         let file_path = self
             .buffer()
@@ -1627,6 +1748,51 @@ mod tests {
     use project::FakeFs;
     use std::path::{Path, PathBuf};
 
+    #[test]
+    fn test_relative_path_between() {
+        assert_eq!(
+            relative_path_between(Path::new("docs"), Path::new("docs/images/logo.png")),
+            "images/logo.png"
+        );
+        assert_eq!(
+            relative_path_between(Path::new("docs/guide"), Path::new("assets/logo.png")),
+            "../../assets/logo.png"
+        );
+        assert_eq!(
+            relative_path_between(Path::new(""), Path::new("logo.png")),
+            "logo.png"
+        );
+    }
+
+    #[test]
+    fn test_image_reference_style() {
+        let style = |name: &str| ImageReferenceStyle::for_language(Some(name.into()));
+        assert_eq!(
+            style("Markdown").format("images/logo.png", "logo"),
+            "![logo](images/logo.png)"
+        );
+        assert_eq!(
+            style("Markdown").format("my images/logo.png", "logo"),
+            "![logo](<my images/logo.png>)"
+        );
+        assert_eq!(
+            style("AsciiDoc").format("images/logo.png", "logo"),
+            "image::images/logo.png[logo]"
+        );
+        assert_eq!(
+            style("HTML").format("images/logo.png", "logo"),
+            "<img src=\"images/logo.png\" alt=\"logo\">"
+        );
+        assert_eq!(
+            style("Rust").format("images/logo.png", "logo"),
+            "\"images/logo.png\""
+        );
+        assert_eq!(
+            ImageReferenceStyle::for_language(None),
+            ImageReferenceStyle::PathString
+        );
+    }
+
     #[gpui::test]
     fn test_path_for_file(cx: &mut AppContext) {
         let file = TestFile {
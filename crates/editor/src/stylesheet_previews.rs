@@ -0,0 +1,574 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use gpui::{img, AppContext, Hsla, Model, ObjectFit, Rgba, Task, ViewContext};
+use language::Point;
+use multi_buffer::Anchor;
+use project::{image_store::is_image_path, ImageItem, ProjectPath};
+use settings::Settings;
+use ui::{prelude::*, Tooltip};
+use util::{post_inc, ResultExt};
+
+use crate::{
+    display_map::{BlockPlacement, BlockProperties, BlockStyle, CustomBlockId, Inlay},
+    hover_links::InlayHighlight,
+    Editor, EditorMode, EditorSettings, InlayId,
+};
+
+const REFRESH_DEBOUNCE: Duration = Duration::from_millis(250);
+const STYLESHEET_LANGUAGES: &[&str] = &["CSS", "SCSS", "Sass", "Less", "PostCSS"];
+const SWATCH: &str = "■ ";
+
+enum ColorSwatches {}
+
+/// Color swatches and `url(...)` image thumbnails shown in stylesheet buffers.
+#[derive(Default)]
+pub(crate) struct StylesheetPreviews {
+    swatches: Vec<InlayId>,
+    thumbnail_blocks: HashSet<CustomBlockId>,
+    /// Images referenced by the buffer, kept open so that they are not reloaded on every edit.
+    image_items: HashMap<ProjectPath, Model<ImageItem>>,
+    refresh_task: Option<Task<Option<()>>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum StylesheetReference {
+    Color { offset: usize, color: Hsla },
+    Image { offset: usize, path: String },
+}
+
+pub(crate) fn refresh_stylesheet_previews(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    if editor.mode != EditorMode::Full {
+        return;
+    }
+    editor.stylesheet_previews.refresh_task = Some(cx.spawn(|editor, mut cx| {
+        async move {
+            cx.background_executor().timer(REFRESH_DEBOUNCE).await;
+            let (project, thumbnail_rows) =
+                editor.update(&mut cx, |editor, cx| update_swatches(editor, cx))?;
+
+            let mut image_items = HashMap::default();
+            for (_, paths) in &thumbnail_rows {
+                for path in paths {
+                    if image_items.contains_key(path) {
+                        continue;
+                    }
+                    let cached = editor.update(&mut cx, |editor, _| {
+                        editor.stylesheet_previews.image_items.get(path).cloned()
+                    })?;
+                    let image_item = match (cached, &project) {
+                        (Some(image_item), _) => image_item,
+                        (None, Some(project)) => {
+                            let Some(image_item) = project
+                                .update(&mut cx, |project, cx| {
+                                    project.open_image(path.clone(), cx)
+                                })?
+                                .await
+                                .log_err()
+                            else {
+                                continue;
+                            };
+                            image_item
+                        }
+                        (None, None) => continue,
+                    };
+                    image_items.insert(path.clone(), image_item);
+                }
+            }
+
+            editor.update(&mut cx, |editor, cx| {
+                editor.stylesheet_previews.image_items = image_items;
+                update_thumbnails(editor, thumbnail_rows, cx);
+            })?;
+            anyhow::Ok(())
+        }
+        .log_err()
+    }));
+}
+
+/// Replaces the color swatches and returns the images to show below each row of the buffer.
+fn update_swatches(
+    editor: &mut Editor,
+    cx: &mut ViewContext<Editor>,
+) -> (
+    Option<Model<project::Project>>,
+    Vec<(Anchor, Vec<ProjectPath>)>,
+) {
+    let to_remove = std::mem::take(&mut editor.stylesheet_previews.swatches);
+    editor.clear_highlights::<ColorSwatches>(cx);
+
+    let references = stylesheet_references(editor, cx);
+    let snapshot = editor.buffer.read(cx).snapshot(cx);
+    let mut swatches = Vec::new();
+    let mut thumbnail_rows = Vec::<(Anchor, Vec<ProjectPath>)>::new();
+    let mut last_thumbnail_row = None;
+    for reference in references {
+        match reference {
+            StylesheetReference::Color { offset, color } => {
+                let id = post_inc(&mut editor.next_inlay_id);
+                swatches.push((
+                    Inlay::color(id, snapshot.anchor_before(offset), SWATCH),
+                    color,
+                ));
+            }
+            StylesheetReference::Image { offset, path } => {
+                let Some(project_path) = resolve_image_path(editor, &path, cx) else {
+                    continue;
+                };
+                let row = snapshot.offset_to_point(offset).row;
+                if last_thumbnail_row == Some(row) {
+                    if let Some((_, paths)) = thumbnail_rows.last_mut() {
+                        if !paths.contains(&project_path) {
+                            paths.push(project_path);
+                        }
+                    }
+                } else {
+                    let anchor = snapshot.anchor_after(Point::new(row, 0));
+                    thumbnail_rows.push((anchor, vec![project_path]));
+                    last_thumbnail_row = Some(row);
+                }
+            }
+        }
+    }
+
+    editor.stylesheet_previews.swatches = swatches.iter().map(|(inlay, _)| inlay.id).collect();
+    let highlights = swatches
+        .iter()
+        .map(|(inlay, color)| {
+            (
+                InlayHighlight {
+                    inlay: inlay.id,
+                    inlay_position: inlay.position,
+                    range: 0.."■".len(),
+                },
+                *color,
+            )
+        })
+        .collect::<Vec<_>>();
+    editor.splice_inlays(
+        to_remove,
+        swatches.into_iter().map(|(inlay, _)| inlay).collect(),
+        cx,
+    );
+    for (highlight, color) in highlights {
+        editor.highlight_inlays::<ColorSwatches>(
+            vec![highlight],
+            gpui::HighlightStyle {
+                color: Some(color),
+                ..Default::default()
+            },
+            cx,
+        );
+    }
+
+    (editor.project.clone(), thumbnail_rows)
+}
+
+fn update_thumbnails(
+    editor: &mut Editor,
+    thumbnail_rows: Vec<(Anchor, Vec<ProjectPath>)>,
+    cx: &mut ViewContext<Editor>,
+) {
+    let old_blocks = std::mem::take(&mut editor.stylesheet_previews.thumbnail_blocks);
+    if !old_blocks.is_empty() {
+        editor.remove_blocks(old_blocks, None, cx);
+    }
+
+    let workspace = editor
+        .workspace
+        .as_ref()
+        .map(|(workspace, _)| workspace.clone());
+    let blocks = thumbnail_rows
+        .into_iter()
+        .filter_map(|(anchor, paths)| {
+            let images = paths
+                .into_iter()
+                .filter_map(|path| {
+                    let image_item = editor.stylesheet_previews.image_items.get(&path)?;
+                    Some((path, image_item.clone()))
+                })
+                .collect::<Vec<_>>();
+            if images.is_empty() {
+                return None;
+            }
+            let workspace = workspace.clone();
+            Some(BlockProperties {
+                placement: BlockPlacement::Below(anchor),
+                height: 1,
+                style: BlockStyle::Flex,
+                priority: 0,
+                render: Arc::new(move |cx| {
+                    let line_height = cx.line_height;
+                    let images = images
+                        .iter()
+                        .map(|(path, image_item)| (path, image_item.read(cx).image.clone()))
+                        .collect::<Vec<_>>();
+                    h_flex()
+                        .id(cx.block_id)
+                        .h(line_height)
+                        .pl(cx.anchor_x)
+                        .gap_1()
+                        .children(images.into_iter().enumerate().map(|(ix, (path, image))| {
+                            let file_name = path
+                                .path
+                                .file_name()
+                                .map(|file_name| file_name.to_string_lossy().to_string())
+                                .unwrap_or_default();
+                            let project_path = path.clone();
+                            let workspace = workspace.clone();
+                            div()
+                                .id(ix)
+                                .h_full()
+                                .cursor_pointer()
+                                .tooltip(move |cx| {
+                                    Tooltip::with_meta(
+                                        file_name.clone(),
+                                        None,
+                                        "Click to open in the image viewer",
+                                        cx,
+                                    )
+                                })
+                                .on_click(move |_, cx| {
+                                    if let Some(workspace) = &workspace {
+                                        workspace
+                                            .update(cx, |workspace, cx| {
+                                                workspace
+                                                    .open_path(project_path.clone(), None, true, cx)
+                                                    .detach_and_log_err(cx);
+                                            })
+                                            .ok();
+                                    }
+                                })
+                                .child(
+                                    img(image)
+                                        .h_full()
+                                        .max_w(line_height * 4.)
+                                        .object_fit(ObjectFit::ScaleDown),
+                                )
+                        }))
+                        .into_any_element()
+                }),
+            })
+        })
+        .collect::<Vec<_>>();
+    editor.stylesheet_previews.thumbnail_blocks =
+        editor.insert_blocks(blocks, None, cx).into_iter().collect();
+}
+
+fn stylesheet_references(editor: &Editor, cx: &AppContext) -> Vec<StylesheetReference> {
+    if !EditorSettings::get_global(cx).stylesheet_previews {
+        return Vec::new();
+    }
+    let Some(buffer) = editor.buffer.read(cx).as_singleton() else {
+        return Vec::new();
+    };
+    let buffer = buffer.read(cx);
+    let is_stylesheet = buffer.language().map_or(false, |language| {
+        STYLESHEET_LANGUAGES.contains(&language.name().0.as_ref())
+    });
+    if !is_stylesheet {
+        return Vec::new();
+    }
+    find_stylesheet_references(&buffer.text())
+}
+
+/// Resolves a `url(...)` reference against the buffer's directory. Paths starting with `/` are
+/// resolved against the worktree root.
+fn resolve_image_path(editor: &Editor, url: &str, cx: &AppContext) -> Option<ProjectPath> {
+    let buffer = editor.buffer.read(cx).as_singleton()?;
+    let file = buffer.read(cx).file()?;
+    let path = match url.strip_prefix('/') {
+        Some(url) => normalize_relative_path(Path::new(""), url)?,
+        None => normalize_relative_path(file.path().parent().unwrap_or(Path::new("")), url)?,
+    };
+    if !is_image_path(&path) {
+        return None;
+    }
+    Some(ProjectPath {
+        worktree_id: file.worktree_id(cx),
+        path: path.into(),
+    })
+}
+
+fn normalize_relative_path(directory: &Path, url: &str) -> Option<PathBuf> {
+    let mut path = directory.to_path_buf();
+    for component in Path::new(url).components() {
+        match component {
+            Component::Normal(component) => path.push(component),
+            Component::ParentDir => {
+                if !path.pop() {
+                    return None;
+                }
+            }
+            Component::CurDir => {}
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(path)
+}
+
+fn find_stylesheet_references(text: &str) -> Vec<StylesheetReference> {
+    let bytes = text.as_bytes();
+    let mut references = Vec::new();
+    let mut depth = 0_usize;
+    let mut in_value = false;
+    let mut ix = 0;
+    while ix < bytes.len() {
+        match bytes[ix] {
+            b'/' if bytes.get(ix + 1) == Some(&b'*') => {
+                ix = text[ix + 2..]
+                    .find("*/")
+                    .map_or(bytes.len(), |end| ix + 2 + end + 2);
+                continue;
+            }
+            quote @ (b'"' | b'\'') => {
+                let mut end = ix + 1;
+                while end < bytes.len() && bytes[end] != quote {
+                    end += if bytes[end] == b'\\' { 2 } else { 1 };
+                }
+                ix = end + 1;
+                continue;
+            }
+            b'{' => {
+                depth += 1;
+                in_value = false;
+            }
+            b'}' => {
+                depth = depth.saturating_sub(1);
+                in_value = false;
+            }
+            b';' => in_value = false,
+            b':' if depth > 0 => in_value = true,
+            b'#' if in_value => {
+                let end = ix
+                    + 1
+                    + bytes[ix + 1..]
+                        .iter()
+                        .take_while(|byte| byte.is_ascii_alphanumeric())
+                        .count();
+                if let Some(color) = parse_hex_color(&text[ix + 1..end]) {
+                    references.push(StylesheetReference::Color { offset: ix, color });
+                }
+                ix = end;
+                continue;
+            }
+            b'(' => {
+                let name_start = text[..ix]
+                    .rfind(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+                    .map_or(0, |start| start + 1);
+                let name = text[name_start..ix].to_ascii_lowercase();
+                let reference = match name.as_str() {
+                    "url" | "rgb" | "rgba" | "hsl" | "hsla" => text[ix..].find(')').map(|len| {
+                        let arguments = &text[ix + 1..ix + len];
+                        let reference = match name.as_str() {
+                            "url" => parse_url(arguments).map(|path| StylesheetReference::Image {
+                                offset: name_start,
+                                path,
+                            }),
+                            "rgb" | "rgba" if in_value => {
+                                parse_rgb(arguments).map(|color| StylesheetReference::Color {
+                                    offset: name_start,
+                                    color,
+                                })
+                            }
+                            "hsl" | "hsla" if in_value => {
+                                parse_hsl(arguments).map(|color| StylesheetReference::Color {
+                                    offset: name_start,
+                                    color,
+                                })
+                            }
+                            _ => None,
+                        };
+                        (reference, ix + len + 1)
+                    }),
+                    _ => None,
+                };
+                if let Some((reference, end)) = reference {
+                    references.extend(reference);
+                    ix = end;
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        ix += 1;
+    }
+    references
+}
+
+/// Returns the path of a local image referenced by `url(...)`, skipping data and remote URLs.
+fn parse_url(arguments: &str) -> Option<String> {
+    let url = arguments.trim().trim_matches(|c| c == '"' || c == '\'');
+    let url = url.split(['?', '#']).next()?;
+    if url.is_empty() || url.contains(':') || url.starts_with("//") {
+        return None;
+    }
+    Some(url.to_string())
+}
+
+fn parse_hex_color(hex: &str) -> Option<Hsla> {
+    if !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+    let digit = |ix: usize| u8::from_str_radix(&hex[ix..ix + 1], 16).ok();
+    let pair = |ix: usize| u8::from_str_radix(&hex[ix..ix + 2], 16).ok();
+    let (r, g, b, a) = match hex.len() {
+        3 => (digit(0)? * 17, digit(1)? * 17, digit(2)? * 17, 255),
+        4 => (
+            digit(0)? * 17,
+            digit(1)? * 17,
+            digit(2)? * 17,
+            digit(3)? * 17,
+        ),
+        6 => (pair(0)?, pair(2)?, pair(4)?, 255),
+        8 => (pair(0)?, pair(2)?, pair(4)?, pair(6)?),
+        _ => return None,
+    };
+    Some(
+        Rgba {
+            r: r as f32 / 255.,
+            g: g as f32 / 255.,
+            b: b as f32 / 255.,
+            a: a as f32 / 255.,
+        }
+        .into(),
+    )
+}
+
+/// Splits the arguments of a color function, accepting both the comma separated and the space
+/// separated syntax.
+fn color_arguments(arguments: &str) -> Option<Vec<&str>> {
+    let arguments = arguments
+        .split([',', ' ', '/', '\t', '\n'])
+        .filter(|argument| !argument.is_empty())
+        .collect::<Vec<_>>();
+    matches!(arguments.len(), 3 | 4).then_some(arguments)
+}
+
+/// Parses a number or a percentage, with percentages mapped onto `0..=scale`.
+fn parse_component(component: &str, scale: f32) -> Option<f32> {
+    match component.strip_suffix('%') {
+        Some(percentage) => Some(percentage.parse::<f32>().ok()? / 100. * scale),
+        None => component.parse::<f32>().ok(),
+    }
+}
+
+fn parse_alpha(arguments: &[&str]) -> Option<f32> {
+    match arguments.get(3) {
+        Some(alpha) => Some(parse_component(alpha, 1.)?.clamp(0., 1.)),
+        None => Some(1.),
+    }
+}
+
+fn parse_rgb(arguments: &str) -> Option<Hsla> {
+    let arguments = color_arguments(arguments)?;
+    let channel = |ix: usize| Some((parse_component(arguments[ix], 255.)? / 255.).clamp(0., 1.));
+    Some(
+        Rgba {
+            r: channel(0)?,
+            g: channel(1)?,
+            b: channel(2)?,
+            a: parse_alpha(&arguments)?,
+        }
+        .into(),
+    )
+}
+
+fn parse_hsl(arguments: &str) -> Option<Hsla> {
+    let arguments = color_arguments(arguments)?;
+    let hue = arguments[0].strip_suffix("deg").unwrap_or(arguments[0]);
+    let hue = (hue.parse::<f32>().ok()? / 360.).rem_euclid(1.);
+    let percentage = |ix: usize| {
+        let component = arguments[ix].strip_suffix('%').unwrap_or(arguments[ix]);
+        Some((component.parse::<f32>().ok()? / 100.).clamp(0., 1.))
+    };
+    Some(gpui::hsla(
+        hue,
+        percentage(1)?,
+        percentage(2)?,
+        parse_alpha(&arguments)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_stylesheet_references() {
+        let text = r##"
+            /* color: #123456; */
+            #header .title:hover {
+                color: #fff;
+                background: rgba(0, 0, 0, 50%) url("../images/bg.png?v=2");
+                border-color: hsl(120deg 100% 50% / 0.5);
+                content: "#abc";
+                mask: url(data:image/png;base64,AAAA);
+                list-style-image: url(https://example.com/bullet.png);
+                width: calc(100% - 10px);
+            }
+        "##;
+        let references = find_stylesheet_references(text);
+        let expected_offsets = [
+            text.find("#fff").unwrap(),
+            text.find("rgba").unwrap(),
+            text.find("url(\"..").unwrap(),
+            text.find("hsl").unwrap(),
+        ];
+        assert_eq!(
+            references
+                .iter()
+                .map(|reference| match reference {
+                    StylesheetReference::Color { offset, .. }
+                    | StylesheetReference::Image { offset, .. } => *offset,
+                })
+                .collect::<Vec<_>>(),
+            expected_offsets
+        );
+        assert_eq!(
+            references[2],
+            StylesheetReference::Image {
+                offset: expected_offsets[2],
+                path: "../images/bg.png".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_colors() {
+        let white: Hsla = Rgba {
+            r: 1.,
+            g: 1.,
+            b: 1.,
+            a: 1.,
+        }
+        .into();
+        assert_eq!(parse_hex_color("fff"), Some(white));
+        assert_eq!(parse_hex_color("ffffffff"), Some(white));
+        assert_eq!(parse_hex_color("fffff"), None);
+        assert_eq!(parse_hex_color("header"), None);
+        assert_eq!(parse_rgb("255, 255, 255"), Some(white));
+        assert_eq!(parse_rgb("100% 100% 100% / 1"), Some(white));
+        assert_eq!(parse_rgb("var(--red), 0, 0"), None);
+        assert_eq!(
+            parse_hsl("120, 100%, 50%, 0.5"),
+            Some(gpui::hsla(1. / 3., 1., 0.5, 0.5))
+        );
+    }
+
+    #[test]
+    fn test_normalize_relative_path() {
+        assert_eq!(
+            normalize_relative_path(Path::new("styles/components"), "../../images/./bg.png"),
+            Some(PathBuf::from("images/bg.png"))
+        );
+        assert_eq!(
+            normalize_relative_path(Path::new("styles"), "../../bg.png"),
+            None
+        );
+    }
+}
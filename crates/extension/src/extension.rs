@@ -98,6 +98,12 @@ pub trait Extension: Send + Sync + 'static {
         worktree: Option<Arc<dyn WorktreeDelegate>>,
     ) -> Result<SlashCommandOutput>;
 
+    async fn run_image_viewer_command(
+        &self,
+        command: ImageViewerCommand,
+        image: ImageViewerImage,
+    ) -> Result<ImageViewerCommandOutput>;
+
     async fn context_server_command(
         &self,
         context_server_id: Arc<str>,
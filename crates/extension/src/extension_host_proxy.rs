@@ -8,7 +8,7 @@ use language::{LanguageMatcher, LanguageName, LanguageServerBinaryStatus, Loaded
 use lsp::LanguageServerName;
 use parking_lot::RwLock;
 
-use crate::{Extension, SlashCommand};
+use crate::{Extension, ImageViewerCommand, SlashCommand};
 
 #[derive(Default)]
 struct GlobalExtensionHostProxy(Arc<ExtensionHostProxy>);
@@ -27,6 +27,7 @@ pub struct ExtensionHostProxy {
     language_server_proxy: RwLock<Option<Arc<dyn ExtensionLanguageServerProxy>>>,
     snippet_proxy: RwLock<Option<Arc<dyn ExtensionSnippetProxy>>>,
     slash_command_proxy: RwLock<Option<Arc<dyn ExtensionSlashCommandProxy>>>,
+    image_viewer_command_proxy: RwLock<Option<Arc<dyn ExtensionImageViewerCommandProxy>>>,
     context_server_proxy: RwLock<Option<Arc<dyn ExtensionContextServerProxy>>>,
     indexed_docs_provider_proxy: RwLock<Option<Arc<dyn ExtensionIndexedDocsProviderProxy>>>,
 }
@@ -52,6 +53,7 @@ impl ExtensionHostProxy {
             language_server_proxy: RwLock::default(),
             snippet_proxy: RwLock::default(),
             slash_command_proxy: RwLock::default(),
+            image_viewer_command_proxy: RwLock::default(),
             context_server_proxy: RwLock::default(),
             indexed_docs_provider_proxy: RwLock::default(),
         }
@@ -81,6 +83,15 @@ impl ExtensionHostProxy {
         self.slash_command_proxy.write().replace(Arc::new(proxy));
     }
 
+    pub fn register_image_viewer_command_proxy(
+        &self,
+        proxy: impl ExtensionImageViewerCommandProxy,
+    ) {
+        self.image_viewer_command_proxy
+            .write()
+            .replace(Arc::new(proxy));
+    }
+
     pub fn register_context_server_proxy(&self, proxy: impl ExtensionContextServerProxy) {
         self.context_server_proxy.write().replace(Arc::new(proxy));
     }
@@ -285,6 +296,28 @@ impl ExtensionSlashCommandProxy for ExtensionHostProxy {
     }
 }
 
+pub trait ExtensionImageViewerCommandProxy: Send + Sync + 'static {
+    fn register_image_viewer_command(
+        &self,
+        extension: Arc<dyn Extension>,
+        command: ImageViewerCommand,
+    );
+}
+
+impl ExtensionImageViewerCommandProxy for ExtensionHostProxy {
+    fn register_image_viewer_command(
+        &self,
+        extension: Arc<dyn Extension>,
+        command: ImageViewerCommand,
+    ) {
+        let Some(proxy) = self.image_viewer_command_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.register_image_viewer_command(extension, command)
+    }
+}
+
 pub trait ExtensionContextServerProxy: Send + Sync + 'static {
     fn register_context_server(
         &self,
@@ -1,8 +1,10 @@
+mod image_viewer;
 mod lsp;
 mod slash_command;
 
 use std::ops::Range;
 
+pub use image_viewer::*;
 pub use lsp::*;
 pub use slash_command::*;
 
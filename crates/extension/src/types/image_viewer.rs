@@ -0,0 +1,26 @@
+/// A command that can be run on the image shown in the image viewer.
+#[derive(Debug, Clone)]
+pub struct ImageViewerCommand {
+    /// The name of the command.
+    pub name: String,
+    /// The description of the command, shown as the tooltip of its toolbar button.
+    pub description: String,
+}
+
+/// The image an image viewer command is run on.
+#[derive(Debug, Clone)]
+pub struct ImageViewerImage {
+    /// The path to the image, relative to the root of its worktree.
+    pub path: String,
+    /// The encoded contents of the image.
+    pub bytes: Vec<u8>,
+}
+
+/// The output of an image viewer command.
+#[derive(Debug, Clone)]
+pub struct ImageViewerCommandOutput {
+    /// A message to show to the user, such as the URL the image was uploaded to.
+    pub message: Option<String>,
+    /// New contents for the image, which are written back to its file.
+    pub bytes: Option<Vec<u8>>,
+}
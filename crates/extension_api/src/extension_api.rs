@@ -19,6 +19,9 @@ pub use wit::{
         github_release_by_tag_name, latest_github_release, GithubRelease, GithubReleaseAsset,
         GithubReleaseOptions,
     },
+    zed::extension::image_viewer::{
+        ImageViewerCommand, ImageViewerCommandOutput, ImageViewerImage,
+    },
     zed::extension::nodejs::{
         node_binary_path, npm_install_package, npm_package_installed_version,
         npm_package_latest_version,
@@ -129,6 +132,15 @@ pub trait Extension: Send + Sync {
         Err("`run_slash_command` not implemented".to_string())
     }
 
+    /// Returns the output from running the provided image viewer command on an image.
+    fn run_image_viewer_command(
+        &self,
+        _command: ImageViewerCommand,
+        _image: ImageViewerImage,
+    ) -> Result<ImageViewerCommandOutput, String> {
+        Err("`run_image_viewer_command` not implemented".to_string())
+    }
+
     /// Returns the command used to start a context server.
     fn context_server_command(
         &mut self,
@@ -279,6 +291,13 @@ impl wit::Guest for Component {
         extension().run_slash_command(command, args, worktree)
     }
 
+    fn run_image_viewer_command(
+        command: ImageViewerCommand,
+        image: ImageViewerImage,
+    ) -> Result<ImageViewerCommandOutput, String> {
+        extension().run_image_viewer_command(command, image)
+    }
+
     fn context_server_command(
         context_server_id: String,
         project: &Project,
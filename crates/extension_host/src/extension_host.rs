@@ -14,8 +14,9 @@ use extension::extension_builder::{CompileExtensionOptions, ExtensionBuilder};
 pub use extension::ExtensionManifest;
 use extension::{
     ExtensionContextServerProxy, ExtensionGrammarProxy, ExtensionHostProxy,
-    ExtensionIndexedDocsProviderProxy, ExtensionLanguageProxy, ExtensionLanguageServerProxy,
-    ExtensionSlashCommandProxy, ExtensionSnippetProxy, ExtensionThemeProxy,
+    ExtensionImageViewerCommandProxy, ExtensionIndexedDocsProviderProxy, ExtensionLanguageProxy,
+    ExtensionLanguageServerProxy, ExtensionSlashCommandProxy, ExtensionSnippetProxy,
+    ExtensionThemeProxy,
 };
 use fs::{Fs, RemoveOptions};
 use futures::{
@@ -1213,6 +1214,16 @@ impl ExtensionStore {
                         );
                     }
 
+                    for (command_name, command) in &manifest.image_viewer_commands {
+                        this.proxy.register_image_viewer_command(
+                            extension.clone(),
+                            extension::ImageViewerCommand {
+                                name: command_name.to_string(),
+                                description: command.description.to_string(),
+                            },
+                        );
+                    }
+
                     for (id, _context_server_entry) in &manifest.context_servers {
                         this.proxy
                             .register_context_server(extension.clone(), id.clone(), cx);
@@ -160,6 +160,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         language_servers: BTreeMap::default(),
                         context_servers: BTreeMap::default(),
                         slash_commands: BTreeMap::default(),
+                        image_viewer_commands: BTreeMap::default(),
                         indexed_docs_providers: BTreeMap::default(),
                         snippets: None,
                     }),
@@ -187,6 +188,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         language_servers: BTreeMap::default(),
                         context_servers: BTreeMap::default(),
                         slash_commands: BTreeMap::default(),
+                        image_viewer_commands: BTreeMap::default(),
                         indexed_docs_providers: BTreeMap::default(),
                         snippets: None,
                     }),
@@ -348,6 +350,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                 language_servers: BTreeMap::default(),
                 context_servers: BTreeMap::default(),
                 slash_commands: BTreeMap::default(),
+                image_viewer_commands: BTreeMap::default(),
                 indexed_docs_providers: BTreeMap::default(),
                 snippets: None,
             }),
@@ -4,7 +4,8 @@ use crate::ExtensionManifest;
 use anyhow::{anyhow, bail, Context as _, Result};
 use async_trait::async_trait;
 use extension::{
-    CodeLabel, Command, Completion, ExtensionHostProxy, KeyValueStoreDelegate, ProjectDelegate,
+    CodeLabel, Command, Completion, ExtensionHostProxy, ImageViewerCommand,
+    ImageViewerCommandOutput, ImageViewerImage, KeyValueStoreDelegate, ProjectDelegate,
     SlashCommand, SlashCommandArgumentCompletion, SlashCommandOutput, Symbol, WorktreeDelegate,
 };
 use fs::{normalize_path, Fs};
@@ -237,6 +238,25 @@ impl extension::Extension for WasmExtension {
         .await
     }
 
+    async fn run_image_viewer_command(
+        &self,
+        command: ImageViewerCommand,
+        image: ImageViewerImage,
+    ) -> Result<ImageViewerCommandOutput> {
+        self.call(|extension, store| {
+            async move {
+                let output = extension
+                    .call_run_image_viewer_command(store, &command.into(), &image.into())
+                    .await?
+                    .map_err(|err| anyhow!("{err}"))?;
+
+                Ok(output.into())
+            }
+            .boxed()
+        })
+        .await
+    }
+
     async fn context_server_command(
         &self,
         context_server_id: Arc<str>,
@@ -21,6 +21,9 @@ use wasmtime::{
 #[cfg(test)]
 pub use latest::CodeLabelSpanLiteral;
 pub use latest::{
+    zed::extension::image_viewer::{
+        ImageViewerCommand, ImageViewerCommandOutput, ImageViewerImage,
+    },
     zed::extension::lsp::{
         Completion, CompletionKind, CompletionLabelDetails, InsertTextFormat, Symbol, SymbolKind,
     },
@@ -422,6 +425,25 @@ impl Extension {
         }
     }
 
+    pub async fn call_run_image_viewer_command(
+        &self,
+        store: &mut Store<WasmState>,
+        command: &ImageViewerCommand,
+        image: &ImageViewerImage,
+    ) -> Result<Result<ImageViewerCommandOutput, String>> {
+        match self {
+            Extension::V020(ext) => {
+                ext.call_run_image_viewer_command(store, command, image)
+                    .await
+            }
+            Extension::V001(_) | Extension::V004(_) | Extension::V006(_) | Extension::V010(_) => {
+                Err(anyhow!(
+                    "`run_image_viewer_command` not available prior to v0.2.0"
+                ))
+            }
+        }
+    }
+
     pub async fn call_context_server_command(
         &self,
         store: &mut Store<WasmState>,
@@ -1,3 +1,6 @@
+use crate::wasm_host::wit::since_v0_2_0::image_viewer::{
+    ImageViewerCommand, ImageViewerCommandOutput, ImageViewerImage,
+};
 use crate::wasm_host::wit::since_v0_2_0::slash_command::SlashCommandOutputSection;
 use crate::wasm_host::wit::{CompletionKind, CompletionLabelDetails, InsertTextFormat, SymbolKind};
 use crate::wasm_host::{wit::ToWasmtimeResult, WasmState};
@@ -237,6 +240,33 @@ impl From<SlashCommandOutputSection> for extension::SlashCommandOutputSection {
     }
 }
 
+impl From<extension::ImageViewerCommand> for ImageViewerCommand {
+    fn from(value: extension::ImageViewerCommand) -> Self {
+        Self {
+            name: value.name,
+            description: value.description,
+        }
+    }
+}
+
+impl From<extension::ImageViewerImage> for ImageViewerImage {
+    fn from(value: extension::ImageViewerImage) -> Self {
+        Self {
+            path: value.path,
+            bytes: value.bytes,
+        }
+    }
+}
+
+impl From<ImageViewerCommandOutput> for extension::ImageViewerCommandOutput {
+    fn from(value: ImageViewerCommandOutput) -> Self {
+        Self {
+            message: value.message,
+            bytes: value.bytes,
+        }
+    }
+}
+
 impl From<SlashCommandArgumentCompletion> for extension::SlashCommandArgumentCompletion {
     fn from(value: SlashCommandArgumentCompletion) -> Self {
         Self {
@@ -588,6 +618,9 @@ impl platform::Host for WasmState {
 #[async_trait]
 impl slash_command::Host for WasmState {}
 
+#[async_trait]
+impl image_viewer::Host for WasmState {}
+
 #[async_trait]
 impl ExtensionImports for WasmState {
     async fn get_settings(
@@ -14,14 +14,17 @@ use file_finder_settings::{FileFinderSettings, FileFinderWidth};
 use file_icons::FileIcons;
 use fuzzy::{CharBag, PathMatch, PathMatchCandidate};
 use gpui::{
-    actions, Action, AnyElement, AppContext, DismissEvent, EventEmitter, FocusHandle,
-    FocusableView, KeyContext, Model, Modifiers, ModifiersChangedEvent, ParentElement, Render,
-    Styled, Task, View, ViewContext, VisualContext, WeakView,
+    actions, img, Action, AnyElement, AppContext, DismissEvent, EventEmitter, FocusHandle,
+    FocusableView, Image, KeyContext, Model, Modifiers, ModifiersChangedEvent, ObjectFit,
+    ParentElement, Render, Styled, Subscription, Task, View, ViewContext, VisualContext, WeakView,
 };
 use new_path_prompt::NewPathPrompt;
 use open_path_prompt::OpenPathPrompt;
 use picker::{Picker, PickerDelegate};
-use project::{PathMatchCandidateSet, Project, ProjectPath, WorktreeId};
+use project::{
+    image_store::is_image_path, PathMatchCandidateSet, Project, ProjectPath, ThumbnailPriority,
+    WorktreeId,
+};
 use settings::Settings;
 use std::{
     cmp,
@@ -57,6 +60,20 @@ pub struct FileFinder {
     picker: View<Picker<FileFinderDelegate>>,
     picker_focus_handle: FocusHandle,
     init_modifiers: Option<Modifiers>,
+    image_preview: Option<ImagePreview>,
+    image_preview_task: Option<Task<()>>,
+    _picker_subscription: Subscription,
+}
+
+/// The size of the image preview's thumbnail in pixels, twice the size it is shown at so that
+/// it stays sharp on high density displays.
+const IMAGE_PREVIEW_THUMBNAIL_SIZE: u32 = 320;
+
+/// A thumbnail of the highlighted match, shown when it is an image.
+struct ImagePreview {
+    project_path: ProjectPath,
+    image: Option<Arc<Image>>,
+    dimensions: Option<(u32, u32)>,
 }
 
 pub fn init_settings(cx: &mut AppContext) {
@@ -161,13 +178,122 @@ impl FileFinder {
         picker.update(cx, |picker, _| {
             picker.delegate.focus_handle = picker_focus_handle.clone();
         });
+        let picker_subscription = cx.observe(&picker, |this, _, cx| this.update_image_preview(cx));
         Self {
             picker,
             picker_focus_handle,
             init_modifiers: cx.modifiers().modified().then_some(cx.modifiers()),
+            image_preview: None,
+            image_preview_task: None,
+            _picker_subscription: picker_subscription,
         }
     }
 
+    fn update_image_preview(&mut self, cx: &mut ViewContext<Self>) {
+        let delegate = &self.picker.read(cx).delegate;
+        let project = delegate.project.clone();
+        let project_path = delegate
+            .matches
+            .get(delegate.selected_index())
+            .map(Match::project_path)
+            .filter(|project_path| is_image_path(&project_path.path));
+        if self
+            .image_preview
+            .as_ref()
+            .map(|preview| &preview.project_path)
+            == project_path.as_ref()
+        {
+            return;
+        }
+
+        let Some(project_path) = project_path else {
+            self.image_preview = None;
+            self.image_preview_task = None;
+            cx.notify();
+            return;
+        };
+
+        self.image_preview = Some(ImagePreview {
+            project_path: project_path.clone(),
+            image: None,
+            dimensions: None,
+        });
+        let load_thumbnail = project.update(cx, |project, cx| {
+            project.load_thumbnail(
+                project_path,
+                IMAGE_PREVIEW_THUMBNAIL_SIZE,
+                ThumbnailPriority::Visible,
+                cx,
+            )
+        });
+        self.image_preview_task = Some(cx.spawn(|this, mut cx| async move {
+            let Some(thumbnail) = load_thumbnail.await.log_err() else {
+                return;
+            };
+            this.update(&mut cx, |this, cx| {
+                if let Some(preview) = this.image_preview.as_mut() {
+                    preview.image = Some(thumbnail.image.clone());
+                    preview.dimensions = thumbnail.dimensions;
+                    cx.notify();
+                }
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn render_image_preview(&self, cx: &mut ViewContext<Self>) -> Option<impl IntoElement> {
+        let preview = self.image_preview.as_ref()?;
+        let image = preview.image.clone()?;
+        let file_name = preview
+            .project_path
+            .path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        Some(
+            h_flex()
+                .p_2()
+                .gap_3()
+                .border_t_1()
+                .border_color(cx.theme().colors().border_variant)
+                .bg(cx.theme().colors().elevated_surface_background)
+                .child(
+                    img(image)
+                        .max_w(px(160.))
+                        .max_h(px(120.))
+                        .object_fit(ObjectFit::ScaleDown),
+                )
+                .child(
+                    v_flex()
+                        .gap_1()
+                        .child(Label::new(file_name))
+                        .when_some(preview.dimensions, |this, (width, height)| {
+                            this.child(
+                                Label::new(format!("{width} × {height}"))
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                        })
+                        .when_some(
+                            preview
+                                .project_path
+                                .path
+                                .parent()
+                                .filter(|parent| !parent.as_os_str().is_empty()),
+                            |this, parent| {
+                                this.child(
+                                    Label::new(parent.to_string_lossy().to_string())
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted),
+                                )
+                            },
+                        ),
+                ),
+        )
+    }
+
     fn handle_modifiers_changed(
         &mut self,
         event: &ModifiersChangedEvent,
@@ -290,6 +416,7 @@ impl Render for FileFinder {
             .on_action(cx.listener(Self::go_to_file_split_up))
             .on_action(cx.listener(Self::go_to_file_split_down))
             .child(self.picker.clone())
+            .children(self.render_image_preview(cx))
     }
 }
 
@@ -369,6 +496,16 @@ impl Match {
         }
     }
 
+    fn project_path(&self) -> ProjectPath {
+        match self {
+            Match::History { path, .. } => path.project.clone(),
+            Match::Search(panel_match) => ProjectPath {
+                worktree_id: WorktreeId::from_usize(panel_match.0.worktree_id),
+                path: panel_match.0.path.clone(),
+            },
+        }
+    }
+
     fn panel_match(&self) -> Option<&ProjectPanelOrdMatch> {
         match self {
             Match::History { panel_match, .. } => panel_match.as_ref(),
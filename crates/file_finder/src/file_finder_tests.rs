@@ -4,7 +4,7 @@ use super::*;
 use editor::Editor;
 use gpui::{Entity, TestAppContext, VisualTestContext};
 use menu::{Confirm, SelectNext, SelectPrev};
-use project::{RemoveOptions, FS_WATCH_LATENCY};
+use project::{image_store::TEST_PNG, RemoveOptions, FS_WATCH_LATENCY};
 use serde_json::json;
 use workspace::{AppState, ToggleFileFinder, Workspace};
 
@@ -1952,6 +1952,59 @@ async fn test_repeat_toggle_action(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_image_preview_for_selected_match(cx: &mut TestAppContext) {
+    let app_state = init_test(cx);
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            "/root",
+            json!({
+                "icons": {},
+                "notes.txt": "",
+            }),
+        )
+        .await;
+    // A png file that consists of a single white pixel
+    app_state
+        .fs
+        .as_fake()
+        .insert_file("/root/icons/icon.png", TEST_PNG.to_vec())
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), ["/root".as_ref()], cx).await;
+    let (picker, workspace, cx) = build_find_picker(project, cx);
+
+    cx.simulate_input("icon");
+    cx.run_until_parked();
+    workspace.update(cx, |workspace, cx| {
+        let finder = workspace.active_modal::<FileFinder>(cx).unwrap();
+        let preview = finder
+            .read(cx)
+            .image_preview
+            .as_ref()
+            .expect("image match should have a preview");
+        assert_eq!(
+            preview.project_path.path.as_ref(),
+            Path::new("icons/icon.png")
+        );
+        assert!(preview.image.is_some());
+        assert_eq!(preview.dimensions, Some((1, 1)));
+    });
+
+    picker
+        .update(cx, |picker, cx| {
+            picker.delegate.update_matches("notes".to_string(), cx)
+        })
+        .await;
+    cx.run_until_parked();
+    workspace.update(cx, |workspace, cx| {
+        let finder = workspace.active_modal::<FileFinder>(cx).unwrap();
+        assert!(finder.read(cx).image_preview.is_none());
+    });
+}
+
 async fn open_close_queried_buffer(
     input: &str,
     expected_matches: usize,
@@ -106,6 +106,11 @@ pub trait Fs: Send + Sync {
         Ok(String::from_utf8(self.load_bytes(path).await?)?)
     }
     async fn load_bytes(&self, path: &Path) -> Result<Vec<u8>>;
+    /// Loads the contents of a file, mapping them into memory instead of reading them when the
+    /// file is large, so that only the parts that are accessed take up memory.
+    async fn load_mapped(&self, path: &Path) -> Result<MappedFile> {
+        Ok(MappedFile::Loaded(self.load_bytes(path).await?))
+    }
     async fn atomic_write(&self, path: PathBuf, text: String) -> Result<()>;
     async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()>;
     async fn canonicalize(&self, path: &Path) -> Result<PathBuf>;
@@ -177,6 +182,36 @@ pub struct RemoveOptions {
     pub ignore_if_not_exists: bool,
 }
 
+/// Files at least this large are mapped into memory by [`Fs::load_mapped`] rather than read.
+const MAP_FILES_LARGER_THAN: u64 = 16 * 1024 * 1024;
+
+/// The contents of a file, either mapped into memory or read into a buffer.
+pub enum MappedFile {
+    Mapped(memmap2::Mmap),
+    Loaded(Vec<u8>),
+}
+
+impl MappedFile {
+    /// Copies the contents into a buffer, unless they were read into one already.
+    pub fn into_vec(self) -> Vec<u8> {
+        match self {
+            MappedFile::Mapped(mmap) => mmap.to_vec(),
+            MappedFile::Loaded(bytes) => bytes,
+        }
+    }
+}
+
+impl std::ops::Deref for MappedFile {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            MappedFile::Mapped(mmap) => mmap,
+            MappedFile::Loaded(bytes) => bytes,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub struct Metadata {
     pub inode: u64,
@@ -512,6 +547,25 @@ impl Fs for RealFs {
         Ok(bytes)
     }
 
+    async fn load_mapped(&self, path: &Path) -> Result<MappedFile> {
+        let path = path.to_path_buf();
+        smol::unblock(move || {
+            let mut file = std::fs::File::open(&path)?;
+            let len = file.metadata()?.len();
+            if len < MAP_FILES_LARGER_THAN {
+                let mut bytes = Vec::with_capacity(len as usize);
+                io::Read::read_to_end(&mut file, &mut bytes)?;
+                return Ok(MappedFile::Loaded(bytes));
+            }
+            // Safety: the file may be changed by other processes while it is mapped, in which
+            // case its contents are read as they are at the time. Callers treat them as
+            // untrusted bytes to decode or hash, which tolerates that.
+            let mmap = unsafe { memmap2::Mmap::map(&file)? };
+            Ok(MappedFile::Mapped(mmap))
+        })
+        .await
+    }
+
     async fn atomic_write(&self, path: PathBuf, data: String) -> Result<()> {
         smol::unblock(move || {
             let mut tmp_file = if cfg!(any(target_os = "linux", target_os = "freebsd")) {
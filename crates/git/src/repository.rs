@@ -1,5 +1,5 @@
-use crate::GitHostingProviderRegistry;
 use crate::{blame::Blame, status::GitStatus};
+use crate::{GitHostingProviderRegistry, Oid};
 use anyhow::{Context, Result};
 use collections::{HashMap, HashSet};
 use git2::BranchType;
@@ -23,6 +23,16 @@ pub struct Branch {
     pub unix_timestamp: Option<i64>,
 }
 
+/// A commit that changed a file, as listed by [`GitRepository::file_history`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileHistoryEntry {
+    pub sha: Oid,
+    pub summary: SharedString,
+    pub author_name: SharedString,
+    /// Commit timestamp, normalized to Unix Epoch format.
+    pub unix_timestamp: i64,
+}
+
 pub trait GitRepository: Send + Sync {
     fn reload_index(&self);
 
@@ -30,6 +40,10 @@ pub trait GitRepository: Send + Sync {
     /// Note that for symlink entries, this will return the contents of the symlink, not the target.
     fn load_index_text(&self, relative_file_path: &Path) -> Option<String>;
 
+    /// Loads a git repository entry's raw contents, for entries that are not text (e.g. images).
+    /// Returns `None` for symlink entries and for paths that are not in the index.
+    fn load_index_bytes(&self, relative_file_path: &Path) -> Option<Vec<u8>>;
+
     /// Returns the URL of the remote with the given name.
     fn remote_url(&self, name: &str) -> Option<String>;
     fn branch_name(&self) -> Option<String>;
@@ -46,6 +60,18 @@ pub trait GitRepository: Send + Sync {
 
     fn blame(&self, path: &Path, content: Rope) -> Result<crate::blame::Blame>;
 
+    /// Lists the commits reachable from HEAD that changed the given file, newest first.
+    /// Commits that deleted the file are not included.
+    fn file_history(
+        &self,
+        relative_file_path: &Path,
+        limit: usize,
+    ) -> Result<Vec<FileHistoryEntry>>;
+
+    /// Loads a file's raw contents as of the given commit.
+    /// Returns `None` for symlink entries and when the file did not exist in that commit.
+    fn load_committed_bytes(&self, sha: Oid, relative_file_path: &Path) -> Result<Option<Vec<u8>>>;
+
     fn path(&self) -> PathBuf;
 }
 
@@ -78,6 +104,27 @@ impl RealGitRepository {
 // https://git-scm.com/book/en/v2/Git-Internals-Git-Objects
 const GIT_MODE_SYMLINK: u32 = 0o120000;
 
+fn load_index_blob(repo: &git2::Repository, relative_file_path: &Path) -> Result<Option<Vec<u8>>> {
+    const STAGE_NORMAL: i32 = 0;
+    let index = repo.index()?;
+
+    // This check is required because index.get_path() unwraps internally :(
+    check_path_to_repo_path_errors(relative_file_path)?;
+
+    let oid = match index.get_path(relative_file_path, STAGE_NORMAL) {
+        Some(entry) if entry.mode != GIT_MODE_SYMLINK => entry.id,
+        _ => return Ok(None),
+    };
+
+    Ok(Some(repo.find_blob(oid)?.content().to_owned()))
+}
+
+fn tree_entry_id(tree: &git2::Tree, relative_file_path: &Path) -> Option<git2::Oid> {
+    tree.get_path(relative_file_path)
+        .ok()
+        .map(|entry| entry.id())
+}
+
 impl GitRepository for RealGitRepository {
     fn reload_index(&self) {
         if let Ok(mut index) = self.repository.lock().index() {
@@ -92,18 +139,9 @@ impl GitRepository for RealGitRepository {
 
     fn load_index_text(&self, relative_file_path: &Path) -> Option<String> {
         fn logic(repo: &git2::Repository, relative_file_path: &Path) -> Result<Option<String>> {
-            const STAGE_NORMAL: i32 = 0;
-            let index = repo.index()?;
-
-            // This check is required because index.get_path() unwraps internally :(
-            check_path_to_repo_path_errors(relative_file_path)?;
-
-            let oid = match index.get_path(relative_file_path, STAGE_NORMAL) {
-                Some(entry) if entry.mode != GIT_MODE_SYMLINK => entry.id,
-                _ => return Ok(None),
+            let Some(content) = load_index_blob(repo, relative_file_path)? else {
+                return Ok(None);
             };
-
-            let content = repo.find_blob(oid)?.content().to_owned();
             Ok(Some(String::from_utf8(content)?))
         }
 
@@ -114,6 +152,14 @@ impl GitRepository for RealGitRepository {
         None
     }
 
+    fn load_index_bytes(&self, relative_file_path: &Path) -> Option<Vec<u8>> {
+        match load_index_blob(&self.repository.lock(), relative_file_path) {
+            Ok(value) => return value,
+            Err(err) => log::error!("Error loading index bytes: {:?}", err),
+        }
+        None
+    }
+
     fn remote_url(&self, name: &str) -> Option<String> {
         let repo = self.repository.lock();
         let remote = repo.find_remote(name).ok()?;
@@ -224,6 +270,69 @@ impl GitRepository for RealGitRepository {
             self.hosting_provider_registry.clone(),
         )
     }
+
+    fn file_history(
+        &self,
+        relative_file_path: &Path,
+        limit: usize,
+    ) -> Result<Vec<FileHistoryEntry>> {
+        check_path_to_repo_path_errors(relative_file_path)?;
+        let repo = self.repository.lock();
+        let mut revwalk = repo.revwalk()?;
+        revwalk.push_head()?;
+        revwalk.set_sorting(git2::Sort::TIME)?;
+
+        let mut history = Vec::new();
+        for oid in revwalk {
+            let commit = repo.find_commit(oid?)?;
+            let Some(entry_id) = tree_entry_id(&commit.tree()?, relative_file_path) else {
+                continue;
+            };
+            // Merges that took the file unchanged from one of their parents didn't change it.
+            let changed = commit.parents().all(|parent| {
+                parent
+                    .tree()
+                    .ok()
+                    .and_then(|tree| tree_entry_id(&tree, relative_file_path))
+                    != Some(entry_id)
+            });
+            if !changed {
+                continue;
+            }
+
+            history.push(FileHistoryEntry {
+                sha: Oid(commit.id()),
+                summary: commit.summary().unwrap_or_default().to_string().into(),
+                author_name: commit
+                    .author()
+                    .name()
+                    .unwrap_or_default()
+                    .to_string()
+                    .into(),
+                unix_timestamp: commit.time().seconds(),
+            });
+            if history.len() >= limit {
+                break;
+            }
+        }
+        Ok(history)
+    }
+
+    fn load_committed_bytes(&self, sha: Oid, relative_file_path: &Path) -> Result<Option<Vec<u8>>> {
+        check_path_to_repo_path_errors(relative_file_path)?;
+        let repo = self.repository.lock();
+        let tree = repo.find_commit(sha.0)?.tree()?;
+        let entry = match tree.get_path(relative_file_path) {
+            Ok(entry) => entry,
+            Err(error) if error.code() == git2::ErrorCode::NotFound => return Ok(None),
+            Err(error) => return Err(error.into()),
+        };
+        if entry.filemode() as u32 == GIT_MODE_SYMLINK {
+            return Ok(None);
+        }
+        let blob = entry.to_object(&repo)?.peel_to_blob()?;
+        Ok(Some(blob.content().to_owned()))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -237,6 +346,8 @@ pub struct FakeGitRepositoryState {
     pub event_emitter: smol::channel::Sender<PathBuf>,
     pub index_contents: HashMap<PathBuf, String>,
     pub blames: HashMap<PathBuf, Blame>,
+    pub file_histories: HashMap<PathBuf, Vec<FileHistoryEntry>>,
+    pub committed_contents: HashMap<(Oid, PathBuf), Vec<u8>>,
     pub worktree_statuses: HashMap<RepoPath, GitFileStatus>,
     pub current_branch_name: Option<String>,
     pub branches: HashSet<String>,
@@ -255,6 +366,8 @@ impl FakeGitRepositoryState {
             event_emitter,
             index_contents: Default::default(),
             blames: Default::default(),
+            file_histories: Default::default(),
+            committed_contents: Default::default(),
             worktree_statuses: Default::default(),
             current_branch_name: Default::default(),
             branches: Default::default(),
@@ -270,6 +383,14 @@ impl GitRepository for FakeGitRepository {
         state.index_contents.get(path).cloned()
     }
 
+    fn load_index_bytes(&self, path: &Path) -> Option<Vec<u8>> {
+        let state = self.state.lock();
+        state
+            .index_contents
+            .get(path)
+            .map(|text| text.clone().into_bytes())
+    }
+
     fn remote_url(&self, _name: &str) -> Option<String> {
         None
     }
@@ -357,6 +478,21 @@ impl GitRepository for FakeGitRepository {
             .with_context(|| format!("failed to get blame for {:?}", path))
             .cloned()
     }
+
+    fn file_history(&self, path: &Path, limit: usize) -> Result<Vec<FileHistoryEntry>> {
+        let state = self.state.lock();
+        let mut history = state.file_histories.get(path).cloned().unwrap_or_default();
+        history.truncate(limit);
+        Ok(history)
+    }
+
+    fn load_committed_bytes(&self, sha: Oid, path: &Path) -> Result<Option<Vec<u8>>> {
+        let state = self.state.lock();
+        Ok(state
+            .committed_contents
+            .get(&(sha, path.to_path_buf()))
+            .cloned())
+    }
 }
 
 fn check_path_to_repo_path_errors(relative_file_path: &Path) -> Result<()> {
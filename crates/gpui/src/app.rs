@@ -724,6 +724,11 @@ impl AppContext {
         self.platform.should_auto_hide_scrollbars()
     }
 
+    /// Returns whether the user has asked the platform to minimize animations and other motion.
+    pub fn should_reduce_motion(&self) -> bool {
+        self.platform.should_reduce_motion()
+    }
+
     /// Restart the application.
     pub fn restart(&self, binary_path: Option<PathBuf>) {
         self.platform.restart(binary_path)
@@ -1367,6 +1372,16 @@ impl AppContext {
         self.loading_assets.remove(&asset_id);
     }
 
+    /// Returns an asset if it has finished loading, without starting to load it otherwise.
+    pub fn loaded_asset<A: Asset>(&self, source: &A::Source) -> Option<A::Output> {
+        let asset_id = (TypeId::of::<A>(), hash(source));
+        self.loading_assets
+            .get(&asset_id)?
+            .downcast_ref::<Shared<Task<A::Output>>>()?
+            .clone()
+            .now_or_never()
+    }
+
     /// Asynchronously load an asset, if the asset hasn't finished loading this will return None.
     ///
     /// Note that the multiple calls to this method will only result in one `Asset::load` call at a
@@ -1,12 +1,13 @@
 use crate::{
-    px, swap_rgba_pa_to_bgra, AbsoluteLength, AnyElement, AppContext, Asset, AssetLogger, Bounds,
-    DefiniteLength, Element, ElementId, GlobalElementId, Hitbox, Image, InteractiveElement,
-    Interactivity, IntoElement, LayoutId, Length, ObjectFit, Pixels, RenderImage, Resource,
-    SharedString, SharedUri, StyleRefinement, Styled, SvgSize, Task, WindowContext,
+    decode_bgra, decode_with_platform_codecs, px, swap_rgba_pa_to_bgra, AbsoluteLength, AnyElement,
+    AppContext, Asset, AssetLogger, Bounds, DefiniteLength, Element, ElementId, GlobalElementId,
+    Hitbox, Image, InteractiveElement, Interactivity, IntoElement, LayoutId, Length, ObjectFit,
+    Pixels, RenderImage, Resource, SharedString, SharedUri, StyleRefinement, Styled, SvgSize, Task,
+    WindowContext,
 };
 use anyhow::{anyhow, Result};
 
-use futures::{AsyncReadExt, Future};
+use futures::{AsyncReadExt, Future, FutureExt as _};
 use image::{
     codecs::gif::GifDecoder, AnimationDecoder, Frame, ImageBuffer, ImageError, ImageFormat,
 };
@@ -124,6 +125,7 @@ impl<F: Fn(&mut WindowContext) -> Option<Result<Arc<RenderImage>, ImageCacheErro
 /// The style of an image element.
 pub struct ImageStyle {
     grayscale: bool,
+    animate: bool,
     object_fit: ObjectFit,
     loading: Option<Box<dyn Fn() -> AnyElement>>,
     fallback: Option<Box<dyn Fn() -> AnyElement>>,
@@ -133,6 +135,7 @@ impl Default for ImageStyle {
     fn default() -> Self {
         Self {
             grayscale: false,
+            animate: true,
             object_fit: ObjectFit::Contain,
             loading: None,
             fallback: None,
@@ -151,6 +154,12 @@ pub trait StyledImage: Sized {
         self
     }
 
+    /// Set whether animated images play, or stay on their current frame.
+    fn animate(mut self, animate: bool) -> Self {
+        self.image_style().animate = animate;
+        self
+    }
+
     /// Set the object fit for the image.
     fn object_fit(mut self, object_fit: ObjectFit) -> Self {
         self.image_style().object_fit = object_fit;
@@ -201,11 +210,19 @@ pub fn img(source: impl Into<ImageSource>) -> Img {
 impl Img {
     /// A list of all format extensions currently supported by this img element
     pub fn extensions() -> &'static [&'static str] {
-        // This is the list in [image::ImageFormat::from_extension] + `svg`
-        &[
+        // This is the list in [image::ImageFormat::from_extension] + `svg`, and `heic` where the
+        // platform's codecs can decode it.
+        #[cfg(any(target_os = "macos", target_os = "windows"))]
+        return &[
+            "avif", "jpg", "jpeg", "png", "gif", "webp", "tif", "tiff", "tga", "dds", "bmp", "ico",
+            "hdr", "exr", "pbm", "pam", "ppm", "pgm", "ff", "farbfeld", "qoi", "svg", "heic",
+            "heif",
+        ];
+        #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+        return &[
             "avif", "jpg", "jpeg", "png", "gif", "webp", "tif", "tiff", "tga", "dds", "bmp", "ico",
             "hdr", "exr", "pbm", "pam", "ppm", "pgm", "ff", "farbfeld", "qoi", "svg",
-        ]
+        ];
     }
 }
 
@@ -274,7 +291,7 @@ impl Element for Img {
                         Some(Ok(data)) => {
                             if let Some(state) = &mut state {
                                 let frame_count = data.frame_count();
-                                if frame_count > 1 {
+                                if frame_count > 1 && self.style.animate {
                                     let current_time = Instant::now();
                                     if let Some(last_frame_time) = state.last_frame_time {
                                         let elapsed = current_time - last_frame_time;
@@ -290,6 +307,8 @@ impl Element for Img {
                                     } else {
                                         state.last_frame_time = Some(current_time);
                                     }
+                                } else {
+                                    state.last_frame_time = None;
                                 }
                                 state.started_loading = None;
                             }
@@ -322,7 +341,7 @@ impl Element for Img {
                                 };
                             }
 
-                            if global_id.is_some() && data.frame_count() > 1 {
+                            if global_id.is_some() && data.frame_count() > 1 && self.style.animate {
                                 cx.request_animation_frame();
                             }
                         }
@@ -461,6 +480,32 @@ impl ImageSource {
 #[derive(Clone)]
 enum ImageDecoder {}
 
+impl Image {
+    /// Starts decoding this image in the background, so that it can be drawn without waiting
+    /// once it is displayed. The decoded frames are kept until [`Image::release_render_image`]
+    /// is called.
+    pub fn prefetch_render_image(self: &Arc<Self>, cx: &mut AppContext) {
+        cx.fetch_asset::<AssetLogger<ImageDecoder>>(self);
+    }
+
+    /// The frames this image is drawn from, if they have been decoded and not released since.
+    pub fn decoded_render_image(self: &Arc<Self>, cx: &AppContext) -> Option<Arc<RenderImage>> {
+        cx.loaded_asset::<AssetLogger<ImageDecoder>>(self)?.ok()
+    }
+
+    /// Frees the memory used to draw this image, both its decoded frames and their copies in the
+    /// sprite atlas. The image is decoded and uploaded again the next time it is drawn.
+    pub fn release_render_image(self: &Arc<Self>, cx: &mut WindowContext) {
+        let (task, is_first) = cx.fetch_asset::<AssetLogger<ImageDecoder>>(self);
+        if !is_first {
+            if let Some(Ok(render_image)) = task.now_or_never() {
+                cx.drop_image(render_image).log_err();
+            }
+        }
+        cx.remove_asset::<AssetLogger<ImageDecoder>>(self);
+    }
+}
+
 impl Asset for ImageDecoder {
     type Source = Arc<Image>;
     type Output = Result<Arc<RenderImage>, ImageCacheError>;
@@ -542,20 +587,13 @@ impl Asset for ImageAssetLoader {
 
                         frames
                     }
-                    _ => {
-                        let mut data =
-                            image::load_from_memory_with_format(&bytes, format)?.into_rgba8();
-
-                        // Convert from RGBA to BGRA.
-                        for pixel in data.chunks_exact_mut(4) {
-                            pixel.swap(0, 2);
-                        }
-
-                        SmallVec::from_elem(Frame::new(data), 1)
-                    }
+                    _ => SmallVec::from_elem(Frame::new(decode_bgra(&bytes, format, None)?), 1),
                 };
 
                 RenderImage::new(data)
+            } else if crate::ImageFormat::is_heic(&bytes) {
+                let data = decode_with_platform_codecs(&bytes, None)?;
+                RenderImage::new(SmallVec::from_elem(Frame::new(data), 1))
             } else {
                 let pixmap =
                     // TODO: Can we make svgs always rescale?
@@ -258,6 +258,50 @@ impl Deref for ScrollWheelEvent {
     }
 }
 
+/// A pinch gesture from the platform, such as two fingers moving apart on a touchscreen.
+#[derive(Clone, Debug)]
+pub struct PinchEvent {
+    /// The position between the fingers on the window.
+    pub position: Point<Pixels>,
+
+    /// How much the distance between the fingers changed since the previous event of the
+    /// gesture, as a ratio. Greater than 1 when they move apart.
+    pub scale: f32,
+
+    /// The modifiers that were held down during the gesture.
+    pub modifiers: Modifiers,
+
+    /// The phase of the gesture.
+    pub touch_phase: TouchPhase,
+}
+
+impl Default for PinchEvent {
+    fn default() -> Self {
+        Self {
+            position: Point::default(),
+            scale: 1.,
+            modifiers: Modifiers::default(),
+            touch_phase: TouchPhase::default(),
+        }
+    }
+}
+
+impl Sealed for PinchEvent {}
+impl InputEvent for PinchEvent {
+    fn to_platform_input(self) -> PlatformInput {
+        PlatformInput::Pinch(self)
+    }
+}
+impl MouseEvent for PinchEvent {}
+
+impl Deref for PinchEvent {
+    type Target = Modifiers;
+
+    fn deref(&self) -> &Self::Target {
+        &self.modifiers
+    }
+}
+
 /// The scroll delta for a scroll wheel event.
 #[derive(Clone, Copy, Debug)]
 pub enum ScrollDelta {
@@ -429,6 +473,8 @@ pub enum PlatformInput {
     MouseExited(MouseExitEvent),
     /// The scroll wheel was used.
     ScrollWheel(ScrollWheelEvent),
+    /// A pinch gesture was made.
+    Pinch(PinchEvent),
     /// Files were dragged and dropped onto the window.
     FileDrop(FileDropEvent),
 }
@@ -444,6 +490,7 @@ impl PlatformInput {
             PlatformInput::MouseMove(event) => Some(event),
             PlatformInput::MouseExited(event) => Some(event),
             PlatformInput::ScrollWheel(event) => Some(event),
+            PlatformInput::Pinch(event) => Some(event),
             PlatformInput::FileDrop(event) => Some(event),
         }
     }
@@ -458,6 +505,7 @@ impl PlatformInput {
             PlatformInput::MouseMove(_) => None,
             PlatformInput::MouseExited(_) => None,
             PlatformInput::ScrollWheel(_) => None,
+            PlatformInput::Pinch(_) => None,
             PlatformInput::FileDrop(_) => None,
         }
     }
@@ -195,6 +195,7 @@ pub(crate) trait Platform: 'static {
 
     fn set_cursor_style(&self, style: CursorStyle);
     fn should_auto_hide_scrollbars(&self) -> bool;
+    fn should_reduce_motion(&self) -> bool;
 
     #[cfg(any(target_os = "linux", target_os = "freebsd"))]
     fn write_to_primary(&self, item: ClipboardItem);
@@ -563,6 +564,7 @@ pub(crate) trait PlatformAtlas: Send + Sync {
         build: &mut dyn FnMut() -> Result<Option<(Size<DevicePixels>, Cow<'a, [u8]>)>>,
     ) -> Result<Option<AtlasTile>>;
     fn remove(&self, key: &AtlasKey);
+    fn contains(&self, key: &AtlasKey) -> bool;
 }
 
 struct AtlasTextureList<T> {
@@ -1280,6 +1282,36 @@ pub enum ImageFormat {
     Bmp,
     /// .tif or .tiff
     Tiff,
+    /// .heic or .heif, which can only be decoded with the platform's codecs
+    Heic,
+}
+
+impl ImageFormat {
+    /// The usual file extension of images in this format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Jpeg => "jpg",
+            ImageFormat::Webp => "webp",
+            ImageFormat::Gif => "gif",
+            ImageFormat::Svg => "svg",
+            ImageFormat::Bmp => "bmp",
+            ImageFormat::Tiff => "tiff",
+            ImageFormat::Heic => "heic",
+        }
+    }
+
+    /// Whether the bytes are a HEIC or HEIF image, which the `image` crate doesn't recognize.
+    pub fn is_heic(bytes: &[u8]) -> bool {
+        // HEIF files start with an `ftyp` box naming the brand of the file.
+        bytes.get(4..8) == Some(b"ftyp")
+            && bytes.get(8..12).map_or(false, |brand| {
+                matches!(
+                    brand,
+                    b"heic" | b"heix" | b"heim" | b"heis" | b"hevc" | b"hevx" | b"mif1" | b"msf1"
+                )
+            })
+    }
 }
 
 /// An image, with a format and certain bytes
@@ -1318,14 +1350,10 @@ impl Image {
             bytes: &[u8],
             format: image::ImageFormat,
         ) -> Result<SmallVec<[Frame; 1]>> {
-            let mut data = image::load_from_memory_with_format(bytes, format)?.into_rgba8();
-
-            // Convert from RGBA to BGRA.
-            for pixel in data.chunks_exact_mut(4) {
-                pixel.swap(0, 2);
-            }
-
-            Ok(SmallVec::from_elem(Frame::new(data), 1))
+            Ok(SmallVec::from_elem(
+                Frame::new(decode_bgra(bytes, format, None)?),
+                1,
+            ))
         }
 
         let frames = match self.format {
@@ -1349,6 +1377,10 @@ impl Image {
             ImageFormat::Webp => frames_for_image(&self.bytes, image::ImageFormat::WebP)?,
             ImageFormat::Bmp => frames_for_image(&self.bytes, image::ImageFormat::Bmp)?,
             ImageFormat::Tiff => frames_for_image(&self.bytes, image::ImageFormat::Tiff)?,
+            ImageFormat::Heic => SmallVec::from_elem(
+                Frame::new(decode_with_platform_codecs(&self.bytes, None)?),
+                1,
+            ),
             ImageFormat::Svg => {
                 let pixmap = svg_renderer.render_pixmap(&self.bytes, SvgSize::ScaleFactor(1.0))?;
 
@@ -1363,6 +1395,35 @@ impl Image {
         Ok(Arc::new(RenderImage::new(frames)))
     }
 
+    /// Decodes the image scaled down to fit within `max_size` pixels on either side, which is
+    /// quicker to decode and draw than the full image when it is larger than that.
+    ///
+    /// Only the first frame of animated images is decoded. SVG images, which can be rendered at
+    /// any size, aren't supported.
+    pub fn to_downscaled_image_data(&self, max_size: u32) -> Result<Arc<RenderImage>> {
+        let format = match self.format {
+            ImageFormat::Png => image::ImageFormat::Png,
+            ImageFormat::Jpeg => image::ImageFormat::Jpeg,
+            ImageFormat::Webp => image::ImageFormat::WebP,
+            ImageFormat::Gif => image::ImageFormat::Gif,
+            ImageFormat::Bmp => image::ImageFormat::Bmp,
+            ImageFormat::Tiff => image::ImageFormat::Tiff,
+            ImageFormat::Heic => {
+                let data = decode_with_platform_codecs(&self.bytes, Some(max_size))?;
+                return Ok(Arc::new(RenderImage::new(SmallVec::from_elem(
+                    Frame::new(data),
+                    1,
+                ))));
+            }
+            ImageFormat::Svg => return Err(anyhow!("SVG images can't be decoded downscaled")),
+        };
+        let data = decode_bgra(&self.bytes, format, Some(max_size))?;
+        Ok(Arc::new(RenderImage::new(SmallVec::from_elem(
+            Frame::new(data),
+            1,
+        ))))
+    }
+
     /// Get the format of the clipboard image
     pub fn format(&self) -> ImageFormat {
         self.format
@@ -1374,6 +1435,60 @@ impl Image {
     }
 }
 
+/// Decodes a still image into BGRA pixels with straight alpha.
+///
+/// PNG and JPEG images are decoded with the platform's codecs where there are some, since they
+/// can be hardware accelerated, and with the `image` crate otherwise or when those fail. When
+/// `max_size` is given, images larger than it on either side are scaled down to fit.
+pub(crate) fn decode_bgra(
+    bytes: &[u8],
+    format: image::ImageFormat,
+    max_size: Option<u32>,
+) -> Result<image::RgbaImage> {
+    if matches!(format, image::ImageFormat::Png | image::ImageFormat::Jpeg) {
+        match decode_with_platform_codecs(bytes, max_size) {
+            Ok(data) => return Ok(data),
+            Err(error) => log::debug!("falling back to decoding a {format:?} image: {error}"),
+        }
+    }
+
+    let mut image = image::load_from_memory_with_format(bytes, format)?;
+    if let Some(max_size) = max_size {
+        if image.width().max(image.height()) > max_size {
+            image = image.thumbnail(max_size, max_size);
+        }
+    }
+    let mut data = image.into_rgba8();
+
+    // Convert from RGBA to BGRA.
+    for pixel in data.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+
+    Ok(data)
+}
+
+/// Decodes the first frame of an image into BGRA pixels with straight alpha, with ImageIO on
+/// macOS and the Windows Imaging Component on Windows, scaled down to fit within `max_size`
+/// pixels on either side if given.
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+pub(crate) fn decode_with_platform_codecs(
+    bytes: &[u8],
+    max_size: Option<u32>,
+) -> Result<image::RgbaImage> {
+    decode_image(bytes, max_size)
+}
+
+/// Decodes the first frame of an image with the platform's codecs, which this platform doesn't
+/// have.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub(crate) fn decode_with_platform_codecs(
+    _bytes: &[u8],
+    _max_size: Option<u32>,
+) -> Result<image::RgbaImage> {
+    Err(anyhow!("this platform has no image codecs"))
+}
+
 /// A clipboard item that should be copied to the clipboard
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ClipboardString {
@@ -131,6 +131,10 @@ impl PlatformAtlas for BladeAtlas {
         }
     }
 
+    fn contains(&self, key: &AtlasKey) -> bool {
+        self.0.lock().tiles_by_key.contains_key(key)
+    }
+
     fn remove(&self, key: &AtlasKey) {
         let mut lock = self.0.lock();
 
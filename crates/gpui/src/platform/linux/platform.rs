@@ -90,6 +90,7 @@ pub(crate) struct LinuxCommon {
     pub(crate) text_system: Arc<dyn PlatformTextSystem>,
     pub(crate) appearance: WindowAppearance,
     pub(crate) auto_hide_scrollbars: bool,
+    pub(crate) reduce_motion: bool,
     pub(crate) callbacks: PlatformHandlers,
     pub(crate) signal: LoopSignal,
     pub(crate) menus: Vec<OwnedMenu>,
@@ -116,6 +117,7 @@ impl LinuxCommon {
             text_system,
             appearance: WindowAppearance::Light,
             auto_hide_scrollbars: false,
+            reduce_motion: false,
             callbacks,
             signal,
             menus: Vec::new(),
@@ -448,6 +450,10 @@ impl<P: LinuxClient + 'static> Platform for P {
         self.with_common(|common| common.auto_hide_scrollbars)
     }
 
+    fn should_reduce_motion(&self) -> bool {
+        self.with_common(|common| common.reduce_motion)
+    }
+
     fn write_credentials(&self, url: &str, username: &str, password: &[u8]) -> Task<Result<()>> {
         let url = url.to_string();
         let username = username.to_string();
@@ -29,7 +29,7 @@ use wayland_client::{
     delegate_noop,
     protocol::{
         wl_buffer, wl_compositor, wl_keyboard, wl_pointer, wl_registry, wl_seat, wl_shm,
-        wl_shm_pool, wl_surface,
+        wl_shm_pool, wl_surface, wl_touch,
     },
     Connection, Dispatch, Proxy, QueueHandle,
 };
@@ -84,8 +84,8 @@ use crate::{
 use crate::{
     AnyWindowHandle, CursorStyle, DisplayId, KeyDownEvent, KeyUpEvent, Keystroke, Modifiers,
     ModifiersChangedEvent, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent,
-    NavigationDirection, Pixels, PlatformDisplay, PlatformInput, Point, ScaledPixels, ScrollDelta,
-    ScrollWheelEvent, TouchPhase,
+    NavigationDirection, PinchEvent, Pixels, PlatformDisplay, PlatformInput, Point, ScaledPixels,
+    ScrollDelta, ScrollWheelEvent, TouchPhase,
 };
 use crate::{LinuxCommon, WindowParams};
 
@@ -189,6 +189,7 @@ pub(crate) struct WaylandClientState {
     wl_seat: wl_seat::WlSeat, // TODO: Multi seat support
     wl_pointer: Option<wl_pointer::WlPointer>,
     wl_keyboard: Option<wl_keyboard::WlKeyboard>,
+    wl_touch: Option<wl_touch::WlTouch>,
     cursor_shape_device: Option<wp_cursor_shape_device_v1::WpCursorShapeDeviceV1>,
     data_device: Option<wl_data_device::WlDataDevice>,
     primary_selection: Option<zwp_primary_selection_device_v1::ZwpPrimarySelectionDeviceV1>,
@@ -205,6 +206,7 @@ pub(crate) struct WaylandClientState {
     compose_state: Option<xkb::compose::State>,
     drag: DragState,
     click: ClickState,
+    touch: TouchState,
     repeat: KeyRepeat,
     pub modifiers: Modifiers,
     axis_source: AxisSource,
@@ -505,6 +507,12 @@ impl WaylandClient {
                             client.cursor.set_size(size);
                         }
                     }
+                    XDPEvent::EnableAnimations(enable_animations) => {
+                        if let Some(client) = client.0.upgrade() {
+                            let mut client = client.borrow_mut();
+                            client.common.reduce_motion = !enable_animations;
+                        }
+                    }
                 }
             })
             .unwrap();
@@ -515,6 +523,7 @@ impl WaylandClient {
             wl_seat: seat,
             wl_pointer: None,
             wl_keyboard: None,
+            wl_touch: None,
             cursor_shape_device: None,
             data_device,
             primary_selection,
@@ -539,6 +548,7 @@ impl WaylandClient {
                 last_location: Point::default(),
                 current_count: 0,
             },
+            touch: TouchState::default(),
             repeat: KeyRepeat {
                 characters_per_second: 16,
                 delay: Duration::from_millis(500),
@@ -1085,6 +1095,15 @@ impl Dispatch<wl_seat::WlSeat, ()> for WaylandClientStatePtr {
 
                 state.wl_pointer = Some(pointer);
             }
+            if capabilities.contains(wl_seat::Capability::Touch) {
+                let touch = seat.get_touch(qh, ());
+
+                if let Some(wl_touch) = &state.wl_touch {
+                    wl_touch.release();
+                }
+
+                state.wl_touch = Some(touch);
+            }
         }
     }
 }
@@ -1704,6 +1723,212 @@ impl Dispatch<wl_pointer::WlPointer, ()> for WaylandClientStatePtr {
     }
 }
 
+/// The contacts of a touchscreen with a window. A single contact is reported as the left mouse
+/// button, and two as a pinch.
+#[derive(Default)]
+pub struct TouchState {
+    window: Option<WaylandWindowStatePtr>,
+    points: Vec<(i32, Point<Pixels>)>,
+    /// The distance between the two contacts of a pinch as of its last event.
+    pinch_distance: Option<f32>,
+}
+
+impl TouchState {
+    fn pinch(&self) -> Option<(Point<Pixels>, f32)> {
+        let [(_, a), (_, b)] = self.points.as_slice() else {
+            return None;
+        };
+        let center = point((a.x + b.x) / 2., (a.y + b.y) / 2.);
+        let distance = ((a.x - b.x).0.powi(2) + (a.y - b.y).0.powi(2)).sqrt();
+        Some((center, distance))
+    }
+}
+
+impl Dispatch<wl_touch::WlTouch, ()> for WaylandClientStatePtr {
+    fn event(
+        this: &mut Self,
+        _: &wl_touch::WlTouch,
+        event: wl_touch::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        let client = this.get_client();
+        let mut state = client.borrow_mut();
+        let modifiers = state.modifiers;
+        let mut inputs = Vec::new();
+
+        match event {
+            wl_touch::Event::Down {
+                serial,
+                surface,
+                id,
+                x,
+                y,
+                ..
+            } => {
+                state.serial_tracker.update(SerialKind::MousePress, serial);
+                let position = point(px(x as f32), px(y as f32));
+                if state.touch.points.is_empty() {
+                    state.touch.window = get_window(&mut state, &surface.id());
+                }
+                state.touch.points.push((id, position));
+                match state.touch.points.len() {
+                    1 => {
+                        let click_elapsed = state.click.last_click.elapsed();
+                        if click_elapsed < DOUBLE_CLICK_INTERVAL
+                            && state.click.last_mouse_button == Some(MouseButton::Left)
+                            && is_within_click_distance(state.click.last_location, position)
+                        {
+                            state.click.current_count += 1;
+                        } else {
+                            state.click.current_count = 1;
+                        }
+                        state.click.last_click = Instant::now();
+                        state.click.last_mouse_button = Some(MouseButton::Left);
+                        state.click.last_location = position;
+
+                        inputs.push(PlatformInput::MouseMove(MouseMoveEvent {
+                            position,
+                            pressed_button: None,
+                            modifiers,
+                        }));
+                        inputs.push(PlatformInput::MouseDown(MouseDownEvent {
+                            button: MouseButton::Left,
+                            position,
+                            modifiers,
+                            click_count: state.click.current_count,
+                            first_mouse: false,
+                        }));
+                    }
+                    2 => {
+                        // The first contact stops acting as a pressed button once a pinch starts.
+                        let (_, first_position) = state.touch.points[0];
+                        inputs.push(PlatformInput::MouseUp(MouseUpEvent {
+                            button: MouseButton::Left,
+                            position: first_position,
+                            modifiers,
+                            click_count: state.click.current_count,
+                        }));
+                        if let Some((center, distance)) = state.touch.pinch() {
+                            state.touch.pinch_distance = Some(distance);
+                            inputs.push(PlatformInput::Pinch(PinchEvent {
+                                position: center,
+                                scale: 1.,
+                                modifiers,
+                                touch_phase: TouchPhase::Started,
+                            }));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            wl_touch::Event::Motion { id, x, y, .. } => {
+                let position = point(px(x as f32), px(y as f32));
+                let Some(touch) = state
+                    .touch
+                    .points
+                    .iter_mut()
+                    .find(|(point_id, _)| *point_id == id)
+                else {
+                    return;
+                };
+                touch.1 = position;
+                match state.touch.points.len() {
+                    1 => inputs.push(PlatformInput::MouseMove(MouseMoveEvent {
+                        position,
+                        pressed_button: Some(MouseButton::Left),
+                        modifiers,
+                    })),
+                    2 => {
+                        if let (Some((center, distance)), Some(previous_distance)) =
+                            (state.touch.pinch(), state.touch.pinch_distance)
+                        {
+                            state.touch.pinch_distance = Some(distance);
+                            if previous_distance > 0. {
+                                inputs.push(PlatformInput::Pinch(PinchEvent {
+                                    position: center,
+                                    scale: distance / previous_distance,
+                                    modifiers,
+                                    touch_phase: TouchPhase::Moved,
+                                }));
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            wl_touch::Event::Up { id, .. } => {
+                let Some(ix) = state
+                    .touch
+                    .points
+                    .iter()
+                    .position(|(point_id, _)| *point_id == id)
+                else {
+                    return;
+                };
+                let pinch = state.touch.pinch();
+                let (_, position) = state.touch.points.remove(ix);
+                match state.touch.points.len() {
+                    0 if state.touch.pinch_distance.is_none() => {
+                        inputs.push(PlatformInput::MouseUp(MouseUpEvent {
+                            button: MouseButton::Left,
+                            position,
+                            modifiers,
+                            click_count: state.click.current_count,
+                        }));
+                    }
+                    1 => {
+                        if let Some((center, _)) = pinch {
+                            inputs.push(PlatformInput::Pinch(PinchEvent {
+                                position: center,
+                                scale: 1.,
+                                modifiers,
+                                touch_phase: TouchPhase::Ended,
+                            }));
+                        }
+                    }
+                    _ => {}
+                }
+                if state.touch.points.is_empty() {
+                    state.touch.pinch_distance = None;
+                }
+            }
+            wl_touch::Event::Cancel => {
+                if let Some((center, _)) = state.touch.pinch() {
+                    inputs.push(PlatformInput::Pinch(PinchEvent {
+                        position: center,
+                        scale: 1.,
+                        modifiers,
+                        touch_phase: TouchPhase::Ended,
+                    }));
+                } else if let [(_, position)] = state.touch.points.as_slice() {
+                    inputs.push(PlatformInput::MouseUp(MouseUpEvent {
+                        button: MouseButton::Left,
+                        position: *position,
+                        modifiers,
+                        click_count: state.click.current_count,
+                    }));
+                }
+                state.touch.points.clear();
+                state.touch.pinch_distance = None;
+            }
+            _ => {}
+        }
+
+        let window = state.touch.window.clone();
+        if state.touch.points.is_empty() {
+            state.touch.window = None;
+        }
+        drop(state);
+        if let Some(window) = window {
+            for input in inputs {
+                window.handle_input(input);
+            }
+        }
+    }
+}
+
 impl Dispatch<wp_fractional_scale_v1::WpFractionalScaleV1, ObjectId> for WaylandClientStatePtr {
     fn event(
         this: &mut Self,
@@ -127,6 +127,7 @@ impl<T: ReceiveData> DataOffer<T> {
                 ImageFormat::Svg => "image/svg+xml",
                 ImageFormat::Bmp => "image/bmp",
                 ImageFormat::Tiff => "image/tiff",
+                ImageFormat::Heic => "image/heic",
             };
             if !self.has_mime_type(mime_type) {
                 continue;
@@ -414,6 +414,9 @@ impl X11Client {
                     XDPEvent::CursorTheme(_) | XDPEvent::CursorSize(_) => {
                         // noop, X11 manages this for us.
                     }
+                    XDPEvent::EnableAnimations(enable_animations) => {
+                        client.with_common(|common| common.reduce_motion = !enable_animations);
+                    }
                 }
             })
             .unwrap();
@@ -15,6 +15,7 @@ pub enum Event {
     CursorTheme(String),
     #[cfg_attr(feature = "x11", allow(dead_code))]
     CursorSize(u32),
+    EnableAnimations(bool),
 }
 
 pub struct XDPEventSource {
@@ -48,6 +49,12 @@ impl XDPEventSource {
                 {
                     sender.send(Event::CursorSize(initial_size))?;
                 }
+                if let Ok(initial_enable_animations) = settings
+                    .read::<bool>("org.gnome.desktop.interface", "enable-animations")
+                    .await
+                {
+                    sender.send(Event::EnableAnimations(initial_enable_animations))?;
+                }
 
                 if let Ok(mut cursor_theme_changed) = settings
                     .receive_setting_changed_with_args(
@@ -87,6 +94,27 @@ impl XDPEventSource {
                         .detach();
                 }
 
+                if let Ok(mut enable_animations_changed) = settings
+                    .receive_setting_changed_with_args::<bool>(
+                        "org.gnome.desktop.interface",
+                        "enable-animations",
+                    )
+                    .await
+                {
+                    let sender = sender.clone();
+                    background
+                        .spawn(async move {
+                            while let Some(enable_animations) =
+                                enable_animations_changed.next().await
+                            {
+                                let enable_animations = enable_animations?;
+                                sender.send(Event::EnableAnimations(enable_animations))?;
+                            }
+                            anyhow::Ok(())
+                        })
+                        .detach();
+                }
+
                 let mut appearance_changed = settings.receive_color_scheme_changed().await?;
                 while let Some(scheme) = appearance_changed.next().await {
                     sender.send(Event::WindowAppearance(WindowAppearance::from_native(
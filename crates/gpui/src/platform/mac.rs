@@ -4,6 +4,7 @@ mod dispatcher;
 mod display;
 mod display_link;
 mod events;
+mod image_decoder;
 
 #[cfg(not(feature = "macos-blade"))]
 mod metal_atlas;
@@ -43,6 +44,7 @@ use std::{
 pub(crate) use dispatcher::*;
 pub(crate) use display::*;
 pub(crate) use display_link::*;
+pub(crate) use image_decoder::*;
 pub(crate) use platform::*;
 pub(crate) use window::*;
 
@@ -0,0 +1,106 @@
+use anyhow::{anyhow, Result};
+use core_foundation::{
+    base::{CFRelease, CFTypeRef, TCFType},
+    boolean::CFBoolean,
+    data::{CFData, CFDataRef},
+    dictionary::{CFDictionary, CFDictionaryRef},
+    number::CFNumber,
+    string::{CFString, CFStringRef},
+};
+use core_graphics::{
+    base::kCGImageAlphaPremultipliedLast,
+    color_space::CGColorSpace,
+    context::CGContext,
+    geometry::{CGPoint, CGRect, CGSize},
+    image::CGImage,
+    sys,
+};
+use foreign_types::ForeignType;
+use image::RgbaImage;
+
+use crate::swap_rgba_pa_to_bgra;
+
+type CGImageSourceRef = CFTypeRef;
+
+#[link(name = "ImageIO", kind = "framework")]
+extern "C" {
+    static kCGImageSourceCreateThumbnailFromImageAlways: CFStringRef;
+    static kCGImageSourceThumbnailMaxPixelSize: CFStringRef;
+
+    fn CGImageSourceCreateWithData(data: CFDataRef, options: CFDictionaryRef) -> CGImageSourceRef;
+    fn CGImageSourceCreateImageAtIndex(
+        source: CGImageSourceRef,
+        index: usize,
+        options: CFDictionaryRef,
+    ) -> *mut sys::CGImage;
+    fn CGImageSourceCreateThumbnailAtIndex(
+        source: CGImageSourceRef,
+        index: usize,
+        options: CFDictionaryRef,
+    ) -> *mut sys::CGImage;
+}
+
+/// Decodes the first frame of an image with ImageIO, into BGRA pixels with straight alpha.
+///
+/// ImageIO uses the hardware decoders of Apple silicon for JPEG and HEIC images. When `max_size`
+/// is given, images larger than it on either side are scaled down to fit while they are
+/// decoded, which skips most of the work of decoding JPEG images in full.
+pub(crate) fn decode_image(bytes: &[u8], max_size: Option<u32>) -> Result<RgbaImage> {
+    let data = CFData::from_buffer(bytes);
+    let image = unsafe {
+        let source = CGImageSourceCreateWithData(data.as_concrete_TypeRef(), std::ptr::null());
+        if source.is_null() {
+            return Err(anyhow!("ImageIO doesn't recognize the image"));
+        }
+        let image = match max_size {
+            Some(max_size) => {
+                let options = CFDictionary::from_CFType_pairs(&[
+                    (
+                        CFString::wrap_under_get_rule(kCGImageSourceCreateThumbnailFromImageAlways)
+                            .as_CFType(),
+                        CFBoolean::true_value().as_CFType(),
+                    ),
+                    (
+                        CFString::wrap_under_get_rule(kCGImageSourceThumbnailMaxPixelSize)
+                            .as_CFType(),
+                        CFNumber::from(max_size as i64).as_CFType(),
+                    ),
+                ]);
+                CGImageSourceCreateThumbnailAtIndex(source, 0, options.as_concrete_TypeRef())
+            }
+            None => CGImageSourceCreateImageAtIndex(source, 0, std::ptr::null()),
+        };
+        CFRelease(source);
+        if image.is_null() {
+            return Err(anyhow!("ImageIO failed to decode the image"));
+        }
+        CGImage::from_ptr(image)
+    };
+
+    let (width, height) = (image.width(), image.height());
+    let mut buffer = vec![0; width * height * 4];
+    // Bitmap contexts only support premultiplied alpha, which is undone once the image is drawn.
+    let context = CGContext::create_bitmap_context(
+        Some(buffer.as_mut_ptr() as *mut _),
+        width,
+        height,
+        8,
+        width * 4,
+        &CGColorSpace::create_device_rgb(),
+        kCGImageAlphaPremultipliedLast,
+    );
+    context.draw_image(
+        CGRect::new(
+            &CGPoint::new(0., 0.),
+            &CGSize::new(width as f64, height as f64),
+        ),
+        &image,
+    );
+    drop(context);
+
+    for pixel in buffer.chunks_exact_mut(4) {
+        swap_rgba_pa_to_bgra(pixel);
+    }
+    RgbaImage::from_raw(width as u32, height as u32, buffer)
+        .ok_or_else(|| anyhow!("ImageIO decoded an image of an unexpected size"))
+}
@@ -79,6 +79,10 @@ impl PlatformAtlas for MetalAtlas {
         }
     }
 
+    fn contains(&self, key: &AtlasKey) -> bool {
+        self.0.lock().tiles_by_key.contains_key(key)
+    }
+
     fn remove(&self, key: &AtlasKey) {
         let mut lock = self.0.lock();
         let Some(id) = lock.tiles_by_key.get(key).map(|v| v.texture_id) else {
@@ -947,6 +947,14 @@ impl Platform for MacPlatform {
         }
     }
 
+    fn should_reduce_motion(&self) -> bool {
+        unsafe {
+            let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+            let reduce_motion: BOOL = msg_send![workspace, accessibilityDisplayShouldReduceMotion];
+            reduce_motion == YES
+        }
+    }
+
     fn write_to_clipboard(&self, item: ClipboardItem) {
         use crate::ClipboardEntry;
 
@@ -1512,6 +1520,7 @@ impl From<ImageFormat> for UTType {
             ImageFormat::Gif => Self::gif(),
             ImageFormat::Bmp => Self::bmp(),
             ImageFormat::Svg => Self::svg(),
+            ImageFormat::Heic => Self::heic(),
         }
     }
 }
@@ -1555,6 +1564,11 @@ impl UTType {
         Self(unsafe { NSPasteboardTypeTIFF }) // This is a rare case where there's a built-in NSPasteboardType
     }
 
+    pub fn heic() -> Self {
+        // https://developer.apple.com/documentation/uniformtypeidentifiers/uttype-swift.struct/heic
+        Self(unsafe { ns_string("public.heic") })
+    }
+
     fn inner(&self) -> *const Object {
         self.0
     }
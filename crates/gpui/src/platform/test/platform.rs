@@ -291,6 +291,10 @@ impl Platform for TestPlatform {
         false
     }
 
+    fn should_reduce_motion(&self) -> bool {
+        false
+    }
+
     #[cfg(any(target_os = "linux", target_os = "freebsd"))]
     fn write_to_primary(&self, item: ClipboardItem) {
         *self.current_primary_item.lock() = Some(item);
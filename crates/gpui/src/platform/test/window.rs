@@ -340,6 +340,10 @@ impl PlatformAtlas for TestAtlas {
         Ok(Some(state.tiles[key].clone()))
     }
 
+    fn contains(&self, key: &AtlasKey) -> bool {
+        self.0.lock().tiles.contains_key(key)
+    }
+
     fn remove(&self, key: &AtlasKey) {
         let mut state = self.0.lock();
         state.tiles.remove(key);
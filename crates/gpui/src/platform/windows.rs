@@ -3,6 +3,7 @@ mod direct_write;
 mod dispatcher;
 mod display;
 mod events;
+mod image_decoder;
 mod platform;
 mod system_settings;
 mod util;
@@ -14,6 +15,7 @@ pub(crate) use direct_write::*;
 pub(crate) use dispatcher::*;
 pub(crate) use display::*;
 pub(crate) use events::*;
+pub(crate) use image_decoder::*;
 pub(crate) use platform::*;
 pub(crate) use system_settings::*;
 pub(crate) use util::*;
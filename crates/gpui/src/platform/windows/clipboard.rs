@@ -20,7 +20,8 @@ use windows::Win32::{
 use windows_core::PCWSTR;
 
 use crate::{
-    hash, ClipboardEntry, ClipboardItem, ClipboardString, Image, ImageFormat, SmartGlobal,
+    decode_with_platform_codecs, hash, ClipboardEntry, ClipboardItem, ClipboardString, Image,
+    ImageFormat, SmartGlobal,
 };
 
 // https://learn.microsoft.com/en-us/windows/win32/api/shellapi/nf-shellapi-dragqueryfilew
@@ -208,7 +209,18 @@ fn write_image_to_clipboard(item: &Image) -> Result<()> {
 }
 
 fn convert_image_to_png_format(bytes: &[u8], image_format: ImageFormat) -> Result<Vec<u8>> {
-    let image = image::load_from_memory_with_format(bytes, image_format.into())?;
+    let image = match image_format {
+        // The `image` crate can't decode HEIC images, so they are decoded with WIC instead.
+        ImageFormat::Heic => {
+            let mut data = decode_with_platform_codecs(bytes, None)?;
+            // Convert from BGRA to RGBA.
+            for pixel in data.chunks_exact_mut(4) {
+                pixel.swap(0, 2);
+            }
+            image::DynamicImage::ImageRgba8(data)
+        }
+        _ => image::load_from_memory_with_format(bytes, image_format.into())?,
+    };
     let mut output_buf = Vec::new();
     image.write_to(
         &mut std::io::Cursor::new(&mut output_buf),
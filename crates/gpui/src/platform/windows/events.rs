@@ -9,7 +9,7 @@ use windows::Win32::{
     UI::{
         Controls::*,
         HiDpi::*,
-        Input::{Ime::*, KeyboardAndMouse::*},
+        Input::{Ime::*, KeyboardAndMouse::*, Touch::*},
         WindowsAndMessaging::*,
     },
 };
@@ -77,6 +77,7 @@ pub(crate) fn handle_msg(
         WM_XBUTTONUP => handle_xbutton_msg(handle, wparam, lparam, handle_mouse_up_msg, state_ptr),
         WM_MOUSEWHEEL => handle_mouse_wheel_msg(handle, wparam, lparam, state_ptr),
         WM_MOUSEHWHEEL => handle_mouse_horizontal_wheel_msg(handle, wparam, lparam, state_ptr),
+        WM_GESTURE => handle_gesture_msg(handle, lparam, state_ptr),
         WM_SYSKEYDOWN => handle_syskeydown_msg(wparam, lparam, state_ptr),
         WM_SYSKEYUP => handle_syskeyup_msg(wparam, state_ptr),
         WM_SYSCOMMAND => handle_system_command(wparam, state_ptr),
@@ -618,6 +619,83 @@ fn handle_mouse_horizontal_wheel_msg(
     }
 }
 
+/// Touchscreen gestures: pinching is reported as [`PinchEvent`]s and panning as scrolling by
+/// pixels. Taps still arrive as mouse clicks.
+fn handle_gesture_msg(
+    handle: HWND,
+    lparam: LPARAM,
+    state_ptr: Rc<WindowsWindowStatePtr>,
+) -> Option<isize> {
+    let gesture_handle = HGESTUREINFO(lparam.0 as _);
+    let mut info = GESTUREINFO {
+        cbSize: std::mem::size_of::<GESTUREINFO>() as u32,
+        ..Default::default()
+    };
+    unsafe { GetGestureInfo(gesture_handle, &mut info) }.log_err()?;
+    if info.dwID != GID_ZOOM.0 && info.dwID != GID_PAN.0 {
+        return None;
+    }
+
+    let mut lock = state_ptr.state.borrow_mut();
+    let scale_factor = lock.scale_factor;
+    let mut location = POINT {
+        x: info.ptsLocation.x.into(),
+        y: info.ptsLocation.y.into(),
+    };
+    unsafe { ScreenToClient(handle, &mut location).ok().log_err() };
+    let position = logical_point(location.x as f32, location.y as f32, scale_factor);
+    let touch_phase = if info.dwFlags & GF_BEGIN != 0 {
+        lock.gesture_state = GestureState::default();
+        TouchPhase::Started
+    } else if info.dwFlags & GF_END != 0 {
+        TouchPhase::Ended
+    } else {
+        TouchPhase::Moved
+    };
+    let modifiers = current_modifiers();
+    let input = if info.dwID == GID_ZOOM.0 {
+        // The argument is the distance between the two contacts.
+        let distance = info.ullArguments as u32 as f32;
+        let scale = match lock.gesture_state.last_distance {
+            Some(last_distance) if last_distance > 0. && distance > 0. => distance / last_distance,
+            _ => 1.,
+        };
+        lock.gesture_state.last_distance = Some(distance);
+        PlatformInput::Pinch(PinchEvent {
+            position,
+            scale,
+            modifiers,
+            touch_phase,
+        })
+    } else {
+        let delta = lock
+            .gesture_state
+            .last_position
+            .map_or(Point::default(), |last_position| position - last_position);
+        lock.gesture_state.last_position = Some(position);
+        PlatformInput::ScrollWheel(ScrollWheelEvent {
+            position,
+            delta: ScrollDelta::Pixels(delta),
+            modifiers,
+            touch_phase,
+        })
+    };
+    if matches!(touch_phase, TouchPhase::Ended) {
+        lock.gesture_state = GestureState::default();
+    }
+
+    let result = if let Some(mut callback) = lock.callbacks.input.take() {
+        drop(lock);
+        callback(input);
+        state_ptr.state.borrow_mut().callbacks.input = Some(callback);
+        Some(0)
+    } else {
+        Some(1)
+    };
+    unsafe { CloseGestureInfoHandle(gesture_handle).log_err() };
+    result
+}
+
 fn retrieve_caret_position(state_ptr: &Rc<WindowsWindowStatePtr>) -> Option<POINT> {
     with_input_handler_and_scale_factor(state_ptr, |input_handler, scale_factor| {
         let caret_range = input_handler.selected_text_range(false)?;
@@ -796,6 +874,7 @@ fn handle_activate_msg(
 }
 
 fn handle_create_msg(handle: HWND, state_ptr: Rc<WindowsWindowStatePtr>) -> Option<isize> {
+    configure_gestures(handle);
     if state_ptr.hide_title_bar {
         notify_frame_changed(handle);
         Some(0)
@@ -804,6 +883,33 @@ fn handle_create_msg(handle: HWND, state_ptr: Rc<WindowsWindowStatePtr>) -> Opti
     }
 }
 
+/// Lets a single finger pan in any direction, as the default is only vertical panning.
+fn configure_gestures(handle: HWND) {
+    let configs = [
+        GESTURECONFIG {
+            dwID: GID_ZOOM,
+            dwWant: GC_ZOOM,
+            dwBlock: 0,
+        },
+        GESTURECONFIG {
+            dwID: GID_PAN,
+            dwWant: GC_PAN
+                | GC_PAN_WITH_SINGLE_FINGER_VERTICALLY
+                | GC_PAN_WITH_SINGLE_FINGER_HORIZONTALLY,
+            dwBlock: GC_PAN_WITH_GUTTER,
+        },
+    ];
+    unsafe {
+        SetGestureConfig(
+            handle,
+            0,
+            &configs,
+            std::mem::size_of::<GESTURECONFIG>() as u32,
+        )
+        .log_err();
+    }
+}
+
 fn handle_dpi_changed_msg(
     handle: HWND,
     wparam: WPARAM,
@@ -0,0 +1,82 @@
+use anyhow::{anyhow, Result};
+use image::RgbaImage;
+use windows::{
+    core::Interface,
+    Win32::{
+        Graphics::Imaging::{
+            CLSID_WICImagingFactory, GUID_WICPixelFormat32bppBGRA, IWICBitmapSource,
+            IWICImagingFactory, WICBitmapDitherTypeNone, WICBitmapInterpolationModeFant,
+            WICBitmapPaletteTypeCustom, WICDecodeMetadataCacheOnDemand,
+        },
+        System::Com::{
+            CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_MULTITHREADED,
+        },
+    },
+};
+
+thread_local! {
+    /// Images are decoded on background threads, each of which needs COM initialized and its
+    /// own factory.
+    static IMAGING_FACTORY: Option<IWICImagingFactory> = unsafe {
+        // Fails harmlessly when COM was already initialized on the thread with another model.
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+        CoCreateInstance(&CLSID_WICImagingFactory, None, CLSCTX_INPROC_SERVER).ok()
+    };
+}
+
+/// Decodes the first frame of an image with the Windows Imaging Component, into BGRA pixels
+/// with straight alpha.
+///
+/// WIC uses the codecs installed on the system, which can be hardware accelerated and include
+/// formats such as HEIC when the corresponding extension is installed. When `max_size` is given,
+/// images larger than it on either side are scaled down to fit, which codecs such as the JPEG
+/// one do while decoding.
+pub(crate) fn decode_image(bytes: &[u8], max_size: Option<u32>) -> Result<RgbaImage> {
+    IMAGING_FACTORY.with(|factory| {
+        let factory = factory
+            .as_ref()
+            .ok_or_else(|| anyhow!("failed to create a WIC imaging factory"))?;
+        unsafe {
+            let stream = factory.CreateStream()?;
+            stream.InitializeFromMemory(bytes)?;
+            let decoder = factory.CreateDecoderFromStream(
+                &stream,
+                std::ptr::null(),
+                WICDecodeMetadataCacheOnDemand,
+            )?;
+            let frame = decoder.GetFrame(0)?;
+            let mut source: IWICBitmapSource = frame.cast()?;
+            if let Some(max_size) = max_size {
+                let (mut width, mut height) = (0, 0);
+                frame.GetSize(&mut width, &mut height)?;
+                if width.max(height) > max_size {
+                    let scale = max_size as f64 / width.max(height) as f64;
+                    let scaler = factory.CreateBitmapScaler()?;
+                    scaler.Initialize(
+                        &source,
+                        ((width as f64 * scale).round() as u32).max(1),
+                        ((height as f64 * scale).round() as u32).max(1),
+                        WICBitmapInterpolationModeFant,
+                    )?;
+                    source = scaler.cast()?;
+                }
+            }
+            let converter = factory.CreateFormatConverter()?;
+            converter.Initialize(
+                &source,
+                &GUID_WICPixelFormat32bppBGRA,
+                WICBitmapDitherTypeNone,
+                None,
+                0.,
+                WICBitmapPaletteTypeCustom,
+            )?;
+
+            let (mut width, mut height) = (0, 0);
+            converter.GetSize(&mut width, &mut height)?;
+            let mut buffer = vec![0; width as usize * height as usize * 4];
+            converter.CopyPixels(std::ptr::null(), width * 4, &mut buffer)?;
+            RgbaImage::from_raw(width, height, buffer)
+                .ok_or_else(|| anyhow!("WIC decoded an image of an unexpected size"))
+        }
+    })
+}
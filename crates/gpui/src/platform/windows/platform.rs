@@ -471,6 +471,10 @@ impl Platform for WindowsPlatform {
         should_auto_hide_scrollbars().log_err().unwrap_or(false)
     }
 
+    fn should_reduce_motion(&self) -> bool {
+        should_reduce_motion().log_err().unwrap_or(false)
+    }
+
     fn write_to_clipboard(&self, item: ClipboardItem) {
         write_to_clipboard(item);
     }
@@ -704,6 +708,20 @@ fn should_auto_hide_scrollbars() -> Result<bool> {
     Ok(ui_settings.AutoHideScrollBars()?)
 }
 
+#[inline]
+fn should_reduce_motion() -> Result<bool> {
+    let mut animations_enabled = BOOL(1);
+    unsafe {
+        SystemParametersInfoW(
+            SPI_GETCLIENTAREAANIMATION,
+            0,
+            Some((&mut animations_enabled) as *mut BOOL as *mut std::ffi::c_void),
+            SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS::default(),
+        )?;
+    }
+    Ok(!animations_enabled.as_bool())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{ClipboardItem, Platform, WindowsPlatform};
@@ -47,6 +47,7 @@ pub struct WindowsWindowState {
     pub renderer: BladeRenderer,
 
     pub click_state: ClickState,
+    pub gesture_state: GestureState,
     pub system_settings: WindowsSystemSettings,
     pub current_cursor: HCURSOR,
     pub nc_button_pressed: Option<u32>,
@@ -98,6 +99,7 @@ impl WindowsWindowState {
         let system_key_handled = false;
         let hovered = false;
         let click_state = ClickState::new();
+        let gesture_state = GestureState::default();
         let system_settings = WindowsSystemSettings::new(display);
         let nc_button_pressed = None;
         let fullscreen = None;
@@ -115,6 +117,7 @@ impl WindowsWindowState {
             hovered,
             renderer,
             click_state,
+            gesture_state,
             system_settings,
             current_cursor,
             nc_button_pressed,
@@ -965,6 +968,14 @@ impl ClickState {
     }
 }
 
+/// The progress of the touch gesture in the window, as `WM_GESTURE` only reports absolute
+/// distances and positions.
+#[derive(Debug, Default)]
+pub(crate) struct GestureState {
+    pub(crate) last_distance: Option<f32>,
+    pub(crate) last_position: Option<Point<Pixels>>,
+}
+
 struct StyleAndBounds {
     style: WINDOW_STYLE,
     x: i32,
@@ -1,9 +1,10 @@
 use crate::{
-    black, fill, point, px, size, Bounds, Half, Hsla, LineLayout, Pixels, Point, Result,
-    SharedString, StrikethroughStyle, UnderlineStyle, WindowContext, WrapBoundary,
-    WrappedLineLayout,
+    black, fill, point, px, size, Bounds, Half, Hsla, IsZero, LineLayout, Pixels, Point,
+    RenderGlyphParams, Result, Rgba, SharedString, StrikethroughStyle, TextSystem, UnderlineStyle,
+    WindowContext, WrapBoundary, WrappedLineLayout, SUBPIXEL_VARIANTS,
 };
 use derive_more::{Deref, DerefMut};
+use image::RgbaImage;
 use smallvec::SmallVec;
 use std::sync::Arc;
 
@@ -77,6 +78,114 @@ impl ShapedLine {
 
         Ok(())
     }
+
+    /// Draw the line onto an RGBA image on the CPU instead of into a window, e.g. to export text
+    /// as a picture. Only the colors of the runs are applied, backgrounds and decorations are not
+    /// drawn.
+    pub fn rasterize(
+        &self,
+        origin: Point<Pixels>,
+        line_height: Pixels,
+        scale_factor: f32,
+        text_system: &TextSystem,
+        canvas: &mut RgbaImage,
+    ) -> Result<()> {
+        let padding_top = (line_height - self.layout.ascent - self.layout.descent) / 2.;
+        let baseline_y = origin.y + padding_top + self.layout.ascent;
+        let mut decoration_runs = self.decoration_runs.iter();
+        let mut run_end = 0;
+        let mut color = black();
+        for run in &self.layout.runs {
+            for glyph in &run.glyphs {
+                while glyph.index >= run_end {
+                    let Some(decoration_run) = decoration_runs.next() else {
+                        break;
+                    };
+                    run_end += decoration_run.len as usize;
+                    color = decoration_run.color;
+                }
+
+                let glyph_origin =
+                    point(origin.x + glyph.position.x, baseline_y).scale(scale_factor);
+                let params = RenderGlyphParams {
+                    font_id: run.font_id,
+                    glyph_id: glyph.id,
+                    font_size: self.layout.font_size,
+                    subpixel_variant: Point {
+                        x: (glyph_origin.x.0.fract() * SUBPIXEL_VARIANTS as f32).floor() as u8,
+                        y: (glyph_origin.y.0.fract() * SUBPIXEL_VARIANTS as f32).floor() as u8,
+                    },
+                    scale_factor,
+                    is_emoji: glyph.is_emoji,
+                };
+                let raster_bounds = text_system.raster_bounds(&params)?;
+                if raster_bounds.is_zero() {
+                    continue;
+                }
+                let (glyph_size, bytes) = text_system.rasterize_glyph(&params)?;
+                let left = glyph_origin.x.0.floor() as i32 + raster_bounds.origin.x.0;
+                let top = glyph_origin.y.0.floor() as i32 + raster_bounds.origin.y.0;
+                blend_glyph(
+                    canvas,
+                    point(left, top),
+                    (glyph_size.width.0, glyph_size.height.0),
+                    &bytes,
+                    glyph.is_emoji,
+                    color.into(),
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Blends a rasterized glyph over the canvas. Emoji are BGRA bitmaps, other glyphs are alpha
+/// masks drawn in `color`.
+fn blend_glyph(
+    canvas: &mut RgbaImage,
+    origin: Point<i32>,
+    (width, height): (i32, i32),
+    bytes: &[u8],
+    is_emoji: bool,
+    color: Rgba,
+) {
+    let to_byte = |channel: f32| (channel.clamp(0., 1.) * 255.).round();
+    for y in 0..height {
+        for x in 0..width {
+            let (canvas_x, canvas_y) = (origin.x + x, origin.y + y);
+            if canvas_x < 0
+                || canvas_y < 0
+                || canvas_x as u32 >= canvas.width()
+                || canvas_y as u32 >= canvas.height()
+            {
+                continue;
+            }
+            let ix = (y * width + x) as usize;
+            let (source, alpha) = if is_emoji {
+                let Some(pixel) = bytes.get(ix * 4..ix * 4 + 4) else {
+                    continue;
+                };
+                (
+                    [pixel[2] as f32, pixel[1] as f32, pixel[0] as f32],
+                    pixel[3] as f32 / 255.,
+                )
+            } else {
+                let Some(coverage) = bytes.get(ix) else {
+                    continue;
+                };
+                (
+                    [to_byte(color.r), to_byte(color.g), to_byte(color.b)],
+                    *coverage as f32 / 255. * color.a,
+                )
+            };
+            let target = canvas.get_pixel_mut(canvas_x as u32, canvas_y as u32);
+            for (channel, source) in source.into_iter().enumerate() {
+                target[channel] =
+                    (source * alpha + target[channel] as f32 * (1. - alpha)).round() as u8;
+            }
+            target[3] = (alpha * 255. + target[3] as f32 * (1. - alpha)).round() as u8;
+        }
+    }
 }
 
 /// A line of text that has been shaped, decorated, and wrapped by the text layout system.
@@ -2695,6 +2695,18 @@ impl<'a> WindowContext<'a> {
         });
     }
 
+    /// Whether any frame of an image is uploaded to the sprite atlas, which happens the first
+    /// time it is painted.
+    pub fn is_image_in_atlas(&self, data: &RenderImage) -> bool {
+        (0..data.frame_count()).any(|frame_index| {
+            let params = RenderImageParams {
+                image_id: data.id,
+                frame_index,
+            };
+            self.window.sprite_atlas.contains(&params.into())
+        })
+    }
+
     /// Removes an image from the sprite atlas.
     pub fn drop_image(&mut self, data: Arc<RenderImage>) -> Result<()> {
         for frame_index in 0..data.frame_count() {
@@ -3129,6 +3141,11 @@ impl<'a> WindowContext<'a> {
                 self.window.modifiers = scroll_wheel.modifiers;
                 PlatformInput::ScrollWheel(scroll_wheel)
             }
+            PlatformInput::Pinch(pinch) => {
+                self.window.mouse_position = pinch.position;
+                self.window.modifiers = pinch.modifiers;
+                PlatformInput::Pinch(pinch)
+            }
             // Translate dragging and dropping of external files from the operating system
             // to internal drag and drop events.
             PlatformInput::FileDrop(file_drop) => match file_drop {
@@ -0,0 +1,216 @@
+use std::{path::Path, sync::Arc};
+
+use gpui::{
+    AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model, Render,
+    Subscription, Task, View, ViewContext, VisualContext, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use project::{Project, ProjectPath};
+use ui::{prelude::*, ListItem, ListItemSpacing};
+use workspace::{notifications::DetachAndPromptErr, ModalView, OpenArchiveImages, Workspace};
+
+use crate::ImageView;
+
+/// A modal listing the images inside an archive, which opens the chosen one read-only.
+pub struct ArchiveImages {
+    picker: View<Picker<ArchiveImagesDelegate>>,
+    _subscription: Subscription,
+}
+
+impl ArchiveImages {
+    pub fn register(workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>) {
+        workspace.register_action(Self::toggle);
+    }
+
+    fn toggle(
+        workspace: &mut Workspace,
+        action: &OpenArchiveImages,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let project = workspace.project().clone();
+        let Some((worktree, relative_path)) = project.read(cx).find_worktree(&action.path, cx)
+        else {
+            return;
+        };
+        let archive = ProjectPath {
+            worktree_id: worktree.read(cx).id(),
+            path: relative_path.into(),
+        };
+        let list_images = project.update(cx, |project, cx| {
+            project.image_store().update(cx, |image_store, cx| {
+                image_store.archive_images(archive.clone(), cx)
+            })
+        });
+
+        cx.spawn(|workspace, mut cx| async move {
+            let images = list_images.await?;
+            workspace.update(&mut cx, |workspace, cx| {
+                let delegate =
+                    ArchiveImagesDelegate::new(cx.view().downgrade(), project, archive, images);
+                workspace.toggle_modal(cx, |cx| ArchiveImages::new(delegate, cx));
+            })
+        })
+        .detach_and_prompt_err("Failed to read archive", cx, |_, _| None);
+    }
+
+    fn new(delegate: ArchiveImagesDelegate, cx: &mut ViewContext<Self>) -> Self {
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| cx.emit(DismissEvent));
+        Self {
+            picker,
+            _subscription,
+        }
+    }
+}
+
+impl ModalView for ArchiveImages {}
+impl EventEmitter<DismissEvent> for ArchiveImages {}
+
+impl FocusableView for ArchiveImages {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for ArchiveImages {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+pub struct ArchiveImagesDelegate {
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    archive: ProjectPath,
+    /// The paths of the images inside the archive.
+    images: Vec<Arc<Path>>,
+    /// Indices into `images` of the images matching the query.
+    matches: Vec<usize>,
+    selected_index: usize,
+}
+
+impl ArchiveImagesDelegate {
+    fn new(
+        workspace: WeakView<Workspace>,
+        project: Model<Project>,
+        archive: ProjectPath,
+        images: Vec<Arc<Path>>,
+    ) -> Self {
+        Self {
+            workspace,
+            project,
+            archive,
+            matches: (0..images.len()).collect(),
+            images,
+            selected_index: 0,
+        }
+    }
+}
+
+impl PickerDelegate for ArchiveImagesDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Search images in archive…".into()
+    }
+
+    fn no_matches_text(&self, _cx: &mut WindowContext) -> SharedString {
+        "No images in this archive".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, _: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let query = query.trim().to_lowercase();
+        self.matches = self
+            .images
+            .iter()
+            .enumerate()
+            .filter(|(_, path)| {
+                query.is_empty() || path.to_string_lossy().to_lowercase().contains(&query)
+            })
+            .map(|(ix, _)| ix)
+            .collect();
+        self.selected_index = self
+            .selected_index
+            .min(self.matches.len().saturating_sub(1));
+        Task::ready(())
+    }
+
+    fn confirm(&mut self, _secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(entry_path) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|ix| self.images.get(*ix))
+            .cloned()
+        else {
+            return;
+        };
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let project = self.project.clone();
+        let archive = self.archive.clone();
+        workspace.update(cx, |workspace, cx| {
+            let open_image = project.update(cx, |project, cx| {
+                project.image_store().update(cx, |image_store, cx| {
+                    image_store.open_image_in_archive(archive, entry_path, cx)
+                })
+            });
+            cx.spawn(|workspace, mut cx| async move {
+                let image_item = open_image.await?;
+                workspace.update(&mut cx, |workspace, cx| {
+                    let image_view = cx.new_view(|cx| ImageView::new(image_item, project, cx));
+                    workspace.add_item_to_active_pane(Box::new(image_view), None, true, cx);
+                })
+            })
+            .detach_and_prompt_err("Failed to open image", cx, |_, _| None);
+        });
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let path = self.images.get(*self.matches.get(ix)?)?;
+        let file_name = path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let directory = path
+            .parent()
+            .map(|parent| parent.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(
+                    v_flex().child(Label::new(file_name).single_line()).child(
+                        Label::new(directory)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .single_line(),
+                    ),
+                ),
+        )
+    }
+}
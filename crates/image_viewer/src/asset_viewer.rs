@@ -0,0 +1,388 @@
+//! Scaffolding shared by the views of binary files of the project, like images and fonts.
+//!
+//! A view of a new kind of file implements [`AssetView`], delegates its tab, breadcrumbs and
+//! serialization to the functions of this module, and is registered with [`register`]. Its
+//! file is loaded into an [`AssetItem`], and the view is stored in the `asset_views` table
+//! under its [`AssetView::KIND`], along with whatever state it chooses to restore.
+
+use std::{
+    ffi::OsStr,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{anyhow, Context as _};
+use file_icons::FileIcons;
+use gpui::{AnyElement, AppContext, AsyncWindowContext, Model, Task, View};
+use project::{Project, ProjectEntryId, ProjectPath};
+use settings::Settings;
+use ui::prelude::*;
+use util::paths::PathExt;
+use workspace::{
+    item::{BreadcrumbText, Item, ProjectItem, SerializableItem, TabContentParams},
+    ItemId, ItemSettings, Workspace, WorkspaceId,
+};
+
+use crate::persistence::IMAGE_VIEWER;
+
+/// The kind of files a view of assets shows.
+pub trait AssetKind: 'static {
+    /// The extensions of the files, in lowercase and without the leading dot.
+    const EXTENSIONS: &'static [&'static str];
+
+    fn matches(path: &Path) -> bool {
+        path.extension()
+            .and_then(OsStr::to_str)
+            .map_or(false, |extension| {
+                Self::EXTENSIONS.contains(&extension.to_ascii_lowercase().as_str())
+            })
+    }
+}
+
+/// The contents of a project file of the [`AssetKind`] `K`.
+pub struct AssetItem<K> {
+    file: Arc<project::File>,
+    content: Arc<[u8]>,
+    _kind: PhantomData<K>,
+}
+
+impl<K> AssetItem<K> {
+    pub fn file(&self) -> &Arc<project::File> {
+        &self.file
+    }
+
+    pub fn content(&self) -> &Arc<[u8]> {
+        &self.content
+    }
+
+    pub fn path(&self) -> &Arc<Path> {
+        &self.file.path
+    }
+
+    pub fn project_path(&self, cx: &AppContext) -> ProjectPath {
+        ProjectPath {
+            worktree_id: self.file.worktree.read(cx).id(),
+            path: self.file.path.clone(),
+        }
+    }
+}
+
+impl<K: AssetKind> project::Item for AssetItem<K> {
+    fn try_open(
+        project: &Model<Project>,
+        path: &ProjectPath,
+        cx: &mut AppContext,
+    ) -> Option<Task<gpui::Result<Model<Self>>>> {
+        if !K::matches(&path.path) {
+            return None;
+        }
+        let Some(worktree) = project.read(cx).worktree_for_id(path.worktree_id, cx) else {
+            return Some(Task::ready(Err(anyhow!("no such worktree"))));
+        };
+        let load = worktree.update(cx, |worktree, cx| worktree.load_binary_file(&path.path, cx));
+        Some(cx.spawn(|mut cx| async move {
+            let loaded = load.await?;
+            cx.new_model(|_| AssetItem {
+                file: loaded.file,
+                content: loaded.content.into(),
+                _kind: PhantomData,
+            })
+        }))
+    }
+
+    fn entry_id(&self, _: &AppContext) -> Option<ProjectEntryId> {
+        self.file.entry_id
+    }
+
+    fn project_path(&self, cx: &AppContext) -> Option<ProjectPath> {
+        Some(AssetItem::project_path(self, cx))
+    }
+}
+
+/// A view of a single [`AssetItem`].
+pub trait AssetView: Item + Sized {
+    type Kind: AssetKind;
+
+    /// Identifies the view's rows in the `asset_views` table, so it has to stay the same
+    /// across versions.
+    const KIND: &'static str;
+
+    fn new(
+        asset: Model<AssetItem<Self::Kind>>,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self;
+
+    fn asset(&self) -> &Model<AssetItem<Self::Kind>>;
+
+    fn project(&self) -> &Model<Project>;
+
+    /// The state of the view that is restored with it, like its scroll position.
+    fn serialize_state(&self, _cx: &AppContext) -> Option<String> {
+        None
+    }
+
+    fn restore_state(&mut self, _state: &str, _cx: &mut ViewContext<Self>) {}
+}
+
+/// Registers an asset view to open the files of its kind and to be restored with the workspace.
+pub fn register<V>(cx: &mut AppContext)
+where
+    V: AssetView + ProjectItem<Item = AssetItem<<V as AssetView>::Kind>> + SerializableItem,
+{
+    workspace::register_project_item::<V>(cx);
+    workspace::register_serializable_item::<V>(cx);
+}
+
+/// The title of the tab of a view of the file at `path`.
+pub fn tab_content(path: &Path, params: TabContentParams) -> AnyElement {
+    let title = path
+        .file_name()
+        .unwrap_or_else(|| path.as_os_str())
+        .to_string_lossy()
+        .to_string();
+    Label::new(title)
+        .single_line()
+        .color(params.text_color())
+        .italic(params.preview)
+        .into_any_element()
+}
+
+/// The file icon of the tab of a view of the file at `path`, when file icons are enabled.
+pub fn tab_icon(path: &Path, cx: &WindowContext) -> Option<Icon> {
+    ItemSettings::get_global(cx)
+        .file_icons
+        .then(|| FileIcons::get_icon(path, cx))
+        .flatten()
+        .map(Icon::from_path)
+}
+
+/// The absolute path of the file, for the tooltip of its tab.
+pub fn tab_tooltip_text(
+    project: &Project,
+    project_path: &ProjectPath,
+    cx: &AppContext,
+) -> Option<SharedString> {
+    let abs_path = project.absolute_path(project_path, cx)?;
+    Some(abs_path.compact().to_string_lossy().to_string().into())
+}
+
+/// The path of the file as breadcrumbs, starting with the name of its worktree when the
+/// project has several.
+pub fn breadcrumbs(
+    project: &Project,
+    project_path: &ProjectPath,
+    cx: &AppContext,
+) -> Option<Vec<BreadcrumbText>> {
+    let path = &project_path.path;
+    let text = if project.visible_worktrees(cx).count() <= 1 {
+        path.to_string_lossy().to_string()
+    } else {
+        project
+            .worktree_for_id(project_path.worktree_id, cx)
+            .map(|worktree| {
+                PathBuf::from(worktree.read(cx).root_name())
+                    .join(path)
+                    .to_string_lossy()
+                    .to_string()
+            })
+            .unwrap_or_else(|| path.to_string_lossy().to_string())
+    };
+    Some(vec![BreadcrumbText {
+        text,
+        highlights: None,
+        font: None,
+    }])
+}
+
+/// The root of the file's worktree, and its path relative to it.
+///
+/// Paths are stored this way, rather than as absolute paths, so that files can still be found
+/// after the project is moved or opened on another machine.
+pub fn stored_path(
+    project: &Project,
+    project_path: &ProjectPath,
+    cx: &AppContext,
+) -> Option<(PathBuf, PathBuf)> {
+    let worktree_root = project
+        .worktree_for_id(project_path.worktree_id, cx)?
+        .read(cx)
+        .abs_path()
+        .to_path_buf();
+    Some((worktree_root, project_path.path.to_path_buf()))
+}
+
+/// Finds the project path of `path`, relative to a worktree that was rooted at
+/// `worktree_root` when it was stored.
+///
+/// When no worktree of the project has that root anymore, for example because the project was
+/// moved, the file is looked up in the worktrees with the same name.
+pub fn find_stored_path(
+    project: &Project,
+    worktree_root: &Path,
+    path: &Path,
+    cx: &AppContext,
+) -> Option<ProjectPath> {
+    let worktrees = project.worktrees(cx).collect::<Vec<_>>();
+    let worktree = worktrees
+        .iter()
+        .find(|worktree| worktree.read(cx).abs_path().as_ref() == worktree_root)
+        .or_else(|| {
+            worktrees.iter().find(|worktree| {
+                let worktree = worktree.read(cx);
+                worktree_root.file_name() == Some(OsStr::new(worktree.root_name()))
+                    && worktree.entry_for_path(path).is_some()
+            })
+        })?;
+    Some(ProjectPath {
+        worktree_id: worktree.read(cx).id(),
+        path: path.into(),
+    })
+}
+
+/// The absolute path of `path`, relative to a worktree rooted at `worktree_root`.
+pub fn stored_abs_path(worktree_root: &Path, path: &Path) -> PathBuf {
+    // Single-file worktrees are rooted at the file itself.
+    if path.as_os_str().is_empty() {
+        worktree_root.to_path_buf()
+    } else {
+        worktree_root.join(path)
+    }
+}
+
+/// The project path of the file at `abs_path`, adding an invisible worktree for it if
+/// necessary.
+pub async fn project_path_for_abs_path(
+    project: &Model<Project>,
+    abs_path: PathBuf,
+    cx: &mut AsyncWindowContext,
+) -> anyhow::Result<ProjectPath> {
+    let (worktree, relative_path) = project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree(abs_path, false, cx)
+        })?
+        .await
+        .context("Path not found")?;
+    let worktree_id = worktree.update(cx, |worktree, _cx| worktree.id())?;
+    Ok(ProjectPath {
+        worktree_id,
+        path: relative_path.into(),
+    })
+}
+
+/// The project path of a file stored with [`stored_path`].
+pub async fn resolve_stored_path(
+    project: &Model<Project>,
+    worktree_root: PathBuf,
+    path: PathBuf,
+    cx: &mut AsyncWindowContext,
+) -> anyhow::Result<ProjectPath> {
+    let project_path = project.update(cx, |project, cx| {
+        find_stored_path(project, &worktree_root, &path, cx)
+    })?;
+    match project_path {
+        Some(project_path) => Ok(project_path),
+        None => {
+            let abs_path = stored_abs_path(&worktree_root, &path);
+            project_path_for_abs_path(project, abs_path, cx).await
+        }
+    }
+}
+
+/// Restores a view stored with [`serialize`], for [`SerializableItem::deserialize`].
+pub fn deserialize<V: AssetView>(
+    project: Model<Project>,
+    workspace_id: WorkspaceId,
+    item_id: ItemId,
+    cx: &mut WindowContext,
+) -> Task<gpui::Result<View<V>>> {
+    cx.spawn(|mut cx| async move {
+        let (worktree_root, path, state) = IMAGE_VIEWER
+            .get_asset_view(item_id, workspace_id, V::KIND.to_string())?
+            .context("No asset path found")?;
+        let project_path = resolve_stored_path(&project, worktree_root, path, &mut cx).await?;
+        let asset = cx
+            .update(|cx| {
+                <AssetItem<V::Kind> as project::Item>::try_open(&project, &project_path, cx)
+            })?
+            .with_context(|| format!("{:?} can't be opened as {}", project_path.path, V::KIND))?
+            .await?;
+
+        cx.update(|cx| {
+            Ok(cx.new_view(|cx| {
+                let mut view = V::new(asset, project, cx);
+                if let Some(state) = state {
+                    view.restore_state(&state, cx);
+                }
+                view
+            }))
+        })?
+    })
+}
+
+/// Stores the path of the view's file and its state, for [`SerializableItem::serialize`].
+pub fn serialize<V: AssetView>(
+    view: &V,
+    workspace: &Workspace,
+    item_id: ItemId,
+    cx: &ViewContext<V>,
+) -> Option<Task<gpui::Result<()>>> {
+    let workspace_id = workspace.database_id()?;
+    let project_path = view.asset().read(cx).project_path(cx);
+    let (worktree_root, path) = stored_path(view.project().read(cx), &project_path, cx)?;
+    let state = view.serialize_state(cx);
+    Some(cx.background_executor().spawn(async move {
+        IMAGE_VIEWER
+            .save_asset_view(
+                item_id,
+                workspace_id,
+                V::KIND.to_string(),
+                worktree_root,
+                path,
+                state,
+            )
+            .await
+    }))
+}
+
+/// Deletes the stored views of this kind that are no longer open, for
+/// [`SerializableItem::cleanup`].
+pub fn cleanup<V: AssetView>(
+    workspace_id: WorkspaceId,
+    alive_items: Vec<ItemId>,
+    cx: &mut WindowContext,
+) -> Task<gpui::Result<()>> {
+    cx.spawn(|_| IMAGE_VIEWER.delete_unloaded_asset_views(V::KIND, workspace_id, alive_items))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Fonts;
+
+    impl AssetKind for Fonts {
+        const EXTENSIONS: &'static [&'static str] = &["ttf", "otf"];
+    }
+
+    #[test]
+    fn test_asset_kind_matches() {
+        assert!(Fonts::matches(Path::new("fonts/Inter.ttf")));
+        assert!(Fonts::matches(Path::new("Inter.OTF")));
+        assert!(!Fonts::matches(Path::new("Inter.ttf.txt")));
+        assert!(!Fonts::matches(Path::new("ttf")));
+    }
+
+    #[test]
+    fn test_stored_abs_path() {
+        assert_eq!(
+            stored_abs_path(Path::new("/project"), Path::new("assets/logo.png")),
+            PathBuf::from("/project/assets/logo.png")
+        );
+        assert_eq!(
+            stored_abs_path(Path::new("/downloads/logo.png"), Path::new("")),
+            PathBuf::from("/downloads/logo.png")
+        );
+    }
+}
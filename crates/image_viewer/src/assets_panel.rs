@@ -0,0 +1,698 @@
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    ffi::OsStr,
+    ops::Range,
+    path::Path,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::Context as _;
+use db::kvp::KEY_VALUE_STORE;
+use editor::{Editor, EditorEvent};
+use file_icons::FileIcons;
+use fs::Fs;
+use gpui::{
+    actions, img, uniform_list, Action, AppContext, AsyncWindowContext, EventEmitter, FocusHandle,
+    FocusableView, Image, Model, ObjectFit, Pixels, Render, Subscription, Task,
+    UniformListScrollHandle, View, ViewContext, WeakView,
+};
+use project::{
+    image_store::{image_dimensions, is_image_path},
+    Project, ProjectPath, WorktreeId,
+};
+use serde::{Deserialize, Serialize};
+use settings::Settings;
+use ui::{prelude::*, ListItem, Tooltip};
+use util::ResultExt;
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    Workspace,
+};
+
+use crate::{
+    assets_panel_settings::{AssetsPanelDockPosition, AssetsPanelSettings},
+    ImageViewerSettings,
+};
+
+actions!(assets_panel, [ToggleFocus]);
+
+const ASSETS_PANEL_KEY: &str = "AssetsPanel";
+const THUMBNAIL_SIZE: Pixels = px(24.);
+const REFRESH_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// Audio and video files, which are listed without thumbnails.
+const MEDIA_EXTENSIONS: &[&str] = &[
+    "aac", "flac", "m4a", "mp3", "ogg", "wav", "avi", "mkv", "mov", "mp4", "webm",
+];
+
+/// The order in which assets are listed within their folder.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum AssetSort {
+    #[default]
+    Name,
+    Dimensions,
+    Size,
+    Modified,
+}
+
+impl AssetSort {
+    fn label(&self) -> &'static str {
+        match self {
+            AssetSort::Name => "Name",
+            AssetSort::Dimensions => "Dimensions",
+            AssetSort::Size => "Size",
+            AssetSort::Modified => "Modified",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Asset {
+    project_path: ProjectPath,
+    size: u64,
+    mtime: Option<SystemTime>,
+    is_image: bool,
+}
+
+impl Asset {
+    fn file_name(&self) -> String {
+        self.project_path
+            .path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
+    fn folder(&self) -> (WorktreeId, Arc<Path>) {
+        let folder = self
+            .project_path
+            .path
+            .parent()
+            .map(Arc::from)
+            .unwrap_or_else(|| Arc::from(Path::new("")));
+        (self.project_path.worktree_id, folder)
+    }
+}
+
+#[derive(Clone, Debug)]
+enum AssetListEntry {
+    Folder {
+        worktree_id: WorktreeId,
+        path: Arc<Path>,
+        asset_count: usize,
+    },
+    /// An index into `AssetsPanel::assets`.
+    Asset(usize),
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedAssetsPanel {
+    width: Option<Pixels>,
+}
+
+/// A dock panel listing the images and other media files of every worktree, grouped by folder.
+pub struct AssetsPanel {
+    project: Model<Project>,
+    workspace: WeakView<Workspace>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    width: Option<Pixels>,
+    filter_editor: View<Editor>,
+    sort: AssetSort,
+    assets: Vec<Asset>,
+    list_entries: Vec<AssetListEntry>,
+    collapsed_folders: HashSet<(WorktreeId, Arc<Path>)>,
+    /// Loaded images by path, `None` when the image failed to load.
+    thumbnails: HashMap<ProjectPath, Option<Arc<Image>>>,
+    /// The range of list entries whose thumbnails were last requested.
+    thumbnails_range: Option<Range<usize>>,
+    load_thumbnails_task: Option<Task<Option<()>>>,
+    refresh_task: Task<()>,
+    scroll_handle: UniformListScrollHandle,
+    pending_serialization: Task<Option<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl AssetsPanel {
+    pub async fn load(
+        workspace: WeakView<Workspace>,
+        mut cx: AsyncWindowContext,
+    ) -> anyhow::Result<View<Self>> {
+        let serialized_panel = cx
+            .background_executor()
+            .spawn(async move { KEY_VALUE_STORE.read_kvp(ASSETS_PANEL_KEY) })
+            .await
+            .context("loading assets panel")
+            .log_err()
+            .flatten()
+            .map(|panel| serde_json::from_str::<SerializedAssetsPanel>(&panel))
+            .transpose()
+            .log_err()
+            .flatten();
+
+        workspace.update(&mut cx, |workspace, cx| {
+            let panel = cx.new_view(|cx| Self::new(workspace, cx));
+            if let Some(serialized_panel) = serialized_panel {
+                panel.update(cx, |panel, cx| {
+                    panel.width = serialized_panel.width.map(|width| width.round());
+                    cx.notify();
+                });
+            }
+            panel
+        })
+    }
+
+    fn new(workspace: &Workspace, cx: &mut ViewContext<Self>) -> Self {
+        let project = workspace.project().clone();
+        let filter_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Filter assets…", cx);
+            editor
+        });
+        let mut include_ignored = AssetsPanelSettings::get_global(cx).include_ignored;
+        let subscriptions = vec![
+            cx.subscribe(&filter_editor, |this, _, event, cx| {
+                if let EditorEvent::Edited { .. } = event {
+                    this.update_list_entries(cx);
+                }
+            }),
+            cx.subscribe(&project, |this, _, event, cx| match event {
+                project::Event::WorktreeUpdatedEntries(..)
+                | project::Event::WorktreeAdded(_)
+                | project::Event::WorktreeRemoved(_) => this.schedule_refresh(cx),
+                _ => {}
+            }),
+            cx.observe_global::<settings::SettingsStore>(move |this, cx| {
+                let new_include_ignored = AssetsPanelSettings::get_global(cx).include_ignored;
+                if include_ignored != new_include_ignored {
+                    include_ignored = new_include_ignored;
+                    this.schedule_refresh(cx);
+                }
+                cx.notify();
+            }),
+        ];
+
+        let mut this = Self {
+            project,
+            workspace: workspace.weak_handle(),
+            fs: workspace.app_state().fs.clone(),
+            focus_handle: cx.focus_handle(),
+            width: None,
+            filter_editor,
+            sort: AssetSort::default(),
+            assets: Vec::new(),
+            list_entries: Vec::new(),
+            collapsed_folders: HashSet::default(),
+            thumbnails: HashMap::default(),
+            thumbnails_range: None,
+            load_thumbnails_task: None,
+            refresh_task: Task::ready(()),
+            scroll_handle: UniformListScrollHandle::new(),
+            pending_serialization: Task::ready(None),
+            _subscriptions: subscriptions,
+        };
+        this.refresh_assets(cx);
+        this
+    }
+
+    fn serialize(&mut self, cx: &mut ViewContext<Self>) {
+        let width = self.width;
+        self.pending_serialization = cx.background_executor().spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        ASSETS_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedAssetsPanel { width })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    fn schedule_refresh(&mut self, cx: &mut ViewContext<Self>) {
+        self.refresh_task = cx.spawn(|this, mut cx| async move {
+            cx.background_executor().timer(REFRESH_DEBOUNCE).await;
+            this.update(&mut cx, |this, cx| this.refresh_assets(cx))
+                .ok();
+        });
+    }
+
+    /// Re-indexes the assets of all visible worktrees.
+    fn refresh_assets(&mut self, cx: &mut ViewContext<Self>) {
+        let include_ignored = AssetsPanelSettings::get_global(cx).include_ignored;
+        let snapshots = self
+            .project
+            .read(cx)
+            .visible_worktrees(cx)
+            .map(|worktree| worktree.read(cx).snapshot())
+            .collect::<Vec<_>>();
+        self.refresh_task = cx.spawn(|this, mut cx| async move {
+            let assets = cx
+                .background_executor()
+                .spawn(async move {
+                    let mut assets = Vec::new();
+                    for snapshot in snapshots {
+                        for entry in snapshot.files(include_ignored, 0) {
+                            let is_image = is_image_path(&entry.path);
+                            if !is_image && !is_media_path(&entry.path) {
+                                continue;
+                            }
+                            assets.push(Asset {
+                                project_path: ProjectPath {
+                                    worktree_id: snapshot.id(),
+                                    path: entry.path.clone(),
+                                },
+                                size: entry.size,
+                                mtime: entry.mtime.map(|mtime| mtime.timestamp_for_user()),
+                                is_image,
+                            });
+                        }
+                    }
+                    assets
+                })
+                .await;
+            this.update(&mut cx, |this, cx| {
+                this.thumbnails.retain(|project_path, _| {
+                    assets
+                        .iter()
+                        .any(|asset| &asset.project_path == project_path)
+                });
+                this.assets = assets;
+                this.update_list_entries(cx);
+            })
+            .ok();
+        });
+    }
+
+    fn set_sort(&mut self, sort: AssetSort, cx: &mut ViewContext<Self>) {
+        self.sort = sort;
+        self.update_list_entries(cx);
+    }
+
+    fn dimensions(&self, asset: &Asset) -> Option<(u32, u32)> {
+        image_dimensions(self.thumbnails.get(&asset.project_path)?.as_ref()?)
+    }
+
+    fn update_list_entries(&mut self, cx: &mut ViewContext<Self>) {
+        let query = self.filter_editor.read(cx).text(cx).to_lowercase();
+        let mut asset_ixs = self
+            .assets
+            .iter()
+            .enumerate()
+            .filter(|(_, asset)| {
+                query.is_empty()
+                    || asset
+                        .project_path
+                        .path
+                        .to_string_lossy()
+                        .to_lowercase()
+                        .contains(&query)
+            })
+            .map(|(ix, _)| ix)
+            .collect::<Vec<_>>();
+        match self.sort {
+            AssetSort::Name => asset_ixs.sort_by_key(|ix| self.assets[*ix].file_name()),
+            AssetSort::Dimensions => asset_ixs.sort_by_key(|ix| {
+                Reverse(
+                    self.dimensions(&self.assets[*ix])
+                        .map(|(width, height)| width as u64 * height as u64),
+                )
+            }),
+            AssetSort::Size => asset_ixs.sort_by_key(|ix| Reverse(self.assets[*ix].size)),
+            AssetSort::Modified => asset_ixs.sort_by_key(|ix| Reverse(self.assets[*ix].mtime)),
+        }
+        // The sort above is stable, so assets keep their order within each folder.
+        asset_ixs.sort_by_key(|ix| self.assets[*ix].folder());
+
+        let mut list_entries = Vec::new();
+        let mut folder_start = 0;
+        for (ix, asset_ix) in asset_ixs.iter().enumerate() {
+            let folder = self.assets[*asset_ix].folder();
+            let next_folder = asset_ixs
+                .get(ix + 1)
+                .map(|next_ix| self.assets[*next_ix].folder());
+            if next_folder.as_ref() == Some(&folder) {
+                continue;
+            }
+            let folder_assets = &asset_ixs[folder_start..=ix];
+            folder_start = ix + 1;
+            let collapsed = self.collapsed_folders.contains(&folder);
+            list_entries.push(AssetListEntry::Folder {
+                worktree_id: folder.0,
+                path: folder.1,
+                asset_count: folder_assets.len(),
+            });
+            if !collapsed {
+                list_entries.extend(folder_assets.iter().copied().map(AssetListEntry::Asset));
+            }
+        }
+        self.list_entries = list_entries;
+        self.thumbnails_range = None;
+        cx.notify();
+    }
+
+    fn toggle_folder(
+        &mut self,
+        worktree_id: WorktreeId,
+        path: Arc<Path>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let folder = (worktree_id, path);
+        if !self.collapsed_folders.remove(&folder) {
+            self.collapsed_folders.insert(folder);
+        }
+        self.update_list_entries(cx);
+    }
+
+    /// Loads the thumbnails of the images among the given list entries, in display order.
+    fn load_thumbnails(&mut self, range: Range<usize>, cx: &mut ViewContext<Self>) {
+        if self.thumbnails_range.as_ref() == Some(&range) {
+            return;
+        }
+        self.thumbnails_range = Some(range.clone());
+        let pending = self.list_entries[range]
+            .iter()
+            .filter_map(|entry| match entry {
+                AssetListEntry::Asset(ix) => Some(&self.assets[*ix]),
+                AssetListEntry::Folder { .. } => None,
+            })
+            .filter(|asset| asset.is_image && !self.thumbnails.contains_key(&asset.project_path))
+            .map(|asset| asset.project_path.clone())
+            .collect::<Vec<_>>();
+        if pending.is_empty() {
+            return;
+        }
+
+        let project = self.project.clone();
+        self.load_thumbnails_task = Some(cx.spawn(|this, mut cx| {
+            async move {
+                for project_path in pending {
+                    let image_item = project
+                        .update(&mut cx, |project, cx| {
+                            project.open_image(project_path.clone(), cx)
+                        })?
+                        .await
+                        .log_err();
+                    let image = image_item.and_then(|image_item| {
+                        image_item
+                            .read_with(&cx, |image_item, _| image_item.image.clone())
+                            .ok()
+                    });
+                    this.update(&mut cx, |this, cx| {
+                        this.thumbnails.insert(project_path, image);
+                        cx.notify();
+                    })?;
+                }
+                anyhow::Ok(())
+            }
+            .log_err()
+        }));
+    }
+
+    fn open_asset(&mut self, project_path: ProjectPath, cx: &mut ViewContext<Self>) {
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace
+                    .open_path(project_path, None, true, cx)
+                    .detach_and_log_err(cx);
+            })
+            .ok();
+    }
+
+    fn folder_label(&self, worktree_id: WorktreeId, path: &Path, cx: &AppContext) -> String {
+        let project = self.project.read(cx);
+        let root_name = project
+            .worktree_for_id(worktree_id, cx)
+            .map(|worktree| worktree.read(cx).root_name().to_string())
+            .unwrap_or_default();
+        if path.as_os_str().is_empty() {
+            root_name
+        } else if project.visible_worktrees(cx).count() > 1 {
+            Path::new(&root_name)
+                .join(path)
+                .to_string_lossy()
+                .to_string()
+        } else {
+            path.to_string_lossy().to_string()
+        }
+    }
+
+    fn render_header(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let sort_button = |sort: AssetSort, cx: &mut ViewContext<Self>| {
+            Button::new(sort.label(), sort.label())
+                .label_size(LabelSize::Small)
+                .selected(self.sort == sort)
+                .tooltip(move |cx| Tooltip::text(format!("Sort by {}", sort.label()), cx))
+                .on_click(cx.listener(move |this, _, cx| this.set_sort(sort, cx)))
+        };
+
+        v_flex()
+            .p_2()
+            .gap_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_2()
+                    .border_1()
+                    .border_color(cx.theme().colors().border)
+                    .rounded_md()
+                    .child(Icon::new(IconName::MagnifyingGlass).color(Color::Muted))
+                    .child(self.filter_editor.clone()),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(sort_button(AssetSort::Name, cx))
+                    .child(sort_button(AssetSort::Dimensions, cx))
+                    .child(sort_button(AssetSort::Size, cx))
+                    .child(sort_button(AssetSort::Modified, cx)),
+            )
+    }
+
+    fn render_list_entry(
+        &self,
+        ix: usize,
+        entry: &AssetListEntry,
+        cx: &mut ViewContext<Self>,
+    ) -> AnyElement {
+        match entry {
+            AssetListEntry::Folder {
+                worktree_id,
+                path,
+                asset_count,
+            } => {
+                let worktree_id = *worktree_id;
+                let path = path.clone();
+                let expanded = !self
+                    .collapsed_folders
+                    .contains(&(worktree_id, path.clone()));
+                ListItem::new(ix)
+                    .toggle(expanded)
+                    .start_slot(Icon::new(IconName::Folder).color(Color::Muted))
+                    .child(
+                        Label::new(self.folder_label(worktree_id, &path, cx))
+                            .single_line()
+                            .color(Color::Muted),
+                    )
+                    .end_slot(
+                        Label::new(asset_count.to_string())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .on_click(cx.listener(move |this, _, cx| {
+                        this.toggle_folder(worktree_id, path.clone(), cx)
+                    }))
+                    .into_any_element()
+            }
+            AssetListEntry::Asset(asset_ix) => {
+                let asset = &self.assets[*asset_ix];
+                let units = ImageViewerSettings::get_global(cx).units();
+                let thumbnail = match self.thumbnails.get(&asset.project_path) {
+                    Some(Some(image)) => img(image.clone())
+                        .size(THUMBNAIL_SIZE)
+                        .object_fit(ObjectFit::ScaleDown)
+                        .into_any_element(),
+                    _ => FileIcons::get_icon(&asset.project_path.path, cx)
+                        .map(|icon| Icon::from_path(icon).color(Color::Muted))
+                        .unwrap_or_else(|| Icon::new(IconName::File).color(Color::Muted))
+                        .into_any_element(),
+                };
+                let dimensions = self
+                    .dimensions(asset)
+                    .map(|(width, height)| units.dimensions(width, height))
+                    .unwrap_or_default();
+                let project_path = asset.project_path.clone();
+                let tooltip_path = asset.project_path.path.to_string_lossy().to_string();
+
+                ListItem::new(ix)
+                    .indent_level(1)
+                    .start_slot(
+                        div()
+                            .size(THUMBNAIL_SIZE)
+                            .flex()
+                            .items_center()
+                            .justify_center()
+                            .child(thumbnail),
+                    )
+                    .child(Label::new(asset.file_name()).single_line())
+                    .end_slot(
+                        h_flex()
+                            .gap_2()
+                            .child(
+                                Label::new(dimensions)
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                            .child(
+                                div().w(rems(4.)).flex().justify_end().child(
+                                    Label::new(units.file_size(asset.size))
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted),
+                                ),
+                            ),
+                    )
+                    .tooltip(move |cx| Tooltip::text(tooltip_path.clone(), cx))
+                    .on_click(
+                        cx.listener(move |this, _, cx| this.open_asset(project_path.clone(), cx)),
+                    )
+                    .into_any_element()
+            }
+        }
+    }
+}
+
+fn is_media_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(OsStr::to_str)
+        .map_or(false, |extension| {
+            MEDIA_EXTENSIONS.contains(&extension.to_lowercase().as_str())
+        })
+}
+
+impl EventEmitter<PanelEvent> for AssetsPanel {}
+
+impl FocusableView for AssetsPanel {
+    fn focus_handle(&self, _cx: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Panel for AssetsPanel {
+    fn persistent_name() -> &'static str {
+        "Assets Panel"
+    }
+
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        match AssetsPanelSettings::get_global(cx).dock {
+            AssetsPanelDockPosition::Left => DockPosition::Left,
+            AssetsPanelDockPosition::Right => DockPosition::Right,
+        }
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        settings::update_settings_file::<AssetsPanelSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings, _| {
+                let dock = match position {
+                    DockPosition::Left | DockPosition::Bottom => AssetsPanelDockPosition::Left,
+                    DockPosition::Right => AssetsPanelDockPosition::Right,
+                };
+                settings.dock = Some(dock);
+            },
+        );
+    }
+
+    fn size(&self, cx: &WindowContext) -> Pixels {
+        self.width
+            .unwrap_or_else(|| AssetsPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        AssetsPanelSettings::get_global(cx)
+            .button
+            .then_some(IconName::Library)
+    }
+
+    fn icon_tooltip(&self, _: &WindowContext) -> Option<&'static str> {
+        Some("Assets Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+}
+
+impl Render for AssetsPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let content = if self.list_entries.is_empty() {
+            let message = if self.assets.is_empty() {
+                "This project contains no images or media"
+            } else {
+                "No assets match the filter"
+            };
+            div()
+                .flex()
+                .flex_1()
+                .items_center()
+                .justify_center()
+                .child(Label::new(message).color(Color::Muted))
+                .into_any_element()
+        } else {
+            uniform_list(
+                cx.view().clone(),
+                "assets",
+                self.list_entries.len(),
+                |this, range, cx| {
+                    this.load_thumbnails(range.clone(), cx);
+                    this.list_entries[range.clone()]
+                        .to_vec()
+                        .iter()
+                        .zip(range)
+                        .map(|(entry, ix)| this.render_list_entry(ix, entry, cx))
+                        .collect()
+                },
+            )
+            .flex_1()
+            .track_scroll(self.scroll_handle.clone())
+            .into_any_element()
+        };
+
+        v_flex()
+            .key_context("AssetsPanel")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .child(self.render_header(cx))
+            .child(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_media_path() {
+        assert!(is_media_path(Path::new("sounds/jump.WAV")));
+        assert!(is_media_path(Path::new("intro.mp4")));
+        assert!(!is_media_path(Path::new("sprite.png")));
+        assert!(!is_media_path(Path::new("README")));
+    }
+}
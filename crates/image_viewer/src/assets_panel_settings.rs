@@ -0,0 +1,52 @@
+use gpui::Pixels;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetsPanelDockPosition {
+    Left,
+    Right,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct AssetsPanelSettings {
+    pub button: bool,
+    pub default_width: Pixels,
+    pub dock: AssetsPanelDockPosition,
+    pub include_ignored: bool,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct AssetsPanelSettingsContent {
+    /// Whether to show the assets panel button in the status bar.
+    ///
+    /// Default: true
+    pub button: Option<bool>,
+    /// Customize default width (in pixels) taken by assets panel
+    ///
+    /// Default: 320
+    pub default_width: Option<f32>,
+    /// The position of assets panel
+    ///
+    /// Default: right
+    pub dock: Option<AssetsPanelDockPosition>,
+    /// Whether to list assets in gitignored directories, like build outputs.
+    ///
+    /// Default: false
+    pub include_ignored: Option<bool>,
+}
+
+impl Settings for AssetsPanelSettings {
+    const KEY: Option<&'static str> = Some("assets_panel");
+
+    type FileContent = AssetsPanelSettingsContent;
+
+    fn load(
+        sources: SettingsSources<Self::FileContent>,
+        _: &mut gpui::AppContext,
+    ) -> anyhow::Result<Self> {
+        sources.json_merge()
+    }
+}
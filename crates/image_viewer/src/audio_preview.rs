@@ -0,0 +1,608 @@
+//! Previews of the audio files of the project, showing their waveform and what they are encoded
+//! with, and playing them.
+
+use std::{io::Cursor, sync::Arc, time::Duration};
+
+use gpui::{
+    actions, img, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, Hsla, Model,
+    RenderImage, Task, View, WeakView,
+};
+use image::RgbaImage;
+use project::Project;
+use rodio::{Decoder, OutputStream, Sink, Source};
+use settings::Settings;
+use theme::Theme;
+use ui::{prelude::*, Tooltip};
+use util::ResultExt;
+use workspace::{
+    item::{BreadcrumbText, Item, ProjectItem, SerializableItem, TabContentParams},
+    ItemId, ToolbarItemLocation, Workspace, WorkspaceId,
+};
+
+use crate::{
+    asset_viewer::{self, AssetItem, AssetKind, AssetView},
+    ImageViewerSettings,
+};
+
+actions!(image_viewer, [TogglePlayback, StopPlayback]);
+
+/// How many columns the waveform is summarized into.
+const WAVEFORM_COLUMNS: usize = 1024;
+
+/// The height of the waveform image, in pixels.
+const WAVEFORM_HEIGHT: u32 = 160;
+
+/// How often the playhead moves while playing.
+const PLAYBACK_REFRESH_INTERVAL: Duration = Duration::from_millis(50);
+
+pub struct AudioFiles;
+
+impl AssetKind for AudioFiles {
+    const EXTENSIONS: &'static [&'static str] = &["wav", "mp3", "ogg"];
+}
+
+/// What is decoded from the audio file to show it.
+#[derive(Debug, PartialEq)]
+struct AudioInfo {
+    channels: u16,
+    sample_rate: u32,
+    duration: Duration,
+    /// The lowest and highest sample of each column of the waveform, between -1 and 1, with the
+    /// channels mixed together.
+    peaks: Vec<(f32, f32)>,
+}
+
+/// Decodes the whole audio file, to find out how long it is and summarize its waveform.
+fn decode_audio(content: Arc<[u8]>) -> anyhow::Result<AudioInfo> {
+    let decoder = Decoder::new(Cursor::new(content))?;
+    let channels = decoder.channels().max(1);
+    let sample_rate = decoder.sample_rate().max(1);
+
+    let mut frames = Vec::new();
+    let mut frame_sum = 0i32;
+    let mut channel = 0;
+    for sample in decoder {
+        frame_sum += sample as i32;
+        channel += 1;
+        if channel == channels {
+            frames.push((frame_sum / channels as i32) as i16);
+            frame_sum = 0;
+            channel = 0;
+        }
+    }
+
+    Ok(AudioInfo {
+        channels,
+        sample_rate,
+        duration: Duration::from_secs_f64(frames.len() as f64 / sample_rate as f64),
+        peaks: waveform_peaks(&frames, WAVEFORM_COLUMNS),
+    })
+}
+
+/// The lowest and highest sample of each of up to `columns` equal runs of `frames`.
+fn waveform_peaks(frames: &[i16], columns: usize) -> Vec<(f32, f32)> {
+    let columns = columns.min(frames.len());
+    let unit = |sample: i16| (sample as f32 / i16::MAX as f32).clamp(-1., 1.);
+    (0..columns)
+        .map(|column| {
+            let start = column * frames.len() / columns;
+            let end = ((column + 1) * frames.len() / columns).max(start + 1);
+            let (min, max) = frames[start..end]
+                .iter()
+                .fold((0, 0), |(min, max), &sample| {
+                    (sample.min(min), sample.max(max))
+                });
+            (unit(min), unit(max))
+        })
+        .collect()
+}
+
+/// Draws the waveform as an image, a column of pixels per peak, in BGRA order.
+fn waveform_image(peaks: &[(f32, f32)], color: Hsla) -> Arc<RenderImage> {
+    let color = color.to_rgb();
+    let pixel = image::Rgba([
+        (color.b * 255.) as u8,
+        (color.g * 255.) as u8,
+        (color.r * 255.) as u8,
+        (color.a * 255.) as u8,
+    ]);
+    let mut image = RgbaImage::new(peaks.len().max(1) as u32, WAVEFORM_HEIGHT);
+    let center = WAVEFORM_HEIGHT as f32 / 2.;
+    for (x, (min, max)) in peaks.iter().enumerate() {
+        let top = (center - max * center).floor().max(0.) as u32;
+        let bottom = (center - min * center).ceil() as u32;
+        // Silence is drawn as a line, rather than not at all.
+        for y in top..bottom.max(top + 1).min(WAVEFORM_HEIGHT) {
+            image.put_pixel(x as u32, y, pixel);
+        }
+    }
+    Arc::new(RenderImage::new(vec![image::Frame::new(image)]))
+}
+
+/// Formats a duration as minutes and seconds, like `3:07.5`.
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs_f32();
+    format!("{}:{:04.1}", (seconds / 60.) as u32, seconds % 60.)
+}
+
+/// The audio output playing the file.
+struct Player {
+    _stream: OutputStream,
+    sink: Sink,
+}
+
+impl Player {
+    fn start(content: Arc<[u8]>) -> anyhow::Result<Self> {
+        let (stream, handle) = OutputStream::try_default()?;
+        let sink = Sink::try_new(&handle)?;
+        sink.append(Decoder::new(Cursor::new(content))?);
+        Ok(Self {
+            _stream: stream,
+            sink,
+        })
+    }
+}
+
+pub struct AudioPreviewView {
+    asset: Model<AssetItem<AudioFiles>>,
+    project: Model<Project>,
+    /// The decoded audio, once decoded.
+    audio: Option<Result<AudioInfo, SharedString>>,
+    /// The waveform, with the color it was drawn in.
+    waveform: Option<(Hsla, Arc<RenderImage>)>,
+    player: Option<Player>,
+    playback_error: Option<SharedString>,
+    focus_handle: FocusHandle,
+    _decode_task: Task<()>,
+    _playback_task: Option<Task<()>>,
+}
+
+impl AudioPreviewView {
+    fn toggle_playback(&mut self, _: &TogglePlayback, cx: &mut ViewContext<Self>) {
+        if let Some(player) = self.player.as_ref().filter(|player| !player.sink.empty()) {
+            if player.sink.is_paused() {
+                player.sink.play();
+                self.watch_playback(cx);
+            } else {
+                player.sink.pause();
+            }
+            cx.notify();
+            return;
+        }
+
+        let content = self.asset.read(cx).content().clone();
+        match Player::start(content) {
+            Ok(player) => {
+                self.player = Some(player);
+                self.playback_error = None;
+                self.watch_playback(cx);
+            }
+            Err(error) => self.playback_error = Some(error.to_string().into()),
+        }
+        cx.notify();
+    }
+
+    fn stop_playback(&mut self, _: &StopPlayback, cx: &mut ViewContext<Self>) {
+        self.player = None;
+        self._playback_task = None;
+        cx.notify();
+    }
+
+    /// Moves the playhead until playback is paused or reaches the end.
+    fn watch_playback(&mut self, cx: &mut ViewContext<Self>) {
+        self._playback_task = Some(cx.spawn(|this, mut cx| async move {
+            loop {
+                cx.background_executor()
+                    .timer(PLAYBACK_REFRESH_INTERVAL)
+                    .await;
+                let playing = this
+                    .update(&mut cx, |this, cx| {
+                        cx.notify();
+                        match &this.player {
+                            Some(player) if player.sink.empty() => {
+                                this.player = None;
+                                false
+                            }
+                            Some(player) => !player.sink.is_paused(),
+                            None => false,
+                        }
+                    })
+                    .unwrap_or(false);
+                if !playing {
+                    break;
+                }
+            }
+        }));
+    }
+
+    fn is_playing(&self) -> bool {
+        self.player.as_ref().map_or(false, |player| {
+            !player.sink.empty() && !player.sink.is_paused()
+        })
+    }
+
+    /// The waveform drawn in `color`, drawing it again if the color changed.
+    fn waveform(
+        &mut self,
+        peaks_color: Hsla,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<Arc<RenderImage>> {
+        let Some(Ok(audio)) = &self.audio else {
+            return None;
+        };
+        match &self.waveform {
+            Some((color, image)) if *color == peaks_color => Some(image.clone()),
+            _ => {
+                let image = waveform_image(&audio.peaks, peaks_color);
+                if let Some((_, old_image)) = self.waveform.replace((peaks_color, image.clone())) {
+                    cx.drop_image(old_image).log_err();
+                }
+                Some(image)
+            }
+        }
+    }
+
+    fn render_audio(&mut self, cx: &mut ViewContext<Self>) -> AnyElement {
+        let colors = cx.theme().colors();
+        let (waveform_color, playhead_color, border_color) =
+            (colors.text_accent, colors.text, colors.border);
+        let waveform = self.waveform(waveform_color, cx);
+        let Some(Ok(audio)) = &self.audio else {
+            return div().into_any_element();
+        };
+
+        let units = ImageViewerSettings::get_global(cx).units();
+        let file_size = self.asset.read(cx).content().len() as u64;
+        let seconds = audio.duration.as_secs_f64();
+        let mut details = vec![
+            format_duration(audio.duration),
+            format!("{} Hz", units.count(audio.sample_rate as usize)),
+            match audio.channels {
+                1 => "Mono".to_string(),
+                2 => "Stereo".to_string(),
+                channels => format!("{channels} channels"),
+            },
+        ];
+        if seconds > 0. {
+            let bitrate = file_size as f64 * 8. / seconds / 1000.;
+            details.push(format!("{} kbps", units.count(bitrate.round() as usize)));
+        }
+        details.push(units.file_size(file_size));
+
+        let position = self
+            .player
+            .as_ref()
+            .map(|player| player.sink.get_pos())
+            .filter(|_| seconds > 0.);
+        let progress = position.map(|position| (position.as_secs_f64() / seconds).min(1.) as f32);
+        let playing = self.is_playing();
+
+        let controls = h_flex()
+            .gap_2()
+            .child(
+                Button::new(
+                    "audio-toggle-playback",
+                    if playing { "Pause" } else { "Play" },
+                )
+                .icon(IconName::Play)
+                .icon_position(IconPosition::Start)
+                .icon_size(IconSize::Small)
+                .label_size(LabelSize::Small)
+                .selected(playing)
+                .tooltip(move |cx| {
+                    let label = if playing { "Pause" } else { "Play" };
+                    Tooltip::for_action(label, &TogglePlayback, cx)
+                })
+                .on_click(cx.listener(|this, _, cx| this.toggle_playback(&TogglePlayback, cx))),
+            )
+            .child(
+                Label::new(format!(
+                    "{} / {}",
+                    format_duration(position.unwrap_or_default()),
+                    format_duration(audio.duration)
+                ))
+                .size(LabelSize::Small),
+            )
+            .when(self.player.is_some(), |this| {
+                this.child(
+                    Button::new("audio-stop-playback", "Stop")
+                        .icon(IconName::Stop)
+                        .icon_position(IconPosition::Start)
+                        .icon_size(IconSize::Small)
+                        .label_size(LabelSize::Small)
+                        .tooltip(|cx| Tooltip::for_action("Stop", &StopPlayback, cx))
+                        .on_click(cx.listener(|this, _, cx| this.stop_playback(&StopPlayback, cx))),
+                )
+            })
+            .when_some(self.playback_error.clone(), |this, error| {
+                this.child(Label::new(error).size(LabelSize::Small).color(Color::Error))
+            });
+
+        v_flex()
+            .w_full()
+            .gap_4()
+            .child(
+                div()
+                    .relative()
+                    .w_full()
+                    .h(px(WAVEFORM_HEIGHT as f32))
+                    .rounded_md()
+                    .border_1()
+                    .border_color(border_color)
+                    .overflow_hidden()
+                    .when_some(waveform, |this, waveform| {
+                        this.child(img(waveform).size_full())
+                    })
+                    .when_some(progress, |this, progress| {
+                        this.child(
+                            div()
+                                .absolute()
+                                .top_0()
+                                .bottom_0()
+                                .left(relative(progress))
+                                .w(px(1.))
+                                .bg(playhead_color),
+                        )
+                    }),
+            )
+            .child(controls)
+            .child(
+                Label::new(details.join(" · "))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .into_any_element()
+    }
+}
+
+impl AssetView for AudioPreviewView {
+    type Kind = AudioFiles;
+
+    const KIND: &'static str = "AudioPreview";
+
+    fn new(
+        asset: Model<AssetItem<AudioFiles>>,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let content = asset.read(cx).content().clone();
+        let decode_task = cx.spawn(|this, mut cx| async move {
+            let audio = cx
+                .background_executor()
+                .spawn(async move { decode_audio(content) })
+                .await
+                .map_err(|error| SharedString::from(error.to_string()));
+            this.update(&mut cx, |this, cx| {
+                this.audio = Some(audio);
+                cx.notify();
+            })
+            .ok();
+        });
+        Self {
+            asset,
+            project,
+            audio: None,
+            waveform: None,
+            player: None,
+            playback_error: None,
+            focus_handle: cx.focus_handle(),
+            _decode_task: decode_task,
+            _playback_task: None,
+        }
+    }
+
+    fn asset(&self) -> &Model<AssetItem<AudioFiles>> {
+        &self.asset
+    }
+
+    fn project(&self) -> &Model<Project> {
+        &self.project
+    }
+}
+
+impl EventEmitter<()> for AudioPreviewView {}
+
+impl FocusableView for AudioPreviewView {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for AudioPreviewView {
+    type Event = ();
+
+    fn to_item_events(_: &Self::Event, _: impl FnMut(workspace::item::ItemEvent)) {}
+
+    fn for_each_project_item(
+        &self,
+        cx: &AppContext,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::Item),
+    ) {
+        f(self.asset.entity_id(), self.asset.read(cx))
+    }
+
+    fn is_singleton(&self, _cx: &AppContext) -> bool {
+        true
+    }
+
+    /// Stops playing when the view is closed or moved out of sight.
+    fn deactivated(&mut self, cx: &mut ViewContext<Self>) {
+        if let Some(player) = &self.player {
+            player.sink.pause();
+            cx.notify();
+        }
+    }
+
+    fn tab_tooltip_text(&self, cx: &AppContext) -> Option<SharedString> {
+        let project_path = self.asset.read(cx).project_path(cx);
+        asset_viewer::tab_tooltip_text(self.project.read(cx), &project_path, cx)
+    }
+
+    fn tab_content(&self, params: TabContentParams, cx: &WindowContext) -> AnyElement {
+        asset_viewer::tab_content(self.asset.read(cx).path(), params)
+    }
+
+    fn tab_icon(&self, cx: &WindowContext) -> Option<Icon> {
+        asset_viewer::tab_icon(self.asset.read(cx).path(), cx)
+    }
+
+    fn breadcrumb_location(&self, _: &AppContext) -> ToolbarItemLocation {
+        ToolbarItemLocation::PrimaryLeft
+    }
+
+    fn breadcrumbs(&self, _theme: &Theme, cx: &AppContext) -> Option<Vec<BreadcrumbText>> {
+        let project_path = self.asset.read(cx).project_path(cx);
+        asset_viewer::breadcrumbs(self.project.read(cx), &project_path, cx)
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: Option<WorkspaceId>,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<View<Self>> {
+        Some(
+            cx.new_view(|cx| {
+                <Self as AssetView>::new(self.asset.clone(), self.project.clone(), cx)
+            }),
+        )
+    }
+}
+
+impl ProjectItem for AudioPreviewView {
+    type Item = AssetItem<AudioFiles>;
+
+    fn for_project_item(
+        project: Model<Project>,
+        item: Model<Self::Item>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        <Self as AssetView>::new(item, project, cx)
+    }
+}
+
+impl SerializableItem for AudioPreviewView {
+    fn serialized_item_kind() -> &'static str {
+        <Self as AssetView>::KIND
+    }
+
+    fn deserialize(
+        project: Model<Project>,
+        _workspace: WeakView<Workspace>,
+        workspace_id: WorkspaceId,
+        item_id: ItemId,
+        cx: &mut WindowContext,
+    ) -> Task<gpui::Result<View<Self>>> {
+        asset_viewer::deserialize(project, workspace_id, item_id, cx)
+    }
+
+    fn cleanup(
+        workspace_id: WorkspaceId,
+        alive_items: Vec<ItemId>,
+        cx: &mut WindowContext,
+    ) -> Task<gpui::Result<()>> {
+        asset_viewer::cleanup::<Self>(workspace_id, alive_items, cx)
+    }
+
+    fn serialize(
+        &mut self,
+        workspace: &mut Workspace,
+        item_id: ItemId,
+        _closing: bool,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<Task<gpui::Result<()>>> {
+        asset_viewer::serialize(self, workspace, item_id, cx)
+    }
+
+    fn should_serialize(&self, _event: &Self::Event) -> bool {
+        false
+    }
+}
+
+impl Render for AudioPreviewView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let content = match &self.audio {
+            Some(Ok(_)) => self.render_audio(cx),
+            Some(Err(error)) => Label::new(error.clone())
+                .color(Color::Muted)
+                .into_any_element(),
+            None => Label::new("Decoding…")
+                .color(Color::Muted)
+                .into_any_element(),
+        };
+        v_flex()
+            .key_context("AudioPreview")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::toggle_playback))
+            .on_action(cx.listener(Self::stop_playback))
+            .size_full()
+            .p_6()
+            .items_center()
+            .justify_center()
+            .bg(cx.theme().colors().editor_background)
+            .child(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 16-bit PCM WAV file of the given interleaved samples.
+    fn wav_file(channels: u16, sample_rate: u32, samples: &[i16]) -> Vec<u8> {
+        let data_size = samples.len() as u32 * 2;
+        let mut wav = Vec::new();
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + data_size).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&channels.to_le_bytes());
+        wav.extend_from_slice(&sample_rate.to_le_bytes());
+        wav.extend_from_slice(&(sample_rate * channels as u32 * 2).to_le_bytes());
+        wav.extend_from_slice(&(channels * 2).to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&data_size.to_le_bytes());
+        for sample in samples {
+            wav.extend_from_slice(&sample.to_le_bytes());
+        }
+        wav
+    }
+
+    #[test]
+    fn test_decode_audio() {
+        // Half a second of stereo audio, with the channels in opposite phase.
+        let samples = (0..8000)
+            .flat_map(|frame| {
+                let sample = if frame % 2 == 0 { 16000 } else { -16000 };
+                [sample, -sample]
+            })
+            .collect::<Vec<i16>>();
+        let audio = decode_audio(wav_file(2, 16000, &samples).into()).unwrap();
+        assert_eq!(audio.channels, 2);
+        assert_eq!(audio.sample_rate, 16000);
+        assert_eq!(audio.duration, Duration::from_millis(500));
+        // Mixing the channels cancels them out.
+        assert!(audio.peaks.iter().all(|&(min, max)| min == 0. && max == 0.));
+
+        assert!(decode_audio(Arc::from(&b"not audio"[..])).is_err());
+    }
+
+    #[test]
+    fn test_waveform_peaks() {
+        let frames = [0, i16::MAX, 0, i16::MIN, 0, 0];
+        assert_eq!(
+            waveform_peaks(&frames, 3),
+            vec![(0., 1.), (-1., 0.), (0., 0.)]
+        );
+        // There are never more columns than frames.
+        assert_eq!(waveform_peaks(&frames[..2], 8).len(), 2);
+        assert!(waveform_peaks(&[], 8).is_empty());
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_millis(187_500)), "3:07.5");
+        assert_eq!(format_duration(Duration::ZERO), "0:00.0");
+    }
+}
@@ -0,0 +1,305 @@
+//! An eyedropper that samples the color of a pixel of the image, which can then be copied or
+//! inserted into the text editor that was last active, written the way colors are written in the
+//! language of that editor.
+
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use editor::Editor;
+use gpui::{AppContext, ClipboardItem, Hsla, Pixels, Point, Task, View};
+use image::{Rgba, RgbaImage};
+use ui::{prelude::*, Tooltip};
+use util::ResultExt;
+use workspace::Workspace;
+
+use crate::{window_workspace, ImageView, ToggleEyedropper};
+
+/// How a color is written in source code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ColorSyntax {
+    /// `#1e90ff`, as in CSS and markup.
+    Hex,
+    /// `Color::rgb(30, 144, 255)`.
+    Rust,
+    /// `Color(red: 0.118, green: 0.565, blue: 1.000)`, as in SwiftUI.
+    Swift,
+    /// `Color(0xFF1E90FF)`, as in Flutter and Jetpack Compose.
+    ArgbLiteral,
+    /// `(30, 144, 255)`.
+    Tuple,
+}
+
+impl ColorSyntax {
+    fn for_language(language: Option<&str>) -> Self {
+        match language {
+            Some("Rust") => ColorSyntax::Rust,
+            Some("Swift") => ColorSyntax::Swift,
+            Some("Dart" | "Kotlin") => ColorSyntax::ArgbLiteral,
+            Some("Python") => ColorSyntax::Tuple,
+            _ => ColorSyntax::Hex,
+        }
+    }
+
+    fn format(&self, Rgba([red, green, blue, alpha]): Rgba<u8>) -> String {
+        let opaque = alpha == u8::MAX;
+        match self {
+            ColorSyntax::Hex if opaque => format!("#{red:02x}{green:02x}{blue:02x}"),
+            ColorSyntax::Hex => format!("#{red:02x}{green:02x}{blue:02x}{alpha:02x}"),
+            ColorSyntax::Rust if opaque => format!("Color::rgb({red}, {green}, {blue})"),
+            ColorSyntax::Rust => format!("Color::rgba({red}, {green}, {blue}, {alpha})"),
+            ColorSyntax::Swift => {
+                let unit = |channel: u8| channel as f32 / 255.;
+                let mut color = format!(
+                    "Color(red: {:.3}, green: {:.3}, blue: {:.3}",
+                    unit(red),
+                    unit(green),
+                    unit(blue)
+                );
+                if !opaque {
+                    color.push_str(&format!(", opacity: {:.3}", unit(alpha)));
+                }
+                color.push(')');
+                color
+            }
+            ColorSyntax::ArgbLiteral => {
+                format!("Color(0x{alpha:02X}{red:02X}{green:02X}{blue:02X})")
+            }
+            ColorSyntax::Tuple if opaque => format!("({red}, {green}, {blue})"),
+            ColorSyntax::Tuple => format!("({red}, {green}, {blue}, {alpha})"),
+        }
+    }
+}
+
+/// The eyedropper of an image view.
+pub(crate) struct ColorPicker {
+    /// The pixels of the image, once decoded.
+    pixels: Option<Arc<RgbaImage>>,
+    sample: Option<Rgba<u8>>,
+    _task: Task<()>,
+}
+
+pub(crate) fn toggle_eyedropper(
+    image_view: &mut ImageView,
+    _: &ToggleEyedropper,
+    cx: &mut ViewContext<ImageView>,
+) {
+    if image_view.color_picker.take().is_none() {
+        let image = image_view
+            .edited_image
+            .clone()
+            .unwrap_or_else(|| image_view.image_item.read(cx).image.clone());
+        let svg_renderer = cx.svg_renderer();
+        let decode = cx.background_executor().spawn(async move {
+            let image = image.to_image_data(svg_renderer)?;
+            let size = image.size(0);
+            let mut bytes = image.as_bytes(0).context("image has no frames")?.to_vec();
+            // Convert from BGRA to RGBA.
+            for pixel in bytes.chunks_exact_mut(4) {
+                pixel.swap(0, 2);
+            }
+            RgbaImage::from_raw(size.width.0 as u32, size.height.0 as u32, bytes)
+                .context("invalid image data")
+        });
+        let task = cx.spawn(|this, mut cx| async move {
+            let pixels = decode.await.log_err().map(Arc::new);
+            this.update(&mut cx, |this, cx| {
+                if let Some(color_picker) = this.color_picker.as_mut() {
+                    color_picker.pixels = pixels;
+                    cx.notify();
+                }
+            })
+            .ok();
+        });
+        image_view.color_picker = Some(ColorPicker {
+            pixels: None,
+            sample: None,
+            _task: task,
+        });
+    }
+    cx.notify();
+}
+
+/// Samples the pixel under `position` while the eyedropper is active, returning whether it is.
+pub(crate) fn sample_at(
+    image_view: &mut ImageView,
+    position: Point<Pixels>,
+    cx: &mut ViewContext<ImageView>,
+) -> bool {
+    if image_view.color_picker.is_none() {
+        return false;
+    }
+    let position = image_view.image_position(position, cx);
+    let Some(color_picker) = image_view.color_picker.as_mut() else {
+        return false;
+    };
+    if let Some((position, pixels)) = position.zip(color_picker.pixels.as_ref()) {
+        let (width, height) = pixels.dimensions();
+        let x = ((position.x * width as f32) as u32).min(width.saturating_sub(1));
+        let y = ((position.y * height as f32) as u32).min(height.saturating_sub(1));
+        color_picker.sample = Some(*pixels.get_pixel(x, y));
+        cx.notify();
+    }
+    true
+}
+
+/// The text editor that was active most recently in any pane of the workspace.
+pub(crate) fn most_recent_editor(workspace: &Workspace, cx: &AppContext) -> Option<View<Editor>> {
+    workspace
+        .panes()
+        .iter()
+        .flat_map(|pane| {
+            let pane = pane.read(cx);
+            pane.activation_history().iter().filter_map(move |entry| {
+                let item = pane
+                    .items()
+                    .find(|item| item.item_id() == entry.entity_id)?;
+                Some((entry.timestamp, item.act_as::<Editor>(cx)?))
+            })
+        })
+        .max_by_key(|(timestamp, _)| *timestamp)
+        .map(|(_, editor)| editor)
+}
+
+/// The syntax of colors in the language at the cursor of `editor`.
+fn editor_color_syntax(editor: &View<Editor>, cx: &AppContext) -> ColorSyntax {
+    let editor = editor.read(cx);
+    let language = editor.language_at(editor.selections.newest_anchor().head(), cx);
+    let name = language.map(|language| language.name());
+    ColorSyntax::for_language(name.as_ref().map(|name| &*name.0))
+}
+
+fn insert_sample(image_view: &mut ImageView, cx: &mut ViewContext<ImageView>) {
+    let Some(color) = image_view
+        .color_picker
+        .as_ref()
+        .and_then(|color_picker| color_picker.sample)
+    else {
+        return;
+    };
+    let Some(workspace) = window_workspace(cx) else {
+        return;
+    };
+    let Some(editor) = most_recent_editor(workspace.read(cx), cx) else {
+        return;
+    };
+    let text = editor_color_syntax(&editor, cx).format(color);
+    editor.update(cx, |editor, cx| editor.insert(&text, cx));
+}
+
+/// A bar below the image showing the sampled color.
+pub(crate) fn render_color_picker_bar(
+    image_view: &ImageView,
+    cx: &mut ViewContext<ImageView>,
+) -> Option<impl IntoElement> {
+    let color_picker = image_view.color_picker.as_ref()?;
+    let editor =
+        window_workspace(cx).and_then(|workspace| most_recent_editor(workspace.read(cx), cx));
+
+    let sample = match (color_picker.sample, color_picker.pixels.is_some()) {
+        (Some(color), _) => {
+            let hex = ColorSyntax::Hex.format(color);
+            let Rgba([red, green, blue, alpha]) = color;
+            let swatch = Hsla::from(gpui::Rgba {
+                r: red as f32 / 255.,
+                g: green as f32 / 255.,
+                b: blue as f32 / 255.,
+                a: alpha as f32 / 255.,
+            });
+            let insert_button = match editor {
+                Some(editor) => {
+                    let syntax = editor_color_syntax(&editor, cx);
+                    let preview = syntax.format(color);
+                    Button::new("color-picker-insert", "Insert at Cursor")
+                        .label_size(LabelSize::Small)
+                        .tooltip(move |cx| Tooltip::text(format!("Insert {preview}"), cx))
+                        .on_click(cx.listener(|this, _, cx| insert_sample(this, cx)))
+                }
+                None => Button::new("color-picker-insert", "Insert at Cursor")
+                    .label_size(LabelSize::Small)
+                    .disabled(true)
+                    .tooltip(|cx| Tooltip::text("No text editor is open", cx)),
+            };
+            h_flex()
+                .gap_2()
+                .child(
+                    div()
+                        .size_4()
+                        .rounded_sm()
+                        .border_1()
+                        .border_color(cx.theme().colors().border)
+                        .bg(swatch),
+                )
+                .child(Label::new(hex.clone()).size(LabelSize::Small))
+                .child(
+                    Label::new(format!("rgba({red}, {green}, {blue}, {alpha})"))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+                .child(
+                    Button::new("color-picker-copy", "Copy")
+                        .label_size(LabelSize::Small)
+                        .on_click(move |_, cx| {
+                            cx.write_to_clipboard(ClipboardItem::new_string(hex.clone()))
+                        }),
+                )
+                .child(insert_button)
+                .into_any_element()
+        }
+        (None, true) => Label::new("Click the image to sample a color")
+            .size(LabelSize::Small)
+            .color(Color::Muted)
+            .into_any_element(),
+        (None, false) => Label::new("Decoding…")
+            .size(LabelSize::Small)
+            .color(Color::Muted)
+            .into_any_element(),
+    };
+
+    Some(
+        h_flex()
+            .w_full()
+            .px_2()
+            .py_1()
+            .gap_2()
+            .border_t_1()
+            .border_color(cx.theme().colors().border)
+            .bg(cx.theme().colors().panel_background)
+            .child(Icon::new(IconName::SwatchBook).size(IconSize::Small))
+            .child(sample)
+            .child(div().flex_1())
+            .child(
+                IconButton::new("color-picker-close", IconName::Close)
+                    .tooltip(|cx| Tooltip::for_action("Close Eyedropper", &ToggleEyedropper, cx))
+                    .on_click(
+                        cx.listener(|this, _, cx| toggle_eyedropper(this, &ToggleEyedropper, cx)),
+                    ),
+            ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_syntax() {
+        let opaque = Rgba([30, 144, 255, 255]);
+        let translucent = Rgba([30, 144, 255, 128]);
+        let format = |language, color| ColorSyntax::for_language(Some(language)).format(color);
+
+        assert_eq!(format("CSS", opaque), "#1e90ff");
+        assert_eq!(format("CSS", translucent), "#1e90ff80");
+        assert_eq!(format("Rust", opaque), "Color::rgb(30, 144, 255)");
+        assert_eq!(
+            format("Rust", translucent),
+            "Color::rgba(30, 144, 255, 128)"
+        );
+        assert_eq!(
+            format("Swift", translucent),
+            "Color(red: 0.118, green: 0.565, blue: 1.000, opacity: 0.502)"
+        );
+        assert_eq!(format("Kotlin", opaque), "Color(0xFF1E90FF)");
+        assert_eq!(format("Python", opaque), "(30, 144, 255)");
+        assert_eq!(ColorSyntax::for_language(None).format(opaque), "#1e90ff");
+    }
+}
@@ -0,0 +1,66 @@
+use gpui::ViewContext;
+use ui::prelude::*;
+use workspace::{SaveAs, SaveIntent};
+
+use crate::{window_workspace, ImageView};
+
+/// A banner shown over an image whose file was deleted while it was open. The image last loaded
+/// from the file stays visible, and can be saved elsewhere before the view is closed.
+pub(crate) fn render_deleted_banner(cx: &mut ViewContext<ImageView>) -> impl IntoElement {
+    h_flex()
+        .w_full()
+        .px_2()
+        .py_1()
+        .gap_2()
+        .justify_between()
+        .border_b_1()
+        .border_color(cx.theme().status().warning_border)
+        .bg(cx.theme().status().warning_background)
+        .child(
+            h_flex()
+                .gap_2()
+                .child(Icon::new(IconName::Warning).color(Color::Warning))
+                .child(Label::new(
+                    "This file was deleted. Showing the image as it was last loaded.",
+                )),
+        )
+        .child(
+            h_flex()
+                .gap_1()
+                .child(
+                    Button::new("deleted-image-save-copy", "Save a Copy")
+                        .style(ButtonStyle::Filled)
+                        .on_click(cx.listener(|_, _, cx| save_copy(cx))),
+                )
+                .child(
+                    Button::new("deleted-image-close", "Close")
+                        .on_click(cx.listener(|_, _, cx| close(cx))),
+                ),
+        )
+}
+
+/// Asks where to save the image, which then shows the saved file instead.
+fn save_copy(cx: &mut ViewContext<ImageView>) {
+    cx.focus_self();
+    cx.dispatch_action(Box::new(SaveAs));
+}
+
+fn close(cx: &mut ViewContext<ImageView>) {
+    let Some(workspace) = window_workspace(cx) else {
+        return;
+    };
+    let this = cx.view().clone();
+    let pane = workspace
+        .read(cx)
+        .panes()
+        .iter()
+        .find(|pane| pane.read(cx).index_for_item(&this).is_some())
+        .cloned();
+    let item_id = this.entity_id();
+    if let Some(pane) = pane {
+        pane.update(cx, |pane, cx| {
+            pane.close_item_by_id(item_id, SaveIntent::Close, cx)
+                .detach_and_log_err(cx)
+        });
+    }
+}
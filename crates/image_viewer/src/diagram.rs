@@ -0,0 +1,347 @@
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    io,
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{anyhow, bail, Context as _, Result};
+use editor::Editor;
+use fs::Fs;
+use gpui::{impl_actions, ViewContext, VisualContext};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use util::command::new_smol_command;
+use workspace::{
+    notifications::{DetachAndPromptErr, NotificationId},
+    Toast, Workspace,
+};
+
+use crate::{open_image_by_abs_path, ImageView, RenderDiagram};
+
+/// The image format diagrams are exported to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagramFormat {
+    #[default]
+    Svg,
+    Png,
+}
+
+impl DiagramFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            DiagramFormat::Svg => "svg",
+            DiagramFormat::Png => "png",
+        }
+    }
+}
+
+/// Renders the diagram under the cursor and writes it next to the file it comes from.
+#[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema)]
+pub struct ExportDiagram {
+    #[serde(default)]
+    pub format: DiagramFormat,
+}
+
+impl_actions!(image_viewer, [ExportDiagram]);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DiagramKind {
+    Mermaid,
+    Graphviz,
+}
+
+impl DiagramKind {
+    /// The kind of diagram written in a code block with the given language tag, or in a file
+    /// with the given extension.
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "mermaid" | "mmd" => Some(DiagramKind::Mermaid),
+            "dot" | "gv" | "graphviz" => Some(DiagramKind::Graphviz),
+            _ => None,
+        }
+    }
+
+    fn program(&self) -> &'static str {
+        match self {
+            DiagramKind::Mermaid => "mmdc",
+            DiagramKind::Graphviz => "dot",
+        }
+    }
+
+    fn source_extension(&self) -> &'static str {
+        match self {
+            DiagramKind::Mermaid => "mmd",
+            DiagramKind::Graphviz => "dot",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct DiagramBlock {
+    kind: DiagramKind,
+    /// The range of the diagram's source, excluding the fences.
+    range: Range<usize>,
+    /// The position of the block among the diagram blocks of the file, starting at 1.
+    index: usize,
+}
+
+struct Diagram {
+    kind: DiagramKind,
+    source: String,
+    /// The path of the file the diagram comes from, when it is on the local file system.
+    source_abs_path: Option<PathBuf>,
+    index: Option<usize>,
+}
+
+impl Diagram {
+    /// A path in the temporary directory that is unique to the diagram's source.
+    fn temp_path(&self, extension: &str) -> PathBuf {
+        let mut hasher = DefaultHasher::new();
+        self.source.hash(&mut hasher);
+        std::env::temp_dir()
+            .join("zed-diagrams")
+            .join(format!("{:x}.{extension}", hasher.finish()))
+    }
+}
+
+pub fn register(workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>) {
+    workspace.register_action(render_diagram);
+    workspace.register_action(export_diagram);
+}
+
+/// Renders the mermaid or graphviz diagram under the cursor and opens it in the image viewer.
+fn render_diagram(workspace: &mut Workspace, _: &RenderDiagram, cx: &mut ViewContext<Workspace>) {
+    let Some(diagram) = active_diagram(workspace, cx) else {
+        return;
+    };
+    let project = workspace.project().clone();
+    let fs = project.read(cx).fs().clone();
+    let output_path = diagram.temp_path(DiagramFormat::Svg.extension());
+
+    cx.spawn(|workspace, mut cx| async move {
+        render(&diagram, DiagramFormat::Svg, &output_path, fs).await?;
+        let image_item = open_image_by_abs_path(&project, output_path, &mut cx).await?;
+        workspace.update(&mut cx, |workspace, cx| {
+            let image_view = cx.new_view(|cx| ImageView::new(image_item, project, cx));
+            workspace.add_item_to_active_pane(Box::new(image_view), None, true, cx);
+        })
+    })
+    .detach_and_prompt_err("Failed to render diagram", cx, |_, _| None);
+}
+
+fn export_diagram(
+    workspace: &mut Workspace,
+    action: &ExportDiagram,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let Some(diagram) = active_diagram(workspace, cx) else {
+        return;
+    };
+    let Some(output_path) = diagram
+        .source_abs_path
+        .as_deref()
+        .and_then(|source_path| export_path(source_path, diagram.index, action.format))
+    else {
+        workspace.show_error(
+            &anyhow!("Diagrams can only be exported from files in local projects"),
+            cx,
+        );
+        return;
+    };
+    let fs = workspace.project().read(cx).fs().clone();
+    let format = action.format;
+
+    cx.spawn(|workspace, mut cx| async move {
+        render(&diagram, format, &output_path, fs).await?;
+        let file_name = output_path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        workspace.update(&mut cx, |workspace, cx| {
+            struct DiagramExported;
+            workspace.show_toast(
+                Toast::new(
+                    NotificationId::unique::<DiagramExported>(),
+                    format!("Exported diagram to {file_name}"),
+                )
+                .autohide(),
+                cx,
+            );
+        })
+    })
+    .detach_and_prompt_err("Failed to export diagram", cx, |_, _| None);
+}
+
+/// The diagram in the active editor: the fenced mermaid or graphviz code block containing the
+/// cursor, or the whole buffer for `.mmd` and `.dot` files.
+fn active_diagram(workspace: &Workspace, cx: &mut ViewContext<Workspace>) -> Option<Diagram> {
+    let editor = workspace.active_item_as::<Editor>(cx)?;
+    let cursor = editor.update(cx, |editor, cx| {
+        editor.selections.newest::<usize>(cx).head()
+    });
+    let buffer = editor.read(cx).buffer().read(cx).as_singleton()?;
+    let buffer = buffer.read(cx);
+    let text = buffer.text();
+    let file = buffer.file();
+    let source_abs_path = file
+        .and_then(|file| file.as_local())
+        .map(|file| file.abs_path(cx));
+
+    if let Some(block) = diagram_block_at(&text, cursor) {
+        return Some(Diagram {
+            kind: block.kind,
+            source: text[block.range].to_string(),
+            source_abs_path,
+            index: Some(block.index),
+        });
+    }
+    let kind = file
+        .and_then(|file| file.path().extension())
+        .and_then(|extension| extension.to_str())
+        .and_then(DiagramKind::from_name)?;
+    Some(Diagram {
+        kind,
+        source: text,
+        source_abs_path,
+        index: None,
+    })
+}
+
+/// Finds the fenced code block of a known diagram language that contains `offset`.
+fn diagram_block_at(text: &str, offset: usize) -> Option<DiagramBlock> {
+    let mut index = 0;
+    let mut open_block: Option<(&str, Option<DiagramKind>, usize, usize)> = None;
+    let mut line_start = 0;
+    for line in text.split_inclusive('\n') {
+        let line_end = line_start + line.len();
+        let trimmed = line.trim();
+        match open_block {
+            None => {
+                let fence_len = trimmed
+                    .find(|c| c != '`' && c != '~')
+                    .unwrap_or(trimmed.len());
+                if fence_len >= 3 && (trimmed.starts_with("```") || trimmed.starts_with("~~~")) {
+                    let language = trimmed[fence_len..].split_whitespace().next();
+                    let kind = language.and_then(DiagramKind::from_name);
+                    if kind.is_some() {
+                        index += 1;
+                    }
+                    open_block = Some((&trimmed[..fence_len], kind, line_start, line_end));
+                }
+            }
+            Some((fence, kind, block_start, source_start)) => {
+                if trimmed.starts_with(fence) && trimmed.trim_start_matches(fence).is_empty() {
+                    open_block = None;
+                    if let Some(kind) = kind {
+                        if (block_start..=line_end).contains(&offset) {
+                            return Some(DiagramBlock {
+                                kind,
+                                range: source_start..line_start,
+                                index,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        line_start = line_end;
+    }
+    None
+}
+
+/// The path a diagram is exported to: next to its source file, numbered after its position
+/// when the file contains several diagrams.
+fn export_path(source_path: &Path, index: Option<usize>, format: DiagramFormat) -> Option<PathBuf> {
+    let file_stem = source_path.file_stem()?.to_string_lossy();
+    let file_name = match index {
+        Some(index) => format!("{file_stem}-diagram-{index}.{}", format.extension()),
+        None => format!("{file_stem}.{}", format.extension()),
+    };
+    Some(source_path.with_file_name(file_name))
+}
+
+/// Renders a diagram to `output_path` with the mermaid CLI or graphviz.
+async fn render(
+    diagram: &Diagram,
+    format: DiagramFormat,
+    output_path: &Path,
+    fs: Arc<dyn Fs>,
+) -> Result<()> {
+    let input_path = diagram.temp_path(diagram.kind.source_extension());
+    if let Some(parent) = input_path.parent() {
+        fs.create_dir(parent).await?;
+    }
+    fs.atomic_write(input_path.clone(), diagram.source.clone())
+        .await?;
+
+    let program = diagram.kind.program();
+    let mut command = new_smol_command(program);
+    match diagram.kind {
+        DiagramKind::Mermaid => {
+            command
+                .arg("--input")
+                .arg(&input_path)
+                .arg("--output")
+                .arg(output_path);
+        }
+        DiagramKind::Graphviz => {
+            command
+                .arg(format!("-T{}", format.extension()))
+                .arg(&input_path)
+                .arg("-o")
+                .arg(output_path);
+        }
+    }
+    let output = match command.output().await {
+        Ok(output) => output,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            bail!("Rendering this diagram requires `{program}` to be installed")
+        }
+        Err(error) => return Err(error).with_context(|| format!("Failed to run {program}")),
+    };
+    if !output.status.success() {
+        bail!(
+            "{program} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diagram_block_at() {
+        let text = "# Flows\n\n```rust\nfn main() {}\n```\n\n```mermaid\ngraph TD\n  A --> B\n```\n\n~~~dot\ndigraph { a -> b }\n~~~\n";
+        assert_eq!(diagram_block_at(text, text.find("fn main").unwrap()), None);
+
+        let offset = text.find("A --> B").unwrap();
+        let block = diagram_block_at(text, offset).unwrap();
+        assert_eq!(block.kind, DiagramKind::Mermaid);
+        assert_eq!(block.index, 1);
+        assert_eq!(&text[block.range], "graph TD\n  A --> B\n");
+
+        let block = diagram_block_at(text, text.find("~~~dot").unwrap()).unwrap();
+        assert_eq!(block.kind, DiagramKind::Graphviz);
+        assert_eq!(block.index, 2);
+        assert_eq!(&text[block.range], "digraph { a -> b }\n");
+    }
+
+    #[test]
+    fn test_export_path() {
+        assert_eq!(
+            export_path(Path::new("/docs/README.md"), Some(2), DiagramFormat::Png),
+            Some(PathBuf::from("/docs/README-diagram-2.png"))
+        );
+        assert_eq!(
+            export_path(Path::new("/docs/flow.mmd"), None, DiagramFormat::Svg),
+            Some(PathBuf::from("/docs/flow.svg"))
+        );
+    }
+}
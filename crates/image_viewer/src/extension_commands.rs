@@ -0,0 +1,229 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context as _};
+use extension::{
+    Extension, ExtensionHostProxy, ExtensionImageViewerCommandProxy, ImageViewerCommand,
+    ImageViewerImage,
+};
+use gpui::{
+    impl_actions, AppContext, EventEmitter, Global, ReadGlobal, View, ViewContext, WeakView,
+};
+use parking_lot::RwLock;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use ui::{prelude::*, Tooltip};
+use workspace::{
+    item::ItemHandle,
+    notifications::{DetachAndPromptErr, NotificationId},
+    Toast, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView,
+};
+
+use crate::{window_workspace, ImageView};
+
+/// Runs a command contributed by an extension on the image.
+#[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema)]
+pub struct RunExtensionCommand {
+    /// The name of the command, as declared in the extension's manifest.
+    pub name: String,
+}
+
+impl_actions!(image_viewer, [RunExtensionCommand]);
+
+#[derive(Clone)]
+struct RegisteredCommand {
+    extension: Arc<dyn Extension>,
+    command: ImageViewerCommand,
+}
+
+#[derive(Default)]
+struct GlobalImageViewerCommandRegistry(Arc<ImageViewerCommandRegistry>);
+
+impl Global for GlobalImageViewerCommandRegistry {}
+
+/// The image viewer commands registered by extensions.
+#[derive(Default)]
+pub struct ImageViewerCommandRegistry {
+    commands: RwLock<Vec<RegisteredCommand>>,
+}
+
+impl ImageViewerCommandRegistry {
+    /// Returns the global [`ImageViewerCommandRegistry`].
+    pub fn global(cx: &AppContext) -> Arc<Self> {
+        GlobalImageViewerCommandRegistry::global(cx).0.clone()
+    }
+
+    /// Returns the global [`ImageViewerCommandRegistry`].
+    ///
+    /// Inserts a default [`ImageViewerCommandRegistry`] if one does not yet exist.
+    pub fn default_global(cx: &mut AppContext) -> Arc<Self> {
+        cx.default_global::<GlobalImageViewerCommandRegistry>()
+            .0
+            .clone()
+    }
+
+    /// Registers a command, replacing the one the same extension registered under that name.
+    fn register_command(&self, extension: Arc<dyn Extension>, command: ImageViewerCommand) {
+        let mut commands = self.commands.write();
+        commands.retain(|registered| {
+            registered.command.name != command.name
+                || registered.extension.manifest().id != extension.manifest().id
+        });
+        commands.push(RegisteredCommand { extension, command });
+    }
+
+    fn command(&self, name: &str) -> Option<RegisteredCommand> {
+        self.commands
+            .read()
+            .iter()
+            .find(|registered| registered.command.name == name)
+            .cloned()
+    }
+
+    /// Returns the registered commands, in registration order.
+    pub fn commands(&self) -> Vec<ImageViewerCommand> {
+        self.commands
+            .read()
+            .iter()
+            .map(|registered| registered.command.clone())
+            .collect()
+    }
+}
+
+struct ImageViewerCommandRegistryProxy {
+    registry: Arc<ImageViewerCommandRegistry>,
+}
+
+impl ExtensionImageViewerCommandProxy for ImageViewerCommandRegistryProxy {
+    fn register_image_viewer_command(
+        &self,
+        extension: Arc<dyn Extension>,
+        command: ImageViewerCommand,
+    ) {
+        self.registry.register_command(extension, command)
+    }
+}
+
+pub(crate) fn init(cx: &mut AppContext) {
+    let proxy = ExtensionHostProxy::default_global(cx);
+    proxy.register_image_viewer_command_proxy(ImageViewerCommandRegistryProxy {
+        registry: ImageViewerCommandRegistry::default_global(cx),
+    });
+}
+
+/// Sends the image's path and bytes to the extension providing the command, then writes back
+/// the image it returns, if any, and shows its message.
+pub(crate) fn run_extension_command(
+    image_view: &mut ImageView,
+    action: &RunExtensionCommand,
+    cx: &mut ViewContext<ImageView>,
+) {
+    let Some(workspace) = window_workspace(cx) else {
+        return;
+    };
+    let Some(registered) = ImageViewerCommandRegistry::global(cx).command(&action.name) else {
+        let error = anyhow!(
+            "No extension provides an image viewer command named {:?}",
+            action.name
+        );
+        workspace.update(cx, |workspace, cx| workspace.show_error(&error, cx));
+        return;
+    };
+    let image_item = image_view.image_item.read(cx);
+    let image = ImageViewerImage {
+        path: image_item.path().to_string_lossy().to_string(),
+        bytes: image_item.image.bytes.clone(),
+    };
+    let abs_path = image_item.file.as_local().map(|file| file.abs_path(cx));
+    let fs = image_view.project.read(cx).fs().clone();
+    let notification_id = NotificationId::composite::<RunExtensionCommand>((
+        "image",
+        image_view.image_item.entity_id(),
+    ));
+
+    cx.spawn(|_, mut cx| async move {
+        let output = registered
+            .extension
+            .run_image_viewer_command(registered.command.clone(), image)
+            .await
+            .with_context(|| format!("Failed to run {}", registered.command.name))?;
+        if let Some(bytes) = output.bytes {
+            let abs_path = abs_path.context("Images can only be replaced in local projects")?;
+            let content = bytes.as_slice();
+            futures::pin_mut!(content);
+            // The image viewer picks up the new contents through its automatic reloading.
+            fs.create_file_with(&abs_path, content).await?;
+        }
+        if let Some(message) = output.message {
+            workspace.update(&mut cx, |workspace, cx| {
+                workspace.show_toast(Toast::new(notification_id, message), cx)
+            })?;
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_prompt_err("Extension command failed", cx, |_, _| None);
+}
+
+/// Shows a button for each extension-provided command in the toolbar of image viewers.
+pub struct ImageViewerToolbar {
+    image_view: Option<WeakView<ImageView>>,
+}
+
+impl ImageViewerToolbar {
+    pub fn new() -> Self {
+        Self { image_view: None }
+    }
+
+    fn image_view(&self) -> Option<View<ImageView>> {
+        self.image_view.as_ref()?.upgrade()
+    }
+}
+
+impl Default for ImageViewerToolbar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Render for ImageViewerToolbar {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let commands = ImageViewerCommandRegistry::global(cx).commands();
+        h_flex()
+            .gap_1()
+            .children(commands.into_iter().map(|command| {
+                let name = command.name.clone();
+                let description = command.description.clone();
+                Button::new(SharedString::from(command.name.clone()), command.name)
+                    .label_size(LabelSize::Small)
+                    .tooltip(move |cx| Tooltip::text(description.clone(), cx))
+                    .on_click(cx.listener(move |this, _, cx| {
+                        if let Some(image_view) = this.image_view() {
+                            let action = RunExtensionCommand { name: name.clone() };
+                            image_view.update(cx, |image_view, cx| {
+                                run_extension_command(image_view, &action, cx)
+                            });
+                        }
+                    }))
+            }))
+    }
+}
+
+impl EventEmitter<ToolbarItemEvent> for ImageViewerToolbar {}
+
+impl ToolbarItemView for ImageViewerToolbar {
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        cx: &mut ViewContext<Self>,
+    ) -> ToolbarItemLocation {
+        self.image_view = active_pane_item
+            .and_then(|item| item.downcast::<ImageView>())
+            .map(|image_view| image_view.downgrade());
+        if self.image_view.is_some()
+            && !ImageViewerCommandRegistry::global(cx).commands().is_empty()
+        {
+            ToolbarItemLocation::PrimaryRight
+        } else {
+            ToolbarItemLocation::Hidden
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use anyhow::{Context as _, Result};
+use gpui::{impl_actions, ViewContext};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use util::command::new_smol_command;
+
+use crate::{window_workspace, ImageView};
+
+/// Opens the image in one of the applications configured in `image_viewer.external_editors`.
+#[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema)]
+pub struct OpenInExternalEditor {
+    /// The name of the editor to use. Defaults to the first configured one.
+    #[serde(default)]
+    pub editor: Option<String>,
+}
+
+impl_actions!(image_viewer, [OpenInExternalEditor]);
+
+pub(crate) fn open_in_external_editor(
+    image_view: &mut ImageView,
+    action: &OpenInExternalEditor,
+    cx: &mut ViewContext<ImageView>,
+) {
+    if let Err(error) = launch_external_editor(image_view, action, cx) {
+        if let Some(workspace) = window_workspace(cx) {
+            workspace.update(cx, |workspace, cx| workspace.show_error(&error, cx));
+        }
+    }
+}
+
+fn launch_external_editor(
+    image_view: &ImageView,
+    action: &OpenInExternalEditor,
+    cx: &mut ViewContext<ImageView>,
+) -> Result<()> {
+    let abs_path = image_view
+        .image_item
+        .read(cx)
+        .file
+        .as_local()
+        .map(|file| file.abs_path(cx))
+        .context("Images can only be opened externally in local projects")?;
+    let editors = &image_view.settings(cx).external_editors;
+    let editor = match &action.editor {
+        Some(name) => Some(
+            editors
+                .iter()
+                .find(|editor| &editor.name == name)
+                .with_context(|| format!("No external editor named {name:?} is configured"))?,
+        ),
+        None => editors.first(),
+    }
+    .cloned();
+
+    match editor {
+        Some(editor) => {
+            new_smol_command(&editor.command)
+                .args(&editor.args)
+                .arg(&abs_path)
+                .spawn()
+                .with_context(|| format!("Failed to launch {}", editor.name))?;
+        }
+        None => cx.open_with_system(&abs_path),
+    }
+    // Saves written back by the editor are picked up by the image's automatic reloading.
+    Ok(())
+}
@@ -0,0 +1,250 @@
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+use gpui::{
+    img, AppContext, EventEmitter, Image, Model, ObjectFit, Render, ScrollHandle, Subscription,
+    Task, ViewContext,
+};
+use project::{image_store::is_image_path, Project, ProjectPath, ThumbnailPriority, WorktreeId};
+use ui::{prelude::*, Tooltip};
+use util::ResultExt;
+
+const FILMSTRIP_THUMBNAIL_SIZE: Pixels = px(64.);
+/// The size thumbnails are loaded at, doubled so that they stay sharp on high density displays.
+const FILMSTRIP_THUMBNAIL_PIXELS: u32 = 128;
+
+/// How many neighbors on each side of the current image get their thumbnails loaded.
+const FILMSTRIP_LOAD_RADIUS: usize = 8;
+
+pub(crate) enum FilmstripEvent {
+    Activate(ProjectPath),
+}
+
+/// A strip of thumbnails for the images that sit next to the current one in its directory.
+pub(crate) struct Filmstrip {
+    project: Model<Project>,
+    worktree_id: WorktreeId,
+    current: Arc<Path>,
+    siblings: Vec<Arc<Path>>,
+    /// Loaded images by path, `None` when the image failed to load.
+    thumbnails: HashMap<Arc<Path>, Option<Arc<Image>>>,
+    scroll_handle: ScrollHandle,
+    load_thumbnails_task: Option<Task<Option<()>>>,
+    _subscription: Subscription,
+}
+
+impl Filmstrip {
+    pub(crate) fn new(
+        project: Model<Project>,
+        current: ProjectPath,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let subscription = cx.subscribe(&project, |this, _, event, cx| {
+            if let project::Event::WorktreeUpdatedEntries(worktree_id, _) = event {
+                if *worktree_id == this.worktree_id {
+                    this.refresh_siblings(cx);
+                }
+            }
+        });
+        let mut this = Self {
+            project,
+            worktree_id: current.worktree_id,
+            current: current.path,
+            siblings: Vec::new(),
+            thumbnails: HashMap::default(),
+            scroll_handle: ScrollHandle::new(),
+            load_thumbnails_task: None,
+            _subscription: subscription,
+        };
+        this.refresh_siblings(cx);
+        this
+    }
+
+    pub(crate) fn set_current(&mut self, current: ProjectPath, cx: &mut ViewContext<Self>) {
+        let directory_changed = current.worktree_id != self.worktree_id
+            || current.path.parent() != self.current.parent();
+        self.worktree_id = current.worktree_id;
+        self.current = current.path;
+        if directory_changed {
+            self.thumbnails.clear();
+            self.refresh_siblings(cx);
+        } else {
+            self.scroll_to_current();
+            self.load_thumbnails(cx);
+            cx.notify();
+        }
+    }
+
+    fn current_index(&self) -> Option<usize> {
+        self.siblings.iter().position(|path| *path == self.current)
+    }
+
+    fn scroll_to_current(&self) {
+        if let Some(ix) = self.current_index() {
+            self.scroll_handle.scroll_to_item(ix);
+        }
+    }
+
+    fn refresh_siblings(&mut self, cx: &mut ViewContext<Self>) {
+        let siblings = sibling_images(
+            self.project.read(cx),
+            &ProjectPath {
+                worktree_id: self.worktree_id,
+                path: self.current.clone(),
+            },
+            cx,
+        );
+        self.thumbnails
+            .retain(|path, _| siblings.iter().any(|sibling| sibling == path));
+        self.siblings = siblings;
+        self.scroll_to_current();
+        self.load_thumbnails(cx);
+        cx.notify();
+    }
+
+    /// Loads the thumbnails around the current image, closest neighbors first.
+    fn load_thumbnails(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(current_ix) = self.current_index() else {
+            return;
+        };
+        let start = current_ix.saturating_sub(FILMSTRIP_LOAD_RADIUS);
+        let end = (current_ix + FILMSTRIP_LOAD_RADIUS + 1).min(self.siblings.len());
+        let mut pending = (start..end)
+            .filter(|ix| !self.thumbnails.contains_key(&self.siblings[*ix]))
+            .collect::<Vec<_>>();
+        if pending.is_empty() {
+            return;
+        }
+        pending.sort_by_key(|ix| ix.abs_diff(current_ix));
+        let pending = pending
+            .into_iter()
+            .map(|ix| self.siblings[ix].clone())
+            .collect::<Vec<_>>();
+
+        let project = self.project.clone();
+        let worktree_id = self.worktree_id;
+        self.load_thumbnails_task = Some(cx.spawn(|this, mut cx| {
+            async move {
+                for path in pending {
+                    let project_path = ProjectPath {
+                        worktree_id,
+                        path: path.clone(),
+                    };
+                    let image = project
+                        .update(&mut cx, |project, cx| {
+                            project.load_thumbnail(
+                                project_path,
+                                FILMSTRIP_THUMBNAIL_PIXELS,
+                                ThumbnailPriority::Visible,
+                                cx,
+                            )
+                        })?
+                        .await
+                        .log_err()
+                        .map(|thumbnail| thumbnail.image.clone());
+                    this.update(&mut cx, |this, cx| {
+                        this.thumbnails.insert(path, image);
+                        cx.notify();
+                    })?;
+                }
+                anyhow::Ok(())
+            }
+            .log_err()
+        }));
+    }
+
+    fn render_thumbnail(
+        &self,
+        ix: usize,
+        path: &Arc<Path>,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let is_current = *path == self.current;
+        let preview = match self.thumbnails.get(path) {
+            Some(Some(image)) => img(image.clone())
+                .max_w(FILMSTRIP_THUMBNAIL_SIZE)
+                .max_h(FILMSTRIP_THUMBNAIL_SIZE)
+                .object_fit(ObjectFit::ScaleDown)
+                .into_any_element(),
+            Some(None) => Icon::new(IconName::Warning)
+                .color(Color::Warning)
+                .into_any_element(),
+            None => div().into_any_element(),
+        };
+        let file_name = path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let project_path = ProjectPath {
+            worktree_id: self.worktree_id,
+            path: path.clone(),
+        };
+
+        div()
+            .id(ix)
+            .flex_none()
+            .size(FILMSTRIP_THUMBNAIL_SIZE)
+            .flex()
+            .items_center()
+            .justify_center()
+            .rounded_sm()
+            .border_2()
+            .border_color(if is_current {
+                cx.theme().colors().border_focused
+            } else {
+                gpui::transparent_black()
+            })
+            .cursor_pointer()
+            .hover(|style| style.bg(cx.theme().colors().element_hover))
+            .tooltip(move |cx| Tooltip::text(file_name.clone(), cx))
+            .on_click(
+                cx.listener(move |_, _, cx| {
+                    cx.emit(FilmstripEvent::Activate(project_path.clone()))
+                }),
+            )
+            .child(preview)
+    }
+}
+
+impl EventEmitter<FilmstripEvent> for Filmstrip {}
+
+/// The images in the same directory as the given path, including it, sorted by path.
+pub(crate) fn sibling_images(
+    project: &Project,
+    project_path: &ProjectPath,
+    cx: &AppContext,
+) -> Vec<Arc<Path>> {
+    let Some(worktree) = project.worktree_for_id(project_path.worktree_id, cx) else {
+        return Vec::new();
+    };
+    let directory = project_path.path.parent().unwrap_or(Path::new(""));
+    let snapshot = worktree.read(cx).snapshot();
+    let mut siblings = snapshot
+        .child_entries(directory)
+        .filter(|entry| entry.is_file() && is_image_path(&entry.path))
+        .map(|entry| entry.path.clone())
+        .collect::<Vec<_>>();
+    siblings.sort();
+    siblings
+}
+
+impl Render for Filmstrip {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        h_flex()
+            .id("filmstrip")
+            .w_full()
+            .flex_none()
+            .p_1()
+            .gap_1()
+            .overflow_x_scroll()
+            .track_scroll(&self.scroll_handle)
+            .border_t_1()
+            .border_color(cx.theme().colors().border)
+            .bg(cx.theme().colors().editor_background)
+            .children(
+                self.siblings
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, path)| self.render_thumbnail(ix, path, cx)),
+            )
+    }
+}
@@ -0,0 +1,550 @@
+//! A specimen of the font files of the project, showing the font's characters at several sizes,
+//! some text of the user's choosing, and the OpenType features that can be turned on for it.
+
+use std::{
+    borrow::Cow,
+    collections::{hash_map::DefaultHasher, BTreeSet, HashSet},
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+use anyhow::bail;
+use editor::{Editor, EditorEvent};
+use gpui::{
+    font, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, FontFeatures,
+    FontStyle, FontWeight, Global, Model, Subscription, Task, View, WeakView,
+};
+use project::Project;
+use settings::Settings;
+use theme::Theme;
+use ui::{prelude::*, Tooltip};
+use workspace::{
+    item::{BreadcrumbText, Item, ProjectItem, SerializableItem, TabContentParams},
+    ItemId, ToolbarItemLocation, Workspace, WorkspaceId,
+};
+
+use crate::{
+    asset_viewer::{self, AssetItem, AssetKind, AssetView},
+    ImageViewerSettings,
+};
+
+/// The sizes the sample text is shown at, in pixels.
+const SAMPLE_SIZES: [f32; 7] = [12., 16., 20., 24., 36., 48., 72.];
+
+/// The text shown at each size when the font doesn't suggest one.
+const DEFAULT_SAMPLE_TEXT: &str = "The quick brown fox jumps over the lazy dog";
+
+/// The characters shown at the top of the specimen, a group per line.
+const CHARACTER_GROUPS: [&str; 4] = [
+    "ABCDEFGHIJKLMNOPQRSTUVWXYZ",
+    "abcdefghijklmnopqrstuvwxyz",
+    "0123456789",
+    "!?.,:;'\"@#$%&*()[]{}<>/\\|-+=_~",
+];
+
+pub struct FontFiles;
+
+impl AssetKind for FontFiles {
+    const EXTENSIONS: &'static [&'static str] = &["ttf", "otf", "ttc", "otc", "woff", "woff2"];
+}
+
+/// What is read from the font file to lay out the specimen.
+#[derive(Debug, PartialEq)]
+struct FontInfo {
+    family: SharedString,
+    /// The name of the font's style within its family, like "Bold Italic".
+    style: Option<String>,
+    weight: u16,
+    italic: bool,
+    glyph_count: u16,
+    version: Option<String>,
+    designer: Option<String>,
+    /// The text the font's designers suggest showing it with.
+    sample_text: Option<String>,
+    /// The tags of the OpenType features of the font, sorted.
+    features: Vec<String>,
+    axes: Vec<VariationAxis>,
+}
+
+/// An axis along which a variable font varies, like its weight.
+#[derive(Debug, PartialEq)]
+struct VariationAxis {
+    tag: String,
+    min: f32,
+    default: f32,
+    max: f32,
+}
+
+/// Reads the first font of a font file.
+fn parse_font(content: &[u8]) -> anyhow::Result<FontInfo> {
+    if content.starts_with(b"wOFF") || content.starts_with(b"wOF2") {
+        bail!("WOFF fonts are compressed, and can't be previewed yet");
+    }
+    let face = ttf_parser::Face::parse(content, 0)?;
+    let name = |name_id: u16| {
+        face.names()
+            .into_iter()
+            .filter(|name| name.name_id == name_id)
+            .find_map(|name| name.to_string())
+    };
+    let Some(family) =
+        name(ttf_parser::name_id::TYPOGRAPHIC_FAMILY).or_else(|| name(ttf_parser::name_id::FAMILY))
+    else {
+        bail!("The font has no family name.");
+    };
+
+    let tables = face.tables();
+    let features = [tables.gsub, tables.gpos]
+        .into_iter()
+        .flatten()
+        .flat_map(|table| {
+            table
+                .features
+                .into_iter()
+                .map(|feature| feature.tag.to_string())
+        })
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+
+    Ok(FontInfo {
+        family: family.into(),
+        style: name(ttf_parser::name_id::TYPOGRAPHIC_SUBFAMILY)
+            .or_else(|| name(ttf_parser::name_id::SUBFAMILY)),
+        weight: face.weight().to_number(),
+        italic: face.is_italic(),
+        glyph_count: face.number_of_glyphs(),
+        version: name(ttf_parser::name_id::VERSION),
+        designer: name(ttf_parser::name_id::DESIGNER),
+        sample_text: name(ttf_parser::name_id::SAMPLE_TEXT),
+        features,
+        axes: face
+            .variation_axes()
+            .into_iter()
+            .filter(|axis| !axis.hidden)
+            .map(|axis| VariationAxis {
+                tag: axis.tag.to_string(),
+                min: axis.min_value,
+                default: axis.def_value,
+                max: axis.max_value,
+            })
+            .collect(),
+    })
+}
+
+/// The fonts added to the text system, by a hash of their contents, since fonts can't be
+/// removed from it once added.
+#[derive(Default)]
+struct AddedFonts(HashSet<u64>);
+
+impl Global for AddedFonts {}
+
+/// Adds the font to the text system, so that text can be drawn with it.
+fn add_font(content: &Arc<[u8]>, cx: &mut AppContext) -> anyhow::Result<()> {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    let hash = hasher.finish();
+    if cx.default_global::<AddedFonts>().0.insert(hash) {
+        if let Err(error) = cx
+            .text_system()
+            .add_fonts(vec![Cow::Owned(content.to_vec())])
+        {
+            cx.global_mut::<AddedFonts>().0.remove(&hash);
+            return Err(error);
+        }
+    }
+    Ok(())
+}
+
+pub enum FontSpecimenEvent {
+    SampleTextChanged,
+}
+
+pub struct FontSpecimenView {
+    asset: Model<AssetItem<FontFiles>>,
+    project: Model<Project>,
+    font: Result<FontInfo, SharedString>,
+    sample_editor: View<Editor>,
+    /// The features turned on for the sample text, by tag.
+    enabled_features: BTreeSet<String>,
+    focus_handle: FocusHandle,
+    _sample_subscription: Subscription,
+}
+
+impl FontSpecimenView {
+    fn sample_text(&self, cx: &AppContext) -> String {
+        let text = self.sample_editor.read(cx).text(cx);
+        if !text.trim().is_empty() {
+            return text;
+        }
+        self.font
+            .as_ref()
+            .ok()
+            .and_then(|font| font.sample_text.clone())
+            .unwrap_or_else(|| DEFAULT_SAMPLE_TEXT.to_string())
+    }
+
+    fn toggle_feature(&mut self, tag: String, cx: &mut ViewContext<Self>) {
+        if !self.enabled_features.remove(&tag) {
+            self.enabled_features.insert(tag);
+        }
+        cx.notify();
+    }
+
+    /// The font as gpui draws it, with the enabled features turned on.
+    fn gpui_font(&self, info: &FontInfo) -> gpui::Font {
+        let mut font = font(info.family.clone());
+        font.weight = FontWeight(info.weight as f32);
+        font.style = if info.italic {
+            FontStyle::Italic
+        } else {
+            FontStyle::Normal
+        };
+        font.features = FontFeatures(Arc::new(
+            self.enabled_features
+                .iter()
+                .map(|tag| (tag.clone(), 1))
+                .collect(),
+        ));
+        font
+    }
+
+    fn render_section(title: &'static str, content: impl IntoElement) -> impl IntoElement {
+        v_flex()
+            .gap_2()
+            .child(Label::new(title).size(LabelSize::Small).color(Color::Muted))
+            .child(content)
+    }
+
+    fn render_specimen(&self, info: &FontInfo, cx: &mut ViewContext<Self>) -> AnyElement {
+        let units = ImageViewerSettings::get_global(cx).units();
+        let font = self.gpui_font(info);
+        let sample_text = SharedString::from(self.sample_text(cx));
+
+        let mut details = Vec::new();
+        if let Some(style) = &info.style {
+            details.push(style.clone());
+        }
+        details.push(format!("{} glyphs", units.count(info.glyph_count as usize)));
+        if let Some(version) = &info.version {
+            details.push(version.clone());
+        }
+        if let Some(designer) = &info.designer {
+            details.push(designer.clone());
+        }
+
+        let header = v_flex()
+            .gap_1()
+            .child(
+                div()
+                    .font(font.clone())
+                    .text_size(px(40.))
+                    .child(info.family.clone()),
+            )
+            .child(
+                Label::new(details.join(" · "))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            );
+
+        let characters = v_flex()
+            .font(font.clone())
+            .text_size(px(32.))
+            .children(CHARACTER_GROUPS.iter().map(|group| div().child(*group)));
+
+        let sample = v_flex()
+            .gap_2()
+            .child(
+                div()
+                    .px_2()
+                    .py_1()
+                    .rounded_md()
+                    .border_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(self.sample_editor.clone()),
+            )
+            .children(SAMPLE_SIZES.iter().map(|size| {
+                h_flex()
+                    .gap_4()
+                    .items_baseline()
+                    .child(
+                        div().w_8().flex_none().child(
+                            Label::new(format!("{size}"))
+                                .size(LabelSize::XSmall)
+                                .color(Color::Muted),
+                        ),
+                    )
+                    .child(
+                        div()
+                            .flex_1()
+                            .overflow_hidden()
+                            .whitespace_nowrap()
+                            .font(font.clone())
+                            .text_size(px(*size))
+                            .child(sample_text.clone()),
+                    )
+            }));
+
+        let features = if info.features.is_empty() {
+            Label::new("The font has no OpenType features.")
+                .size(LabelSize::Small)
+                .color(Color::Muted)
+                .into_any_element()
+        } else {
+            h_flex()
+                .flex_wrap()
+                .gap_1()
+                .children(info.features.iter().map(|tag| {
+                    let enabled = self.enabled_features.contains(tag);
+                    let tag = tag.clone();
+                    Button::new(
+                        SharedString::from(format!("font-feature-{tag}")),
+                        tag.clone(),
+                    )
+                    .label_size(LabelSize::Small)
+                    .selected(enabled)
+                    .tooltip(move |cx| {
+                        let action = if enabled { "Turn off" } else { "Turn on" };
+                        Tooltip::text(format!("{action} in the sample text"), cx)
+                    })
+                    .on_click(cx.listener(move |this, _, cx| this.toggle_feature(tag.clone(), cx)))
+                }))
+                .into_any_element()
+        };
+
+        v_flex()
+            .gap_6()
+            .child(header)
+            .child(Self::render_section("Characters", characters))
+            .child(Self::render_section("Sample", sample))
+            .child(Self::render_section("Features", features))
+            .when(!info.axes.is_empty(), |this| {
+                this.child(Self::render_section(
+                    "Variation Axes",
+                    v_flex().children(info.axes.iter().map(|axis| {
+                        Label::new(format!(
+                            "{}  {}–{} (default {})",
+                            axis.tag, axis.min, axis.max, axis.default
+                        ))
+                        .size(LabelSize::Small)
+                    })),
+                ))
+            })
+            .into_any_element()
+    }
+}
+
+impl AssetView for FontSpecimenView {
+    type Kind = FontFiles;
+
+    const KIND: &'static str = "FontSpecimen";
+
+    fn new(
+        asset: Model<AssetItem<FontFiles>>,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let content = asset.read(cx).content().clone();
+        let font = parse_font(&content)
+            .and_then(|font| {
+                add_font(&content, cx)?;
+                Ok(font)
+            })
+            .map_err(|error| SharedString::from(error.to_string()));
+        let sample_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Type sample text…", cx);
+            editor
+        });
+        let sample_subscription = cx.subscribe(&sample_editor, |_, _, event, cx| {
+            if let EditorEvent::Edited { .. } = event {
+                cx.emit(FontSpecimenEvent::SampleTextChanged);
+                cx.notify();
+            }
+        });
+        Self {
+            asset,
+            project,
+            font,
+            sample_editor,
+            enabled_features: BTreeSet::new(),
+            focus_handle: cx.focus_handle(),
+            _sample_subscription: sample_subscription,
+        }
+    }
+
+    fn asset(&self) -> &Model<AssetItem<FontFiles>> {
+        &self.asset
+    }
+
+    fn project(&self) -> &Model<Project> {
+        &self.project
+    }
+
+    fn serialize_state(&self, cx: &AppContext) -> Option<String> {
+        Some(self.sample_editor.read(cx).text(cx))
+    }
+
+    fn restore_state(&mut self, state: &str, cx: &mut ViewContext<Self>) {
+        self.sample_editor
+            .update(cx, |editor, cx| editor.set_text(state, cx));
+    }
+}
+
+impl EventEmitter<FontSpecimenEvent> for FontSpecimenView {}
+
+impl FocusableView for FontSpecimenView {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for FontSpecimenView {
+    type Event = FontSpecimenEvent;
+
+    fn to_item_events(_: &Self::Event, _: impl FnMut(workspace::item::ItemEvent)) {}
+
+    fn for_each_project_item(
+        &self,
+        cx: &AppContext,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::Item),
+    ) {
+        f(self.asset.entity_id(), self.asset.read(cx))
+    }
+
+    fn is_singleton(&self, _cx: &AppContext) -> bool {
+        true
+    }
+
+    fn tab_tooltip_text(&self, cx: &AppContext) -> Option<SharedString> {
+        let project_path = self.asset.read(cx).project_path(cx);
+        asset_viewer::tab_tooltip_text(self.project.read(cx), &project_path, cx)
+    }
+
+    fn tab_content(&self, params: TabContentParams, cx: &WindowContext) -> AnyElement {
+        asset_viewer::tab_content(self.asset.read(cx).path(), params)
+    }
+
+    fn tab_icon(&self, cx: &WindowContext) -> Option<Icon> {
+        asset_viewer::tab_icon(self.asset.read(cx).path(), cx)
+    }
+
+    fn breadcrumb_location(&self, _: &AppContext) -> ToolbarItemLocation {
+        ToolbarItemLocation::PrimaryLeft
+    }
+
+    fn breadcrumbs(&self, _theme: &Theme, cx: &AppContext) -> Option<Vec<BreadcrumbText>> {
+        let project_path = self.asset.read(cx).project_path(cx);
+        asset_viewer::breadcrumbs(self.project.read(cx), &project_path, cx)
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: Option<WorkspaceId>,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<View<Self>> {
+        let sample_text = self.sample_editor.read(cx).text(cx);
+        Some(cx.new_view(|cx| {
+            let mut view = <Self as AssetView>::new(self.asset.clone(), self.project.clone(), cx);
+            view.restore_state(&sample_text, cx);
+            view.enabled_features = self.enabled_features.clone();
+            view
+        }))
+    }
+}
+
+impl ProjectItem for FontSpecimenView {
+    type Item = AssetItem<FontFiles>;
+
+    fn for_project_item(
+        project: Model<Project>,
+        item: Model<Self::Item>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        <Self as AssetView>::new(item, project, cx)
+    }
+}
+
+impl SerializableItem for FontSpecimenView {
+    fn serialized_item_kind() -> &'static str {
+        <Self as AssetView>::KIND
+    }
+
+    fn deserialize(
+        project: Model<Project>,
+        _workspace: WeakView<Workspace>,
+        workspace_id: WorkspaceId,
+        item_id: ItemId,
+        cx: &mut WindowContext,
+    ) -> Task<gpui::Result<View<Self>>> {
+        asset_viewer::deserialize(project, workspace_id, item_id, cx)
+    }
+
+    fn cleanup(
+        workspace_id: WorkspaceId,
+        alive_items: Vec<ItemId>,
+        cx: &mut WindowContext,
+    ) -> Task<gpui::Result<()>> {
+        asset_viewer::cleanup::<Self>(workspace_id, alive_items, cx)
+    }
+
+    fn serialize(
+        &mut self,
+        workspace: &mut Workspace,
+        item_id: ItemId,
+        _closing: bool,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<Task<gpui::Result<()>>> {
+        asset_viewer::serialize(self, workspace, item_id, cx)
+    }
+
+    fn should_serialize(&self, event: &Self::Event) -> bool {
+        matches!(event, FontSpecimenEvent::SampleTextChanged)
+    }
+}
+
+impl Render for FontSpecimenView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let content = match &self.font {
+            Ok(info) => self.render_specimen(info, cx),
+            Err(error) => v_flex()
+                .size_full()
+                .items_center()
+                .justify_center()
+                .child(Label::new(error.clone()).color(Color::Muted))
+                .into_any_element(),
+        };
+        div()
+            .id("font-specimen")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .p_6()
+            .overflow_y_scroll()
+            .bg(cx.theme().colors().editor_background)
+            .child(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_font() {
+        let font = parse_font(include_bytes!(
+            "../../../assets/fonts/plex-sans/ZedPlexSans-Regular.ttf"
+        ))
+        .unwrap();
+        assert_eq!(font.family, "Zed Plex Sans");
+        assert_eq!(font.style.as_deref(), Some("Regular"));
+        assert!(!font.italic);
+        assert_eq!(font.glyph_count, 1019);
+        assert!(font.features.iter().any(|tag| tag == "liga"));
+        assert!(font.features.iter().any(|tag| tag == "ss01"));
+        assert!(font.axes.is_empty());
+
+        let error = parse_font(b"wOF2\0\x01\0\0").unwrap_err();
+        assert!(error.to_string().contains("WOFF"));
+        assert!(parse_font(b"not a font").is_err());
+    }
+}
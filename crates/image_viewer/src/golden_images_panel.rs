@@ -0,0 +1,699 @@
+//! A dock panel for reviewing the results of screenshot tests. It pairs the expected images of
+//! the tests with the images they produced, as configured by `golden_images_panel.patterns`,
+//! and lists the pairs that differ, each of which opens in an [`ImageCompareView`].
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::Context as _;
+use db::kvp::KEY_VALUE_STORE;
+use fs::Fs;
+use gpui::{
+    actions, uniform_list, Action, AppContext, AsyncWindowContext, EventEmitter, FocusHandle,
+    FocusableView, Model, Pixels, Render, Subscription, Task, UniformListScrollHandle, View,
+    ViewContext, WeakView,
+};
+use project::{image_store::is_image_path, Project};
+use serde::{Deserialize, Serialize};
+use settings::Settings;
+use ui::{prelude::*, ListItem, Tooltip};
+use util::ResultExt;
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    Workspace,
+};
+
+use crate::{
+    golden_images_panel_settings::{
+        GoldenImagePattern, GoldenImagesPanelDockPosition, GoldenImagesPanelSettings,
+    },
+    image_compare::{CompareSide, ImageCompareView},
+    open_image_by_abs_path,
+};
+
+actions!(golden_images_panel, [ToggleFocus, RefreshGoldenImages]);
+
+const GOLDEN_IMAGES_PANEL_KEY: &str = "GoldenImagesPanel";
+/// Test runs write many images at once, so the panel waits for them to settle before comparing.
+const REFRESH_DEBOUNCE: Duration = Duration::from_secs(1);
+
+/// A part of a path pattern.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum PatternToken {
+    Literal(String),
+    /// `*`, which matches any part of a file or folder name.
+    Name,
+    /// `**/`, which matches any number of folders.
+    Folders,
+}
+
+fn parse_pattern(pattern: &str) -> Vec<PatternToken> {
+    let mut tokens = Vec::new();
+    let mut rest = pattern;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("**/") {
+            tokens.push(PatternToken::Folders);
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix('*') {
+            tokens.push(PatternToken::Name);
+            rest = after.trim_start_matches('*');
+        } else {
+            let literal_len = rest.find('*').unwrap_or(rest.len());
+            tokens.push(PatternToken::Literal(rest[..literal_len].to_string()));
+            rest = &rest[literal_len..];
+        }
+    }
+    tokens
+}
+
+/// Matches `path` against `tokens`, pushing what each wildcard matched onto `captures`.
+fn match_pattern(tokens: &[PatternToken], path: &str, captures: &mut Vec<String>) -> bool {
+    let Some((token, rest)) = tokens.split_first() else {
+        return path.is_empty();
+    };
+    let candidates: Vec<usize> = match token {
+        PatternToken::Literal(literal) => {
+            return path
+                .strip_prefix(literal.as_str())
+                .is_some_and(|path| match_pattern(rest, path, captures));
+        }
+        PatternToken::Name => {
+            let name_len = path.find('/').unwrap_or(path.len());
+            (0..=name_len)
+                .filter(|len| path.is_char_boundary(*len))
+                .collect()
+        }
+        PatternToken::Folders => std::iter::once(0)
+            .chain(path.match_indices('/').map(|(ix, _)| ix + 1))
+            .collect(),
+    };
+    for len in candidates {
+        captures.push(path[..len].to_string());
+        if match_pattern(rest, &path[len..], captures) {
+            return true;
+        }
+        captures.pop();
+    }
+    false
+}
+
+/// The path of the image produced by a test whose expected image is at `expected_path`,
+/// relative to the root of their worktree and separated by `/`.
+fn actual_path(pattern: &GoldenImagePattern, expected_path: &str) -> Option<String> {
+    let expected = parse_pattern(&pattern.expected);
+    let actual = parse_pattern(&pattern.actual);
+    let wildcards = |tokens: &[PatternToken]| {
+        tokens
+            .iter()
+            .filter(|token| !matches!(token, PatternToken::Literal(_)))
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+    if wildcards(&expected) != wildcards(&actual) {
+        return None;
+    }
+    let mut captures = Vec::new();
+    if !match_pattern(&expected, expected_path, &mut captures) {
+        return None;
+    }
+    let mut captures = captures.into_iter();
+    Some(
+        actual
+            .into_iter()
+            .map(|token| match token {
+                PatternToken::Literal(literal) => literal,
+                PatternToken::Name | PatternToken::Folders => captures.next().unwrap_or_default(),
+            })
+            .collect(),
+    )
+}
+
+/// How the image produced by a test compares to the expected one.
+#[derive(Clone, Debug, PartialEq)]
+enum PairStatus {
+    Identical,
+    Different {
+        differing_pixels: u64,
+        total_pixels: u64,
+    },
+    DimensionsDiffer,
+    MissingActual,
+    Failed(SharedString),
+}
+
+impl PairStatus {
+    fn label(&self) -> SharedString {
+        match self {
+            PairStatus::Identical => "Identical".into(),
+            PairStatus::Different {
+                differing_pixels,
+                total_pixels,
+            } => format!(
+                "{:.2}% of pixels",
+                *differing_pixels as f64 * 100. / (*total_pixels).max(1) as f64
+            )
+            .into(),
+            PairStatus::DimensionsDiffer => "Size differs".into(),
+            PairStatus::MissingActual => "Not produced".into(),
+            PairStatus::Failed(_) => "Unreadable".into(),
+        }
+    }
+}
+
+/// Compares two encoded images pixel by pixel, so that images encoded differently but showing
+/// the same pixels are identical.
+fn compare_images(expected: &[u8], actual: &[u8]) -> anyhow::Result<PairStatus> {
+    if expected == actual {
+        return Ok(PairStatus::Identical);
+    }
+    let expected = image::load_from_memory(expected)?.into_rgba8();
+    let actual = image::load_from_memory(actual)?.into_rgba8();
+    if expected.dimensions() != actual.dimensions() {
+        return Ok(PairStatus::DimensionsDiffer);
+    }
+    let differing_pixels = expected
+        .pixels()
+        .zip(actual.pixels())
+        .filter(|(expected, actual)| expected != actual)
+        .count() as u64;
+    if differing_pixels == 0 {
+        return Ok(PairStatus::Identical);
+    }
+    Ok(PairStatus::Different {
+        differing_pixels,
+        total_pixels: expected.width() as u64 * expected.height() as u64,
+    })
+}
+
+#[derive(Clone, Debug)]
+struct GoldenImagePair {
+    /// The path of the expected image relative to its worktree, as shown in the list.
+    expected_path: String,
+    expected_abs_path: PathBuf,
+    actual_abs_path: PathBuf,
+    status: PairStatus,
+}
+
+impl GoldenImagePair {
+    fn differs(&self) -> bool {
+        self.status != PairStatus::Identical
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedGoldenImagesPanel {
+    width: Option<Pixels>,
+}
+
+/// A dock panel listing the screenshot tests whose produced image differs from the expected one.
+pub struct GoldenImagesPanel {
+    project: Model<Project>,
+    workspace: WeakView<Workspace>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    width: Option<Pixels>,
+    pairs: Vec<GoldenImagePair>,
+    /// Indices into `pairs` of the pairs that differ, in list order.
+    mismatches: Vec<usize>,
+    comparing: bool,
+    refresh_task: Task<()>,
+    scroll_handle: UniformListScrollHandle,
+    pending_serialization: Task<Option<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl GoldenImagesPanel {
+    pub async fn load(
+        workspace: WeakView<Workspace>,
+        mut cx: AsyncWindowContext,
+    ) -> anyhow::Result<View<Self>> {
+        let serialized_panel = cx
+            .background_executor()
+            .spawn(async move { KEY_VALUE_STORE.read_kvp(GOLDEN_IMAGES_PANEL_KEY) })
+            .await
+            .context("loading golden images panel")
+            .log_err()
+            .flatten()
+            .map(|panel| serde_json::from_str::<SerializedGoldenImagesPanel>(&panel))
+            .transpose()
+            .log_err()
+            .flatten();
+
+        workspace.update(&mut cx, |workspace, cx| {
+            let panel = cx.new_view(|cx| Self::new(workspace, cx));
+            if let Some(serialized_panel) = serialized_panel {
+                panel.update(cx, |panel, cx| {
+                    panel.width = serialized_panel.width.map(|width| width.round());
+                    cx.notify();
+                });
+            }
+            panel
+        })
+    }
+
+    fn new(workspace: &Workspace, cx: &mut ViewContext<Self>) -> Self {
+        let project = workspace.project().clone();
+        let mut patterns = GoldenImagesPanelSettings::get_global(cx).patterns.clone();
+        let subscriptions = vec![
+            cx.subscribe(&project, |this, _, event, cx| match event {
+                project::Event::WorktreeUpdatedEntries(..)
+                | project::Event::WorktreeAdded(_)
+                | project::Event::WorktreeRemoved(_) => this.schedule_refresh(cx),
+                _ => {}
+            }),
+            cx.observe_global::<settings::SettingsStore>(move |this, cx| {
+                let new_patterns = &GoldenImagesPanelSettings::get_global(cx).patterns;
+                if &patterns != new_patterns {
+                    patterns = new_patterns.clone();
+                    this.refresh(&RefreshGoldenImages, cx);
+                }
+                cx.notify();
+            }),
+        ];
+
+        let mut this = Self {
+            project,
+            workspace: workspace.weak_handle(),
+            fs: workspace.app_state().fs.clone(),
+            focus_handle: cx.focus_handle(),
+            width: None,
+            pairs: Vec::new(),
+            mismatches: Vec::new(),
+            comparing: false,
+            refresh_task: Task::ready(()),
+            scroll_handle: UniformListScrollHandle::new(),
+            pending_serialization: Task::ready(None),
+            _subscriptions: subscriptions,
+        };
+        this.refresh(&RefreshGoldenImages, cx);
+        this
+    }
+
+    fn serialize(&mut self, cx: &mut ViewContext<Self>) {
+        let width = self.width;
+        self.pending_serialization = cx.background_executor().spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        GOLDEN_IMAGES_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedGoldenImagesPanel { width })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    fn schedule_refresh(&mut self, cx: &mut ViewContext<Self>) {
+        self.refresh_task = cx.spawn(|this, mut cx| async move {
+            cx.background_executor().timer(REFRESH_DEBOUNCE).await;
+            this.update(&mut cx, |this, cx| this.refresh(&RefreshGoldenImages, cx))
+                .ok();
+        });
+    }
+
+    /// Pairs up the expected and actual images of all visible worktrees, and compares them.
+    fn refresh(&mut self, _: &RefreshGoldenImages, cx: &mut ViewContext<Self>) {
+        let patterns = GoldenImagesPanelSettings::get_global(cx).patterns.clone();
+        let snapshots = self
+            .project
+            .read(cx)
+            .visible_worktrees(cx)
+            .map(|worktree| worktree.read(cx).snapshot())
+            .collect::<Vec<_>>();
+        let fs = self.fs.clone();
+        self.comparing = true;
+        cx.notify();
+        self.refresh_task = cx.spawn(|this, mut cx| async move {
+            let background = cx.background_executor().clone();
+            let candidates = background
+                .spawn(async move {
+                    let mut candidates = Vec::new();
+                    let mut seen = HashSet::new();
+                    for snapshot in snapshots {
+                        // Test outputs are usually gitignored, but expected images are not.
+                        for entry in snapshot.files(true, 0) {
+                            if !is_image_path(&entry.path) {
+                                continue;
+                            }
+                            let expected_path = entry.path.to_string_lossy().replace('\\', "/");
+                            for pattern in &patterns {
+                                let Some(actual_path) = actual_path(pattern, &expected_path) else {
+                                    continue;
+                                };
+                                let expected_abs_path = snapshot.abs_path().join(&*entry.path);
+                                let actual_abs_path = snapshot.abs_path().join(actual_path);
+                                if expected_abs_path != actual_abs_path
+                                    && seen.insert(expected_abs_path.clone())
+                                {
+                                    candidates.push((
+                                        expected_path.clone(),
+                                        expected_abs_path,
+                                        actual_abs_path,
+                                    ));
+                                }
+                            }
+                        }
+                    }
+                    candidates
+                })
+                .await;
+
+            let mut pairs = Vec::with_capacity(candidates.len());
+            for (expected_path, expected_abs_path, actual_abs_path) in candidates {
+                let status = if !fs.is_file(&actual_abs_path).await {
+                    PairStatus::MissingActual
+                } else {
+                    let expected = fs.load_bytes(&expected_abs_path).await;
+                    let actual = fs.load_bytes(&actual_abs_path).await;
+                    background
+                        .spawn(async move { compare_images(&expected?, &actual?) })
+                        .await
+                        .unwrap_or_else(|error| PairStatus::Failed(error.to_string().into()))
+                };
+                pairs.push(GoldenImagePair {
+                    expected_path,
+                    expected_abs_path,
+                    actual_abs_path,
+                    status,
+                });
+            }
+            pairs.sort_by(|a, b| a.expected_path.cmp(&b.expected_path));
+
+            this.update(&mut cx, |this, cx| {
+                this.mismatches = (0..pairs.len()).filter(|ix| pairs[*ix].differs()).collect();
+                this.pairs = pairs;
+                this.comparing = false;
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    /// Opens the expected and actual image of a pair side by side.
+    fn open_pair(&mut self, pair_ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(pair) = self.pairs.get(pair_ix) else {
+            return;
+        };
+        if pair.status == PairStatus::MissingActual {
+            self.open_expected(pair_ix, cx);
+            return;
+        }
+        let project = self.project.clone();
+        let workspace = self.workspace.clone();
+        let expected_abs_path = pair.expected_abs_path.clone();
+        let actual_abs_path = pair.actual_abs_path.clone();
+        cx.spawn(|_, mut cx| async move {
+            let expected = open_image_by_abs_path(&project, expected_abs_path, &mut cx).await?;
+            let actual = open_image_by_abs_path(&project, actual_abs_path, &mut cx).await?;
+            workspace.update(&mut cx, |workspace, cx| {
+                let view = cx.new_view(|cx| {
+                    ImageCompareView::new(
+                        CompareSide::File(expected),
+                        CompareSide::File(actual),
+                        project,
+                        cx,
+                    )
+                });
+                workspace.add_item_to_active_pane(Box::new(view), None, true, cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn open_expected(&mut self, pair_ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(pair) = self.pairs.get(pair_ix) else {
+            return;
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace
+                    .open_abs_path(pair.expected_abs_path.clone(), true, cx)
+                    .detach_and_log_err(cx);
+            })
+            .ok();
+    }
+
+    fn render_header(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let summary = if self.comparing {
+            "Comparing…".to_string()
+        } else if self.pairs.is_empty() {
+            "No golden images found".to_string()
+        } else {
+            format!(
+                "{} of {} images differ",
+                self.mismatches.len(),
+                self.pairs.len()
+            )
+        };
+        h_flex()
+            .p_2()
+            .gap_1()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                Label::new(summary)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(
+                IconButton::new("refresh-golden-images", IconName::RotateCw)
+                    .icon_size(IconSize::Small)
+                    .disabled(self.comparing)
+                    .tooltip(|cx| Tooltip::for_action("Compare Again", &RefreshGoldenImages, cx))
+                    .on_click(cx.listener(|this, _, cx| this.refresh(&RefreshGoldenImages, cx))),
+            )
+    }
+
+    fn render_mismatch(&self, ix: usize, cx: &mut ViewContext<Self>) -> AnyElement {
+        let pair_ix = self.mismatches[ix];
+        let pair = &self.pairs[pair_ix];
+        let path = Path::new(&pair.expected_path);
+        let file_name = path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let folder = path
+            .parent()
+            .map(|folder| folder.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let tooltip = match &pair.status {
+            PairStatus::Failed(error) => error.to_string(),
+            _ => format!(
+                "{}\n{}",
+                pair.expected_abs_path.display(),
+                pair.actual_abs_path.display()
+            ),
+        };
+        let status_color = match pair.status {
+            PairStatus::Different { .. } => Color::Modified,
+            _ => Color::Error,
+        };
+
+        ListItem::new(ix)
+            .start_slot(Icon::new(IconName::Diff).color(status_color))
+            .child(
+                h_flex()
+                    .gap_1()
+                    .overflow_hidden()
+                    .child(Label::new(file_name).single_line())
+                    .child(
+                        Label::new(folder)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .single_line(),
+                    ),
+            )
+            .end_slot(
+                Label::new(pair.status.label())
+                    .size(LabelSize::Small)
+                    .color(status_color),
+            )
+            .tooltip(move |cx| Tooltip::text(tooltip.clone(), cx))
+            .on_click(cx.listener(move |this, _, cx| this.open_pair(pair_ix, cx)))
+            .into_any_element()
+    }
+}
+
+impl EventEmitter<PanelEvent> for GoldenImagesPanel {}
+
+impl FocusableView for GoldenImagesPanel {
+    fn focus_handle(&self, _cx: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Panel for GoldenImagesPanel {
+    fn persistent_name() -> &'static str {
+        "Golden Images Panel"
+    }
+
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        match GoldenImagesPanelSettings::get_global(cx).dock {
+            GoldenImagesPanelDockPosition::Left => DockPosition::Left,
+            GoldenImagesPanelDockPosition::Right => DockPosition::Right,
+        }
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        settings::update_settings_file::<GoldenImagesPanelSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings, _| {
+                let dock = match position {
+                    DockPosition::Left | DockPosition::Bottom => {
+                        GoldenImagesPanelDockPosition::Left
+                    }
+                    DockPosition::Right => GoldenImagesPanelDockPosition::Right,
+                };
+                settings.dock = Some(dock);
+            },
+        );
+    }
+
+    fn size(&self, cx: &WindowContext) -> Pixels {
+        self.width
+            .unwrap_or_else(|| GoldenImagesPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        GoldenImagesPanelSettings::get_global(cx)
+            .button
+            .then_some(IconName::Diff)
+    }
+
+    fn icon_tooltip(&self, _: &WindowContext) -> Option<&'static str> {
+        Some("Golden Images Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+}
+
+impl Render for GoldenImagesPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let content = if self.mismatches.is_empty() {
+            let message = if self.pairs.is_empty() {
+                "Screenshot tests are found by the `golden_images_panel.patterns` setting"
+            } else {
+                "All images match"
+            };
+            div()
+                .flex()
+                .flex_1()
+                .p_4()
+                .items_center()
+                .justify_center()
+                .child(Label::new(message).color(Color::Muted))
+                .into_any_element()
+        } else {
+            uniform_list(
+                cx.view().clone(),
+                "golden-image-mismatches",
+                self.mismatches.len(),
+                |this, range, cx| range.map(|ix| this.render_mismatch(ix, cx)).collect(),
+            )
+            .flex_1()
+            .track_scroll(self.scroll_handle.clone())
+            .into_any_element()
+        };
+
+        v_flex()
+            .key_context("GoldenImagesPanel")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::refresh))
+            .size_full()
+            .child(self.render_header(cx))
+            .child(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageFormat, Rgba, RgbaImage};
+    use std::io::Cursor;
+
+    fn pattern(expected: &str, actual: &str) -> GoldenImagePattern {
+        GoldenImagePattern {
+            expected: expected.into(),
+            actual: actual.into(),
+        }
+    }
+
+    #[test]
+    fn test_actual_path() {
+        let default = pattern("**/expected/*.png", "**/actual/*.png");
+        assert_eq!(
+            actual_path(&default, "tests/ui/expected/button.png").as_deref(),
+            Some("tests/ui/actual/button.png")
+        );
+        assert_eq!(
+            actual_path(&default, "expected/button.png").as_deref(),
+            Some("actual/button.png")
+        );
+        assert_eq!(actual_path(&default, "expected/nested/button.png"), None);
+        assert_eq!(actual_path(&default, "tests/actual/button.png"), None);
+
+        let renamed = pattern("snapshots/**/*.png", "target/snapshots/**/*.new.png");
+        assert_eq!(
+            actual_path(&renamed, "snapshots/a/b/menu.png").as_deref(),
+            Some("target/snapshots/a/b/menu.new.png")
+        );
+
+        // Patterns whose wildcards don't correspond can't be filled in.
+        let mismatched = pattern("**/expected/*.png", "actual/*.png");
+        assert_eq!(actual_path(&mismatched, "expected/button.png"), None);
+    }
+
+    fn encode(image: &RgbaImage, format: ImageFormat) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut bytes), format)
+            .unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_compare_images() {
+        let expected = RgbaImage::from_pixel(4, 4, Rgba([10, 20, 30, 255]));
+        let png = encode(&expected, ImageFormat::Png);
+        assert_eq!(compare_images(&png, &png).unwrap(), PairStatus::Identical);
+        // The same pixels in a different encoding.
+        let bmp = encode(&expected, ImageFormat::Bmp);
+        assert_eq!(compare_images(&png, &bmp).unwrap(), PairStatus::Identical);
+
+        let mut actual = expected.clone();
+        actual.put_pixel(1, 2, Rgba([255, 0, 0, 255]));
+        assert_eq!(
+            compare_images(&png, &encode(&actual, ImageFormat::Png)).unwrap(),
+            PairStatus::Different {
+                differing_pixels: 1,
+                total_pixels: 16,
+            }
+        );
+
+        let smaller = RgbaImage::from_pixel(2, 4, Rgba([10, 20, 30, 255]));
+        assert_eq!(
+            compare_images(&png, &encode(&smaller, ImageFormat::Png)).unwrap(),
+            PairStatus::DimensionsDiffer
+        );
+    }
+}
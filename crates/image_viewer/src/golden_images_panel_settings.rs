@@ -0,0 +1,62 @@
+use gpui::Pixels;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum GoldenImagesPanelDockPosition {
+    Left,
+    Right,
+}
+
+/// Where the expected images of screenshot tests are, and where the images the tests produced
+/// are written next to them. Both are paths relative to the root of a worktree, in which `*`
+/// matches any part of a file or folder name and `**/` any number of folders. The wildcards
+/// of `actual` are replaced by what the same wildcards of `expected` matched.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct GoldenImagePattern {
+    pub expected: String,
+    pub actual: String,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct GoldenImagesPanelSettings {
+    pub button: bool,
+    pub default_width: Pixels,
+    pub dock: GoldenImagesPanelDockPosition,
+    pub patterns: Vec<GoldenImagePattern>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct GoldenImagesPanelSettingsContent {
+    /// Whether to show the golden images panel button in the status bar.
+    ///
+    /// Default: true
+    pub button: Option<bool>,
+    /// Customize default width (in pixels) taken by golden images panel
+    ///
+    /// Default: 320
+    pub default_width: Option<f32>,
+    /// The position of golden images panel
+    ///
+    /// Default: right
+    pub dock: Option<GoldenImagesPanelDockPosition>,
+    /// The pairs of paths of expected and actual images of screenshot tests.
+    ///
+    /// Default: [{"expected": "**/expected/*.png", "actual": "**/actual/*.png"}]
+    pub patterns: Option<Vec<GoldenImagePattern>>,
+}
+
+impl Settings for GoldenImagesPanelSettings {
+    const KEY: Option<&'static str> = Some("golden_images_panel");
+
+    type FileContent = GoldenImagesPanelSettingsContent;
+
+    fn load(
+        sources: SettingsSources<Self::FileContent>,
+        _: &mut gpui::AppContext,
+    ) -> anyhow::Result<Self> {
+        sources.json_merge()
+    }
+}
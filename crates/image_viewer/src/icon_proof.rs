@@ -0,0 +1,261 @@
+//! Proofing icons, by showing the image downscaled to the sizes icons are displayed at side by
+//! side, so that it can be checked that it stays legible at the smallest of them.
+
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use gpui::{img, opaque_grey, Hsla, RenderImage, Task};
+use image::{imageops, RgbaImage};
+use ui::{prelude::*, Tooltip};
+use util::ResultExt;
+
+use crate::{checkered_background, ImageView, ToggleIconProof};
+
+/// The sizes icons are commonly provided at, from favicons to app icons.
+const ICON_SIZES: [u32; 6] = [16, 32, 48, 64, 128, 256];
+
+/// What the icons are shown over.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum IconBackground {
+    /// A checkerboard showing which parts of the icons are transparent.
+    #[default]
+    Transparent,
+    /// The light and dark backgrounds of the places icons are shown, such as tab bars and docks,
+    /// one above the other.
+    LightAndDark,
+}
+
+/// The proofing view of an image view.
+pub(crate) struct IconProof {
+    background: IconBackground,
+    /// The image downscaled to each of `ICON_SIZES`, once done.
+    icons: Vec<(u32, Arc<RenderImage>)>,
+    _task: Option<Task<()>>,
+}
+
+pub(crate) fn toggle_icon_proof(
+    image_view: &mut ImageView,
+    _: &ToggleIconProof,
+    cx: &mut ViewContext<ImageView>,
+) {
+    if image_view.icon_proof.is_some() {
+        release_icons(image_view, cx);
+        image_view.icon_proof = None;
+    } else {
+        image_view.icon_proof = Some(IconProof {
+            background: IconBackground::default(),
+            icons: Vec::new(),
+            _task: None,
+        });
+        update_icon_proof(image_view, cx);
+    }
+    cx.notify();
+}
+
+/// Downscales the image again after it changed, when the proofing view is open.
+pub(crate) fn update_icon_proof(image_view: &mut ImageView, cx: &mut ViewContext<ImageView>) {
+    if image_view.icon_proof.is_none() || !image_view.shown {
+        return;
+    }
+    let image = image_view
+        .edited_image
+        .clone()
+        .unwrap_or_else(|| image_view.image_item.read(cx).image.clone());
+    let svg_renderer = cx.svg_renderer();
+    let task = cx.spawn(|this, mut cx| async move {
+        let icons = cx
+            .background_executor()
+            .spawn(async move {
+                let image = image.to_image_data(svg_renderer)?;
+                downscale_to_icon_sizes(&image)
+            })
+            .await
+            .log_err()
+            .unwrap_or_default();
+        this.update(&mut cx, |this, cx| {
+            release_icons(this, cx);
+            if let Some(icon_proof) = this.icon_proof.as_mut() {
+                icon_proof.icons = icons;
+                cx.notify();
+            }
+        })
+        .ok();
+    });
+    if let Some(icon_proof) = image_view.icon_proof.as_mut() {
+        icon_proof._task = Some(task);
+    }
+}
+
+/// Frees the memory used to draw the icons, which are drawn again from scratch if shown again.
+pub(crate) fn release_icons(image_view: &ImageView, cx: &mut ViewContext<ImageView>) {
+    if let Some(icon_proof) = image_view.icon_proof.as_ref() {
+        for (_, icon) in &icon_proof.icons {
+            cx.drop_image(icon.clone()).log_err();
+        }
+    }
+}
+
+/// Downscales the first frame of the image to fit each of `ICON_SIZES`, keeping its aspect
+/// ratio. The frames are kept in the BGRA order they are drawn in.
+fn downscale_to_icon_sizes(image: &RenderImage) -> anyhow::Result<Vec<(u32, Arc<RenderImage>)>> {
+    let size = image.size(0);
+    let bytes = image.as_bytes(0).context("image has no frames")?.to_vec();
+    let frame = RgbaImage::from_raw(size.width.0 as u32, size.height.0 as u32, bytes)
+        .context("invalid image data")?;
+    Ok(ICON_SIZES
+        .iter()
+        .map(|&icon_size| {
+            let (width, height) = icon_dimensions(frame.dimensions(), icon_size);
+            let icon = imageops::resize(&frame, width, height, imageops::FilterType::Lanczos3);
+            (
+                icon_size,
+                Arc::new(RenderImage::new(vec![image::Frame::new(icon)])),
+            )
+        })
+        .collect())
+}
+
+/// The dimensions of an image of the given dimensions scaled to fit a square icon.
+fn icon_dimensions((width, height): (u32, u32), icon_size: u32) -> (u32, u32) {
+    let (width, height) = (width.max(1) as f32, height.max(1) as f32);
+    let scale = icon_size as f32 / width.max(height);
+    (
+        ((width * scale).round() as u32).max(1),
+        ((height * scale).round() as u32).max(1),
+    )
+}
+
+/// A bar below the image showing it at each of the icon sizes.
+pub(crate) fn render_icon_proof_bar(
+    image_view: &ImageView,
+    cx: &mut ViewContext<ImageView>,
+) -> Option<impl IntoElement> {
+    let icon_proof = image_view.icon_proof.as_ref()?;
+    let units = image_view.settings(cx).units();
+
+    let background_button = |id: &'static str, label: &'static str, background: IconBackground| {
+        Button::new(id, label)
+            .label_size(LabelSize::Small)
+            .selected(icon_proof.background == background)
+            .on_click(cx.listener(move |this, _, cx| {
+                if let Some(icon_proof) = this.icon_proof.as_mut() {
+                    icon_proof.background = background;
+                    cx.notify();
+                }
+            }))
+    };
+    let header = h_flex()
+        .gap_1()
+        .child(Label::new("Icon Sizes").size(LabelSize::Small))
+        .child(div().flex_1())
+        .child(background_button(
+            "icon-proof-transparent",
+            "Transparent",
+            IconBackground::Transparent,
+        ))
+        .child(background_button(
+            "icon-proof-light-and-dark",
+            "Light & Dark",
+            IconBackground::LightAndDark,
+        ))
+        .child(
+            IconButton::new("icon-proof-close", IconName::Close)
+                .tooltip(|cx| Tooltip::for_action("Close Icon Sizes", &ToggleIconProof, cx))
+                .on_click(cx.listener(|this, _, cx| toggle_icon_proof(this, &ToggleIconProof, cx))),
+        );
+
+    let rows: Vec<Option<Hsla>> = match icon_proof.background {
+        IconBackground::Transparent => vec![None],
+        IconBackground::LightAndDark => {
+            vec![Some(opaque_grey(0.96, 1.)), Some(opaque_grey(0.12, 1.))]
+        }
+    };
+    let icons = if icon_proof.icons.is_empty() {
+        Label::new("Downscaling…")
+            .size(LabelSize::Small)
+            .color(Color::Muted)
+            .into_any_element()
+    } else {
+        v_flex()
+            .children(rows.into_iter().map(|background| {
+                h_flex()
+                    .p_2()
+                    .gap_4()
+                    .items_end()
+                    .rounded_md()
+                    .when_some(background, |row, background| row.bg(background))
+                    .children(icon_proof.icons.iter().map(|(icon_size, icon)| {
+                        render_icon(*icon_size, icon, background.is_none(), cx)
+                    }))
+            }))
+            .child(
+                h_flex()
+                    .px_2()
+                    .gap_4()
+                    .children(icon_proof.icons.iter().map(|(icon_size, _)| {
+                        div().w(px(*icon_size as f32)).flex_none().child(
+                            Label::new(units.dimensions(*icon_size, *icon_size))
+                                .size(LabelSize::XSmall)
+                                .color(Color::Muted),
+                        )
+                    })),
+            )
+            .into_any_element()
+    };
+
+    Some(
+        v_flex()
+            .w_full()
+            .p_2()
+            .gap_2()
+            .border_t_1()
+            .border_color(cx.theme().colors().border)
+            .bg(cx.theme().colors().panel_background)
+            .child(header)
+            .child(
+                div()
+                    .id("icon-proof-icons")
+                    .overflow_x_scroll()
+                    .child(icons),
+            ),
+    )
+}
+
+/// An icon centered in a square of its size, with one pixel of the icon to a pixel of the UI.
+fn render_icon(
+    icon_size: u32,
+    icon: &Arc<RenderImage>,
+    checkered: bool,
+    cx: &WindowContext,
+) -> impl IntoElement {
+    let icon_dimensions = icon.size(0);
+    div()
+        .relative()
+        .flex_none()
+        .flex()
+        .items_center()
+        .justify_center()
+        .size(px(icon_size as f32))
+        .when(checkered, |this| this.child(checkered_background(cx)))
+        .child(
+            img(icon.clone())
+                .relative()
+                .w(px(icon_dimensions.width.0 as f32))
+                .h(px(icon_dimensions.height.0 as f32)),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_icon_dimensions() {
+        assert_eq!(icon_dimensions((512, 512), 16), (16, 16));
+        // Wide and tall images fit inside the icon's square.
+        assert_eq!(icon_dimensions((400, 200), 32), (32, 16));
+        assert_eq!(icon_dimensions((100, 300), 48), (16, 48));
+        // Small images are scaled up to the icon size.
+        assert_eq!(icon_dimensions((8, 8), 128), (128, 128));
+    }
+}
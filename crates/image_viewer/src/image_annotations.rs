@@ -0,0 +1,314 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use gpui::{fill, point, px, size, Bounds, Hsla, Pixels, Point, WindowContext};
+use rpc::proto::{self, PeerId};
+
+use crate::image_viewer_settings::ImageZoomMode;
+
+/// How long a finished stroke stays visible before it is removed.
+pub(crate) const ANNOTATION_LIFETIME: Duration = Duration::from_secs(8);
+
+const POINTER_SIZE: Pixels = px(10.);
+const STROKE_WIDTH: Pixels = px(3.);
+
+/// A participant's pointer and scribbles over an image, in coordinates normalized to the image's
+/// width and height.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct Annotations {
+    pub pointer: Option<Point<f32>>,
+    pub strokes: Vec<Vec<Point<f32>>>,
+}
+
+/// The annotations shown over an image: the local participant's, which expire a while after
+/// being drawn, and the latest ones received from each collaborator.
+#[derive(Default)]
+pub(crate) struct ImageAnnotations {
+    local: Annotations,
+    /// When each local stroke was finished, `None` for the stroke being drawn.
+    local_stroke_finish_times: Vec<Option<Instant>>,
+    remote: HashMap<PeerId, Annotations>,
+}
+
+impl ImageAnnotations {
+    pub fn local(&self) -> &Annotations {
+        &self.local
+    }
+
+    pub fn remote(&self) -> impl Iterator<Item = (&PeerId, &Annotations)> {
+        self.remote.iter()
+    }
+
+    pub fn is_drawing(&self) -> bool {
+        self.local_stroke_finish_times
+            .last()
+            .map_or(false, |finished_at| finished_at.is_none())
+    }
+
+    /// Returns whether the pointer moved.
+    pub fn set_pointer(&mut self, pointer: Option<Point<f32>>) -> bool {
+        let changed = self.local.pointer != pointer;
+        self.local.pointer = pointer;
+        changed
+    }
+
+    pub fn start_stroke(&mut self, at: Point<f32>) {
+        self.local.strokes.push(vec![at]);
+        self.local_stroke_finish_times.push(None);
+    }
+
+    /// Returns whether there was a stroke being drawn to extend.
+    pub fn extend_stroke(&mut self, to: Point<f32>) -> bool {
+        if !self.is_drawing() {
+            return false;
+        }
+        if let Some(stroke) = self.local.strokes.last_mut() {
+            stroke.push(to);
+        }
+        true
+    }
+
+    /// Returns whether there was a stroke being drawn to finish.
+    pub fn finish_stroke(&mut self, now: Instant) -> bool {
+        if !self.is_drawing() {
+            return false;
+        }
+        if let Some(finished_at) = self.local_stroke_finish_times.last_mut() {
+            *finished_at = Some(now);
+        }
+        true
+    }
+
+    /// Removes the local strokes that have outlived [`ANNOTATION_LIFETIME`], returning whether
+    /// any were removed.
+    pub fn expire_strokes(&mut self, now: Instant) -> bool {
+        let previous_len = self.local.strokes.len();
+        let mut finish_times = self.local_stroke_finish_times.iter();
+        self.local.strokes.retain(|_| {
+            finish_times
+                .next()
+                .copied()
+                .flatten()
+                .map_or(true, |finished_at| now - finished_at < ANNOTATION_LIFETIME)
+        });
+        self.local_stroke_finish_times.retain(|finished_at| {
+            finished_at.map_or(true, |finished_at| now - finished_at < ANNOTATION_LIFETIME)
+        });
+        self.local.strokes.len() != previous_len
+    }
+
+    /// When the oldest finished local stroke should be removed.
+    pub fn next_expiration(&self) -> Option<Instant> {
+        self.local_stroke_finish_times
+            .iter()
+            .flatten()
+            .min()
+            .map(|finished_at| *finished_at + ANNOTATION_LIFETIME)
+    }
+
+    pub fn clear_local(&mut self) {
+        self.local = Annotations::default();
+        self.local_stroke_finish_times.clear();
+    }
+
+    pub fn clear_remote(&mut self) {
+        self.remote.clear();
+    }
+
+    pub fn remove_peer(&mut self, peer_id: PeerId) -> bool {
+        self.remote.remove(&peer_id).is_some()
+    }
+
+    pub fn apply_remote(&mut self, peer_id: PeerId, message: &proto::UpdateImageAnnotations) {
+        let annotations = Annotations {
+            pointer: message.pointer.as_ref().map(point_from_proto),
+            strokes: message
+                .strokes
+                .iter()
+                .map(|stroke| stroke.points.iter().map(point_from_proto).collect())
+                .collect(),
+        };
+        if annotations == Annotations::default() {
+            self.remote.remove(&peer_id);
+        } else {
+            self.remote.insert(peer_id, annotations);
+        }
+    }
+
+    pub fn local_to_proto(
+        &self,
+    ) -> (
+        Option<proto::ImageAnnotationPoint>,
+        Vec<proto::ImageAnnotationStroke>,
+    ) {
+        let pointer = self.local.pointer.map(point_to_proto);
+        let strokes = self
+            .local
+            .strokes
+            .iter()
+            .map(|stroke| proto::ImageAnnotationStroke {
+                points: stroke.iter().copied().map(point_to_proto).collect(),
+            })
+            .collect();
+        (pointer, strokes)
+    }
+}
+
+fn point_from_proto(point: &proto::ImageAnnotationPoint) -> Point<f32> {
+    gpui::point(point.x, point.y)
+}
+
+fn point_to_proto(point: Point<f32>) -> proto::ImageAnnotationPoint {
+    proto::ImageAnnotationPoint {
+        x: point.x,
+        y: point.y,
+    }
+}
+
+/// Where an image of the given size is drawn inside `container` when centered and scaled
+/// according to `zoom`, as the image viewer does.
+pub(crate) fn displayed_image_bounds(
+    container: Bounds<Pixels>,
+    (width, height): (u32, u32),
+    zoom: ImageZoomMode,
+) -> Bounds<Pixels> {
+    let (width, height) = (width.max(1) as f32, height.max(1) as f32);
+    let fill_scale = (container.size.width.0 / width).min(container.size.height.0 / height);
+    let scale = match zoom {
+        ImageZoomMode::Fit => fill_scale.min(1.),
+        ImageZoomMode::Fill => fill_scale,
+        ImageZoomMode::ActualSize => 1.,
+        ImageZoomMode::Scale(scale) => scale,
+    };
+    Bounds::centered_at(
+        container.center(),
+        size(px(width * scale), px(height * scale)),
+    )
+}
+
+/// Converts a window position into coordinates normalized to `image_bounds`, or `None` when the
+/// position is outside of the image.
+pub(crate) fn normalize_position(
+    position: Point<Pixels>,
+    image_bounds: Bounds<Pixels>,
+) -> Option<Point<f32>> {
+    if !image_bounds.contains(&position) {
+        return None;
+    }
+    Some(point(
+        (position.x - image_bounds.origin.x).0 / image_bounds.size.width.0,
+        (position.y - image_bounds.origin.y).0 / image_bounds.size.height.0,
+    ))
+}
+
+fn denormalize_position(position: Point<f32>, image_bounds: Bounds<Pixels>) -> Point<Pixels> {
+    point(
+        image_bounds.origin.x + image_bounds.size.width * position.x,
+        image_bounds.origin.y + image_bounds.size.height * position.y,
+    )
+}
+
+pub(crate) fn paint_annotations(
+    annotations: &Annotations,
+    color: Hsla,
+    image_bounds: Bounds<Pixels>,
+    cx: &mut WindowContext,
+) {
+    let mut paint_dot = |center: Point<Pixels>, diameter: Pixels| {
+        cx.paint_quad(
+            fill(Bounds::centered_at(center, size(diameter, diameter)), color)
+                .corner_radii(diameter / 2.),
+        );
+    };
+
+    for stroke in &annotations.strokes {
+        let mut points = stroke
+            .iter()
+            .map(|position| denormalize_position(*position, image_bounds));
+        let Some(mut previous) = points.next() else {
+            continue;
+        };
+        paint_dot(previous, STROKE_WIDTH);
+        for current in points {
+            // Fill the gaps between sampled mouse positions with overlapping dots.
+            let delta = current - previous;
+            let distance = (delta.x.0 * delta.x.0 + delta.y.0 * delta.y.0).sqrt();
+            let steps = (distance / (STROKE_WIDTH.0 / 2.)).ceil().max(1.) as usize;
+            for step in 1..=steps {
+                let t = step as f32 / steps as f32;
+                paint_dot(
+                    point(previous.x + delta.x * t, previous.y + delta.y * t),
+                    STROKE_WIDTH,
+                );
+            }
+            previous = current;
+        }
+    }
+
+    if let Some(pointer) = annotations.pointer {
+        paint_dot(denormalize_position(pointer, image_bounds), POINTER_SIZE);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_displayed_image_bounds() {
+        let container = Bounds::new(point(px(0.), px(0.)), size(px(200.), px(100.)));
+
+        // Small images are centered at their natural size.
+        let bounds = displayed_image_bounds(container, (50, 20), ImageZoomMode::Fit);
+        assert_eq!(bounds.origin, point(px(75.), px(40.)));
+        assert_eq!(bounds.size, size(px(50.), px(20.)));
+
+        // Unless they are scaled up to fill the container.
+        let bounds = displayed_image_bounds(container, (50, 20), ImageZoomMode::Fill);
+        assert_eq!(bounds.origin, point(px(0.), px(10.)));
+        assert_eq!(bounds.size, size(px(200.), px(80.)));
+
+        // Large images overflow the container at their actual size.
+        let bounds = displayed_image_bounds(container, (400, 400), ImageZoomMode::ActualSize);
+        assert_eq!(bounds.origin, point(px(-100.), px(-150.)));
+
+        // Or are scaled down to fit.
+        let bounds = displayed_image_bounds(container, (400, 400), ImageZoomMode::Fit);
+        assert_eq!(bounds.origin, point(px(50.), px(0.)));
+        assert_eq!(bounds.size, size(px(100.), px(100.)));
+
+        assert_eq!(
+            normalize_position(point(px(100.), px(50.)), bounds),
+            Some(point(0.5, 0.5))
+        );
+        assert_eq!(normalize_position(point(px(10.), px(50.)), bounds), None);
+    }
+
+    #[test]
+    fn test_stroke_expiration() {
+        let mut annotations = ImageAnnotations::default();
+        let start = Instant::now();
+
+        annotations.start_stroke(point(0.1, 0.1));
+        assert!(annotations.extend_stroke(point(0.2, 0.2)));
+        assert!(annotations.finish_stroke(start));
+        assert!(!annotations.extend_stroke(point(0.3, 0.3)));
+
+        annotations.start_stroke(point(0.5, 0.5));
+        assert!(annotations.finish_stroke(start + Duration::from_secs(2)));
+        assert_eq!(
+            annotations.next_expiration(),
+            Some(start + ANNOTATION_LIFETIME)
+        );
+
+        assert!(!annotations.expire_strokes(start + Duration::from_secs(1)));
+        assert!(annotations.expire_strokes(start + ANNOTATION_LIFETIME));
+        assert_eq!(annotations.local().strokes, vec![vec![point(0.5, 0.5)]]);
+        assert_eq!(
+            annotations.next_expiration(),
+            Some(start + Duration::from_secs(2) + ANNOTATION_LIFETIME)
+        );
+    }
+}
@@ -0,0 +1,899 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use git::Oid;
+use gpui::{
+    canvas, img, relative, AnyElement, AppContext, AsyncWindowContext, Bounds, ClipboardEntry,
+    ClipboardItem, EventEmitter, FocusHandle, FocusableView, Image, InteractiveElement,
+    IntoElement, KeyUpEvent, Model, MouseButton, MouseDownEvent, MouseMoveEvent, ObjectFit,
+    ParentElement, Point, Render, Styled, Subscription, Task, View, ViewContext, VisualContext,
+    WeakView, WindowContext,
+};
+use project::{image_store::ImageItemEvent, ImageItem, Project};
+use settings::Settings;
+use ui::{prelude::*, Tooltip};
+use workspace::{
+    item::{Item, SerializableItem, TabContentParams},
+    notifications::{DetachAndPromptErr, NotificationId},
+    CompareImages, ItemId, Toast, Workspace, WorkspaceId,
+};
+
+use crate::{
+    checkered_background,
+    image_metrics::ImageMetrics,
+    keyboard_cursor::MoveToolCursor,
+    open_image_by_abs_path,
+    persistence::{SerializedCompareSide, SerializedImageCompare, IMAGE_VIEWER},
+    BlinkCompare, CompareWithClipboard, CompareWithIndex, CopyImageMetrics, CycleCompareMode,
+    ImageView, ImageViewerSettings,
+};
+
+const IMAGE_COMPARE_KIND: &str = "ImageCompareView";
+
+/// How long each side stays visible while blinking between the two images.
+const BLINK_INTERVAL: Duration = Duration::from_millis(250);
+
+/// One of the two images shown by an [`ImageCompareView`].
+#[derive(Clone)]
+pub enum CompareSide {
+    /// An image opened from the project, which follows changes on disk.
+    File(Model<ImageItem>),
+    /// The version of a project image that is staged in the git index.
+    GitIndex {
+        image_item: Model<ImageItem>,
+        image: Arc<Image>,
+    },
+    /// The version of a project image that was committed in a past commit.
+    GitCommit {
+        image_item: Model<ImageItem>,
+        sha: Oid,
+        image: Arc<Image>,
+    },
+    /// An image taken from the system clipboard, e.g. a freshly captured screenshot.
+    Clipboard(Arc<Image>),
+}
+
+impl CompareSide {
+    fn image(&self, cx: &AppContext) -> Arc<Image> {
+        match self {
+            CompareSide::File(image_item) => image_item.read(cx).image.clone(),
+            CompareSide::GitIndex { image, .. }
+            | CompareSide::GitCommit { image, .. }
+            | CompareSide::Clipboard(image) => image.clone(),
+        }
+    }
+
+    fn image_item(&self) -> Option<&Model<ImageItem>> {
+        match self {
+            CompareSide::File(image_item)
+            | CompareSide::GitIndex { image_item, .. }
+            | CompareSide::GitCommit { image_item, .. } => Some(image_item),
+            CompareSide::Clipboard(_) => None,
+        }
+    }
+
+    fn file_name(image_item: &Model<ImageItem>, cx: &AppContext) -> String {
+        let path = image_item.read(cx).path();
+        path.file_name()
+            .unwrap_or_else(|| path.as_os_str())
+            .to_string_lossy()
+            .to_string()
+    }
+
+    fn label(&self, cx: &AppContext) -> SharedString {
+        match self {
+            CompareSide::File(image_item) => Self::file_name(image_item, cx).into(),
+            CompareSide::GitIndex { image_item, .. } => {
+                format!("{} (Index)", Self::file_name(image_item, cx)).into()
+            }
+            CompareSide::GitCommit {
+                image_item, sha, ..
+            } => format!(
+                "{} ({})",
+                Self::file_name(image_item, cx),
+                sha.display_short()
+            )
+            .into(),
+            CompareSide::Clipboard(_) => "Clipboard".into(),
+        }
+    }
+
+    /// Reads the first image from the system clipboard, if there is one.
+    pub fn from_clipboard(cx: &AppContext) -> Option<Self> {
+        cx.read_from_clipboard()?
+            .into_entries()
+            .find_map(|entry| match entry {
+                ClipboardEntry::Image(image) => Some(CompareSide::Clipboard(Arc::new(image))),
+                ClipboardEntry::String(_) => None,
+            })
+    }
+}
+
+/// How the two images of a comparison are laid out relative to each other.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CompareMode {
+    /// Both images are shown next to each other.
+    #[default]
+    SideBySide,
+    /// The images are stacked, with the right one revealed to the right of a movable divider.
+    Swipe,
+    /// The right image is blended over the left one, with the divider position as its opacity.
+    Onion,
+}
+
+impl CompareMode {
+    fn label(&self) -> &'static str {
+        match self {
+            CompareMode::SideBySide => "Side by Side",
+            CompareMode::Swipe => "Swipe",
+            CompareMode::Onion => "Onion Skin",
+        }
+    }
+
+    fn next(&self) -> Self {
+        match self {
+            CompareMode::SideBySide => CompareMode::Swipe,
+            CompareMode::Swipe => CompareMode::Onion,
+            CompareMode::Onion => CompareMode::SideBySide,
+        }
+    }
+}
+
+/// Shows two images next to each other, e.g. the staged and the working tree version of a file.
+pub struct ImageCompareView {
+    left: CompareSide,
+    right: CompareSide,
+    project: Model<Project>,
+    focus_handle: FocusHandle,
+    mode: CompareMode,
+    /// A fraction between 0 and 1, used by the [`CompareMode::Swipe`] and [`CompareMode::Onion`] modes.
+    divider_position: f32,
+    content_bounds: Option<Bounds<Pixels>>,
+    blink: Option<BlinkState>,
+    metrics: Option<Result<ImageMetrics, SharedString>>,
+    metrics_task: Option<Task<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+struct BlinkState {
+    showing_left: bool,
+    /// Alternates between the images, unless motion is reduced, in which case only the right
+    /// one is shown until the key is released.
+    _task: Option<Task<()>>,
+    /// The keys bound to [`BlinkCompare`] when blinking started. Releasing one of them stops
+    /// blinking; when empty (e.g. started from the command palette), any key release does.
+    keys: Vec<String>,
+}
+
+pub enum ImageCompareViewEvent {
+    TitleChanged,
+    LayoutChanged,
+}
+
+impl EventEmitter<ImageCompareViewEvent> for ImageCompareView {}
+
+impl ImageCompareView {
+    pub fn new(
+        left: CompareSide,
+        right: CompareSide,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let mut this = Self {
+            left,
+            right,
+            project,
+            focus_handle: cx.focus_handle(),
+            mode: CompareMode::default(),
+            divider_position: 0.5,
+            content_bounds: None,
+            blink: None,
+            metrics: None,
+            metrics_task: None,
+            _subscriptions: Vec::new(),
+        };
+        this.subscribe_to_sides(cx);
+        this.compute_metrics(cx);
+        this
+    }
+
+    fn compute_metrics(&mut self, cx: &mut ViewContext<Self>) {
+        let left = self.left.image(cx);
+        let right = self.right.image(cx);
+        let metrics = cx
+            .background_executor()
+            .spawn(async move { ImageMetrics::compute(&left, &right) });
+
+        self.metrics = None;
+        self.metrics_task = Some(cx.spawn(|this, mut cx| async move {
+            let metrics = metrics.await;
+            this.update(&mut cx, |this, cx| {
+                this.metrics = Some(metrics.map_err(|error| error.to_string().into()));
+                this.metrics_task = None;
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    fn copy_metrics(&mut self, _: &CopyImageMetrics, cx: &mut ViewContext<Self>) {
+        if let Some(Ok(metrics)) = &self.metrics {
+            cx.write_to_clipboard(ClipboardItem::new_string(metrics.to_string()));
+        }
+    }
+
+    fn subscribe_to_sides(&mut self, cx: &mut ViewContext<Self>) {
+        self._subscriptions = [&self.left, &self.right]
+            .into_iter()
+            .filter_map(|side| side.image_item())
+            .map(|image_item| cx.subscribe(image_item, Self::on_image_event))
+            .collect();
+    }
+
+    /// Replaces the left side of the comparison, e.g. with an image from the clipboard.
+    pub fn set_left(&mut self, left: CompareSide, cx: &mut ViewContext<Self>) {
+        self.left = left;
+        self.subscribe_to_sides(cx);
+        self.compute_metrics(cx);
+        cx.emit(ImageCompareViewEvent::TitleChanged);
+        cx.notify();
+    }
+
+    pub fn set_mode(&mut self, mode: CompareMode, cx: &mut ViewContext<Self>) {
+        if self.mode != mode {
+            self.mode = mode;
+            cx.emit(ImageCompareViewEvent::LayoutChanged);
+            cx.notify();
+        }
+    }
+
+    fn cycle_mode(&mut self, _: &CycleCompareMode, cx: &mut ViewContext<Self>) {
+        self.set_mode(self.mode.next(), cx);
+    }
+
+    pub fn set_divider_position(&mut self, position: f32, cx: &mut ViewContext<Self>) {
+        let position = position.clamp(0., 1.);
+        if self.divider_position != position {
+            self.divider_position = position;
+            cx.emit(ImageCompareViewEvent::LayoutChanged);
+            cx.notify();
+        }
+    }
+
+    fn move_divider_to(&mut self, position: Point<Pixels>, cx: &mut ViewContext<Self>) {
+        if self.mode == CompareMode::SideBySide {
+            return;
+        }
+        if let Some(bounds) = self.content_bounds {
+            let fraction = (position.x - bounds.left()) / bounds.size.width;
+            self.set_divider_position(fraction, cx);
+        }
+    }
+
+    /// Moves the divider from the keyboard, in the modes that have one.
+    fn move_divider(&mut self, action: &MoveToolCursor, cx: &mut ViewContext<Self>) {
+        if self.mode == CompareMode::SideBySide {
+            cx.propagate();
+            return;
+        }
+        self.set_divider_position(self.divider_position + action.offset().x, cx);
+    }
+
+    /// Starts alternating between the two images in place; blinking stops once the key is released.
+    fn blink(&mut self, _: &BlinkCompare, cx: &mut ViewContext<Self>) {
+        if self.blink.is_some() {
+            return;
+        }
+
+        let keys = cx
+            .bindings_for_action_in(&BlinkCompare, &self.focus_handle)
+            .iter()
+            .filter_map(|binding| binding.keystrokes().last())
+            .map(|keystroke| keystroke.key.clone())
+            .collect();
+
+        if ImageViewerSettings::get_global(cx).should_reduce_motion(cx) {
+            self.blink = Some(BlinkState {
+                showing_left: false,
+                _task: None,
+                keys,
+            });
+            cx.notify();
+            return;
+        }
+
+        let task = cx.spawn(|this, mut cx| async move {
+            loop {
+                cx.background_executor().timer(BLINK_INTERVAL).await;
+                let blinking = this
+                    .update(&mut cx, |this, cx| {
+                        let Some(blink) = this.blink.as_mut() else {
+                            return false;
+                        };
+                        blink.showing_left = !blink.showing_left;
+                        cx.notify();
+                        true
+                    })
+                    .unwrap_or(false);
+                if !blinking {
+                    break;
+                }
+            }
+        });
+        self.blink = Some(BlinkState {
+            showing_left: true,
+            _task: Some(task),
+            keys,
+        });
+        cx.notify();
+    }
+
+    fn stop_blinking(&mut self, event: &KeyUpEvent, cx: &mut ViewContext<Self>) {
+        let Some(blink) = self.blink.as_ref() else {
+            return;
+        };
+        if blink.keys.is_empty() || blink.keys.contains(&event.keystroke.key) {
+            self.blink = None;
+            cx.notify();
+        }
+    }
+
+    pub fn register(workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>) {
+        workspace.register_action(Self::compare_with_index);
+        workspace.register_action(Self::compare_with_clipboard);
+        workspace.register_action(Self::compare_images);
+    }
+
+    /// Opens a compare view between two project images, such as two selected in the project
+    /// panel.
+    fn compare_images(
+        workspace: &mut Workspace,
+        action: &CompareImages,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let project = workspace.project().clone();
+        let left = action.left.clone();
+        let right = action.right.clone();
+        cx.spawn(|workspace, mut cx| async move {
+            let left = open_image_by_abs_path(&project, left, &mut cx).await?;
+            let right = open_image_by_abs_path(&project, right, &mut cx).await?;
+            workspace.update(&mut cx, |workspace, cx| {
+                let view = cx.new_view(|cx| {
+                    ImageCompareView::new(
+                        CompareSide::File(left),
+                        CompareSide::File(right),
+                        project,
+                        cx,
+                    )
+                });
+                workspace.add_item_to_active_pane(Box::new(view), None, true, cx);
+            })
+        })
+        .detach_and_prompt_err("Failed to compare images", cx, |_, _| None);
+    }
+
+    /// Compares the active image against the clipboard image. When a compare view is active,
+    /// its left side is replaced with the clipboard image instead.
+    fn compare_with_clipboard(
+        workspace: &mut Workspace,
+        _: &CompareWithClipboard,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let Some(clipboard_side) = CompareSide::from_clipboard(cx) else {
+            struct NoClipboardImage;
+            workspace.show_toast(
+                Toast::new(
+                    NotificationId::unique::<NoClipboardImage>(),
+                    "The clipboard does not contain an image",
+                )
+                .autohide(),
+                cx,
+            );
+            return;
+        };
+
+        if let Some(compare_view) = workspace.active_item_as::<ImageCompareView>(cx) {
+            compare_view.update(cx, |compare_view, cx| {
+                compare_view.set_left(clipboard_side, cx)
+            });
+            return;
+        }
+
+        let Some(image_view) = workspace.active_item_as::<ImageView>(cx) else {
+            return;
+        };
+        let image_item = image_view.read(cx).image_item.clone();
+        let project = workspace.project().clone();
+        let view = cx.new_view(|cx| {
+            ImageCompareView::new(clipboard_side, CompareSide::File(image_item), project, cx)
+        });
+        workspace.add_item_to_active_pane(Box::new(view), None, true, cx);
+    }
+
+    /// Opens a compare view between the staged and the working tree version of the active image.
+    fn compare_with_index(
+        workspace: &mut Workspace,
+        _: &CompareWithIndex,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let Some(image_view) = workspace.active_item_as::<ImageView>(cx) else {
+            return;
+        };
+        let image_item = image_view.read(cx).image_item.clone();
+        Self::open_index_comparison(workspace, image_item, cx);
+    }
+
+    pub fn open_index_comparison(
+        workspace: &mut Workspace,
+        image_item: Model<ImageItem>,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let project = workspace.project().clone();
+        let load_index_image = project
+            .read(cx)
+            .image_store()
+            .update(cx, |image_store, cx| {
+                image_store.load_index_image(&image_item, cx)
+            });
+
+        cx.spawn(|workspace, mut cx| async move {
+            let index_image = load_index_image
+                .await?
+                .context("Image is not tracked in the git index")?;
+            workspace.update(&mut cx, |workspace, cx| {
+                let view = cx.new_view(|cx| {
+                    ImageCompareView::new(
+                        CompareSide::GitIndex {
+                            image_item: image_item.clone(),
+                            image: index_image,
+                        },
+                        CompareSide::File(image_item),
+                        project,
+                        cx,
+                    )
+                });
+                workspace.add_item_to_active_pane(Box::new(view), None, true, cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Opens a compare view between a committed and the working tree version of an image.
+    pub fn open_commit_comparison(
+        workspace: &mut Workspace,
+        image_item: Model<ImageItem>,
+        sha: Oid,
+        committed_image: Arc<Image>,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let project = workspace.project().clone();
+        let view = cx.new_view(|cx| {
+            ImageCompareView::new(
+                CompareSide::GitCommit {
+                    image_item: image_item.clone(),
+                    sha,
+                    image: committed_image,
+                },
+                CompareSide::File(image_item),
+                project,
+                cx,
+            )
+        });
+        workspace.add_item_to_active_pane(Box::new(view), None, true, cx);
+    }
+
+    fn serialize_side(side: &CompareSide, cx: &AppContext) -> Option<SerializedCompareSide> {
+        Some(match side {
+            CompareSide::File(image_item) => {
+                SerializedCompareSide::File(image_item.read(cx).file.as_local()?.abs_path(cx))
+            }
+            CompareSide::GitIndex { image_item, .. } => {
+                SerializedCompareSide::GitIndex(image_item.read(cx).file.as_local()?.abs_path(cx))
+            }
+            CompareSide::GitCommit {
+                image_item, sha, ..
+            } => SerializedCompareSide::GitCommit {
+                path: image_item.read(cx).file.as_local()?.abs_path(cx),
+                sha: sha.to_string(),
+            },
+            CompareSide::Clipboard(_) => SerializedCompareSide::Clipboard,
+        })
+    }
+
+    async fn deserialize_side(
+        project: &Model<Project>,
+        side: SerializedCompareSide,
+        cx: &mut AsyncWindowContext,
+    ) -> anyhow::Result<CompareSide> {
+        match side {
+            SerializedCompareSide::File(abs_path) => Ok(CompareSide::File(
+                open_image_by_abs_path(project, abs_path, cx).await?,
+            )),
+            SerializedCompareSide::GitIndex(abs_path) => {
+                let image_item = open_image_by_abs_path(project, abs_path, cx).await?;
+                let image = project
+                    .update(cx, |project, cx| {
+                        project.image_store().update(cx, |image_store, cx| {
+                            image_store.load_index_image(&image_item, cx)
+                        })
+                    })?
+                    .await?
+                    .context("Image is no longer tracked in the git index")?;
+                Ok(CompareSide::GitIndex { image_item, image })
+            }
+            SerializedCompareSide::GitCommit { path, sha } => {
+                let sha = sha.parse::<Oid>()?;
+                let image_item = open_image_by_abs_path(project, path, cx).await?;
+                let image = project
+                    .update(cx, |project, cx| {
+                        project.image_store().update(cx, |image_store, cx| {
+                            image_store.load_committed_image(&image_item, sha, cx)
+                        })
+                    })?
+                    .await?
+                    .context("Image no longer exists in the commit")?;
+                Ok(CompareSide::GitCommit {
+                    image_item,
+                    sha,
+                    image,
+                })
+            }
+            SerializedCompareSide::Clipboard => {
+                Err(anyhow::anyhow!("Clipboard images are not restored"))
+            }
+        }
+    }
+
+    fn on_image_event(
+        &mut self,
+        _: Model<ImageItem>,
+        event: &ImageItemEvent,
+        cx: &mut ViewContext<Self>,
+    ) {
+        match event {
+            ImageItemEvent::FileHandleChanged | ImageItemEvent::Renamed { .. } => {
+                cx.emit(ImageCompareViewEvent::TitleChanged);
+                cx.notify();
+            }
+            ImageItemEvent::Reloaded => {
+                self.compute_metrics(cx);
+                cx.emit(ImageCompareViewEvent::TitleChanged);
+                cx.notify();
+            }
+            ImageItemEvent::ReloadNeeded => {}
+            ImageItemEvent::MetadataComputed
+            | ImageItemEvent::Edited
+            | ImageItemEvent::Saved
+            | ImageItemEvent::Conflicted => cx.notify(),
+        }
+    }
+
+    fn render_metrics(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let (text, copyable) = match &self.metrics {
+            None => ("Computing PSNR and SSIM…".into(), false),
+            Some(Ok(metrics)) => (SharedString::from(metrics.to_string()), true),
+            Some(Err(error)) => (error.clone(), false),
+        };
+
+        h_flex()
+            .px_2()
+            .py_1()
+            .gap_1()
+            .border_t_1()
+            .border_color(cx.theme().colors().border)
+            .child(Label::new(text).size(LabelSize::Small).color(Color::Muted))
+            .when(copyable, |this| {
+                this.child(
+                    IconButton::new("copy-image-metrics", IconName::Copy)
+                        .icon_size(IconSize::Small)
+                        .tooltip(|cx| Tooltip::for_action("Copy Metrics", &CopyImageMetrics, cx))
+                        .on_click(
+                            cx.listener(|this, _, cx| this.copy_metrics(&CopyImageMetrics, cx)),
+                        ),
+                )
+            })
+    }
+
+    fn render_mode_bar(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        h_flex()
+            .px_2()
+            .py_1()
+            .gap_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .children(
+                [
+                    CompareMode::SideBySide,
+                    CompareMode::Swipe,
+                    CompareMode::Onion,
+                ]
+                .into_iter()
+                .map(|mode| {
+                    Button::new(mode.label(), mode.label())
+                        .label_size(LabelSize::Small)
+                        .selected(self.mode == mode)
+                        .on_click(cx.listener(move |this, _, cx| this.set_mode(mode, cx)))
+                }),
+            )
+    }
+
+    /// Renders both images on top of each other, aligned to the same bounds.
+    fn render_stacked(&self, cx: &mut ViewContext<Self>) -> AnyElement {
+        let animate = !ImageViewerSettings::get_global(cx).should_reduce_motion(cx);
+        let centered_image = |image: Arc<Image>, id: &'static str| {
+            div()
+                .absolute()
+                .size_full()
+                .flex()
+                .justify_center()
+                .items_center()
+                .child(
+                    img(image)
+                        .object_fit(ObjectFit::ScaleDown)
+                        .animate(animate)
+                        .max_w_full()
+                        .max_h_full()
+                        .id(id),
+                )
+        };
+
+        let right_image = self.right.image(cx);
+        let top = match self.mode {
+            CompareMode::Onion => centered_image(right_image, "right-image")
+                .opacity(self.divider_position)
+                .into_any_element(),
+            _ => {
+                // Offset the clipped container's contents so that the right image stays aligned
+                // with the left one, regardless of where the divider is.
+                let divider = self.divider_position.min(0.999);
+                div()
+                    .absolute()
+                    .top_0()
+                    .bottom_0()
+                    .right_0()
+                    .left(relative(divider))
+                    .overflow_hidden()
+                    .border_l_2()
+                    .border_color(cx.theme().colors().border_focused)
+                    .child(
+                        div()
+                            .absolute()
+                            .top_0()
+                            .h_full()
+                            .left(relative(-divider / (1. - divider)))
+                            .w(relative(1. / (1. - divider)))
+                            .child(centered_image(right_image, "right-image")),
+                    )
+                    .into_any_element()
+            }
+        };
+
+        let view = cx.view().clone();
+        div()
+            .id("stacked-images")
+            .relative()
+            .size_full()
+            .child(
+                canvas(
+                    move |bounds, cx| {
+                        view.update(cx, |this, _| this.content_bounds = Some(bounds));
+                    },
+                    |_, _, _| {},
+                )
+                .absolute()
+                .size_full(),
+            )
+            .child(checkered_background(cx))
+            .child(centered_image(self.left.image(cx), "left-image"))
+            .child(top)
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, event: &MouseDownEvent, cx| {
+                    this.move_divider_to(event.position, cx)
+                }),
+            )
+            .on_mouse_move(cx.listener(|this, event: &MouseMoveEvent, cx| {
+                if event.pressed_button == Some(MouseButton::Left) {
+                    this.move_divider_to(event.position, cx)
+                }
+            }))
+            .into_any_element()
+    }
+
+    fn render_side(&self, side: &CompareSide, id: &'static str, cx: &WindowContext) -> AnyElement {
+        let animate = !ImageViewerSettings::get_global(cx).should_reduce_motion(cx);
+        v_flex()
+            .flex_1()
+            .h_full()
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(Label::new(side.label(cx)).size(LabelSize::Small)),
+            )
+            .child(
+                div()
+                    .relative()
+                    .flex_1()
+                    .w_full()
+                    .child(checkered_background(cx))
+                    .child(
+                        div()
+                            .flex()
+                            .justify_center()
+                            .items_center()
+                            .size_full()
+                            .child(
+                                img(side.image(cx))
+                                    .object_fit(ObjectFit::ScaleDown)
+                                    .animate(animate)
+                                    .max_w_full()
+                                    .max_h_full()
+                                    .id(id),
+                            ),
+                    ),
+            )
+            .into_any_element()
+    }
+}
+
+impl Item for ImageCompareView {
+    type Event = ImageCompareViewEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(workspace::item::ItemEvent)) {
+        match event {
+            ImageCompareViewEvent::TitleChanged => f(workspace::item::ItemEvent::UpdateTab),
+            ImageCompareViewEvent::LayoutChanged => {}
+        }
+    }
+
+    fn tab_content(&self, params: TabContentParams, cx: &WindowContext) -> AnyElement {
+        let title = format!("{} ↔ {}", self.left.label(cx), self.right.label(cx));
+        Label::new(title)
+            .single_line()
+            .color(params.text_color())
+            .italic(params.preview)
+            .into_any_element()
+    }
+
+    fn tab_icon(&self, _cx: &WindowContext) -> Option<Icon> {
+        Some(Icon::new(IconName::Diff))
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("image compare view")
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: Option<workspace::WorkspaceId>,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<View<Self>>
+    where
+        Self: Sized,
+    {
+        Some(cx.new_view(|cx| {
+            let mut view = Self::new(
+                self.left.clone(),
+                self.right.clone(),
+                self.project.clone(),
+                cx,
+            );
+            view.mode = self.mode;
+            view.divider_position = self.divider_position;
+            view
+        }))
+    }
+}
+
+impl SerializableItem for ImageCompareView {
+    fn serialized_item_kind() -> &'static str {
+        IMAGE_COMPARE_KIND
+    }
+
+    fn deserialize(
+        project: Model<Project>,
+        _workspace: WeakView<Workspace>,
+        workspace_id: WorkspaceId,
+        item_id: ItemId,
+        cx: &mut WindowContext,
+    ) -> Task<gpui::Result<View<Self>>> {
+        cx.spawn(|mut cx| async move {
+            let compare = IMAGE_VIEWER
+                .get_image_compare(item_id, workspace_id)?
+                .context("No image comparison found")?;
+            let left = Self::deserialize_side(&project, compare.left, &mut cx).await?;
+            let right = Self::deserialize_side(&project, compare.right, &mut cx).await?;
+
+            cx.update(|cx| {
+                Ok(cx.new_view(|cx| {
+                    let mut view = ImageCompareView::new(left, right, project, cx);
+                    view.mode = compare.mode;
+                    view.divider_position = compare.divider_position;
+                    view
+                }))
+            })?
+        })
+    }
+
+    fn cleanup(
+        workspace_id: WorkspaceId,
+        alive_items: Vec<ItemId>,
+        cx: &mut WindowContext,
+    ) -> Task<gpui::Result<()>> {
+        cx.spawn(|_| IMAGE_VIEWER.delete_unloaded_compare_views(workspace_id, alive_items))
+    }
+
+    fn serialize(
+        &mut self,
+        workspace: &mut Workspace,
+        item_id: ItemId,
+        _closing: bool,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<Task<gpui::Result<()>>> {
+        let workspace_id = workspace.database_id()?;
+        let compare = SerializedImageCompare {
+            left: Self::serialize_side(&self.left, cx)?,
+            right: Self::serialize_side(&self.right, cx)?,
+            mode: self.mode,
+            divider_position: self.divider_position,
+        };
+
+        Some(cx.background_executor().spawn(async move {
+            IMAGE_VIEWER
+                .save_image_compare(item_id, workspace_id, compare)
+                .await
+        }))
+    }
+
+    fn should_serialize(&self, event: &Self::Event) -> bool {
+        matches!(event, ImageCompareViewEvent::LayoutChanged)
+    }
+}
+
+impl FocusableView for ImageCompareView {
+    fn focus_handle(&self, _cx: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for ImageCompareView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let content = if let Some(blink) = self.blink.as_ref() {
+            let side = if blink.showing_left {
+                &self.left
+            } else {
+                &self.right
+            };
+            h_flex()
+                .size_full()
+                .child(self.render_side(side, "blink-image", cx))
+                .into_any_element()
+        } else {
+            match self.mode {
+                CompareMode::SideBySide => h_flex()
+                    .size_full()
+                    .child(self.render_side(&self.left, "left-image", cx))
+                    .child(div().h_full().w_px().bg(cx.theme().colors().border))
+                    .child(self.render_side(&self.right, "right-image", cx))
+                    .into_any_element(),
+                CompareMode::Swipe | CompareMode::Onion => self.render_stacked(cx),
+            }
+        };
+
+        v_flex()
+            .key_context("ImageCompareView")
+            .track_focus(&self.focus_handle(cx))
+            .on_action(cx.listener(Self::blink))
+            .on_action(cx.listener(Self::copy_metrics))
+            .on_action(cx.listener(Self::cycle_mode))
+            .on_action(cx.listener(Self::move_divider))
+            .on_key_up(cx.listener(Self::stop_blinking))
+            .size_full()
+            .child(self.render_mode_bar(cx))
+            .child(div().flex_1().w_full().child(content))
+            .child(self.render_metrics(cx))
+    }
+}
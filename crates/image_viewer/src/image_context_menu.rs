@@ -0,0 +1,257 @@
+use std::path::{Path, PathBuf};
+
+use gpui::{ClipboardItem, DismissEvent, MouseDownEvent, ViewContext};
+use ui::{prelude::*, ContextMenu};
+use workspace::{item::ItemHandle as _, CopyPath, CopyRelativePath};
+
+use crate::{
+    color_picker::most_recent_editor, window_workspace, CompareWithClipboard, CompareWithIndex,
+    CopyImage, CopyImageAsMarkdown, CopyPathRelativeToActiveFile, CopyPosixPath, ImageView,
+    OpenInExternalEditor, Print, PrintScale, RevealInFileManager,
+};
+
+/// Shows the actions that apply to the image where it was right-clicked.
+pub(crate) fn deploy_context_menu(
+    image_view: &mut ImageView,
+    event: &MouseDownEvent,
+    cx: &mut ViewContext<ImageView>,
+) {
+    let image_item = image_view.image_item.read(cx);
+    let has_project_file = image_item.has_project_file();
+    let is_local = image_item.file.as_local().is_some();
+    let focus_handle = image_view.focus_handle.clone();
+    let context_menu = ContextMenu::build(cx, |menu, _| {
+        menu.context(focus_handle)
+            .action("Copy Image", Box::new(CopyImage))
+            .when(has_project_file, |menu| {
+                menu.action("Copy Path", Box::new(CopyPath))
+                    .action("Copy Relative Path", Box::new(CopyRelativePath))
+                    .action(
+                        "Copy Path Relative to Active File",
+                        Box::new(CopyPathRelativeToActiveFile),
+                    )
+                    .action("Copy POSIX Path", Box::new(CopyPosixPath))
+                    .action("Copy as Markdown", Box::new(CopyImageAsMarkdown))
+            })
+            .when(is_local, |menu| {
+                menu.separator()
+                    .action(
+                        if cfg!(target_os = "macos") {
+                            "Reveal in Finder"
+                        } else {
+                            "Reveal in File Manager"
+                        },
+                        Box::new(RevealInFileManager),
+                    )
+                    .action(
+                        "Open in External Editor",
+                        Box::new(OpenInExternalEditor::default()),
+                    )
+            })
+            .separator()
+            .action("Print…", Box::new(Print::default()))
+            .action(
+                "Print at Actual Size…",
+                Box::new(Print {
+                    scale: Some(PrintScale::ActualSize),
+                }),
+            )
+            .separator()
+            .action("Compare with Clipboard", Box::new(CompareWithClipboard))
+            .when(has_project_file, |menu| {
+                menu.action("Compare with Staged", Box::new(CompareWithIndex))
+            })
+    });
+
+    cx.focus_view(&context_menu);
+    let subscription = cx.subscribe(&context_menu, |this, _, _: &DismissEvent, cx| {
+        if this
+            .context_menu
+            .as_ref()
+            .is_some_and(|(context_menu, _, _)| context_menu.focus_handle(cx).contains_focused(cx))
+        {
+            cx.focus_self();
+        }
+        this.context_menu.take();
+        cx.notify();
+    });
+    image_view.context_menu = Some((context_menu, event.position, subscription));
+    cx.notify();
+}
+
+/// Copies the image as it is shown, including unsaved edits.
+pub(crate) fn copy_image(
+    image_view: &mut ImageView,
+    _: &CopyImage,
+    cx: &mut ViewContext<ImageView>,
+) {
+    let image = image_view
+        .edited_image
+        .clone()
+        .unwrap_or_else(|| image_view.image_item.read(cx).image.clone());
+    cx.write_to_clipboard(ClipboardItem::new_image(&image));
+}
+
+pub(crate) fn copy_path(image_view: &mut ImageView, _: &CopyPath, cx: &mut ViewContext<ImageView>) {
+    if let Some(abs_path) = image_view.abs_path(cx) {
+        cx.write_to_clipboard(ClipboardItem::new_string(
+            abs_path.to_string_lossy().to_string(),
+        ));
+    }
+}
+
+pub(crate) fn copy_relative_path(
+    image_view: &mut ImageView,
+    _: &CopyRelativePath,
+    cx: &mut ViewContext<ImageView>,
+) {
+    let image_item = image_view.image_item.read(cx);
+    if image_item.has_project_file() {
+        let path = image_item.path().to_string_lossy().to_string();
+        cx.write_to_clipboard(ClipboardItem::new_string(path));
+    }
+}
+
+/// Copies the path of the image relative to the directory of the most recently active editor's
+/// file, as it would be written in that file to refer to the image.
+pub(crate) fn copy_path_relative_to_active_file(
+    image_view: &mut ImageView,
+    _: &CopyPathRelativeToActiveFile,
+    cx: &mut ViewContext<ImageView>,
+) {
+    let image_item = image_view.image_item.read(cx);
+    if !image_item.has_project_file() {
+        return;
+    }
+    let image_path = image_item.project_path(cx);
+    let Some(editor_path) = window_workspace(cx)
+        .and_then(|workspace| most_recent_editor(workspace.read(cx), cx))
+        .and_then(|editor| editor.project_path(cx))
+    else {
+        return;
+    };
+
+    let relative_path = if image_path.worktree_id == editor_path.worktree_id {
+        path_relative_to_file(&image_path.path, &editor_path.path)
+    } else {
+        let project = image_view.project.read(cx);
+        project
+            .absolute_path(&image_path, cx)
+            .zip(project.absolute_path(&editor_path, cx))
+            .and_then(|(image_path, editor_path)| path_relative_to_file(&image_path, &editor_path))
+    };
+    if let Some(relative_path) = relative_path {
+        cx.write_to_clipboard(ClipboardItem::new_string(
+            relative_path.to_string_lossy().to_string(),
+        ));
+    }
+}
+
+/// Copies the path of the image relative to its worktree's root, separated with forward slashes
+/// on every platform.
+pub(crate) fn copy_posix_path(
+    image_view: &mut ImageView,
+    _: &CopyPosixPath,
+    cx: &mut ViewContext<ImageView>,
+) {
+    let image_item = image_view.image_item.read(cx);
+    if image_item.has_project_file() {
+        let path = posix_path(image_item.path());
+        cx.write_to_clipboard(ClipboardItem::new_string(path));
+    }
+}
+
+/// Copies a Markdown image link to the image, relative to its worktree's root.
+pub(crate) fn copy_image_as_markdown(
+    image_view: &mut ImageView,
+    _: &CopyImageAsMarkdown,
+    cx: &mut ViewContext<ImageView>,
+) {
+    let image_item = image_view.image_item.read(cx);
+    if image_item.has_project_file() {
+        let markdown = markdown_image_link(image_item.path());
+        cx.write_to_clipboard(ClipboardItem::new_string(markdown));
+    }
+}
+
+pub(crate) fn reveal_in_file_manager(
+    image_view: &mut ImageView,
+    _: &RevealInFileManager,
+    cx: &mut ViewContext<ImageView>,
+) {
+    let abs_path = image_view
+        .image_item
+        .read(cx)
+        .file
+        .as_local()
+        .map(|file| file.abs_path(cx));
+    if let Some(abs_path) = abs_path {
+        cx.reveal_path(&abs_path);
+    }
+}
+
+fn markdown_image_link(path: &Path) -> String {
+    let alt_text = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let target = posix_path(path);
+    // Link destinations can only contain spaces when wrapped in angle brackets.
+    if target.contains(' ') {
+        format!("![{alt_text}](<{target}>)")
+    } else {
+        format!("![{alt_text}]({target})")
+    }
+}
+
+fn posix_path(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// The path that leads from the directory containing `file_path` to `path`.
+fn path_relative_to_file(path: &Path, file_path: &Path) -> Option<PathBuf> {
+    let directory = file_path.parent().unwrap_or(Path::new(""));
+    pathdiff::diff_paths(path, directory)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_image_link() {
+        assert_eq!(
+            markdown_image_link(Path::new("docs/images/logo.png")),
+            "![logo](docs/images/logo.png)"
+        );
+        assert_eq!(
+            markdown_image_link(Path::new("assets/app icon.svg")),
+            "![app icon](<assets/app icon.svg>)"
+        );
+    }
+
+    #[test]
+    fn test_posix_path() {
+        let path: PathBuf = ["assets", "icons", "logo.png"].iter().collect();
+        assert_eq!(posix_path(&path), "assets/icons/logo.png");
+    }
+
+    #[test]
+    fn test_path_relative_to_file() {
+        assert_eq!(
+            path_relative_to_file(Path::new("assets/logo.png"), Path::new("src/app.rs")),
+            Some(PathBuf::from("../assets/logo.png"))
+        );
+        assert_eq!(
+            path_relative_to_file(Path::new("src/logo.png"), Path::new("src/app.rs")),
+            Some(PathBuf::from("logo.png"))
+        );
+        assert_eq!(
+            path_relative_to_file(Path::new("logo.png"), Path::new("README.md")),
+            Some(PathBuf::from("logo.png"))
+        );
+    }
+}
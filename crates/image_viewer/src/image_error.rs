@@ -0,0 +1,114 @@
+use editor::Editor;
+use gpui::{ClickEvent, Model, ViewContext, VisualContext};
+use project::{Project, ProjectPath};
+use ui::prelude::*;
+use workspace::{notifications::DetachAndPromptErr, Workspace};
+
+use crate::UnitFormatter;
+
+/// What is known about an image that failed to open or decode.
+pub(crate) struct ImageErrorDetails {
+    pub file_name: String,
+    /// The format the image was expected to be in, such as from its extension.
+    pub format: Option<String>,
+    pub byte_size: Option<u64>,
+    /// The error of the image store or the decoder.
+    pub error: SharedString,
+}
+
+pub(crate) type ClickHandler = Box<dyn Fn(&ClickEvent, &mut WindowContext)>;
+
+/// A card describing why an image can't be shown, with ways to try again or look at the file
+/// differently.
+pub(crate) fn render_error_card(
+    details: &ImageErrorDetails,
+    units: UnitFormatter,
+    on_retry: impl Fn(&ClickEvent, &mut WindowContext) + 'static,
+    on_open_as_text: Option<ClickHandler>,
+    on_reveal: Option<ClickHandler>,
+    cx: &WindowContext,
+) -> impl IntoElement {
+    let facts = details
+        .format
+        .iter()
+        .cloned()
+        .chain(details.byte_size.map(|size| units.file_size(size)))
+        .collect::<Vec<_>>();
+
+    v_flex()
+        .max_w(rems(32.))
+        .p_4()
+        .gap_2()
+        .rounded_md()
+        .border_1()
+        .border_color(cx.theme().colors().border)
+        .bg(cx.theme().colors().elevated_surface_background)
+        .child(
+            h_flex()
+                .gap_2()
+                .child(Icon::new(IconName::Warning).color(Color::Error))
+                .child(Label::new(format!("Can't display {}", details.file_name))),
+        )
+        .when(!facts.is_empty(), |card| {
+            card.child(
+                Label::new(facts.join(" · "))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+        })
+        .child(
+            div()
+                .p_2()
+                .rounded_sm()
+                .bg(cx.theme().colors().editor_background)
+                .font_buffer(cx)
+                .child(Label::new(details.error.clone()).size(LabelSize::Small)),
+        )
+        .child(
+            h_flex()
+                .gap_1()
+                .child(
+                    Button::new("image-error-retry", "Retry")
+                        .style(ButtonStyle::Filled)
+                        .on_click(on_retry),
+                )
+                .when_some(on_open_as_text, |buttons, on_open_as_text| {
+                    buttons.child(
+                        Button::new("image-error-open-as-text", "Open as Text")
+                            .on_click(on_open_as_text),
+                    )
+                })
+                .when_some(on_reveal, |buttons, on_reveal| {
+                    buttons.child(
+                        Button::new(
+                            "image-error-reveal",
+                            if cfg!(target_os = "macos") {
+                                "Reveal in Finder"
+                            } else {
+                                "Reveal in File Manager"
+                            },
+                        )
+                        .on_click(on_reveal),
+                    )
+                }),
+        )
+}
+
+/// Opens the file of an image in a text editor, such as to look at SVG source that failed to
+/// parse.
+pub(crate) fn open_as_text(
+    project: Model<Project>,
+    project_path: ProjectPath,
+    workspace: &mut Workspace,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let open_buffer = project.update(cx, |project, cx| project.open_buffer(project_path, cx));
+    cx.spawn(|workspace, mut cx| async move {
+        let buffer = open_buffer.await?;
+        workspace.update(&mut cx, |workspace, cx| {
+            let editor = cx.new_view(|cx| Editor::for_buffer(buffer, Some(project), cx));
+            workspace.add_item_to_active_pane(Box::new(editor), None, true, cx);
+        })
+    })
+    .detach_and_prompt_err("Failed to open file as text", cx, |_, _| None);
+}
@@ -0,0 +1,656 @@
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
+};
+
+use editor::{Editor, EditorEvent};
+use gpui::{
+    img, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, Image, Model, ObjectFit,
+    Render, ScrollHandle, Subscription, Task, View, ViewContext, WeakView,
+};
+use project::{image_store::is_image_path, Project, ProjectPath, ThumbnailPriority, WorktreeId};
+use ui::{prelude::*, Tooltip};
+use util::ResultExt;
+use workspace::{
+    item::{Item, TabContentParams},
+    OpenFolderAsGallery, OpenImagesAsGallery, Workspace, WorkspaceId,
+};
+
+use crate::{recent_images, ImageView, OpenGallery};
+
+const THUMBNAIL_SIZE: Pixels = px(160.);
+/// The size thumbnails are loaded at, doubled so that they stay sharp on high density displays.
+pub(crate) const THUMBNAIL_PIXELS: u32 = 320;
+
+/// The order in which a gallery lists its images.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GallerySort {
+    #[default]
+    Name,
+    Modified,
+    Size,
+    RecentlyViewed,
+}
+
+impl GallerySort {
+    fn label(&self) -> &'static str {
+        match self {
+            GallerySort::Name => "Name",
+            GallerySort::Modified => "Last Modified",
+            GallerySort::Size => "Size",
+            GallerySort::RecentlyViewed => "Recently Viewed",
+        }
+    }
+
+    fn next(&self) -> Self {
+        match self {
+            GallerySort::Name => GallerySort::Modified,
+            GallerySort::Modified => GallerySort::Size,
+            GallerySort::Size => GallerySort::RecentlyViewed,
+            GallerySort::RecentlyViewed => GallerySort::Name,
+        }
+    }
+}
+
+/// The images of a worktree that a gallery lists.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GallerySource {
+    /// Every image directly inside a directory.
+    Directory(Arc<Path>),
+    /// A set of images picked together, such as the ones selected in the project panel.
+    Images(Vec<Arc<Path>>),
+}
+
+#[derive(Clone, Debug)]
+struct GalleryEntry {
+    path: Arc<Path>,
+    mtime: Option<SystemTime>,
+    size: u64,
+}
+
+impl GalleryEntry {
+    fn file_name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+}
+
+/// A thumbnail grid of every image in a single directory of a worktree, or of a set of its
+/// images.
+pub struct ImageGalleryView {
+    project: Model<Project>,
+    workspace: WeakView<Workspace>,
+    workspace_id: Option<WorkspaceId>,
+    worktree_id: WorktreeId,
+    source: GallerySource,
+    entries: Vec<GalleryEntry>,
+    /// The paths of the images of the worktree viewed recently, most recent first.
+    recently_viewed: Vec<Arc<Path>>,
+    visible_entries: Vec<GalleryEntry>,
+    /// Loaded images by path, `None` when the image failed to load.
+    thumbnails: HashMap<Arc<Path>, Option<Arc<Image>>>,
+    sort: GallerySort,
+    filter_editor: View<Editor>,
+    focus_handle: FocusHandle,
+    scroll_handle: ScrollHandle,
+    /// The range of visible entries that thumbnails were last requested for.
+    thumbnails_range: Option<Range<usize>>,
+    /// The thumbnails being loaded by path, along with how urgently they were asked for.
+    thumbnail_tasks: HashMap<Arc<Path>, (ThumbnailPriority, Task<Option<()>>)>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ImageGalleryView {
+    pub fn new(
+        project: Model<Project>,
+        workspace: WeakView<Workspace>,
+        workspace_id: Option<WorkspaceId>,
+        worktree_id: WorktreeId,
+        source: GallerySource,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let filter_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Filter images…", cx);
+            editor
+        });
+        let subscriptions = vec![
+            cx.subscribe(&filter_editor, |this, _, event, cx| {
+                if let EditorEvent::Edited { .. } = event {
+                    this.update_visible_entries(cx);
+                }
+            }),
+            cx.subscribe(&project, |this, _, event, cx| {
+                if let project::Event::WorktreeUpdatedEntries(worktree_id, _) = event {
+                    if *worktree_id == this.worktree_id {
+                        this.refresh_entries(cx);
+                    }
+                }
+            }),
+        ];
+
+        let mut this = Self {
+            project,
+            workspace,
+            workspace_id,
+            worktree_id,
+            source,
+            entries: Vec::new(),
+            recently_viewed: Vec::new(),
+            visible_entries: Vec::new(),
+            thumbnails: HashMap::default(),
+            sort: GallerySort::default(),
+            filter_editor,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: ScrollHandle::new(),
+            thumbnails_range: None,
+            thumbnail_tasks: HashMap::default(),
+            _subscriptions: subscriptions,
+        };
+        this.refresh_entries(cx);
+        this
+    }
+
+    pub fn register(workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>) {
+        workspace.register_action(Self::open_folder_as_gallery);
+        workspace.register_action(Self::open_images_as_gallery);
+        workspace.register_action(Self::open_gallery);
+    }
+
+    fn open_folder_as_gallery(
+        workspace: &mut Workspace,
+        action: &OpenFolderAsGallery,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let Some((worktree, relative_path)) = workspace
+            .project()
+            .read(cx)
+            .find_worktree(&action.directory, cx)
+        else {
+            return;
+        };
+        let worktree_id = worktree.read(cx).id();
+        let source = GallerySource::Directory(relative_path.into());
+        Self::open(workspace, worktree_id, source, cx);
+    }
+
+    /// Opens a gallery of the given images, with one gallery for each worktree they are in.
+    fn open_images_as_gallery(
+        workspace: &mut Workspace,
+        action: &OpenImagesAsGallery,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let mut images_by_worktree = Vec::<(WorktreeId, Vec<Arc<Path>>)>::new();
+        for abs_path in &action.paths {
+            let Some((worktree, relative_path)) =
+                workspace.project().read(cx).find_worktree(abs_path, cx)
+            else {
+                continue;
+            };
+            let worktree_id = worktree.read(cx).id();
+            let path = Arc::from(relative_path);
+            match images_by_worktree
+                .iter_mut()
+                .find(|(id, _)| *id == worktree_id)
+            {
+                Some((_, paths)) => paths.push(path),
+                None => images_by_worktree.push((worktree_id, vec![path])),
+            }
+        }
+        for (worktree_id, paths) in images_by_worktree {
+            Self::open(workspace, worktree_id, GallerySource::Images(paths), cx);
+        }
+    }
+
+    /// Opens a gallery for the directory containing the active image.
+    fn open_gallery(workspace: &mut Workspace, _: &OpenGallery, cx: &mut ViewContext<Workspace>) {
+        let Some(image_view) = workspace.active_item_as::<ImageView>(cx) else {
+            return;
+        };
+        let project_path = image_view.read(cx).image_item.read(cx).project_path(cx);
+        let directory = project_path
+            .path
+            .parent()
+            .map(Arc::from)
+            .unwrap_or_else(|| Arc::from(Path::new("")));
+        let source = GallerySource::Directory(directory);
+        Self::open(workspace, project_path.worktree_id, source, cx);
+    }
+
+    fn open(
+        workspace: &mut Workspace,
+        worktree_id: WorktreeId,
+        source: GallerySource,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let existing = workspace.items_of_type::<Self>(cx).find(|gallery| {
+            let gallery = gallery.read(cx);
+            gallery.worktree_id == worktree_id && gallery.source == source
+        });
+        if let Some(existing) = existing {
+            workspace.activate_item(&existing, true, true, cx);
+            return;
+        }
+
+        let project = workspace.project().clone();
+        let weak_workspace = cx.view().downgrade();
+        let workspace_id = workspace.database_id();
+        let view = cx.new_view(|cx| {
+            ImageGalleryView::new(
+                project,
+                weak_workspace,
+                workspace_id,
+                worktree_id,
+                source,
+                cx,
+            )
+        });
+        workspace.add_item_to_active_pane(Box::new(view), None, true, cx);
+    }
+
+    fn refresh_entries(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(worktree) = self.project.read(cx).worktree_for_id(self.worktree_id, cx) else {
+            return;
+        };
+        let snapshot = worktree.read(cx).snapshot();
+        let entries: Box<dyn Iterator<Item = _>> = match &self.source {
+            GallerySource::Directory(directory) => Box::new(snapshot.child_entries(directory)),
+            GallerySource::Images(paths) => Box::new(
+                paths
+                    .iter()
+                    .filter_map(|path| snapshot.entry_for_path(path)),
+            ),
+        };
+        self.entries = entries
+            .filter(|entry| entry.is_file() && is_image_path(&entry.path))
+            .map(|entry| GalleryEntry {
+                path: entry.path.clone(),
+                mtime: entry.mtime.map(|mtime| mtime.timestamp_for_user()),
+                size: entry.size,
+            })
+            .collect();
+        self.thumbnails
+            .retain(|path, _| self.entries.iter().any(|entry| &entry.path == path));
+        self.update_visible_entries(cx);
+    }
+
+    fn update_visible_entries(&mut self, cx: &mut ViewContext<Self>) {
+        let query = self.filter_editor.read(cx).text(cx).to_lowercase();
+        let mut visible_entries = self
+            .entries
+            .iter()
+            .filter(|entry| query.is_empty() || entry.file_name().to_lowercase().contains(&query))
+            .cloned()
+            .collect::<Vec<_>>();
+        match self.sort {
+            GallerySort::Name => visible_entries.sort_by_key(|entry| entry.file_name()),
+            GallerySort::Modified => visible_entries.sort_by_key(|entry| Reverse(entry.mtime)),
+            GallerySort::Size => visible_entries.sort_by_key(|entry| Reverse(entry.size)),
+            GallerySort::RecentlyViewed => visible_entries.sort_by_key(|entry| {
+                self.recently_viewed
+                    .iter()
+                    .position(|path| path == &entry.path)
+                    .unwrap_or(usize::MAX)
+            }),
+        }
+        self.visible_entries = visible_entries;
+        self.load_thumbnails(cx);
+        cx.notify();
+    }
+
+    fn cycle_sort(&mut self, cx: &mut ViewContext<Self>) {
+        self.sort = self.sort.next();
+        if self.sort == GallerySort::RecentlyViewed {
+            self.load_recently_viewed(cx);
+        }
+        self.update_visible_entries(cx);
+    }
+
+    fn load_recently_viewed(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(workspace_id) = self.workspace_id else {
+            return;
+        };
+        let load_recent_images = recent_images::load(workspace_id, cx);
+        cx.spawn(|this, mut cx| async move {
+            let recent_images = load_recent_images.await?;
+            this.update(&mut cx, |this, cx| {
+                let Some(worktree) = this.project.read(cx).worktree_for_id(this.worktree_id, cx)
+                else {
+                    return;
+                };
+                let worktree_root = worktree.read(cx).abs_path();
+                this.recently_viewed = recent_images
+                    .into_iter()
+                    .filter(|(root, _)| root.as_path() == worktree_root.as_ref())
+                    .map(|(_, path)| Arc::from(path))
+                    .collect();
+                this.update_visible_entries(cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// The range of entries that are scrolled into view, as of the last time the grid was laid
+    /// out.
+    fn scrolled_into_view(&self) -> Range<usize> {
+        let bounds = self.scroll_handle.bounds();
+        let offset = self.scroll_handle.offset();
+        let top = bounds.top() - offset.y;
+        let bottom = bounds.bottom() - offset.y;
+        let is_visible = |ix: &usize| {
+            self.scroll_handle
+                .bounds_for_item(*ix)
+                .map_or(false, |item| item.bottom() >= top && item.top() <= bottom)
+        };
+        let mut entries = 0..self.visible_entries.len();
+        let Some(start) = entries.find(|ix| is_visible(ix)) else {
+            return 0..0;
+        };
+        let end = entries
+            .find(|ix| !is_visible(ix))
+            .unwrap_or(self.visible_entries.len());
+        start..end
+    }
+
+    /// Loads the thumbnails of the entries in view ahead of those a screen above and below
+    /// them, and stops loading the ones that were scrolled further away.
+    fn load_thumbnails(&mut self, cx: &mut ViewContext<Self>) {
+        let in_view = self.scrolled_into_view();
+        self.thumbnails_range = Some(in_view.clone());
+        let margin = in_view.len().max(1);
+        let nearby = in_view.start.saturating_sub(margin)
+            ..(in_view.end + margin).min(self.visible_entries.len());
+
+        let wanted = self.visible_entries[nearby.clone()]
+            .iter()
+            .zip(nearby)
+            .filter(|(entry, _)| !self.thumbnails.contains_key(&entry.path))
+            .map(|(entry, ix)| {
+                let priority = if in_view.contains(&ix) {
+                    ThumbnailPriority::Visible
+                } else {
+                    ThumbnailPriority::Background
+                };
+                (entry.path.clone(), priority)
+            })
+            .collect::<HashMap<_, _>>();
+        self.thumbnail_tasks
+            .retain(|path, _| wanted.contains_key(path));
+
+        for (path, priority) in wanted {
+            if self
+                .thumbnail_tasks
+                .get(&path)
+                .map_or(false, |(requested, _)| *requested >= priority)
+            {
+                continue;
+            }
+            let project_path = ProjectPath {
+                worktree_id: self.worktree_id,
+                path: path.clone(),
+            };
+            // Asking again for a thumbnail that is already loading shares the same load.
+            let load_thumbnail = self.project.update(cx, |project, cx| {
+                project.load_thumbnail(project_path, THUMBNAIL_PIXELS, priority, cx)
+            });
+            let task = cx.spawn({
+                let path = path.clone();
+                |this, mut cx| async move {
+                    let image = load_thumbnail
+                        .await
+                        .log_err()
+                        .map(|thumbnail| thumbnail.image.clone());
+                    this.update(&mut cx, |this, cx| {
+                        this.thumbnail_tasks.remove(&path);
+                        this.thumbnails.insert(path, image);
+                        cx.notify();
+                    })
+                    .ok()
+                }
+            });
+            self.thumbnail_tasks.insert(path, (priority, task));
+        }
+    }
+
+    fn open_entry(&mut self, path: Arc<Path>, cx: &mut ViewContext<Self>) {
+        let project_path = ProjectPath {
+            worktree_id: self.worktree_id,
+            path,
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace
+                    .open_path(project_path, None, true, cx)
+                    .detach_and_log_err(cx);
+            })
+            .ok();
+    }
+
+    fn title(&self, cx: &AppContext) -> String {
+        let directory = match &self.source {
+            GallerySource::Directory(directory) => directory,
+            GallerySource::Images(paths) => return format!("{} Images", paths.len()),
+        };
+        match directory.file_name() {
+            Some(name) => name.to_string_lossy().to_string(),
+            None => self
+                .project
+                .read(cx)
+                .worktree_for_id(self.worktree_id, cx)
+                .map(|worktree| worktree.read(cx).root_name().to_string())
+                .unwrap_or_default(),
+        }
+    }
+
+    fn render_toolbar(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        h_flex()
+            .p_2()
+            .gap_2()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                h_flex()
+                    .flex_1()
+                    .px_2()
+                    .py_1()
+                    .gap_2()
+                    .border_1()
+                    .border_color(cx.theme().colors().border)
+                    .rounded_md()
+                    .child(Icon::new(IconName::MagnifyingGlass).color(Color::Muted))
+                    .child(self.filter_editor.clone()),
+            )
+            .child(
+                Label::new(format!(
+                    "{} of {} images",
+                    self.visible_entries.len(),
+                    self.entries.len()
+                ))
+                .size(LabelSize::Small)
+                .color(Color::Muted),
+            )
+            .child(
+                Button::new("cycle-gallery-sort", format!("Sort: {}", self.sort.label()))
+                    .label_size(LabelSize::Small)
+                    .tooltip(|cx| Tooltip::text("Change Sort Order", cx))
+                    .on_click(cx.listener(|this, _, cx| this.cycle_sort(cx))),
+            )
+    }
+
+    fn render_thumbnail(
+        &self,
+        ix: usize,
+        entry: &GalleryEntry,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let path = entry.path.clone();
+        let preview = match self.thumbnails.get(&entry.path) {
+            Some(Some(image)) => img(image.clone())
+                .max_w(THUMBNAIL_SIZE)
+                .max_h(THUMBNAIL_SIZE)
+                .object_fit(ObjectFit::ScaleDown)
+                .into_any_element(),
+            Some(None) => Icon::new(IconName::Warning)
+                .color(Color::Warning)
+                .into_any_element(),
+            None => Label::new("Loading…")
+                .size(LabelSize::Small)
+                .color(Color::Muted)
+                .into_any_element(),
+        };
+
+        v_flex()
+            .id(ix)
+            .w(THUMBNAIL_SIZE)
+            .p_1()
+            .gap_1()
+            .rounded_md()
+            .cursor_pointer()
+            .hover(|style| style.bg(cx.theme().colors().element_hover))
+            .tooltip({
+                let path = PathBuf::from(entry.path.as_ref());
+                move |cx| Tooltip::text(path.to_string_lossy().to_string(), cx)
+            })
+            .on_click(cx.listener(move |this, _, cx| this.open_entry(path.clone(), cx)))
+            .child(
+                div()
+                    .size(THUMBNAIL_SIZE)
+                    .flex()
+                    .items_center()
+                    .justify_center()
+                    .child(preview),
+            )
+            .child(
+                Label::new(entry.file_name())
+                    .size(LabelSize::Small)
+                    .single_line(),
+            )
+    }
+}
+
+impl EventEmitter<()> for ImageGalleryView {}
+
+impl FocusableView for ImageGalleryView {
+    fn focus_handle(&self, _cx: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for ImageGalleryView {
+    type Event = ();
+
+    fn tab_content(&self, params: TabContentParams, cx: &WindowContext) -> AnyElement {
+        Label::new(format!("{} (Gallery)", self.title(cx)))
+            .single_line()
+            .color(params.text_color())
+            .italic(params.preview)
+            .into_any_element()
+    }
+
+    fn tab_icon(&self, _cx: &WindowContext) -> Option<Icon> {
+        Some(Icon::new(IconName::Folder))
+    }
+
+    fn tab_tooltip_text(&self, _cx: &AppContext) -> Option<SharedString> {
+        match &self.source {
+            GallerySource::Directory(directory) => {
+                Some(directory.to_string_lossy().to_string().into())
+            }
+            GallerySource::Images(paths) => Some(
+                paths
+                    .iter()
+                    .map(|path| path.to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+                    .into(),
+            ),
+        }
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("image gallery")
+    }
+
+    fn clone_on_split(
+        &self,
+        workspace_id: Option<WorkspaceId>,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<View<Self>>
+    where
+        Self: Sized,
+    {
+        Some(cx.new_view(|cx| {
+            let mut view = Self::new(
+                self.project.clone(),
+                self.workspace.clone(),
+                workspace_id,
+                self.worktree_id,
+                self.source.clone(),
+                cx,
+            );
+            view.sort = self.sort;
+            view.recently_viewed = self.recently_viewed.clone();
+            view.update_visible_entries(cx);
+            view
+        }))
+    }
+}
+
+impl Render for ImageGalleryView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let content = if self.visible_entries.is_empty() {
+            let message = if self.entries.is_empty() {
+                match self.source {
+                    GallerySource::Directory(_) => "This folder contains no images",
+                    GallerySource::Images(_) => "None of these images exist anymore",
+                }
+            } else {
+                "No images match the filter"
+            };
+            div()
+                .flex()
+                .flex_1()
+                .items_center()
+                .justify_center()
+                .child(Label::new(message).color(Color::Muted))
+                .into_any_element()
+        } else {
+            // Which entries are in view is only known once the grid is laid out.
+            if self.thumbnails_range.as_ref() != Some(&self.scrolled_into_view()) {
+                cx.on_next_frame(|this, cx| this.load_thumbnails(cx));
+            }
+            div()
+                .id("image-gallery-grid")
+                .flex_1()
+                .overflow_y_scroll()
+                .track_scroll(&self.scroll_handle)
+                .on_scroll_wheel(cx.listener(|_, _, cx| cx.notify()))
+                .p_2()
+                .flex()
+                .flex_wrap()
+                .content_start()
+                .gap_2()
+                .children(
+                    self.visible_entries
+                        .iter()
+                        .enumerate()
+                        .map(|(ix, entry)| self.render_thumbnail(ix, entry, cx)),
+                )
+                .into_any_element()
+        };
+
+        v_flex()
+            .key_context("ImageGallery")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_toolbar(cx))
+            .child(content)
+    }
+}
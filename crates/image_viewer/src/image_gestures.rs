@@ -0,0 +1,166 @@
+//! Moving around a zoomed image by dragging, scrolling and pinching. On touchscreens, a finger
+//! drags like the mouse, two fingers pinch to zoom, and a double tap fits the image to the view.
+
+use gpui::{
+    canvas, point, Bounds, DispatchPhase, MouseDownEvent, MouseMoveEvent, PinchEvent, Pixels,
+    Point, ScrollWheelEvent, Size, ViewContext,
+};
+use ui::prelude::*;
+
+use crate::{ImageView, ImageZoomMode, MAX_SCALE, MIN_SCALE};
+
+/// Whether the image can be moved around in the view at the given zoom.
+pub(crate) fn can_pan(zoom: ImageZoomMode) -> bool {
+    zoom != ImageZoomMode::Fit
+}
+
+/// Where the image is drawn after being moved around by `pan_offset`.
+pub(crate) fn panned(image_bounds: Bounds<Pixels>, pan_offset: Point<Pixels>) -> Bounds<Pixels> {
+    Bounds {
+        origin: image_bounds.origin + pan_offset,
+        size: image_bounds.size,
+    }
+}
+
+/// Fits the image to the view on a double click or tap, or starts dragging it around.
+pub(crate) fn start_pan(
+    image_view: &mut ImageView,
+    event: &MouseDownEvent,
+    cx: &mut ViewContext<ImageView>,
+) {
+    if event.click_count == 2 {
+        image_view.pan_start = None;
+        image_view.set_zoom(ImageZoomMode::Fit, cx);
+    } else if can_pan(image_view.zoom) {
+        image_view.pan_start = Some(event.position);
+    }
+}
+
+pub(crate) fn drag_pan(
+    image_view: &mut ImageView,
+    event: &MouseMoveEvent,
+    cx: &mut ViewContext<ImageView>,
+) {
+    let Some(last_position) = image_view.pan_start else {
+        return;
+    };
+    if !event.dragging() {
+        image_view.pan_start = None;
+        return;
+    }
+    image_view.pan_start = Some(event.position);
+    image_view.pan_offset = image_view.pan_offset + (event.position - last_position);
+    image_view.view_state_changed(cx);
+    cx.notify();
+}
+
+pub(crate) fn scroll_pan(
+    image_view: &mut ImageView,
+    event: &ScrollWheelEvent,
+    cx: &mut ViewContext<ImageView>,
+) {
+    if !can_pan(image_view.zoom) {
+        return;
+    }
+    image_view.pan_offset = image_view.pan_offset + event.delta.pixel_delta(cx.line_height());
+    image_view.view_state_changed(cx);
+    cx.stop_propagation();
+    cx.notify();
+}
+
+fn pinch(image_view: &mut ImageView, event: &PinchEvent, cx: &mut ViewContext<ImageView>) {
+    let (Some(area_bounds), Some(scale)) = (
+        image_view.image_area_bounds.get(),
+        image_view.current_scale(cx),
+    ) else {
+        return;
+    };
+    let new_scale = (scale * event.scale).clamp(MIN_SCALE, MAX_SCALE);
+    let anchor = event.position - area_bounds.center();
+    image_view.pan_offset = zoom_pan_offset(image_view.pan_offset, anchor, new_scale / scale);
+    image_view.set_zoom(ImageZoomMode::Scale(new_scale), cx);
+    cx.notify();
+}
+
+/// The pan offset that keeps the point of the image under `anchor`, relative to the center of
+/// the view, in place when the image is scaled by `ratio`.
+fn zoom_pan_offset(pan_offset: Point<Pixels>, anchor: Point<Pixels>, ratio: f32) -> Point<Pixels> {
+    let image_anchor = anchor - pan_offset;
+    point(
+        anchor.x - image_anchor.x * ratio,
+        anchor.y - image_anchor.y * ratio,
+    )
+}
+
+/// The pan offset that keeps the point of the image at the center of the view there when the
+/// image is drawn at `new_size` instead of `old_size`, such as after reloading at different
+/// dimensions.
+pub(crate) fn remap_pan_offset(
+    pan_offset: Point<Pixels>,
+    old_size: Size<Pixels>,
+    new_size: Size<Pixels>,
+) -> Point<Pixels> {
+    let ratio = |new: Pixels, old: Pixels| if old.0 > 0. { new.0 / old.0 } else { 1. };
+    point(
+        pan_offset.x * ratio(new_size.width, old_size.width),
+        pan_offset.y * ratio(new_size.height, old_size.height),
+    )
+}
+
+/// An element over the image area listening for pinch gestures, which elements have no
+/// handlers for.
+pub(crate) fn pinch_listener(cx: &mut ViewContext<ImageView>) -> impl IntoElement {
+    let image_view = cx.view().downgrade();
+    canvas(
+        |_, _| {},
+        move |bounds, _, cx| {
+            cx.on_mouse_event(move |event: &PinchEvent, phase, cx| {
+                if phase == DispatchPhase::Bubble && bounds.contains(&event.position) {
+                    image_view
+                        .update(cx, |image_view, cx| pinch(image_view, event, cx))
+                        .ok();
+                }
+            });
+        },
+    )
+    .size_full()
+    .absolute()
+    .top_0()
+    .left_0()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{px, size};
+
+    #[test]
+    fn test_zoom_pan_offset() {
+        // Zooming at the center of a centered image keeps it centered.
+        assert_eq!(
+            zoom_pan_offset(Point::default(), Point::default(), 2.),
+            Point::default()
+        );
+        // The point under the anchor stays there.
+        let pan_offset = point(px(10.), px(-20.));
+        let anchor = point(px(50.), px(30.));
+        let new_offset = zoom_pan_offset(pan_offset, anchor, 2.);
+        assert_eq!(new_offset, point(px(-30.), px(-70.)));
+    }
+
+    #[test]
+    fn test_remap_pan_offset() {
+        let pan_offset = point(px(40.), px(-30.));
+        let old_size = size(px(200.), px(100.));
+        assert_eq!(remap_pan_offset(pan_offset, old_size, old_size), pan_offset);
+        // Doubling the width keeps the same column of the image at the center.
+        assert_eq!(
+            remap_pan_offset(pan_offset, old_size, size(px(400.), px(100.))),
+            point(px(80.), px(-30.))
+        );
+        assert_eq!(
+            remap_pan_offset(pan_offset, size(px(0.), px(0.)), old_size),
+            pan_offset
+        );
+    }
+}
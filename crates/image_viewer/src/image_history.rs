@@ -0,0 +1,287 @@
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use git::repository::FileHistoryEntry;
+use gpui::{
+    AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model, Render,
+    Subscription, Task, View, ViewContext, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use project::ImageItem;
+use time::{OffsetDateTime, UtcOffset};
+use ui::{prelude::*, ListItem, ListItemSpacing, Tooltip};
+use workspace::{notifications::DetachAndPromptErr, ModalView, Workspace};
+
+use crate::{ImageCompareView, ImageView, ShowImageHistory};
+
+/// How many commits are listed at most.
+const HISTORY_LIMIT: usize = 500;
+
+/// A modal listing the commits that changed the active image.
+pub struct ImageHistory {
+    picker: View<Picker<ImageHistoryDelegate>>,
+    _subscription: Subscription,
+}
+
+impl ImageHistory {
+    pub fn register(workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>) {
+        workspace.register_action(Self::toggle);
+    }
+
+    fn toggle(workspace: &mut Workspace, _: &ShowImageHistory, cx: &mut ViewContext<Workspace>) {
+        let Some(image_view) = workspace.active_item_as::<ImageView>(cx) else {
+            return;
+        };
+        let image_item = image_view.read(cx).image_item.clone();
+        let load_history = workspace
+            .project()
+            .read(cx)
+            .image_store()
+            .update(cx, |image_store, cx| {
+                image_store.load_image_history(&image_item, HISTORY_LIMIT, cx)
+            });
+
+        cx.spawn(|workspace, mut cx| async move {
+            let history = load_history.await?;
+            anyhow::ensure!(!history.is_empty(), "The image has no git history");
+            workspace.update(&mut cx, |workspace, cx| {
+                let delegate =
+                    ImageHistoryDelegate::new(cx.view().downgrade(), image_item, history);
+                workspace.toggle_modal(cx, |cx| ImageHistory::new(delegate, cx));
+            })
+        })
+        .detach_and_prompt_err("Failed to load image history", cx, |_, _| None);
+    }
+
+    fn new(delegate: ImageHistoryDelegate, cx: &mut ViewContext<Self>) -> Self {
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| cx.emit(DismissEvent));
+        Self {
+            picker,
+            _subscription,
+        }
+    }
+}
+
+impl ModalView for ImageHistory {}
+impl EventEmitter<DismissEvent> for ImageHistory {}
+
+impl FocusableView for ImageHistory {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for ImageHistory {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+pub struct ImageHistoryDelegate {
+    workspace: WeakView<Workspace>,
+    image_item: Model<ImageItem>,
+    history: Vec<FileHistoryEntry>,
+    /// Indices into `history` of the commits matching the query.
+    matches: Vec<usize>,
+    selected_index: usize,
+    timezone: UtcOffset,
+}
+
+impl ImageHistoryDelegate {
+    fn new(
+        workspace: WeakView<Workspace>,
+        image_item: Model<ImageItem>,
+        history: Vec<FileHistoryEntry>,
+    ) -> Self {
+        let local_offset = chrono::Local::now().offset().local_minus_utc();
+        Self {
+            workspace,
+            image_item,
+            matches: (0..history.len()).collect(),
+            history,
+            selected_index: 0,
+            timezone: UtcOffset::from_whole_seconds(local_offset).unwrap_or(UtcOffset::UTC),
+        }
+    }
+
+    /// Opens the image as of the given commit, either on its own or compared against the working
+    /// tree version.
+    fn open(&self, ix: usize, compare: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(entry) = self
+            .matches
+            .get(ix)
+            .and_then(|history_ix| self.history.get(*history_ix))
+        else {
+            return;
+        };
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let sha = entry.sha;
+        let image_item = self.image_item.clone();
+        let load_image = workspace
+            .read(cx)
+            .project()
+            .read(cx)
+            .image_store()
+            .update(cx, |image_store, cx| {
+                image_store.load_committed_image(&image_item, sha, cx)
+            });
+        let fs = workspace.read(cx).project().read(cx).fs().clone();
+        let file_name = image_item
+            .read(cx)
+            .path()
+            .file_name()
+            .map(|file_name| file_name.to_os_string())
+            .unwrap_or_else(|| "image".into());
+
+        cx.spawn(|picker, mut cx| async move {
+            let image = load_image
+                .await?
+                .context("The image does not exist in this commit")?;
+            if compare {
+                workspace.update(&mut cx, |workspace, cx| {
+                    ImageCompareView::open_commit_comparison(workspace, image_item, sha, image, cx);
+                })?;
+            } else {
+                // Historical versions are written out next to each other in a temporary
+                // directory, keeping the original file name for the tab title.
+                let abs_path = std::env::temp_dir()
+                    .join("zed-image-history")
+                    .join(sha.display_short())
+                    .join(file_name);
+                if !fs.is_file(&abs_path).await {
+                    if let Some(parent) = abs_path.parent() {
+                        fs.create_dir(parent).await?;
+                    }
+                    let content = image.bytes.as_slice();
+                    futures::pin_mut!(content);
+                    fs.create_file_with(&abs_path, content).await?;
+                }
+                workspace
+                    .update(&mut cx, |workspace, cx| {
+                        workspace.open_abs_path(abs_path, false, cx)
+                    })?
+                    .await?;
+            }
+            picker.update(&mut cx, |_, cx| cx.emit(DismissEvent))
+        })
+        .detach_and_prompt_err("Failed to open image version", cx, |_, _| None);
+    }
+
+    fn format_timestamp(&self, unix_timestamp: i64) -> String {
+        match OffsetDateTime::from_unix_timestamp(unix_timestamp) {
+            Ok(timestamp) => time_format::format_localized_timestamp(
+                timestamp,
+                OffsetDateTime::now_utc(),
+                self.timezone,
+                time_format::TimestampFormat::Relative,
+            ),
+            Err(_) => String::new(),
+        }
+    }
+}
+
+impl PickerDelegate for ImageHistoryDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Search commits…".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, _: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let query = query.trim().to_lowercase();
+        self.matches = self
+            .history
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| {
+                query.is_empty()
+                    || entry.summary.to_lowercase().contains(&query)
+                    || entry.author_name.to_lowercase().contains(&query)
+                    || entry.sha.to_string().starts_with(&query)
+            })
+            .map(|(ix, _)| ix)
+            .collect();
+        self.selected_index = self
+            .selected_index
+            .min(self.matches.len().saturating_sub(1));
+        Task::ready(())
+    }
+
+    fn confirm(&mut self, secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        self.open(self.selected_index, secondary, cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let entry = self.history.get(*self.matches.get(ix)?)?;
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(
+                    v_flex()
+                        .child(Label::new(entry.summary.clone()).single_line())
+                        .child(
+                            Label::new(format!(
+                                "{} · {} · {}",
+                                entry.sha.display_short(),
+                                entry.author_name,
+                                self.format_timestamp(entry.unix_timestamp)
+                            ))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                        ),
+                )
+                .end_slot(
+                    IconButton::new(("compare-image-version", ix), IconName::Diff)
+                        .icon_size(IconSize::Small)
+                        .tooltip(|cx| Tooltip::text("Compare with Working Tree", cx))
+                        .on_click(
+                            cx.listener(move |picker, _, cx| picker.delegate.open(ix, true, cx)),
+                        ),
+                ),
+        )
+    }
+
+    fn render_header(&self, cx: &mut ViewContext<Picker<Self>>) -> Option<AnyElement> {
+        let file_name = self
+            .image_item
+            .read(cx)
+            .path()
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Some(
+            Label::new(format!("History of {file_name}"))
+                .size(LabelSize::Small)
+                .color(Color::Muted)
+                .mt_1()
+                .ml_3()
+                .into_any_element(),
+        )
+    }
+}
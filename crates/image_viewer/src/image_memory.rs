@@ -0,0 +1,199 @@
+use std::time::Duration;
+
+use gpui::{
+    actions, AppContext, EventEmitter, FocusHandle, FocusableView, Model, Task, View, ViewContext,
+    VisualContext,
+};
+use project::Project;
+use settings::Settings;
+use ui::{prelude::*, Table};
+use workspace::{item::Item, Workspace, WorkspaceId};
+
+use crate::ImageViewerSettings;
+
+actions!(debug, [OpenImageMemory]);
+
+/// How often the view is refreshed, since images are decoded and uploaded to the GPU without
+/// notifying anything.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Lists the images open in the project with the memory they use, to help find out where the
+/// memory goes after browsing many images.
+pub struct ImageMemoryView {
+    project: Model<Project>,
+    focus_handle: FocusHandle,
+    _refresh_task: Task<()>,
+}
+
+impl ImageMemoryView {
+    pub fn register(workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>) {
+        workspace.register_action(|workspace, _: &OpenImageMemory, cx| {
+            let project = workspace.project().clone();
+            let view = cx.new_view(|cx| ImageMemoryView::new(project, cx));
+            workspace.add_item_to_active_pane(Box::new(view), None, true, cx)
+        });
+    }
+
+    fn new(project: Model<Project>, cx: &mut ViewContext<Self>) -> Self {
+        let refresh_task = cx.spawn(|this, mut cx| async move {
+            loop {
+                cx.background_executor().timer(REFRESH_INTERVAL).await;
+                if this.update(&mut cx, |_, cx| cx.notify()).is_err() {
+                    break;
+                }
+            }
+        });
+        Self {
+            project,
+            focus_handle: cx.focus_handle(),
+            _refresh_task: refresh_task,
+        }
+    }
+}
+
+impl EventEmitter<()> for ImageMemoryView {}
+
+impl FocusableView for ImageMemoryView {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for ImageMemoryView {
+    type Event = ();
+
+    fn to_item_events(_: &Self::Event, _: impl FnMut(workspace::item::ItemEvent)) {}
+
+    fn tab_content_text(&self, _cx: &WindowContext) -> Option<SharedString> {
+        Some("Image Memory".into())
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: Option<WorkspaceId>,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<View<Self>> {
+        let project = self.project.clone();
+        Some(cx.new_view(|cx| Self::new(project, cx)))
+    }
+}
+
+impl Render for ImageMemoryView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let units = ImageViewerSettings::get_global(cx).units();
+        let image_store = self.project.read(cx).image_store().read(cx);
+        let cache_stats = image_store.image_cache_stats(cx).unwrap_or_default();
+        let mut images = image_store
+            .images()
+            .map(|image_item| {
+                let image_item = image_item.read(cx);
+                let project_path = image_item.project_path(cx);
+                let render_image = image_item.image.decoded_render_image(cx);
+                let decoded_size = render_image.as_ref().map(|render_image| {
+                    (0..render_image.frame_count())
+                        .map(|frame_index| {
+                            let size = render_image.size(frame_index);
+                            size.width.0 as u64 * size.height.0 as u64 * 4
+                        })
+                        .sum::<u64>()
+                });
+                let on_gpu = render_image
+                    .as_ref()
+                    .map_or(false, |render_image| cx.is_image_in_atlas(render_image));
+                let cache_hits = cache_stats
+                    .entries
+                    .iter()
+                    .find(|(path, _, _)| *path == project_path)
+                    .map_or(0, |(_, _, hits)| *hits);
+                (
+                    project_path.path.to_string_lossy().to_string(),
+                    image_item.file_size(),
+                    decoded_size,
+                    on_gpu,
+                    cache_hits,
+                )
+            })
+            .collect::<Vec<_>>();
+        images.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| b.1.cmp(&a.1)));
+
+        let file_size_total = images.iter().map(|image| image.1).sum::<u64>();
+        let decoded_total = images.iter().filter_map(|image| image.2).sum::<u64>();
+        let on_gpu_total = images
+            .iter()
+            .filter(|image| image.3)
+            .filter_map(|image| image.2)
+            .sum::<u64>();
+        let summary = format!(
+            "{} open images: {} read, {} decoded, {} on the GPU",
+            images.len(),
+            units.file_size(file_size_total),
+            units.file_size(decoded_total),
+            units.file_size(on_gpu_total),
+        );
+        let cache_summary = format!(
+            "{} of {} cached, {} hits, {} misses",
+            units.file_size(cache_stats.size as u64),
+            units.file_size(cache_stats.budget as u64),
+            cache_stats.hits,
+            cache_stats.misses,
+        );
+
+        v_flex()
+            .id("image-memory")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .overflow_y_scroll()
+            .p_4()
+            .gap_2()
+            .bg(cx.theme().colors().editor_background)
+            .child(Label::new("Open Images").size(LabelSize::Large))
+            .child(Label::new(summary).color(Color::Muted))
+            .child(
+                Table::new(vec!["Path", "File Size", "Decoded", "On GPU", "Cache Hits"])
+                    .striped()
+                    .rows(
+                        images
+                            .into_iter()
+                            .map(|(path, file_size, decoded_size, on_gpu, cache_hits)| {
+                                vec![
+                                    path,
+                                    units.file_size(file_size),
+                                    decoded_size
+                                        .map_or("—".to_string(), |size| units.file_size(size)),
+                                    if on_gpu { "Yes" } else { "No" }.to_string(),
+                                    cache_hits.to_string(),
+                                ]
+                            })
+                            .collect(),
+                    ),
+            )
+            .child(
+                Label::new("Cache of Recently Opened Images")
+                    .size(LabelSize::Large)
+                    .mt_4(),
+            )
+            .child(Label::new(cache_summary).color(Color::Muted))
+            .child(
+                Table::new(vec!["Path", "File Size", "Hits"])
+                    .striped()
+                    .rows(
+                        cache_stats
+                            .entries
+                            .into_iter()
+                            .rev()
+                            .map(|(project_path, size, hits)| {
+                                vec![
+                                    project_path.path.to_string_lossy().to_string(),
+                                    units.file_size(size as u64),
+                                    hits.to_string(),
+                                ]
+                            })
+                            .collect(),
+                    ),
+            )
+    }
+}
@@ -0,0 +1,183 @@
+use std::fmt;
+
+use anyhow::{anyhow, Result};
+use gpui::Image;
+use image::RgbaImage;
+
+/// The side length of the square windows over which SSIM is averaged.
+const SSIM_WINDOW_SIZE: u32 = 8;
+const SSIM_C1: f64 = (0.01 * 255.0) * (0.01 * 255.0);
+const SSIM_C2: f64 = (0.03 * 255.0) * (0.03 * 255.0);
+
+/// Objective similarity measurements between two images of the same size.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ImageMetrics {
+    /// Peak signal-to-noise ratio over the RGB channels, in decibels.
+    /// Infinite when both images are identical.
+    pub psnr: f64,
+    /// Mean structural similarity of the luma channel, between -1 and 1.
+    pub ssim: f64,
+}
+
+impl ImageMetrics {
+    pub fn compute(left: &Image, right: &Image) -> Result<Self> {
+        let left = image::load_from_memory(left.bytes())?.into_rgba8();
+        let right = image::load_from_memory(right.bytes())?.into_rgba8();
+        Self::compute_for_buffers(&left, &right)
+    }
+
+    pub fn compute_for_buffers(left: &RgbaImage, right: &RgbaImage) -> Result<Self> {
+        if left.dimensions() != right.dimensions() {
+            let (left_width, left_height) = left.dimensions();
+            let (right_width, right_height) = right.dimensions();
+            return Err(anyhow!(
+                "Image dimensions differ ({left_width}×{left_height} vs {right_width}×{right_height})"
+            ));
+        }
+
+        Ok(Self {
+            psnr: psnr(left, right),
+            ssim: ssim(left, right),
+        })
+    }
+}
+
+impl fmt::Display for ImageMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.psnr.is_infinite() {
+            write!(f, "PSNR: ∞ dB, SSIM: {:.4}", self.ssim)
+        } else {
+            write!(f, "PSNR: {:.2} dB, SSIM: {:.4}", self.psnr, self.ssim)
+        }
+    }
+}
+
+fn psnr(left: &RgbaImage, right: &RgbaImage) -> f64 {
+    let mut squared_error_sum = 0.0;
+    let mut sample_count = 0usize;
+    for (left, right) in left.pixels().zip(right.pixels()) {
+        for channel in 0..3 {
+            let difference = left[channel] as f64 - right[channel] as f64;
+            squared_error_sum += difference * difference;
+            sample_count += 1;
+        }
+    }
+
+    if sample_count == 0 || squared_error_sum == 0.0 {
+        return f64::INFINITY;
+    }
+
+    let mean_squared_error = squared_error_sum / sample_count as f64;
+    10.0 * (255.0 * 255.0 / mean_squared_error).log10()
+}
+
+fn ssim(left: &RgbaImage, right: &RgbaImage) -> f64 {
+    let (width, height) = left.dimensions();
+    let window_width = SSIM_WINDOW_SIZE.min(width);
+    let window_height = SSIM_WINDOW_SIZE.min(height);
+    if window_width == 0 || window_height == 0 {
+        return 1.0;
+    }
+
+    let mut ssim_sum = 0.0;
+    let mut window_count = 0usize;
+    let mut y = 0;
+    while y + window_height <= height {
+        let mut x = 0;
+        while x + window_width <= width {
+            ssim_sum += window_ssim(left, right, x, y, window_width, window_height);
+            window_count += 1;
+            x += window_width;
+        }
+        y += window_height;
+    }
+
+    ssim_sum / window_count as f64
+}
+
+fn window_ssim(
+    left: &RgbaImage,
+    right: &RgbaImage,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+) -> f64 {
+    let luma = |pixel: &image::Rgba<u8>| {
+        0.299 * pixel[0] as f64 + 0.587 * pixel[1] as f64 + 0.114 * pixel[2] as f64
+    };
+
+    let sample_count = (width * height) as f64;
+    let mut left_sum = 0.0;
+    let mut right_sum = 0.0;
+    for row in y..y + height {
+        for column in x..x + width {
+            left_sum += luma(left.get_pixel(column, row));
+            right_sum += luma(right.get_pixel(column, row));
+        }
+    }
+    let left_mean = left_sum / sample_count;
+    let right_mean = right_sum / sample_count;
+
+    let mut left_variance = 0.0;
+    let mut right_variance = 0.0;
+    let mut covariance = 0.0;
+    for row in y..y + height {
+        for column in x..x + width {
+            let left_delta = luma(left.get_pixel(column, row)) - left_mean;
+            let right_delta = luma(right.get_pixel(column, row)) - right_mean;
+            left_variance += left_delta * left_delta;
+            right_variance += right_delta * right_delta;
+            covariance += left_delta * right_delta;
+        }
+    }
+    left_variance /= sample_count;
+    right_variance /= sample_count;
+    covariance /= sample_count;
+
+    ((2.0 * left_mean * right_mean + SSIM_C1) * (2.0 * covariance + SSIM_C2))
+        / ((left_mean * left_mean + right_mean * right_mean + SSIM_C1)
+            * (left_variance + right_variance + SSIM_C2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    fn gradient(width: u32, height: u32) -> RgbaImage {
+        RgbaImage::from_fn(width, height, |x, y| {
+            Rgba([(x * 8) as u8, (y * 8) as u8, ((x + y) * 4) as u8, 255])
+        })
+    }
+
+    #[test]
+    fn test_identical_images() {
+        let image = gradient(16, 16);
+        let metrics = ImageMetrics::compute_for_buffers(&image, &image).unwrap();
+        assert!(metrics.psnr.is_infinite());
+        assert!((metrics.ssim - 1.0).abs() < 1e-9);
+        assert_eq!(metrics.to_string(), "PSNR: ∞ dB, SSIM: 1.0000");
+    }
+
+    #[test]
+    fn test_slightly_different_images() {
+        let left = gradient(16, 16);
+        let mut right = left.clone();
+        right.put_pixel(3, 3, Rgba([0, 0, 0, 255]));
+
+        let metrics = ImageMetrics::compute_for_buffers(&left, &right).unwrap();
+        assert!(metrics.psnr.is_finite());
+        assert!(metrics.psnr > 30.0);
+        assert!(metrics.ssim < 1.0);
+        assert!(metrics.ssim > 0.5);
+    }
+
+    #[test]
+    fn test_mismatched_dimensions() {
+        let error = ImageMetrics::compute_for_buffers(&gradient(16, 16), &gradient(8, 16))
+            .unwrap_err()
+            .to_string();
+        assert_eq!(error, "Image dimensions differ (16×16 vs 8×16)");
+    }
+}
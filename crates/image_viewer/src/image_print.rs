@@ -0,0 +1,241 @@
+//! Printing images. The image is laid out on a page of the configured paper size, which is then
+//! handed to the platform's print dialog, so that it comes out the same whatever scaling the
+//! dialog defaults to.
+
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context as _, Result};
+use gpui::{impl_actions, Image, RenderImage, ViewContext};
+use image::{imageops, Rgba, RgbaImage};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use util::command::new_smol_command;
+use workspace::notifications::DetachAndPromptErr;
+
+use crate::{ImageRendering, ImageView, PaperSize, PrintScale};
+
+/// Prints the image as it is shown, including unsaved edits.
+#[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema)]
+pub struct Print {
+    /// How big to print the image. Defaults to `image_viewer.print_scale`.
+    #[serde(default)]
+    pub scale: Option<PrintScale>,
+}
+
+impl_actions!(image_viewer, [Print]);
+
+/// The resolution pages are laid out at.
+const PAGE_DPI: f32 = 300.;
+/// The blank space left around the edges of pages, which most printers can't print on.
+const PAGE_MARGIN_INCHES: f32 = 0.25;
+
+/// Where an image goes on a page, in pixels of the page at `PAGE_DPI`.
+#[derive(Debug, PartialEq, Hash)]
+struct PageLayout {
+    page_size: (u32, u32),
+    /// The part of the page that is printed on, inside the margins.
+    printable_origin: (u32, u32),
+    printable_size: (u32, u32),
+    /// Where the image goes relative to the printable area. It extends past the printable area
+    /// when it is printed at an actual size larger than the page.
+    image_origin: (i64, i64),
+    image_size: (u32, u32),
+}
+
+fn layout_page(
+    image_size: (u32, u32),
+    paper: PaperSize,
+    scale: PrintScale,
+    dpi: f32,
+) -> PageLayout {
+    let (width, height) = (image_size.0.max(1) as f32, image_size.1.max(1) as f32);
+    let (paper_width, paper_height) = paper.inches();
+    // Turn the page sideways for images that are wider than they are tall.
+    let (paper_width, paper_height) = if width > height {
+        (paper_height, paper_width)
+    } else {
+        (paper_width, paper_height)
+    };
+    let page_size = (
+        (paper_width * PAGE_DPI).round() as u32,
+        (paper_height * PAGE_DPI).round() as u32,
+    );
+    let margin = (PAGE_MARGIN_INCHES * PAGE_DPI).round() as u32;
+    let printable_size = (page_size.0 - 2 * margin, page_size.1 - 2 * margin);
+
+    let factor = match scale {
+        PrintScale::FitToPage => {
+            (printable_size.0 as f32 / width).min(printable_size.1 as f32 / height)
+        }
+        PrintScale::ActualSize => PAGE_DPI / dpi.max(1.),
+    };
+    let scaled_size = (
+        ((width * factor).round() as u32).max(1),
+        ((height * factor).round() as u32).max(1),
+    );
+    PageLayout {
+        page_size,
+        printable_origin: (margin, margin),
+        printable_size,
+        image_origin: (
+            (printable_size.0 as i64 - scaled_size.0 as i64) / 2,
+            (printable_size.1 as i64 - scaled_size.1 as i64) / 2,
+        ),
+        image_size: scaled_size,
+    }
+}
+
+pub(crate) fn print(image_view: &mut ImageView, action: &Print, cx: &mut ViewContext<ImageView>) {
+    let image_item = image_view.image_item.read(cx);
+    let Some(dimensions) = image_item.edited_dimensions() else {
+        return;
+    };
+    let image = image_view
+        .edited_image
+        .clone()
+        .unwrap_or_else(|| image_item.image.clone());
+    let settings = image_view.settings(cx);
+    let layout = layout_page(
+        dimensions,
+        settings.print_paper_size,
+        action.scale.unwrap_or(settings.print_scale),
+        settings.print_dpi,
+    );
+    let filter = match image_view.rendering {
+        ImageRendering::Smooth => imageops::FilterType::CatmullRom,
+        ImageRendering::Pixelated => imageops::FilterType::Nearest,
+    };
+    let svg_renderer = cx.svg_renderer();
+
+    let render_page = cx.background_executor().spawn(async move {
+        let path = page_path(&image, &layout);
+        let page = render_page(&image.to_image_data(svg_renderer)?, &layout, filter)?;
+        std::fs::create_dir_all(path.parent().context("page path has no parent")?)?;
+        page.save_with_format(&path, image::ImageFormat::Png)?;
+        anyhow::Ok(path)
+    });
+    cx.spawn(|_, mut cx| async move {
+        let path = render_page.await?;
+        if !open_print_dialog(&path).await? {
+            // Without a print dialog to open, show the page in the system's image viewer to be
+            // printed from there.
+            cx.update(|cx| cx.open_with_system(&path))?;
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_prompt_err("Failed to print image", cx, |_, _| None);
+}
+
+/// Draws the first frame of `image` on a white page laid out by `layout`.
+fn render_page(
+    image: &RenderImage,
+    layout: &PageLayout,
+    filter: imageops::FilterType,
+) -> Result<image::RgbImage> {
+    let size = image.size(0);
+    let mut bytes = image.as_bytes(0).context("image has no frames")?.to_vec();
+    // Convert from BGRA to RGBA.
+    for pixel in bytes.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+    let decoded = RgbaImage::from_raw(size.width.0 as u32, size.height.0 as u32, bytes)
+        .context("invalid image data")?;
+    let (width, height) = layout.image_size;
+    let scaled = imageops::resize(&decoded, width, height, filter);
+
+    let (printable_width, printable_height) = layout.printable_size;
+    let mut printable = RgbaImage::from_pixel(
+        printable_width,
+        printable_height,
+        Rgba([255, 255, 255, 255]),
+    );
+    imageops::overlay(
+        &mut printable,
+        &scaled,
+        layout.image_origin.0,
+        layout.image_origin.1,
+    );
+    let (page_width, page_height) = layout.page_size;
+    let mut page = RgbaImage::from_pixel(page_width, page_height, Rgba([255, 255, 255, 255]));
+    imageops::replace(
+        &mut page,
+        &printable,
+        layout.printable_origin.0 as i64,
+        layout.printable_origin.1 as i64,
+    );
+    Ok(image::DynamicImage::ImageRgba8(page).into_rgb8())
+}
+
+/// A path in the temporary directory that is unique to the image being printed and its layout.
+fn page_path(image: &Image, layout: &PageLayout) -> PathBuf {
+    let mut hasher = DefaultHasher::new();
+    image.bytes().hash(&mut hasher);
+    layout.hash(&mut hasher);
+    std::env::temp_dir()
+        .join("zed-print")
+        .join(format!("{:x}.png", hasher.finish()))
+}
+
+/// Opens the platform's print dialog for the page at `path`, returning false on platforms
+/// without one shared by their desktops.
+async fn open_print_dialog(path: &Path) -> Result<bool> {
+    let mut command = if cfg!(target_os = "macos") {
+        let mut command = new_smol_command("osascript");
+        command
+            .args(["-e", "on run argv"])
+            .args([
+                "-e",
+                "tell application \"Preview\" to print (POSIX file (item 1 of argv)) with print dialog",
+            ])
+            .args(["-e", "end run"]);
+        command
+    } else if cfg!(target_os = "windows") {
+        let mut command = new_smol_command("powershell");
+        command.args([
+            "-NoProfile",
+            "-Command",
+            "& { Start-Process -FilePath $args[0] -Verb Print }",
+        ]);
+        command
+    } else {
+        return Ok(false);
+    };
+    let output = command.arg(path).output().await?;
+    anyhow::ensure!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_page() {
+        // A tall image fills the width of a portrait page, inside the margins.
+        let layout = layout_page((1000, 2000), PaperSize::Letter, PrintScale::FitToPage, 96.);
+        assert_eq!(layout.page_size, (2550, 3300));
+        assert_eq!(layout.printable_size, (2400, 3150));
+        assert_eq!(layout.image_size, (1575, 3150));
+        assert_eq!(layout.image_origin, (412, 0));
+
+        // A wide image is printed on a landscape page.
+        let layout = layout_page((2000, 1000), PaperSize::Letter, PrintScale::FitToPage, 96.);
+        assert_eq!(layout.page_size, (3300, 2550));
+
+        // At actual size, 96 pixels of the image make an inch.
+        let layout = layout_page((96, 192), PaperSize::A4, PrintScale::ActualSize, 96.);
+        assert_eq!(layout.image_size, (300, 600));
+
+        // Images larger than the page overflow it, centered.
+        let layout = layout_page((96, 9600), PaperSize::Letter, PrintScale::ActualSize, 96.);
+        assert_eq!(layout.image_size, (300, 30000));
+        assert_eq!(layout.image_origin.1, (3150 - 30000) / 2);
+    }
+}
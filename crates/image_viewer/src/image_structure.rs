@@ -0,0 +1,132 @@
+use gpui::{
+    point, px, quad, size, Bounds, Hsla, Image, ImageFormat, Pixels, SharedString, WindowContext,
+};
+
+/// An element of a structured image, such as a group or shape of an SVG.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImageStructureEntry {
+    pub name: SharedString,
+    pub depth: usize,
+    /// The element's bounding box, normalized to the image's width and height.
+    pub bounds: Option<Bounds<f32>>,
+}
+
+/// Lists the elements of an image in document order, for formats that have any.
+///
+/// Only SVGs are supported: layered formats like PSD cannot be decoded by the image viewer.
+pub fn image_structure(image: &Image) -> Vec<ImageStructureEntry> {
+    match image.format {
+        ImageFormat::Svg => svg_structure(&image.bytes),
+        _ => Vec::new(),
+    }
+}
+
+fn svg_structure(bytes: &[u8]) -> Vec<ImageStructureEntry> {
+    let Ok(tree) = usvg::Tree::from_data(bytes, &usvg::Options::default()) else {
+        return Vec::new();
+    };
+    let image_size = tree.size();
+    let mut entries = Vec::new();
+    let mut stack = tree
+        .root()
+        .children()
+        .iter()
+        .rev()
+        .map(|node| (node, 0))
+        .collect::<Vec<_>>();
+    while let Some((node, depth)) = stack.pop() {
+        let name = match (node.id(), node) {
+            ("", usvg::Node::Group(_)) => "group",
+            ("", usvg::Node::Path(_)) => "path",
+            ("", usvg::Node::Image(_)) => "image",
+            ("", usvg::Node::Text(_)) => "text",
+            (id, _) => id,
+        };
+        let rect = node.abs_bounding_box();
+        let bounds = (rect.width() > 0. || rect.height() > 0.).then(|| Bounds {
+            origin: point(
+                rect.x() / image_size.width(),
+                rect.y() / image_size.height(),
+            ),
+            size: size(
+                rect.width() / image_size.width(),
+                rect.height() / image_size.height(),
+            ),
+        });
+        entries.push(ImageStructureEntry {
+            name: SharedString::from(name.to_string()),
+            depth,
+            bounds,
+        });
+        if let usvg::Node::Group(group) = node {
+            stack.extend(
+                group
+                    .children()
+                    .iter()
+                    .rev()
+                    .map(|child| (child, depth + 1)),
+            );
+        }
+    }
+    entries
+}
+
+/// Outlines an element's normalized bounds over the image drawn at `image_bounds`.
+pub(crate) fn paint_structure_highlight(
+    bounds: Bounds<f32>,
+    color: Hsla,
+    image_bounds: Bounds<Pixels>,
+    cx: &mut WindowContext,
+) {
+    let highlight_bounds = Bounds {
+        origin: point(
+            image_bounds.origin.x + image_bounds.size.width * bounds.origin.x,
+            image_bounds.origin.y + image_bounds.size.height * bounds.origin.y,
+        ),
+        size: size(
+            image_bounds.size.width * bounds.size.width,
+            image_bounds.size.height * bounds.size.height,
+        ),
+    };
+    cx.paint_quad(quad(
+        highlight_bounds,
+        px(0.),
+        color.opacity(0.15),
+        px(2.),
+        color,
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_svg_structure() {
+        let svg = r#"
+            <svg xmlns="http://www.w3.org/2000/svg" width="100" height="50">
+                <g id="background" opacity="0.5">
+                    <rect id="sky" x="0" y="0" width="100" height="25" fill="blue"/>
+                    <rect x="0" y="25" width="100" height="25" fill="green"/>
+                </g>
+                <circle id="sun" cx="75" cy="12.5" r="10" fill="yellow"/>
+            </svg>
+        "#;
+        let structure = svg_structure(svg.as_bytes());
+        let names = structure
+            .iter()
+            .map(|entry| (entry.name.as_ref(), entry.depth))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [("background", 0), ("sky", 1), ("path", 1), ("sun", 0)]
+        );
+        assert_eq!(
+            structure[1].bounds,
+            Some(Bounds {
+                origin: point(0., 0.),
+                size: size(1., 0.5),
+            })
+        );
+    }
+}
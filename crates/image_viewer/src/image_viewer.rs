@@ -1,30 +1,276 @@
-use std::path::PathBuf;
+mod archive_images;
+pub mod asset_viewer;
+pub mod assets_panel;
+mod assets_panel_settings;
+mod audio_preview;
+mod color_picker;
+mod deleted_image;
+mod diagram;
+mod extension_commands;
+mod external_editor;
+mod filmstrip;
+mod font_specimen;
+pub mod golden_images_panel;
+mod golden_images_panel_settings;
+mod icon_proof;
+mod image_annotations;
+mod image_compare;
+mod image_context_menu;
+mod image_error;
+mod image_gallery;
+mod image_gestures;
+mod image_history;
+mod image_memory;
+mod image_metrics;
+mod image_print;
+mod image_structure;
+mod image_viewer_controls;
+mod image_viewer_settings;
+mod image_viewer_settings_controls;
+mod keyboard_cursor;
+mod live_reload;
+mod model_formats;
+mod model_preview;
+mod nine_patch;
+mod palette_filter;
+mod pending_image;
+mod persistence;
+mod pixel_sources;
+mod recent_images;
+mod reload_history;
+mod screenshot;
+mod similar_images;
+mod sprite_sheet;
+mod svg_preview;
+mod texture_atlas;
+mod tiff_overview;
+mod tile_preview;
 
-use anyhow::Context as _;
+use std::{
+    any::Any,
+    cell::Cell,
+    collections::{HashMap, HashSet},
+    io,
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::{Arc, LazyLock},
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Context as _};
+use assets_panel_settings::AssetsPanelSettings;
+use color_picker::ColorPicker;
+use filmstrip::{Filmstrip, FilmstripEvent};
+use golden_images_panel_settings::GoldenImagesPanelSettings;
 use gpui::{
-    canvas, div, fill, img, opaque_grey, point, size, AnyElement, AppContext, Bounds, EventEmitter,
-    FocusHandle, FocusableView, InteractiveElement, IntoElement, Model, ObjectFit, ParentElement,
-    Render, Styled, Task, View, ViewContext, VisualContext, WeakView, WindowContext,
+    actions, anchored, canvas, deferred, div, fill, img, opaque_grey, point, size, AnyElement,
+    AppContext, AsyncWindowContext, Bounds, ClickEvent, ClipboardEntry, ContentMask, Corners,
+    EntityId, EventEmitter, ExternalPaths, FocusHandle, FocusableView, Global, Hsla, Image,
+    ImageFormat, ImageSource, Img, InteractiveElement, IntoElement, KeyContext, Model, MouseButton,
+    MouseDownEvent, MouseMoveEvent, MouseUpEvent, ObjectFit, ParentElement, Pixels, PromptLevel,
+    Render, RenderImage, Rgba, Styled, Subscription, Task, TextRun, View, ViewContext,
+    VisualContext, WeakView, WindowContext,
 };
+use icon_proof::IconProof;
+use image_annotations::{
+    displayed_image_bounds, normalize_position, paint_annotations, ImageAnnotations,
+};
+use image_structure::{image_structure, paint_structure_highlight};
+use keyboard_cursor::{initial_cursor, move_cursor, CommitToolCursor, MoveToolCursor};
+use live_reload::LiveReloads;
+use nine_patch::NinePatchPreview;
 use persistence::IMAGE_VIEWER;
+use sprite_sheet::SpriteSheet;
+use texture_atlas::TextureAtlas;
 use theme::Theme;
-use ui::prelude::*;
+use ui::{prelude::*, tooltip_container, ContextMenu};
 
-use file_icons::FileIcons;
-use project::{image_store::ImageItemEvent, ImageItem, Project, ProjectPath};
-use settings::Settings;
-use util::paths::PathExt;
+use project::{
+    image_edits,
+    image_store::{is_image_path, ImageItemEvent},
+    ImageEdit, ImageItem, Project, ProjectPath, WorktreeId,
+};
+use rpc::proto::{self, PeerId};
+use settings::{Settings, SettingsLocation, SettingsStore};
+use util::{paths::PathExt, ResultExt};
 use workspace::{
-    item::{BreadcrumbText, Item, ProjectItem, SerializableItem, TabContentParams},
-    ItemId, ItemSettings, ToolbarItemLocation, Workspace, WorkspaceId,
+    item::{
+        BreadcrumbText, Dedup, FollowEvent, FollowableItem, Item, ProjectItem, SerializableItem,
+        TabContentParams, TabTooltipContent,
+    },
+    notifications::{DetachAndPromptErr, NotificationId},
+    FollowableViewRegistry, ItemId, Toast, ToolbarItemLocation, ViewId, Workspace, WorkspaceId,
 };
 
+pub use audio_preview::{AudioPreviewView, StopPlayback, TogglePlayback};
+pub use diagram::{DiagramFormat, ExportDiagram};
+pub use extension_commands::{ImageViewerCommandRegistry, ImageViewerToolbar, RunExtensionCommand};
+pub use external_editor::OpenInExternalEditor;
+pub use font_specimen::FontSpecimenView;
+pub use image_compare::{CompareMode, CompareSide, ImageCompareView};
+pub use image_gallery::{GallerySort, GallerySource, ImageGalleryView};
+pub use image_memory::{ImageMemoryView, OpenImageMemory};
+pub use image_print::Print;
+pub use image_structure::ImageStructureEntry;
+pub use image_viewer_controls::ImageViewerControls;
+pub use image_viewer_settings::{
+    ExternalImageEditor, FileSizeUnits, ImageFilter, ImageRendering, ImageViewerBackground,
+    ImageViewerDefaults, ImageViewerFileTypeSettings, ImageViewerSettings, ImageZoomMode,
+    PaperSize, PrintScale, UnitFormatter,
+};
+pub use image_viewer_settings_controls::ImageViewerSettingsControls;
+pub use model_preview::ModelPreviewView;
+pub use pending_image::PendingImageView;
+pub use pixel_sources::{
+    PixelSourceLocation, PixelSourceMap, PixelSourceProvider, PixelSourceRegistry,
+    RegionMapProvider,
+};
+pub use similar_images::SimilarImagesView;
+pub use svg_preview::SvgPreviewView;
+
+actions!(
+    image_viewer,
+    [
+        BlinkCompare,
+        CaptureScreenshot,
+        CompareWithClipboard,
+        CompareWithIndex,
+        CopyImage,
+        CopyImageAsMarkdown,
+        CopyImageMetrics,
+        CopyPathRelativeToActiveFile,
+        CopyPosixPath,
+        ClearAnnotations,
+        CycleBackground,
+        CycleCompareMode,
+        FindSimilarImages,
+        FlipReloadVersion,
+        FlipHorizontal,
+        FlipVertical,
+        NextImage,
+        OpenAsImage,
+        OpenGallery,
+        OpenImageFromClipboard,
+        OpenSvgPreviewToTheSide,
+        PreviousImage,
+        RenderDiagram,
+        RevealInFileManager,
+        RotateClockwise,
+        RotateCounterclockwise,
+        ShowImageHistory,
+        ShowNewerVersion,
+        ShowOlderVersion,
+        ShowRecentImages,
+        ToggleAnimation,
+        ZoomIn,
+        ZoomOut,
+        ZoomToActualSize,
+        ZoomToFit,
+        ToggleAnnotating,
+        ToggleEyedropper,
+        ToggleFilmstrip,
+        ToggleIconProof,
+        ToggleNinePatch,
+        ToggleReloadHistory,
+        ToggleSpriteSheet,
+        ToggleTilePreview
+    ]
+);
+
 const IMAGE_VIEWER_KIND: &str = "ImageView";
 
+/// How long the zoom, pan and panels have to stay unchanged before the view is serialized.
+const VIEW_STATE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// How much zooming in or out scales the image.
+const ZOOM_STEP: f32 = 1.25;
+const MIN_SCALE: f32 = 0.05;
+const MAX_SCALE: f32 = 64.;
+
 pub struct ImageView {
     image_item: Model<ImageItem>,
     project: Model<Project>,
     focus_handle: FocusHandle,
+    filmstrip: Option<View<Filmstrip>>,
+    /// Whether the view has been rendered. Until it is, such as for tabs restored in the
+    /// background, decoding anything besides the image itself is deferred.
+    shown: bool,
+    /// Whether to open the filmstrip once the view is shown.
+    filmstrip_pending: bool,
+    remote_id: Option<ViewId>,
+    /// The settings the view's state was last derived from, so that changing a setting only
+    /// overrides the parts of the state it affects.
+    defaults: ImageViewerDefaults,
+    background: ImageViewerBackground,
+    zoom: ImageZoomMode,
+    filter: ImageFilter,
+    rendering: ImageRendering,
+    /// A copy of the image scaled up without smoothing, shown instead of it when pixelated
+    /// rendering is enabled.
+    pixelated_image: Option<Arc<Image>>,
+    pixelate_task: Option<Task<()>>,
+    /// A copy of the image with its unsaved edits applied, such as rotations, shown instead of
+    /// it until they are saved.
+    edited_image: Option<Arc<Image>>,
+    edit_task: Option<Task<()>>,
+    context_menu: Option<(View<ContextMenu>, gpui::Point<Pixels>, Subscription)>,
+    /// How far the image was moved from the center of the view by dragging, scrolling or
+    /// pinching.
+    pan_offset: gpui::Point<Pixels>,
+    /// The dimensions of the image that `pan_offset` applies to, so that it can be remapped when
+    /// the image reloads at different ones.
+    viewport_dimensions: Option<(u32, u32)>,
+    /// The position of the pointer as of the last step of a drag moving the image.
+    pan_start: Option<gpui::Point<Pixels>>,
+    /// A reduced copy of an image that is too large to decode, for formats that can be
+    /// decoded piece by piece.
+    overview: Option<Arc<Image>>,
+    overview_task: Option<Task<()>>,
+    /// Whether the view was its pane's preview tab as of the last render.
+    preview_tab: bool,
+    /// A copy of the image decoded at a reduced resolution, shown instead of it while the view
+    /// is a preview tab.
+    preview_image: Option<Arc<RenderImage>>,
+    preview_image_task: Option<Task<()>>,
+    /// Whether animated images are playing, as opposed to paused on their current frame.
+    animating: bool,
+    pixel_grid: bool,
+    /// Whether the user chose to display the image even though it exceeds the decode limits.
+    decode_oversized: bool,
+    /// Whether dragging over the image draws annotations shared with collaborators.
+    annotating: bool,
+    /// Whether the image is shown repeated as tiles.
+    tiling: bool,
+    annotations: ImageAnnotations,
+    /// The bounds of the area the image is centered in, as of the last paint.
+    image_area_bounds: Rc<Cell<Option<Bounds<Pixels>>>>,
+    expire_annotations_task: Option<Task<()>>,
+    release_task: Option<Task<()>>,
+    /// Whether the view is in a pane, as opposed to closed or being moved to another pane.
+    in_pane: bool,
+    activate_image_task: Option<Task<Option<()>>>,
+    /// The images following the displayed one in the direction the user is flipping through
+    /// its directory, decoded ahead of time so that showing them is instant.
+    prefetched_images: Vec<Model<ImageItem>>,
+    prefetch_task: Option<Task<Option<()>>>,
+    view_state_task: Option<Task<()>>,
+    /// The elements of structured images, such as SVGs.
+    structure: Vec<ImageStructureEntry>,
+    /// The element of `structure` whose bounds are outlined over the image.
+    highlighted_structure_entry: Option<usize>,
+    structure_task: Option<Task<()>>,
+    color_picker: Option<ColorPicker>,
+    icon_proof: Option<IconProof>,
+    nine_patch: Option<NinePatchPreview>,
+    sprite_sheet: Option<SpriteSheet>,
+    /// The named regions of the image, when it is a texture atlas described by a file next to it.
+    texture_atlas: Option<TextureAtlas>,
+    texture_atlas_task: Option<Task<()>>,
+    live_reloads: LiveReloads,
+    live_reload_task: Option<Task<()>>,
+    reload_history: ReloadHistory,
+    _image_subscription: Subscription,
+    _subscriptions: Vec<Subscription>,
 }
 
 impl ImageView {
@@ -33,12 +279,1219 @@ impl ImageView {
         project: Model<Project>,
         cx: &mut ViewContext<Self>,
     ) -> Self {
-        cx.subscribe(&image_item, Self::on_image_event).detach();
-        Self {
+        let image_subscription = cx.subscribe(&image_item, Self::on_image_event);
+        let subscriptions = vec![
+            cx.subscribe(&project, Self::on_project_event),
+            // Make sure collaborators don't keep seeing our pointer once the view is gone.
+            cx.on_release(|this, _, cx| {
+                let project_path = this.image_item.read(cx).project_path(cx);
+                this.project
+                    .read(cx)
+                    .update_image_annotations(&project_path, None, Vec::new());
+            }),
+            cx.observe_global::<SettingsStore>(Self::apply_settings),
+        ];
+        let settings = image_settings(&image_item, cx);
+        let defaults = settings.defaults_for_path(
+            image_item.read(cx).path(),
+            settings.should_reduce_motion(cx),
+        );
+        let viewport_dimensions = image_item.read(cx).edited_dimensions();
+        let reload_history = ReloadHistory::new(image_item.read(cx).image.clone());
+        let mut this = Self {
             image_item,
             project,
             focus_handle: cx.focus_handle(),
+            filmstrip: None,
+            shown: false,
+            filmstrip_pending: false,
+            remote_id: None,
+            defaults,
+            background: defaults.background,
+            zoom: defaults.zoom,
+            filter: defaults.filter,
+            rendering: defaults.rendering,
+            pixelated_image: None,
+            pixelate_task: None,
+            edited_image: None,
+            edit_task: None,
+            context_menu: None,
+            pan_offset: gpui::Point::default(),
+            viewport_dimensions,
+            pan_start: None,
+            overview: None,
+            overview_task: None,
+            preview_tab: false,
+            preview_image: None,
+            preview_image_task: None,
+            animating: defaults.autoplay_animations,
+            pixel_grid: defaults.pixel_grid,
+            decode_oversized: false,
+            annotating: false,
+            tiling: false,
+            annotations: ImageAnnotations::default(),
+            image_area_bounds: Rc::default(),
+            expire_annotations_task: None,
+            release_task: None,
+            in_pane: false,
+            activate_image_task: None,
+            prefetched_images: Vec::new(),
+            prefetch_task: None,
+            view_state_task: None,
+            structure: Vec::new(),
+            highlighted_structure_entry: None,
+            structure_task: None,
+            color_picker: None,
+            icon_proof: None,
+            nine_patch: None,
+            sprite_sheet: None,
+            texture_atlas: None,
+            texture_atlas_task: None,
+            live_reloads: LiveReloads::default(),
+            live_reload_task: None,
+            reload_history,
+            _image_subscription: image_subscription,
+            _subscriptions: subscriptions,
+        };
+        this.register_open_view(cx);
+        this
+    }
+
+    /// Records the view in the registry of the views showing its image.
+    fn register_open_view(&self, cx: &mut ViewContext<Self>) {
+        let view = cx.view().downgrade();
+        cx.default_global::<OpenImageViews>()
+            .0
+            .entry(self.image_item.entity_id())
+            .or_default()
+            .push(view);
+    }
+
+    /// Whether another view in a pane shows the same image, in which case its memory can't be
+    /// freed yet.
+    fn image_shown_elsewhere(&self, cx: &mut ViewContext<Self>) -> bool {
+        let views = cx.default_global::<OpenImageViews>();
+        views.0.retain(|_, views| {
+            views.retain(|view| view.upgrade().is_some());
+            !views.is_empty()
+        });
+        let views = views
+            .0
+            .get(&self.image_item.entity_id())
+            .cloned()
+            .unwrap_or_default();
+        let this = cx.view().entity_id();
+        views
+            .into_iter()
+            .filter_map(|view| view.upgrade())
+            .filter(|view| view.entity_id() != this)
+            .any(|view| {
+                let view = view.read(cx);
+                view.in_pane && view.image_item == self.image_item
+            })
+    }
+
+    /// Frees the memory used to draw the image once the view has stayed hidden for as long as
+    /// configured. Rendering the view again cancels this, and redraws the image from scratch if
+    /// it was already freed.
+    fn schedule_release(&mut self, cx: &mut ViewContext<Self>) {
+        let minutes = self.settings(cx).release_hidden_images_after;
+        if minutes == 0 {
+            return;
+        }
+        self.release_task = Some(cx.spawn(|this, mut cx| async move {
+            cx.background_executor()
+                .timer(Duration::from_secs(minutes * 60))
+                .await;
+            this.update(&mut cx, |this, cx| this.release(cx)).ok();
+        }));
+    }
+
+    /// Frees the memory used to draw the image and the copies of it made by the view.
+    fn release(&mut self, cx: &mut ViewContext<Self>) {
+        let image = self.image_item.read(cx).image.clone();
+        image.release_render_image(cx);
+        if let Some(pixelated_image) = self.pixelated_image.clone() {
+            pixelated_image.release_render_image(cx);
+        }
+        if let Some(edited_image) = self.edited_image.clone() {
+            edited_image.release_render_image(cx);
+        }
+        if let Some(preview_image) = self.preview_image.clone() {
+            cx.drop_image(preview_image).log_err();
+        }
+        icon_proof::release_icons(self, cx);
+        self.release_prefetched_images(Vec::new(), cx);
+    }
+
+    /// Starts the work deferred until the view is first rendered.
+    fn show(&mut self, cx: &mut ViewContext<Self>) {
+        self.shown = true;
+        self.update_structure(cx);
+        texture_atlas::load_texture_atlas(self, cx);
+        nine_patch::update_nine_patch(self, cx);
+        self.update_edited_image(cx);
+        self.update_pixelated_image(cx);
+        self.update_overview(cx);
+        self.update_preview_image(cx);
+        if std::mem::take(&mut self.filmstrip_pending) {
+            self.set_filmstrip_open(true, cx);
+        }
+    }
+
+    pub fn structure(&self) -> &[ImageStructureEntry] {
+        &self.structure
+    }
+
+    pub fn highlighted_structure_entry(&self) -> Option<usize> {
+        self.highlighted_structure_entry
+    }
+
+    /// Outlines the bounds of the given element of [`Self::structure`] over the image.
+    pub fn highlight_structure_entry(&mut self, ix: Option<usize>, cx: &mut ViewContext<Self>) {
+        if self.highlighted_structure_entry != ix {
+            self.highlighted_structure_entry = ix;
+            cx.notify();
+        }
+    }
+
+    fn update_structure(&mut self, cx: &mut ViewContext<Self>) {
+        if !self.shown {
+            return;
+        }
+        let image = self.image_item.read(cx).image.clone();
+        self.structure_task = Some(cx.spawn(|this, mut cx| async move {
+            let structure = cx
+                .background_executor()
+                .spawn(async move { image_structure(&image) })
+                .await;
+            this.update(&mut cx, |this, cx| {
+                if this.structure != structure {
+                    this.structure = structure;
+                    this.highlighted_structure_entry = None;
+                    cx.notify();
+                }
+            })
+            .ok();
+        }));
+    }
+
+    /// Scales the image up without smoothing when pixelated rendering is enabled, since images
+    /// are always drawn with linear sampling.
+    fn update_pixelated_image(&mut self, cx: &mut ViewContext<Self>) {
+        self.pixelated_image = None;
+        self.pixelate_task = None;
+        if !self.shown {
+            return;
+        }
+        let ImageZoomMode::Scale(scale) = self.zoom else {
+            return;
+        };
+        let image_item = self.image_item.read(cx);
+        let image = self
+            .edited_image
+            .clone()
+            .unwrap_or_else(|| image_item.image.clone());
+        let factor = scale.ceil() as u32;
+        if self.rendering != ImageRendering::Pixelated
+            || factor < 2
+            || matches!(image.format, ImageFormat::Svg | ImageFormat::Gif)
+        {
+            return;
+        }
+        let Some((width, height)) = image_item.edited_dimensions() else {
+            return;
+        };
+        let scaled_megapixels =
+            width as f64 * factor as f64 * height as f64 * factor as f64 / 1_000_000.;
+        if scaled_megapixels > self.settings(cx).max_decoded_megapixels as f64 {
+            return;
+        }
+        self.pixelate_task = Some(cx.spawn(|this, mut cx| async move {
+            let pixelated_image = cx
+                .background_executor()
+                .spawn(async move { pixelate(&image, factor) })
+                .await
+                .log_err();
+            this.update(&mut cx, |this, cx| {
+                this.pixelated_image = pixelated_image.map(Arc::new);
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    fn update_edited_image(&mut self, cx: &mut ViewContext<Self>) {
+        self.edit_task = None;
+        let image_item = self.image_item.read(cx);
+        if !self.shown || image_item.edits().is_empty() {
+            if self.edited_image.take().is_some() {
+                self.update_pixelated_image(cx);
+            }
+            icon_proof::update_icon_proof(self, cx);
+            return;
+        }
+        let image = image_item.image.clone();
+        let edits = image_item.edits().to_vec();
+        // The previous copy stays visible until the new one is ready.
+        self.edit_task = Some(cx.spawn(|this, mut cx| async move {
+            let edited_image = cx
+                .background_executor()
+                .spawn(async move { image_edits::edited_image(&image, &edits) })
+                .await
+                .log_err();
+            this.update(&mut cx, |this, cx| {
+                this.edited_image = edited_image;
+                this.update_pixelated_image(cx);
+                icon_proof::update_icon_proof(this, cx);
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    /// Decodes an overview of TIFF images that exceed the decode limits, which can be read one
+    /// strip or tile at a time without decoding the whole image.
+    fn update_overview(&mut self, cx: &mut ViewContext<Self>) {
+        self.overview = None;
+        self.overview_task = None;
+        let image = self.image_item.read(cx).image.clone();
+        if !self.shown
+            || image.format != ImageFormat::Tiff
+            || self.exceeded_decode_limit(cx).is_none()
+        {
+            return;
+        }
+        let max_pixels = (self.settings(cx).max_decoded_megapixels * 1_000_000.) as u64;
+        self.overview_task = Some(cx.spawn(|this, mut cx| async move {
+            let overview = cx
+                .background_executor()
+                .spawn(async move { tiff_overview::decode_overview(&image.bytes, max_pixels) })
+                .await
+                .log_err();
+            this.update(&mut cx, |this, cx| {
+                this.overview = overview.map(Arc::new);
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    /// Whether the view is its pane's preview tab, which the next file opened in the pane
+    /// replaces.
+    fn is_preview_tab(&self, cx: &mut ViewContext<Self>) -> bool {
+        let Some(workspace) = window_workspace(cx) else {
+            return false;
+        };
+        let this = cx.view().clone();
+        workspace.read(cx).pane_for(&this).map_or(false, |pane| {
+            pane.read(cx).is_active_preview_item(this.entity_id())
+        })
+    }
+
+    /// Decodes large images at a reduced resolution while the view is a preview tab, since
+    /// those are mostly skimmed through. The image is decoded in full once the tab is kept open
+    /// or zoomed into.
+    fn update_preview_image(&mut self, cx: &mut ViewContext<Self>) {
+        if let Some(preview_image) = self.preview_image.take() {
+            cx.drop_image(preview_image).log_err();
+        }
+        self.preview_image_task = None;
+        if !self.shown
+            || !self.preview_tab
+            || !matches!(self.zoom, ImageZoomMode::Fit | ImageZoomMode::Fill)
+            || self.exceeded_decode_limit(cx).is_some()
+        {
+            return;
+        }
+        let max_size = self.settings(cx).preview_tab_max_size;
+        let image_item = self.image_item.read(cx);
+        let image = image_item.image.clone();
+        // Animations are only shown in full, and there's nothing to save once the image has
+        // been decoded in full anyway.
+        if max_size == 0
+            || matches!(image.format, ImageFormat::Svg | ImageFormat::Gif)
+            || image.decoded_render_image(cx).is_some()
+        {
+            return;
+        }
+        let Some((width, height)) = image_item.dimensions() else {
+            return;
+        };
+        if width.max(height) <= max_size {
+            return;
+        }
+        self.preview_image_task = Some(cx.spawn(|this, mut cx| async move {
+            let preview_image = cx
+                .background_executor()
+                .spawn(async move { image.to_downscaled_image_data(max_size) })
+                .await
+                .log_err();
+            this.update(&mut cx, |this, cx| {
+                // Decode the image in full if it couldn't be decoded at a reduced resolution.
+                if preview_image.is_none() {
+                    this.preview_image_task = None;
+                }
+                this.preview_image = preview_image;
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    /// The root of the image's worktree, and its path relative to it.
+    fn stored_path(&self, cx: &AppContext) -> Option<(PathBuf, PathBuf)> {
+        let image_item = self.image_item.read(cx);
+        if !image_item.has_project_file() {
+            return None;
+        }
+        asset_viewer::stored_path(self.project.read(cx), &image_item.project_path(cx), cx)
+    }
+
+    /// Adds the image to the workspace's recently viewed images.
+    fn record_recent_image(&self, workspace_id: Option<WorkspaceId>, cx: &AppContext) {
+        if let Some((workspace_id, (worktree_root, image_path))) =
+            workspace_id.zip(self.stored_path(cx))
+        {
+            recent_images::record(workspace_id, worktree_root, image_path, cx);
+        }
+    }
+
+    /// The image viewer settings for this image, including the ones of its worktree.
+    fn settings<'a>(&self, cx: &'a AppContext) -> &'a ImageViewerSettings {
+        image_settings(&self.image_item, cx)
+    }
+
+    fn abs_path(&self, cx: &AppContext) -> Option<PathBuf> {
+        let image_item = self.image_item.read(cx);
+        if !image_item.has_project_file() {
+            return None;
         }
+        let project_path = image_item.project_path(cx);
+        self.project.read(cx).absolute_path(&project_path, cx)
+    }
+
+    /// Replaces the displayed image, keeping this view (and its tab) in place.
+    ///
+    /// `local` is false when the change comes from a followed collaborator.
+    fn set_image_item(
+        &mut self,
+        image_item: Model<ImageItem>,
+        local: bool,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.clear_annotations(&ClearAnnotations, cx);
+        self.annotations.clear_remote();
+        self._image_subscription = cx.subscribe(&image_item, Self::on_image_event);
+        self.image_item = image_item;
+        self.pan_offset = gpui::Point::default();
+        self.viewport_dimensions = self.image_item.read(cx).edited_dimensions();
+        self.reload_history = ReloadHistory::new(self.image_item.read(cx).image.clone());
+        self.register_open_view(cx);
+        self.update_structure(cx);
+        texture_atlas::load_texture_atlas(self, cx);
+        self.update_pixelated_image(cx);
+        self.update_overview(cx);
+        self.update_preview_image(cx);
+        if local {
+            let workspace_id =
+                window_workspace(cx).and_then(|workspace| workspace.read(cx).database_id());
+            self.record_recent_image(workspace_id, cx);
+        }
+        if let Some(filmstrip) = self.filmstrip.as_ref() {
+            let project_path = self.image_item.read(cx).project_path(cx);
+            filmstrip.update(cx, |filmstrip, cx| filmstrip.set_current(project_path, cx));
+        }
+        sprite_sheet::load_sidecar(self, cx);
+        nine_patch::update_nine_patch(self, cx);
+        cx.emit(ImageViewEvent::ImageChanged { local });
+        cx.notify();
+    }
+
+    /// Why the image is too large to be decoded without the user asking for it, if it is.
+    fn exceeded_decode_limit(&self, cx: &AppContext) -> Option<String> {
+        decode_limit_exceeded(self.image_item.read(cx), self.settings(cx))
+    }
+
+    /// How much the image is currently scaled, as of the last paint.
+    fn current_scale(&self, cx: &AppContext) -> Option<f32> {
+        let (width, height) = self.image_item.read(cx).edited_dimensions()?;
+        let area_bounds = self.image_area_bounds.get()?;
+        let image_bounds = displayed_image_bounds(area_bounds, (width, height), self.zoom);
+        Some(image_bounds.size.width.0 / width.max(1) as f32)
+    }
+
+    fn zoom_in(&mut self, _: &ZoomIn, cx: &mut ViewContext<Self>) {
+        let scale = self.current_scale(cx).unwrap_or(1.) * ZOOM_STEP;
+        self.set_zoom(ImageZoomMode::Scale(scale.min(MAX_SCALE)), cx);
+    }
+
+    fn zoom_out(&mut self, _: &ZoomOut, cx: &mut ViewContext<Self>) {
+        let scale = self.current_scale(cx).unwrap_or(1.) / ZOOM_STEP;
+        self.set_zoom(ImageZoomMode::Scale(scale.max(MIN_SCALE)), cx);
+    }
+
+    fn zoom_to_actual_size(&mut self, _: &ZoomToActualSize, cx: &mut ViewContext<Self>) {
+        self.set_zoom(ImageZoomMode::ActualSize, cx);
+    }
+
+    fn zoom_to_fit(&mut self, _: &ZoomToFit, cx: &mut ViewContext<Self>) {
+        self.set_zoom(ImageZoomMode::Fit, cx);
+    }
+
+    fn set_zoom(&mut self, zoom: ImageZoomMode, cx: &mut ViewContext<Self>) {
+        if self.zoom == zoom {
+            return;
+        }
+        self.zoom = zoom;
+        if !matches!(zoom, ImageZoomMode::Scale(_)) {
+            self.pan_offset = gpui::Point::default();
+        }
+        self.update_pixelated_image(cx);
+        self.update_preview_image(cx);
+        self.view_state_changed(cx);
+        cx.notify();
+    }
+
+    /// Lets the workspace serialize the view once its state stops changing, rather than after
+    /// each step of a zoom or drag.
+    fn view_state_changed(&mut self, cx: &mut ViewContext<Self>) {
+        self.view_state_task = Some(cx.spawn(|this, mut cx| async move {
+            cx.background_executor().timer(VIEW_STATE_DEBOUNCE).await;
+            this.update(&mut cx, |_, cx| cx.emit(ImageViewEvent::ViewStateChanged))
+                .ok();
+        }));
+    }
+
+    /// Previews changed settings on the open image, leaving the state the user changed through
+    /// the view alone unless the corresponding setting changed too.
+    fn apply_settings(&mut self, cx: &mut ViewContext<Self>) {
+        let settings = self.settings(cx);
+        let defaults = settings.defaults_for_path(
+            self.image_item.read(cx).path(),
+            settings.should_reduce_motion(cx),
+        );
+        let previous = std::mem::replace(&mut self.defaults, defaults);
+        if previous == defaults {
+            return;
+        }
+        if previous.background != defaults.background {
+            self.background = defaults.background;
+        }
+        if previous.filter != defaults.filter {
+            self.filter = defaults.filter;
+        }
+        if previous.autoplay_animations != defaults.autoplay_animations {
+            self.animating = defaults.autoplay_animations;
+        }
+        if previous.pixel_grid != defaults.pixel_grid {
+            self.pixel_grid = defaults.pixel_grid;
+        }
+        if previous.rendering != defaults.rendering {
+            self.rendering = defaults.rendering;
+            self.update_pixelated_image(cx);
+        }
+        if previous.zoom != defaults.zoom {
+            self.set_zoom(defaults.zoom, cx);
+        }
+        cx.notify();
+    }
+
+    /// Records an edit to the image, which is shown right away and applied to its file when it
+    /// is saved.
+    fn edit_image(&mut self, edit: ImageEdit, cx: &mut ViewContext<Self>) {
+        if self.image_item.read(cx).is_in_archive() {
+            return;
+        }
+        self.image_item
+            .update(cx, |image_item, cx| image_item.edit(edit, cx));
+    }
+
+    fn rotate_clockwise(&mut self, _: &RotateClockwise, cx: &mut ViewContext<Self>) {
+        self.edit_image(ImageEdit::Rotate { quarter_turns: 1 }, cx);
+    }
+
+    fn rotate_counterclockwise(&mut self, _: &RotateCounterclockwise, cx: &mut ViewContext<Self>) {
+        self.edit_image(ImageEdit::Rotate { quarter_turns: 3 }, cx);
+    }
+
+    fn flip_horizontal(&mut self, _: &FlipHorizontal, cx: &mut ViewContext<Self>) {
+        self.edit_image(ImageEdit::Flip { horizontal: true }, cx);
+    }
+
+    fn flip_vertical(&mut self, _: &FlipVertical, cx: &mut ViewContext<Self>) {
+        self.edit_image(ImageEdit::Flip { horizontal: false }, cx);
+    }
+
+    fn toggle_animation(&mut self, _: &ToggleAnimation, cx: &mut ViewContext<Self>) {
+        self.animating = !self.animating;
+        cx.notify();
+    }
+
+    /// Switches to the next background, such as the high contrast one for images that are hard
+    /// to see against the default one. The configured background applies again to new views.
+    fn cycle_background(&mut self, _: &CycleBackground, cx: &mut ViewContext<Self>) {
+        self.background = self.background.next();
+        cx.notify();
+    }
+
+    fn toggle_annotating(&mut self, _: &ToggleAnnotating, cx: &mut ViewContext<Self>) {
+        self.annotating = !self.annotating;
+        // A stroke started from the keyboard has no mouse button to release.
+        if !self.annotating && self.annotations.finish_stroke(Instant::now()) {
+            self.schedule_annotation_expiration(cx);
+            self.broadcast_annotations(cx);
+        }
+        cx.notify();
+    }
+
+    /// Moves the pointer over the image while annotating, drawing when a stroke was started
+    /// from the keyboard.
+    fn move_tool_cursor(&mut self, action: &MoveToolCursor, cx: &mut ViewContext<Self>) {
+        if !self.annotating {
+            cx.propagate();
+            return;
+        }
+        let position = self
+            .annotations
+            .local()
+            .pointer
+            .unwrap_or_else(initial_cursor);
+        let position = move_cursor(position, action);
+        let mut changed = self.annotations.set_pointer(Some(position));
+        if self.annotations.is_drawing() {
+            changed |= self.annotations.extend_stroke(position);
+        }
+        if changed {
+            self.broadcast_annotations(cx);
+            cx.notify();
+        }
+    }
+
+    /// Starts a stroke at the pointer while annotating, or finishes the one being drawn.
+    fn commit_tool_cursor(&mut self, _: &CommitToolCursor, cx: &mut ViewContext<Self>) {
+        if !self.annotating {
+            cx.propagate();
+            return;
+        }
+        if self.annotations.is_drawing() {
+            if self.annotations.finish_stroke(Instant::now()) {
+                self.schedule_annotation_expiration(cx);
+            }
+        } else {
+            let position = self
+                .annotations
+                .local()
+                .pointer
+                .unwrap_or_else(initial_cursor);
+            self.annotations.set_pointer(Some(position));
+            self.annotations.start_stroke(position);
+        }
+        self.broadcast_annotations(cx);
+        cx.notify();
+    }
+
+    /// The key context of the view, which tells whether a tool is taking the arrow keys.
+    fn key_context(&self) -> KeyContext {
+        let mut key_context = KeyContext::new_with_defaults();
+        key_context.add("ImageViewer");
+        if self.annotating {
+            key_context.add("tool_active");
+        }
+        if self.reload_history.open {
+            key_context.add("reload_history");
+        }
+        key_context
+    }
+
+    fn clear_annotations(&mut self, _: &ClearAnnotations, cx: &mut ViewContext<Self>) {
+        self.annotations.clear_local();
+        self.expire_annotations_task = None;
+        self.broadcast_annotations(cx);
+        cx.notify();
+    }
+
+    fn broadcast_annotations(&self, cx: &AppContext) {
+        let project_path = self.image_item.read(cx).project_path(cx);
+        let (pointer, strokes) = self.annotations.local_to_proto();
+        self.project
+            .read(cx)
+            .update_image_annotations(&project_path, pointer, strokes);
+    }
+
+    /// Maps a window position to a position normalized to the displayed image.
+    fn image_position(
+        &self,
+        position: gpui::Point<Pixels>,
+        cx: &AppContext,
+    ) -> Option<gpui::Point<f32>> {
+        let area_bounds = self.image_area_bounds.get()?;
+        let dimensions = self.image_item.read(cx).edited_dimensions()?;
+        normalize_position(
+            position,
+            image_gestures::panned(
+                displayed_image_bounds(area_bounds, dimensions, self.zoom),
+                self.pan_offset,
+            ),
+        )
+    }
+
+    fn on_image_mouse_move(&mut self, event: &MouseMoveEvent, cx: &mut ViewContext<Self>) {
+        image_gestures::drag_pan(self, event, cx);
+        texture_atlas::update_hovered_region(self, event.position, cx);
+        let position = self.image_position(event.position, cx);
+        let mut changed = self.annotations.set_pointer(position);
+        if let Some(position) = position.filter(|_| event.dragging()) {
+            changed |= self.annotations.extend_stroke(position);
+        }
+        if changed {
+            self.broadcast_annotations(cx);
+            cx.notify();
+        }
+    }
+
+    fn on_image_mouse_down(&mut self, event: &MouseDownEvent, cx: &mut ViewContext<Self>) {
+        if color_picker::sample_at(self, event.position, cx) {
+            return;
+        }
+        if pixel_sources::reveal_source_at(self, event, cx) {
+            return;
+        }
+        if !self.annotating {
+            image_gestures::start_pan(self, event, cx);
+            return;
+        }
+        if let Some(position) = self.image_position(event.position, cx) {
+            self.annotations.start_stroke(position);
+            self.broadcast_annotations(cx);
+            cx.notify();
+        }
+    }
+
+    fn on_image_mouse_up(&mut self, _: &MouseUpEvent, cx: &mut ViewContext<Self>) {
+        self.pan_start = None;
+        if self.annotations.finish_stroke(Instant::now()) {
+            self.schedule_annotation_expiration(cx);
+        }
+    }
+
+    fn schedule_annotation_expiration(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(expires_at) = self.annotations.next_expiration() else {
+            self.expire_annotations_task = None;
+            return;
+        };
+        let delay = expires_at.saturating_duration_since(Instant::now());
+        self.expire_annotations_task = Some(cx.spawn(|this, mut cx| async move {
+            cx.background_executor().timer(delay).await;
+            this.update(&mut cx, |this, cx| {
+                if this.annotations.expire_strokes(Instant::now()) {
+                    this.broadcast_annotations(cx);
+                    cx.notify();
+                }
+                this.schedule_annotation_expiration(cx);
+            })
+            .ok();
+        }));
+    }
+
+    fn on_project_event(
+        &mut self,
+        _: Model<Project>,
+        event: &project::Event,
+        cx: &mut ViewContext<Self>,
+    ) {
+        match event {
+            project::Event::ImageAnnotationsUpdated {
+                peer_id,
+                annotations,
+            } => {
+                let project_path = self.image_item.read(cx).project_path(cx);
+                if project_path.worktree_id.to_proto() == annotations.worktree_id
+                    && project_path.path.as_ref() == Path::new(&annotations.path)
+                {
+                    self.annotations.apply_remote(*peer_id, annotations);
+                    cx.notify();
+                }
+            }
+            project::Event::CollaboratorLeft(peer_id) => {
+                if self.annotations.remove_peer(*peer_id) {
+                    cx.notify();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn render_decode_error(
+        &self,
+        error: SharedString,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let image_item = self.image_item.read(cx);
+        let details = image_error::ImageErrorDetails {
+            file_name: image_item
+                .path()
+                .file_name()
+                .map(|file_name| file_name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            format: Some(image_item.format().extension().to_uppercase()),
+            byte_size: Some(image_item.file_size()),
+            error,
+        };
+        let on_open_as_text = image_item.has_project_file().then(|| {
+            let project = self.project.clone();
+            let project_path = image_item.project_path(cx);
+            Box::new(move |_: &ClickEvent, cx: &mut WindowContext| {
+                if let Some(workspace) = window_workspace(cx) {
+                    workspace.update(cx, |workspace, cx| {
+                        image_error::open_as_text(
+                            project.clone(),
+                            project_path.clone(),
+                            workspace,
+                            cx,
+                        )
+                    });
+                }
+            }) as image_error::ClickHandler
+        });
+        let on_reveal = image_item.file.as_local().is_some().then(|| {
+            Box::new(cx.listener(|this, _: &ClickEvent, cx| {
+                image_context_menu::reveal_in_file_manager(this, &RevealInFileManager, cx)
+            })) as image_error::ClickHandler
+        });
+        image_error::render_error_card(
+            &details,
+            self.settings(cx).units(),
+            cx.listener(|this, _, cx| this.reload(cx)),
+            on_open_as_text,
+            on_reveal,
+            cx,
+        )
+    }
+
+    fn render_annotations(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let image_area_bounds = self.image_area_bounds.clone();
+        let dimensions = self.image_item.read(cx).edited_dimensions();
+        let zoom = self.zoom;
+        let pan_offset = self.pan_offset;
+        let pixel_grid_color = self.pixel_grid.then(|| cx.theme().colors().border_variant);
+        let players = cx.theme().players().clone();
+        let structure_highlight = self
+            .highlighted_structure_entry
+            .and_then(|ix| self.structure.get(ix)?.bounds)
+            .map(|bounds| (bounds, cx.theme().colors().text_accent));
+        let sprite_grid = self.sprite_sheet.as_ref().and_then(|sprite_sheet| {
+            let colors = (cx.theme().colors().border, cx.theme().colors().text_accent);
+            Some((sprite_sheet.grid?, sprite_sheet.current_frame, colors))
+        });
+        let atlas_regions = self.texture_atlas.as_ref().map(|texture_atlas| {
+            let colors = (
+                cx.theme().colors().border_variant,
+                cx.theme().colors().text_accent,
+            );
+            (
+                texture_atlas.regions.clone(),
+                texture_atlas.hovered_region,
+                colors,
+            )
+        });
+        let nine_patch = nine_patch::displayed_nine_patch(self, cx)
+            .map(|patch| (patch, cx.theme().colors().text_accent));
+        let collaborators = self.project.read(cx).collaborators();
+        let mut annotations = vec![(self.annotations.local().clone(), players.local().cursor)];
+        annotations.extend(self.annotations.remote().map(|(peer_id, remote)| {
+            let color = collaborators
+                .get(peer_id)
+                .map_or(players.absent(), |collaborator| {
+                    players.color_for_participant(collaborator.replica_id as u32)
+                });
+            (remote.clone(), color.cursor)
+        }));
+
+        canvas(
+            move |bounds, _| image_area_bounds.set(Some(bounds)),
+            move |bounds, _, cx| {
+                let Some(dimensions) = dimensions else {
+                    return;
+                };
+                let image_bounds = image_gestures::panned(
+                    displayed_image_bounds(bounds, dimensions, zoom),
+                    pan_offset,
+                );
+                if let Some(color) = pixel_grid_color {
+                    paint_pixel_grid(image_bounds, dimensions, bounds, color, cx);
+                }
+                if let Some((grid, current_frame, colors)) = sprite_grid {
+                    sprite_sheet::paint_sprite_grid(
+                        image_bounds,
+                        dimensions,
+                        bounds,
+                        grid,
+                        current_frame,
+                        colors,
+                        cx,
+                    );
+                }
+                if let Some((regions, hovered_region, colors)) = &atlas_regions {
+                    texture_atlas::paint_atlas_regions(
+                        image_bounds,
+                        dimensions,
+                        bounds,
+                        regions,
+                        *hovered_region,
+                        *colors,
+                        cx,
+                    );
+                }
+                if let Some((patch, color)) = &nine_patch {
+                    nine_patch::paint_nine_patch_guides(
+                        image_bounds,
+                        dimensions,
+                        patch,
+                        *color,
+                        cx,
+                    );
+                }
+                if let Some((highlight_bounds, color)) = structure_highlight {
+                    paint_structure_highlight(highlight_bounds, color, image_bounds, cx);
+                }
+                for (annotations, color) in &annotations {
+                    paint_annotations(annotations, *color, image_bounds, cx);
+                }
+            },
+        )
+        .size_full()
+        .absolute()
+        .top_0()
+        .left_0()
+    }
+
+    /// The images in the same directory as the displayed one, including it, sorted by path.
+    pub fn sibling_images(&self, cx: &AppContext) -> Vec<ProjectPath> {
+        let image_item = self.image_item.read(cx);
+        if !image_item.has_project_file() {
+            return Vec::new();
+        }
+        let project_path = image_item.project_path(cx);
+        filmstrip::sibling_images(self.project.read(cx), &project_path, cx)
+            .into_iter()
+            .map(|path| ProjectPath {
+                worktree_id: project_path.worktree_id,
+                path,
+            })
+            .collect()
+    }
+
+    fn next_image(&mut self, _: &NextImage, cx: &mut ViewContext<Self>) {
+        self.step_image(1, cx);
+    }
+
+    fn previous_image(&mut self, _: &PreviousImage, cx: &mut ViewContext<Self>) {
+        self.step_image(-1, cx);
+    }
+
+    /// Displays the sibling image `offset` positions away from the current one, wrapping
+    /// around at either end of the directory.
+    fn step_image(&mut self, offset: isize, cx: &mut ViewContext<Self>) {
+        let siblings = self.sibling_images(cx);
+        let current = self.image_item.read(cx).project_path(cx);
+        let Some(ix) = siblings.iter().position(|path| *path == current) else {
+            return;
+        };
+        let next_ix = (ix as isize + offset).rem_euclid(siblings.len() as isize) as usize;
+        if next_ix != ix {
+            self.activate_image(siblings[next_ix].clone(), cx);
+            self.prefetch_images(&siblings, next_ix, offset.signum(), cx);
+        }
+    }
+
+    /// Decodes the images after the one at `ix` in `siblings`, going in `direction`, as many as
+    /// configured and as fit in the image cache. Images that exceed the decode limits are
+    /// skipped, since they are only decoded when the user asks for it.
+    fn prefetch_images(
+        &mut self,
+        siblings: &[ProjectPath],
+        ix: usize,
+        direction: isize,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let settings = self.settings(cx);
+        let count = settings
+            .prefetch_adjacent_images
+            .min(siblings.len().saturating_sub(2));
+        let mut budget = (settings.image_cache_size as usize).saturating_mul(1024 * 1024);
+        let open_images = (1..=count as isize)
+            .map(|step| {
+                let sibling_ix =
+                    (ix as isize + step * direction).rem_euclid(siblings.len() as isize);
+                let project_path = siblings[sibling_ix as usize].clone();
+                self.project
+                    .update(cx, |project, cx| project.open_image(project_path, cx))
+            })
+            .collect::<Vec<_>>();
+
+        self.prefetch_task = Some(cx.spawn(|this, mut cx| async move {
+            let mut image_items = Vec::new();
+            for open_image in open_images {
+                image_items.extend(open_image.await.log_err());
+            }
+            this.update(&mut cx, |this, cx| {
+                let prefetched_images = image_items
+                    .into_iter()
+                    .filter(|image_item| {
+                        let settings = image_settings(image_item, cx);
+                        let image_item = image_item.read(cx);
+                        let Some((width, height)) = image_item.dimensions() else {
+                            return false;
+                        };
+                        let decoded_size = width as usize * height as usize * 4;
+                        if decoded_size > budget
+                            || decode_limit_exceeded(image_item, settings).is_some()
+                        {
+                            return false;
+                        }
+                        budget -= decoded_size;
+                        true
+                    })
+                    .collect::<Vec<_>>();
+                for image_item in &prefetched_images {
+                    let image = image_item.read(cx).image.clone();
+                    image.prefetch_render_image(cx);
+                }
+                this.release_prefetched_images(prefetched_images, cx);
+            })
+            .ok()
+        }));
+    }
+
+    /// Replaces the prefetched images, freeing the memory used by those that are neither
+    /// displayed nor prefetched again.
+    fn release_prefetched_images(
+        &mut self,
+        prefetched_images: Vec<Model<ImageItem>>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        for image_item in std::mem::replace(&mut self.prefetched_images, prefetched_images) {
+            if image_item != self.image_item && !self.prefetched_images.contains(&image_item) {
+                let image = image_item.read(cx).image.clone();
+                image.release_render_image(cx);
+            }
+        }
+    }
+
+    /// Displays another image in this view, keeping its tab in place.
+    pub fn activate_image(&mut self, project_path: ProjectPath, cx: &mut ViewContext<Self>) {
+        let open_image = self
+            .project
+            .update(cx, |project, cx| project.open_image(project_path, cx));
+        // Owned by the view so that closing it stops decoding an image it no longer needs.
+        self.activate_image_task = Some(cx.spawn(|this, mut cx| async move {
+            let image_item = open_image.await.log_err()?;
+            this.update(&mut cx, |this, cx| {
+                this.set_image_item(image_item, true, cx)
+            })
+            .ok()
+        }));
+    }
+
+    fn toggle_filmstrip(&mut self, _: &ToggleFilmstrip, cx: &mut ViewContext<Self>) {
+        self.set_filmstrip_open(self.filmstrip.is_none(), cx);
+    }
+
+    fn set_filmstrip_open(&mut self, open: bool, cx: &mut ViewContext<Self>) {
+        // Loading the thumbnails decodes the neighboring images, so wait until the view is shown.
+        if !self.shown {
+            self.filmstrip_pending = open;
+            return;
+        }
+        if open != self.filmstrip.is_some() {
+            self.view_state_changed(cx);
+        }
+        if !open {
+            self.filmstrip = None;
+        } else if self.filmstrip.is_none() {
+            let project = self.project.clone();
+            let project_path = self.image_item.read(cx).project_path(cx);
+            let filmstrip = cx.new_view(|cx| Filmstrip::new(project, project_path, cx));
+            cx.subscribe(&filmstrip, Self::on_filmstrip_event).detach();
+            self.filmstrip = Some(filmstrip);
+        }
+        cx.notify();
+    }
+
+    fn on_filmstrip_event(
+        &mut self,
+        _: View<Filmstrip>,
+        event: &FilmstripEvent,
+        cx: &mut ViewContext<Self>,
+    ) {
+        match event {
+            FilmstripEvent::Activate(project_path) => {
+                self.activate_image(project_path.clone(), cx);
+            }
+        }
+    }
+
+    /// Copies images dropped from outside the project into a destination chosen by the user
+    /// inside one of the project's worktrees, then opens the copies.
+    fn save_external_images(&mut self, paths: Vec<PathBuf>, cx: &mut ViewContext<Self>) {
+        let Some(workspace) = window_workspace(cx) else {
+            return;
+        };
+        let project = self.project.clone();
+        let fs = project.read(cx).fs().clone();
+        let start_directory = self
+            .image_item
+            .read(cx)
+            .file
+            .as_local()
+            .and_then(|file| Some(file.abs_path(cx).parent()?.to_path_buf()))
+            .unwrap_or_default();
+
+        cx.spawn(|_, mut cx| async move {
+            for source_path in paths {
+                let destination = cx.update(|cx| cx.prompt_for_new_path(&start_directory))?;
+                let Some(destination) = destination.await?? else {
+                    continue;
+                };
+                let worktree_path = project.update(&mut cx, |project, cx| {
+                    project
+                        .find_worktree(&destination, cx)
+                        .map(|(worktree, path)| ProjectPath {
+                            worktree_id: worktree.read(cx).id(),
+                            path: path.into(),
+                        })
+                })?;
+                let Some(project_path) = worktree_path else {
+                    workspace.update(&mut cx, |workspace, cx| {
+                        workspace.show_error(
+                            &anyhow!(
+                                "Cannot save {:?}: the destination must be inside the project",
+                                destination
+                            ),
+                            cx,
+                        )
+                    })?;
+                    continue;
+                };
+
+                fs.copy_file(
+                    &source_path,
+                    &destination,
+                    fs::CopyOptions {
+                        overwrite: true,
+                        ignore_if_exists: false,
+                    },
+                )
+                .await
+                .with_context(|| format!("copying {source_path:?} to {destination:?}"))?;
+                workspace
+                    .update(&mut cx, |workspace, cx| {
+                        workspace.open_path(project_path, None, true, cx)
+                    })?
+                    .await?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Reloads the image after it changed on disk. When that would discard the annotations drawn
+    /// over it, the user is asked to confirm instead.
+    ///
+    /// Images rewritten many times a second are only reported as changed a few times a second,
+    /// as configured by `reload_debounce`.
+    fn reload_changed_image(&mut self, cx: &mut ViewContext<Self>) {
+        if self.annotations.local().strokes.is_empty() {
+            self.reload(cx);
+        } else {
+            self.show_reload_conflict(cx);
+        }
+    }
+
+    /// Keeps the zoom and pan as they were when the image reloads. At the same dimensions, the
+    /// image is shown exactly as before; at different ones, the point of the image at the center
+    /// of the view stays there.
+    fn pin_viewport(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(dimensions) = self.image_item.read(cx).edited_dimensions() else {
+            return;
+        };
+        if let Some((old_dimensions, area_bounds)) = self
+            .viewport_dimensions
+            .filter(|old_dimensions| *old_dimensions != dimensions)
+            .zip(self.image_area_bounds.get())
+        {
+            self.pan_offset = image_gestures::remap_pan_offset(
+                self.pan_offset,
+                displayed_image_bounds(area_bounds, old_dimensions, self.zoom).size,
+                displayed_image_bounds(area_bounds, dimensions, self.zoom).size,
+            );
+        }
+        self.viewport_dimensions = Some(dimensions);
+    }
+
+    fn reload(&mut self, cx: &mut ViewContext<Self>) {
+        self.clear_annotations(&ClearAnnotations, cx);
+        let images = HashSet::from_iter([self.image_item.clone()]);
+        self.project
+            .update(cx, |project, cx| project.reload_images(images, cx))
+            .detach_and_log_err(cx);
+    }
+
+    /// Asks whether to keep the unsaved edits to the image, which overwrite its file once saved,
+    /// or to take the contents it was changed to on disk, discarding the edits.
+    fn resolve_conflict(&mut self, cx: &mut ViewContext<Self>) {
+        let path = self.image_item.read(cx).path();
+        let file_name = path
+            .file_name()
+            .unwrap_or_else(|| path.as_os_str())
+            .to_string_lossy()
+            .to_string();
+        let message = format!("{file_name} changed on disk while you were editing it.");
+        let detail = "Keep your edits to save them over the file, or take the file's new \
+            contents and discard your edits.";
+        let answer = cx.prompt(
+            PromptLevel::Warning,
+            &message,
+            Some(detail),
+            &["Keep Mine", "Take Theirs"],
+        );
+        cx.spawn(|this, mut cx| async move {
+            let take_theirs = answer.await? == 1;
+            this.update(&mut cx, |this, cx| {
+                if take_theirs {
+                    this.image_item
+                        .update(cx, |image_item, cx| image_item.discard_edits(cx));
+                    this.reload(cx);
+                } else {
+                    this.image_item
+                        .update(cx, |image_item, cx| image_item.keep_edits(cx));
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn show_reload_conflict(&self, cx: &mut ViewContext<Self>) {
+        let Some(workspace) = window_workspace(cx) else {
+            return;
+        };
+        let path = self.image_item.read(cx).path();
+        let file_name = path
+            .file_name()
+            .unwrap_or_else(|| path.as_os_str())
+            .to_string_lossy()
+            .to_string();
+        let this = cx.view().downgrade();
+        struct ReloadConflict;
+        let toast = Toast::new(
+            NotificationId::composite::<ReloadConflict>(("image", self.image_item.entity_id())),
+            format!("{file_name} changed on disk. Reloading it will discard your annotations."),
+        )
+        .on_click("Reload", move |cx| {
+            this.update(cx, |this, cx| this.reload(cx)).ok();
+        });
+        workspace.update(cx, |workspace, cx| workspace.show_toast(toast, cx));
     }
 
     fn on_image_event(
@@ -48,17 +1501,68 @@ impl ImageView {
         cx: &mut ViewContext<Self>,
     ) {
         match event {
-            ImageItemEvent::FileHandleChanged | ImageItemEvent::Reloaded => {
+            ImageItemEvent::FileHandleChanged => {
+                cx.emit(ImageViewEvent::TitleChanged);
+                cx.notify();
+            }
+            ImageItemEvent::Renamed { .. } => {
+                if let Some(filmstrip) = self.filmstrip.as_ref() {
+                    let project_path = self.image_item.read(cx).project_path(cx);
+                    filmstrip.update(cx, |filmstrip, cx| filmstrip.set_current(project_path, cx));
+                }
+                cx.emit(ImageViewEvent::Renamed);
+                cx.notify();
+            }
+            ImageItemEvent::Reloaded => {
+                live_reload::record_reload(self, cx);
+                reload_history::record_reload(self, cx);
+                self.pin_viewport(cx);
+                self.update_structure(cx);
+                // Tools that pack atlases write the image and its description together.
+                texture_atlas::load_texture_atlas(self, cx);
+                nine_patch::update_nine_patch(self, cx);
+                self.update_edited_image(cx);
+                self.update_pixelated_image(cx);
+                self.update_overview(cx);
+                self.update_preview_image(cx);
+                cx.emit(ImageViewEvent::TitleChanged);
+                cx.notify();
+            }
+            ImageItemEvent::ReloadNeeded => self.reload_changed_image(cx),
+            ImageItemEvent::MetadataComputed => {
+                self.pin_viewport(cx);
+                cx.notify();
+            }
+            ImageItemEvent::Conflicted => {
+                cx.emit(ImageViewEvent::TitleChanged);
+                self.resolve_conflict(cx);
+            }
+            ImageItemEvent::Edited | ImageItemEvent::Saved => {
+                self.viewport_dimensions = self.image_item.read(cx).edited_dimensions();
+                self.update_edited_image(cx);
                 cx.emit(ImageViewEvent::TitleChanged);
                 cx.notify();
             }
-            ImageItemEvent::ReloadNeeded => {}
         }
     }
 }
 
+/// The views of each image item, by the item's entity id, so that an image's memory is freed
+/// once the last view showing it is closed.
+#[derive(Default)]
+struct OpenImageViews(HashMap<EntityId, Vec<WeakView<ImageView>>>);
+
+impl Global for OpenImageViews {}
+
 pub enum ImageViewEvent {
     TitleChanged,
+    /// The image's file was renamed or moved, so the view is stored under its new path.
+    Renamed,
+    ImageChanged {
+        local: bool,
+    },
+    /// The zoom, pan or open panels changed, once they have stopped changing for a moment.
+    ViewStateChanged,
 }
 
 impl EventEmitter<ImageViewEvent> for ImageView {}
@@ -68,13 +1572,43 @@ impl Item for ImageView {
 
     fn to_item_events(event: &Self::Event, mut f: impl FnMut(workspace::item::ItemEvent)) {
         match event {
-            ImageViewEvent::TitleChanged => {
+            ImageViewEvent::TitleChanged
+            | ImageViewEvent::Renamed
+            | ImageViewEvent::ImageChanged { .. } => {
                 f(workspace::item::ItemEvent::UpdateTab);
                 f(workspace::item::ItemEvent::UpdateBreadcrumbs);
             }
+            ImageViewEvent::ViewStateChanged => {}
         }
     }
 
+    fn added_to_workspace(&mut self, workspace: &mut Workspace, cx: &mut ViewContext<Self>) {
+        self.in_pane = true;
+        self.record_recent_image(workspace.database_id(), cx);
+    }
+
+    /// Frees the image's memory as soon as the last view showing it is closed, rather than
+    /// leaving it to be freed when nothing refers to the image anymore, which other parts of
+    /// the app can keep from happening for a long time.
+    fn removed_from_pane(&mut self, cx: &mut ViewContext<Self>) {
+        self.in_pane = false;
+        // Views moved to another pane are added to it right after being removed. The view is
+        // kept alive until then, since closing it drops it before deferred work runs.
+        let view = cx.view().clone();
+        WindowContext::defer(cx, move |cx| {
+            view.update(cx, |this, cx| {
+                if !this.in_pane && !this.image_shown_elsewhere(cx) {
+                    this.release_task = None;
+                    this.release(cx);
+                }
+            })
+        });
+    }
+
+    fn deactivated(&mut self, cx: &mut ViewContext<Self>) {
+        self.schedule_release(cx);
+    }
+
     fn for_each_project_item(
         &self,
         cx: &AppContext,
@@ -87,33 +1621,127 @@ impl Item for ImageView {
         true
     }
 
+    fn is_dirty(&self, cx: &AppContext) -> bool {
+        self.image_item.read(cx).is_dirty()
+    }
+
+    fn has_conflict(&self, cx: &AppContext) -> bool {
+        self.image_item.read(cx).has_conflict()
+    }
+
+    /// Untitled images and images inside archives can only be saved as a new file.
+    fn can_save(&self, cx: &AppContext) -> bool {
+        self.image_item.read(cx).has_project_file()
+    }
+
+    fn save(
+        &mut self,
+        _format: bool,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<gpui::Result<()>> {
+        let image_item = self.image_item.clone();
+        project.update(cx, |project, cx| {
+            project
+                .image_store()
+                .update(cx, |image_store, cx| image_store.save_image(image_item, cx))
+        })
+    }
+
+    fn save_as(
+        &mut self,
+        project: Model<Project>,
+        path: ProjectPath,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<gpui::Result<()>> {
+        let image_item = self.image_item.clone();
+        project.update(cx, |project, cx| {
+            project.image_store().update(cx, |image_store, cx| {
+                image_store.save_image_as(image_item, path, cx)
+            })
+        })
+    }
+
+    fn reload(
+        &mut self,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<gpui::Result<()>> {
+        self.image_item
+            .update(cx, |image_item, cx| image_item.discard_edits(cx));
+        let images = HashSet::from_iter([self.image_item.clone()]);
+        project.update(cx, |project, cx| project.reload_images(images, cx))
+    }
+
+    /// Images dropped from outside the project are saved into it instead of being opened from
+    /// their external location.
+    fn handle_drop(&mut self, dropped: &dyn Any, cx: &mut ViewContext<Self>) -> bool {
+        let Some(external_paths) = dropped.downcast_ref::<ExternalPaths>() else {
+            return false;
+        };
+        let project = self.project.read(cx);
+        if !project.is_local() {
+            return false;
+        }
+        let paths = external_paths.paths();
+        if paths.is_empty()
+            || !paths
+                .iter()
+                .all(|path| is_image_path(path) && project.find_worktree(path, cx).is_none())
+        {
+            return false;
+        }
+
+        self.save_external_images(paths.to_vec(), cx);
+        true
+    }
+
+    /// The image's path, followed by the file it links to when it's a symlink.
     fn tab_tooltip_text(&self, cx: &AppContext) -> Option<SharedString> {
-        let abs_path = self.image_item.read(cx).file.as_local()?.abs_path(cx);
+        let abs_path = self.abs_path(cx)?;
         let file_path = abs_path.compact().to_string_lossy().to_string();
-        Some(file_path.into())
+        match self.image_item.read(cx).canonical_path() {
+            Some(canonical_path) if canonical_path.as_ref() != abs_path => {
+                let target_path = canonical_path.compact().to_string_lossy().to_string();
+                Some(format!("{file_path} → {target_path}").into())
+            }
+            _ => Some(file_path.into()),
+        }
+    }
+
+    fn tab_tooltip_content(&self, cx: &AppContext) -> Option<TabTooltipContent> {
+        let path = self.tab_tooltip_text(cx)?;
+        let image_item = self.image_item.read(cx);
+        let image = image_item.image.clone();
+        let units = self.settings(cx).units();
+        let mut details = Vec::new();
+        if let Some((width, height)) = image_item.dimensions() {
+            details.push(units.dimensions(width, height));
+        }
+        if let Some(frame_count) = image_item.frame_count().filter(|count| *count > 1) {
+            details.push(format!("{} frames", units.count(frame_count)));
+        }
+        details.push(units.file_size(image_item.file_size()));
+        let details = SharedString::from(details.join(" · "));
+        Some(TabTooltipContent::Custom(Box::new(move |cx| {
+            let path = path.clone();
+            let image = image.clone();
+            let details = details.clone();
+            cx.new_view(|_| ImageTabTooltip {
+                path,
+                image,
+                details,
+            })
+            .into()
+        })))
     }
 
     fn tab_content(&self, params: TabContentParams, cx: &WindowContext) -> AnyElement {
-        let path = self.image_item.read(cx).file.path();
-        let title = path
-            .file_name()
-            .unwrap_or_else(|| path.as_os_str())
-            .to_string_lossy()
-            .to_string();
-        Label::new(title)
-            .single_line()
-            .color(params.text_color())
-            .italic(params.preview)
-            .into_any_element()
+        asset_viewer::tab_content(self.image_item.read(cx).file.path(), params)
     }
 
     fn tab_icon(&self, cx: &WindowContext) -> Option<Icon> {
-        let path = self.image_item.read(cx).path();
-        ItemSettings::get_global(cx)
-            .file_icons
-            .then(|| FileIcons::get_icon(path, cx))
-            .flatten()
-            .map(Icon::from_path)
+        asset_viewer::tab_icon(self.image_item.read(cx).path(), cx)
     }
 
     fn breadcrumb_location(&self, _: &AppContext) -> ToolbarItemLocation {
@@ -121,12 +1749,8 @@ impl Item for ImageView {
     }
 
     fn breadcrumbs(&self, _theme: &Theme, cx: &AppContext) -> Option<Vec<BreadcrumbText>> {
-        let text = breadcrumbs_text_for_image(self.project.read(cx), self.image_item.read(cx), cx);
-        Some(vec![BreadcrumbText {
-            text,
-            highlights: None,
-            font: None,
-        }])
+        let project_path = self.image_item.read(cx).project_path(cx);
+        asset_viewer::breadcrumbs(self.project.read(cx), &project_path, cx)
     }
 
     fn clone_on_split(
@@ -137,29 +1761,142 @@ impl Item for ImageView {
     where
         Self: Sized,
     {
-        Some(cx.new_view(|cx| Self {
-            image_item: self.image_item.clone(),
-            project: self.project.clone(),
-            focus_handle: cx.focus_handle(),
-        }))
+        Some(cx.new_view(|cx| Self::new(self.image_item.clone(), self.project.clone(), cx)))
     }
 }
 
-fn breadcrumbs_text_for_image(project: &Project, image: &ImageItem, cx: &AppContext) -> String {
-    let path = image.path();
-    if project.visible_worktrees(cx).count() <= 1 {
-        return path.to_string_lossy().to_string();
+/// A tab tooltip showing a thumbnail of the image next to its path.
+struct ImageTabTooltip {
+    path: SharedString,
+    image: Arc<Image>,
+    /// The image's dimensions and file size.
+    details: SharedString,
+}
+
+impl Render for ImageTabTooltip {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        tooltip_container(cx, |el, _| {
+            el.gap_1()
+                .child(
+                    img(self.image.clone())
+                        .max_w(px(192.))
+                        .max_h(px(128.))
+                        .object_fit(ObjectFit::ScaleDown),
+                )
+                .child(Label::new(self.path.clone()))
+                .child(
+                    Label::new(self.details.clone())
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+        })
     }
+}
 
-    project
-        .worktree_for_id(image.project_path(cx).worktree_id, cx)
-        .map(|worktree| {
-            PathBuf::from(worktree.read(cx).root_name())
-                .join(path)
-                .to_string_lossy()
-                .to_string()
+/// Opens the active file in the image viewer, for images that are otherwise edited as text,
+/// like SVGs.
+fn open_as_image(workspace: &mut Workspace, _: &OpenAsImage, cx: &mut ViewContext<Workspace>) {
+    let Some(project_path) = workspace
+        .active_item(cx)
+        .and_then(|item| item.project_path(cx))
+    else {
+        return;
+    };
+    let is_image = project_path
+        .path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map_or(false, |extension| {
+            Img::extensions().contains(&extension.to_lowercase().as_str())
+        });
+    if !is_image {
+        return;
+    }
+
+    let project = workspace.project().clone();
+    let open_image = project.update(cx, |project, cx| project.open_image(project_path, cx));
+    cx.spawn(|workspace, mut cx| async move {
+        let image_item = open_image.await?;
+        workspace.update(&mut cx, |workspace, cx| {
+            let image_view = cx.new_view(|cx| ImageView::new(image_item, project, cx));
+            workspace.add_item_to_active_pane(Box::new(image_view), None, true, cx);
+        })
+    })
+    .detach_and_prompt_err("Failed to open image", cx, |_, _| None);
+}
+
+/// Opens the image in the clipboard as an untitled image.
+fn open_image_from_clipboard(
+    workspace: &mut Workspace,
+    _: &OpenImageFromClipboard,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let Some(image) = cx.read_from_clipboard().and_then(|item| {
+        item.into_entries().find_map(|entry| match entry {
+            ClipboardEntry::Image(image) => Some(image),
+            ClipboardEntry::String(_) => None,
+        })
+    }) else {
+        return;
+    };
+    open_untitled_image(workspace, image.bytes, cx);
+}
+
+/// Opens image contents that aren't backed by a file, such as terminal or REPL output, as an
+/// untitled image, which can then be saved into the project.
+pub fn open_untitled_image(
+    workspace: &mut Workspace,
+    content: Vec<u8>,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let project = workspace.project().clone();
+    let create_image = project.update(cx, |project, cx| {
+        project
+            .image_store()
+            .update(cx, |image_store, cx| image_store.create_image(content, cx))
+    });
+    cx.spawn(|workspace, mut cx| async move {
+        let image_item = create_image.await?;
+        workspace.update(&mut cx, |workspace, cx| {
+            let image_view = cx.new_view(|cx| ImageView::new(image_item, project, cx));
+            workspace.add_item_to_active_pane(Box::new(image_view), None, true, cx);
         })
-        .unwrap_or_else(|| path.to_string_lossy().to_string())
+    })
+    .detach_and_prompt_err("Failed to open image", cx, |_, _| None);
+}
+
+/// The workspace of the window the view is in.
+pub(crate) fn window_workspace(cx: &mut WindowContext) -> Option<View<Workspace>> {
+    cx.window_handle()
+        .downcast::<Workspace>()
+        .and_then(|handle| handle.root(cx).ok())
+}
+
+/// Opens the image at the given absolute path, adding an invisible worktree for it if necessary.
+pub(crate) async fn open_image_by_abs_path(
+    project: &Model<Project>,
+    abs_path: PathBuf,
+    cx: &mut AsyncWindowContext,
+) -> anyhow::Result<Model<ImageItem>> {
+    let project_path = asset_viewer::project_path_for_abs_path(project, abs_path, cx).await?;
+    project
+        .update(cx, |project, cx| project.open_image(project_path, cx))?
+        .await
+}
+
+/// Opens the image at `image_path` relative to a worktree that was rooted at `worktree_root`
+/// when the image view was serialized.
+async fn open_serialized_image(
+    project: &Model<Project>,
+    worktree_root: PathBuf,
+    image_path: PathBuf,
+    cx: &mut AsyncWindowContext,
+) -> anyhow::Result<Model<ImageItem>> {
+    let project_path =
+        asset_viewer::resolve_stored_path(project, worktree_root, image_path, cx).await?;
+    project
+        .update(cx, |project, cx| project.open_image(project_path, cx))?
+        .await
 }
 
 impl SerializableItem for ImageView {
@@ -175,28 +1912,30 @@ impl SerializableItem for ImageView {
         cx: &mut WindowContext,
     ) -> Task<gpui::Result<View<Self>>> {
         cx.spawn(|mut cx| async move {
-            let image_path = IMAGE_VIEWER
-                .get_image_path(item_id, workspace_id)?
+            let (worktree_root, image_path, filmstrip_open, zoom, pan_x, pan_y) = IMAGE_VIEWER
+                .get_image_view(item_id, workspace_id)?
                 .ok_or_else(|| anyhow::anyhow!("No image path found"))?;
-
-            let (worktree, relative_path) = project
-                .update(&mut cx, |project, cx| {
-                    project.find_or_create_worktree(image_path.clone(), false, cx)
-                })?
-                .await
-                .context("Path not found")?;
-            let worktree_id = worktree.update(&mut cx, |worktree, _cx| worktree.id())?;
-
-            let project_path = ProjectPath {
-                worktree_id,
-                path: relative_path.into(),
+            let image_item = match worktree_root {
+                Some(worktree_root) => {
+                    open_serialized_image(&project, worktree_root, image_path, &mut cx).await?
+                }
+                None => open_image_by_abs_path(&project, image_path, &mut cx).await?,
             };
 
-            let image_item = project
-                .update(&mut cx, |project, cx| project.open_image(project_path, cx))?
-                .await?;
-
-            cx.update(|cx| Ok(cx.new_view(|cx| ImageView::new(image_item, project, cx))))?
+            cx.update(|cx| {
+                Ok(cx.new_view(|cx| {
+                    let mut image_view = ImageView::new(image_item, project, cx);
+                    image_view.set_filmstrip_open(filmstrip_open, cx);
+                    if let Some(zoom) = zoom.and_then(|zoom| serde_json::from_str(&zoom).log_err())
+                    {
+                        image_view.set_zoom(zoom, cx);
+                    }
+                    if image_gestures::can_pan(image_view.zoom) {
+                        image_view.pan_offset = point(px(pan_x), px(pan_y));
+                    }
+                    image_view
+                }))
+            })?
         })
     }
 
@@ -216,19 +1955,153 @@ impl SerializableItem for ImageView {
         cx: &mut ViewContext<Self>,
     ) -> Option<Task<gpui::Result<()>>> {
         let workspace_id = workspace.database_id()?;
-        let image_path = self.image_item.read(cx).file.as_local()?.abs_path(cx);
+        let (worktree_root, image_path) = self.stored_path(cx)?;
+        let filmstrip_open = self.filmstrip.is_some() || self.filmstrip_pending;
+        let zoom = serde_json::to_string(&self.zoom).ok()?;
+        let pan_offset = self.pan_offset;
 
         Some(cx.background_executor().spawn({
             async move {
                 IMAGE_VIEWER
-                    .save_image_path(item_id, workspace_id, image_path)
+                    .save_image_view(
+                        item_id,
+                        workspace_id,
+                        worktree_root,
+                        image_path,
+                        filmstrip_open,
+                        zoom,
+                        pan_offset.x.0,
+                        pan_offset.y.0,
+                    )
                     .await
             }
         }))
     }
 
-    fn should_serialize(&self, _event: &Self::Event) -> bool {
-        false
+    fn should_serialize(&self, event: &Self::Event) -> bool {
+        matches!(
+            event,
+            ImageViewEvent::ViewStateChanged
+                | ImageViewEvent::Renamed
+                | ImageViewEvent::ImageChanged { local: true }
+        )
+    }
+}
+
+impl FollowableItem for ImageView {
+    fn remote_id(&self) -> Option<ViewId> {
+        self.remote_id
+    }
+
+    fn to_state_proto(&self, cx: &WindowContext) -> Option<proto::view::Variant> {
+        let image_item = self.image_item.read(cx);
+        if image_item.file.is_private() || !image_item.has_project_file() {
+            return None;
+        }
+        let project_path = image_item.project_path(cx);
+        Some(proto::view::Variant::ImageView(proto::view::ImageView {
+            worktree_id: project_path.worktree_id.to_proto(),
+            path: project_path.path.to_string_lossy().to_string(),
+        }))
+    }
+
+    fn from_state_proto(
+        workspace: View<Workspace>,
+        remote_id: ViewId,
+        state: &mut Option<proto::view::Variant>,
+        cx: &mut WindowContext,
+    ) -> Option<Task<gpui::Result<View<Self>>>> {
+        let Some(proto::view::Variant::ImageView(_)) = state else {
+            return None;
+        };
+        let Some(proto::view::Variant::ImageView(state)) = state.take() else {
+            unreachable!()
+        };
+
+        let project = workspace.read(cx).project().clone();
+        let open_image = project.update(cx, |project, cx| {
+            project.open_image(project_path_from_proto(state.worktree_id, &state.path), cx)
+        });
+        Some(cx.spawn(|mut cx| async move {
+            let image_item = open_image.await?;
+            cx.new_view(|cx| {
+                let mut image_view = ImageView::new(image_item, project, cx);
+                image_view.remote_id = Some(remote_id);
+                image_view
+            })
+        }))
+    }
+
+    fn to_follow_event(event: &Self::Event) -> Option<FollowEvent> {
+        match event {
+            ImageViewEvent::ImageChanged { local: true } => Some(FollowEvent::Unfollow),
+            _ => None,
+        }
+    }
+
+    fn add_event_to_update_proto(
+        &self,
+        event: &Self::Event,
+        update: &mut Option<proto::update_view::Variant>,
+        cx: &WindowContext,
+    ) -> bool {
+        let ImageViewEvent::ImageChanged { .. } = event else {
+            return false;
+        };
+        let image_item = self.image_item.read(cx);
+        if image_item.file.is_private() {
+            return false;
+        }
+        let project_path = image_item.project_path(cx);
+        *update = Some(proto::update_view::Variant::ImageView(
+            proto::update_view::ImageView {
+                worktree_id: project_path.worktree_id.to_proto(),
+                path: project_path.path.to_string_lossy().to_string(),
+            },
+        ));
+        true
+    }
+
+    fn apply_update_proto(
+        &mut self,
+        project: &Model<Project>,
+        message: proto::update_view::Variant,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<gpui::Result<()>> {
+        let proto::update_view::Variant::ImageView(message) = message else {
+            return Task::ready(Err(anyhow!(
+                "received a non-image update for an image view"
+            )));
+        };
+        let open_image = project.update(cx, |project, cx| {
+            project.open_image(
+                project_path_from_proto(message.worktree_id, &message.path),
+                cx,
+            )
+        });
+        cx.spawn(|this, mut cx| async move {
+            let image_item = open_image.await?;
+            this.update(&mut cx, |this, cx| {
+                this.set_image_item(image_item, false, cx)
+            })
+        })
+    }
+
+    fn is_project_item(&self, _cx: &WindowContext) -> bool {
+        true
+    }
+
+    fn set_leader_peer_id(&mut self, _: Option<PeerId>, _: &mut ViewContext<Self>) {}
+
+    fn dedup(&self, existing: &Self, _cx: &WindowContext) -> Option<Dedup> {
+        (self.image_item == existing.image_item).then_some(Dedup::KeepExisting)
+    }
+}
+
+fn project_path_from_proto(worktree_id: u64, path: &str) -> ProjectPath {
+    ProjectPath {
+        worktree_id: WorktreeId::from_proto(worktree_id),
+        path: Path::new(path).into(),
     }
 }
 
@@ -241,73 +2114,507 @@ impl FocusableView for ImageView {
 
 impl Render for ImageView {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
-        let image = self.image_item.read(cx).image.clone();
-        let checkered_background = |bounds: Bounds<Pixels>, _, cx: &mut WindowContext| {
-            let square_size = 32.0;
-
-            let start_y = bounds.origin.y.0;
-            let height = bounds.size.height.0;
-            let start_x = bounds.origin.x.0;
-            let width = bounds.size.width.0;
-
-            let mut y = start_y;
-            let mut x = start_x;
-            let mut color_swapper = true;
-            // draw checkerboard pattern
-            while y <= start_y + height {
-                // Keeping track of the grid in order to be resilient to resizing
-                let start_swap = color_swapper;
-                while x <= start_x + width {
-                    let rect =
-                        Bounds::new(point(px(x), px(y)), size(px(square_size), px(square_size)));
-
-                    let color = if color_swapper {
-                        opaque_grey(0.6, 0.4)
+        // Promoting a preview tab only re-renders its pane.
+        let preview_tab = self.is_preview_tab(cx);
+        if !self.shown {
+            self.preview_tab = preview_tab;
+            self.show(cx);
+        } else if self.preview_tab != preview_tab {
+            self.preview_tab = preview_tab;
+            self.update_preview_image(cx);
+        }
+        self.release_task = None;
+        let image_item = self.image_item.read(cx);
+        let dimensions = image_item.edited_dimensions();
+        let sheet_image = self
+            .edited_image
+            .clone()
+            .unwrap_or_else(|| image_item.image.clone());
+        let image: ImageSource = match (
+            self.reload_history.shown_image(),
+            self.preview_image.clone(),
+        ) {
+            (Some(earlier_version), _) => earlier_version.into(),
+            (None, Some(preview_image)) => preview_image.into(),
+            (None, None) => self
+                .pixelated_image
+                .clone()
+                .or_else(|| self.edited_image.clone())
+                .unwrap_or_else(|| image_item.image.clone())
+                .into(),
+        };
+        // Don't start decoding the image in full while a reduced copy is being decoded.
+        let decoding_preview_image =
+            self.preview_image.is_none() && self.preview_image_task.is_some();
+        let exceeded_decode_limit = (!self.decode_oversized)
+            .then(|| self.exceeded_decode_limit(cx))
+            .flatten();
+        let decode_error = image_item.decode_error().cloned();
+        let image_element = match exceeded_decode_limit {
+            Some(reason) => v_flex()
+                .gap_2()
+                .items_center()
+                .when_some(self.overview.clone(), |this, overview| {
+                    this.size_full().p_2().child(
+                        img(overview)
+                            .object_fit(ObjectFit::Contain)
+                            .grayscale(self.filter == ImageFilter::Grayscale)
+                            .flex_1()
+                            .w_full()
+                            .min_h_0()
+                            .id("overview"),
+                    )
+                })
+                .child(
+                    Label::new(if self.overview.is_some() {
+                        format!("{reason}, showing a reduced overview")
                     } else {
-                        opaque_grey(0.7, 0.4)
-                    };
-
-                    cx.paint_quad(fill(rect, color));
-                    color_swapper = !color_swapper;
-                    x += square_size;
+                        reason
+                    })
+                    .color(Color::Muted),
+                )
+                .child(
+                    Button::new("decode-anyway", "Decode Anyway")
+                        .style(ButtonStyle::Filled)
+                        .on_click(cx.listener(|this, _, cx| {
+                            this.decode_oversized = true;
+                            cx.notify();
+                        })),
+                )
+                .into_any_element(),
+            None if decoding_preview_image => div().into_any_element(),
+            None if decode_error.is_some() => self
+                .render_decode_error(decode_error.unwrap_or_default(), cx)
+                .into_any_element(),
+            None => {
+                let image = img(image)
+                    .object_fit(self.zoom.object_fit())
+                    .grayscale(self.filter == ImageFilter::Grayscale)
+                    .animate(self.animating)
+                    .id("img");
+                match self.zoom {
+                    ImageZoomMode::Fit => image.max_w_full().max_h_full().into_any_element(),
+                    ImageZoomMode::Fill => image.size_full().into_any_element(),
+                    ImageZoomMode::ActualSize => image.flex_none().into_any_element(),
+                    ImageZoomMode::Scale(scale) => match dimensions {
+                        Some((width, height)) => image
+                            .flex_none()
+                            .w(px(width as f32 * scale))
+                            .h(px(height as f32 * scale))
+                            .into_any_element(),
+                        None => image.flex_none().into_any_element(),
+                    },
                 }
-                x = start_x;
-                color_swapper = !start_swap;
-                y += square_size;
             }
         };
 
-        let checkered_background = canvas(|_, _| (), checkered_background)
-            .border_2()
-            .border_color(cx.theme().styles.colors.border)
-            .size_full()
-            .absolute()
-            .top_0()
-            .left_0();
+        // Only the actions that apply to the image are registered, so that the command palette
+        // doesn't offer the others.
+        let image_item = self.image_item.read(cx);
+        let has_project_file = image_item.has_project_file();
+        let is_local = image_item.file.as_local().is_some();
+        let editable = !image_item.is_in_archive();
+        let animated = image_item.frame_count().is_some_and(|count| count > 1);
+        let has_annotations = !self.annotations.local().strokes.is_empty();
+        let has_reload_history = self.reload_history.len() > 1;
 
-        div()
+        v_flex()
+            .key_context(self.key_context())
             .track_focus(&self.focus_handle(cx))
             .size_full()
-            .child(checkered_background)
+            .on_action(cx.listener(Self::zoom_in))
+            .on_action(cx.listener(Self::zoom_out))
+            .on_action(cx.listener(Self::zoom_to_actual_size))
+            .on_action(cx.listener(Self::zoom_to_fit))
+            .on_action(cx.listener(Self::toggle_annotating))
+            .on_action(cx.listener(Self::cycle_background))
+            .on_action(cx.listener(image_print::print))
+            .on_action(cx.listener(sprite_sheet::toggle_sprite_sheet))
+            .on_action(cx.listener(nine_patch::toggle_nine_patch))
+            .on_action(cx.listener(icon_proof::toggle_icon_proof))
+            .on_action(cx.listener(tile_preview::toggle_tile_preview))
+            .on_action(cx.listener(color_picker::toggle_eyedropper))
+            .on_action(cx.listener(image_context_menu::copy_image))
+            .on_action(cx.listener(extension_commands::run_extension_command))
+            .when(animated, |this| {
+                this.on_action(cx.listener(Self::toggle_animation))
+            })
+            .when(editable, |this| {
+                this.on_action(cx.listener(Self::rotate_clockwise))
+                    .on_action(cx.listener(Self::rotate_counterclockwise))
+                    .on_action(cx.listener(Self::flip_horizontal))
+                    .on_action(cx.listener(Self::flip_vertical))
+            })
+            .when(has_project_file, |this| {
+                this.on_action(cx.listener(Self::next_image))
+                    .on_action(cx.listener(Self::previous_image))
+                    .on_action(cx.listener(Self::toggle_filmstrip))
+                    .on_action(cx.listener(image_context_menu::copy_image_as_markdown))
+                    .on_action(cx.listener(image_context_menu::copy_path))
+                    .on_action(cx.listener(image_context_menu::copy_relative_path))
+                    .on_action(cx.listener(image_context_menu::copy_path_relative_to_active_file))
+                    .on_action(cx.listener(image_context_menu::copy_posix_path))
+            })
+            .when(is_local, |this| {
+                this.on_action(cx.listener(external_editor::open_in_external_editor))
+                    .on_action(cx.listener(image_context_menu::reveal_in_file_manager))
+            })
+            .when(has_annotations, |this| {
+                this.on_action(cx.listener(Self::clear_annotations))
+            })
+            .when(self.annotating, |this| {
+                this.on_action(cx.listener(Self::move_tool_cursor))
+                    .on_action(cx.listener(Self::commit_tool_cursor))
+            })
+            .when(has_reload_history, |this| {
+                this.on_action(cx.listener(reload_history::toggle_reload_history))
+                    .on_action(cx.listener(reload_history::show_older_version))
+                    .on_action(cx.listener(reload_history::show_newer_version))
+                    .on_action(cx.listener(reload_history::flip_reload_version))
+            })
+            .when(self.image_item.read(cx).is_deleted(), |this| {
+                this.child(deleted_image::render_deleted_banner(cx))
+            })
             .child(
                 div()
-                    .flex()
-                    .justify_center()
-                    .items_center()
+                    .id("image-area")
+                    .relative()
+                    .flex_1()
                     .w_full()
-                    // TODO: In browser based Tailwind & Flex this would be h-screen and we'd use w-full
-                    .h_full()
-                    .child(
-                        img(image)
-                            .object_fit(ObjectFit::ScaleDown)
-                            .max_w_full()
-                            .max_h_full()
-                            .id("img"),
-                    ),
+                    .overflow_hidden()
+                    .when(self.annotating || self.color_picker.is_some(), |div| {
+                        div.cursor_crosshair()
+                    })
+                    .on_mouse_move(cx.listener(Self::on_image_mouse_move))
+                    .on_mouse_down(MouseButton::Left, cx.listener(Self::on_image_mouse_down))
+                    .on_mouse_up(MouseButton::Left, cx.listener(Self::on_image_mouse_up))
+                    .on_scroll_wheel(cx.listener(image_gestures::scroll_pan))
+                    .on_click(cx.listener(texture_atlas::zoom_to_clicked_region))
+                    .on_mouse_down(
+                        MouseButton::Right,
+                        cx.listener(image_context_menu::deploy_context_menu),
+                    )
+                    .on_hover(cx.listener(|this, hovered, cx| {
+                        if *hovered {
+                            return;
+                        }
+                        if this.annotations.set_pointer(None) {
+                            this.broadcast_annotations(cx);
+                            cx.notify();
+                        }
+                        if let Some(texture_atlas) = this.texture_atlas.as_mut() {
+                            if texture_atlas.hovered_region.take().is_some() {
+                                cx.notify();
+                            }
+                        }
+                    }))
+                    .child(image_background(self.background, cx))
+                    .when(self.tiling, |this| {
+                        this.child(tile_preview::render_tile_preview(
+                            self,
+                            sheet_image.clone(),
+                            cx,
+                        ))
+                    })
+                    .when(!self.tiling, |this| {
+                        this.child(
+                            div()
+                                .flex()
+                                .justify_center()
+                                .items_center()
+                                .w_full()
+                                // TODO: In browser based Tailwind & Flex this would be h-screen and we'd use w-full
+                                .h_full()
+                                .relative()
+                                .left(self.pan_offset.x)
+                                .top(self.pan_offset.y)
+                                .child(image_element),
+                        )
+                        .child(self.render_annotations(cx))
+                    })
+                    .children(live_reload::render_live_badge(self, cx))
+                    .child(image_gestures::pinch_listener(cx)),
             )
+            .children(color_picker::render_color_picker_bar(self, cx))
+            .children(icon_proof::render_icon_proof_bar(self, cx))
+            .children(nine_patch::render_nine_patch_bar(
+                self,
+                sheet_image.clone(),
+                cx,
+            ))
+            .children(sprite_sheet::render_sprite_sheet_bar(self, sheet_image, cx))
+            .children(reload_history::render_reload_history_bar(self, cx))
+            .children(self.filmstrip.clone())
+            .children(self.context_menu.as_ref().map(|(menu, position, _)| {
+                deferred(
+                    anchored()
+                        .position(*position)
+                        .anchor(gpui::AnchorCorner::TopLeft)
+                        .child(menu.clone()),
+                )
+                .with_priority(1)
+            }))
     }
 }
 
+/// The image viewer settings for `image_item`, including overrides from the `.zed/settings.json`
+/// of its worktree.
+fn image_settings<'a>(
+    image_item: &Model<ImageItem>,
+    cx: &'a AppContext,
+) -> &'a ImageViewerSettings {
+    let file = &image_item.read(cx).file;
+    ImageViewerSettings::get(
+        Some(SettingsLocation {
+            worktree_id: file.worktree_id(cx),
+            path: file.path().as_ref(),
+        }),
+        cx,
+    )
+}
+
+/// Why `image_item` is too large to be decoded without the user asking for it, if it is.
+fn decode_limit_exceeded(image_item: &ImageItem, settings: &ImageViewerSettings) -> Option<String> {
+    let file_size = image_item.image.bytes.len() as u64;
+    let units = settings.units();
+    if file_size > settings.max_file_size.saturating_mul(1024 * 1024) {
+        return Some(format!(
+            "This image is too large to display ({})",
+            units.file_size(file_size)
+        ));
+    }
+    if image_item.image.format == ImageFormat::Svg {
+        return None;
+    }
+    let (width, height) = image_item.dimensions()?;
+    let megapixels = width as f32 * height as f32 / 1_000_000.;
+    (megapixels > settings.max_decoded_megapixels).then(|| {
+        format!(
+            "This image has too many pixels to display ({})",
+            units.dimensions(width, height)
+        )
+    })
+}
+
+/// Scales `image` up by `factor` using nearest-neighbor sampling.
+fn pixelate(image: &Image, factor: u32) -> anyhow::Result<Image> {
+    let decoded = image::load_from_memory(&image.bytes)?;
+    let scaled = decoded.resize_exact(
+        decoded.width() * factor,
+        decoded.height() * factor,
+        image::imageops::FilterType::Nearest,
+    );
+    let mut bytes = Vec::new();
+    scaled.write_to(&mut io::Cursor::new(&mut bytes), image::ImageFormat::Png)?;
+    Ok(Image {
+        format: ImageFormat::Png,
+        id: gpui::hash(&bytes),
+        bytes,
+    })
+}
+
+/// Paints a line between adjacent pixels of an image drawn at `image_bounds`, once they are
+/// large enough for the lines not to hide them. Only the lines inside `visible_bounds` are painted.
+fn paint_pixel_grid(
+    image_bounds: Bounds<Pixels>,
+    (width, height): (u32, u32),
+    visible_bounds: Bounds<Pixels>,
+    color: Hsla,
+    cx: &mut WindowContext,
+) {
+    const MIN_PIXEL_SIZE: f32 = 4.;
+
+    let pixel_width = image_bounds.size.width.0 / width.max(1) as f32;
+    let pixel_height = image_bounds.size.height.0 / height.max(1) as f32;
+    if pixel_width < MIN_PIXEL_SIZE || pixel_height < MIN_PIXEL_SIZE {
+        return;
+    }
+    let visible_bounds = image_bounds.intersect(&visible_bounds);
+    let visible_columns = (
+        ((visible_bounds.origin.x - image_bounds.origin.x).0 / pixel_width).ceil() as u32,
+        ((visible_bounds.right() - image_bounds.origin.x).0 / pixel_width) as u32,
+    );
+    let visible_rows = (
+        ((visible_bounds.origin.y - image_bounds.origin.y).0 / pixel_height).ceil() as u32,
+        ((visible_bounds.bottom() - image_bounds.origin.y).0 / pixel_height) as u32,
+    );
+    for column in visible_columns.0..=visible_columns.1 {
+        let x = image_bounds.origin.x + px(column as f32 * pixel_width);
+        cx.paint_quad(fill(
+            Bounds::new(
+                point(x, visible_bounds.origin.y),
+                size(px(1.), visible_bounds.size.height),
+            ),
+            color,
+        ));
+    }
+    for row in visible_rows.0..=visible_rows.1 {
+        let y = image_bounds.origin.y + px(row as f32 * pixel_height);
+        cx.paint_quad(fill(
+            Bounds::new(
+                point(visible_bounds.origin.x, y),
+                size(visible_bounds.size.width, px(1.)),
+            ),
+            color,
+        ));
+    }
+}
+
+/// Where the region of an image with the given bounds in pixels is drawn, when the image is
+/// drawn at `image_bounds`.
+pub(crate) fn image_region_bounds(
+    image_bounds: Bounds<Pixels>,
+    (width, height): (u32, u32),
+    region: Bounds<u32>,
+) -> Bounds<Pixels> {
+    let scale_x = image_bounds.size.width.0 / width.max(1) as f32;
+    let scale_y = image_bounds.size.height.0 / height.max(1) as f32;
+    Bounds {
+        origin: image_bounds.origin
+            + point(
+                px(region.origin.x as f32 * scale_x),
+                px(region.origin.y as f32 * scale_y),
+            ),
+        size: size(
+            px(region.size.width as f32 * scale_x),
+            px(region.size.height as f32 * scale_y),
+        ),
+    }
+}
+
+/// Paints a small label with its top left corner at `origin`, such as to name a region of an
+/// image.
+pub(crate) fn paint_label(
+    text: SharedString,
+    origin: gpui::Point<Pixels>,
+    background: Hsla,
+    cx: &mut WindowContext,
+) {
+    let font_size = px(10.);
+    let run = TextRun {
+        len: text.len(),
+        font: cx.text_style().font(),
+        color: gpui::white(),
+        background_color: None,
+        underline: None,
+        strikethrough: None,
+    };
+    let Some(line) = cx
+        .text_system()
+        .shape_line(text, font_size, &[run])
+        .log_err()
+    else {
+        return;
+    };
+    cx.paint_quad(fill(
+        Bounds {
+            origin,
+            size: size(line.width + px(4.), font_size + px(2.)),
+        },
+        background,
+    ));
+    line.paint(origin + point(px(2.), px(1.)), font_size, cx)
+        .log_err();
+}
+
+/// The backdrop drawn behind images, as configured in the image viewer settings.
+pub(crate) fn image_background(
+    background: ImageViewerBackground,
+    cx: &WindowContext,
+) -> AnyElement {
+    let color = match background {
+        ImageViewerBackground::Checkerboard => {
+            return checkered_background(cx).into_any_element();
+        }
+        ImageViewerBackground::HighContrastCheckerboard => {
+            return high_contrast_checkered_background(cx).into_any_element();
+        }
+        ImageViewerBackground::Editor => cx.theme().colors().editor_background,
+        ImageViewerBackground::Black => gpui::black(),
+        ImageViewerBackground::White => gpui::white(),
+    };
+    div()
+        .size_full()
+        .absolute()
+        .top_0()
+        .left_0()
+        .bg(color)
+        .into_any_element()
+}
+
+/// The size of the squares of the checkerboard backdrop.
+const CHECKERBOARD_SQUARE_SIZE: f32 = 32.;
+/// How many squares wide and high the checkerboard tile is.
+const CHECKERBOARD_TILE_SQUARES: u32 = 8;
+/// How many texels the tile has per pixel, so that it stays sharp on high density displays.
+const CHECKERBOARD_TILE_SCALE: u32 = 2;
+
+/// A tile of the checkerboard backdrop, drawn once and then painted as many times as it takes to
+/// cover the backdrop, so that large backdrops don't paint a quad for every square on every
+/// frame.
+static CHECKERBOARD_TILE: LazyLock<Arc<RenderImage>> =
+    LazyLock::new(|| checkerboard_tile(opaque_grey(0.6, 0.4), opaque_grey(0.7, 0.4)));
+static HIGH_CONTRAST_CHECKERBOARD_TILE: LazyLock<Arc<RenderImage>> =
+    LazyLock::new(|| checkerboard_tile(gpui::black(), gpui::white()));
+
+fn checkerboard_tile(dark: Hsla, light: Hsla) -> Arc<RenderImage> {
+    let texels_per_square = CHECKERBOARD_SQUARE_SIZE as u32 * CHECKERBOARD_TILE_SCALE;
+    let texels = texels_per_square * CHECKERBOARD_TILE_SQUARES;
+    // The atlas stores images in BGRA order.
+    let bgra = |color: Hsla| {
+        let color = Rgba::from(color);
+        image::Rgba([color.b, color.g, color.r, color.a].map(|channel| (channel * 255.) as u8))
+    };
+    let (dark, light) = (bgra(dark), bgra(light));
+    let tile = image::RgbaImage::from_fn(texels, texels, |x, y| {
+        if (x / texels_per_square + y / texels_per_square) % 2 == 0 {
+            dark
+        } else {
+            light
+        }
+    });
+    Arc::new(RenderImage::new(vec![image::Frame::new(tile)]))
+}
+
+/// A checkerboard backdrop that makes the transparent regions of an image visible.
+pub(crate) fn checkered_background(cx: &WindowContext) -> impl IntoElement {
+    checkerboard(&CHECKERBOARD_TILE, cx)
+}
+
+/// A black and white checkerboard backdrop, for images whose colors are close to the grey
+/// squares of the default one.
+pub(crate) fn high_contrast_checkered_background(cx: &WindowContext) -> impl IntoElement {
+    checkerboard(&HIGH_CONTRAST_CHECKERBOARD_TILE, cx)
+}
+
+fn checkerboard(tile: &Arc<RenderImage>, cx: &WindowContext) -> impl IntoElement {
+    let tile = tile.clone();
+    let paint_checkerboard = move |bounds: Bounds<Pixels>, _, cx: &mut WindowContext| {
+        let tile_size = px(CHECKERBOARD_SQUARE_SIZE * CHECKERBOARD_TILE_SQUARES as f32);
+        cx.with_content_mask(Some(ContentMask { bounds }), |cx| {
+            let mut y = bounds.top();
+            while y < bounds.bottom() {
+                let mut x = bounds.left();
+                while x < bounds.right() {
+                    let tile_bounds = Bounds::new(point(x, y), size(tile_size, tile_size));
+                    cx.paint_image(tile_bounds, Corners::default(), tile.clone(), 0, false)
+                        .log_err();
+                    x += tile_size;
+                }
+                y += tile_size;
+            }
+        });
+    };
+
+    canvas(|_, _| (), paint_checkerboard)
+        .border_2()
+        .border_color(cx.theme().styles.colors.border)
+        .size_full()
+        .absolute()
+        .top_0()
+        .left_0()
+}
+
 impl ProjectItem for ImageView {
     type Item = ImageItem;
 
@@ -324,87 +2631,48 @@ impl ProjectItem for ImageView {
 }
 
 pub fn init(cx: &mut AppContext) {
+    ImageViewerSettings::register(cx);
+    AssetsPanelSettings::register(cx);
+    GoldenImagesPanelSettings::register(cx);
     workspace::register_project_item::<ImageView>(cx);
-    workspace::register_serializable_item::<ImageView>(cx)
-}
+    workspace::register_serializable_item::<ImageView>(cx);
+    workspace::register_serializable_item::<ImageCompareView>(cx);
+    asset_viewer::register::<FontSpecimenView>(cx);
+    asset_viewer::register::<AudioPreviewView>(cx);
+    asset_viewer::register::<ModelPreviewView>(cx);
+    FollowableViewRegistry::register::<ImageView>(cx);
+    extension_commands::init(cx);
+    pixel_sources::init(cx);
 
-mod persistence {
-    use anyhow::Result;
-    use std::path::PathBuf;
-
-    use db::{define_connection, query, sqlez::statement::Statement, sqlez_macros::sql};
-    use workspace::{ItemId, WorkspaceDb, WorkspaceId};
-
-    define_connection! {
-        pub static ref IMAGE_VIEWER: ImageViewerDb<WorkspaceDb> =
-            &[sql!(
-                CREATE TABLE image_viewers (
-                    workspace_id INTEGER,
-                    item_id INTEGER UNIQUE,
-
-                    image_path BLOB,
-
-                    PRIMARY KEY(workspace_id, item_id),
-                    FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
-                    ON DELETE CASCADE
-                ) STRICT;
-            )];
-    }
-
-    impl ImageViewerDb {
-        query! {
-           pub async fn update_workspace_id(
-                new_id: WorkspaceId,
-                old_id: WorkspaceId,
-                item_id: ItemId
-            ) -> Result<()> {
-                UPDATE image_viewers
-                SET workspace_id = ?
-                WHERE workspace_id = ? AND item_id = ?
-            }
-        }
-
-        query! {
-            pub async fn save_image_path(
-                item_id: ItemId,
-                workspace_id: WorkspaceId,
-                image_path: PathBuf
-            ) -> Result<()> {
-                INSERT OR REPLACE INTO image_viewers(item_id, workspace_id, image_path)
-                VALUES (?, ?, ?)
-            }
-        }
-
-        query! {
-            pub fn get_image_path(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Option<PathBuf>> {
-                SELECT image_path
-                FROM image_viewers
-                WHERE item_id = ? AND workspace_id = ?
-            }
-        }
-
-        pub async fn delete_unloaded_items(
-            &self,
-            workspace: WorkspaceId,
-            alive_items: Vec<ItemId>,
-        ) -> Result<()> {
-            let placeholders = alive_items
-                .iter()
-                .map(|_| "?")
-                .collect::<Vec<&str>>()
-                .join(", ");
-
-            let query = format!("DELETE FROM image_viewers WHERE workspace_id = ? AND item_id NOT IN ({placeholders})");
+    cx.observe_new_views(|workspace: &mut Workspace, cx| {
+        archive_images::ArchiveImages::register(workspace, cx);
+        ImageCompareView::register(workspace, cx);
+        ImageGalleryView::register(workspace, cx);
+        image_history::ImageHistory::register(workspace, cx);
+        PendingImageView::register(workspace, cx);
+        recent_images::RecentImages::register(workspace, cx);
+        screenshot::register(workspace, cx);
+        diagram::register(workspace, cx);
+        SvgPreviewView::register(workspace, cx);
+        ImageMemoryView::register(workspace, cx);
+        SimilarImagesView::register(workspace, cx);
+        palette_filter::register(workspace, cx);
+        workspace.register_action(open_as_image);
+        workspace.register_action(open_image_from_clipboard);
+        update_image_store_settings(workspace, cx);
+        cx.observe_global::<SettingsStore>(update_image_store_settings)
+            .detach();
+    })
+    .detach();
+}
 
-            self.write(move |conn| {
-                let mut statement = Statement::prepare(conn, query)?;
-                let mut next_index = statement.bind(&workspace, 1)?;
-                for id in alive_items {
-                    next_index = statement.bind(&id, next_index)?;
-                }
-                statement.exec()
-            })
-            .await
-        }
-    }
+fn update_image_store_settings(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+    let settings = ImageViewerSettings::get_global(cx);
+    let cache_size = (settings.image_cache_size as usize).saturating_mul(1024 * 1024);
+    let reload_debounce = Duration::from_millis(settings.reload_debounce);
+    let image_store = workspace.project().read(cx).image_store();
+    image_store.update(cx, |image_store, cx| {
+        image_store.set_image_cache_size(cache_size, cx);
+        image_store.set_reload_debounce(reload_debounce, cx);
+    });
 }
@@ -0,0 +1,363 @@
+use gpui::{EventEmitter, Subscription, View, ViewContext, WeakView};
+use ui::{prelude::*, ContextMenu, IconButtonShape, PopoverMenu, Tooltip};
+use workspace::{item::ItemHandle, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView};
+
+use crate::{
+    assets_panel, color_picker, icon_proof, nine_patch, reload_history, sprite_sheet, tile_preview,
+    FlipHorizontal, FlipVertical, ImageView, ImageZoomMode, RotateClockwise,
+    RotateCounterclockwise, ToggleAnnotating, ToggleEyedropper, ToggleFilmstrip, ToggleIconProof,
+    ToggleNinePatch, ToggleReloadHistory, ToggleSpriteSheet, ToggleTilePreview, ZoomIn, ZoomOut,
+};
+
+/// The scales offered by the zoom menu, besides fitting the image to the view.
+const ZOOM_MENU_SCALES: &[f32] = &[0.5, 2., 4., 8.];
+
+/// A toolbar row for the active image view, so that its zoom, tools and panels can be found
+/// without knowing their key bindings.
+pub struct ImageViewerControls {
+    image_view: Option<WeakView<ImageView>>,
+    _subscription: Option<Subscription>,
+}
+
+impl ImageViewerControls {
+    pub fn new() -> Self {
+        Self {
+            image_view: None,
+            _subscription: None,
+        }
+    }
+
+    fn image_view(&self) -> Option<View<ImageView>> {
+        self.image_view.as_ref()?.upgrade()
+    }
+
+    fn update_image_view(
+        &self,
+        cx: &mut ViewContext<Self>,
+        update: impl FnOnce(&mut ImageView, &mut ViewContext<ImageView>),
+    ) {
+        if let Some(image_view) = self.image_view() {
+            image_view.update(cx, update);
+        }
+    }
+
+    fn render_zoom_menu(
+        &self,
+        image_view: &View<ImageView>,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let zoom = image_view.read(cx).zoom;
+        let label = match (zoom, image_view.read(cx).current_scale(cx)) {
+            (ImageZoomMode::Fit, Some(scale)) => format!("Fit ({:.0}%)", scale * 100.),
+            (ImageZoomMode::Fill, Some(scale)) => format!("Fill ({:.0}%)", scale * 100.),
+            (ImageZoomMode::Fit, None) => "Fit".to_string(),
+            (ImageZoomMode::Fill, None) => "Fill".to_string(),
+            (ImageZoomMode::ActualSize, _) => "100%".to_string(),
+            (ImageZoomMode::Scale(scale), _) => format!("{:.0}%", scale * 100.),
+        };
+        let image_view = image_view.downgrade();
+        PopoverMenu::new("image-zoom-menu")
+            .trigger(
+                Button::new("image-zoom", label)
+                    .label_size(LabelSize::Small)
+                    .icon(IconName::ChevronDown)
+                    .icon_size(IconSize::XSmall)
+                    .icon_position(IconPosition::End)
+                    .tooltip(|cx| Tooltip::text("Zoom", cx)),
+            )
+            .menu(move |cx| {
+                let image_view = image_view.clone();
+                Some(ContextMenu::build(cx, move |mut menu, _| {
+                    let modes = [
+                        ("Fit", ImageZoomMode::Fit),
+                        ("Fill", ImageZoomMode::Fill),
+                        ("Actual Size", ImageZoomMode::ActualSize),
+                    ];
+                    for (label, mode) in modes {
+                        menu = menu.toggleable_entry(
+                            label,
+                            zoom == mode,
+                            IconPosition::Start,
+                            None,
+                            set_zoom_handler(image_view.clone(), mode),
+                        );
+                    }
+                    menu = menu.separator();
+                    for scale in ZOOM_MENU_SCALES {
+                        let mode = ImageZoomMode::Scale(*scale);
+                        menu = menu.toggleable_entry(
+                            format!("{:.0}%", scale * 100.),
+                            zoom == mode,
+                            IconPosition::Start,
+                            None,
+                            set_zoom_handler(image_view.clone(), mode),
+                        );
+                    }
+                    menu
+                }))
+            })
+    }
+}
+
+fn set_zoom_handler(
+    image_view: WeakView<ImageView>,
+    zoom: ImageZoomMode,
+) -> impl Fn(&mut WindowContext) + 'static {
+    move |cx| {
+        image_view
+            .update(cx, |image_view, cx| image_view.set_zoom(zoom, cx))
+            .ok();
+    }
+}
+
+impl Default for ImageViewerControls {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Render for ImageViewerControls {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let Some(image_view) = self.image_view() else {
+            return div().into_any_element();
+        };
+        let (
+            annotating,
+            filmstrip_open,
+            sprite_sheet_open,
+            nine_patch_open,
+            icon_proof_open,
+            tiling,
+            picking_color,
+            reload_history,
+            editable,
+        ) = {
+            let image_view = image_view.read(cx);
+            (
+                image_view.annotating,
+                image_view.filmstrip.is_some(),
+                image_view.sprite_sheet.is_some(),
+                image_view.nine_patch.is_some(),
+                image_view.icon_proof.is_some(),
+                image_view.tiling,
+                image_view.color_picker.is_some(),
+                (image_view.reload_history.len() > 1).then_some(image_view.reload_history.open),
+                !image_view.image_item.read(cx).is_in_archive(),
+            )
+        };
+
+        h_flex()
+            .gap_1()
+            .child(
+                IconButton::new("image-zoom-out", IconName::Dash)
+                    .shape(IconButtonShape::Square)
+                    .tooltip(|cx| Tooltip::for_action("Zoom Out", &ZoomOut, cx))
+                    .on_click(cx.listener(|this, _, cx| {
+                        this.update_image_view(cx, |image_view, cx| {
+                            image_view.zoom_out(&ZoomOut, cx)
+                        })
+                    })),
+            )
+            .child(self.render_zoom_menu(&image_view, cx))
+            .child(
+                IconButton::new("image-zoom-in", IconName::Plus)
+                    .shape(IconButtonShape::Square)
+                    .tooltip(|cx| Tooltip::for_action("Zoom In", &ZoomIn, cx))
+                    .on_click(cx.listener(|this, _, cx| {
+                        this.update_image_view(cx, |image_view, cx| image_view.zoom_in(&ZoomIn, cx))
+                    })),
+            )
+            .child(
+                IconButton::new("image-rotate-ccw", IconName::RotateCcw)
+                    .shape(IconButtonShape::Square)
+                    .disabled(!editable)
+                    .tooltip(|cx| {
+                        Tooltip::for_action("Rotate Counterclockwise", &RotateCounterclockwise, cx)
+                    })
+                    .on_click(cx.listener(|this, _, cx| {
+                        this.update_image_view(cx, |image_view, cx| {
+                            image_view.rotate_counterclockwise(&RotateCounterclockwise, cx)
+                        })
+                    })),
+            )
+            .child(
+                IconButton::new("image-rotate-cw", IconName::RotateCw)
+                    .shape(IconButtonShape::Square)
+                    .disabled(!editable)
+                    .tooltip(|cx| Tooltip::for_action("Rotate Clockwise", &RotateClockwise, cx))
+                    .on_click(cx.listener(|this, _, cx| {
+                        this.update_image_view(cx, |image_view, cx| {
+                            image_view.rotate_clockwise(&RotateClockwise, cx)
+                        })
+                    })),
+            )
+            .child(
+                Button::new("image-flip-horizontal", "Flip ↔")
+                    .label_size(LabelSize::Small)
+                    .disabled(!editable)
+                    .tooltip(|cx| Tooltip::for_action("Flip Horizontally", &FlipHorizontal, cx))
+                    .on_click(cx.listener(|this, _, cx| {
+                        this.update_image_view(cx, |image_view, cx| {
+                            image_view.flip_horizontal(&FlipHorizontal, cx)
+                        })
+                    })),
+            )
+            .child(
+                Button::new("image-flip-vertical", "Flip ↕")
+                    .label_size(LabelSize::Small)
+                    .disabled(!editable)
+                    .tooltip(|cx| Tooltip::for_action("Flip Vertically", &FlipVertical, cx))
+                    .on_click(cx.listener(|this, _, cx| {
+                        this.update_image_view(cx, |image_view, cx| {
+                            image_view.flip_vertical(&FlipVertical, cx)
+                        })
+                    })),
+            )
+            .child(
+                Button::new("image-tool-pointer", "Pointer")
+                    .label_size(LabelSize::Small)
+                    .selected(!annotating)
+                    .tooltip(|cx| Tooltip::text("Point Without Drawing", cx))
+                    .on_click(cx.listener(move |this, _, cx| {
+                        if annotating {
+                            this.update_image_view(cx, |image_view, cx| {
+                                image_view.toggle_annotating(&ToggleAnnotating, cx)
+                            })
+                        }
+                    })),
+            )
+            .child(
+                Button::new("image-tool-annotate", "Annotate")
+                    .label_size(LabelSize::Small)
+                    .icon(IconName::Pencil)
+                    .icon_size(IconSize::XSmall)
+                    .icon_position(IconPosition::Start)
+                    .selected(annotating)
+                    .tooltip(|cx| Tooltip::for_action("Annotate", &ToggleAnnotating, cx))
+                    .on_click(cx.listener(move |this, _, cx| {
+                        if !annotating {
+                            this.update_image_view(cx, |image_view, cx| {
+                                image_view.toggle_annotating(&ToggleAnnotating, cx)
+                            })
+                        }
+                    })),
+            )
+            .child(
+                Button::new("image-toggle-filmstrip", "Filmstrip")
+                    .label_size(LabelSize::Small)
+                    .selected(filmstrip_open)
+                    .tooltip(|cx| Tooltip::for_action("Toggle Filmstrip", &ToggleFilmstrip, cx))
+                    .on_click(cx.listener(|this, _, cx| {
+                        this.update_image_view(cx, |image_view, cx| {
+                            image_view.toggle_filmstrip(&ToggleFilmstrip, cx)
+                        })
+                    })),
+            )
+            .child(
+                Button::new("image-toggle-sprite-sheet", "Sprites")
+                    .label_size(LabelSize::Small)
+                    .selected(sprite_sheet_open)
+                    .tooltip(|cx| {
+                        Tooltip::for_action("Toggle Sprite Sheet", &ToggleSpriteSheet, cx)
+                    })
+                    .on_click(cx.listener(|this, _, cx| {
+                        this.update_image_view(cx, |image_view, cx| {
+                            sprite_sheet::toggle_sprite_sheet(image_view, &ToggleSpriteSheet, cx)
+                        })
+                    })),
+            )
+            .child(
+                Button::new("image-toggle-nine-patch", "9-Slice")
+                    .label_size(LabelSize::Small)
+                    .selected(nine_patch_open)
+                    .tooltip(|cx| {
+                        Tooltip::for_action("Toggle Nine-Patch Preview", &ToggleNinePatch, cx)
+                    })
+                    .on_click(cx.listener(|this, _, cx| {
+                        this.update_image_view(cx, |image_view, cx| {
+                            nine_patch::toggle_nine_patch(image_view, &ToggleNinePatch, cx)
+                        })
+                    })),
+            )
+            .child(
+                Button::new("image-toggle-icon-proof", "Icons")
+                    .label_size(LabelSize::Small)
+                    .selected(icon_proof_open)
+                    .tooltip(|cx| Tooltip::for_action("Toggle Icon Sizes", &ToggleIconProof, cx))
+                    .on_click(cx.listener(|this, _, cx| {
+                        this.update_image_view(cx, |image_view, cx| {
+                            icon_proof::toggle_icon_proof(image_view, &ToggleIconProof, cx)
+                        })
+                    })),
+            )
+            .child(
+                Button::new("image-toggle-tile-preview", "Tile")
+                    .label_size(LabelSize::Small)
+                    .selected(tiling)
+                    .tooltip(|cx| {
+                        Tooltip::for_action("Toggle Tile Preview", &ToggleTilePreview, cx)
+                    })
+                    .on_click(cx.listener(|this, _, cx| {
+                        this.update_image_view(cx, |image_view, cx| {
+                            tile_preview::toggle_tile_preview(image_view, &ToggleTilePreview, cx)
+                        })
+                    })),
+            )
+            .child(
+                IconButton::new("image-toggle-eyedropper", IconName::SwatchBook)
+                    .selected(picking_color)
+                    .tooltip(|cx| Tooltip::for_action("Toggle Eyedropper", &ToggleEyedropper, cx))
+                    .on_click(cx.listener(|this, _, cx| {
+                        this.update_image_view(cx, |image_view, cx| {
+                            color_picker::toggle_eyedropper(image_view, &ToggleEyedropper, cx)
+                        })
+                    })),
+            )
+            .children(reload_history.map(|reload_history_open| {
+                IconButton::new("image-toggle-reload-history", IconName::HistoryRerun)
+                    .selected(reload_history_open)
+                    .tooltip(|cx| {
+                        Tooltip::for_action("Toggle Reload History", &ToggleReloadHistory, cx)
+                    })
+                    .on_click(cx.listener(|this, _, cx| {
+                        this.update_image_view(cx, |image_view, cx| {
+                            reload_history::toggle_reload_history(
+                                image_view,
+                                &ToggleReloadHistory,
+                                cx,
+                            )
+                        })
+                    }))
+            }))
+            .child(
+                Button::new("image-toggle-assets-panel", "Assets")
+                    .label_size(LabelSize::Small)
+                    .tooltip(|cx| {
+                        Tooltip::for_action("Toggle Assets Panel", &assets_panel::ToggleFocus, cx)
+                    })
+                    .on_click(|_, cx| cx.dispatch_action(Box::new(assets_panel::ToggleFocus))),
+            )
+            .into_any_element()
+    }
+}
+
+impl EventEmitter<ToolbarItemEvent> for ImageViewerControls {}
+
+impl ToolbarItemView for ImageViewerControls {
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        cx: &mut ViewContext<Self>,
+    ) -> ToolbarItemLocation {
+        let image_view = active_pane_item.and_then(|item| item.downcast::<ImageView>());
+        self._subscription = image_view
+            .as_ref()
+            .map(|image_view| cx.observe(image_view, |_, _, cx| cx.notify()));
+        self.image_view = image_view.as_ref().map(|image_view| image_view.downgrade());
+        if self.image_view.is_some() {
+            ToolbarItemLocation::Secondary
+        } else {
+            ToolbarItemLocation::Hidden
+        }
+    }
+}
@@ -0,0 +1,571 @@
+use std::{collections::HashMap, path::Path, sync::OnceLock};
+
+use gpui::{AppContext, ObjectFit};
+use num_format::{Locale, ToFormattedString};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct ImageViewerSettings {
+    pub background: ImageViewerBackground,
+    pub default_zoom: ImageZoomMode,
+    pub filter: ImageFilter,
+    pub rendering: ImageRendering,
+    pub autoplay_animations: bool,
+    pub reduce_motion: Option<bool>,
+    pub pixel_grid: bool,
+    pub file_types: HashMap<String, ImageViewerFileTypeSettings>,
+    pub max_file_size: u64,
+    pub max_decoded_megapixels: f32,
+    pub image_cache_size: u64,
+    pub release_hidden_images_after: u64,
+    pub reload_debounce: u64,
+    pub live_reload_threshold: u32,
+    pub show_live_reload_rate: bool,
+    pub reload_history_size: usize,
+    pub prefetch_adjacent_images: usize,
+    pub preview_tab_max_size: u32,
+    pub file_size_units: FileSizeUnits,
+    pub number_locale: Option<String>,
+    pub screenshots_directory: String,
+    pub print_scale: PrintScale,
+    pub print_dpi: f32,
+    pub print_paper_size: PaperSize,
+    pub external_editors: Vec<ExternalImageEditor>,
+}
+
+/// What is drawn behind images, and shows through their transparent regions.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageViewerBackground {
+    /// A checkerboard, which makes transparent regions stand out.
+    #[default]
+    Checkerboard,
+    /// The background color of the editor.
+    Editor,
+    Black,
+    White,
+    /// A black and white checkerboard, for assets whose colors are hard to tell apart from the
+    /// default one.
+    HighContrastCheckerboard,
+}
+
+impl ImageViewerBackground {
+    /// The background after this one, for cycling through them from the keyboard.
+    pub fn next(&self) -> Self {
+        match self {
+            ImageViewerBackground::Checkerboard => ImageViewerBackground::HighContrastCheckerboard,
+            ImageViewerBackground::HighContrastCheckerboard => ImageViewerBackground::Black,
+            ImageViewerBackground::Black => ImageViewerBackground::White,
+            ImageViewerBackground::White => ImageViewerBackground::Editor,
+            ImageViewerBackground::Editor => ImageViewerBackground::Checkerboard,
+        }
+    }
+}
+
+/// How big images are shown when they are opened.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageZoomMode {
+    /// Scale images down to fit the viewer, leaving smaller ones at their actual size.
+    #[default]
+    Fit,
+    /// Scale images up or down to fill the viewer.
+    Fill,
+    /// Show images at their actual size, cropping the ones larger than the viewer.
+    ActualSize,
+    /// Show images at a multiple of their actual size, such as 4 for 400%.
+    Scale(f32),
+}
+
+impl ImageZoomMode {
+    pub fn object_fit(&self) -> ObjectFit {
+        match self {
+            ImageZoomMode::Fit => ObjectFit::ScaleDown,
+            ImageZoomMode::Fill => ObjectFit::Contain,
+            ImageZoomMode::ActualSize => ObjectFit::None,
+            ImageZoomMode::Scale(_) => ObjectFit::Fill,
+        }
+    }
+}
+
+/// How images are sampled when they are scaled up.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageRendering {
+    /// Blend neighboring pixels, which suits photos.
+    #[default]
+    Smooth,
+    /// Keep pixels sharp, which suits pixel art.
+    Pixelated,
+}
+
+/// A filter applied to images when they are displayed.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageFilter {
+    #[default]
+    None,
+    Grayscale,
+}
+
+/// The units file sizes are shown in.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FileSizeUnits {
+    /// Powers of 1024: KiB, MiB, GiB.
+    #[default]
+    Binary,
+    /// Powers of 1000: KB, MB, GB.
+    Decimal,
+}
+
+impl FileSizeUnits {
+    pub fn format(&self, size: u64) -> String {
+        self.format_in(size, &Locale::en)
+    }
+
+    fn format_in(&self, size: u64, locale: &Locale) -> String {
+        let (base, units) = match self {
+            FileSizeUnits::Binary => (1024., ["B", "KiB", "MiB", "GiB"]),
+            FileSizeUnits::Decimal => (1000., ["B", "KB", "MB", "GB"]),
+        };
+        if (size as f64) < base {
+            return format!("{} {}", size.to_formatted_string(locale), units[0]);
+        }
+        let mut size = size as f64;
+        let mut unit = 0;
+        while size >= base && unit < units.len() - 1 {
+            size /= base;
+            unit += 1;
+        }
+        let whole = size.trunc() as u64;
+        let tenths = ((size.fract() * 10.).round() as u64).min(9);
+        format!(
+            "{}{}{tenths} {}",
+            whole.to_formatted_string(locale),
+            locale.decimal(),
+            units[unit]
+        )
+    }
+}
+
+/// How big images are printed.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PrintScale {
+    /// Scale images up or down to fill the page.
+    #[default]
+    FitToPage,
+    /// Print `print_dpi` pixels of the image per inch of paper, cropping images larger than the
+    /// page.
+    ActualSize,
+}
+
+/// The paper images are printed on.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PaperSize {
+    /// 8.5 × 11 inches.
+    #[default]
+    Letter,
+    /// 210 × 297 millimeters.
+    A4,
+}
+
+impl PaperSize {
+    /// The width and height of the paper in inches, in portrait orientation.
+    pub(crate) fn inches(&self) -> (f32, f32) {
+        match self {
+            PaperSize::Letter => (8.5, 11.),
+            PaperSize::A4 => (210. / 25.4, 297. / 25.4),
+        }
+    }
+}
+
+/// Formats the numbers shown about images, such as file sizes and dimensions, in the units and
+/// locale chosen in the settings.
+#[derive(Clone, Copy, Debug)]
+pub struct UnitFormatter {
+    file_size_units: FileSizeUnits,
+    locale: Locale,
+}
+
+impl UnitFormatter {
+    pub fn new(file_size_units: FileSizeUnits, locale_name: Option<&str>) -> Self {
+        let locale = match locale_name {
+            Some(locale_name) => parse_locale(locale_name),
+            None => system_locale(),
+        };
+        Self {
+            file_size_units,
+            locale: locale.unwrap_or(Locale::en),
+        }
+    }
+
+    pub fn file_size(&self, size: u64) -> String {
+        self.file_size_units.format_in(size, &self.locale)
+    }
+
+    pub fn dimensions(&self, width: u32, height: u32) -> String {
+        format!(
+            "{} × {}",
+            width.to_formatted_string(&self.locale),
+            height.to_formatted_string(&self.locale)
+        )
+    }
+
+    pub fn count(&self, count: usize) -> String {
+        count.to_formatted_string(&self.locale)
+    }
+}
+
+/// Finds the locale with the given name, such as "fr-CA" or "de_DE.UTF-8", falling back to its
+/// language when there is no locale specific to its region.
+fn parse_locale(name: &str) -> Option<Locale> {
+    let name = name.split('.').next().unwrap_or_default().replace('_', "-");
+    let language = name.split('-').next().unwrap_or_default();
+    Locale::from_name(&name)
+        .or_else(|_| Locale::from_name(language))
+        .ok()
+}
+
+fn system_locale() -> Option<Locale> {
+    static SYSTEM_LOCALE: OnceLock<Option<Locale>> = OnceLock::new();
+    *SYSTEM_LOCALE.get_or_init(|| parse_locale(&sys_locale::get_locale()?))
+}
+
+/// Image viewer settings that apply to images with a particular file extension, overriding the
+/// ones set for all images.
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug, PartialEq)]
+pub struct ImageViewerFileTypeSettings {
+    pub background: Option<ImageViewerBackground>,
+    pub default_zoom: Option<ImageZoomMode>,
+    pub filter: Option<ImageFilter>,
+    pub rendering: Option<ImageRendering>,
+    pub autoplay_animations: Option<bool>,
+    pub pixel_grid: Option<bool>,
+}
+
+/// How an image viewer shows an image when it is opened.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ImageViewerDefaults {
+    pub background: ImageViewerBackground,
+    pub zoom: ImageZoomMode,
+    pub filter: ImageFilter,
+    pub rendering: ImageRendering,
+    pub autoplay_animations: bool,
+    pub pixel_grid: bool,
+}
+
+impl ImageViewerSettings {
+    /// Whether motion should be kept to a minimum, either because of the `reduce_motion` setting
+    /// or, when that isn't set, the system's preference.
+    pub fn should_reduce_motion(&self, cx: &AppContext) -> bool {
+        self.reduce_motion
+            .unwrap_or_else(|| cx.should_reduce_motion())
+    }
+
+    /// The defaults for the image at `path`, taking the settings for its file extension into
+    /// account. Animations don't play on their own when `reduce_motion` is true.
+    pub fn defaults_for_path(&self, path: &Path, reduce_motion: bool) -> ImageViewerDefaults {
+        let file_type = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(|extension| {
+                self.file_types.iter().find_map(|(key, settings)| {
+                    key.trim_start_matches('.')
+                        .eq_ignore_ascii_case(extension)
+                        .then_some(settings)
+                })
+            });
+        let file_type = file_type.cloned().unwrap_or_default();
+        ImageViewerDefaults {
+            background: file_type.background.unwrap_or(self.background),
+            zoom: file_type.default_zoom.unwrap_or(self.default_zoom),
+            filter: file_type.filter.unwrap_or(self.filter),
+            rendering: file_type.rendering.unwrap_or(self.rendering),
+            autoplay_animations: !reduce_motion
+                && file_type
+                    .autoplay_animations
+                    .unwrap_or(self.autoplay_animations),
+            pixel_grid: file_type.pixel_grid.unwrap_or(self.pixel_grid),
+        }
+    }
+
+    pub fn units(&self) -> UnitFormatter {
+        UnitFormatter::new(self.file_size_units, self.number_locale.as_deref())
+    }
+}
+
+/// An application that images can be opened in for editing.
+#[derive(Clone, Serialize, Deserialize, JsonSchema, Debug, PartialEq)]
+pub struct ExternalImageEditor {
+    /// The name shown for the editor, and used to pick it from key bindings.
+    pub name: String,
+    /// The program to run. The path of the image is passed as its last argument.
+    pub command: String,
+    /// Arguments passed before the path of the image.
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct ImageViewerSettingsContent {
+    /// What to draw behind images: "checkerboard", "high_contrast_checkerboard", "editor",
+    /// "black" or "white".
+    ///
+    /// Default: checkerboard
+    pub background: Option<ImageViewerBackground>,
+    /// How big images are shown when they are opened: "fit", "fill", "actual_size", or a
+    /// multiple of their actual size such as `{ "scale": 4 }`.
+    ///
+    /// Default: fit
+    pub default_zoom: Option<ImageZoomMode>,
+    /// A filter to display images with: "none" or "grayscale".
+    ///
+    /// Default: none
+    pub filter: Option<ImageFilter>,
+    /// How images are sampled when they are scaled up: "smooth", or "pixelated" to keep the
+    /// edges of pixels sharp.
+    ///
+    /// Default: smooth
+    pub rendering: Option<ImageRendering>,
+    /// Whether animated images start playing when they are opened.
+    ///
+    /// Default: true
+    pub autoplay_animations: Option<bool>,
+    /// Whether to keep animated images paused when they are opened and show the other image
+    /// without blinking when comparing, regardless of `autoplay_animations`. When null, follows
+    /// the system's reduced motion preference.
+    ///
+    /// Default: null
+    pub reduce_motion: Option<bool>,
+    /// Whether to draw lines between pixels when images are zoomed in far enough to tell them
+    /// apart.
+    ///
+    /// Default: false
+    pub pixel_grid: Option<bool>,
+    /// Settings that apply to images with a particular file extension, such as "png" or "svg",
+    /// overriding the ones above.
+    ///
+    /// Default: {}
+    pub file_types: Option<HashMap<String, ImageViewerFileTypeSettings>>,
+    /// Images larger than this, in megabytes, are not decoded until asked to.
+    ///
+    /// Default: 100
+    pub max_file_size: Option<u64>,
+    /// Images with more pixels than this, in millions, are not decoded until asked to.
+    ///
+    /// Default: 200
+    pub max_decoded_megapixels: Option<f32>,
+    /// How much memory, in megabytes, recently closed images are kept in so that reopening
+    /// them doesn't read and decode them again.
+    ///
+    /// Default: 256
+    pub image_cache_size: Option<u64>,
+    /// How many minutes an image tab has to stay hidden before the memory used to draw its
+    /// image, including on the GPU, is freed. The image is decoded again when the tab is
+    /// shown. 0 keeps images loaded.
+    ///
+    /// Default: 10
+    pub release_hidden_images_after: Option<u64>,
+    /// How many milliseconds to wait between reloads of an image that keeps changing on disk,
+    /// such as one rewritten by a build process. The latest contents are always loaded in the
+    /// end.
+    ///
+    /// Default: 250
+    pub reload_debounce: Option<u64>,
+    /// How many times an image has to change on disk within a minute for its view to be marked
+    /// as live, such as the output of a graphics development loop. 0 never marks images as live.
+    ///
+    /// Default: 10
+    pub live_reload_threshold: Option<u32>,
+    /// Whether the live badge of images that keep changing on disk shows how many times they
+    /// were reloaded and how many times per second they currently are.
+    ///
+    /// Default: false
+    pub show_live_reload_rate: Option<bool>,
+    /// How many of the versions an image was reloaded with are kept in memory, to flip back
+    /// through with `image_viewer: toggle reload history`. 0 keeps none.
+    ///
+    /// Default: 20
+    pub reload_history_size: Option<usize>,
+    /// How many of the next images in a directory are decoded ahead of time while flipping
+    /// through it, so that showing them is instant. They count towards `image_cache_size`.
+    /// 0 disables this.
+    ///
+    /// Default: 2
+    pub prefetch_adjacent_images: Option<usize>,
+    /// The largest width or height, in pixels, that images in preview tabs are decoded at, so
+    /// that skimming through a directory doesn't wait for each image to be decoded in full.
+    /// Images are decoded in full once their tab is kept open or zoomed into. 0 disables this.
+    ///
+    /// Default: 2048
+    pub preview_tab_max_size: Option<u32>,
+    /// The units to show file sizes in: "binary" (KiB, MiB) or "decimal" (KB, MB).
+    ///
+    /// Default: binary
+    pub file_size_units: Option<FileSizeUnits>,
+    /// The locale to format numbers in, such as "de" or "fr-CA", which decides their digit
+    /// grouping and decimal separator. When null, the system's locale is used.
+    ///
+    /// Default: null
+    pub number_locale: Option<Option<String>>,
+    /// Directory, relative to the worktree root, where screenshots captured with
+    /// `image_viewer: capture screenshot` are saved.
+    ///
+    /// Default: "screenshots"
+    pub screenshots_directory: Option<String>,
+    /// How big images are printed: "fit_to_page", or "actual_size" to print `print_dpi` pixels
+    /// of the image per inch of paper.
+    ///
+    /// Default: fit_to_page
+    pub print_scale: Option<PrintScale>,
+    /// How many pixels of an image make an inch of paper when it is printed at its actual size.
+    ///
+    /// Default: 96
+    pub print_dpi: Option<f32>,
+    /// The paper images are printed on: "letter" or "a4".
+    ///
+    /// Default: letter
+    pub print_paper_size: Option<PaperSize>,
+    /// Applications that images can be opened in with `image_viewer: open in external editor`.
+    /// The first one is used unless the action names another. When empty, images are opened
+    /// with the system's default application.
+    ///
+    /// Default: []
+    pub external_editors: Option<Vec<ExternalImageEditor>>,
+}
+
+impl Settings for ImageViewerSettings {
+    const KEY: Option<&'static str> = Some("image_viewer");
+
+    type FileContent = ImageViewerSettingsContent;
+
+    fn load(
+        sources: SettingsSources<Self::FileContent>,
+        _: &mut gpui::AppContext,
+    ) -> anyhow::Result<Self> {
+        sources.json_merge()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_file_size() {
+        assert_eq!(FileSizeUnits::Binary.format(512), "512 B");
+        assert_eq!(FileSizeUnits::Binary.format(1536), "1.5 KiB");
+        assert_eq!(FileSizeUnits::Binary.format(5 * 1024 * 1024), "5.0 MiB");
+        assert_eq!(FileSizeUnits::Decimal.format(1500), "1.5 KB");
+        assert_eq!(FileSizeUnits::Decimal.format(2_000_000_000), "2.0 GB");
+    }
+
+    #[test]
+    fn test_unit_formatter() {
+        let english = UnitFormatter::new(FileSizeUnits::Binary, Some("en-US"));
+        assert_eq!(english.dimensions(1920, 1080), "1,920 × 1,080");
+        assert_eq!(english.file_size(1536), "1.5 KiB");
+
+        let german = UnitFormatter::new(FileSizeUnits::Decimal, Some("de_DE.UTF-8"));
+        assert_eq!(german.dimensions(1920, 1080), "1.920 × 1.080");
+        assert_eq!(german.file_size(1500), "1,5 KB");
+        assert_eq!(german.file_size(999), "999 B");
+        assert_eq!(german.count(12000), "12.000");
+    }
+
+    #[test]
+    fn test_deserialize_zoom_mode() {
+        assert_eq!(
+            serde_json::from_str::<ImageZoomMode>(r#""actual_size""#).unwrap(),
+            ImageZoomMode::ActualSize
+        );
+        assert_eq!(
+            serde_json::from_str::<ImageZoomMode>(r#"{ "scale": 4 }"#).unwrap(),
+            ImageZoomMode::Scale(4.)
+        );
+    }
+
+    #[test]
+    fn test_defaults_for_path() {
+        let settings = ImageViewerSettings {
+            background: ImageViewerBackground::Checkerboard,
+            default_zoom: ImageZoomMode::Fit,
+            filter: ImageFilter::None,
+            rendering: ImageRendering::Smooth,
+            autoplay_animations: true,
+            reduce_motion: None,
+            pixel_grid: false,
+            file_types: HashMap::from_iter([
+                (
+                    "png".to_string(),
+                    ImageViewerFileTypeSettings {
+                        default_zoom: Some(ImageZoomMode::ActualSize),
+                        pixel_grid: Some(true),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    ".svg".to_string(),
+                    ImageViewerFileTypeSettings {
+                        background: Some(ImageViewerBackground::White),
+                        ..Default::default()
+                    },
+                ),
+            ]),
+            max_file_size: 100,
+            max_decoded_megapixels: 200.,
+            image_cache_size: 256,
+            release_hidden_images_after: 10,
+            reload_debounce: 250,
+            live_reload_threshold: 10,
+            show_live_reload_rate: false,
+            reload_history_size: 20,
+            prefetch_adjacent_images: 2,
+            preview_tab_max_size: 2048,
+            file_size_units: FileSizeUnits::Binary,
+            number_locale: None,
+            screenshots_directory: "screenshots".to_string(),
+            print_scale: PrintScale::FitToPage,
+            print_dpi: 96.,
+            print_paper_size: PaperSize::Letter,
+            external_editors: Vec::new(),
+        };
+
+        let defaults = settings.defaults_for_path(Path::new("sprites/hero.PNG"), false);
+        assert_eq!(defaults.zoom, ImageZoomMode::ActualSize);
+        assert!(defaults.pixel_grid);
+        assert_eq!(defaults.background, ImageViewerBackground::Checkerboard);
+
+        let defaults = settings.defaults_for_path(Path::new("logo.svg"), false);
+        assert_eq!(defaults.zoom, ImageZoomMode::Fit);
+        assert_eq!(defaults.background, ImageViewerBackground::White);
+
+        let defaults = settings.defaults_for_path(Path::new("photo.jpg"), false);
+        assert_eq!(defaults.zoom, ImageZoomMode::Fit);
+        assert!(!defaults.pixel_grid);
+        assert!(defaults.autoplay_animations);
+
+        let defaults = settings.defaults_for_path(Path::new("photo.jpg"), true);
+        assert!(!defaults.autoplay_animations);
+    }
+
+    #[test]
+    fn test_cycle_backgrounds() {
+        let mut background = ImageViewerBackground::Checkerboard;
+        let mut seen = Vec::new();
+        for _ in 0..5 {
+            seen.push(background);
+            background = background.next();
+        }
+        assert_eq!(background, ImageViewerBackground::Checkerboard);
+        assert_eq!(seen[1], ImageViewerBackground::HighContrastCheckerboard);
+        assert!(seen.contains(&ImageViewerBackground::Black));
+        assert!(seen.contains(&ImageViewerBackground::White));
+        assert!(seen.contains(&ImageViewerBackground::Editor));
+    }
+}
@@ -0,0 +1,484 @@
+use gpui::AppContext;
+use settings::{EditableSettingControl, Settings};
+use ui::{
+    prelude::*, CheckboxWithLabel, ContextMenu, DropdownMenu, NumericStepper, SettingsContainer,
+    SettingsGroup, ToggleButton,
+};
+
+use crate::{
+    ImageFilter, ImageRendering, ImageViewerBackground, ImageViewerSettings, ImageZoomMode,
+};
+
+#[derive(IntoElement)]
+pub struct ImageViewerSettingsControls {}
+
+impl Default for ImageViewerSettingsControls {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ImageViewerSettingsControls {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl RenderOnce for ImageViewerSettingsControls {
+    fn render(self, _cx: &mut WindowContext) -> impl IntoElement {
+        SettingsContainer::new()
+            .child(
+                SettingsGroup::new("Display")
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .justify_between()
+                            .child(BackgroundControl)
+                            .child(DefaultZoomControl),
+                    )
+                    .child(PixelGridControl),
+            )
+            .child(
+                SettingsGroup::new("Filters")
+                    .child(GrayscaleControl)
+                    .child(PixelatedRenderingControl),
+            )
+            .child(SettingsGroup::new("Animation").child(AutoplayAnimationsControl))
+            .child(
+                SettingsGroup::new("Performance")
+                    .child(MaxFileSizeControl)
+                    .child(MaxDecodedMegapixelsControl)
+                    .child(ImageCacheSizeControl),
+            )
+    }
+}
+
+#[derive(IntoElement)]
+struct BackgroundControl;
+
+impl BackgroundControl {
+    fn label(background: ImageViewerBackground) -> &'static str {
+        match background {
+            ImageViewerBackground::Checkerboard => "Checkerboard",
+            ImageViewerBackground::Editor => "Editor",
+            ImageViewerBackground::Black => "Black",
+            ImageViewerBackground::White => "White",
+            ImageViewerBackground::HighContrastCheckerboard => "High Contrast Checkerboard",
+        }
+    }
+}
+
+impl EditableSettingControl for BackgroundControl {
+    type Value = ImageViewerBackground;
+    type Settings = ImageViewerSettings;
+
+    fn name(&self) -> SharedString {
+        "Background".into()
+    }
+
+    fn read(cx: &AppContext) -> Self::Value {
+        ImageViewerSettings::get_global(cx).background
+    }
+
+    fn apply(
+        settings: &mut <Self::Settings as Settings>::FileContent,
+        value: Self::Value,
+        _cx: &AppContext,
+    ) {
+        settings.background = Some(value);
+    }
+}
+
+impl RenderOnce for BackgroundControl {
+    fn render(self, cx: &mut WindowContext) -> impl IntoElement {
+        let value = Self::read(cx);
+
+        h_flex()
+            .gap_2()
+            .child(Label::new(self.name()))
+            .child(DropdownMenu::new(
+                "image-viewer-background",
+                Self::label(value),
+                ContextMenu::build(cx, |mut menu, _cx| {
+                    for background in [
+                        ImageViewerBackground::Checkerboard,
+                        ImageViewerBackground::HighContrastCheckerboard,
+                        ImageViewerBackground::Editor,
+                        ImageViewerBackground::Black,
+                        ImageViewerBackground::White,
+                    ] {
+                        menu = menu.custom_entry(
+                            move |_cx| Label::new(Self::label(background)).into_any_element(),
+                            move |cx| Self::write(background, cx),
+                        )
+                    }
+
+                    menu
+                }),
+            ))
+    }
+}
+
+#[derive(IntoElement)]
+struct DefaultZoomControl;
+
+impl EditableSettingControl for DefaultZoomControl {
+    type Value = ImageZoomMode;
+    type Settings = ImageViewerSettings;
+
+    fn name(&self) -> SharedString {
+        "Default Zoom".into()
+    }
+
+    fn read(cx: &AppContext) -> Self::Value {
+        ImageViewerSettings::get_global(cx).default_zoom
+    }
+
+    fn apply(
+        settings: &mut <Self::Settings as Settings>::FileContent,
+        value: Self::Value,
+        _cx: &AppContext,
+    ) {
+        settings.default_zoom = Some(value);
+    }
+}
+
+impl RenderOnce for DefaultZoomControl {
+    fn render(self, cx: &mut WindowContext) -> impl IntoElement {
+        let value = Self::read(cx);
+
+        h_flex()
+            .child(
+                ToggleButton::new("zoom-fit", "Fit")
+                    .style(ButtonStyle::Filled)
+                    .size(ButtonSize::Large)
+                    .selected(value == ImageZoomMode::Fit)
+                    .on_click(|_, cx| Self::write(ImageZoomMode::Fit, cx))
+                    .first(),
+            )
+            .child(
+                ToggleButton::new("zoom-fill", "Fill")
+                    .style(ButtonStyle::Filled)
+                    .size(ButtonSize::Large)
+                    .selected(value == ImageZoomMode::Fill)
+                    .on_click(|_, cx| Self::write(ImageZoomMode::Fill, cx))
+                    .middle(),
+            )
+            .child(
+                ToggleButton::new("zoom-actual-size", "Actual Size")
+                    .style(ButtonStyle::Filled)
+                    .size(ButtonSize::Large)
+                    .selected(value == ImageZoomMode::ActualSize)
+                    .on_click(|_, cx| Self::write(ImageZoomMode::ActualSize, cx))
+                    .last(),
+            )
+    }
+}
+
+#[derive(IntoElement)]
+struct PixelGridControl;
+
+impl EditableSettingControl for PixelGridControl {
+    type Value = bool;
+    type Settings = ImageViewerSettings;
+
+    fn name(&self) -> SharedString {
+        "Pixel Grid".into()
+    }
+
+    fn read(cx: &AppContext) -> Self::Value {
+        ImageViewerSettings::get_global(cx).pixel_grid
+    }
+
+    fn apply(
+        settings: &mut <Self::Settings as Settings>::FileContent,
+        value: Self::Value,
+        _cx: &AppContext,
+    ) {
+        settings.pixel_grid = Some(value);
+    }
+}
+
+impl RenderOnce for PixelGridControl {
+    fn render(self, cx: &mut WindowContext) -> impl IntoElement {
+        let value = Self::read(cx);
+
+        CheckboxWithLabel::new(
+            "image-viewer-pixel-grid",
+            Label::new(self.name()),
+            value.into(),
+            |selection, cx| {
+                Self::write(
+                    match selection {
+                        Selection::Selected => true,
+                        Selection::Unselected | Selection::Indeterminate => false,
+                    },
+                    cx,
+                );
+            },
+        )
+    }
+}
+
+#[derive(IntoElement)]
+struct GrayscaleControl;
+
+impl EditableSettingControl for GrayscaleControl {
+    type Value = bool;
+    type Settings = ImageViewerSettings;
+
+    fn name(&self) -> SharedString {
+        "Grayscale".into()
+    }
+
+    fn read(cx: &AppContext) -> Self::Value {
+        ImageViewerSettings::get_global(cx).filter == ImageFilter::Grayscale
+    }
+
+    fn apply(
+        settings: &mut <Self::Settings as Settings>::FileContent,
+        value: Self::Value,
+        _cx: &AppContext,
+    ) {
+        settings.filter = Some(if value {
+            ImageFilter::Grayscale
+        } else {
+            ImageFilter::None
+        });
+    }
+}
+
+impl RenderOnce for GrayscaleControl {
+    fn render(self, cx: &mut WindowContext) -> impl IntoElement {
+        let value = Self::read(cx);
+
+        CheckboxWithLabel::new(
+            "image-viewer-grayscale",
+            Label::new(self.name()),
+            value.into(),
+            |selection, cx| {
+                Self::write(
+                    match selection {
+                        Selection::Selected => true,
+                        Selection::Unselected | Selection::Indeterminate => false,
+                    },
+                    cx,
+                );
+            },
+        )
+    }
+}
+
+#[derive(IntoElement)]
+struct PixelatedRenderingControl;
+
+impl EditableSettingControl for PixelatedRenderingControl {
+    type Value = bool;
+    type Settings = ImageViewerSettings;
+
+    fn name(&self) -> SharedString {
+        "Pixelated Rendering".into()
+    }
+
+    fn read(cx: &AppContext) -> Self::Value {
+        ImageViewerSettings::get_global(cx).rendering == ImageRendering::Pixelated
+    }
+
+    fn apply(
+        settings: &mut <Self::Settings as Settings>::FileContent,
+        value: Self::Value,
+        _cx: &AppContext,
+    ) {
+        settings.rendering = Some(if value {
+            ImageRendering::Pixelated
+        } else {
+            ImageRendering::Smooth
+        });
+    }
+}
+
+impl RenderOnce for PixelatedRenderingControl {
+    fn render(self, cx: &mut WindowContext) -> impl IntoElement {
+        let value = Self::read(cx);
+
+        CheckboxWithLabel::new(
+            "image-viewer-pixelated-rendering",
+            Label::new(self.name()),
+            value.into(),
+            |selection, cx| {
+                Self::write(
+                    match selection {
+                        Selection::Selected => true,
+                        Selection::Unselected | Selection::Indeterminate => false,
+                    },
+                    cx,
+                );
+            },
+        )
+    }
+}
+
+#[derive(IntoElement)]
+struct AutoplayAnimationsControl;
+
+impl EditableSettingControl for AutoplayAnimationsControl {
+    type Value = bool;
+    type Settings = ImageViewerSettings;
+
+    fn name(&self) -> SharedString {
+        "Autoplay Animations".into()
+    }
+
+    fn read(cx: &AppContext) -> Self::Value {
+        ImageViewerSettings::get_global(cx).autoplay_animations
+    }
+
+    fn apply(
+        settings: &mut <Self::Settings as Settings>::FileContent,
+        value: Self::Value,
+        _cx: &AppContext,
+    ) {
+        settings.autoplay_animations = Some(value);
+    }
+}
+
+impl RenderOnce for AutoplayAnimationsControl {
+    fn render(self, cx: &mut WindowContext) -> impl IntoElement {
+        let value = Self::read(cx);
+
+        CheckboxWithLabel::new(
+            "image-viewer-autoplay-animations",
+            Label::new(self.name()),
+            value.into(),
+            |selection, cx| {
+                Self::write(
+                    match selection {
+                        Selection::Selected => true,
+                        Selection::Unselected | Selection::Indeterminate => false,
+                    },
+                    cx,
+                );
+            },
+        )
+    }
+}
+
+#[derive(IntoElement)]
+struct MaxFileSizeControl;
+
+impl EditableSettingControl for MaxFileSizeControl {
+    type Value = u64;
+    type Settings = ImageViewerSettings;
+
+    fn name(&self) -> SharedString {
+        "Largest File Size (MB)".into()
+    }
+
+    fn read(cx: &AppContext) -> Self::Value {
+        ImageViewerSettings::get_global(cx).max_file_size
+    }
+
+    fn apply(
+        settings: &mut <Self::Settings as Settings>::FileContent,
+        value: Self::Value,
+        _cx: &AppContext,
+    ) {
+        settings.max_file_size = Some(value);
+    }
+}
+
+impl RenderOnce for MaxFileSizeControl {
+    fn render(self, cx: &mut WindowContext) -> impl IntoElement {
+        let value = Self::read(cx);
+
+        h_flex()
+            .gap_2()
+            .child(Label::new(self.name()))
+            .child(NumericStepper::new(
+                "image-viewer-max-file-size",
+                value.to_string(),
+                move |_, cx| Self::write(value.saturating_sub(10).max(10), cx),
+                move |_, cx| Self::write(value + 10, cx),
+            ))
+    }
+}
+
+#[derive(IntoElement)]
+struct MaxDecodedMegapixelsControl;
+
+impl EditableSettingControl for MaxDecodedMegapixelsControl {
+    type Value = f32;
+    type Settings = ImageViewerSettings;
+
+    fn name(&self) -> SharedString {
+        "Most Pixels (Millions)".into()
+    }
+
+    fn read(cx: &AppContext) -> Self::Value {
+        ImageViewerSettings::get_global(cx).max_decoded_megapixels
+    }
+
+    fn apply(
+        settings: &mut <Self::Settings as Settings>::FileContent,
+        value: Self::Value,
+        _cx: &AppContext,
+    ) {
+        settings.max_decoded_megapixels = Some(value);
+    }
+}
+
+impl RenderOnce for MaxDecodedMegapixelsControl {
+    fn render(self, cx: &mut WindowContext) -> impl IntoElement {
+        let value = Self::read(cx);
+
+        h_flex()
+            .gap_2()
+            .child(Label::new(self.name()))
+            .child(NumericStepper::new(
+                "image-viewer-max-decoded-megapixels",
+                value.to_string(),
+                move |_, cx| Self::write((value - 50.).max(50.), cx),
+                move |_, cx| Self::write(value + 50., cx),
+            ))
+    }
+}
+
+#[derive(IntoElement)]
+struct ImageCacheSizeControl;
+
+impl EditableSettingControl for ImageCacheSizeControl {
+    type Value = u64;
+    type Settings = ImageViewerSettings;
+
+    fn name(&self) -> SharedString {
+        "Image Cache Size (MB)".into()
+    }
+
+    fn read(cx: &AppContext) -> Self::Value {
+        ImageViewerSettings::get_global(cx).image_cache_size
+    }
+
+    fn apply(
+        settings: &mut <Self::Settings as Settings>::FileContent,
+        value: Self::Value,
+        _cx: &AppContext,
+    ) {
+        settings.image_cache_size = Some(value);
+    }
+}
+
+impl RenderOnce for ImageCacheSizeControl {
+    fn render(self, cx: &mut WindowContext) -> impl IntoElement {
+        let value = Self::read(cx);
+
+        h_flex()
+            .gap_2()
+            .child(Label::new(self.name()))
+            .child(NumericStepper::new(
+                "image-viewer-image-cache-size",
+                value.to_string(),
+                move |_, cx| Self::write(value.saturating_sub(64), cx),
+                move |_, cx| Self::write(value + 64, cx),
+            ))
+    }
+}
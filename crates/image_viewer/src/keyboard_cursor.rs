@@ -0,0 +1,85 @@
+//! Operating the viewer's tools without a mouse. While a tool is active, the arrow keys move a
+//! virtual cursor over the image, or the compare divider, and enter commits at the cursor, such
+//! as starting or finishing an annotation stroke.
+
+use gpui::{actions, impl_actions, point, Point};
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CursorDirection {
+    #[default]
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// Moves the virtual cursor of the active tool.
+#[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema)]
+pub struct MoveToolCursor {
+    pub direction: CursorDirection,
+    /// Whether to move by a larger step.
+    #[serde(default)]
+    pub far: bool,
+}
+
+impl_actions!(image_viewer, [MoveToolCursor]);
+actions!(image_viewer, [CommitToolCursor]);
+
+/// How far a step moves the cursor, as a fraction of the image's width or height.
+const STEP: f32 = 0.01;
+const FAR_STEP: f32 = 0.1;
+
+impl MoveToolCursor {
+    /// The horizontal and vertical distance to move by, normalized to the image.
+    pub(crate) fn offset(&self) -> Point<f32> {
+        let step = if self.far { FAR_STEP } else { STEP };
+        match self.direction {
+            CursorDirection::Left => point(-step, 0.),
+            CursorDirection::Right => point(step, 0.),
+            CursorDirection::Up => point(0., -step),
+            CursorDirection::Down => point(0., step),
+        }
+    }
+}
+
+/// Where the cursor starts when a tool is first used from the keyboard.
+pub(crate) fn initial_cursor() -> Point<f32> {
+    point(0.5, 0.5)
+}
+
+/// Moves a position normalized to the image, keeping it over the image.
+pub(crate) fn move_cursor(position: Point<f32>, action: &MoveToolCursor) -> Point<f32> {
+    let offset = action.offset();
+    point(
+        (position.x + offset.x).clamp(0., 1.),
+        (position.y + offset.y).clamp(0., 1.),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_move_cursor() {
+        let right = MoveToolCursor {
+            direction: CursorDirection::Right,
+            far: false,
+        };
+        let far_up = MoveToolCursor {
+            direction: CursorDirection::Up,
+            far: true,
+        };
+
+        let position = move_cursor(initial_cursor(), &right);
+        assert!((position.x - 0.51).abs() < 1e-6);
+        assert_eq!(position.y, 0.5);
+
+        let position = move_cursor(point(0.5, 0.05), &far_up);
+        assert_eq!(position, point(0.5, 0.));
+        assert_eq!(move_cursor(point(1., 0.), &right), point(1., 0.));
+    }
+}
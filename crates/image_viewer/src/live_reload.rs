@@ -0,0 +1,162 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use gpui::ViewContext;
+use ui::{prelude::*, Indicator};
+
+use crate::ImageView;
+
+/// How far back reloads are counted to decide whether an image is live.
+const LIVE_WINDOW: Duration = Duration::from_secs(60);
+/// How far back reloads are counted for the rate shown in the live badge, which should follow
+/// changes in how fast the image is regenerated quickly.
+const RATE_WINDOW: Duration = Duration::from_secs(5);
+/// How often the live badge is redrawn, so that its rate stays current and it goes away once
+/// the image stops changing.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// When an image was reloaded after changing on disk, to tell images that are actively
+/// regenerated, such as the output of a graphics development loop, from ones that changed once.
+#[derive(Default)]
+pub(crate) struct LiveReloads {
+    /// The reloads within [`LIVE_WINDOW`], oldest first.
+    reloads: VecDeque<Instant>,
+    /// How many times the image was reloaded since it was opened.
+    total: usize,
+}
+
+impl LiveReloads {
+    fn record(&mut self, now: Instant) {
+        self.reloads.push_back(now);
+        self.total += 1;
+        self.prune(now);
+    }
+
+    fn prune(&mut self, now: Instant) {
+        while self
+            .reloads
+            .front()
+            .is_some_and(|reload| now.duration_since(*reload) > LIVE_WINDOW)
+        {
+            self.reloads.pop_front();
+        }
+    }
+
+    /// Whether the image changed at least `threshold` times within the last minute.
+    /// A threshold of 0 never marks images as live.
+    fn is_live(&self, threshold: u32, now: Instant) -> bool {
+        let recent = self
+            .reloads
+            .iter()
+            .filter(|reload| now.duration_since(**reload) <= LIVE_WINDOW)
+            .count();
+        threshold > 0 && recent >= threshold as usize
+    }
+
+    /// How many times per second the image was reloaded recently.
+    fn rate(&self, now: Instant) -> f32 {
+        let recent = self
+            .reloads
+            .iter()
+            .filter(|reload| now.duration_since(**reload) <= RATE_WINDOW)
+            .count();
+        recent as f32 / RATE_WINDOW.as_secs_f32()
+    }
+}
+
+/// Counts a reload of the image, and keeps the live badge current while the image is live.
+pub(crate) fn record_reload(image_view: &mut ImageView, cx: &mut ViewContext<ImageView>) {
+    let now = Instant::now();
+    image_view.live_reloads.record(now);
+    let threshold = image_view.settings(cx).live_reload_threshold;
+    if image_view.live_reloads.is_live(threshold, now) && image_view.live_reload_task.is_none() {
+        image_view.live_reload_task = Some(cx.spawn(|this, mut cx| async move {
+            loop {
+                cx.background_executor().timer(REFRESH_INTERVAL).await;
+                let live = this.update(&mut cx, |this, cx| {
+                    let now = Instant::now();
+                    this.live_reloads.prune(now);
+                    let threshold = this.settings(cx).live_reload_threshold;
+                    let live = this.live_reloads.is_live(threshold, now);
+                    if !live {
+                        this.live_reload_task = None;
+                    }
+                    cx.notify();
+                    live
+                });
+                if !matches!(live, Ok(true)) {
+                    break;
+                }
+            }
+        }));
+    }
+}
+
+/// A badge shown over images that keep changing on disk, with how often they do when
+/// `show_live_reload_rate` is enabled.
+pub(crate) fn render_live_badge(
+    image_view: &ImageView,
+    cx: &mut ViewContext<ImageView>,
+) -> Option<impl IntoElement> {
+    let settings = image_view.settings(cx);
+    let now = Instant::now();
+    let live_reloads = &image_view.live_reloads;
+    if !live_reloads.is_live(settings.live_reload_threshold, now) {
+        return None;
+    }
+    let rate = settings.show_live_reload_rate.then(|| {
+        format!(
+            "{} updates · {:.1}/s",
+            live_reloads.total,
+            live_reloads.rate(now)
+        )
+    });
+    Some(
+        h_flex()
+            .absolute()
+            .top_2()
+            .right_2()
+            .px_1p5()
+            .py_0p5()
+            .gap_1p5()
+            .rounded_md()
+            .bg(cx.theme().colors().elevated_surface_background)
+            .border_1()
+            .border_color(cx.theme().status().success_border)
+            .child(Indicator::dot().color(Color::Success))
+            .child(
+                Label::new("Live")
+                    .size(LabelSize::Small)
+                    .color(Color::Success),
+            )
+            .children(rate.map(|rate| Label::new(rate).size(LabelSize::Small).color(Color::Muted))),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_live_reloads() {
+        let start = Instant::now();
+        let mut live_reloads = LiveReloads::default();
+        for i in 0..10 {
+            live_reloads.record(start + Duration::from_millis(500 * i));
+        }
+        let now = start + Duration::from_millis(4500);
+        assert!(live_reloads.is_live(10, now));
+        assert!(!live_reloads.is_live(11, now));
+        assert!(!live_reloads.is_live(0, now));
+        assert_eq!(live_reloads.rate(now), 2.);
+
+        let later = start + Duration::from_secs(90);
+        assert!(!live_reloads.is_live(1, later));
+        assert_eq!(live_reloads.rate(later), 0.);
+        live_reloads.record(later);
+        assert_eq!(live_reloads.reloads.len(), 1);
+        assert_eq!(live_reloads.total, 11);
+    }
+}
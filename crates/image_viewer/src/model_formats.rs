@@ -0,0 +1,554 @@
+//! Reading the triangles of 3D models from Wavefront OBJ and glTF files, which is all that's
+//! needed to preview their shape.
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, bail, Context as _};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde::Deserialize;
+
+/// The triangles of a model, with every part of it moved into place.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Mesh {
+    pub positions: Vec<[f32; 3]>,
+    pub triangles: Vec<[u32; 3]>,
+}
+
+impl Mesh {
+    /// The center of the mesh's bounding box, and the radius of the sphere around it that
+    /// contains the whole mesh.
+    pub fn bounding_sphere(&self) -> Option<([f32; 3], f32)> {
+        let first = *self.positions.first()?;
+        let (min, max) =
+            self.positions
+                .iter()
+                .fold((first, first), |(mut min, mut max), position| {
+                    for axis in 0..3 {
+                        min[axis] = min[axis].min(position[axis]);
+                        max[axis] = max[axis].max(position[axis]);
+                    }
+                    (min, max)
+                });
+        let center = [0, 1, 2].map(|axis| (min[axis] + max[axis]) / 2.);
+        let radius = self
+            .positions
+            .iter()
+            .map(|position| {
+                (0..3)
+                    .map(|axis| (position[axis] - center[axis]).powi(2))
+                    .sum::<f32>()
+                    .sqrt()
+            })
+            .fold(0., f32::max);
+        Some((center, radius))
+    }
+}
+
+/// Reads the faces of an OBJ file, as triangle fans, ignoring everything but vertex positions.
+pub(crate) fn parse_obj(text: &str) -> anyhow::Result<Mesh> {
+    let mut mesh = Mesh::default();
+    for (row, line) in text.lines().enumerate() {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("v") => {
+                let mut position = [0.; 3];
+                for coordinate in &mut position {
+                    *coordinate = words
+                        .next()
+                        .and_then(|word| word.parse().ok())
+                        .with_context(|| format!("invalid vertex on line {}", row + 1))?;
+                }
+                mesh.positions.push(position);
+            }
+            Some("f") => {
+                let vertices = words
+                    .map(|word| {
+                        // Faces refer to vertices by `position/texture/normal`, counting from 1, or
+                        // from the end when negative.
+                        let index = word.split('/').next().unwrap_or_default();
+                        let index: i64 = index.parse().ok()?;
+                        let index = match index {
+                            index if index > 0 => index - 1,
+                            index => mesh.positions.len() as i64 + index,
+                        };
+                        (0..mesh.positions.len() as i64)
+                            .contains(&index)
+                            .then_some(index as u32)
+                    })
+                    .collect::<Option<Vec<_>>>()
+                    .with_context(|| format!("invalid face on line {}", row + 1))?;
+                for corner in 2..vertices.len() {
+                    mesh.triangles
+                        .push([vertices[0], vertices[corner - 1], vertices[corner]]);
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(mesh)
+}
+
+const GLB_MAGIC: &[u8] = b"glTF";
+const GLB_JSON_CHUNK: u32 = 0x4E4F534A;
+const GLB_BIN_CHUNK: u32 = 0x004E4942;
+
+#[derive(Deserialize)]
+struct Gltf {
+    #[serde(default)]
+    buffers: Vec<GltfBuffer>,
+    #[serde(default, rename = "bufferViews")]
+    buffer_views: Vec<GltfBufferView>,
+    #[serde(default)]
+    accessors: Vec<GltfAccessor>,
+    #[serde(default)]
+    meshes: Vec<GltfMesh>,
+    #[serde(default)]
+    nodes: Vec<GltfNode>,
+    #[serde(default)]
+    scenes: Vec<GltfScene>,
+    scene: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct GltfBuffer {
+    uri: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GltfBufferView {
+    buffer: usize,
+    #[serde(default)]
+    byte_offset: usize,
+    byte_length: usize,
+    byte_stride: Option<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GltfAccessor {
+    buffer_view: Option<usize>,
+    #[serde(default)]
+    byte_offset: usize,
+    component_type: u32,
+    count: usize,
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+#[derive(Deserialize)]
+struct GltfMesh {
+    primitives: Vec<GltfPrimitive>,
+}
+
+#[derive(Deserialize)]
+struct GltfPrimitive {
+    attributes: HashMap<String, usize>,
+    indices: Option<usize>,
+    mode: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct GltfNode {
+    mesh: Option<usize>,
+    #[serde(default)]
+    children: Vec<usize>,
+    matrix: Option<Matrix>,
+    translation: Option<[f32; 3]>,
+    rotation: Option<[f32; 4]>,
+    scale: Option<[f32; 3]>,
+}
+
+#[derive(Deserialize)]
+struct GltfScene {
+    #[serde(default)]
+    nodes: Vec<usize>,
+}
+
+/// A column-major 4×4 transformation matrix, as glTF stores them.
+type Matrix = [f32; 16];
+
+const IDENTITY: Matrix = [
+    1., 0., 0., 0., //
+    0., 1., 0., 0., //
+    0., 0., 1., 0., //
+    0., 0., 0., 1.,
+];
+
+fn multiply(a: &Matrix, b: &Matrix) -> Matrix {
+    let mut product = [0.; 16];
+    for column in 0..4 {
+        for row in 0..4 {
+            product[column * 4 + row] = (0..4).map(|k| a[k * 4 + row] * b[column * 4 + k]).sum();
+        }
+    }
+    product
+}
+
+fn transform_point(matrix: &Matrix, [x, y, z]: [f32; 3]) -> [f32; 3] {
+    [0, 1, 2]
+        .map(|row| matrix[row] * x + matrix[4 + row] * y + matrix[8 + row] * z + matrix[12 + row])
+}
+
+impl GltfNode {
+    fn local_transform(&self) -> Matrix {
+        if let Some(matrix) = self.matrix {
+            return matrix;
+        }
+        let [tx, ty, tz] = self.translation.unwrap_or([0., 0., 0.]);
+        let [x, y, z, w] = self.rotation.unwrap_or([0., 0., 0., 1.]);
+        let [sx, sy, sz] = self.scale.unwrap_or([1., 1., 1.]);
+        // Translation × rotation × scale, with the rotation given as a unit quaternion.
+        [
+            (1. - 2. * (y * y + z * z)) * sx,
+            (2. * (x * y + z * w)) * sx,
+            (2. * (x * z - y * w)) * sx,
+            0.,
+            (2. * (x * y - z * w)) * sy,
+            (1. - 2. * (x * x + z * z)) * sy,
+            (2. * (y * z + x * w)) * sy,
+            0.,
+            (2. * (x * z + y * w)) * sz,
+            (2. * (y * z - x * w)) * sz,
+            (1. - 2. * (x * x + y * y)) * sz,
+            0.,
+            tx,
+            ty,
+            tz,
+            1.,
+        ]
+    }
+}
+
+/// Splits a glTF file into its JSON and, for binary `.glb` files, the buffer stored with it.
+fn split_gltf(content: &[u8]) -> anyhow::Result<(&[u8], Option<&[u8]>)> {
+    if !content.starts_with(GLB_MAGIC) {
+        return Ok((content, None));
+    }
+    let read_u32 = |offset: usize| -> anyhow::Result<u32> {
+        let bytes = content
+            .get(offset..offset + 4)
+            .context("truncated GLB file")?;
+        Ok(u32::from_le_bytes(bytes.try_into()?))
+    };
+    let mut json = None;
+    let mut bin = None;
+    // Chunks follow the 12 byte header, each starting with its length and type.
+    let mut offset = 12;
+    while offset + 8 <= content.len() {
+        let length = read_u32(offset)? as usize;
+        let kind = read_u32(offset + 4)?;
+        let data = content
+            .get(offset + 8..offset + 8 + length)
+            .context("truncated GLB chunk")?;
+        match kind {
+            GLB_JSON_CHUNK => json = Some(data),
+            GLB_BIN_CHUNK => bin = bin.or(Some(data)),
+            _ => {}
+        }
+        offset += 8 + length;
+    }
+    Ok((json.context("GLB file has no JSON chunk")?, bin))
+}
+
+/// The URIs of the buffers a glTF file keeps in separate files, which have to be passed to
+/// [`parse_gltf`].
+pub(crate) fn gltf_external_buffers(content: &[u8]) -> anyhow::Result<Vec<String>> {
+    let (json, _) = split_gltf(content)?;
+    let gltf: Gltf = serde_json::from_slice(json)?;
+    Ok(gltf
+        .buffers
+        .into_iter()
+        .filter_map(|buffer| buffer.uri)
+        .filter(|uri| !uri.starts_with("data:"))
+        .collect())
+}
+
+/// Reads the triangles of the default scene of a glTF file, given the contents of the files
+/// its buffers are kept in by URI.
+pub(crate) fn parse_gltf(
+    content: &[u8],
+    external_buffers: &HashMap<String, Vec<u8>>,
+) -> anyhow::Result<Mesh> {
+    let (json, glb_buffer) = split_gltf(content)?;
+    let gltf: Gltf = serde_json::from_slice(json)?;
+
+    let mut decoded_buffers = Vec::new();
+    for buffer in &gltf.buffers {
+        decoded_buffers.push(match &buffer.uri {
+            Some(uri) if uri.starts_with("data:") => {
+                let (_, data) = uri.split_once(',').context("invalid data URI")?;
+                Some(STANDARD.decode(data)?)
+            }
+            _ => None,
+        });
+    }
+    let buffers = gltf
+        .buffers
+        .iter()
+        .zip(&decoded_buffers)
+        .map(|(buffer, decoded)| match (&buffer.uri, decoded) {
+            (_, Some(decoded)) => Ok(decoded.as_slice()),
+            (Some(uri), None) => external_buffers
+                .get(uri)
+                .map(Vec::as_slice)
+                .with_context(|| format!("buffer {uri} wasn't loaded")),
+            (None, None) => glb_buffer.context("GLB file has no binary chunk"),
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let reader = AccessorReader {
+        gltf: &gltf,
+        buffers: &buffers,
+    };
+    let mut mesh = Mesh::default();
+    let mut add_mesh = |mesh_index: usize, transform: &Matrix| -> anyhow::Result<()> {
+        let gltf_mesh = gltf.meshes.get(mesh_index).context("invalid mesh index")?;
+        for primitive in &gltf_mesh.primitives {
+            // Only triangle lists are read, since points and lines don't have a shape to show.
+            if primitive.mode.unwrap_or(4) != 4 {
+                continue;
+            }
+            let Some(&positions) = primitive.attributes.get("POSITION") else {
+                continue;
+            };
+            let first_vertex = mesh.positions.len() as u32;
+            let positions = reader.read_positions(positions)?;
+            let vertex_count = positions.len() as u32;
+            mesh.positions.extend(
+                positions
+                    .into_iter()
+                    .map(|position| transform_point(transform, position)),
+            );
+            let indices = match primitive.indices {
+                Some(indices) => reader.read_indices(indices)?,
+                None => (0..vertex_count).collect(),
+            };
+            for triangle in indices.chunks_exact(3) {
+                if triangle.iter().any(|&index| index >= vertex_count) {
+                    bail!("vertex index out of range");
+                }
+                mesh.triangles.push([
+                    first_vertex + triangle[0],
+                    first_vertex + triangle[1],
+                    first_vertex + triangle[2],
+                ]);
+            }
+        }
+        Ok(())
+    };
+
+    let roots = match gltf.scene.or((!gltf.scenes.is_empty()).then_some(0)) {
+        Some(scene) => gltf
+            .scenes
+            .get(scene)
+            .context("invalid scene index")?
+            .nodes
+            .clone(),
+        // Files without scenes are libraries of meshes, which are all shown in place.
+        None => {
+            for mesh_index in 0..gltf.meshes.len() {
+                add_mesh(mesh_index, &IDENTITY)?;
+            }
+            return Ok(mesh);
+        }
+    };
+    let mut stack = roots
+        .into_iter()
+        .map(|node| (node, IDENTITY, 0))
+        .collect::<Vec<_>>();
+    while let Some((node_index, parent_transform, depth)) = stack.pop() {
+        // Guards against nodes that are their own ancestors, which valid files don't have.
+        if depth > 64 {
+            bail!("node hierarchy is too deep");
+        }
+        let node = gltf.nodes.get(node_index).context("invalid node index")?;
+        let transform = multiply(&parent_transform, &node.local_transform());
+        if let Some(mesh_index) = node.mesh {
+            add_mesh(mesh_index, &transform)?;
+        }
+        stack.extend(
+            node.children
+                .iter()
+                .map(|&child| (child, transform, depth + 1)),
+        );
+    }
+    Ok(mesh)
+}
+
+/// Reads the values of glTF accessors out of the file's buffers.
+struct AccessorReader<'a> {
+    gltf: &'a Gltf,
+    buffers: &'a [&'a [u8]],
+}
+
+impl AccessorReader<'_> {
+    /// The bytes of each element of the accessor, which are `element_size` bytes long.
+    fn elements(&self, index: usize, element_size: usize) -> anyhow::Result<Vec<&[u8]>> {
+        let accessor = self
+            .gltf
+            .accessors
+            .get(index)
+            .context("invalid accessor index")?;
+        // Accessors without a buffer view are all zeros, and only used with sparse values.
+        let view_index = accessor
+            .buffer_view
+            .ok_or_else(|| anyhow!("sparse accessors aren't supported"))?;
+        let view = self
+            .gltf
+            .buffer_views
+            .get(view_index)
+            .context("invalid buffer view index")?;
+        let buffer = self
+            .buffers
+            .get(view.buffer)
+            .context("invalid buffer index")?;
+        let view_bytes = buffer
+            .get(view.byte_offset..view.byte_offset + view.byte_length)
+            .context("buffer view out of range")?;
+        let stride = view.byte_stride.unwrap_or(element_size);
+        (0..accessor.count)
+            .map(|element| {
+                let start = accessor.byte_offset + element * stride;
+                view_bytes
+                    .get(start..start + element_size)
+                    .context("accessor out of range")
+            })
+            .collect()
+    }
+
+    fn read_positions(&self, index: usize) -> anyhow::Result<Vec<[f32; 3]>> {
+        let accessor = self
+            .gltf
+            .accessors
+            .get(index)
+            .context("invalid accessor index")?;
+        if accessor.kind != "VEC3" || accessor.component_type != 5126 {
+            bail!("positions have to be three floats");
+        }
+        Ok(self
+            .elements(index, 12)?
+            .into_iter()
+            .map(|bytes| {
+                [0, 1, 2].map(|axis| {
+                    f32::from_le_bytes(bytes[axis * 4..axis * 4 + 4].try_into().unwrap())
+                })
+            })
+            .collect())
+    }
+
+    fn read_indices(&self, index: usize) -> anyhow::Result<Vec<u32>> {
+        let accessor = self
+            .gltf
+            .accessors
+            .get(index)
+            .context("invalid accessor index")?;
+        let element_size = match accessor.component_type {
+            5121 => 1,
+            5123 => 2,
+            5125 => 4,
+            component_type => bail!("invalid index component type {component_type}"),
+        };
+        Ok(self
+            .elements(index, element_size)?
+            .into_iter()
+            .map(|bytes| match bytes.len() {
+                1 => bytes[0] as u32,
+                2 => u16::from_le_bytes([bytes[0], bytes[1]]) as u32,
+                _ => u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_obj() {
+        let mesh = parse_obj(
+            "# A unit square, as one quad.\n\
+             o square\n\
+             v 0 0 0\n\
+             v 1 0 0\n\
+             v 1 1 0\n\
+             v 0 1 0\n\
+             vn 0 0 1\n\
+             f 1//1 2//1 3//1 -1//1\n",
+        )
+        .unwrap();
+        assert_eq!(mesh.positions.len(), 4);
+        assert_eq!(mesh.triangles, vec![[0, 1, 2], [0, 2, 3]]);
+        assert_eq!(
+            mesh.bounding_sphere(),
+            Some(([0.5, 0.5, 0.], 0.5f32.sqrt()))
+        );
+
+        assert!(parse_obj("v 0 0 0\nf 1 2 3\n").is_err());
+        assert!(parse_obj("v 0 zero 0\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_gltf() {
+        // One triangle, moved along x by its node, with its positions in a data URI.
+        let positions = [[0f32, 0., 0.], [1., 0., 0.], [0., 1., 0.]]
+            .iter()
+            .flatten()
+            .flat_map(|coordinate| coordinate.to_le_bytes())
+            .collect::<Vec<u8>>();
+        let gltf = serde_json::json!({
+            "scene": 0,
+            "scenes": [{ "nodes": [0] }],
+            "nodes": [{ "mesh": 0, "translation": [2.0, 0.0, 0.0] }],
+            "meshes": [{ "primitives": [{ "attributes": { "POSITION": 0 } }] }],
+            "accessors": [
+                { "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3" }
+            ],
+            "bufferViews": [{ "buffer": 0, "byteLength": 36 }],
+            "buffers": [{
+                "byteLength": 36,
+                "uri": format!("data:application/octet-stream;base64,{}", STANDARD.encode(&positions))
+            }]
+        });
+        let content = serde_json::to_vec(&gltf).unwrap();
+        assert!(gltf_external_buffers(&content).unwrap().is_empty());
+        let mesh = parse_gltf(&content, &HashMap::default()).unwrap();
+        assert_eq!(
+            mesh.positions,
+            vec![[2., 0., 0.], [3., 0., 0.], [2., 1., 0.]]
+        );
+        assert_eq!(mesh.triangles, vec![[0, 1, 2]]);
+
+        // The same file, with its buffer kept next to it.
+        let mut gltf = gltf;
+        gltf["buffers"][0]["uri"] = "triangle.bin".into();
+        let content = serde_json::to_vec(&gltf).unwrap();
+        assert_eq!(
+            gltf_external_buffers(&content).unwrap(),
+            vec!["triangle.bin"]
+        );
+        assert!(parse_gltf(&content, &HashMap::default()).is_err());
+        let external_buffers = HashMap::from_iter([("triangle.bin".to_string(), positions)]);
+        assert_eq!(
+            parse_gltf(&content, &external_buffers).unwrap().triangles,
+            vec![[0, 1, 2]]
+        );
+    }
+
+    #[test]
+    fn test_node_transform() {
+        // A quarter turn around z, which takes x to y.
+        let half_sqrt_2 = 0.5f32.sqrt();
+        let node = GltfNode {
+            mesh: None,
+            children: Vec::new(),
+            matrix: None,
+            translation: None,
+            rotation: Some([0., 0., half_sqrt_2, half_sqrt_2]),
+            scale: Some([2., 2., 2.]),
+        };
+        let [x, y, z] = transform_point(&node.local_transform(), [1., 0., 0.]);
+        assert!(x.abs() < 1e-6 && (y - 2.).abs() < 1e-6 && z.abs() < 1e-6);
+    }
+}
@@ -0,0 +1,570 @@
+//! Previews of the 3D models of the project, drawn in software from a camera that can be
+//! orbited around the model by dragging, so that models can be told apart without opening them
+//! in a modeling tool.
+
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+use anyhow::Context as _;
+use gpui::{
+    img, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, Model, MouseButton,
+    MouseDownEvent, MouseMoveEvent, ObjectFit, Pixels, Point, RenderImage, Task, View, WeakView,
+};
+use image::RgbaImage;
+use project::Project;
+use serde::{Deserialize, Serialize};
+use settings::Settings;
+use theme::Theme;
+use ui::{prelude::*, Tooltip};
+use util::ResultExt;
+use workspace::{
+    item::{BreadcrumbText, Item, ProjectItem, SerializableItem, TabContentParams},
+    ItemId, ToolbarItemLocation, Workspace, WorkspaceId,
+};
+
+use crate::{
+    asset_viewer::{self, AssetItem, AssetKind, AssetView},
+    model_formats::{gltf_external_buffers, parse_gltf, parse_obj, Mesh},
+    ImageViewerSettings,
+};
+
+/// The size of the square the model is drawn in, in pixels.
+const SNAPSHOT_SIZE: u32 = 640;
+
+/// How far the camera turns per pixel dragged, in radians.
+const ORBIT_SPEED: f32 = 0.01;
+
+/// The color of the model, a grey that stands out from both light and dark themes.
+const MODEL_COLOR: [f32; 3] = [0.72, 0.72, 0.74];
+
+pub struct ModelFiles;
+
+impl AssetKind for ModelFiles {
+    const EXTENSIONS: &'static [&'static str] = &["glb", "gltf", "obj"];
+}
+
+/// Where the camera looks at the model from.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+struct Orbit {
+    /// The turn around the vertical axis, in radians.
+    yaw: f32,
+    /// The tilt toward looking from above, in radians.
+    pitch: f32,
+}
+
+impl Default for Orbit {
+    /// Three quarters from above, which shows the front, a side and the top of most models.
+    fn default() -> Self {
+        Self {
+            yaw: -std::f32::consts::FRAC_PI_4,
+            pitch: 0.45,
+        }
+    }
+}
+
+impl Orbit {
+    fn dragged(self, delta: Point<Pixels>) -> Self {
+        let limit = std::f32::consts::FRAC_PI_2;
+        Self {
+            yaw: self.yaw + delta.x.0 * ORBIT_SPEED,
+            pitch: (self.pitch + delta.y.0 * ORBIT_SPEED).clamp(-limit, limit),
+        }
+    }
+
+    /// Moves a point of model space, relative to the center of the model, into camera space,
+    /// in which the camera looks down the negative z axis.
+    fn to_camera(self, [x, y, z]: [f32; 3]) -> [f32; 3] {
+        let (yaw_sin, yaw_cos) = self.yaw.sin_cos();
+        let (x, z) = (x * yaw_cos + z * yaw_sin, z * yaw_cos - x * yaw_sin);
+        let (pitch_sin, pitch_cos) = self.pitch.sin_cos();
+        let (y, z) = (y * pitch_cos - z * pitch_sin, y * pitch_sin + z * pitch_cos);
+        [x, y, z]
+    }
+}
+
+/// Draws the mesh seen from `orbit`, fitted to a square of `size` pixels and lit from the
+/// camera, in BGRA order.
+fn render_mesh(mesh: &Mesh, orbit: Orbit, size: u32) -> RgbaImage {
+    let mut image = RgbaImage::new(size, size);
+    let Some((center, radius)) = mesh.bounding_sphere() else {
+        return image;
+    };
+    let scale = size as f32 / 2. * 0.9 / radius.max(f32::EPSILON);
+    let half_size = size as f32 / 2.;
+    let points = mesh
+        .positions
+        .iter()
+        .map(|position| {
+            let [x, y, z] = orbit.to_camera([0, 1, 2].map(|axis| position[axis] - center[axis]));
+            [half_size + x * scale, half_size - y * scale, z * scale]
+        })
+        .collect::<Vec<_>>();
+
+    let mut depths = vec![f32::NEG_INFINITY; (size * size) as usize];
+    for triangle in &mesh.triangles {
+        let [a, b, c] = triangle.map(|index| points[index as usize]);
+        let [ab, ac] = [b, c].map(|point| [0, 1, 2].map(|axis| point[axis] - a[axis]));
+        let normal = [
+            ab[1] * ac[2] - ab[2] * ac[1],
+            ab[2] * ac[0] - ab[0] * ac[2],
+            ab[0] * ac[1] - ab[1] * ac[0],
+        ];
+        let normal_length = normal.iter().map(|axis| axis * axis).sum::<f32>().sqrt();
+        // Twice the area of the triangle on screen, negative when its corners go clockwise.
+        let area = normal[2];
+        if normal_length <= f32::EPSILON || area == 0. {
+            continue;
+        }
+        // Faces are lit by how directly they face the camera, from either side, since models
+        // aren't always consistent about which side of a face is the outside.
+        let light = 0.25 + 0.75 * (area / normal_length).abs();
+        let pixel = image::Rgba([
+            (MODEL_COLOR[2] * light * 255.) as u8,
+            (MODEL_COLOR[1] * light * 255.) as u8,
+            (MODEL_COLOR[0] * light * 255.) as u8,
+            255,
+        ]);
+
+        let min_x = a[0].min(b[0]).min(c[0]).floor().max(0.) as u32;
+        let max_x = (a[0].max(b[0]).max(c[0]).ceil() as u32).min(size.saturating_sub(1));
+        let min_y = a[1].min(b[1]).min(c[1]).floor().max(0.) as u32;
+        let max_y = (a[1].max(b[1]).max(c[1]).ceil() as u32).min(size.saturating_sub(1));
+        if min_x > max_x || min_y > max_y {
+            continue;
+        }
+        let edge = |p: [f32; 3], q: [f32; 3], x: f32, y: f32| {
+            (q[0] - p[0]) * (y - p[1]) - (q[1] - p[1]) * (x - p[0])
+        };
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+                // Barycentric weights, which are all positive inside the triangle.
+                let weights = [
+                    edge(b, c, px, py) / area,
+                    edge(c, a, px, py) / area,
+                    edge(a, b, px, py) / area,
+                ];
+                if weights.iter().any(|weight| *weight < 0.) {
+                    continue;
+                }
+                let depth = weights[0] * a[2] + weights[1] * b[2] + weights[2] * c[2];
+                let index = (y * size + x) as usize;
+                if depth > depths[index] {
+                    depths[index] = depth;
+                    image.put_pixel(x, y, pixel);
+                }
+            }
+        }
+    }
+    image
+}
+
+pub enum ModelPreviewEvent {
+    OrbitChanged,
+}
+
+pub struct ModelPreviewView {
+    asset: Model<AssetItem<ModelFiles>>,
+    project: Model<Project>,
+    mesh: Option<Result<Arc<Mesh>, SharedString>>,
+    orbit: Orbit,
+    snapshot: Option<Arc<RenderImage>>,
+    /// Where a drag to orbit the camera started, and the orbit when it did.
+    drag_start: Option<(Point<Pixels>, Orbit)>,
+    focus_handle: FocusHandle,
+    _load_task: Task<()>,
+    _render_task: Option<Task<()>>,
+}
+
+impl ModelPreviewView {
+    /// Loads the buffers a glTF file keeps next to it, by their URI.
+    fn load_external_buffers(
+        asset: &Model<AssetItem<ModelFiles>>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<anyhow::Result<HashMap<String, Vec<u8>>>> {
+        let asset = asset.read(cx);
+        let is_gltf = asset
+            .path()
+            .extension()
+            .map_or(false, |extension| extension.eq_ignore_ascii_case("gltf"));
+        let uris = if is_gltf {
+            match gltf_external_buffers(asset.content()) {
+                Ok(uris) => uris,
+                Err(error) => return Task::ready(Err(error)),
+            }
+        } else {
+            Vec::new()
+        };
+        let directory = asset.path().parent().unwrap_or(Path::new("")).to_path_buf();
+        let worktree = asset.file().worktree.clone();
+        let loads = uris
+            .into_iter()
+            .map(|uri| {
+                let path = directory.join(&uri);
+                let load = worktree.update(cx, |worktree, cx| worktree.load_binary_file(&path, cx));
+                (uri, load)
+            })
+            .collect::<Vec<_>>();
+        cx.background_executor().spawn(async move {
+            let mut buffers = HashMap::default();
+            for (uri, load) in loads {
+                let loaded = load
+                    .await
+                    .with_context(|| format!("loading buffer {uri}"))?;
+                buffers.insert(uri, loaded.content);
+            }
+            Ok(buffers)
+        })
+    }
+
+    /// Draws the model again from the current orbit, in the background.
+    fn update_snapshot(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(Ok(mesh)) = self.mesh.clone() else {
+            return;
+        };
+        let orbit = self.orbit;
+        self._render_task = Some(cx.spawn(|this, mut cx| async move {
+            let snapshot = cx
+                .background_executor()
+                .spawn(async move {
+                    let image = render_mesh(&mesh, orbit, SNAPSHOT_SIZE);
+                    Arc::new(RenderImage::new(vec![image::Frame::new(image)]))
+                })
+                .await;
+            this.update(&mut cx, |this, cx| {
+                if let Some(old_snapshot) = this.snapshot.replace(snapshot) {
+                    cx.drop_image(old_snapshot).log_err();
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    fn set_orbit(&mut self, orbit: Orbit, cx: &mut ViewContext<Self>) {
+        if self.orbit != orbit {
+            self.orbit = orbit;
+            self.update_snapshot(cx);
+        }
+    }
+
+    fn on_mouse_down(&mut self, event: &MouseDownEvent, _: &mut ViewContext<Self>) {
+        self.drag_start = Some((event.position, self.orbit));
+    }
+
+    fn on_mouse_move(&mut self, event: &MouseMoveEvent, cx: &mut ViewContext<Self>) {
+        let Some((start, orbit)) = self.drag_start else {
+            return;
+        };
+        if event.pressed_button != Some(MouseButton::Left) {
+            self.end_drag(cx);
+            return;
+        }
+        self.set_orbit(orbit.dragged(event.position - start), cx);
+    }
+
+    fn end_drag(&mut self, cx: &mut ViewContext<Self>) {
+        if self.drag_start.take().is_some() {
+            cx.emit(ModelPreviewEvent::OrbitChanged);
+        }
+    }
+
+    fn render_model(&self, mesh: &Mesh, cx: &mut ViewContext<Self>) -> AnyElement {
+        let units = ImageViewerSettings::get_global(cx).units();
+        let details = format!(
+            "{} triangles · {} vertices · Drag to orbit",
+            units.count(mesh.triangles.len()),
+            units.count(mesh.positions.len())
+        );
+        v_flex()
+            .size_full()
+            .gap_2()
+            .child(
+                div()
+                    .id("model-preview-snapshot")
+                    .flex_1()
+                    .w_full()
+                    .cursor_grab()
+                    .on_mouse_down(MouseButton::Left, cx.listener(Self::on_mouse_down))
+                    .on_mouse_move(cx.listener(Self::on_mouse_move))
+                    .on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(|this, _, cx| this.end_drag(cx)),
+                    )
+                    .on_mouse_up_out(
+                        MouseButton::Left,
+                        cx.listener(|this, _, cx| this.end_drag(cx)),
+                    )
+                    .when_some(self.snapshot.clone(), |this, snapshot| {
+                        this.child(img(snapshot).size_full().object_fit(ObjectFit::Contain))
+                    }),
+            )
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Label::new(details)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(div().flex_1())
+                    .child(
+                        Button::new("model-preview-reset", "Reset View")
+                            .label_size(LabelSize::Small)
+                            .disabled(self.orbit == Orbit::default())
+                            .tooltip(|cx| Tooltip::text("Look from the default angle", cx))
+                            .on_click(cx.listener(|this, _, cx| {
+                                this.set_orbit(Orbit::default(), cx);
+                                cx.emit(ModelPreviewEvent::OrbitChanged);
+                            })),
+                    ),
+            )
+            .into_any_element()
+    }
+}
+
+impl AssetView for ModelPreviewView {
+    type Kind = ModelFiles;
+
+    const KIND: &'static str = "ModelPreview";
+
+    fn new(
+        asset: Model<AssetItem<ModelFiles>>,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let external_buffers = Self::load_external_buffers(&asset, cx);
+        let content = asset.read(cx).content().clone();
+        let is_obj = asset
+            .read(cx)
+            .path()
+            .extension()
+            .map_or(false, |extension| extension.eq_ignore_ascii_case("obj"));
+        let load_task = cx.spawn(|this, mut cx| async move {
+            let mesh = async {
+                let external_buffers = external_buffers.await?;
+                cx.background_executor()
+                    .spawn(async move {
+                        if is_obj {
+                            parse_obj(&String::from_utf8_lossy(&content))
+                        } else {
+                            parse_gltf(&content, &external_buffers)
+                        }
+                    })
+                    .await
+            }
+            .await
+            .map(Arc::new)
+            .map_err(|error| SharedString::from(error.to_string()));
+            this.update(&mut cx, |this, cx| {
+                this.mesh = Some(mesh);
+                this.update_snapshot(cx);
+                cx.notify();
+            })
+            .ok();
+        });
+        Self {
+            asset,
+            project,
+            mesh: None,
+            orbit: Orbit::default(),
+            snapshot: None,
+            drag_start: None,
+            focus_handle: cx.focus_handle(),
+            _load_task: load_task,
+            _render_task: None,
+        }
+    }
+
+    fn asset(&self) -> &Model<AssetItem<ModelFiles>> {
+        &self.asset
+    }
+
+    fn project(&self) -> &Model<Project> {
+        &self.project
+    }
+
+    fn serialize_state(&self, _cx: &AppContext) -> Option<String> {
+        serde_json::to_string(&self.orbit).ok()
+    }
+
+    fn restore_state(&mut self, state: &str, cx: &mut ViewContext<Self>) {
+        if let Some(orbit) = serde_json::from_str(state).log_err() {
+            self.set_orbit(orbit, cx);
+        }
+    }
+}
+
+impl EventEmitter<ModelPreviewEvent> for ModelPreviewView {}
+
+impl FocusableView for ModelPreviewView {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for ModelPreviewView {
+    type Event = ModelPreviewEvent;
+
+    fn to_item_events(_: &Self::Event, _: impl FnMut(workspace::item::ItemEvent)) {}
+
+    fn for_each_project_item(
+        &self,
+        cx: &AppContext,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::Item),
+    ) {
+        f(self.asset.entity_id(), self.asset.read(cx))
+    }
+
+    fn is_singleton(&self, _cx: &AppContext) -> bool {
+        true
+    }
+
+    fn tab_tooltip_text(&self, cx: &AppContext) -> Option<SharedString> {
+        let project_path = self.asset.read(cx).project_path(cx);
+        asset_viewer::tab_tooltip_text(self.project.read(cx), &project_path, cx)
+    }
+
+    fn tab_content(&self, params: TabContentParams, cx: &WindowContext) -> AnyElement {
+        asset_viewer::tab_content(self.asset.read(cx).path(), params)
+    }
+
+    fn tab_icon(&self, cx: &WindowContext) -> Option<Icon> {
+        asset_viewer::tab_icon(self.asset.read(cx).path(), cx)
+    }
+
+    fn breadcrumb_location(&self, _: &AppContext) -> ToolbarItemLocation {
+        ToolbarItemLocation::PrimaryLeft
+    }
+
+    fn breadcrumbs(&self, _theme: &Theme, cx: &AppContext) -> Option<Vec<BreadcrumbText>> {
+        let project_path = self.asset.read(cx).project_path(cx);
+        asset_viewer::breadcrumbs(self.project.read(cx), &project_path, cx)
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: Option<WorkspaceId>,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<View<Self>> {
+        let orbit = self.orbit;
+        Some(cx.new_view(|cx| {
+            let mut view = <Self as AssetView>::new(self.asset.clone(), self.project.clone(), cx);
+            view.orbit = orbit;
+            view
+        }))
+    }
+}
+
+impl ProjectItem for ModelPreviewView {
+    type Item = AssetItem<ModelFiles>;
+
+    fn for_project_item(
+        project: Model<Project>,
+        item: Model<Self::Item>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        <Self as AssetView>::new(item, project, cx)
+    }
+}
+
+impl SerializableItem for ModelPreviewView {
+    fn serialized_item_kind() -> &'static str {
+        <Self as AssetView>::KIND
+    }
+
+    fn deserialize(
+        project: Model<Project>,
+        _workspace: WeakView<Workspace>,
+        workspace_id: WorkspaceId,
+        item_id: ItemId,
+        cx: &mut WindowContext,
+    ) -> Task<gpui::Result<View<Self>>> {
+        asset_viewer::deserialize(project, workspace_id, item_id, cx)
+    }
+
+    fn cleanup(
+        workspace_id: WorkspaceId,
+        alive_items: Vec<ItemId>,
+        cx: &mut WindowContext,
+    ) -> Task<gpui::Result<()>> {
+        asset_viewer::cleanup::<Self>(workspace_id, alive_items, cx)
+    }
+
+    fn serialize(
+        &mut self,
+        workspace: &mut Workspace,
+        item_id: ItemId,
+        _closing: bool,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<Task<gpui::Result<()>>> {
+        asset_viewer::serialize(self, workspace, item_id, cx)
+    }
+
+    fn should_serialize(&self, event: &Self::Event) -> bool {
+        matches!(event, ModelPreviewEvent::OrbitChanged)
+    }
+}
+
+impl Render for ModelPreviewView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let content = match &self.mesh {
+            Some(Ok(mesh)) if mesh.triangles.is_empty() => Label::new("The model has no faces.")
+                .color(Color::Muted)
+                .into_any_element(),
+            Some(Ok(mesh)) => self.render_model(mesh, cx),
+            Some(Err(error)) => Label::new(error.clone())
+                .color(Color::Muted)
+                .into_any_element(),
+            None => Label::new("Loading…")
+                .color(Color::Muted)
+                .into_any_element(),
+        };
+        v_flex()
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .p_4()
+            .items_center()
+            .justify_center()
+            .bg(cx.theme().colors().editor_background)
+            .child(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_mesh() {
+        // A square facing the camera, which covers the middle of the image but not its corners.
+        let mesh = Mesh {
+            positions: vec![[-1., -1., 0.], [1., -1., 0.], [1., 1., 0.], [-1., 1., 0.]],
+            triangles: vec![[0, 1, 2], [0, 2, 3]],
+        };
+        let facing = Orbit { yaw: 0., pitch: 0. };
+        let image = render_mesh(&mesh, facing, 64);
+        assert_eq!(image.get_pixel(32, 32)[3], 255);
+        assert_eq!(image.get_pixel(0, 0)[3], 0);
+        // Seen edge-on, the square disappears.
+        let edge_on = Orbit {
+            yaw: std::f32::consts::FRAC_PI_2,
+            pitch: 0.,
+        };
+        let image = render_mesh(&mesh, edge_on, 64);
+        assert!(image.pixels().all(|pixel| pixel[3] == 0));
+
+        assert!(render_mesh(&Mesh::default(), facing, 8)
+            .pixels()
+            .all(|pixel| pixel[3] == 0));
+    }
+
+    #[test]
+    fn test_orbit_to_camera() {
+        let orbit = Orbit {
+            yaw: std::f32::consts::FRAC_PI_2,
+            pitch: 0.,
+        };
+        let [x, y, z] = orbit.to_camera([1., 0., 0.]);
+        assert!(x.abs() < 1e-6 && y.abs() < 1e-6 && (z + 1.).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,521 @@
+//! Nine-patch images, which stretch in their middle while keeping their edges and corners intact,
+//! as used for the buttons and panels of user interfaces. The image is previewed stretched to a
+//! few sizes, so that such assets can be checked without running the app using them.
+//!
+//! Android `.9.png` images mark the rows and columns that stretch with black pixels in a border
+//! around the image. Other images are sliced by insets entered by hand.
+
+use std::{path::Path, sync::Arc};
+
+use editor::{Editor, EditorEvent};
+use gpui::{
+    img, point, px, size, AppContext, Bounds, Edges, Hsla, Image, Subscription, Task, View,
+};
+use image::RgbaImage;
+use ui::{prelude::*, Tooltip};
+use util::ResultExt;
+
+use crate::{ImageView, ToggleNinePatch};
+
+/// The sizes images are previewed at, as multiples of their width and height.
+const PREVIEW_SCALES: [(f32, f32); 4] = [(1., 1.), (2., 1.), (1., 2.), (3., 2.)];
+/// The largest width or height previews are shown at, scaling down larger images.
+const MAX_PREVIEW_SIZE: f32 = 192.;
+
+/// A run of rows or columns of an image that either stretch or keep their size.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Segment {
+    pub start: u32,
+    pub len: u32,
+    pub stretches: bool,
+}
+
+/// How an image is sliced to be stretched.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct NinePatch {
+    /// The columns of the image, left to right, not including the border of `.9.png` images.
+    pub columns: Vec<Segment>,
+    /// The rows of the image, top to bottom.
+    pub rows: Vec<Segment>,
+}
+
+impl NinePatch {
+    /// Slices an image of the given size so that the part inside `insets` stretches.
+    pub(crate) fn from_insets((width, height): (u32, u32), insets: Edges<u32>) -> Option<Self> {
+        fn segments(len: u32, start_inset: u32, end_inset: u32) -> Option<Vec<Segment>> {
+            let middle = len.checked_sub(start_inset + end_inset)?;
+            let segments = [
+                (0, start_inset, false),
+                (start_inset, middle, true),
+                (start_inset + middle, end_inset, false),
+            ];
+            Some(
+                segments
+                    .into_iter()
+                    .filter(|(_, len, _)| *len > 0)
+                    .map(|(start, len, stretches)| Segment {
+                        start,
+                        len,
+                        stretches,
+                    })
+                    .collect(),
+            )
+        }
+        Some(Self {
+            columns: segments(width, insets.left, insets.right)?,
+            rows: segments(height, insets.top, insets.bottom)?,
+        })
+    }
+
+    /// Reads the stretching rows and columns marked in the top and left borders of an Android
+    /// `.9.png` image.
+    pub(crate) fn from_android(image: &RgbaImage) -> Option<Self> {
+        let (width, height) = image.dimensions();
+        if width < 3 || height < 3 {
+            return None;
+        }
+        let is_marker = |x, y| image.get_pixel(x, y).0 == [0, 0, 0, 255];
+        let columns = segments_from_markers((1..width - 1).map(|x| is_marker(x, 0)), 1);
+        let rows = segments_from_markers((1..height - 1).map(|y| is_marker(0, y)), 1);
+        let stretches = |segments: &[Segment]| segments.iter().any(|segment| segment.stretches);
+        (stretches(&columns) || stretches(&rows)).then_some(Self { columns, rows })
+    }
+
+    /// The size of the image without the border of `.9.png` images.
+    pub(crate) fn content_size(&self) -> (u32, u32) {
+        let len = |segments: &[Segment]| segments.iter().map(|segment| segment.len).sum();
+        (len(&self.columns), len(&self.rows))
+    }
+}
+
+/// Groups runs of rows or columns by whether they are marked as stretching, starting at `offset`
+/// in the image.
+fn segments_from_markers(markers: impl Iterator<Item = bool>, offset: u32) -> Vec<Segment> {
+    let mut segments: Vec<Segment> = Vec::new();
+    for (ix, stretches) in markers.enumerate() {
+        match segments.last_mut() {
+            Some(segment) if segment.stretches == stretches => segment.len += 1,
+            _ => segments.push(Segment {
+                start: offset + ix as u32,
+                len: 1,
+                stretches,
+            }),
+        }
+    }
+    segments
+}
+
+/// The lengths the segments are drawn at to fill `target`. The stretching ones share the space
+/// left by the others in proportion to their length, and the others shrink when there isn't
+/// enough space for them.
+fn layout_segments(segments: &[Segment], target: f32) -> Vec<f32> {
+    let total = |stretches: bool| -> f32 {
+        segments
+            .iter()
+            .filter(|segment| segment.stretches == stretches)
+            .map(|segment| segment.len as f32)
+            .sum()
+    };
+    let (fixed, stretching) = (total(false), total(true));
+    let spare = (target - fixed).max(0.);
+    let fixed_scale = if fixed > target { target / fixed } else { 1. };
+    segments
+        .iter()
+        .map(|segment| {
+            if !segment.stretches {
+                segment.len as f32 * fixed_scale
+            } else if stretching > 0. {
+                spare * segment.len as f32 / stretching
+            } else {
+                0.
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn is_android_nine_patch(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name.to_string_lossy().to_lowercase().ends_with(".9.png"))
+}
+
+/// The nine-patch preview of an image view.
+pub(crate) struct NinePatchPreview {
+    source: NinePatchSource,
+    _task: Option<Task<()>>,
+}
+
+enum NinePatchSource {
+    /// Read from the border of an Android `.9.png` image, once it is decoded.
+    Android(Option<NinePatch>),
+    /// Insets entered by hand, from the left, top, right and bottom.
+    Insets {
+        editors: [View<Editor>; 4],
+        _subscriptions: Vec<Subscription>,
+    },
+}
+
+impl NinePatchPreview {
+    fn patch(&self, dimensions: (u32, u32), cx: &AppContext) -> Option<NinePatch> {
+        match &self.source {
+            NinePatchSource::Android(patch) => patch.clone(),
+            NinePatchSource::Insets { editors, .. } => {
+                let [left, top, right, bottom] = editors
+                    .each_ref()
+                    .map(|editor| editor.read(cx).text(cx).trim().parse::<u32>().unwrap_or(0));
+                NinePatch::from_insets(
+                    dimensions,
+                    Edges {
+                        top,
+                        right,
+                        bottom,
+                        left,
+                    },
+                )
+            }
+        }
+    }
+}
+
+pub(crate) fn toggle_nine_patch(
+    image_view: &mut ImageView,
+    _: &ToggleNinePatch,
+    cx: &mut ViewContext<ImageView>,
+) {
+    if image_view.nine_patch.take().is_none() {
+        open_nine_patch(image_view, cx);
+    }
+    cx.notify();
+}
+
+fn open_nine_patch(image_view: &mut ImageView, cx: &mut ViewContext<ImageView>) {
+    let image_item = image_view.image_item.read(cx);
+    if is_android_nine_patch(image_item.path()) {
+        let image = image_item.image.clone();
+        let task = cx.spawn(|this, mut cx| async move {
+            let patch = cx
+                .background_executor()
+                .spawn(async move {
+                    let image = image::load_from_memory(&image.bytes)?.into_rgba8();
+                    anyhow::Ok(NinePatch::from_android(&image))
+                })
+                .await
+                .log_err()
+                .flatten();
+            this.update(&mut cx, |this, cx| {
+                if let Some(nine_patch) = this.nine_patch.as_mut() {
+                    nine_patch.source = NinePatchSource::Android(patch);
+                    cx.notify();
+                }
+            })
+            .ok();
+        });
+        image_view.nine_patch = Some(NinePatchPreview {
+            source: NinePatchSource::Android(None),
+            _task: Some(task),
+        });
+    } else {
+        let mut subscriptions = Vec::new();
+        let editors = ["Left", "Top", "Right", "Bottom"].map(|placeholder| {
+            let editor = cx.new_view(|cx| {
+                let mut editor = Editor::single_line(cx);
+                editor.set_placeholder_text(placeholder, cx);
+                editor
+            });
+            subscriptions.push(cx.subscribe(&editor, |_, _, event, cx| {
+                if let EditorEvent::Edited { .. } = event {
+                    cx.notify();
+                }
+            }));
+            editor
+        });
+        image_view.nine_patch = Some(NinePatchPreview {
+            source: NinePatchSource::Insets {
+                editors,
+                _subscriptions: subscriptions,
+            },
+            _task: None,
+        });
+    }
+}
+
+/// Opens the preview for `.9.png` images when they are displayed, and closes the one of the
+/// previous image when it was read from its border.
+pub(crate) fn update_nine_patch(image_view: &mut ImageView, cx: &mut ViewContext<ImageView>) {
+    if let Some(NinePatchPreview {
+        source: NinePatchSource::Android(_),
+        ..
+    }) = image_view.nine_patch
+    {
+        image_view.nine_patch = None;
+    }
+    if image_view.nine_patch.is_none()
+        && is_android_nine_patch(image_view.image_item.read(cx).path())
+    {
+        open_nine_patch(image_view, cx);
+    }
+}
+
+/// The slices of the image used by the preview, for outlining them over the image.
+pub(crate) fn displayed_nine_patch(image_view: &ImageView, cx: &AppContext) -> Option<NinePatch> {
+    let dimensions = image_view.image_item.read(cx).edited_dimensions()?;
+    image_view.nine_patch.as_ref()?.patch(dimensions, cx)
+}
+
+/// A bar below the image previewing it stretched to a few sizes.
+pub(crate) fn render_nine_patch_bar(
+    image_view: &ImageView,
+    image: Arc<Image>,
+    cx: &mut ViewContext<ImageView>,
+) -> Option<impl IntoElement> {
+    let nine_patch = image_view.nine_patch.as_ref()?;
+    let dimensions = image_view.image_item.read(cx).edited_dimensions();
+    let patch = dimensions.and_then(|dimensions| nine_patch.patch(dimensions, cx));
+    let units = image_view.settings(cx).units();
+
+    let header = h_flex()
+        .gap_1()
+        .child(Label::new("Nine-Patch").size(LabelSize::Small))
+        .map(|header| match &nine_patch.source {
+            NinePatchSource::Android(_) => header.child(
+                Label::new("· Stretching marked in the image's border")
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            ),
+            NinePatchSource::Insets { editors, .. } => header
+                .child(
+                    Label::new("· Insets")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+                .children(editors.iter().map(|editor| {
+                    div()
+                        .w(rems(3.5))
+                        .px_1()
+                        .rounded_sm()
+                        .border_1()
+                        .border_color(cx.theme().colors().border)
+                        .bg(cx.theme().colors().editor_background)
+                        .child(editor.clone())
+                })),
+        })
+        .child(div().flex_1())
+        .child(
+            IconButton::new("nine-patch-close", IconName::Close)
+                .tooltip(|cx| Tooltip::for_action("Close Nine-Patch Preview", &ToggleNinePatch, cx))
+                .on_click(cx.listener(|this, _, cx| toggle_nine_patch(this, &ToggleNinePatch, cx))),
+        );
+
+    let previews = match (patch, dimensions) {
+        (Some(patch), Some(dimensions)) => {
+            let (content_width, content_height) = patch.content_size();
+            let largest = PREVIEW_SCALES
+                .iter()
+                .map(|(x, y)| (content_width as f32 * x).max(content_height as f32 * y))
+                .fold(1., f32::max);
+            let preview_scale = (MAX_PREVIEW_SIZE / largest).min(1.);
+            h_flex()
+                .gap_4()
+                .items_end()
+                .children(PREVIEW_SCALES.iter().map(|(x, y)| {
+                    let target = (
+                        (content_width as f32 * x).round(),
+                        (content_height as f32 * y).round(),
+                    );
+                    v_flex()
+                        .gap_1()
+                        .child(render_stretched(
+                            image.clone(),
+                            &patch,
+                            dimensions,
+                            target,
+                            preview_scale,
+                        ))
+                        .child(
+                            Label::new(units.dimensions(target.0 as u32, target.1 as u32))
+                                .size(LabelSize::XSmall)
+                                .color(Color::Muted),
+                        )
+                }))
+                .into_any_element()
+        }
+        _ => Label::new(match nine_patch.source {
+            NinePatchSource::Android(_) => "No stretching is marked in the image's border",
+            NinePatchSource::Insets { .. } => "The insets are larger than the image",
+        })
+        .size(LabelSize::Small)
+        .color(Color::Muted)
+        .into_any_element(),
+    };
+
+    Some(
+        v_flex()
+            .w_full()
+            .p_2()
+            .gap_2()
+            .border_t_1()
+            .border_color(cx.theme().colors().border)
+            .bg(cx.theme().colors().panel_background)
+            .child(header)
+            .child(previews),
+    )
+}
+
+/// The image stretched to `target`, in pixels of the image, drawn as a grid of its slices.
+fn render_stretched(
+    image: Arc<Image>,
+    patch: &NinePatch,
+    (width, height): (u32, u32),
+    target: (f32, f32),
+    preview_scale: f32,
+) -> impl IntoElement {
+    let column_widths = layout_segments(&patch.columns, target.0);
+    let row_heights = layout_segments(&patch.rows, target.1);
+    v_flex()
+        .flex_none()
+        .children(patch.rows.iter().zip(row_heights).map(|(row, row_height)| {
+            h_flex()
+                .flex_none()
+                .children(patch.columns.iter().zip(column_widths.clone()).map(
+                    |(column, column_width)| {
+                        let scale_x = column_width / column.len as f32 * preview_scale;
+                        let scale_y = row_height / row.len as f32 * preview_scale;
+                        div()
+                            .relative()
+                            .flex_none()
+                            .overflow_hidden()
+                            .w(px(column_width * preview_scale))
+                            .h(px(row_height * preview_scale))
+                            .child(
+                                img(image.clone())
+                                    .absolute()
+                                    .left(px(-(column.start as f32) * scale_x))
+                                    .top(px(-(row.start as f32) * scale_y))
+                                    .w(px(width as f32 * scale_x))
+                                    .h(px(height as f32 * scale_y)),
+                            )
+                    },
+                ))
+        }))
+}
+
+/// Draws lines between the slices of the image drawn at `image_bounds`.
+pub(crate) fn paint_nine_patch_guides(
+    image_bounds: Bounds<Pixels>,
+    (width, height): (u32, u32),
+    patch: &NinePatch,
+    color: Hsla,
+    cx: &mut WindowContext,
+) {
+    let scale_x = image_bounds.size.width.0 / width.max(1) as f32;
+    let scale_y = image_bounds.size.height.0 / height.max(1) as f32;
+    for column in patch.columns.iter().skip(1) {
+        let x = image_bounds.origin.x + px(column.start as f32 * scale_x);
+        cx.paint_quad(gpui::fill(
+            Bounds::new(
+                point(x, image_bounds.origin.y),
+                size(px(1.), image_bounds.size.height),
+            ),
+            color,
+        ));
+    }
+    for row in patch.rows.iter().skip(1) {
+        let y = image_bounds.origin.y + px(row.start as f32 * scale_y);
+        cx.paint_quad(gpui::fill(
+            Bounds::new(
+                point(image_bounds.origin.x, y),
+                size(image_bounds.size.width, px(1.)),
+            ),
+            color,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    fn segment(start: u32, len: u32, stretches: bool) -> Segment {
+        Segment {
+            start,
+            len,
+            stretches,
+        }
+    }
+
+    #[test]
+    fn test_from_insets() {
+        let patch = NinePatch::from_insets(
+            (30, 20),
+            Edges {
+                top: 4,
+                right: 8,
+                bottom: 0,
+                left: 8,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            patch.columns,
+            vec![
+                segment(0, 8, false),
+                segment(8, 14, true),
+                segment(22, 8, false)
+            ]
+        );
+        assert_eq!(patch.rows, vec![segment(0, 4, false), segment(4, 16, true)]);
+
+        let too_large = Edges {
+            top: 0,
+            right: 20,
+            bottom: 0,
+            left: 20,
+        };
+        assert_eq!(NinePatch::from_insets((30, 20), too_large), None);
+    }
+
+    #[test]
+    fn test_from_android() {
+        // A 6 × 5 image inside the border, stretching in columns 2 to 3 and row 2.
+        let mut image = RgbaImage::from_pixel(8, 7, Rgba([255, 255, 255, 255]));
+        for x in 3..5 {
+            image.put_pixel(x, 0, Rgba([0, 0, 0, 255]));
+        }
+        image.put_pixel(0, 3, Rgba([0, 0, 0, 255]));
+        let patch = NinePatch::from_android(&image).unwrap();
+        assert_eq!(
+            patch.columns,
+            vec![
+                segment(1, 2, false),
+                segment(3, 2, true),
+                segment(5, 2, false)
+            ]
+        );
+        assert_eq!(
+            patch.rows,
+            vec![
+                segment(1, 2, false),
+                segment(3, 1, true),
+                segment(4, 2, false)
+            ]
+        );
+        assert_eq!(patch.content_size(), (6, 5));
+
+        let unmarked = RgbaImage::from_pixel(8, 7, Rgba([255, 255, 255, 255]));
+        assert_eq!(NinePatch::from_android(&unmarked), None);
+    }
+
+    #[test]
+    fn test_layout_segments() {
+        let segments = [
+            segment(0, 4, false),
+            segment(4, 2, true),
+            segment(6, 6, true),
+            segment(12, 4, false),
+        ];
+        // Stretching segments share the extra space in proportion to their length.
+        assert_eq!(layout_segments(&segments, 32.), vec![4., 6., 18., 4.]);
+        // Fixed segments shrink when there isn't enough space.
+        assert_eq!(layout_segments(&segments, 4.), vec![2., 0., 0., 2.]);
+    }
+}
@@ -0,0 +1,65 @@
+//! Keeps the command palette to the image viewer actions that apply where it was opened.
+//!
+//! Actions handled by image views are only available while one is focused, and only the ones
+//! that apply to its image are registered. The actions below are handled by the workspace for
+//! its active image view instead, so they are hidden while no image view is active.
+
+use std::any::TypeId;
+
+use command_palette_hooks::CommandPaletteFilter;
+use gpui::ViewContext;
+use workspace::Workspace;
+
+use crate::{
+    CompareWithClipboard, CompareWithIndex, ImageCompareView, ImageView, OpenGallery,
+    ShowImageHistory,
+};
+
+/// The workspace actions that act on the active image view.
+fn image_view_actions() -> [TypeId; 3] {
+    [
+        TypeId::of::<CompareWithIndex>(),
+        TypeId::of::<OpenGallery>(),
+        TypeId::of::<ShowImageHistory>(),
+    ]
+}
+
+/// The workspace actions that act on the active image view or image compare view.
+fn compare_actions() -> [TypeId; 1] {
+    [TypeId::of::<CompareWithClipboard>()]
+}
+
+pub(crate) fn register(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+    update_filter(workspace, cx);
+    let this = cx.view().clone();
+    cx.subscribe(&this, |workspace, _, event, cx| {
+        if let workspace::Event::ActiveItemChanged = event {
+            update_filter(workspace, cx);
+        }
+    })
+    .detach();
+    // The filter is shared by all windows, so it follows the one being used.
+    cx.observe_window_activation(|workspace, cx| {
+        if cx.is_window_active() {
+            update_filter(workspace, cx);
+        }
+    })
+    .detach();
+}
+
+fn update_filter(workspace: &Workspace, cx: &mut ViewContext<Workspace>) {
+    let image_view_active = workspace.active_item_as::<ImageView>(cx).is_some();
+    let compare_view_active = workspace.active_item_as::<ImageCompareView>(cx).is_some();
+    CommandPaletteFilter::update_global(cx, |filter, _| {
+        if image_view_active {
+            filter.show_action_types(image_view_actions().iter());
+        } else {
+            filter.hide_action_types(&image_view_actions());
+        }
+        if image_view_active || compare_view_active {
+            filter.show_action_types(compare_actions().iter());
+        } else {
+            filter.hide_action_types(&compare_actions());
+        }
+    });
+}
@@ -0,0 +1,394 @@
+use std::{path::PathBuf, time::Duration};
+
+use gpui::{
+    percentage, Animation, AnimationExt, AppContext, ClickEvent, EventEmitter, FocusHandle,
+    FocusableView, Model, Task, Transformation, View, ViewContext, VisualContext,
+};
+use project::{Project, ProjectPath};
+use settings::Settings;
+use ui::prelude::*;
+use workspace::{
+    item::{Item, TabContentParams},
+    OpenImages, OpenImagesInSplits, SplitDirection, Workspace,
+};
+
+use crate::{
+    image_error::{open_as_text, render_error_card, ClickHandler, ImageErrorDetails},
+    window_workspace, ImageView, ImageViewerSettings,
+};
+
+/// A tab standing in for an image while it loads, replaced with an [`ImageView`] once it has.
+///
+/// Opening many images at once adds one of these for each of them, so that they all get a tab
+/// right away while the image store loads a few of them at a time.
+pub struct PendingImageView {
+    project: Model<Project>,
+    project_path: ProjectPath,
+    focus_handle: FocusHandle,
+    /// Why the image failed to load.
+    error: Option<SharedString>,
+    load_task: Task<()>,
+    /// Re-renders the view while the image loads, showing the progress of reading it.
+    progress_task: Task<()>,
+}
+
+/// How large an image's file needs to be for a progress bar to be shown while it is read, rather
+/// than only a spinner.
+const PROGRESS_BAR_THRESHOLD: u64 = 16 * 1024 * 1024;
+
+/// How often the progress of reading an image is shown.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+impl PendingImageView {
+    pub fn register(workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>) {
+        workspace.register_action(Self::open_images);
+        workspace.register_action(Self::open_images_in_splits);
+    }
+
+    fn project_paths(
+        workspace: &Workspace,
+        abs_paths: &[PathBuf],
+        cx: &AppContext,
+    ) -> Vec<ProjectPath> {
+        abs_paths
+            .iter()
+            .filter_map(|abs_path| {
+                let (worktree, path) = workspace.project().read(cx).find_worktree(abs_path, cx)?;
+                Some(ProjectPath {
+                    worktree_id: worktree.read(cx).id(),
+                    path: path.into(),
+                })
+            })
+            .collect()
+    }
+
+    /// Opens the given images in the active pane, activating the first of them.
+    fn open_images(
+        workspace: &mut Workspace,
+        action: &OpenImages,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let project = workspace.project().clone();
+        let pane = workspace.active_pane().clone();
+        let project_paths = Self::project_paths(workspace, &action.paths, cx);
+
+        for (ix, project_path) in project_paths.into_iter().enumerate() {
+            let activate = ix == 0;
+            let existing = pane
+                .read(cx)
+                .items_of_type::<ImageView>()
+                .find(|image_view| {
+                    image_view.read(cx).image_item.read(cx).project_path(cx) == project_path
+                });
+            if let Some(existing) = existing {
+                if activate {
+                    pane.update(cx, |pane, cx| {
+                        if let Some(ix) = pane.index_for_item(&existing) {
+                            pane.activate_item(ix, true, true, cx);
+                        }
+                    });
+                }
+                continue;
+            }
+
+            let pending_image =
+                cx.new_view(|cx| PendingImageView::new(project.clone(), project_path, cx));
+            pane.update(cx, |pane, cx| {
+                pane.add_item(Box::new(pending_image), activate, activate, None, cx)
+            });
+        }
+    }
+
+    /// Opens the first of the given images in the active pane, and each of the others in a pane
+    /// split to the right of the previous one, focusing the first.
+    fn open_images_in_splits(
+        workspace: &mut Workspace,
+        action: &OpenImagesInSplits,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let project = workspace.project().clone();
+        let mut pane = workspace.active_pane().clone();
+        let project_paths = Self::project_paths(workspace, &action.paths, cx);
+
+        for (ix, project_path) in project_paths.into_iter().enumerate() {
+            if ix > 0 {
+                pane = workspace.split_pane(pane, SplitDirection::Right, cx);
+            }
+            let pending_image =
+                cx.new_view(|cx| PendingImageView::new(project.clone(), project_path, cx));
+            pane.update(cx, |pane, cx| {
+                pane.add_item(Box::new(pending_image), true, ix == 0, None, cx)
+            });
+        }
+    }
+
+    fn new(project: Model<Project>, project_path: ProjectPath, cx: &mut ViewContext<Self>) -> Self {
+        let mut this = Self {
+            project,
+            project_path,
+            focus_handle: cx.focus_handle(),
+            error: None,
+            load_task: Task::ready(()),
+            progress_task: Task::ready(()),
+        };
+        this.load(cx);
+        this
+    }
+
+    fn load(&mut self, cx: &mut ViewContext<Self>) {
+        self.error = None;
+        let open_image = self.project.update(cx, |project, cx| {
+            project.open_image(self.project_path.clone(), cx)
+        });
+        self.load_task = cx.spawn(|this, mut cx| async move {
+            let image_item = match open_image.await {
+                Ok(image_item) => image_item,
+                Err(error) => {
+                    this.update(&mut cx, |this, cx| {
+                        this.error = Some(error.to_string().into());
+                        cx.notify();
+                    })
+                    .ok();
+                    return;
+                }
+            };
+            let Some(workspace) = this
+                .update(&mut cx, |_, cx| window_workspace(cx))
+                .ok()
+                .flatten()
+            else {
+                return;
+            };
+            workspace
+                .update(&mut cx, |workspace, cx| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+                    let project = workspace.project().clone();
+                    let image_view = cx.new_view(|cx| ImageView::new(image_item, project, cx));
+                    Self::replace_with(&this, image_view, workspace, cx);
+                })
+                .ok();
+        });
+        self.progress_task = cx.spawn(|this, mut cx| async move {
+            loop {
+                cx.background_executor().timer(PROGRESS_INTERVAL).await;
+                let loading = this
+                    .update(&mut cx, |this, cx| {
+                        cx.notify();
+                        this.error.is_none()
+                    })
+                    .unwrap_or(false);
+                if !loading {
+                    break;
+                }
+            }
+        });
+        cx.notify();
+    }
+
+    /// Puts the loaded image in place of the pending one, keeping its position in its pane and
+    /// whether it is active and focused.
+    fn replace_with(
+        this: &View<Self>,
+        image_view: View<ImageView>,
+        workspace: &mut Workspace,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let Some(pane) = workspace
+            .panes()
+            .iter()
+            .find(|pane| pane.read(cx).index_for_item(this).is_some())
+            .cloned()
+        else {
+            return;
+        };
+        let focused = this.read(cx).focus_handle.contains_focused(cx);
+        pane.update(cx, |pane, cx| {
+            let Some(ix) = pane.index_for_item(this) else {
+                return;
+            };
+            let active = pane.active_item_index() == ix;
+            pane.add_item(Box::new(image_view), active, focused, Some(ix), cx);
+            if let Some(ix) = pane.index_for_item(this) {
+                pane.remove_item(ix, false, false, cx);
+            }
+        });
+    }
+
+    fn abs_path(&self, cx: &AppContext) -> Option<PathBuf> {
+        let worktree = self
+            .project
+            .read(cx)
+            .worktree_for_id(self.project_path.worktree_id, cx)?;
+        worktree.read(cx).absolutize(&self.project_path.path).ok()
+    }
+
+    fn render_error(&self, error: SharedString, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let byte_size = self
+            .project
+            .read(cx)
+            .entry_for_path(&self.project_path, cx)
+            .map(|entry| entry.size);
+        let details = ImageErrorDetails {
+            file_name: self.file_name(),
+            format: self
+                .project_path
+                .path
+                .extension()
+                .map(|extension| extension.to_string_lossy().to_uppercase()),
+            byte_size,
+            error,
+        };
+        let on_open_as_text = {
+            let project = self.project.clone();
+            let project_path = self.project_path.clone();
+            Box::new(move |_: &ClickEvent, cx: &mut WindowContext| {
+                if let Some(workspace) = window_workspace(cx) {
+                    workspace.update(cx, |workspace, cx| {
+                        open_as_text(project.clone(), project_path.clone(), workspace, cx)
+                    });
+                }
+            }) as ClickHandler
+        };
+        let on_reveal = self.abs_path(cx).map(|abs_path| {
+            Box::new(move |_: &ClickEvent, cx: &mut WindowContext| cx.reveal_path(&abs_path))
+                as ClickHandler
+        });
+        render_error_card(
+            &details,
+            ImageViewerSettings::get_global(cx).units(),
+            cx.listener(|this, _, cx| this.load(cx)),
+            Some(on_open_as_text),
+            on_reveal,
+            cx,
+        )
+    }
+
+    /// A spinner while the image loads, which becomes a progress bar while large files are
+    /// read.
+    fn render_progress(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let progress = self
+            .project
+            .read(cx)
+            .image_store()
+            .read(cx)
+            .load_progress(&self.project_path);
+        let units = ImageViewerSettings::get_global(cx).units();
+        let reading = progress.as_ref().and_then(|progress| {
+            let total_bytes = progress.total_bytes()?;
+            let fraction = progress.fraction()?;
+            (total_bytes >= PROGRESS_BAR_THRESHOLD && !progress.is_decoding())
+                .then(|| (fraction, progress.bytes_read(), total_bytes))
+        });
+        let decoding = progress.is_some_and(|progress| progress.is_decoding());
+
+        match reading {
+            Some((fraction, bytes_read, total_bytes)) => v_flex()
+                .gap_2()
+                .items_center()
+                .child(Label::new(format!("Reading {}…", self.file_name())).color(Color::Muted))
+                .child(
+                    div()
+                        .w(rems(16.))
+                        .h_1()
+                        .rounded_sm()
+                        .bg(cx.theme().colors().border)
+                        .child(
+                            div()
+                                .h_full()
+                                .w(relative(fraction))
+                                .rounded_sm()
+                                .bg(cx.theme().colors().text_accent),
+                        ),
+                )
+                .child(
+                    Label::new(format!(
+                        "{} of {}",
+                        units.file_size(bytes_read),
+                        units.file_size(total_bytes)
+                    ))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+                )
+                .into_any_element(),
+            None => h_flex()
+                .gap_2()
+                .child(
+                    Icon::new(IconName::ArrowCircle)
+                        .color(Color::Muted)
+                        .with_animation(
+                            "image-loading",
+                            Animation::new(Duration::from_secs(2)).repeat(),
+                            |icon, delta| icon.transform(Transformation::rotate(percentage(delta))),
+                        ),
+                )
+                .child(
+                    Label::new(if decoding {
+                        format!("Decoding {}…", self.file_name())
+                    } else {
+                        format!("Loading {}…", self.file_name())
+                    })
+                    .color(Color::Muted),
+                )
+                .into_any_element(),
+        }
+    }
+
+    fn file_name(&self) -> String {
+        self.project_path
+            .path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+}
+
+impl EventEmitter<()> for PendingImageView {}
+
+impl FocusableView for PendingImageView {
+    fn focus_handle(&self, _cx: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for PendingImageView {
+    type Event = ();
+
+    fn tab_content(&self, params: TabContentParams, _cx: &WindowContext) -> AnyElement {
+        Label::new(self.file_name())
+            .single_line()
+            .color(params.text_color())
+            .italic(params.preview)
+            .into_any_element()
+    }
+
+    fn tab_icon(&self, _cx: &WindowContext) -> Option<Icon> {
+        Some(match self.error {
+            Some(_) => Icon::new(IconName::Warning).color(Color::Warning),
+            None => Icon::new(IconName::ArrowCircle).color(Color::Muted),
+        })
+    }
+
+    fn tab_tooltip_text(&self, cx: &AppContext) -> Option<SharedString> {
+        let abs_path = self.abs_path(cx)?;
+        Some(abs_path.to_string_lossy().to_string().into())
+    }
+}
+
+impl Render for PendingImageView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let content = match self.error.clone() {
+            Some(error) => self.render_error(error, cx).into_any_element(),
+            None => self.render_progress(cx).into_any_element(),
+        };
+        div()
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .flex()
+            .items_center()
+            .justify_center()
+            .bg(cx.theme().colors().editor_background)
+            .child(content)
+    }
+}
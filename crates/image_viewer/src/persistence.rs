@@ -0,0 +1,438 @@
+use anyhow::{anyhow, Result};
+use std::path::PathBuf;
+
+use db::{
+    define_connection, query,
+    sqlez::{
+        bindable::{Bind, Column, StaticColumnCount},
+        statement::Statement,
+    },
+    sqlez_macros::sql,
+};
+use workspace::{ItemId, WorkspaceDb, WorkspaceId};
+
+use crate::image_compare::CompareMode;
+
+/// The persisted form of one side of an image comparison.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum SerializedCompareSide {
+    File(PathBuf),
+    GitIndex(PathBuf),
+    GitCommit {
+        path: PathBuf,
+        sha: String,
+    },
+    /// Clipboard contents are not persisted, so such comparisons can't be restored.
+    Clipboard,
+}
+
+impl SerializedCompareSide {
+    /// The kind of the side, which for committed images also holds the commit SHA.
+    fn kind(&self) -> String {
+        match self {
+            SerializedCompareSide::File(_) => "file".into(),
+            SerializedCompareSide::GitIndex(_) => "git_index".into(),
+            SerializedCompareSide::GitCommit { sha, .. } => format!("git_commit:{sha}"),
+            SerializedCompareSide::Clipboard => "clipboard".into(),
+        }
+    }
+
+    fn path(&self) -> Option<PathBuf> {
+        match self {
+            SerializedCompareSide::File(path)
+            | SerializedCompareSide::GitIndex(path)
+            | SerializedCompareSide::GitCommit { path, .. } => Some(path.clone()),
+            SerializedCompareSide::Clipboard => None,
+        }
+    }
+}
+
+impl StaticColumnCount for SerializedCompareSide {
+    fn column_count() -> usize {
+        2
+    }
+}
+
+impl Bind for SerializedCompareSide {
+    fn bind(&self, statement: &Statement, start_index: i32) -> Result<i32> {
+        let start_index = statement.bind(&self.kind(), start_index)?;
+        statement.bind(&self.path(), start_index)
+    }
+}
+
+impl Column for SerializedCompareSide {
+    fn column(statement: &mut Statement, start_index: i32) -> Result<(Self, i32)> {
+        let (kind, start_index): (String, i32) = Column::column(statement, start_index)?;
+        let (path, start_index): (Option<PathBuf>, i32) = Column::column(statement, start_index)?;
+        let side = match (kind.as_str(), path) {
+            ("file", Some(path)) => SerializedCompareSide::File(path),
+            ("git_index", Some(path)) => SerializedCompareSide::GitIndex(path),
+            ("clipboard", _) => SerializedCompareSide::Clipboard,
+            (kind, Some(path)) if kind.starts_with("git_commit:") => {
+                SerializedCompareSide::GitCommit {
+                    path,
+                    sha: kind["git_commit:".len()..].to_string(),
+                }
+            }
+            (kind, _) => return Err(anyhow!("Invalid compare side {kind:?}")),
+        };
+        Ok((side, start_index))
+    }
+}
+
+impl StaticColumnCount for CompareMode {}
+
+impl Bind for CompareMode {
+    fn bind(&self, statement: &Statement, start_index: i32) -> Result<i32> {
+        let mode = match self {
+            CompareMode::SideBySide => "side_by_side",
+            CompareMode::Swipe => "swipe",
+            CompareMode::Onion => "onion",
+        };
+        statement.bind(&mode, start_index)
+    }
+}
+
+impl Column for CompareMode {
+    fn column(statement: &mut Statement, start_index: i32) -> Result<(Self, i32)> {
+        let (mode, start_index): (String, i32) = Column::column(statement, start_index)?;
+        let mode = match mode.as_str() {
+            "swipe" => CompareMode::Swipe,
+            "onion" => CompareMode::Onion,
+            _ => CompareMode::SideBySide,
+        };
+        Ok((mode, start_index))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct SerializedImageCompare {
+    pub left: SerializedCompareSide,
+    pub right: SerializedCompareSide,
+    pub mode: CompareMode,
+    pub divider_position: f32,
+}
+
+impl StaticColumnCount for SerializedImageCompare {
+    fn column_count() -> usize {
+        2 * SerializedCompareSide::column_count() + 2
+    }
+}
+
+impl Bind for SerializedImageCompare {
+    fn bind(&self, statement: &Statement, start_index: i32) -> Result<i32> {
+        let start_index = statement.bind(&self.left, start_index)?;
+        let start_index = statement.bind(&self.right, start_index)?;
+        let start_index = statement.bind(&self.mode, start_index)?;
+        statement.bind(&self.divider_position, start_index)
+    }
+}
+
+impl Column for SerializedImageCompare {
+    fn column(statement: &mut Statement, start_index: i32) -> Result<(Self, i32)> {
+        let (left, start_index) = SerializedCompareSide::column(statement, start_index)?;
+        let (right, start_index) = SerializedCompareSide::column(statement, start_index)?;
+        let (mode, start_index) = CompareMode::column(statement, start_index)?;
+        let (divider_position, start_index): (f32, i32) = Column::column(statement, start_index)?;
+        let compare = Self {
+            left,
+            right,
+            mode,
+            divider_position,
+        };
+        Ok((compare, start_index))
+    }
+}
+
+define_connection! {
+    pub static ref IMAGE_VIEWER: ImageViewerDb<WorkspaceDb> =
+        &[sql!(
+            CREATE TABLE image_viewers (
+                workspace_id INTEGER,
+                item_id INTEGER UNIQUE,
+
+                image_path BLOB,
+
+                PRIMARY KEY(workspace_id, item_id),
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+            ) STRICT;
+        ),
+        sql!(
+            CREATE TABLE image_compare_views (
+                workspace_id INTEGER,
+                item_id INTEGER UNIQUE,
+
+                left_kind TEXT NOT NULL,
+                left_path BLOB,
+                right_kind TEXT NOT NULL,
+                right_path BLOB,
+                blend_mode TEXT NOT NULL,
+                divider_position REAL NOT NULL,
+
+                PRIMARY KEY(workspace_id, item_id),
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+            ) STRICT;
+        ),
+        sql!(
+            ALTER TABLE image_viewers ADD COLUMN filmstrip_open INTEGER NOT NULL DEFAULT FALSE;
+        ),
+        // Rows written before this migration have no worktree root and an absolute image path.
+        sql!(
+            ALTER TABLE image_viewers ADD COLUMN worktree_root BLOB;
+        ),
+        sql!(
+            CREATE TABLE recent_images (
+                workspace_id INTEGER,
+                worktree_root BLOB NOT NULL,
+                image_path BLOB NOT NULL,
+                opened_at INTEGER NOT NULL,
+
+                PRIMARY KEY(workspace_id, worktree_root, image_path),
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+            ) STRICT;
+        ),
+        sql!(
+            ALTER TABLE image_viewers ADD COLUMN zoom TEXT;
+        ),
+        sql!(
+            CREATE TABLE asset_views (
+                workspace_id INTEGER,
+                item_id INTEGER UNIQUE,
+
+                kind TEXT NOT NULL,
+                worktree_root BLOB NOT NULL,
+                asset_path BLOB NOT NULL,
+                state TEXT,
+
+                PRIMARY KEY(workspace_id, item_id),
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+            ) STRICT;
+        ),
+        sql!(
+            ALTER TABLE image_viewers ADD COLUMN pan_x REAL NOT NULL DEFAULT 0;
+            ALTER TABLE image_viewers ADD COLUMN pan_y REAL NOT NULL DEFAULT 0;
+        )];
+}
+
+impl ImageViewerDb {
+    query! {
+       pub async fn update_workspace_id(
+            new_id: WorkspaceId,
+            old_id: WorkspaceId,
+            item_id: ItemId
+        ) -> Result<()> {
+            UPDATE image_viewers
+            SET workspace_id = ?
+            WHERE workspace_id = ? AND item_id = ?
+        }
+    }
+
+    query! {
+        pub async fn save_image_view(
+            item_id: ItemId,
+            workspace_id: WorkspaceId,
+            worktree_root: PathBuf,
+            image_path: PathBuf,
+            filmstrip_open: bool,
+            zoom: String,
+            pan_x: f32,
+            pan_y: f32
+        ) -> Result<()> {
+            INSERT OR REPLACE INTO image_viewers(item_id, workspace_id, worktree_root, image_path, filmstrip_open, zoom, pan_x, pan_y)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        }
+    }
+
+    query! {
+        pub fn get_image_view(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Option<(Option<PathBuf>, PathBuf, bool, Option<String>, f32, f32)>> {
+            SELECT worktree_root, image_path, filmstrip_open, zoom, pan_x, pan_y
+            FROM image_viewers
+            WHERE item_id = ? AND workspace_id = ?
+        }
+    }
+
+    query! {
+        pub async fn save_asset_view(
+            item_id: ItemId,
+            workspace_id: WorkspaceId,
+            kind: String,
+            worktree_root: PathBuf,
+            asset_path: PathBuf,
+            state: Option<String>
+        ) -> Result<()> {
+            INSERT OR REPLACE INTO asset_views(item_id, workspace_id, kind, worktree_root, asset_path, state)
+            VALUES (?, ?, ?, ?, ?, ?)
+        }
+    }
+
+    query! {
+        pub fn get_asset_view(item_id: ItemId, workspace_id: WorkspaceId, kind: String) -> Result<Option<(PathBuf, PathBuf, Option<String>)>> {
+            SELECT worktree_root, asset_path, state
+            FROM asset_views
+            WHERE item_id = ? AND workspace_id = ? AND kind = ?
+        }
+    }
+
+    query! {
+       pub async fn update_asset_view_workspace_id(
+            new_id: WorkspaceId,
+            old_id: WorkspaceId,
+            item_id: ItemId
+        ) -> Result<()> {
+            UPDATE asset_views
+            SET workspace_id = ?
+            WHERE workspace_id = ? AND item_id = ?
+        }
+    }
+
+    query! {
+        pub async fn save_image_compare(
+            item_id: ItemId,
+            workspace_id: WorkspaceId,
+            compare: SerializedImageCompare
+        ) -> Result<()> {
+            INSERT OR REPLACE INTO image_compare_views(
+                item_id,
+                workspace_id,
+                left_kind,
+                left_path,
+                right_kind,
+                right_path,
+                blend_mode,
+                divider_position
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        }
+    }
+
+    query! {
+        pub fn get_image_compare(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Option<SerializedImageCompare>> {
+            SELECT left_kind, left_path, right_kind, right_path, blend_mode, divider_position
+            FROM image_compare_views
+            WHERE item_id = ? AND workspace_id = ?
+        }
+    }
+
+    query! {
+       pub async fn update_image_compare_workspace_id(
+            new_id: WorkspaceId,
+            old_id: WorkspaceId,
+            item_id: ItemId
+        ) -> Result<()> {
+            UPDATE image_compare_views
+            SET workspace_id = ?
+            WHERE workspace_id = ? AND item_id = ?
+        }
+    }
+
+    query! {
+        pub async fn save_recent_image(
+            workspace_id: WorkspaceId,
+            worktree_root: PathBuf,
+            image_path: PathBuf,
+            opened_at: i64
+        ) -> Result<()> {
+            INSERT OR REPLACE INTO recent_images(workspace_id, worktree_root, image_path, opened_at)
+            VALUES (?, ?, ?, ?)
+        }
+    }
+
+    query! {
+        pub async fn delete_old_recent_images(workspace_id: WorkspaceId, limit: i64) -> Result<()> {
+            DELETE FROM recent_images
+            WHERE workspace_id = ?1 AND rowid NOT IN (
+                SELECT rowid
+                FROM recent_images
+                WHERE workspace_id = ?1
+                ORDER BY opened_at DESC
+                LIMIT ?2
+            )
+        }
+    }
+
+    query! {
+        pub fn get_recent_images(workspace_id: WorkspaceId) -> Result<Vec<(PathBuf, PathBuf)>> {
+            SELECT worktree_root, image_path
+            FROM recent_images
+            WHERE workspace_id = ?
+            ORDER BY opened_at DESC
+        }
+    }
+
+    // Images opened in both workspaces keep the row moved from the old one.
+    query! {
+       pub async fn update_recent_images_workspace_id(
+            new_id: WorkspaceId,
+            old_id: WorkspaceId
+        ) -> Result<()> {
+            UPDATE OR REPLACE recent_images
+            SET workspace_id = ?
+            WHERE workspace_id = ?
+        }
+    }
+
+    pub async fn delete_unloaded_compare_views(
+        &self,
+        workspace: WorkspaceId,
+        alive_items: Vec<ItemId>,
+    ) -> Result<()> {
+        self.delete_unloaded_rows("image_compare_views", None, workspace, alive_items)
+            .await
+    }
+
+    pub async fn delete_unloaded_items(
+        &self,
+        workspace: WorkspaceId,
+        alive_items: Vec<ItemId>,
+    ) -> Result<()> {
+        self.delete_unloaded_rows("image_viewers", None, workspace, alive_items)
+            .await
+    }
+
+    /// Deletes the rows of the asset views of the given kind that are no longer open.
+    pub async fn delete_unloaded_asset_views(
+        &self,
+        kind: &'static str,
+        workspace: WorkspaceId,
+        alive_items: Vec<ItemId>,
+    ) -> Result<()> {
+        self.delete_unloaded_rows("asset_views", Some(kind), workspace, alive_items)
+            .await
+    }
+
+    async fn delete_unloaded_rows(
+        &self,
+        table: &'static str,
+        kind: Option<&'static str>,
+        workspace: WorkspaceId,
+        alive_items: Vec<ItemId>,
+    ) -> Result<()> {
+        let placeholders = alive_items
+            .iter()
+            .map(|_| "?")
+            .collect::<Vec<&str>>()
+            .join(", ");
+
+        let kind_condition = if kind.is_some() { " AND kind = ?" } else { "" };
+        let query = format!(
+            "DELETE FROM {table} WHERE workspace_id = ?{kind_condition} AND item_id NOT IN ({placeholders})"
+        );
+
+        self.write(move |conn| {
+            let mut statement = Statement::prepare(conn, query)?;
+            let mut next_index = statement.bind(&workspace, 1)?;
+            if let Some(kind) = kind {
+                next_index = statement.bind(&kind, next_index)?;
+            }
+            for id in alive_items {
+                next_index = statement.bind(&id, next_index)?;
+            }
+            statement.exec()
+        })
+        .await
+    }
+}
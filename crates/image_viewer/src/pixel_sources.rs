@@ -0,0 +1,286 @@
+//! Maps the pixels of generated images back to the source that produced them.
+//!
+//! Build tools that generate images (plots, charts, rendered diagrams) can write a sidecar file
+//! describing which source location produced each region of the image. Secondary-clicking a pixel
+//! of such an image opens that location. Each sidecar format is read by a
+//! [`PixelSourceProvider`] registered with the global [`PixelSourceRegistry`]; the built-in
+//! [`RegionMapProvider`] reads `<image file name>.srcmap.json` files like:
+//!
+//! ```json
+//! {
+//!   "regions": [
+//!     { "x": 0, "y": 0, "width": 640, "height": 480, "path": "plot.py", "line": 12 },
+//!     { "x": 40, "y": 60, "width": 200, "height": 20, "path": "plot.py", "line": 18, "column": 5 }
+//!   ]
+//! }
+//! ```
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{anyhow, Context as _, Result};
+use editor::{scroll::Autoscroll, Bias, Editor, MultiBufferPoint};
+use gpui::{AppContext, Global, MouseDownEvent, ReadGlobal, ViewContext};
+use parking_lot::RwLock;
+use serde::Deserialize;
+use workspace::notifications::DetachAndPromptErr;
+
+use crate::{window_workspace, ImageView};
+
+/// A location in a source file, as one-based row and column.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PixelSourceLocation {
+    /// The source file, relative to the directory containing the sidecar file unless absolute.
+    pub path: PathBuf,
+    pub row: u32,
+    pub column: u32,
+}
+
+/// The source locations of the pixels of one image, parsed from its sidecar file.
+pub trait PixelSourceMap: Send + Sync {
+    /// Returns the source location that produced the pixel at `x` and `y`, if any.
+    fn source_at(&self, x: u32, y: u32) -> Option<PixelSourceLocation>;
+}
+
+/// Reads one format of sidecar files that map the pixels of images to their sources.
+pub trait PixelSourceProvider: Send + Sync + 'static {
+    /// Returns where the sidecar file of the image at `image_path` would be, if this provider
+    /// can describe that image.
+    fn sidecar_path(&self, image_path: &Path) -> Option<PathBuf>;
+
+    /// Parses the contents of a sidecar file found at the path returned by
+    /// [`PixelSourceProvider::sidecar_path`].
+    fn parse(&self, sidecar: &str) -> Result<Arc<dyn PixelSourceMap>>;
+}
+
+#[derive(Default)]
+struct GlobalPixelSourceRegistry(Arc<PixelSourceRegistry>);
+
+impl Global for GlobalPixelSourceRegistry {}
+
+/// The providers used to find the sources of the pixels of images.
+#[derive(Default)]
+pub struct PixelSourceRegistry {
+    providers: RwLock<Vec<Arc<dyn PixelSourceProvider>>>,
+}
+
+impl PixelSourceRegistry {
+    /// Returns the global [`PixelSourceRegistry`].
+    pub fn global(cx: &AppContext) -> Arc<Self> {
+        GlobalPixelSourceRegistry::global(cx).0.clone()
+    }
+
+    /// Returns the global [`PixelSourceRegistry`].
+    ///
+    /// Inserts a default [`PixelSourceRegistry`] if one does not yet exist.
+    pub fn default_global(cx: &mut AppContext) -> Arc<Self> {
+        cx.default_global::<GlobalPixelSourceRegistry>().0.clone()
+    }
+
+    /// Registers a provider, which is consulted after the ones registered before it.
+    pub fn register_provider(&self, provider: Arc<dyn PixelSourceProvider>) {
+        self.providers.write().push(provider);
+    }
+
+    /// Returns the registered providers, in registration order.
+    pub fn providers(&self) -> Vec<Arc<dyn PixelSourceProvider>> {
+        self.providers.read().clone()
+    }
+}
+
+/// Reads `<image file name>.srcmap.json` sidecar files, which list rectangular regions of the
+/// image and the source location of each.
+pub struct RegionMapProvider;
+
+impl PixelSourceProvider for RegionMapProvider {
+    fn sidecar_path(&self, image_path: &Path) -> Option<PathBuf> {
+        let mut file_name = image_path.file_name()?.to_os_string();
+        file_name.push(".srcmap.json");
+        Some(image_path.with_file_name(file_name))
+    }
+
+    fn parse(&self, sidecar: &str) -> Result<Arc<dyn PixelSourceMap>> {
+        let region_map: RegionMap = serde_json::from_str(sidecar)?;
+        Ok(Arc::new(region_map))
+    }
+}
+
+#[derive(Deserialize)]
+struct RegionMap {
+    regions: Vec<Region>,
+}
+
+#[derive(Deserialize)]
+struct Region {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    path: PathBuf,
+    #[serde(default = "first_position")]
+    line: u32,
+    #[serde(default = "first_position")]
+    column: u32,
+}
+
+fn first_position() -> u32 {
+    1
+}
+
+impl Region {
+    fn contains(&self, x: u32, y: u32) -> bool {
+        (self.x..self.x.saturating_add(self.width)).contains(&x)
+            && (self.y..self.y.saturating_add(self.height)).contains(&y)
+    }
+}
+
+impl PixelSourceMap for RegionMap {
+    /// Regions can nest, such as a label within a plot, so the smallest one containing the pixel
+    /// is the most specific source.
+    fn source_at(&self, x: u32, y: u32) -> Option<PixelSourceLocation> {
+        let region = self
+            .regions
+            .iter()
+            .filter(|region| region.contains(x, y))
+            .min_by_key(|region| region.width as u64 * region.height as u64)?;
+        Some(PixelSourceLocation {
+            path: region.path.clone(),
+            row: region.line,
+            column: region.column,
+        })
+    }
+}
+
+pub(crate) fn init(cx: &mut AppContext) {
+    PixelSourceRegistry::default_global(cx).register_provider(Arc::new(RegionMapProvider));
+}
+
+/// Opens the source of the pixel under a secondary click, returning whether the click was one.
+pub(crate) fn reveal_source_at(
+    image_view: &mut ImageView,
+    event: &MouseDownEvent,
+    cx: &mut ViewContext<ImageView>,
+) -> bool {
+    if !event.modifiers.secondary() {
+        return false;
+    }
+    let Some(position) = image_view.image_position(event.position, cx) else {
+        return false;
+    };
+    let image_item = image_view.image_item.read(cx);
+    let Some((abs_path, (width, height))) = image_item
+        .file
+        .as_local()
+        .map(|file| file.abs_path(cx))
+        .zip(image_item.edited_dimensions())
+    else {
+        return false;
+    };
+    let x = ((position.x * width as f32) as u32).min(width.saturating_sub(1));
+    let y = ((position.y * height as f32) as u32).min(height.saturating_sub(1));
+    let Some(workspace) = window_workspace(cx) else {
+        return true;
+    };
+    let providers = PixelSourceRegistry::global(cx).providers();
+    let fs = image_view.project.read(cx).fs().clone();
+
+    cx.spawn(|_, mut cx| async move {
+        let mut source_map = None;
+        for provider in providers {
+            let Some(sidecar_path) = provider.sidecar_path(&abs_path) else {
+                continue;
+            };
+            if !fs.is_file(&sidecar_path).await {
+                continue;
+            }
+            let sidecar = fs.load(&sidecar_path).await?;
+            let map = provider
+                .parse(&sidecar)
+                .with_context(|| format!("parsing {sidecar_path:?}"))?;
+            source_map = Some((sidecar_path, map));
+            break;
+        }
+        let (sidecar_path, source_map) = source_map.ok_or_else(|| {
+            anyhow!(
+                "No source map found for {:?}",
+                abs_path.file_name().unwrap_or_default()
+            )
+        })?;
+        let Some(location) = source_map.source_at(x, y) else {
+            return Ok(());
+        };
+
+        let source_path = sidecar_path
+            .parent()
+            .unwrap_or(Path::new(""))
+            .join(&location.path);
+        let item = workspace
+            .update(&mut cx, |workspace, cx| {
+                workspace.open_abs_path(source_path, true, cx)
+            })?
+            .await?;
+        if let Some(editor) = item.downcast::<Editor>() {
+            editor.update(&mut cx, |editor, cx| {
+                let buffer = editor.buffer().read(cx).snapshot(cx);
+                let point = buffer.clip_point(
+                    MultiBufferPoint::new(
+                        location.row.saturating_sub(1),
+                        location.column.saturating_sub(1),
+                    ),
+                    Bias::Left,
+                );
+                editor.change_selections(Some(Autoscroll::center()), cx, |selections| {
+                    selections.select_ranges([point..point])
+                });
+            })?;
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_prompt_err("Failed to reveal pixel in source", cx, |_, _| None);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_region_map() {
+        let provider = RegionMapProvider;
+        assert_eq!(
+            provider.sidecar_path(Path::new("/out/plot.png")),
+            Some(PathBuf::from("/out/plot.png.srcmap.json"))
+        );
+
+        let map = provider
+            .parse(
+                r#"{
+                    "regions": [
+                        { "x": 0, "y": 0, "width": 100, "height": 100, "path": "plot.py", "line": 3 },
+                        { "x": 10, "y": 10, "width": 20, "height": 5, "path": "plot.py", "line": 8, "column": 5 }
+                    ]
+                }"#,
+            )
+            .unwrap();
+        assert_eq!(
+            map.source_at(50, 50),
+            Some(PixelSourceLocation {
+                path: PathBuf::from("plot.py"),
+                row: 3,
+                column: 1,
+            })
+        );
+        assert_eq!(
+            map.source_at(12, 14),
+            Some(PixelSourceLocation {
+                path: PathBuf::from("plot.py"),
+                row: 8,
+                column: 5,
+            })
+        );
+        assert_eq!(map.source_at(12, 15).map(|location| location.row), Some(3));
+        assert_eq!(map.source_at(100, 0), None);
+    }
+}
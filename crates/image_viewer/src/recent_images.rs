@@ -0,0 +1,238 @@
+use std::{path::PathBuf, sync::Arc};
+
+use anyhow::Result;
+use gpui::{
+    AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Render, Subscription, Task,
+    View, ViewContext, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use project::ProjectPath;
+use ui::{prelude::*, ListItem, ListItemSpacing};
+use util::paths::PathExt;
+use workspace::{notifications::DetachAndPromptErr, ModalView, Workspace, WorkspaceId};
+
+use crate::{
+    asset_viewer::{find_stored_path, stored_abs_path},
+    persistence::IMAGE_VIEWER,
+    ShowRecentImages,
+};
+
+/// How many images are remembered per workspace.
+const RECENT_IMAGES_LIMIT: i64 = 50;
+
+/// Adds an image, given as the root of its worktree and its path relative to it, to the
+/// workspace's recently viewed images.
+pub(crate) fn record(
+    workspace_id: WorkspaceId,
+    worktree_root: PathBuf,
+    image_path: PathBuf,
+    cx: &AppContext,
+) {
+    let opened_at = chrono::Utc::now().timestamp_millis();
+    cx.background_executor()
+        .spawn(async move {
+            IMAGE_VIEWER
+                .save_recent_image(workspace_id, worktree_root, image_path, opened_at)
+                .await?;
+            IMAGE_VIEWER
+                .delete_old_recent_images(workspace_id, RECENT_IMAGES_LIMIT)
+                .await
+        })
+        .detach_and_log_err(cx);
+}
+
+/// Loads the worktree roots and worktree-relative paths of the workspace's recently viewed
+/// images, most recent first.
+pub(crate) fn load(
+    workspace_id: WorkspaceId,
+    cx: &AppContext,
+) -> Task<Result<Vec<(PathBuf, PathBuf)>>> {
+    cx.background_executor()
+        .spawn(async move { IMAGE_VIEWER.get_recent_images(workspace_id) })
+}
+
+/// A modal listing the images recently viewed in the workspace.
+pub struct RecentImages {
+    picker: View<Picker<RecentImagesDelegate>>,
+    _subscription: Subscription,
+}
+
+impl RecentImages {
+    pub fn register(workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>) {
+        workspace.register_action(Self::toggle);
+    }
+
+    fn toggle(workspace: &mut Workspace, _: &ShowRecentImages, cx: &mut ViewContext<Workspace>) {
+        let Some(workspace_id) = workspace.database_id() else {
+            return;
+        };
+        let load_recent_images = load(workspace_id, cx);
+
+        cx.spawn(|workspace, mut cx| async move {
+            let recent_images = load_recent_images.await?;
+            workspace.update(&mut cx, |workspace, cx| {
+                let project = workspace.project().read(cx);
+                let images = recent_images
+                    .into_iter()
+                    .map(|(worktree_root, image_path)| RecentImage {
+                        project_path: find_stored_path(project, &worktree_root, &image_path, cx),
+                        abs_path: stored_abs_path(&worktree_root, &image_path),
+                    })
+                    .collect();
+                let delegate = RecentImagesDelegate::new(cx.view().downgrade(), images);
+                workspace.toggle_modal(cx, |cx| RecentImages::new(delegate, cx));
+            })
+        })
+        .detach_and_prompt_err("Failed to load recent images", cx, |_, _| None);
+    }
+
+    fn new(delegate: RecentImagesDelegate, cx: &mut ViewContext<Self>) -> Self {
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| cx.emit(DismissEvent));
+        Self {
+            picker,
+            _subscription,
+        }
+    }
+}
+
+impl ModalView for RecentImages {}
+impl EventEmitter<DismissEvent> for RecentImages {}
+
+impl FocusableView for RecentImages {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for RecentImages {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+struct RecentImage {
+    /// Where the image is in the project, if it belongs to one of its worktrees.
+    project_path: Option<ProjectPath>,
+    abs_path: PathBuf,
+}
+
+impl RecentImage {
+    fn file_name(&self) -> String {
+        self.abs_path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+}
+
+pub struct RecentImagesDelegate {
+    workspace: WeakView<Workspace>,
+    images: Vec<RecentImage>,
+    /// Indices into `images` of the images matching the query.
+    matches: Vec<usize>,
+    selected_index: usize,
+}
+
+impl RecentImagesDelegate {
+    fn new(workspace: WeakView<Workspace>, images: Vec<RecentImage>) -> Self {
+        Self {
+            workspace,
+            matches: (0..images.len()).collect(),
+            images,
+            selected_index: 0,
+        }
+    }
+}
+
+impl PickerDelegate for RecentImagesDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Search recent images…".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, _: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let query = query.trim().to_lowercase();
+        self.matches = self
+            .images
+            .iter()
+            .enumerate()
+            .filter(|(_, image)| {
+                query.is_empty()
+                    || image
+                        .abs_path
+                        .to_string_lossy()
+                        .to_lowercase()
+                        .contains(&query)
+            })
+            .map(|(ix, _)| ix)
+            .collect();
+        self.selected_index = self
+            .selected_index
+            .min(self.matches.len().saturating_sub(1));
+        Task::ready(())
+    }
+
+    fn confirm(&mut self, _secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(image) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|ix| self.images.get(*ix))
+        else {
+            return;
+        };
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        workspace.update(cx, |workspace, cx| {
+            let open_task = match image.project_path.clone() {
+                Some(project_path) => workspace.open_path(project_path, None, true, cx),
+                None => workspace.open_abs_path(image.abs_path.clone(), false, cx),
+            };
+            open_task.detach_and_prompt_err("Failed to open image", cx, |_, _| None);
+        });
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let image = self.images.get(*self.matches.get(ix)?)?;
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(
+                    v_flex()
+                        .child(Label::new(image.file_name()).single_line())
+                        .child(
+                            Label::new(image.abs_path.compact().to_string_lossy().to_string())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .single_line(),
+                        ),
+                ),
+        )
+    }
+}
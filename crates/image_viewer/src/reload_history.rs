@@ -0,0 +1,309 @@
+//! A time machine for images that are rewritten over and over, such as the output of a shader
+//! or render development loop. The last versions the image was reloaded with are kept in
+//! memory, and a scrubber under the image flips back through them.
+
+use std::{collections::VecDeque, sync::Arc};
+
+use gpui::{Image, ViewContext};
+use ui::{prelude::*, Tooltip};
+
+use crate::{
+    FlipReloadVersion, ImageView, ShowNewerVersion, ShowOlderVersion, ToggleReloadHistory,
+};
+
+struct ImageVersion {
+    id: usize,
+    image: Arc<Image>,
+}
+
+/// The versions an image was shown with, oldest first, ending with the current one.
+pub(crate) struct ReloadHistory {
+    versions: VecDeque<ImageVersion>,
+    next_id: usize,
+    /// The version shown instead of the current one while scrubbing.
+    shown: Option<usize>,
+    /// The version that flipping from the current one shows, which is the last one scrubbed to.
+    flip_target: Option<usize>,
+    /// Whether the scrubber is shown under the image.
+    pub(crate) open: bool,
+}
+
+impl ReloadHistory {
+    pub(crate) fn new(image: Arc<Image>) -> Self {
+        Self {
+            versions: VecDeque::from([ImageVersion { id: 0, image }]),
+            next_id: 1,
+            shown: None,
+            flip_target: None,
+            open: false,
+        }
+    }
+
+    /// Adds the version the image was reloaded with, forgetting the oldest ones beyond
+    /// `capacity`. The scrubber stays on the version it shows, unless that one is forgotten.
+    fn record(&mut self, image: Arc<Image>, capacity: usize) {
+        self.versions.push_back(ImageVersion {
+            id: self.next_id,
+            image,
+        });
+        self.next_id += 1;
+        while self.versions.len() > capacity.max(1) {
+            self.versions.pop_front();
+        }
+        if self.shown.is_some_and(|id| self.index_of(id).is_none()) {
+            self.shown = self.versions.front().map(|version| version.id);
+        }
+        if self
+            .flip_target
+            .is_some_and(|id| self.index_of(id).is_none())
+        {
+            self.flip_target = None;
+        }
+    }
+
+    fn index_of(&self, id: usize) -> Option<usize> {
+        self.versions.iter().position(|version| version.id == id)
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.versions.len()
+    }
+
+    /// The index of the version being shown, where the last one is the current version.
+    fn shown_index(&self) -> usize {
+        self.shown
+            .and_then(|id| self.index_of(id))
+            .unwrap_or(self.versions.len().saturating_sub(1))
+    }
+
+    /// The earlier version shown instead of the current one, if scrubbing.
+    pub(crate) fn shown_image(&self) -> Option<Arc<Image>> {
+        let id = self.shown?;
+        let ix = self.index_of(id)?;
+        Some(self.versions[ix].image.clone())
+    }
+
+    fn show(&mut self, ix: usize) {
+        let ix = ix.min(self.versions.len().saturating_sub(1));
+        self.shown = if ix + 1 == self.versions.len() {
+            None
+        } else {
+            let id = self.versions[ix].id;
+            self.flip_target = Some(id);
+            Some(id)
+        };
+    }
+
+    fn step(&mut self, delta: isize) {
+        let ix = self.shown_index().saturating_add_signed(delta);
+        self.show(ix);
+    }
+
+    /// Switches between the current version and the last one scrubbed to.
+    fn flip(&mut self) {
+        if let Some(shown) = self.shown.take() {
+            self.flip_target = Some(shown);
+        } else if let Some(target) = self.flip_target {
+            self.shown = Some(target);
+        }
+    }
+}
+
+/// Keeps the version the image was just reloaded with.
+pub(crate) fn record_reload(image_view: &mut ImageView, cx: &mut ViewContext<ImageView>) {
+    let capacity = image_view.settings(cx).reload_history_size;
+    let image = image_view.image_item.read(cx).image.clone();
+    image_view.reload_history.record(image, capacity);
+}
+
+pub(crate) fn toggle_reload_history(
+    image_view: &mut ImageView,
+    _: &ToggleReloadHistory,
+    cx: &mut ViewContext<ImageView>,
+) {
+    let history = &mut image_view.reload_history;
+    history.open = !history.open;
+    if !history.open {
+        history.shown = None;
+    }
+    cx.notify();
+}
+
+pub(crate) fn show_older_version(
+    image_view: &mut ImageView,
+    _: &ShowOlderVersion,
+    cx: &mut ViewContext<ImageView>,
+) {
+    image_view.reload_history.open = true;
+    image_view.reload_history.step(-1);
+    cx.notify();
+}
+
+pub(crate) fn show_newer_version(
+    image_view: &mut ImageView,
+    _: &ShowNewerVersion,
+    cx: &mut ViewContext<ImageView>,
+) {
+    image_view.reload_history.step(1);
+    cx.notify();
+}
+
+pub(crate) fn flip_reload_version(
+    image_view: &mut ImageView,
+    _: &FlipReloadVersion,
+    cx: &mut ViewContext<ImageView>,
+) {
+    image_view.reload_history.flip();
+    cx.notify();
+}
+
+/// The scrubber under an image, with a notch for each version it was reloaded with.
+pub(crate) fn render_reload_history_bar(
+    image_view: &ImageView,
+    cx: &mut ViewContext<ImageView>,
+) -> Option<impl IntoElement> {
+    let history = &image_view.reload_history;
+    if !history.open {
+        return None;
+    }
+    let count = history.len();
+    let shown_ix = history.shown_index();
+    let units = image_view.settings(cx).units();
+    let position_label = if shown_ix + 1 == count {
+        format!("Current version of {}", units.count(count))
+    } else {
+        format!(
+            "Version {} of {}, {} behind",
+            units.count(shown_ix + 1),
+            units.count(count),
+            units.count(count - shown_ix - 1)
+        )
+    };
+    let colors = cx.theme().colors();
+    let notch_color = colors.element_background;
+    let hover_color = colors.element_hover;
+    let shown_color = colors.text_accent;
+
+    Some(
+        h_flex()
+            .w_full()
+            .p_2()
+            .gap_2()
+            .border_t_1()
+            .border_color(colors.border)
+            .bg(colors.panel_background)
+            .child(
+                IconButton::new("reload-history-older", IconName::ChevronLeft)
+                    .shape(IconButtonShape::Square)
+                    .disabled(shown_ix == 0)
+                    .tooltip(|cx| Tooltip::for_action("Show Older Version", &ShowOlderVersion, cx))
+                    .on_click(
+                        cx.listener(|this, _, cx| show_older_version(this, &ShowOlderVersion, cx)),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .flex_1()
+                    .h_4()
+                    .gap_px()
+                    .children((0..count).map(|ix| {
+                        div()
+                            .id(("reload-history-version", ix))
+                            .flex_1()
+                            .h_full()
+                            .rounded_sm()
+                            .bg(if ix == shown_ix {
+                                shown_color
+                            } else {
+                                notch_color
+                            })
+                            .hover(|style| style.bg(hover_color))
+                            .on_click(cx.listener(move |this, _, cx| {
+                                this.reload_history.show(ix);
+                                cx.notify();
+                            }))
+                    })),
+            )
+            .child(
+                IconButton::new("reload-history-newer", IconName::ChevronRight)
+                    .shape(IconButtonShape::Square)
+                    .disabled(shown_ix + 1 == count)
+                    .tooltip(|cx| Tooltip::for_action("Show Newer Version", &ShowNewerVersion, cx))
+                    .on_click(
+                        cx.listener(|this, _, cx| show_newer_version(this, &ShowNewerVersion, cx)),
+                    ),
+            )
+            .child(Label::new(position_label).size(LabelSize::Small))
+            .child(
+                Button::new("reload-history-flip", "A/B")
+                    .label_size(LabelSize::Small)
+                    .disabled(history.shown.is_none() && history.flip_target.is_none())
+                    .tooltip(|cx| {
+                        Tooltip::for_action(
+                            "Flip Between Current and Scrubbed Version",
+                            &FlipReloadVersion,
+                            cx,
+                        )
+                    })
+                    .on_click(
+                        cx.listener(|this, _, cx| {
+                            flip_reload_version(this, &FlipReloadVersion, cx)
+                        }),
+                    ),
+            )
+            .child(
+                IconButton::new("reload-history-close", IconName::Close)
+                    .shape(IconButtonShape::Square)
+                    .tooltip(|cx| Tooltip::for_action("Close", &ToggleReloadHistory, cx))
+                    .on_click(cx.listener(|this, _, cx| {
+                        toggle_reload_history(this, &ToggleReloadHistory, cx)
+                    })),
+            ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::ImageFormat;
+
+    fn image(byte: u8) -> Arc<Image> {
+        Arc::new(Image {
+            format: ImageFormat::Png,
+            bytes: vec![byte],
+            id: byte as u64,
+        })
+    }
+
+    fn shown_byte(history: &ReloadHistory) -> Option<u8> {
+        history.shown_image().map(|image| image.bytes[0])
+    }
+
+    #[test]
+    fn test_reload_history() {
+        let mut history = ReloadHistory::new(image(0));
+        for byte in 1..5 {
+            history.record(image(byte), 3);
+        }
+        assert_eq!(history.len(), 3);
+        assert_eq!(shown_byte(&history), None);
+
+        history.step(-1);
+        assert_eq!(shown_byte(&history), Some(3));
+        history.step(-5);
+        assert_eq!(shown_byte(&history), Some(2));
+
+        // Flipping alternates between the current version and the last one scrubbed to.
+        history.flip();
+        assert_eq!(shown_byte(&history), None);
+        history.flip();
+        assert_eq!(shown_byte(&history), Some(2));
+
+        // Reloading keeps showing the scrubbed version, until it's forgotten.
+        history.record(image(5), 3);
+        assert_eq!(shown_byte(&history), Some(3));
+        history.step(1);
+        history.step(1);
+        assert_eq!(shown_byte(&history), None);
+    }
+}
@@ -0,0 +1,167 @@
+use std::path::Path;
+
+use anyhow::Result;
+use gpui::ViewContext;
+use project::ProjectPath;
+use settings::{Settings, SettingsLocation};
+use util::command::new_smol_command;
+use workspace::{
+    notifications::{DetachAndPromptErr, NotificationId},
+    Toast, Workspace,
+};
+
+use crate::{image_viewer_settings::ImageViewerSettings, CaptureScreenshot};
+
+pub fn register(workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>) {
+    workspace.register_action(capture_screenshot);
+}
+
+/// Lets the user pick a screen region, saves it into the screenshots directory of the active
+/// worktree and opens it in the image viewer.
+fn capture_screenshot(
+    workspace: &mut Workspace,
+    _: &CaptureScreenshot,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let project = workspace.project().clone();
+    if !project.read(cx).is_local() {
+        struct RemoteScreenshot;
+        workspace.show_toast(
+            Toast::new(
+                NotificationId::unique::<RemoteScreenshot>(),
+                "Screenshots can only be captured into local projects",
+            )
+            .autohide(),
+            cx,
+        );
+        return;
+    }
+
+    let worktree = workspace
+        .active_item(cx)
+        .and_then(|item| item.project_path(cx))
+        .and_then(|project_path| {
+            project
+                .read(cx)
+                .worktree_for_id(project_path.worktree_id, cx)
+        })
+        .or_else(|| project.read(cx).visible_worktrees(cx).next());
+    let Some(worktree) = worktree else {
+        return;
+    };
+
+    let settings = ImageViewerSettings::get(
+        Some(SettingsLocation {
+            worktree_id: worktree.read(cx).id(),
+            path: Path::new(""),
+        }),
+        cx,
+    );
+    let directory = Path::new(&settings.screenshots_directory).to_owned();
+    let file_stem = format!(
+        "screenshot-{}",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    );
+    let snapshot = worktree.read(cx).snapshot();
+    let path = (0..)
+        .map(|ix| {
+            let file_name = if ix == 0 {
+                format!("{file_stem}.png")
+            } else {
+                format!("{file_stem}-{ix}.png")
+            };
+            directory.join(file_name)
+        })
+        .find(|path| snapshot.entry_for_path(path).is_none())
+        .unwrap();
+    let project_path = ProjectPath {
+        worktree_id: snapshot.id(),
+        path: path.as_path().into(),
+    };
+    let Some(abs_path) = project.read(cx).absolute_path(&project_path, cx) else {
+        return;
+    };
+    let fs = project.read(cx).fs().clone();
+
+    cx.spawn(|workspace, mut cx| async move {
+        if let Some(parent) = abs_path.parent() {
+            fs.create_dir(parent).await?;
+        }
+        capture_screen_region(&abs_path).await?;
+        // Cancelling the selection leaves no file behind.
+        if !fs.is_file(&abs_path).await {
+            return Ok(());
+        }
+        workspace
+            .update(&mut cx, |workspace, cx| {
+                workspace.open_path(project_path, None, true, cx)
+            })?
+            .await?;
+        anyhow::Ok(())
+    })
+    .detach_and_prompt_err("Failed to capture screenshot", cx, |_, _| None);
+}
+
+/// Runs the platform's interactive screenshot tool, which saves the selected region as a PNG
+/// at `abs_path`.
+#[cfg(target_os = "macos")]
+async fn capture_screen_region(abs_path: &Path) -> Result<()> {
+    new_smol_command("screencapture")
+        .args(["-i", "-x"])
+        .arg(abs_path)
+        .status()
+        .await?;
+    Ok(())
+}
+
+#[cfg(any(target_os = "linux", target_os = "freebsd"))]
+async fn capture_screen_region(abs_path: &Path) -> Result<()> {
+    use std::io;
+
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        if let Ok(selection) = new_smol_command("slurp").output().await {
+            if selection.status.success() {
+                let geometry = String::from_utf8(selection.stdout)?;
+                new_smol_command("grim")
+                    .arg("-g")
+                    .arg(geometry.trim())
+                    .arg(abs_path)
+                    .status()
+                    .await?;
+            }
+            return Ok(());
+        }
+    }
+
+    let tools: [(&str, &[&str]); 4] = [
+        ("gnome-screenshot", &["--area", "--file"]),
+        (
+            "spectacle",
+            &["--region", "--background", "--nonotify", "--output"],
+        ),
+        ("maim", &["--select"]),
+        ("scrot", &["--select"]),
+    ];
+    for (program, args) in tools {
+        match new_smol_command(program)
+            .args(args)
+            .arg(abs_path)
+            .status()
+            .await
+        {
+            Ok(_) => return Ok(()),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => continue,
+            Err(error) => return Err(error.into()),
+        }
+    }
+    Err(anyhow::anyhow!(
+        "No screenshot tool found. Install gnome-screenshot, spectacle, maim, scrot, or grim and slurp."
+    ))
+}
+
+#[cfg(target_os = "windows")]
+async fn capture_screen_region(_abs_path: &Path) -> Result<()> {
+    Err(anyhow::anyhow!(
+        "Capturing screenshots is not supported on Windows yet"
+    ))
+}
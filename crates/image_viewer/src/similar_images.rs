@@ -0,0 +1,460 @@
+//! Finds images of the project that look alike, such as an asset committed again at another size
+//! or in another format, by comparing perceptual hashes of their thumbnails.
+//!
+//! The thumbnails are the ones galleries show, so images already seen in a gallery are hashed
+//! without being read again.
+
+use std::{collections::HashMap, sync::Arc};
+
+use futures::future::join_all;
+use gpui::{
+    img, AppContext, EventEmitter, FocusHandle, FocusableView, Image, Model, ObjectFit, Task, View,
+    ViewContext, VisualContext, WeakView,
+};
+use project::{image_store::is_image_path, Project, ProjectPath, ThumbnailPriority};
+use settings::Settings;
+use ui::{prelude::*, Tooltip};
+use util::ResultExt;
+use workspace::{item::Item, Workspace, WorkspaceId};
+
+use crate::{image_gallery::THUMBNAIL_PIXELS, FindSimilarImages, ImageView, ImageViewerSettings};
+
+/// How many bits of their perceptual hashes two images can differ by to be considered alike.
+const MAX_HASH_DISTANCE: u32 = 10;
+/// How many thumbnails are asked for at once while scanning.
+const SCAN_BATCH_SIZE: usize = 16;
+const THUMBNAIL_SIZE: Pixels = px(96.);
+
+struct ScannedImage {
+    project_path: ProjectPath,
+    file_size: u64,
+    hash: u64,
+    thumbnail: Arc<Image>,
+}
+
+/// Lists the images of the project that look like the image it was opened from, or every group
+/// of images that look alike when it was opened without one.
+pub struct SimilarImagesView {
+    project: Model<Project>,
+    workspace: WeakView<Workspace>,
+    focus_handle: FocusHandle,
+    /// The image to find look-alikes of.
+    target: Option<ProjectPath>,
+    images: Vec<ScannedImage>,
+    /// How many images were scanned so far, including ones that couldn't be hashed.
+    scanned: usize,
+    total: usize,
+    /// Groups of indices into `images`, or `None` while scanning.
+    groups: Option<Vec<Vec<usize>>>,
+    _scan_task: Task<Option<()>>,
+}
+
+impl SimilarImagesView {
+    pub fn register(workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>) {
+        workspace.register_action(Self::find_similar_images);
+    }
+
+    /// Finds the images that look like the active one, or all groups of images that look alike
+    /// when no image is active.
+    fn find_similar_images(
+        workspace: &mut Workspace,
+        _: &FindSimilarImages,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let target = workspace
+            .active_item_as::<ImageView>(cx)
+            .map(|image_view| image_view.read(cx).image_item.read(cx))
+            .filter(|image_item| image_item.has_project_file())
+            .map(|image_item| image_item.project_path(cx));
+        let project = workspace.project().clone();
+        let weak_workspace = cx.view().downgrade();
+        let view = cx.new_view(|cx| Self::new(project, weak_workspace, target, cx));
+        workspace.add_item_to_active_pane(Box::new(view), None, true, cx);
+    }
+
+    fn new(
+        project: Model<Project>,
+        workspace: WeakView<Workspace>,
+        target: Option<ProjectPath>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let mut image_paths = Vec::new();
+        for worktree in project.read(cx).visible_worktrees(cx) {
+            let worktree = worktree.read(cx);
+            image_paths.extend(
+                worktree
+                    .files(false, 0)
+                    .filter(|entry| is_image_path(&entry.path))
+                    .map(|entry| {
+                        let project_path = ProjectPath {
+                            worktree_id: worktree.id(),
+                            path: entry.path.clone(),
+                        };
+                        (project_path, entry.size)
+                    }),
+            );
+        }
+        // The image may be ignored, or outside of the visible worktrees.
+        if let Some(target) = target.as_ref() {
+            if !image_paths.iter().any(|(path, _)| path == target) {
+                image_paths.push((target.clone(), 0));
+            }
+        }
+
+        let total = image_paths.len();
+        let scan_task = cx.spawn(|this, mut cx| async move {
+            for batch in image_paths.chunks(SCAN_BATCH_SIZE) {
+                let load_thumbnails = this
+                    .update(&mut cx, |this, cx| {
+                        this.project.update(cx, |project, cx| {
+                            batch
+                                .iter()
+                                .map(|(project_path, _)| {
+                                    project.load_thumbnail(
+                                        project_path.clone(),
+                                        THUMBNAIL_PIXELS,
+                                        ThumbnailPriority::Background,
+                                        cx,
+                                    )
+                                })
+                                .collect::<Vec<_>>()
+                        })
+                    })
+                    .ok()?;
+                let thumbnails = join_all(load_thumbnails).await;
+                let batch = batch.to_vec();
+                let scanned = cx
+                    .background_executor()
+                    .spawn(async move {
+                        batch
+                            .into_iter()
+                            .zip(thumbnails)
+                            .filter_map(|((project_path, file_size), thumbnail)| {
+                                let thumbnail = thumbnail.log_err()?;
+                                Some(ScannedImage {
+                                    project_path,
+                                    file_size,
+                                    hash: thumbnail.perceptual_hash()?,
+                                    thumbnail: thumbnail.image.clone(),
+                                })
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                    .await;
+                this.update(&mut cx, |this, cx| {
+                    this.scanned += SCAN_BATCH_SIZE.min(this.total - this.scanned);
+                    this.images.extend(scanned);
+                    cx.notify();
+                })
+                .ok()?;
+            }
+
+            let (hashes, target_ix) = this
+                .update(&mut cx, |this, _| {
+                    let hashes = this
+                        .images
+                        .iter()
+                        .map(|image| image.hash)
+                        .collect::<Vec<_>>();
+                    let target_ix = this.target.as_ref().map(|target| {
+                        this.images
+                            .iter()
+                            .position(|image| &image.project_path == target)
+                    });
+                    (hashes, target_ix)
+                })
+                .ok()?;
+            let groups = cx
+                .background_executor()
+                .spawn(async move {
+                    match target_ix {
+                        Some(Some(target_ix)) => {
+                            vec![similar_to(target_ix, &hashes, MAX_HASH_DISTANCE)]
+                        }
+                        // The image being compared against couldn't be hashed.
+                        Some(None) => Vec::new(),
+                        None => group_similar(&hashes, MAX_HASH_DISTANCE),
+                    }
+                })
+                .await;
+            this.update(&mut cx, |this, cx| {
+                this.set_groups(groups);
+                cx.notify();
+            })
+            .ok()
+        });
+
+        Self {
+            project,
+            workspace,
+            focus_handle: cx.focus_handle(),
+            target,
+            images: Vec::new(),
+            scanned: 0,
+            total,
+            groups: None,
+            _scan_task: scan_task,
+        }
+    }
+
+    /// Keeps the groups with more than one image, putting the ones taking up the most space
+    /// that could be reclaimed first.
+    fn set_groups(&mut self, mut groups: Vec<Vec<usize>>) {
+        groups.retain(|group| group.len() > 1);
+        groups.sort_by_key(|group| std::cmp::Reverse(self.reclaimable_size(group)));
+        self.groups = Some(groups);
+    }
+
+    /// How much smaller the project would be with only the largest image of a group.
+    fn reclaimable_size(&self, group: &[usize]) -> u64 {
+        let sizes = group.iter().map(|&ix| self.images[ix].file_size);
+        sizes.clone().sum::<u64>() - sizes.max().unwrap_or(0)
+    }
+
+    fn open_image(&mut self, project_path: ProjectPath, cx: &mut ViewContext<Self>) {
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace
+                    .open_path(project_path, None, true, cx)
+                    .detach_and_log_err(cx);
+            })
+            .ok();
+    }
+
+    fn title(&self) -> SharedString {
+        match self
+            .target
+            .as_ref()
+            .and_then(|target| target.path.file_name())
+        {
+            Some(file_name) => format!("Similar to {}", file_name.to_string_lossy()).into(),
+            None => "Similar Images".into(),
+        }
+    }
+
+    fn render_image(&self, ix: usize, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let units = ImageViewerSettings::get_global(cx).units();
+        let image = &self.images[ix];
+        let project_path = image.project_path.clone();
+        let path = image.project_path.path.to_string_lossy().to_string();
+        let file_name = image
+            .project_path
+            .path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let is_target = self.target.as_ref() == Some(&image.project_path);
+
+        v_flex()
+            .id(("similar-image", ix))
+            .w(THUMBNAIL_SIZE)
+            .p_1()
+            .gap_0p5()
+            .rounded_md()
+            .cursor_pointer()
+            .when(is_target, |this| {
+                this.border_1()
+                    .border_color(cx.theme().colors().border_focused)
+            })
+            .hover(|style| style.bg(cx.theme().colors().element_hover))
+            .tooltip(move |cx| Tooltip::text(path.clone(), cx))
+            .on_click(cx.listener(move |this, _, cx| this.open_image(project_path.clone(), cx)))
+            .child(
+                div()
+                    .size(THUMBNAIL_SIZE)
+                    .flex()
+                    .items_center()
+                    .justify_center()
+                    .child(
+                        img(image.thumbnail.clone())
+                            .max_w(THUMBNAIL_SIZE)
+                            .max_h(THUMBNAIL_SIZE)
+                            .object_fit(ObjectFit::ScaleDown),
+                    ),
+            )
+            .child(Label::new(file_name).size(LabelSize::Small).single_line())
+            .child(
+                Label::new(units.file_size(image.file_size))
+                    .size(LabelSize::XSmall)
+                    .color(Color::Muted),
+            )
+    }
+}
+
+/// The images whose hashes are within `max_distance` bits of the one at `target_ix`, starting
+/// with that one and then from the most alike.
+fn similar_to(target_ix: usize, hashes: &[u64], max_distance: u32) -> Vec<usize> {
+    let target = hashes[target_ix];
+    let mut similar = hashes
+        .iter()
+        .enumerate()
+        .filter(|(ix, _)| *ix != target_ix)
+        .map(|(ix, hash)| (ix, (hash ^ target).count_ones()))
+        .filter(|(_, distance)| *distance <= max_distance)
+        .collect::<Vec<_>>();
+    similar.sort_by_key(|(_, distance)| *distance);
+    std::iter::once(target_ix)
+        .chain(similar.into_iter().map(|(ix, _)| ix))
+        .collect()
+}
+
+/// Groups the hashes that are within `max_distance` bits of each other, directly or through
+/// other hashes of their group.
+fn group_similar(hashes: &[u64], max_distance: u32) -> Vec<Vec<usize>> {
+    fn root(parents: &mut [usize], mut ix: usize) -> usize {
+        while parents[ix] != ix {
+            parents[ix] = parents[parents[ix]];
+            ix = parents[ix];
+        }
+        ix
+    }
+
+    let mut parents = (0..hashes.len()).collect::<Vec<_>>();
+    for a in 0..hashes.len() {
+        for b in a + 1..hashes.len() {
+            if (hashes[a] ^ hashes[b]).count_ones() <= max_distance {
+                let (root_a, root_b) = (root(&mut parents, a), root(&mut parents, b));
+                parents[root_b] = root_a;
+            }
+        }
+    }
+
+    let mut groups = HashMap::<usize, Vec<usize>>::default();
+    for ix in 0..hashes.len() {
+        groups.entry(root(&mut parents, ix)).or_default().push(ix);
+    }
+    let mut groups = groups.into_values().collect::<Vec<_>>();
+    groups.sort();
+    groups
+}
+
+impl EventEmitter<()> for SimilarImagesView {}
+
+impl FocusableView for SimilarImagesView {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for SimilarImagesView {
+    type Event = ();
+
+    fn to_item_events(_: &Self::Event, _: impl FnMut(workspace::item::ItemEvent)) {}
+
+    fn tab_content_text(&self, _cx: &WindowContext) -> Option<SharedString> {
+        Some(self.title())
+    }
+
+    fn tab_icon(&self, _cx: &WindowContext) -> Option<Icon> {
+        Some(Icon::new(IconName::MagnifyingGlass))
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("similar images")
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: Option<WorkspaceId>,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<View<Self>> {
+        let project = self.project.clone();
+        let workspace = self.workspace.clone();
+        let target = self.target.clone();
+        Some(cx.new_view(|cx| Self::new(project, workspace, target, cx)))
+    }
+}
+
+impl Render for SimilarImagesView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let units = ImageViewerSettings::get_global(cx).units();
+        let summary = match self.groups.as_ref() {
+            None => format!(
+                "Scanning {} of {} images…",
+                units.count(self.scanned),
+                units.count(self.total)
+            ),
+            Some(groups) if groups.is_empty() => "No images look alike".to_string(),
+            Some(groups) if self.target.is_some() => {
+                let count = groups.iter().map(|group| group.len() - 1).sum::<usize>();
+                format!("{} images look alike", units.count(count))
+            }
+            Some(groups) => {
+                let reclaimable = groups
+                    .iter()
+                    .map(|group| self.reclaimable_size(group))
+                    .sum::<u64>();
+                format!(
+                    "{} groups of images look alike, {} could be reclaimed",
+                    units.count(groups.len()),
+                    units.file_size(reclaimable)
+                )
+            }
+        };
+        let groups = self.groups.clone().unwrap_or_default();
+
+        v_flex()
+            .id("similar-images")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .overflow_y_scroll()
+            .p_4()
+            .gap_2()
+            .bg(cx.theme().colors().editor_background)
+            .child(Label::new(self.title()).size(LabelSize::Large))
+            .child(Label::new(summary).color(Color::Muted))
+            .children(groups.into_iter().map(|group| {
+                let reclaimable = self.reclaimable_size(&group);
+                v_flex()
+                    .gap_1()
+                    .pt_2()
+                    .border_t_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .when(self.target.is_none(), |this| {
+                        this.child(
+                            Label::new(format!(
+                                "{} images, {} could be reclaimed",
+                                units.count(group.len()),
+                                units.file_size(reclaimable)
+                            ))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                        )
+                    })
+                    .child(
+                        h_flex()
+                            .flex_wrap()
+                            .gap_2()
+                            .children(group.into_iter().map(|ix| self.render_image(ix, cx))),
+                    )
+            }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_similar() {
+        let hashes = [
+            0b0000,
+            0b0001,
+            0b0011,
+            u64::MAX,
+            0b0111 << 60,
+            u64::MAX >> 1,
+        ];
+        assert_eq!(
+            group_similar(&hashes, 1),
+            vec![vec![0, 1, 2], vec![3, 5], vec![4]]
+        );
+        assert_eq!(
+            group_similar(&hashes, 0),
+            (0..hashes.len()).map(|ix| vec![ix]).collect::<Vec<_>>()
+        );
+
+        assert_eq!(similar_to(0, &hashes, 2), vec![0, 1, 2]);
+        assert_eq!(similar_to(2, &hashes, 1), vec![2, 1]);
+    }
+}
@@ -0,0 +1,511 @@
+//! Sprite sheets, images made of equally sized frames laid out in a grid as used for the
+//! animations of 2D games. The grid is overlaid on the image with each frame numbered, and the
+//! frames can be played back one after the other.
+//!
+//! The size of the frames is entered by hand, or read from a `<name>.sprites.json` file next to
+//! the image, such as `{ "frame_width": 32, "frame_height": 32, "fps": 8 }`.
+
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+use editor::{Editor, EditorEvent};
+use gpui::{img, outline, point, size, AppContext, Bounds, Hsla, Image, Subscription, Task, View};
+use serde::Deserialize;
+use ui::{prelude::*, Tooltip};
+use util::ResultExt;
+
+use crate::{image_region_bounds, paint_label, ImageView, ToggleSpriteSheet};
+
+/// How many frames are played per second when the sidecar file doesn't say.
+const DEFAULT_FPS: u32 = 12;
+/// The largest width or height the current frame is previewed at.
+const FRAME_PREVIEW_SIZE: f32 = 96.;
+/// How wide frames have to be drawn for their numbers to be shown.
+const MIN_NUMBERED_FRAME_WIDTH: f32 = 24.;
+
+/// How the frames of a sprite sheet are laid out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub(crate) struct SpriteGrid {
+    pub frame_width: u32,
+    pub frame_height: u32,
+    /// The space between the frames and the edges of the sheet.
+    #[serde(default)]
+    pub margin: u32,
+    /// The space between neighboring frames.
+    #[serde(default)]
+    pub spacing: u32,
+}
+
+impl SpriteGrid {
+    /// The frames of a sheet of the given size in pixels, row by row. Partial frames at the right
+    /// and bottom edges are left out.
+    pub(crate) fn frames(&self, (width, height): (u32, u32)) -> Vec<Bounds<u32>> {
+        let columns = self.fit(width, self.frame_width);
+        let rows = self.fit(height, self.frame_height);
+        (0..rows)
+            .flat_map(|row| {
+                (0..columns).map(move |column| Bounds {
+                    origin: point(
+                        self.margin + column * (self.frame_width + self.spacing),
+                        self.margin + row * (self.frame_height + self.spacing),
+                    ),
+                    size: size(self.frame_width, self.frame_height),
+                })
+            })
+            .collect()
+    }
+
+    /// How many frames of the given length fit along a side of the sheet.
+    fn fit(&self, side: u32, frame: u32) -> u32 {
+        let Some(available) = side.checked_sub(2 * self.margin) else {
+            return 0;
+        };
+        if frame == 0 || available < frame {
+            return 0;
+        }
+        (available - frame) / (frame + self.spacing) + 1
+    }
+}
+
+/// The contents of a `.sprites.json` file describing the sprite sheet next to it.
+#[derive(Deserialize)]
+struct SpriteSheetSidecar {
+    #[serde(flatten)]
+    grid: SpriteGrid,
+    fps: Option<u32>,
+}
+
+/// The sprite sheet mode of an image view.
+pub(crate) struct SpriteSheet {
+    pub(crate) grid: Option<SpriteGrid>,
+    /// The file the grid was read from, if it wasn't entered by hand.
+    sidecar_path: Option<PathBuf>,
+    frame_width_editor: View<Editor>,
+    frame_height_editor: View<Editor>,
+    fps_editor: View<Editor>,
+    pub(crate) current_frame: usize,
+    play_task: Option<Task<()>>,
+    load_sidecar_task: Option<Task<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl SpriteSheet {
+    fn new(cx: &mut ViewContext<ImageView>) -> Self {
+        let new_editor = |placeholder: &str, cx: &mut ViewContext<ImageView>| {
+            let editor = cx.new_view(|cx| {
+                let mut editor = Editor::single_line(cx);
+                editor.set_placeholder_text(placeholder, cx);
+                editor
+            });
+            let subscription = cx.subscribe(&editor, |this, _, event, cx| {
+                if let EditorEvent::Edited { .. } = event {
+                    grid_edited(this, cx);
+                }
+            });
+            (editor, subscription)
+        };
+        let (frame_width_editor, frame_width_subscription) = new_editor("Width", cx);
+        let (frame_height_editor, frame_height_subscription) = new_editor("Height", cx);
+        let (fps_editor, fps_subscription) = new_editor("FPS", cx);
+        Self {
+            grid: None,
+            sidecar_path: None,
+            frame_width_editor,
+            frame_height_editor,
+            fps_editor,
+            current_frame: 0,
+            play_task: None,
+            load_sidecar_task: None,
+            _subscriptions: vec![
+                frame_width_subscription,
+                frame_height_subscription,
+                fps_subscription,
+            ],
+        }
+    }
+
+    fn fps(&self, cx: &AppContext) -> u32 {
+        self.fps_editor
+            .read(cx)
+            .text(cx)
+            .trim()
+            .parse()
+            .ok()
+            .filter(|fps| *fps > 0)
+            .unwrap_or(DEFAULT_FPS)
+    }
+
+    fn is_playing(&self) -> bool {
+        self.play_task.is_some()
+    }
+}
+
+pub(crate) fn toggle_sprite_sheet(
+    image_view: &mut ImageView,
+    _: &ToggleSpriteSheet,
+    cx: &mut ViewContext<ImageView>,
+) {
+    if image_view.sprite_sheet.take().is_none() {
+        image_view.sprite_sheet = Some(SpriteSheet::new(cx));
+        load_sidecar(image_view, cx);
+    }
+    cx.notify();
+}
+
+/// Reads the grid of the displayed image from the `.sprites.json` file next to it, if there
+/// is one.
+pub(crate) fn load_sidecar(image_view: &mut ImageView, cx: &mut ViewContext<ImageView>) {
+    let Some(abs_path) = image_view.abs_path(cx) else {
+        return;
+    };
+    let Some(sprite_sheet) = image_view.sprite_sheet.as_mut() else {
+        return;
+    };
+    sprite_sheet.sidecar_path = None;
+    sprite_sheet.current_frame = 0;
+    let sidecar_path = abs_path.with_extension("sprites.json");
+    let fs = image_view.project.read(cx).fs().clone();
+    sprite_sheet.load_sidecar_task = Some(cx.spawn(|this, mut cx| async move {
+        let Ok(contents) = fs.load(&sidecar_path).await else {
+            return;
+        };
+        let Some(sidecar) = serde_json::from_str::<SpriteSheetSidecar>(&contents).log_err() else {
+            return;
+        };
+        this.update(&mut cx, |this, cx| {
+            let Some(sprite_sheet) = this.sprite_sheet.as_mut() else {
+                return;
+            };
+            for (editor, value) in [
+                (&sprite_sheet.frame_width_editor, sidecar.grid.frame_width),
+                (&sprite_sheet.frame_height_editor, sidecar.grid.frame_height),
+                (&sprite_sheet.fps_editor, sidecar.fps.unwrap_or(DEFAULT_FPS)),
+            ] {
+                editor.update(cx, |editor, cx| editor.set_text(value.to_string(), cx));
+            }
+            // Editing the frame size keeps the margin and spacing of the grid set here.
+            sprite_sheet.grid = Some(sidecar.grid);
+            sprite_sheet.sidecar_path = Some(sidecar_path);
+            sprite_sheet.current_frame = 0;
+            cx.notify();
+        })
+        .ok();
+    }));
+}
+
+fn grid_edited(image_view: &mut ImageView, cx: &mut ViewContext<ImageView>) {
+    let Some(sprite_sheet) = image_view.sprite_sheet.as_mut() else {
+        return;
+    };
+    let parse = |editor: &View<Editor>| editor.read(cx).text(cx).trim().parse::<u32>().ok();
+    let frame_width = parse(&sprite_sheet.frame_width_editor);
+    let frame_height = parse(&sprite_sheet.frame_height_editor);
+    let grid = match (frame_width, frame_height) {
+        (Some(frame_width), Some(frame_height)) if frame_width > 0 && frame_height > 0 => {
+            let (margin, spacing) = sprite_sheet
+                .grid
+                .map_or((0, 0), |grid| (grid.margin, grid.spacing));
+            Some(SpriteGrid {
+                frame_width,
+                frame_height,
+                margin,
+                spacing,
+            })
+        }
+        _ => None,
+    };
+    if grid != sprite_sheet.grid {
+        sprite_sheet.grid = grid;
+        sprite_sheet.sidecar_path = None;
+        sprite_sheet.current_frame = 0;
+    }
+    if sprite_sheet.is_playing() {
+        // Pick up a changed frame rate.
+        play(image_view, cx);
+    }
+    cx.notify();
+}
+
+fn frame_count(image_view: &ImageView, cx: &AppContext) -> usize {
+    let grid = image_view
+        .sprite_sheet
+        .as_ref()
+        .and_then(|sheet| sheet.grid);
+    match (grid, image_view.image_item.read(cx).edited_dimensions()) {
+        (Some(grid), Some(dimensions)) => grid.frames(dimensions).len(),
+        _ => 0,
+    }
+}
+
+fn step_frame(image_view: &mut ImageView, offset: isize, cx: &mut ViewContext<ImageView>) {
+    let frame_count = frame_count(image_view, cx);
+    let Some(sprite_sheet) = image_view.sprite_sheet.as_mut() else {
+        return;
+    };
+    if frame_count > 0 {
+        sprite_sheet.current_frame = (sprite_sheet.current_frame as isize + offset)
+            .rem_euclid(frame_count as isize) as usize;
+        cx.notify();
+    }
+}
+
+fn play(image_view: &mut ImageView, cx: &mut ViewContext<ImageView>) {
+    let Some(sprite_sheet) = image_view.sprite_sheet.as_mut() else {
+        return;
+    };
+    let interval = Duration::from_secs(1) / sprite_sheet.fps(cx);
+    sprite_sheet.play_task = Some(cx.spawn(|this, mut cx| async move {
+        loop {
+            cx.background_executor().timer(interval).await;
+            if this
+                .update(&mut cx, |this, cx| step_frame(this, 1, cx))
+                .is_err()
+            {
+                break;
+            }
+        }
+    }));
+}
+
+fn toggle_playing(image_view: &mut ImageView, cx: &mut ViewContext<ImageView>) {
+    let Some(sprite_sheet) = image_view.sprite_sheet.as_mut() else {
+        return;
+    };
+    if sprite_sheet.play_task.take().is_none() {
+        play(image_view, cx);
+    }
+    cx.notify();
+}
+
+/// A bar below the image for entering the size of the frames and playing them back.
+pub(crate) fn render_sprite_sheet_bar(
+    image_view: &ImageView,
+    image: Arc<Image>,
+    cx: &mut ViewContext<ImageView>,
+) -> Option<impl IntoElement> {
+    let sprite_sheet = image_view.sprite_sheet.as_ref()?;
+    let dimensions = image_view.image_item.read(cx).edited_dimensions();
+    let frames = match (sprite_sheet.grid, dimensions) {
+        (Some(grid), Some(dimensions)) => grid.frames(dimensions),
+        _ => Vec::new(),
+    };
+    let current_frame = frames.get(sprite_sheet.current_frame).copied();
+    let playing = sprite_sheet.is_playing();
+    let units = image_view.settings(cx).units();
+    let frame_label = if frames.is_empty() {
+        "No frames".to_string()
+    } else {
+        format!(
+            "Frame {} of {}",
+            units.count(sprite_sheet.current_frame + 1),
+            units.count(frames.len())
+        )
+    };
+    let editor_field = |editor: &View<Editor>, cx: &ViewContext<ImageView>| {
+        div()
+            .w(rems(4.))
+            .px_1()
+            .rounded_sm()
+            .border_1()
+            .border_color(cx.theme().colors().border)
+            .bg(cx.theme().colors().editor_background)
+            .child(editor.clone())
+    };
+
+    Some(
+        h_flex()
+            .w_full()
+            .p_2()
+            .gap_3()
+            .border_t_1()
+            .border_color(cx.theme().colors().border)
+            .bg(cx.theme().colors().panel_background)
+            .children(
+                current_frame
+                    .zip(dimensions)
+                    .map(|(frame, dimensions)| render_frame_preview(image, frame, dimensions)),
+            )
+            .child(
+                v_flex()
+                    .gap_1()
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .child(Label::new("Frame size").size(LabelSize::Small))
+                            .child(editor_field(&sprite_sheet.frame_width_editor, cx))
+                            .child(Label::new("×").size(LabelSize::Small))
+                            .child(editor_field(&sprite_sheet.frame_height_editor, cx))
+                            .child(Label::new("px").size(LabelSize::Small))
+                            .child(editor_field(&sprite_sheet.fps_editor, cx))
+                            .child(Label::new("fps").size(LabelSize::Small)),
+                    )
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .child(
+                                IconButton::new("sprite-previous-frame", IconName::ChevronLeft)
+                                    .disabled(frames.is_empty())
+                                    .tooltip(|cx| Tooltip::text("Previous Frame", cx))
+                                    .on_click(cx.listener(|this, _, cx| step_frame(this, -1, cx))),
+                            )
+                            .child(
+                                IconButton::new(
+                                    "sprite-play",
+                                    if playing {
+                                        IconName::Stop
+                                    } else {
+                                        IconName::Play
+                                    },
+                                )
+                                .disabled(frames.is_empty())
+                                .tooltip(move |cx| {
+                                    Tooltip::text(if playing { "Stop" } else { "Play Frames" }, cx)
+                                })
+                                .on_click(cx.listener(|this, _, cx| toggle_playing(this, cx))),
+                            )
+                            .child(
+                                IconButton::new("sprite-next-frame", IconName::ChevronRight)
+                                    .disabled(frames.is_empty())
+                                    .tooltip(|cx| Tooltip::text("Next Frame", cx))
+                                    .on_click(cx.listener(|this, _, cx| step_frame(this, 1, cx))),
+                            )
+                            .child(
+                                Label::new(frame_label)
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                            .when_some(sprite_sheet.sidecar_path.as_ref(), |row, path| {
+                                let file_name = path
+                                    .file_name()
+                                    .map(|name| name.to_string_lossy().to_string())
+                                    .unwrap_or_default();
+                                row.child(
+                                    Label::new(format!("· From {file_name}"))
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted),
+                                )
+                            }),
+                    ),
+            )
+            .child(div().flex_1())
+            .child(
+                IconButton::new("sprite-sheet-close", IconName::Close)
+                    .tooltip(|cx| Tooltip::for_action("Close Sprite Sheet", &ToggleSpriteSheet, cx))
+                    .on_click(
+                        cx.listener(|this, _, cx| {
+                            toggle_sprite_sheet(this, &ToggleSpriteSheet, cx)
+                        }),
+                    ),
+            ),
+    )
+}
+
+/// The current frame on its own, by clipping the sheet to it.
+fn render_frame_preview(
+    image: Arc<Image>,
+    frame: Bounds<u32>,
+    (width, height): (u32, u32),
+) -> impl IntoElement {
+    let mut scale = (FRAME_PREVIEW_SIZE / frame.size.width as f32)
+        .min(FRAME_PREVIEW_SIZE / frame.size.height as f32);
+    // Keep the pixels of pixel art the same size.
+    if scale > 1. {
+        scale = scale.floor();
+    }
+    div()
+        .relative()
+        .flex_none()
+        .overflow_hidden()
+        .w(px(frame.size.width as f32 * scale))
+        .h(px(frame.size.height as f32 * scale))
+        .child(
+            img(image)
+                .absolute()
+                .left(px(-(frame.origin.x as f32) * scale))
+                .top(px(-(frame.origin.y as f32) * scale))
+                .w(px(width as f32 * scale))
+                .h(px(height as f32 * scale)),
+        )
+}
+
+/// The grid of a sprite sheet over the image drawn at `image_bounds`, with its frames numbered
+/// and the current one highlighted.
+pub(crate) fn paint_sprite_grid(
+    image_bounds: Bounds<Pixels>,
+    dimensions: (u32, u32),
+    visible_bounds: Bounds<Pixels>,
+    grid: SpriteGrid,
+    current_frame: usize,
+    (color, current_color): (Hsla, Hsla),
+    cx: &mut WindowContext,
+) {
+    for (ix, frame) in grid.frames(dimensions).into_iter().enumerate() {
+        let bounds = image_region_bounds(image_bounds, dimensions, frame);
+        if !bounds.intersects(&visible_bounds) {
+            continue;
+        }
+        let is_current = ix == current_frame;
+        cx.paint_quad(outline(
+            bounds,
+            if is_current { current_color } else { color },
+        ));
+        if bounds.size.width.0 >= MIN_NUMBERED_FRAME_WIDTH {
+            paint_label(
+                (ix + 1).to_string().into(),
+                bounds.origin + point(px(1.), px(1.)),
+                if is_current {
+                    current_color
+                } else {
+                    gpui::black().opacity(0.6)
+                },
+                cx,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sprite_grid_frames() {
+        let grid = SpriteGrid {
+            frame_width: 16,
+            frame_height: 16,
+            margin: 0,
+            spacing: 0,
+        };
+        let frames = grid.frames((64, 40));
+        // Four columns, and two full rows with a partial one left out.
+        assert_eq!(frames.len(), 8);
+        assert_eq!(frames[5].origin, point(16, 16));
+
+        let grid = SpriteGrid {
+            frame_width: 16,
+            frame_height: 16,
+            margin: 1,
+            spacing: 2,
+        };
+        let frames = grid.frames((1 + 16 + 2 + 16 + 1, 18));
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[1].origin, point(19, 1));
+
+        assert!(grid.frames((10, 10)).is_empty());
+    }
+
+    #[test]
+    fn test_sprite_sheet_sidecar() {
+        let sidecar: SpriteSheetSidecar =
+            serde_json::from_str(r#"{ "frame_width": 32, "frame_height": 24, "fps": 8 }"#).unwrap();
+        assert_eq!(
+            sidecar.grid,
+            SpriteGrid {
+                frame_width: 32,
+                frame_height: 24,
+                margin: 0,
+                spacing: 0,
+            }
+        );
+        assert_eq!(sidecar.fps, Some(8));
+    }
+}
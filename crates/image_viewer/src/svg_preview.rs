@@ -0,0 +1,213 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Result;
+use editor::{Editor, EditorEvent};
+use gpui::{
+    img, AppContext, EventEmitter, FocusHandle, FocusableView, Image, ImageFormat, ObjectFit,
+    Subscription, Task, View, ViewContext,
+};
+use settings::Settings;
+use ui::prelude::*;
+use workspace::{item::Item, Pane, SplitDirection, Workspace};
+
+use crate::{image_background, ImageViewerSettings, OpenSvgPreviewToTheSide};
+
+const RERENDER_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Shows the unsaved contents of an SVG editor as an image, updated as it is edited.
+pub struct SvgPreviewView {
+    editor: View<Editor>,
+    focus_handle: FocusHandle,
+    /// The last contents of the editor that parsed successfully.
+    image: Option<Arc<Image>>,
+    /// Why the current contents of the editor can't be rendered.
+    error: Option<SharedString>,
+    tab_description: Option<SharedString>,
+    render_task: Option<Task<Result<()>>>,
+    _subscription: Subscription,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SvgPreviewEvent {}
+
+impl EventEmitter<SvgPreviewEvent> for SvgPreviewView {}
+
+impl SvgPreviewView {
+    pub fn register(workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>) {
+        workspace.register_action(|workspace, _: &OpenSvgPreviewToTheSide, cx| {
+            let Some(editor) = workspace
+                .active_item(cx)
+                .and_then(|item| item.act_as::<Editor>(cx))
+                .filter(|editor| is_svg_editor(editor, cx))
+            else {
+                return;
+            };
+            let pane = workspace
+                .find_pane_in_direction(SplitDirection::Right, cx)
+                .unwrap_or_else(|| {
+                    workspace.split_pane(workspace.active_pane().clone(), SplitDirection::Right, cx)
+                });
+            pane.update(cx, |pane, cx| {
+                if let Some(existing_view_ix) = find_existing_preview_item_ix(pane, &editor, cx) {
+                    pane.activate_item(existing_view_ix, true, true, cx);
+                } else {
+                    let view = cx.new_view(|cx| SvgPreviewView::new(editor.clone(), cx));
+                    pane.add_item(Box::new(view), false, false, None, cx)
+                }
+            });
+            editor.focus_handle(cx).focus(cx);
+            cx.notify();
+        });
+    }
+
+    fn new(editor: View<Editor>, cx: &mut ViewContext<Self>) -> Self {
+        let subscription = cx.subscribe(&editor, |this, _, event: &EditorEvent, cx| {
+            if let EditorEvent::Edited { .. } | EditorEvent::Reloaded = event {
+                this.render_editor_contents(true, cx);
+            }
+        });
+        let tab_description = editor
+            .read(cx)
+            .tab_description(0, cx)
+            .map(|description| format!("Preview {description}").into());
+        let mut this = Self {
+            editor,
+            focus_handle: cx.focus_handle(),
+            image: None,
+            error: None,
+            tab_description,
+            render_task: None,
+            _subscription: subscription,
+        };
+        this.render_editor_contents(false, cx);
+        this
+    }
+
+    fn render_editor_contents(&mut self, wait_for_debounce: bool, cx: &mut ViewContext<Self>) {
+        let contents = self.editor.read(cx).buffer().read(cx).snapshot(cx).text();
+        self.render_task = Some(cx.spawn(|this, mut cx| async move {
+            if wait_for_debounce {
+                cx.background_executor().timer(RERENDER_DEBOUNCE).await;
+            }
+            let result = cx
+                .background_executor()
+                .spawn(async move {
+                    let bytes = contents.into_bytes();
+                    usvg::Tree::from_data(&bytes, &usvg::Options::default())?;
+                    anyhow::Ok(Image {
+                        format: ImageFormat::Svg,
+                        id: gpui::hash(&bytes),
+                        bytes,
+                    })
+                })
+                .await;
+            this.update(&mut cx, |this, cx| {
+                match result {
+                    Ok(image) => {
+                        this.image = Some(Arc::new(image));
+                        this.error = None;
+                    }
+                    Err(error) => this.error = Some(error.to_string().into()),
+                }
+                cx.notify();
+            })
+        }));
+    }
+}
+
+fn is_svg_editor(editor: &View<Editor>, cx: &AppContext) -> bool {
+    editor
+        .read(cx)
+        .buffer()
+        .read(cx)
+        .as_singleton()
+        .and_then(|buffer| buffer.read(cx).file().cloned())
+        .and_then(|file| {
+            file.path()
+                .extension()
+                .map(|extension| extension.eq_ignore_ascii_case("svg"))
+        })
+        .unwrap_or(false)
+}
+
+fn find_existing_preview_item_ix(
+    pane: &Pane,
+    editor: &View<Editor>,
+    cx: &AppContext,
+) -> Option<usize> {
+    pane.items_of_type::<SvgPreviewView>()
+        .find(|preview| &preview.read(cx).editor == editor)
+        .and_then(|preview| pane.index_for_item(&preview))
+}
+
+impl FocusableView for SvgPreviewView {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for SvgPreviewView {
+    type Event = SvgPreviewEvent;
+
+    fn tab_icon(&self, _cx: &WindowContext) -> Option<Icon> {
+        Some(Icon::new(IconName::Eye))
+    }
+
+    fn tab_content_text(&self, _cx: &WindowContext) -> Option<SharedString> {
+        Some(
+            self.tab_description
+                .clone()
+                .unwrap_or_else(|| "SVG Preview".into()),
+        )
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("svg preview")
+    }
+
+    fn to_item_events(_event: &Self::Event, _f: impl FnMut(workspace::item::ItemEvent)) {}
+}
+
+impl Render for SvgPreviewView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("SvgPreview")
+            .track_focus(&self.focus_handle(cx))
+            .size_full()
+            .child(
+                div()
+                    .relative()
+                    .flex_1()
+                    .w_full()
+                    .child(image_background(
+                        ImageViewerSettings::get_global(cx).background,
+                        cx,
+                    ))
+                    .child(
+                        div()
+                            .flex()
+                            .justify_center()
+                            .items_center()
+                            .size_full()
+                            .children(self.image.clone().map(|image| {
+                                img(image)
+                                    .object_fit(ObjectFit::ScaleDown)
+                                    .max_w_full()
+                                    .max_h_full()
+                                    .id("svg-preview")
+                            })),
+                    ),
+            )
+            .children(self.error.clone().map(|error| {
+                h_flex()
+                    .gap_2()
+                    .px_2()
+                    .py_1()
+                    .border_t_1()
+                    .border_color(cx.theme().colors().border)
+                    .bg(cx.theme().status().error_background)
+                    .child(Icon::new(IconName::XCircle).color(Color::Error))
+                    .child(Label::new(error).size(LabelSize::Small))
+            }))
+    }
+}
@@ -0,0 +1,484 @@
+//! Texture atlases, images packing many smaller ones whose names and bounds are described by a
+//! file next to them: the JSON written by TexturePacker and similar tools, or a libGDX or Spine
+//! `.atlas` file. The regions are outlined over the image, named when hovered, and zoomed into
+//! when clicked.
+
+use std::path::Path;
+
+use anyhow::{Context as _, Result};
+use gpui::{outline, point, px, size, Bounds, ClickEvent, Hsla, Pixels, SharedString, ViewContext};
+use serde::Deserialize;
+use ui::prelude::*;
+use util::ResultExt;
+
+use crate::{image_region_bounds, paint_label, ImageView, ImageZoomMode, MAX_SCALE, MIN_SCALE};
+
+/// How much of the view a region fills once it is zoomed into, leaving room to see around it.
+const REGION_ZOOM_FILL: f32 = 0.8;
+/// How far the pointer can move between pressing and releasing for it to count as a click.
+const MAX_CLICK_DISTANCE: f32 = 4.;
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct AtlasRegion {
+    pub name: SharedString,
+    /// Where the region is in the image, in pixels.
+    pub bounds: Bounds<u32>,
+}
+
+pub(crate) struct TextureAtlas {
+    pub regions: Vec<AtlasRegion>,
+    pub hovered_region: Option<usize>,
+}
+
+/// Reads the regions of the displayed image from an atlas file next to it, if there is one.
+pub(crate) fn load_texture_atlas(image_view: &mut ImageView, cx: &mut ViewContext<ImageView>) {
+    let Some(abs_path) = image_view.abs_path(cx) else {
+        image_view.texture_atlas = None;
+        image_view.texture_atlas_task = None;
+        return;
+    };
+    let fs = image_view.project.read(cx).fs().clone();
+    image_view.texture_atlas_task = Some(cx.spawn(|this, mut cx| async move {
+        let mut texture_atlas = None;
+        for atlas_path in [
+            abs_path.with_extension("json"),
+            abs_path.with_extension("atlas"),
+        ] {
+            let Ok(contents) = fs.load(&atlas_path).await else {
+                continue;
+            };
+            let regions = parse_atlas(&atlas_path, &contents, &abs_path).log_err();
+            if let Some(regions) = regions.filter(|regions| !regions.is_empty()) {
+                texture_atlas = Some(TextureAtlas {
+                    regions,
+                    hovered_region: None,
+                });
+                break;
+            }
+        }
+        this.update(&mut cx, |this, cx| {
+            this.texture_atlas = texture_atlas;
+            cx.notify();
+        })
+        .ok();
+    }));
+}
+
+/// The regions described by the atlas file at `atlas_path` that are in the image at
+/// `image_path`.
+fn parse_atlas(atlas_path: &Path, contents: &str, image_path: &Path) -> Result<Vec<AtlasRegion>> {
+    let image_file_name = image_path
+        .file_name()
+        .context("image has no file name")?
+        .to_string_lossy();
+    if atlas_path
+        .extension()
+        .is_some_and(|extension| extension == "atlas")
+    {
+        Ok(parse_libgdx_atlas(contents, &image_file_name))
+    } else {
+        parse_texture_packer_json(contents, &image_file_name)
+    }
+}
+
+#[derive(Deserialize)]
+struct TexturePackerAtlas {
+    frames: TexturePackerFrames,
+    #[serde(default)]
+    meta: Option<TexturePackerMeta>,
+}
+
+/// TexturePacker writes frames either as an object keyed by their names, or as an array.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TexturePackerFrames {
+    Hash(serde_json::Map<String, serde_json::Value>),
+    Array(Vec<TexturePackerNamedFrame>),
+}
+
+#[derive(Deserialize)]
+struct TexturePackerNamedFrame {
+    filename: String,
+    #[serde(flatten)]
+    frame: TexturePackerFrame,
+}
+
+#[derive(Deserialize)]
+struct TexturePackerFrame {
+    frame: TexturePackerRect,
+    /// Whether the frame is stored turned a quarter turn, swapping its width and height.
+    #[serde(default)]
+    rotated: bool,
+}
+
+#[derive(Deserialize)]
+struct TexturePackerRect {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+#[derive(Deserialize)]
+struct TexturePackerMeta {
+    image: Option<String>,
+}
+
+fn parse_texture_packer_json(contents: &str, image_file_name: &str) -> Result<Vec<AtlasRegion>> {
+    let atlas: TexturePackerAtlas = serde_json::from_str(contents)?;
+    // The JSON may describe another image with the same name in a different format.
+    if let Some(image) = atlas.meta.and_then(|meta| meta.image) {
+        let described_file_name = Path::new(&image)
+            .file_name()
+            .map(|name| name.to_string_lossy());
+        if described_file_name.is_some_and(|name| name != image_file_name) {
+            return Ok(Vec::new());
+        }
+    }
+    let frames = match atlas.frames {
+        TexturePackerFrames::Hash(frames) => frames
+            .into_iter()
+            .map(|(name, frame)| Ok((name, serde_json::from_value(frame)?)))
+            .collect::<Result<Vec<_>>>()?,
+        TexturePackerFrames::Array(frames) => frames
+            .into_iter()
+            .map(|frame| (frame.filename, frame.frame))
+            .collect(),
+    };
+    Ok(frames
+        .into_iter()
+        .map(|(name, frame): (String, TexturePackerFrame)| {
+            let rect = frame.frame;
+            let (width, height) = if frame.rotated {
+                (rect.h, rect.w)
+            } else {
+                (rect.w, rect.h)
+            };
+            AtlasRegion {
+                name: name.into(),
+                bounds: Bounds {
+                    origin: point(rect.x, rect.y),
+                    size: size(width, height),
+                },
+            }
+        })
+        .collect())
+}
+
+/// Parses the regions on the page of a libGDX or Spine atlas that is the image, in both the
+/// format with indented `xy` and `size` properties and the newer one with `bounds`.
+fn parse_libgdx_atlas(contents: &str, image_file_name: &str) -> Vec<AtlasRegion> {
+    struct PendingRegion {
+        name: String,
+        origin: Option<(u32, u32)>,
+        size: Option<(u32, u32)>,
+        rotated: bool,
+    }
+
+    fn parse_pair(value: &str) -> Option<(u32, u32)> {
+        let mut numbers = value.split(',').map(|number| number.trim().parse().ok());
+        Some((numbers.next()??, numbers.next()??))
+    }
+
+    fn flush(region: Option<PendingRegion>, regions: &mut Vec<AtlasRegion>) {
+        let Some(region) = region else {
+            return;
+        };
+        if let (Some(origin), Some((width, height))) = (region.origin, region.size) {
+            let (width, height) = if region.rotated {
+                (height, width)
+            } else {
+                (width, height)
+            };
+            regions.push(AtlasRegion {
+                name: region.name.into(),
+                bounds: Bounds {
+                    origin: point(origin.0, origin.1),
+                    size: size(width, height),
+                },
+            });
+        }
+    }
+
+    let mut regions = Vec::new();
+    // Pages are separated by blank lines and start with the name of their image.
+    let mut expecting_page = true;
+    let mut in_image_page = false;
+    let mut region = None;
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            flush(region.take(), &mut regions);
+            expecting_page = true;
+            continue;
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            // Properties before the first region belong to the page.
+            let Some(region) = region.as_mut() else {
+                continue;
+            };
+            match key.trim() {
+                "xy" => region.origin = parse_pair(value),
+                "size" => region.size = parse_pair(value),
+                "bounds" => {
+                    // The origin followed by the size, such as "4,8,16,16".
+                    region.origin = parse_pair(value);
+                    region.size = value.splitn(3, ',').nth(2).and_then(parse_pair);
+                }
+                "rotate" => region.rotated = matches!(value.trim(), "true" | "90"),
+                _ => {}
+            }
+            continue;
+        }
+        flush(region.take(), &mut regions);
+        if expecting_page {
+            expecting_page = false;
+            in_image_page = line == image_file_name;
+        } else if in_image_page {
+            region = Some(PendingRegion {
+                name: line.to_string(),
+                origin: None,
+                size: None,
+                rotated: false,
+            });
+        }
+    }
+    flush(region.take(), &mut regions);
+    regions
+}
+
+/// The smallest region containing the given pixel of the image, so that regions nested in
+/// others can be picked.
+fn region_at(regions: &[AtlasRegion], position: gpui::Point<f32>) -> Option<usize> {
+    regions
+        .iter()
+        .enumerate()
+        .filter(|(_, region)| {
+            let bounds = region.bounds;
+            position.x >= bounds.origin.x as f32
+                && position.y >= bounds.origin.y as f32
+                && position.x < (bounds.origin.x + bounds.size.width) as f32
+                && position.y < (bounds.origin.y + bounds.size.height) as f32
+        })
+        .min_by_key(|(_, region)| region.bounds.size.width * region.bounds.size.height)
+        .map(|(ix, _)| ix)
+}
+
+pub(crate) fn update_hovered_region(
+    image_view: &mut ImageView,
+    position: gpui::Point<Pixels>,
+    cx: &mut ViewContext<ImageView>,
+) {
+    let pixel = image_view
+        .image_position(position, cx)
+        .zip(image_view.image_item.read(cx).edited_dimensions())
+        .map(|(position, (width, height))| {
+            point(position.x * width as f32, position.y * height as f32)
+        });
+    let Some(texture_atlas) = image_view.texture_atlas.as_mut() else {
+        return;
+    };
+    let hovered_region = pixel.and_then(|pixel| region_at(&texture_atlas.regions, pixel));
+    if texture_atlas.hovered_region != hovered_region {
+        texture_atlas.hovered_region = hovered_region;
+        cx.notify();
+    }
+}
+
+/// Zooms into the region under the pointer when it is clicked without dragging the image.
+pub(crate) fn zoom_to_clicked_region(
+    image_view: &mut ImageView,
+    event: &ClickEvent,
+    cx: &mut ViewContext<ImageView>,
+) {
+    if image_view.annotating
+        || event.up.click_count != 1
+        || (event.up.position - event.down.position).magnitude() > MAX_CLICK_DISTANCE as f64
+    {
+        return;
+    }
+    let Some(region) = image_view
+        .texture_atlas
+        .as_ref()
+        .and_then(|atlas| Some(atlas.regions.get(atlas.hovered_region?)?.bounds))
+    else {
+        return;
+    };
+    let (Some(area_bounds), Some(dimensions)) = (
+        image_view.image_area_bounds.get(),
+        image_view.image_item.read(cx).edited_dimensions(),
+    ) else {
+        return;
+    };
+    let (scale, pan_offset) = region_zoom(area_bounds.size, dimensions, region);
+    image_view.set_zoom(ImageZoomMode::Scale(scale), cx);
+    image_view.pan_offset = pan_offset;
+    image_view.view_state_changed(cx);
+    cx.notify();
+}
+
+/// The scale and pan offset that center `region` of an image of the given dimensions in a view
+/// of `area_size`, filling most of it.
+fn region_zoom(
+    area_size: gpui::Size<Pixels>,
+    (width, height): (u32, u32),
+    region: Bounds<u32>,
+) -> (f32, gpui::Point<Pixels>) {
+    let scale = (area_size.width.0 / region.size.width.max(1) as f32)
+        .min(area_size.height.0 / region.size.height.max(1) as f32)
+        * REGION_ZOOM_FILL;
+    let scale = scale.clamp(MIN_SCALE, MAX_SCALE);
+    let region_center = (
+        region.origin.x as f32 + region.size.width as f32 / 2.,
+        region.origin.y as f32 + region.size.height as f32 / 2.,
+    );
+    // The image is centered in the view before it is panned.
+    let pan_offset = point(
+        px((width as f32 / 2. - region_center.0) * scale),
+        px((height as f32 / 2. - region_center.1) * scale),
+    );
+    (scale, pan_offset)
+}
+
+/// Outlines the regions of an atlas over the image drawn at `image_bounds`, naming the hovered
+/// one.
+pub(crate) fn paint_atlas_regions(
+    image_bounds: Bounds<Pixels>,
+    dimensions: (u32, u32),
+    visible_bounds: Bounds<Pixels>,
+    regions: &[AtlasRegion],
+    hovered_region: Option<usize>,
+    (color, hovered_color): (Hsla, Hsla),
+    cx: &mut WindowContext,
+) {
+    for (ix, region) in regions.iter().enumerate() {
+        let bounds = image_region_bounds(image_bounds, dimensions, region.bounds);
+        if bounds.intersects(&visible_bounds) && Some(ix) != hovered_region {
+            cx.paint_quad(outline(bounds, color));
+        }
+    }
+    // Paint the hovered region last so that its outline and label are on top.
+    let Some(region) = hovered_region.and_then(|ix| regions.get(ix)) else {
+        return;
+    };
+    let bounds = image_region_bounds(image_bounds, dimensions, region.bounds);
+    cx.paint_quad(outline(bounds, hovered_color));
+    let label = format!(
+        "{} ({} × {})",
+        region.name, region.bounds.size.width, region.bounds.size.height
+    );
+    // Put the label above the region, or inside it at the top edge of the view.
+    let origin = point(
+        bounds.origin.x,
+        (bounds.origin.y - px(14.)).max(visible_bounds.origin.y),
+    );
+    paint_label(label.into(), origin, hovered_color, cx);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn region(name: &str, x: u32, y: u32, width: u32, height: u32) -> AtlasRegion {
+        AtlasRegion {
+            name: name.to_string().into(),
+            bounds: Bounds {
+                origin: point(x, y),
+                size: size(width, height),
+            },
+        }
+    }
+
+    #[test]
+    fn test_parse_texture_packer_json() {
+        let hash = r#"{
+            "frames": {
+                "hero/idle.png": { "frame": { "x": 0, "y": 0, "w": 32, "h": 48 }, "rotated": false },
+                "hero/jump.png": { "frame": { "x": 32, "y": 0, "w": 32, "h": 48 }, "rotated": true }
+            },
+            "meta": { "image": "sheet.png" }
+        }"#;
+        assert_eq!(
+            parse_texture_packer_json(hash, "sheet.png").unwrap(),
+            vec![
+                region("hero/idle.png", 0, 0, 32, 48),
+                region("hero/jump.png", 32, 0, 48, 32),
+            ]
+        );
+        // Atlases for another image are ignored.
+        assert!(parse_texture_packer_json(hash, "sheet.webp")
+            .unwrap()
+            .is_empty());
+
+        let array = r#"{
+            "frames": [
+                { "filename": "coin", "frame": { "x": 4, "y": 8, "w": 16, "h": 16 } }
+            ]
+        }"#;
+        assert_eq!(
+            parse_texture_packer_json(array, "sheet.png").unwrap(),
+            vec![region("coin", 4, 8, 16, 16)]
+        );
+    }
+
+    #[test]
+    fn test_parse_libgdx_atlas() {
+        let legacy = "
+sheet.png
+size: 256, 256
+format: RGBA8888
+filter: Linear,Linear
+repeat: none
+idle
+  rotate: false
+  xy: 2, 2
+  size: 32, 48
+  orig: 32, 48
+  offset: 0, 0
+  index: -1
+jump
+  rotate: true
+  xy: 36, 2
+  size: 32, 48
+
+other.png
+size: 64, 64
+coin
+  xy: 0, 0
+  size: 16, 16
+";
+        assert_eq!(
+            parse_libgdx_atlas(legacy, "sheet.png"),
+            vec![region("idle", 2, 2, 32, 48), region("jump", 36, 2, 48, 32)]
+        );
+
+        let current = "sheet.png\nsize:256,256\nfilter:Linear,Linear\ncoin\nbounds:4,8,16,16\n";
+        assert_eq!(
+            parse_libgdx_atlas(current, "sheet.png"),
+            vec![region("coin", 4, 8, 16, 16)]
+        );
+    }
+
+    #[test]
+    fn test_region_at() {
+        let regions = vec![region("panel", 0, 0, 64, 64), region("icon", 8, 8, 16, 16)];
+        assert_eq!(region_at(&regions, point(10., 10.)), Some(1));
+        assert_eq!(region_at(&regions, point(40., 40.)), Some(0));
+        assert_eq!(region_at(&regions, point(80., 10.)), None);
+    }
+
+    #[test]
+    fn test_region_zoom() {
+        let (scale, pan_offset) = region_zoom(
+            size(px(400.), px(400.)),
+            (200, 100),
+            Bounds {
+                origin: point(0, 0),
+                size: size(20, 10),
+            },
+        );
+        assert_eq!(scale, 16.);
+        // The region's center is 90 by 45 pixels up and to the left of the image's center.
+        assert_eq!(pan_offset, point(px(1440.), px(720.)));
+    }
+}
@@ -0,0 +1,118 @@
+use std::io::Cursor;
+
+use anyhow::{bail, Result};
+use gpui::{Image, ImageFormat};
+use image::{Rgba, RgbaImage};
+use tiff::{
+    decoder::{ChunkType, Decoder, DecodingResult},
+    ColorType,
+};
+
+/// Decodes a reduced copy of a TIFF image with at most `max_pixels` pixels.
+///
+/// The image is read one strip or tile at a time and only the pixels that make it into the
+/// overview are kept, so images far larger than memory allows to decode at once can still be
+/// looked at.
+pub(crate) fn decode_overview(bytes: &[u8], max_pixels: u64) -> Result<Image> {
+    let mut decoder = Decoder::new(Cursor::new(bytes))?;
+    let (width, height) = decoder.dimensions()?;
+    let channels = match decoder.colortype()? {
+        ColorType::Gray(8 | 16) => 1,
+        ColorType::GrayA(8 | 16) => 2,
+        ColorType::RGB(8 | 16) => 3,
+        ColorType::RGBA(8 | 16) => 4,
+        color_type => bail!("Overviews of {color_type:?} images are not supported"),
+    };
+
+    let factor = overview_factor(width, height, max_pixels);
+    let mut overview = RgbaImage::new(width.div_ceil(factor), height.div_ceil(factor));
+
+    let (chunk_width, chunk_height) = decoder.chunk_dimensions();
+    let chunk_count = match decoder.get_chunk_type() {
+        ChunkType::Strip => decoder.strip_count()?,
+        ChunkType::Tile => decoder.tile_count()?,
+    };
+    let chunks_across = width.div_ceil(chunk_width);
+    for chunk_index in 0..chunk_count {
+        let samples = match decoder.read_chunk(chunk_index)? {
+            DecodingResult::U8(samples) => samples,
+            DecodingResult::U16(samples) => samples.into_iter().map(|s| (s >> 8) as u8).collect(),
+            _ => bail!("Overviews of floating point images are not supported"),
+        };
+        let (data_width, data_height) = decoder.chunk_data_dimensions(chunk_index);
+        let origin_x = (chunk_index % chunks_across) * chunk_width;
+        let origin_y = (chunk_index / chunks_across) * chunk_height;
+
+        // Only visit the rows and columns of the chunk that land on a pixel of the overview.
+        let first_row = (factor - origin_y % factor) % factor;
+        let first_column = (factor - origin_x % factor) % factor;
+        for row in (first_row..data_height).step_by(factor as usize) {
+            for column in (first_column..data_width).step_by(factor as usize) {
+                let offset = ((row * data_width + column) * channels) as usize;
+                let Some(pixel) = samples.get(offset..offset + channels as usize) else {
+                    continue;
+                };
+                let rgba = match *pixel {
+                    [gray] => [gray, gray, gray, 255],
+                    [gray, alpha] => [gray, gray, gray, alpha],
+                    [r, g, b] => [r, g, b, 255],
+                    [r, g, b, a] => [r, g, b, a],
+                    _ => unreachable!(),
+                };
+                overview.put_pixel(
+                    (origin_x + column) / factor,
+                    (origin_y + row) / factor,
+                    Rgba(rgba),
+                );
+            }
+        }
+    }
+
+    let mut bytes = Vec::new();
+    overview.write_to(&mut Cursor::new(&mut bytes), image::ImageFormat::Png)?;
+    Ok(Image {
+        format: ImageFormat::Png,
+        id: gpui::hash(&bytes),
+        bytes,
+    })
+}
+
+/// How many pixels of the image, along each axis, make up one pixel of its overview.
+fn overview_factor(width: u32, height: u32, max_pixels: u64) -> u32 {
+    let pixels = width as f64 * height as f64;
+    (pixels / max_pixels.max(1) as f64).sqrt().ceil().max(1.) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tiff::encoder::{colortype, TiffEncoder};
+
+    #[test]
+    fn test_decode_overview() {
+        let (width, height) = (64, 32);
+        let mut data = vec![0u8; (width * height * 3) as usize];
+        // A red pixel that lands on the second pixel of the overview's first row.
+        data[2 * 3] = 255;
+        let mut tiff = Vec::new();
+        TiffEncoder::new(Cursor::new(&mut tiff))
+            .unwrap()
+            .write_image::<colortype::RGB8>(width, height, &data)
+            .unwrap();
+
+        let overview = decode_overview(&tiff, 512).unwrap();
+        let overview = image::load_from_memory(&overview.bytes)
+            .unwrap()
+            .into_rgba8();
+        assert_eq!(overview.dimensions(), (32, 16));
+        assert_eq!(overview.get_pixel(1, 0), &Rgba([255, 0, 0, 255]));
+        assert_eq!(overview.get_pixel(0, 0), &Rgba([0, 0, 0, 255]));
+    }
+
+    #[test]
+    fn test_overview_factor() {
+        assert_eq!(overview_factor(100, 100, 1_000_000), 1);
+        assert_eq!(overview_factor(4000, 4000, 1_000_000), 4);
+        assert_eq!(overview_factor(4001, 4000, 1_000_000), 5);
+    }
+}
@@ -0,0 +1,99 @@
+//! Previewing images as tiles, repeated three times across and down with the seams between the
+//! copies marked, so that it can be checked that textures meant to repeat do so without visible
+//! edges.
+
+use std::sync::Arc;
+
+use gpui::{canvas, point, size, Bounds, ContentMask, Corners, Image};
+use ui::prelude::*;
+use util::ResultExt;
+
+use crate::{
+    image_annotations::displayed_image_bounds, image_gestures, ImageView, ToggleTilePreview,
+};
+
+/// How many times the image is repeated across and down.
+const TILES: u32 = 3;
+
+pub(crate) fn toggle_tile_preview(
+    image_view: &mut ImageView,
+    _: &ToggleTilePreview,
+    cx: &mut ViewContext<ImageView>,
+) {
+    image_view.tiling = !image_view.tiling;
+    cx.notify();
+}
+
+/// Draws the tiles in place of the image, at the view's zoom and pan.
+pub(crate) fn render_tile_preview(
+    image_view: &ImageView,
+    image: Arc<Image>,
+    cx: &mut ViewContext<ImageView>,
+) -> impl IntoElement {
+    let image_area_bounds = image_view.image_area_bounds.clone();
+    let dimensions = image_view.image_item.read(cx).edited_dimensions();
+    let zoom = image_view.zoom;
+    let pan_offset = image_view.pan_offset;
+    let seam_color = cx.theme().colors().text_accent.opacity(0.6);
+
+    canvas(
+        move |bounds, _| image_area_bounds.set(Some(bounds)),
+        move |bounds, _, cx| {
+            let Some((width, height)) = dimensions else {
+                return;
+            };
+            let Some(render_image) = image.use_render_image(cx) else {
+                return;
+            };
+            let tiled_bounds = image_gestures::panned(
+                displayed_image_bounds(bounds, (width * TILES, height * TILES), zoom),
+                pan_offset,
+            );
+            let tile_size = size(
+                tiled_bounds.size.width / TILES as f32,
+                tiled_bounds.size.height / TILES as f32,
+            );
+            cx.with_content_mask(Some(ContentMask { bounds }), |cx| {
+                for row in 0..TILES {
+                    for column in 0..TILES {
+                        let origin = tiled_bounds.origin
+                            + point(
+                                tile_size.width * column as f32,
+                                tile_size.height * row as f32,
+                            );
+                        cx.paint_image(
+                            Bounds::new(origin, tile_size),
+                            Corners::default(),
+                            render_image.clone(),
+                            0,
+                            false,
+                        )
+                        .log_err();
+                    }
+                }
+                for seam in 1..TILES {
+                    let x = tiled_bounds.origin.x + tile_size.width * seam as f32;
+                    cx.paint_quad(gpui::fill(
+                        Bounds::new(
+                            point(x, tiled_bounds.origin.y),
+                            size(px(1.), tiled_bounds.size.height),
+                        ),
+                        seam_color,
+                    ));
+                    let y = tiled_bounds.origin.y + tile_size.height * seam as f32;
+                    cx.paint_quad(gpui::fill(
+                        Bounds::new(
+                            point(tiled_bounds.origin.x, y),
+                            size(tiled_bounds.size.width, px(1.)),
+                        ),
+                        seam_color,
+                    ));
+                }
+            });
+        },
+    )
+    .size_full()
+    .absolute()
+    .top_0()
+    .left_0()
+}
@@ -1,5 +1,6 @@
 use gpui::{
-    px, FontStyle, FontWeight, HighlightStyle, SharedString, StrikethroughStyle, UnderlineStyle,
+    px, DefiniteLength, FontStyle, FontWeight, HighlightStyle, SharedString, StrikethroughStyle,
+    UnderlineStyle,
 };
 use language::HighlightId;
 use std::{fmt::Display, ops::Range, path::PathBuf};
@@ -298,6 +299,10 @@ pub enum Image {
         link: Option<Link>,
         /// alt text if it exists
         alt_text: Option<ParsedMarkdownText>,
+        /// The width requested by an HTML `width` attribute.
+        width: Option<DefiniteLength>,
+        /// The height requested by an HTML `height` attribute.
+        height: Option<DefiniteLength>,
     },
     ///  Image path on the filesystem.
     Path {
@@ -310,6 +315,10 @@ pub enum Image {
         link: Option<Link>,
         /// alt text if it exists
         alt_text: Option<ParsedMarkdownText>,
+        /// The width requested by an HTML `width` attribute.
+        width: Option<DefiniteLength>,
+        /// The height requested by an HTML `height` attribute.
+        height: Option<DefiniteLength>,
     },
 }
 
@@ -326,6 +335,8 @@ impl Image {
                 url: text,
                 link,
                 alt_text: None,
+                width: None,
+                height: None,
             });
         }
         let path = PathBuf::from(&text);
@@ -336,6 +347,8 @@ impl Image {
                 path,
                 link,
                 alt_text: None,
+                width: None,
+                height: None,
             });
         }
         if let Some(file_location_directory) = file_location_directory {
@@ -347,6 +360,8 @@ impl Image {
                 path,
                 link,
                 alt_text: None,
+                width: None,
+                height: None,
             });
         }
         None
@@ -358,18 +373,24 @@ impl Image {
                 ref source_range,
                 ref url,
                 ref link,
+                width,
+                height,
                 ..
             } => Image::Web {
                 source_range: source_range.clone(),
                 url: url.clone(),
                 link: link.clone(),
                 alt_text: Some(alt_text),
+                width: *width,
+                height: *height,
             },
             Image::Path {
                 ref source_range,
                 ref display_path,
                 ref path,
                 ref link,
+                width,
+                height,
                 ..
             } => Image::Path {
                 source_range: source_range.clone(),
@@ -377,9 +398,33 @@ impl Image {
                 path: path.clone(),
                 link: link.clone(),
                 alt_text: Some(alt_text),
+                width: *width,
+                height: *height,
             },
         }
     }
+
+    pub fn with_size(
+        mut self,
+        new_width: Option<DefiniteLength>,
+        new_height: Option<DefiniteLength>,
+    ) -> Self {
+        match &mut self {
+            Image::Web { width, height, .. } | Image::Path { width, height, .. } => {
+                *width = new_width;
+                *height = new_height;
+            }
+        }
+        self
+    }
+
+    pub fn size(&self) -> (Option<DefiniteLength>, Option<DefiniteLength>) {
+        match self {
+            Image::Web { width, height, .. } | Image::Path { width, height, .. } => {
+                (*width, *height)
+            }
+        }
+    }
 }
 
 impl Display for Image {
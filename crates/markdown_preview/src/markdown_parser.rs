@@ -1,10 +1,10 @@
 use crate::markdown_elements::*;
 use async_recursion::async_recursion;
 use collections::FxHashMap;
-use gpui::FontWeight;
+use gpui::{px, relative, DefiniteLength, FontWeight};
 use language::LanguageRegistry;
 use pulldown_cmark::{Alignment, Event, Options, Parser, Tag, TagEnd};
-use std::{ops::Range, path::PathBuf, sync::Arc, vec};
+use std::{mem, ops::Range, path::PathBuf, sync::Arc, vec};
 
 pub async fn parse_markdown(
     markdown_input: &str,
@@ -174,6 +174,15 @@ impl<'a> MarkdownParser<'a> {
                     let code_block = self.parse_code_block(language).await;
                     Some(vec![ParsedMarkdownElement::CodeBlock(code_block)])
                 }
+                Tag::HtmlBlock => {
+                    self.cursor += 1;
+                    let images = self.parse_html_block(source_range);
+                    if images.is_empty() {
+                        Some(vec![])
+                    } else {
+                        Some(vec![ParsedMarkdownElement::Paragraph(images)])
+                    }
+                }
                 _ => None,
             },
             Event::Rule => {
@@ -229,8 +238,26 @@ impl<'a> MarkdownParser<'a> {
                 }
 
                 // We want to ignore any inline HTML tags in the text but keep
-                // the text between them
-                Event::InlineHtml(_) => {}
+                // the text between them, except for images
+                Event::InlineHtml(html) => {
+                    for html_image in parse_html_images(html) {
+                        let Some(image) = self.html_image(html_image, source_range.clone()) else {
+                            continue;
+                        };
+                        if !text.is_empty() {
+                            markdown_text_like.push(MarkdownParagraphChunk::Text(
+                                ParsedMarkdownText {
+                                    source_range: source_range.clone(),
+                                    contents: mem::take(&mut text),
+                                    highlights: mem::take(&mut highlights),
+                                    region_ranges: mem::take(&mut region_ranges),
+                                    regions: mem::take(&mut regions),
+                                },
+                            ));
+                        }
+                        markdown_text_like.push(MarkdownParagraphChunk::Image(image));
+                    }
+                }
 
                 Event::Text(t) => {
                     text.push_str(t.as_ref());
@@ -443,6 +470,50 @@ impl<'a> MarkdownParser<'a> {
         markdown_text_like
     }
 
+    /// Parses the images out of a block of HTML, ignoring everything else.
+    fn parse_html_block(&mut self, source_range: Range<usize>) -> MarkdownParagraph {
+        let mut html = String::new();
+        while !self.eof() {
+            let (current, _) = self.current().unwrap();
+            match current {
+                Event::Html(text) => html.push_str(text),
+                Event::End(TagEnd::HtmlBlock) => {
+                    self.cursor += 1;
+                    break;
+                }
+                _ => {}
+            }
+            self.cursor += 1;
+        }
+
+        parse_html_images(&html)
+            .into_iter()
+            .filter_map(|html_image| self.html_image(html_image, source_range.clone()))
+            .map(MarkdownParagraphChunk::Image)
+            .collect()
+    }
+
+    fn html_image(&self, html_image: HtmlImage, source_range: Range<usize>) -> Option<Image> {
+        let image = Image::identify(
+            source_range.clone(),
+            self.file_location_directory.clone(),
+            html_image.src,
+            None,
+        )?;
+        let alt_text = ParsedMarkdownText {
+            source_range,
+            contents: html_image.alt.unwrap_or_else(|| "img".to_string()),
+            highlights: vec![],
+            region_ranges: vec![],
+            regions: vec![],
+        };
+        Some(
+            image
+                .with_alt_text(alt_text)
+                .with_size(html_image.width, html_image.height),
+        )
+    }
+
     fn parse_heading(&mut self, level: pulldown_cmark::HeadingLevel) -> ParsedMarkdownHeading {
         let (_event, source_range) = self.previous().unwrap();
         let source_range = source_range.clone();
@@ -773,6 +844,109 @@ impl<'a> MarkdownParser<'a> {
     }
 }
 
+/// An `<img>` tag found in HTML embedded in the document.
+#[derive(Debug, PartialEq)]
+struct HtmlImage {
+    src: String,
+    alt: Option<String>,
+    width: Option<DefiniteLength>,
+    height: Option<DefiniteLength>,
+}
+
+fn parse_html_images(html: &str) -> Vec<HtmlImage> {
+    let lowercase = html.to_ascii_lowercase();
+    let mut images = Vec::new();
+    let mut offset = 0;
+    while let Some(start) = lowercase[offset..].find("<img") {
+        let attributes_start = offset + start + "<img".len();
+        let (attributes, len) = parse_html_attributes(&html[attributes_start..]);
+        offset = attributes_start + len;
+
+        let mut src = None;
+        let mut image = HtmlImage {
+            src: String::new(),
+            alt: None,
+            width: None,
+            height: None,
+        };
+        for (name, value) in attributes {
+            match name.as_str() {
+                "src" => src = Some(value),
+                "alt" => image.alt = Some(value),
+                "width" => image.width = parse_html_length(&value),
+                "height" => image.height = parse_html_length(&value),
+                _ => {}
+            }
+        }
+        if let Some(src) = src {
+            image.src = src;
+            images.push(image);
+        }
+    }
+    images
+}
+
+/// Parses the attributes of a tag up to its closing `>`, returning them along with how many bytes
+/// they took up.
+fn parse_html_attributes(html: &str) -> (Vec<(String, String)>, usize) {
+    let mut attributes = Vec::new();
+    let mut chars = html.char_indices().peekable();
+    while let Some((ix, char)) = chars.next() {
+        if char == '>' {
+            return (attributes, ix + 1);
+        }
+        if char.is_whitespace() || char == '/' {
+            continue;
+        }
+
+        let mut name = char.to_ascii_lowercase().to_string();
+        while let Some((_, char)) =
+            chars.next_if(|(_, char)| !char.is_whitespace() && !matches!(char, '=' | '>' | '/'))
+        {
+            name.push(char.to_ascii_lowercase());
+        }
+        while chars.next_if(|(_, char)| char.is_whitespace()).is_some() {}
+        if chars.next_if(|(_, char)| *char == '=').is_none() {
+            attributes.push((name, String::new()));
+            continue;
+        }
+        while chars.next_if(|(_, char)| char.is_whitespace()).is_some() {}
+
+        let mut value = String::new();
+        match chars.peek().map(|(_, char)| *char) {
+            Some(quote @ ('"' | '\'')) => {
+                chars.next();
+                for (_, char) in chars.by_ref() {
+                    if char == quote {
+                        break;
+                    }
+                    value.push(char);
+                }
+            }
+            _ => {
+                while let Some((_, char)) =
+                    chars.next_if(|(_, char)| !char.is_whitespace() && *char != '>')
+                {
+                    value.push(char);
+                }
+            }
+        }
+        attributes.push((name, value));
+    }
+    (attributes, html.len())
+}
+
+/// Parses an HTML length such as `200`, `200px` or `50%`.
+fn parse_html_length(value: &str) -> Option<DefiniteLength> {
+    let value = value.trim();
+    if let Some(percentage) = value.strip_suffix('%') {
+        let percentage = percentage.trim().parse::<f32>().ok()?;
+        return Some(relative(percentage / 100.));
+    }
+    let pixels = value.strip_suffix("px").unwrap_or(value).trim();
+    Some(px(pixels.parse::<f32>().ok()?).into())
+}
+
 #[cfg(test)]
 mod tests {
     use core::panic;
@@ -950,10 +1124,60 @@ mod tests {
                       regions: vec![],
                  },
                   ),
+                width: None,
+                height: None,
             },)
         );
     }
 
+    #[gpui::test]
+    async fn test_html_image_detection() {
+        let parsed =
+            parse("Before <img src=\"https://zed.dev/logo.png\" width=\"120\" height=50%> after")
+                .await;
+
+        let paragraph = if let ParsedMarkdownElement::Paragraph(text) = &parsed.children[0] {
+            text
+        } else {
+            panic!("Expected a paragraph");
+        };
+        assert_eq!(paragraph.len(), 3);
+        let MarkdownParagraphChunk::Image(image) = &paragraph[1] else {
+            panic!("Expected an image");
+        };
+        assert_eq!(image.to_string(), "https://zed.dev/logo.png");
+        assert_eq!(image.size(), (Some(px(120.).into()), Some(relative(0.5))));
+
+        let parsed = parse(
+            "<p align=\"center\">\n  <img alt='Logo' src='https://zed.dev/logo.png'>\n</p>\n\nText",
+        )
+        .await;
+        let paragraph = if let ParsedMarkdownElement::Paragraph(text) = &parsed.children[0] {
+            text
+        } else {
+            panic!("Expected a paragraph");
+        };
+        let MarkdownParagraphChunk::Image(image) = &paragraph[0] else {
+            panic!("Expected an image");
+        };
+        assert_eq!(image.size(), (None, None));
+        assert_eq!(parsed.children[1], p("Text", 75..79));
+    }
+
+    #[test]
+    fn test_parse_html_images() {
+        assert_eq!(
+            parse_html_images(r#"<IMG Src="a b.png" ALT='An "image"' width=10px /><img alt=x>"#),
+            vec![HtmlImage {
+                src: "a b.png".to_string(),
+                alt: Some("An \"image\"".to_string()),
+                width: Some(px(10.).into()),
+                height: None,
+            }]
+        );
+        assert_eq!(parse_html_length("auto"), None);
+    }
+
     #[gpui::test]
     async fn test_header_only_table() {
         let markdown = "\
@@ -0,0 +1,35 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct MarkdownPreviewSettings {
+    pub max_image_width: Option<f32>,
+    pub max_image_height: Option<f32>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct MarkdownPreviewSettingsContent {
+    /// The largest width, in pixels, to show images at. Images are always scaled down to fit the
+    /// width of the preview.
+    ///
+    /// Default: null
+    pub max_image_width: Option<f32>,
+    /// The largest height, in pixels, to show images at.
+    ///
+    /// Default: null
+    pub max_image_height: Option<f32>,
+}
+
+impl Settings for MarkdownPreviewSettings {
+    const KEY: Option<&'static str> = Some("markdown_preview");
+
+    type FileContent = MarkdownPreviewSettingsContent;
+
+    fn load(
+        sources: SettingsSources<Self::FileContent>,
+        _: &mut gpui::AppContext,
+    ) -> anyhow::Result<Self> {
+        sources.json_merge()
+    }
+}
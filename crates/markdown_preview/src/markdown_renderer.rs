@@ -1,14 +1,18 @@
-use crate::markdown_elements::{
-    HeadingLevel, Image, Link, MarkdownParagraph, MarkdownParagraphChunk, ParsedMarkdown,
-    ParsedMarkdownBlockQuote, ParsedMarkdownCodeBlock, ParsedMarkdownElement,
-    ParsedMarkdownHeading, ParsedMarkdownListItem, ParsedMarkdownListItemType, ParsedMarkdownTable,
-    ParsedMarkdownTableAlignment, ParsedMarkdownTableRow, ParsedMarkdownText,
+use crate::{
+    markdown_elements::{
+        HeadingLevel, Image, Link, MarkdownParagraph, MarkdownParagraphChunk, ParsedMarkdown,
+        ParsedMarkdownBlockQuote, ParsedMarkdownCodeBlock, ParsedMarkdownElement,
+        ParsedMarkdownHeading, ParsedMarkdownListItem, ParsedMarkdownListItemType,
+        ParsedMarkdownTable, ParsedMarkdownTableAlignment, ParsedMarkdownTableRow,
+        ParsedMarkdownText,
+    },
+    markdown_preview_settings::MarkdownPreviewSettings,
 };
 use gpui::{
     div, img, px, rems, AbsoluteLength, AnyElement, ClipboardItem, DefiniteLength, Div, Element,
     ElementId, HighlightStyle, Hsla, ImageSource, InteractiveText, IntoElement, Keystroke, Length,
-    Modifiers, ParentElement, Resource, SharedString, Styled, StyledText, TextStyle, WeakView,
-    WindowContext,
+    Modifiers, ObjectFit, ParentElement, Pixels, Resource, SharedString, Styled, StyledText,
+    TextStyle, WeakView, WindowContext,
 };
 use settings::Settings;
 use std::{
@@ -42,6 +46,8 @@ pub struct RenderContext {
     syntax_theme: Arc<SyntaxTheme>,
     indent: usize,
     checkbox_clicked_callback: Option<CheckboxClickedCallback>,
+    max_image_width: Option<Pixels>,
+    max_image_height: Option<Pixels>,
 }
 
 impl RenderContext {
@@ -50,6 +56,7 @@ impl RenderContext {
 
         let settings = ThemeSettings::get_global(cx);
         let buffer_font_family = settings.buffer_font.family.clone();
+        let preview_settings = MarkdownPreviewSettings::get_global(cx);
         let mut buffer_text_style = cx.text_style();
         buffer_text_style.font_family = buffer_font_family.clone();
 
@@ -67,6 +74,8 @@ impl RenderContext {
             code_block_background_color: theme.colors().surface_background,
             code_span_background_color: theme.colors().editor_document_highlight_read_background,
             checkbox_clicked_callback: None,
+            max_image_width: preview_settings.max_image_width.map(px),
+            max_image_height: preview_settings.max_image_height.map(px),
         }
     }
 
@@ -505,17 +514,20 @@ fn render_markdown_text(parsed_new: &MarkdownParagraph, cx: &mut RenderContext)
             }
 
             MarkdownParagraphChunk::Image(image) => {
-                let (link, source_range, image_source, alt_text) = match image {
+                let (width, height) = image.size();
+                let (link, source_range, image_source, alt_text, image_path) = match image {
                     Image::Web {
                         link,
                         source_range,
                         url,
                         alt_text,
+                        ..
                     } => (
                         link,
                         source_range,
                         Resource::Uri(url.clone().into()),
                         alt_text,
+                        None,
                     ),
                     Image::Path {
                         link,
@@ -530,21 +542,32 @@ fn render_markdown_text(parsed_new: &MarkdownParagraph, cx: &mut RenderContext)
                             source_range,
                             Resource::Path(Arc::from(image_path)),
                             alt_text,
+                            Some(path.clone()),
                         )
                     }
                 };
 
                 let element_id = cx.next_id(source_range);
 
-                match link {
-                    None => {
-                        let fallback_workspace = workspace_clone.clone();
-                        let fallback_syntax_theme = syntax_theme.clone();
-                        let fallback_text_style = text_style.clone();
-                        let fallback_alt_text = alt_text.clone();
-                        let element_id_new = element_id.clone();
-                        let element = div()
-                            .child(img(ImageSource::Resource(image_source)).with_fallback({
+                let fallback_workspace = workspace_clone.clone();
+                let fallback_syntax_theme = syntax_theme.clone();
+                let fallback_text_style = text_style.clone();
+                let fallback_alt_text = alt_text.clone();
+                let element_id_new = element_id.clone();
+                let image_element = div()
+                    .child(
+                        img(ImageSource::Resource(image_source))
+                            .max_w_full()
+                            .when_some(cx.max_image_width, |image, max_width| {
+                                image.max_w(max_width)
+                            })
+                            .when_some(cx.max_image_height, |image, max_height| {
+                                image.max_h(max_height)
+                            })
+                            .when_some(width, |image, width| image.w(width))
+                            .when_some(height, |image, height| image.h(height))
+                            .object_fit(ObjectFit::ScaleDown)
+                            .with_fallback({
                                 move || {
                                     fallback_text(
                                         fallback_alt_text.clone().unwrap(),
@@ -555,33 +578,15 @@ fn render_markdown_text(parsed_new: &MarkdownParagraph, cx: &mut RenderContext)
                                         &fallback_text_style,
                                     )
                                 }
-                            }))
-                            .id(element_id_new)
-                            .into_any();
-                        any_element.push(element);
-                    }
-                    Some(link) => {
+                            }),
+                    )
+                    .id(element_id_new);
+
+                let image_element = match (link, image_path) {
+                    (Some(link), _) => {
                         let link_click = link.clone();
                         let link_tooltip = link.clone();
-                        let fallback_workspace = workspace_clone.clone();
-                        let fallback_syntax_theme = syntax_theme.clone();
-                        let fallback_text_style = text_style.clone();
-                        let fallback_alt_text = alt_text.clone();
-                        let element_id_new = element_id.clone();
-                        let image_element = div()
-                            .child(img(ImageSource::Resource(image_source)).with_fallback({
-                                move || {
-                                    fallback_text(
-                                        fallback_alt_text.clone().unwrap(),
-                                        element_id.clone(),
-                                        &fallback_syntax_theme,
-                                        code_span_bg_color,
-                                        fallback_workspace.clone(),
-                                        &fallback_text_style,
-                                    )
-                                }
-                            }))
-                            .id(element_id_new)
+                        image_element
                             .tooltip(move |cx| LinkPreview::new(&link_tooltip.to_string(), cx))
                             .on_click({
                                 let workspace = workspace_clone.clone();
@@ -598,10 +603,29 @@ fn render_markdown_text(parsed_new: &MarkdownParagraph, cx: &mut RenderContext)
                                     }
                                 }
                             })
-                            .into_any();
-                        any_element.push(image_element);
+                            .into_any()
                     }
-                }
+                    // Local images open in the image viewer, which shares the project's image
+                    // store with the preview.
+                    (None, Some(image_path)) => image_element
+                        .cursor_pointer()
+                        .tooltip(|cx| Tooltip::text("Open in Image Viewer", cx))
+                        .on_click({
+                            let workspace = workspace_clone.clone();
+                            move |_event, window_cx| {
+                                if let Some(workspace) = &workspace {
+                                    _ = workspace.update(window_cx, |workspace, cx| {
+                                        workspace
+                                            .open_abs_path(image_path.clone(), false, cx)
+                                            .detach();
+                                    });
+                                }
+                            }
+                        })
+                        .into_any(),
+                    (None, None) => image_element.into_any(),
+                };
+                any_element.push(image_element);
             }
         }
     }
@@ -31,6 +31,7 @@ use gpui::{
     StatefulInteractiveElement as _, Styled, Subscription, Task, UniformListScrollHandle, View,
     ViewContext, VisualContext, WeakView, WindowContext,
 };
+use image_viewer::ImageView;
 use itertools::Itertools;
 use language::{BufferId, BufferSnapshot, OffsetRangeExt, OutlineItem};
 use menu::{Cancel, SelectFirst, SelectLast, SelectNext, SelectPrev};
@@ -101,6 +102,7 @@ pub struct OutlinePanel {
     unfolded_dirs: HashMap<WorktreeId, BTreeSet<ProjectEntryId>>,
     selected_entry: SelectedEntry,
     active_item: Option<ActiveItem>,
+    active_image: Option<ActiveImage>,
     _subscriptions: Vec<Subscription>,
     updating_fs_entries: bool,
     fs_entries_update_task: Task<()>,
@@ -558,6 +560,12 @@ struct ActiveItem {
     _editor_subscrpiption: Subscription,
 }
 
+/// An image view whose structure, such as the elements of an SVG, is shown instead of outlines.
+struct ActiveImage {
+    image_view: WeakView<ImageView>,
+    _subscription: Subscription,
+}
+
 #[derive(Debug)]
 pub enum Event {
     Focus,
@@ -655,6 +663,7 @@ impl OutlinePanel {
                             }
                         } else {
                             outline_panel.clear_previous(cx);
+                            outline_panel.update_active_image(&workspace, cx);
                             cx.notify();
                         }
                     }
@@ -714,6 +723,7 @@ impl OutlinePanel {
                 context_menu: None,
                 width: None,
                 active_item: None,
+                active_image: None,
                 pending_serialization: Task::ready(None),
                 updating_fs_entries: false,
                 fs_entries_update_task: Task::ready(()),
@@ -2482,6 +2492,7 @@ impl OutlinePanel {
         self.collapsed_entries.clear();
         self.unfolded_dirs.clear();
         self.active_item = None;
+        self.active_image = None;
         self.fs_entries.clear();
         self.fs_entries_depth.clear();
         self.fs_children_count.clear();
@@ -2492,6 +2503,62 @@ impl OutlinePanel {
         self.mode = ItemsDisplayMode::Outline;
     }
 
+    fn update_active_image(&mut self, workspace: &View<Workspace>, cx: &mut ViewContext<Self>) {
+        let image_view = workspace.read(cx).active_item_as::<ImageView>(cx);
+        self.active_image = image_view.map(|image_view| ActiveImage {
+            _subscription: cx.observe(&image_view, |_, _, cx| cx.notify()),
+            image_view: image_view.downgrade(),
+        });
+    }
+
+    /// Lists the elements of the active image. Clicking one outlines its bounds in the image.
+    fn render_image_structure(
+        &self,
+        image_view: &View<ImageView>,
+        query: Option<&str>,
+        indent_size: f32,
+        cx: &mut ViewContext<Self>,
+    ) -> Div {
+        let query = query.map(|query| query.to_lowercase());
+        let image_view_handle = image_view.downgrade();
+        let image_view = image_view.read(cx);
+        let highlighted_entry = image_view.highlighted_structure_entry();
+        let entries = image_view
+            .structure()
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| {
+                query
+                    .as_ref()
+                    .map_or(true, |query| entry.name.to_lowercase().contains(query))
+            })
+            .map(|(ix, entry)| {
+                let image_view = image_view_handle.clone();
+                let selected = highlighted_entry == Some(ix);
+                ListItem::new(("image-structure-entry", ix))
+                    .indent_level(entry.depth)
+                    .indent_step_size(px(indent_size))
+                    .selected(selected)
+                    .child(Label::new(entry.name.clone()).single_line())
+                    .on_click(move |_, cx| {
+                        image_view
+                            .update(cx, |image_view, cx| {
+                                image_view.highlight_structure_entry((!selected).then_some(ix), cx)
+                            })
+                            .ok();
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        v_flex().size_full().child(
+            v_flex()
+                .id("image-structure")
+                .size_full()
+                .overflow_y_scroll()
+                .children(entries),
+        )
+    }
+
     fn location_for_editor_selection(
         &self,
         editor: &View<Editor>,
@@ -3851,7 +3918,14 @@ impl OutlinePanel {
         indent_size: f32,
         cx: &mut ViewContext<'_, Self>,
     ) -> Div {
-        let contents = if self.cached_entries.is_empty() {
+        let active_image_view = self
+            .active_image
+            .as_ref()
+            .and_then(|active_image| active_image.image_view.upgrade())
+            .filter(|image_view| !image_view.read(cx).structure().is_empty());
+        let contents = if let Some(image_view) = active_image_view {
+            self.render_image_structure(&image_view, query.as_deref(), indent_size, cx)
+        } else if self.cached_entries.is_empty() {
             let header = if self.updating_fs_entries {
                 "Loading outlines"
             } else if query.is_some() {
@@ -4201,6 +4275,9 @@ impl Panel for OutlinePanel {
                             }
                         } else if !outline_panel.pinned {
                             outline_panel.clear_previous(cx);
+                            if let Some(workspace) = outline_panel.workspace.upgrade() {
+                                outline_panel.update_active_image(&workspace, cx);
+                            }
                         }
                     }
                     outline_panel.serialize(cx);
@@ -284,6 +284,14 @@ pub fn default_prettier_dir() -> &'static PathBuf {
     DEFAULT_PRETTIER_DIR.get_or_init(|| support_dir().join("prettier"))
 }
 
+/// Returns the path to the thumbnails directory.
+///
+/// This is where the small copies of images shown in lists and previews are stored.
+pub fn thumbnails_dir() -> &'static PathBuf {
+    static THUMBNAILS_DIR: OnceLock<PathBuf> = OnceLock::new();
+    THUMBNAILS_DIR.get_or_init(|| support_dir().join("thumbnails"))
+}
+
 /// Returns the path to the remote server binaries directory.
 pub fn remote_servers_dir() -> &'static PathBuf {
     static REMOTE_SERVERS_DIR: OnceLock<PathBuf> = OnceLock::new();
@@ -0,0 +1,110 @@
+//! Images stored inside archives, such as the assets of zip, jar and apk files, which can be
+//! viewed without extracting them.
+
+use std::{
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{anyhow, Result};
+use async_zip::base::read::mem::ZipFileReader;
+
+use crate::image_store::is_image_path;
+
+/// The extensions of the zip-based archives whose images can be viewed.
+const ARCHIVE_EXTENSIONS: &[&str] = &["zip", "jar", "apk", "aar"];
+
+pub fn is_archive_path(path: &Path) -> bool {
+    let ext = path
+        .extension()
+        .and_then(OsStr::to_str)
+        .map(str::to_lowercase)
+        .unwrap_or_default();
+    ARCHIVE_EXTENSIONS.contains(&ext.as_str())
+}
+
+/// Lists the paths of the images inside an archive, in the order they are stored in.
+pub(crate) async fn list_images(archive: Vec<u8>) -> Result<Vec<Arc<Path>>> {
+    let reader = ZipFileReader::new(archive).await?;
+    let mut images = Vec::new();
+    for entry in reader.file().entries() {
+        let Ok(file_name) = entry.filename().as_str() else {
+            continue;
+        };
+        let path = Path::new(file_name);
+        if !entry.dir()? && is_image_path(path) {
+            images.push(Arc::from(path));
+        }
+    }
+    Ok(images)
+}
+
+/// Reads the contents of an entry of an archive.
+pub(crate) async fn read_entry(archive: Vec<u8>, entry_path: &Path) -> Result<Vec<u8>> {
+    let reader = ZipFileReader::new(archive).await?;
+    let index = reader
+        .file()
+        .entries()
+        .iter()
+        .position(|entry| {
+            entry
+                .filename()
+                .as_str()
+                .map_or(false, |file_name| Path::new(file_name) == entry_path)
+        })
+        .ok_or_else(|| anyhow!("no entry {entry_path:?} in the archive"))?;
+    let mut content = Vec::new();
+    reader
+        .reader_with_entry(index)
+        .await?
+        .read_to_end_checked(&mut content)
+        .await?;
+    Ok(content)
+}
+
+/// The path an image inside an archive is shown under: the archive's path followed by the
+/// image's path inside it.
+pub(crate) fn virtual_path(archive_path: &Path, entry_path: &Path) -> PathBuf {
+    archive_path.join(entry_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_zip::{base::write::ZipFileWriter, Compression, ZipEntryBuilder};
+
+    #[test]
+    fn test_read_archive_images() {
+        let archive = smol::block_on(async {
+            let mut archive = Vec::new();
+            let mut writer = ZipFileWriter::new(&mut archive);
+            for (path, content) in [
+                ("res/icon.png", b"icon".as_slice()),
+                ("classes.dex", b"code".as_slice()),
+                ("res/splash.JPG", b"splash".as_slice()),
+            ] {
+                let entry = ZipEntryBuilder::new(path.into(), Compression::Deflate);
+                writer.write_entry_whole(entry, content).await.unwrap();
+            }
+            writer.close().await.unwrap();
+            archive
+        });
+
+        let images = smol::block_on(list_images(archive.clone())).unwrap();
+        assert_eq!(
+            images,
+            vec![
+                Arc::from(Path::new("res/icon.png")),
+                Arc::from(Path::new("res/splash.JPG")),
+            ]
+        );
+        assert_eq!(
+            smol::block_on(read_entry(archive.clone(), Path::new("res/splash.JPG"))).unwrap(),
+            b"splash"
+        );
+        assert!(smol::block_on(read_entry(archive, Path::new("res/missing.png"))).is_err());
+        assert!(is_archive_path(Path::new("app/build/app-release.APK")));
+        assert!(!is_archive_path(Path::new("res/icon.png")));
+    }
+}
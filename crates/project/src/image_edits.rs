@@ -0,0 +1,228 @@
+//! Changes made to images in the editor, which are applied to their contents when they are saved.
+
+use std::{io::Cursor, sync::Arc};
+
+use anyhow::{anyhow, Result};
+use image::{DynamicImage, Rgba, RgbaImage};
+
+/// A change made to an image, in the pixels of the image as left by the edits before it.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ImageEdit {
+    /// Keeps only the given part of the image.
+    Crop {
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    },
+    /// Turns the image clockwise by the given number of quarter turns.
+    Rotate { quarter_turns: u32 },
+    /// Mirrors the image from left to right if `horizontal`, or from top to bottom otherwise.
+    Flip { horizontal: bool },
+    /// Draws a line through the given points.
+    Annotate {
+        points: Vec<(f32, f32)>,
+        color: [u8; 4],
+        width: f32,
+    },
+}
+
+impl ImageEdit {
+    /// The dimensions of an image of the given dimensions after the edit.
+    pub fn apply_to_dimensions(&self, (width, height): (u32, u32)) -> (u32, u32) {
+        match self {
+            ImageEdit::Crop {
+                x,
+                y,
+                width: crop_width,
+                height: crop_height,
+            } => (
+                (*crop_width).min(width.saturating_sub(*x)),
+                (*crop_height).min(height.saturating_sub(*y)),
+            ),
+            ImageEdit::Rotate { quarter_turns } if quarter_turns % 2 == 1 => (height, width),
+            ImageEdit::Rotate { .. } | ImageEdit::Flip { .. } | ImageEdit::Annotate { .. } => {
+                (width, height)
+            }
+        }
+    }
+}
+
+/// Applies edits to the contents of an image, and encodes the result in the same format.
+///
+/// Only the first frame of animated images could be kept, so they can't be edited, and neither
+/// can SVG images or images in formats that can only be decoded.
+pub fn apply_edits(image: &gpui::Image, edits: &[ImageEdit]) -> Result<Vec<u8>> {
+    if edits.is_empty() {
+        return Ok(image.bytes.clone());
+    }
+
+    let format = match image.format {
+        gpui::ImageFormat::Png => image::ImageFormat::Png,
+        gpui::ImageFormat::Jpeg => image::ImageFormat::Jpeg,
+        gpui::ImageFormat::Webp => image::ImageFormat::WebP,
+        gpui::ImageFormat::Gif => image::ImageFormat::Gif,
+        gpui::ImageFormat::Bmp => image::ImageFormat::Bmp,
+        gpui::ImageFormat::Tiff => image::ImageFormat::Tiff,
+        format => return Err(anyhow!("{format:?} images can't be edited")),
+    };
+    if crate::image_store::frame_count(image.format, &image.bytes)? > 1 {
+        return Err(anyhow!("animated images can't be edited"));
+    }
+
+    let mut edited = image::load_from_memory_with_format(&image.bytes, format)?;
+    for edit in edits {
+        edited = apply_edit(edited, edit);
+    }
+    // JPEG has no alpha channel, and GIF and BMP encoders only take 8-bit pixels.
+    edited = match format {
+        image::ImageFormat::Jpeg => DynamicImage::ImageRgb8(edited.into_rgb8()),
+        image::ImageFormat::Gif | image::ImageFormat::Bmp => {
+            DynamicImage::ImageRgba8(edited.into_rgba8())
+        }
+        _ => edited,
+    };
+
+    let mut bytes = Vec::new();
+    edited.write_to(&mut Cursor::new(&mut bytes), format)?;
+    Ok(bytes)
+}
+
+/// A copy of the image with the edits applied, to show them before they are saved.
+pub fn edited_image(image: &gpui::Image, edits: &[ImageEdit]) -> Result<Arc<gpui::Image>> {
+    crate::image_store::create_gpui_image(apply_edits(image, edits)?)
+}
+
+fn apply_edit(image: DynamicImage, edit: &ImageEdit) -> DynamicImage {
+    match edit {
+        ImageEdit::Crop {
+            x,
+            y,
+            width,
+            height,
+        } => image.crop_imm(*x, *y, *width, *height),
+        ImageEdit::Rotate { quarter_turns } => match quarter_turns % 4 {
+            1 => image.rotate90(),
+            2 => image.rotate180(),
+            3 => image.rotate270(),
+            _ => image,
+        },
+        ImageEdit::Flip { horizontal: true } => image.fliph(),
+        ImageEdit::Flip { horizontal: false } => image.flipv(),
+        ImageEdit::Annotate {
+            points,
+            color,
+            width,
+        } => {
+            let has_alpha = image.color().has_alpha();
+            let mut pixels = image.into_rgba8();
+            draw_stroke(&mut pixels, points, Rgba(*color), *width);
+            let annotated = DynamicImage::ImageRgba8(pixels);
+            if has_alpha {
+                annotated
+            } else {
+                DynamicImage::ImageRgb8(annotated.into_rgb8())
+            }
+        }
+    }
+}
+
+/// Draws a line of the given width through the points, blending it over the image.
+fn draw_stroke(image: &mut RgbaImage, points: &[(f32, f32)], color: Rgba<u8>, width: f32) {
+    let radius = (width / 2.).max(0.5);
+    let segments = points
+        .iter()
+        .zip(points.iter().skip(1))
+        .map(|(start, end)| (*start, *end))
+        .chain(points.first().map(|point| (*point, *point)));
+
+    let mut covered = vec![false; image.width() as usize * image.height() as usize];
+    for ((x0, y0), (x1, y1)) in segments {
+        let min_x = (x0.min(x1) - radius).floor().max(0.) as u32;
+        let min_y = (y0.min(y1) - radius).floor().max(0.) as u32;
+        let max_x = ((x0.max(x1) + radius).ceil() as u32).min(image.width());
+        let max_y = ((y0.max(y1) + radius).ceil() as u32).min(image.height());
+        for y in min_y..max_y {
+            for x in min_x..max_x {
+                let ix = y as usize * image.width() as usize + x as usize;
+                let center = (x as f32 + 0.5, y as f32 + 0.5);
+                // Pixels where segments overlap are only blended once.
+                if covered[ix] || distance_to_segment(center, (x0, y0), (x1, y1)) > radius {
+                    continue;
+                }
+                covered[ix] = true;
+                blend(image.get_pixel_mut(x, y), color);
+            }
+        }
+    }
+}
+
+fn distance_to_segment(point: (f32, f32), start: (f32, f32), end: (f32, f32)) -> f32 {
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    let length_squared = dx * dx + dy * dy;
+    let t = if length_squared == 0. {
+        0.
+    } else {
+        (((point.0 - start.0) * dx + (point.1 - start.1) * dy) / length_squared).clamp(0., 1.)
+    };
+    let (closest_x, closest_y) = (start.0 + t * dx, start.1 + t * dy);
+    ((point.0 - closest_x).powi(2) + (point.1 - closest_y).powi(2)).sqrt()
+}
+
+fn blend(pixel: &mut Rgba<u8>, color: Rgba<u8>) {
+    let alpha = color.0[3] as f32 / 255.;
+    let below_alpha = pixel.0[3] as f32 / 255.;
+    let out_alpha = alpha + below_alpha * (1. - alpha);
+    if out_alpha == 0. {
+        return;
+    }
+    for channel in 0..3 {
+        let value = (color.0[channel] as f32 * alpha
+            + pixel.0[channel] as f32 * below_alpha * (1. - alpha))
+            / out_alpha;
+        pixel.0[channel] = value.round() as u8;
+    }
+    pixel.0[3] = (out_alpha * 255.).round() as u8;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::image_store::{create_gpui_image, image_dimensions};
+
+    #[test]
+    fn test_apply_edits() {
+        let mut bytes = Vec::new();
+        RgbaImage::from_pixel(40, 20, Rgba([255, 255, 255, 255]))
+            .write_to(&mut Cursor::new(&mut bytes), image::ImageFormat::Png)
+            .unwrap();
+        let image = create_gpui_image(bytes).unwrap();
+
+        let edits = [
+            ImageEdit::Crop {
+                x: 10,
+                y: 0,
+                width: 100,
+                height: 10,
+            },
+            ImageEdit::Rotate { quarter_turns: 1 },
+            ImageEdit::Flip { horizontal: false },
+            ImageEdit::Annotate {
+                points: vec![(0., 0.), (10., 0.)],
+                color: [255, 0, 0, 255],
+                width: 2.,
+            },
+        ];
+        let dimensions = edits.iter().fold((40, 20), |dimensions, edit| {
+            edit.apply_to_dimensions(dimensions)
+        });
+        assert_eq!(dimensions, (10, 30));
+
+        let edited = create_gpui_image(apply_edits(&image, &edits).unwrap()).unwrap();
+        assert_eq!(edited.format, gpui::ImageFormat::Png);
+        assert_eq!(image_dimensions(&edited), Some((10, 30)));
+        let pixels = image::load_from_memory(&edited.bytes).unwrap().into_rgba8();
+        assert_eq!(pixels.get_pixel(5, 0), &Rgba([255, 0, 0, 255]));
+        assert_eq!(pixels.get_pixel(5, 5), &Rgba([255, 255, 255, 255]));
+    }
+}
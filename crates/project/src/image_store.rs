@@ -1,22 +1,38 @@
 use crate::{
+    image_archives,
+    image_edits::{self, ImageEdit},
+    load_queue::LoadQueue,
+    thumbnails::{self, Thumbnail, ThumbnailPriority, MAX_CONCURRENT_THUMBNAILS},
     worktree_store::{WorktreeStore, WorktreeStoreEvent},
     Project, ProjectEntryId, ProjectPath,
 };
 use anyhow::{Context as _, Result};
-use collections::{hash_map, HashMap, HashSet};
-use futures::{channel::oneshot, StreamExt};
+use collections::{HashMap, HashSet};
+use fs::{MTime, MappedFile};
+use futures::{
+    channel::{mpsc, oneshot},
+    future::{Shared, WeakShared},
+    FutureExt as _,
+};
+use git::{
+    repository::{FileHistoryEntry, GitRepository, RepoPath},
+    Oid,
+};
 use gpui::{
-    hash, prelude::*, AppContext, EventEmitter, Img, Model, ModelContext, Subscription, Task,
-    WeakModel,
+    hash, prelude::*, AppContext, AsyncAppContext, EventEmitter, Img, Model, ModelContext,
+    SharedString, Subscription, Task, WeakModel,
 };
 use language::{DiskState, File};
-use rpc::{AnyProtoClient, ErrorExt as _};
+use rpc::{proto, AnyProtoClient, ErrorExt as _, TypedEnvelope};
+use std::collections::VecDeque;
 use std::ffi::OsStr;
 use std::num::NonZeroU64;
-use std::path::Path;
-use std::sync::Arc;
-use util::ResultExt;
-use worktree::{LoadedBinaryFile, PathChange, Worktree};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
+use util::{post_inc, ResultExt};
+use worktree::{LoadedBinaryFile, PathChange, Worktree, WorktreeId};
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, PartialOrd, Ord, Eq)]
 pub struct ImageId(NonZeroU64);
@@ -37,6 +53,20 @@ pub enum ImageItemEvent {
     ReloadNeeded,
     Reloaded,
     FileHandleChanged,
+    /// The image's file was renamed or moved within its worktree, after which the image is
+    /// found under its new path.
+    Renamed {
+        old_path: Arc<Path>,
+    },
+    /// The image's [`ImageMetadata`] was read, after it was opened or reloaded.
+    MetadataComputed,
+    /// Edits were made to the image, undone or discarded.
+    Edited,
+    /// The image's edits were written to its file.
+    Saved,
+    /// The image's file changed on disk while it had unsaved edits, which are kept until they
+    /// are either saved over the file or discarded by reloading it.
+    Conflicted,
 }
 
 impl EventEmitter<ImageItemEvent> for ImageItem {}
@@ -52,9 +82,70 @@ pub struct ImageItem {
     pub file: Arc<dyn File>,
     pub image: Arc<gpui::Image>,
     reload_task: Option<Task<()>>,
+    check_for_changes_task: Option<Task<()>>,
+    /// Whether the check for changes is reading the file, rather than waiting to start.
+    checking_for_changes: bool,
+    /// Whether the file changed again while it was being checked for changes.
+    changed_while_checking: bool,
+    /// When the file was last checked for changes.
+    last_checked_for_changes: Option<Instant>,
+    /// How long to wait between checks for changes, so that files rewritten many times a
+    /// second are only reloaded a few times a second.
+    reload_debounce: Duration,
+    /// Facts about the image read from its contents, once they have been.
+    metadata: Option<ImageMetadata>,
+    metadata_task: Option<Task<()>>,
+    /// Why the image's contents could not be decoded, if reading its metadata failed.
+    decode_error: Option<SharedString>,
+    /// The edits made to the image since it was last saved.
+    edits: Vec<ImageEdit>,
+    /// When the file was last modified as of reading or saving the loaded contents.
+    saved_mtime: Option<MTime>,
+    /// Whether the file's contents changed on disk while the image had unsaved edits.
+    has_conflict: bool,
+    /// The path of the file with symlinks resolved, when it was opened from a local worktree.
+    canonical_path: Option<Arc<Path>>,
+}
+
+/// Facts about an image read from its contents, so that they can be shown without reading the
+/// image again.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ImageMetadata {
+    pub width: u32,
+    pub height: u32,
+    /// How many frames the image has, which is more than one for animations.
+    pub frame_count: usize,
+    /// How the pixels of raster images are stored.
+    pub color_type: Option<image::ColorType>,
 }
 
 impl ImageItem {
+    fn new(
+        file: Arc<dyn File>,
+        image: Arc<gpui::Image>,
+        saved_mtime: Option<MTime>,
+        cx: &ModelContext<Self>,
+    ) -> Self {
+        Self {
+            id: cx.entity_id().as_non_zero_u64().into(),
+            file,
+            image,
+            reload_task: None,
+            check_for_changes_task: None,
+            checking_for_changes: false,
+            changed_while_checking: false,
+            last_checked_for_changes: None,
+            reload_debounce: DEFAULT_RELOAD_DEBOUNCE,
+            metadata: None,
+            metadata_task: None,
+            decode_error: None,
+            edits: Vec::new(),
+            saved_mtime,
+            has_conflict: false,
+            canonical_path: None,
+        }
+    }
+
     pub fn project_path(&self, cx: &AppContext) -> ProjectPath {
         ProjectPath {
             worktree_id: self.file.worktree_id(cx),
@@ -66,11 +157,234 @@ impl ImageItem {
         self.file.path()
     }
 
+    /// Whether the image was created from contents that aren't backed by a file and hasn't
+    /// been saved since.
+    pub fn is_untitled(&self) -> bool {
+        self.file.as_any().is::<UntitledImageFile>()
+    }
+
+    /// Whether the image's file was deleted since it was opened. Its last loaded contents are
+    /// kept, so that they can still be shown and saved elsewhere.
+    pub fn is_deleted(&self) -> bool {
+        self.file.disk_state() == DiskState::Deleted
+    }
+
+    /// The path of the image's file with symlinks resolved, when it was opened from a local
+    /// worktree. Opening the file through any symlink to it finds this image.
+    pub fn canonical_path(&self) -> Option<&Arc<Path>> {
+        self.canonical_path.as_ref()
+    }
+
+    /// Whether the image was opened from an entry of an archive, which can't be written to.
+    pub fn is_in_archive(&self) -> bool {
+        self.file.as_any().is::<ArchiveEntryFile>()
+    }
+
+    /// Whether the image is backed by a file of its own in the project, which it can be saved
+    /// to, as opposed to being untitled or inside an archive.
+    pub fn has_project_file(&self) -> bool {
+        !self.is_untitled() && !self.is_in_archive()
+    }
+
+    /// Whether the image has edits or contents that haven't been written to its file.
+    pub fn is_dirty(&self) -> bool {
+        !self.edits.is_empty() || self.is_untitled()
+    }
+
+    /// The edits made to the image since it was last saved, in the order they were made.
+    pub fn edits(&self) -> &[ImageEdit] {
+        &self.edits
+    }
+
+    pub fn edit(&mut self, edit: ImageEdit, cx: &mut ModelContext<Self>) {
+        self.edits.push(edit);
+        cx.emit(ImageItemEvent::Edited);
+        cx.notify();
+    }
+
+    /// Undoes the last edit made to the image, returning it.
+    pub fn undo_edit(&mut self, cx: &mut ModelContext<Self>) -> Option<ImageEdit> {
+        let edit = self.edits.pop()?;
+        cx.emit(ImageItemEvent::Edited);
+        cx.notify();
+        Some(edit)
+    }
+
+    pub fn discard_edits(&mut self, cx: &mut ModelContext<Self>) {
+        if self.edits.is_empty() {
+            return;
+        }
+        self.edits.clear();
+        cx.emit(ImageItemEvent::Edited);
+        cx.notify();
+    }
+
+    /// Whether the image's file changed on disk since its contents were loaded or saved, while
+    /// it had unsaved edits.
+    pub fn has_conflict(&self) -> bool {
+        if self.has_conflict {
+            return true;
+        }
+        if self.edits.is_empty() {
+            return false;
+        }
+        match (self.file.disk_state(), self.saved_mtime) {
+            (DiskState::Present { mtime }, Some(saved_mtime)) => {
+                mtime.bad_is_greater_than(saved_mtime)
+            }
+            _ => false,
+        }
+    }
+
+    /// Resolves a conflict in favor of the unsaved edits, which overwrite the file's new contents
+    /// once saved.
+    pub fn keep_edits(&mut self, cx: &mut ModelContext<Self>) {
+        self.has_conflict = false;
+        self.saved_mtime = self.file.disk_state().mtime();
+        cx.notify();
+    }
+
+    /// When the image's file was last modified as of reading or saving the loaded contents.
+    pub fn saved_mtime(&self) -> Option<MTime> {
+        self.saved_mtime
+    }
+
+    /// Reports that the file changed on disk, which is a conflict when there are unsaved edits
+    /// that reloading it would discard.
+    fn changed_on_disk(&mut self, cx: &mut ModelContext<Self>) {
+        if self.edits.is_empty() {
+            cx.emit(ImageItemEvent::ReloadNeeded);
+        } else if !self.has_conflict {
+            self.has_conflict = true;
+            cx.emit(ImageItemEvent::Conflicted);
+            cx.notify();
+        }
+    }
+
+    /// Returns the pixel dimensions the image will have once its edits are saved.
+    pub fn edited_dimensions(&self) -> Option<(u32, u32)> {
+        let dimensions = self.dimensions()?;
+        Some(self.edits.iter().fold(dimensions, |dimensions, edit| {
+            edit.apply_to_dimensions(dimensions)
+        }))
+    }
+
+    /// Returns the pixel dimensions of the image, from its metadata once it has been computed
+    /// and by reading the image's header until then.
+    pub fn dimensions(&self) -> Option<(u32, u32)> {
+        match &self.metadata {
+            Some(metadata) => Some((metadata.width, metadata.height)),
+            None => image_dimensions(&self.image),
+        }
+    }
+
+    /// Returns the image's metadata, which is computed in the background after the image is
+    /// opened or reloaded.
+    pub fn metadata(&self) -> Option<&ImageMetadata> {
+        self.metadata.as_ref()
+    }
+
+    /// Returns why the image could not be decoded, once its metadata failed to be read.
+    pub fn decode_error(&self) -> Option<&SharedString> {
+        self.decode_error.as_ref()
+    }
+
+    pub fn width(&self) -> Option<u32> {
+        Some(self.metadata?.width)
+    }
+
+    pub fn height(&self) -> Option<u32> {
+        Some(self.metadata?.height)
+    }
+
+    pub fn frame_count(&self) -> Option<usize> {
+        Some(self.metadata?.frame_count)
+    }
+
+    pub fn color_type(&self) -> Option<image::ColorType> {
+        self.metadata?.color_type
+    }
+
+    pub fn format(&self) -> gpui::ImageFormat {
+        self.image.format
+    }
+
+    /// Decodes the image's frames in the background, and sends them in the order they are
+    /// played as they are decoded. Decoding stops early when the receiver is dropped.
+    pub fn stream_frames(&self, cx: &AppContext) -> mpsc::UnboundedReceiver<Result<ImageFrame>> {
+        let image = self.image.clone();
+        let (tx, rx) = mpsc::unbounded();
+        cx.background_executor()
+            .spawn(async move {
+                match image_frames(&image) {
+                    Ok(frames) => {
+                        for frame in frames {
+                            if tx.unbounded_send(frame).is_err() {
+                                break;
+                            }
+                        }
+                    }
+                    Err(error) => {
+                        tx.unbounded_send(Err(error)).ok();
+                    }
+                }
+            })
+            .detach();
+        rx
+    }
+
+    /// Returns the size of the image's file in bytes.
+    pub fn file_size(&self) -> u64 {
+        self.image.bytes.len() as u64
+    }
+
+    fn compute_metadata(&mut self, cx: &mut ModelContext<Self>) {
+        let image = self.image.clone();
+        self.metadata_task = Some(cx.spawn(|this, mut cx| async move {
+            let metadata = cx
+                .background_executor()
+                .spawn(async move { read_image_metadata(&image) })
+                .await;
+            this.update(&mut cx, |this, cx| {
+                this.metadata_task = None;
+                match metadata {
+                    Ok(metadata) => {
+                        this.metadata = metadata;
+                        this.decode_error = None;
+                    }
+                    Err(error) => {
+                        this.metadata = None;
+                        this.decode_error = Some(format!("{error:#}").into());
+                    }
+                }
+                cx.emit(ImageItemEvent::MetadataComputed);
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    /// Shows new contents of the image, after it changed on disk. Edits made to the previous
+    /// contents no longer apply, so they are discarded.
+    fn set_image(&mut self, image: Arc<gpui::Image>, cx: &mut ModelContext<Self>) {
+        self.discard_edits(cx);
+        self.has_conflict = false;
+        self.saved_mtime = self.file.disk_state().mtime();
+        self.image = image;
+        self.metadata = None;
+        self.decode_error = None;
+        self.compute_metadata(cx);
+        cx.emit(ImageItemEvent::Reloaded);
+    }
+
     fn file_updated(&mut self, new_file: Arc<dyn File>, cx: &mut ModelContext<Self>) {
         let mut file_changed = false;
+        let mut contents_may_have_changed = false;
 
         let old_file = self.file.as_ref();
-        if new_file.path() != old_file.path() {
+        let old_path = old_file.path().clone();
+        let renamed = new_file.path() != &old_path;
+        if renamed {
             file_changed = true;
         }
 
@@ -79,34 +393,105 @@ impl ImageItem {
         if old_state != new_state {
             file_changed = true;
             if matches!(new_state, DiskState::Present { .. }) {
-                cx.emit(ImageItemEvent::ReloadNeeded)
+                contents_may_have_changed = true;
             }
         }
 
         self.file = new_file;
+        if renamed {
+            // The file may not resolve to the same target anymore, such as when a symlink to it
+            // was moved.
+            self.canonical_path = None;
+        }
+        if contents_may_have_changed {
+            self.check_for_changes(cx);
+        }
         if file_changed {
             cx.emit(ImageItemEvent::FileHandleChanged);
+            if renamed {
+                cx.emit(ImageItemEvent::Renamed { old_path });
+            }
             cx.notify();
         }
     }
 
+    /// Asks for the image to be reloaded if its contents on disk differ from the ones loaded.
+    ///
+    /// Files are often written without changing, such as when checking out a branch in which
+    /// they are the same, and comparing their hash is much cheaper than decoding them and
+    /// uploading them to the GPU again. Checks are at least `reload_debounce` apart, and a check
+    /// that is waiting to start reads whatever the file contains by then.
+    fn check_for_changes(&mut self, cx: &mut ModelContext<Self>) {
+        if self.check_for_changes_task.is_some() {
+            if self.checking_for_changes {
+                self.changed_while_checking = true;
+            }
+            return;
+        }
+
+        let Some((fs, abs_path)) = worktree::File::from_dyn(Some(&self.file)).and_then(|file| {
+            let worktree = file.worktree.read(cx).as_local()?;
+            Some((worktree.fs().clone(), worktree.absolutize(&file.path).ok()?))
+        }) else {
+            self.changed_on_disk(cx);
+            return;
+        };
+
+        let now = cx.background_executor().now();
+        let delay = self
+            .last_checked_for_changes
+            .map_or(Duration::ZERO, |last_checked| {
+                (last_checked + self.reload_debounce).saturating_duration_since(now)
+            });
+        // Large files are mapped rather than read, so that hashing them doesn't allocate a
+        // second copy of the image next to the one that is loaded.
+        self.check_for_changes_task = Some(cx.spawn(|this, mut cx| async move {
+            if !delay.is_zero() {
+                cx.background_executor().timer(delay).await;
+            }
+            this.update(&mut cx, |this, cx| {
+                this.checking_for_changes = true;
+                this.last_checked_for_changes = Some(cx.background_executor().now());
+            })
+            .ok();
+            let content_hash = cx
+                .background_executor()
+                .spawn(async move {
+                    let content = fs.load_mapped(&abs_path).await?;
+                    anyhow::Ok(content_hash(&content))
+                })
+                .await
+                .ok();
+            this.update(&mut cx, |this, cx| {
+                this.check_for_changes_task = None;
+                this.checking_for_changes = false;
+                if content_hash != Some(this.image.id) {
+                    this.changed_on_disk(cx);
+                } else {
+                    // Rewriting the same contents only moves the modification time forward.
+                    this.saved_mtime = this.file.disk_state().mtime();
+                }
+                if std::mem::take(&mut this.changed_while_checking) {
+                    this.check_for_changes(cx);
+                }
+            })
+            .ok();
+        }));
+    }
+
     fn reload(&mut self, cx: &mut ModelContext<Self>) -> Option<oneshot::Receiver<()>> {
         let local_file = self.file.as_local()?;
         let (tx, rx) = futures::channel::oneshot::channel();
 
         let content = local_file.load_bytes(cx);
         self.reload_task = Some(cx.spawn(|this, mut cx| async move {
-            if let Some(image) = content
-                .await
-                .context("Failed to load image content")
-                .and_then(create_gpui_image)
-                .log_err()
-            {
-                this.update(&mut cx, |this, cx| {
-                    this.image = image;
-                    cx.emit(ImageItemEvent::Reloaded);
-                })
-                .log_err();
+            let image = match content.await.context("Failed to load image content") {
+                Ok(content) => decode_image(content, &cx).await,
+                Err(error) => Err(error),
+            };
+            if let Some(image) = image.log_err() {
+                this.update(&mut cx, |this, cx| this.set_image(image, cx))
+                    .log_err();
             }
             _ = tx.send(());
         }));
@@ -123,17 +508,7 @@ impl crate::Item for ImageItem {
         let path = path.clone();
         let project = project.clone();
 
-        let ext = path
-            .path
-            .extension()
-            .and_then(OsStr::to_str)
-            .map(str::to_lowercase)
-            .unwrap_or_default();
-        let ext = ext.as_str();
-
-        // Only open the item if it's a binary image (no SVGs, etc.)
-        // Since we do not have a way to toggle to an editor
-        if Img::extensions().contains(&ext) && !ext.contains("svg") {
+        if is_image_path(&path.path) {
             Some(cx.spawn(|mut cx| async move {
                 project
                     .update(&mut cx, |project, cx| project.open_image(path, cx))?
@@ -149,15 +524,123 @@ impl crate::Item for ImageItem {
     }
 
     fn project_path(&self, cx: &AppContext) -> Option<ProjectPath> {
+        if !self.has_project_file() {
+            return None;
+        }
         Some(self.project_path(cx).clone())
     }
 }
 
+/// The file of an image created with [`ImageStore::create_image`], until it is saved.
+struct UntitledImageFile {
+    path: Arc<Path>,
+    worktree_id: WorktreeId,
+}
+
+impl File for UntitledImageFile {
+    fn as_local(&self) -> Option<&dyn language::LocalFile> {
+        None
+    }
+
+    fn disk_state(&self) -> DiskState {
+        DiskState::New
+    }
+
+    fn path(&self) -> &Arc<Path> {
+        &self.path
+    }
+
+    fn full_path(&self, _: &AppContext) -> PathBuf {
+        self.path.to_path_buf()
+    }
+
+    fn file_name<'a>(&'a self, _: &'a AppContext) -> &'a OsStr {
+        self.path.as_os_str()
+    }
+
+    fn worktree_id(&self, _: &AppContext) -> WorktreeId {
+        self.worktree_id
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn to_proto(&self, _: &AppContext) -> proto::File {
+        proto::File {
+            worktree_id: self.worktree_id.to_proto(),
+            entry_id: None,
+            path: self.path.to_string_lossy().into(),
+            mtime: None,
+            is_deleted: false,
+        }
+    }
+
+    fn is_private(&self) -> bool {
+        false
+    }
+}
+
+/// The file of an image opened from an entry of an archive with
+/// [`ImageStore::open_image_in_archive`].
+struct ArchiveEntryFile {
+    /// The archive's path followed by the entry's path inside it.
+    path: Arc<Path>,
+    archive: Arc<dyn File>,
+}
+
+impl File for ArchiveEntryFile {
+    fn as_local(&self) -> Option<&dyn language::LocalFile> {
+        None
+    }
+
+    fn disk_state(&self) -> DiskState {
+        self.archive.disk_state()
+    }
+
+    fn path(&self) -> &Arc<Path> {
+        &self.path
+    }
+
+    fn full_path(&self, cx: &AppContext) -> PathBuf {
+        let archive_path = self.archive.path();
+        let entry_path = self.path.strip_prefix(archive_path).unwrap_or(&self.path);
+        self.archive.full_path(cx).join(entry_path)
+    }
+
+    fn file_name<'a>(&'a self, _: &'a AppContext) -> &'a OsStr {
+        self.path.file_name().unwrap_or(self.path.as_os_str())
+    }
+
+    fn worktree_id(&self, cx: &AppContext) -> WorktreeId {
+        self.archive.worktree_id(cx)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn to_proto(&self, cx: &AppContext) -> proto::File {
+        proto::File {
+            worktree_id: self.archive.worktree_id(cx).to_proto(),
+            entry_id: None,
+            path: self.path.to_string_lossy().into(),
+            mtime: self.disk_state().mtime().map(|mtime| mtime.into()),
+            is_deleted: false,
+        }
+    }
+
+    fn is_private(&self) -> bool {
+        self.archive.is_private()
+    }
+}
+
 trait ImageStoreImpl {
     fn open_image(
         &self,
         path: Arc<Path>,
         worktree: Model<Worktree>,
+        progress: Arc<ImageLoadProgress>,
         cx: &mut ModelContext<ImageStore>,
     ) -> Task<Result<Model<ImageItem>>>;
 
@@ -168,26 +651,131 @@ trait ImageStoreImpl {
     ) -> Task<Result<()>>;
 
     fn as_local(&self) -> Option<Model<LocalImageStore>>;
+
+    fn as_remote(&self) -> Option<Model<RemoteImageStore>>;
+}
+
+struct RemoteImageStore {
+    upstream_client: AnyProtoClient,
+    project_id: u64,
+    /// The images the host is sending, by the id they are sent under.
+    loading_remote_images_by_id: HashMap<u64, ReceivingImage>,
+    /// The images received before anything waited for them.
+    received_images_by_id: HashMap<u64, Result<ReceivedImage>>,
+    remote_image_listeners: HashMap<u64, oneshot::Sender<Result<ReceivedImage>>>,
+}
+
+struct ReceivingImage {
+    file: proto::File,
+    content_size: usize,
+    content: Vec<u8>,
 }
 
-struct RemoteImageStore {}
+type ReceivedImage = (proto::File, Vec<u8>);
 
 struct LocalImageStore {
     local_image_ids_by_path: HashMap<ProjectPath, ImageId>,
     local_image_ids_by_entry_id: HashMap<ProjectEntryId, ImageId>,
+    image_cache: ImageCache,
+    /// The contents of the opened images by the canonical path of their file, so that a file
+    /// opened through several worktrees or symlinks is only read and decoded once.
+    decoded_images_by_canonical_path: HashMap<PathBuf, DecodedImage>,
+    /// The open images by the canonical path of their file, so that opening a file through a
+    /// symlink finds the image opened from its target and the other way around.
+    local_image_ids_by_canonical_path: HashMap<PathBuf, ImageId>,
     image_store: WeakModel<ImageStore>,
     _subscription: Subscription,
 }
 
+struct DecodedImage {
+    /// When the file was last modified as of decoding it.
+    mtime: MTime,
+    image: Weak<gpui::Image>,
+}
+
 pub struct ImageStore {
     state: Box<dyn ImageStoreImpl>,
     opened_images: HashMap<ImageId, WeakModel<ImageItem>>,
     worktree_store: Model<WorktreeStore>,
-    #[allow(clippy::type_complexity)]
-    loading_images_by_path: HashMap<
-        ProjectPath,
-        postage::watch::Receiver<Option<Result<Model<ImageItem>, Arc<anyhow::Error>>>>,
-    >,
+    /// The images being loaded, which stop loading once nothing waits for them anymore.
+    loading_images_by_path: HashMap<ProjectPath, WeakShared<LoadingImage>>,
+    load_progress_by_path: HashMap<ProjectPath, Arc<ImageLoadProgress>>,
+    /// The thumbnails loaded in this session by path and size, dropped when the image changes.
+    thumbnails: HashMap<(ProjectPath, u32), Arc<Thumbnail>>,
+    /// The thumbnails being generated, which stop generating once nothing waits for them anymore.
+    loading_thumbnails: HashMap<(ProjectPath, u32), LoadingThumbnail>,
+    thumbnail_queue: LoadQueue,
+    /// Limits how many images are read and decoded at once, so that opening many of them
+    /// together doesn't use up memory and CPU all at once.
+    image_load_queue: LoadQueue,
+    reload_debounce: Duration,
+    downstream_client: Option<(AnyProtoClient, u64)>,
+    next_image_transfer_id: u64,
+}
+
+/// How long to wait between reloads of an image that keeps changing on disk, unless configured
+/// otherwise.
+pub const DEFAULT_RELOAD_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// How many images are read and decoded at once.
+const MAX_CONCURRENT_IMAGE_LOADS: usize = 4;
+
+/// The size of the chunks images are sent to guests in, which keeps each message well below the
+/// maximum message size.
+const MAX_IMAGE_CHUNK_SIZE: usize = 256 * 1024;
+
+type LoadingImage = Task<Result<Model<ImageItem>, Arc<anyhow::Error>>>;
+
+/// How far an image has been loaded, updated from the background as its file is read and its
+/// contents are decoded.
+#[derive(Debug, Default)]
+pub struct ImageLoadProgress {
+    total_bytes: AtomicU64,
+    bytes_read: AtomicU64,
+    decoding: AtomicBool,
+}
+
+impl ImageLoadProgress {
+    fn new(total_bytes: Option<u64>) -> Self {
+        Self {
+            total_bytes: AtomicU64::new(total_bytes.unwrap_or(0)),
+            ..Default::default()
+        }
+    }
+
+    /// The size of the image's file, when it is known before reading it.
+    pub fn total_bytes(&self) -> Option<u64> {
+        Some(self.total_bytes.load(Ordering::Relaxed)).filter(|total_bytes| *total_bytes > 0)
+    }
+
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read.load(Ordering::Relaxed)
+    }
+
+    /// Whether the file was read and its contents are being decoded.
+    pub fn is_decoding(&self) -> bool {
+        self.decoding.load(Ordering::Relaxed)
+    }
+
+    /// The fraction of the file that was read, when its size is known.
+    pub fn fraction(&self) -> Option<f32> {
+        let total_bytes = self.total_bytes()?;
+        Some((self.bytes_read() as f64 / total_bytes as f64).min(1.) as f32)
+    }
+
+    fn set_bytes_read(&self, bytes_read: u64) {
+        self.bytes_read.store(bytes_read, Ordering::Relaxed);
+    }
+
+    fn set_decoding(&self) {
+        self.decoding.store(true, Ordering::Relaxed);
+    }
+}
+
+struct LoadingThumbnail {
+    task: WeakShared<Task<Result<Arc<Thumbnail>, Arc<anyhow::Error>>>>,
+    /// Whether anything waiting for the thumbnail shows it.
+    visible: Arc<AtomicBool>,
 }
 
 impl ImageStore {
@@ -207,30 +795,85 @@ impl ImageStore {
                 LocalImageStore {
                     local_image_ids_by_path: Default::default(),
                     local_image_ids_by_entry_id: Default::default(),
+                    image_cache: ImageCache::new(DEFAULT_IMAGE_CACHE_SIZE),
+                    decoded_images_by_canonical_path: Default::default(),
+                    local_image_ids_by_canonical_path: Default::default(),
                     image_store: this,
                     _subscription: subscription,
                 }
             })),
             opened_images: Default::default(),
             loading_images_by_path: Default::default(),
+            load_progress_by_path: Default::default(),
+            thumbnails: Default::default(),
+            loading_thumbnails: Default::default(),
+            thumbnail_queue: LoadQueue::new(MAX_CONCURRENT_THUMBNAILS),
+            image_load_queue: LoadQueue::new(MAX_CONCURRENT_IMAGE_LOADS),
+            reload_debounce: DEFAULT_RELOAD_DEBOUNCE,
+            downstream_client: None,
+            next_image_transfer_id: 0,
             worktree_store,
         }
     }
 
+    pub fn init(client: &AnyProtoClient) {
+        client.add_model_request_handler(Self::handle_open_image_by_path);
+    }
+
     pub fn remote(
         worktree_store: Model<WorktreeStore>,
-        _upstream_client: AnyProtoClient,
-        _remote_id: u64,
+        upstream_client: AnyProtoClient,
+        remote_id: u64,
         cx: &mut ModelContext<Self>,
     ) -> Self {
         Self {
-            state: Box::new(cx.new_model(|_| RemoteImageStore {})),
+            state: Box::new(cx.new_model(|_| RemoteImageStore {
+                upstream_client,
+                project_id: remote_id,
+                loading_remote_images_by_id: Default::default(),
+                received_images_by_id: Default::default(),
+                remote_image_listeners: Default::default(),
+            })),
             opened_images: Default::default(),
             loading_images_by_path: Default::default(),
+            load_progress_by_path: Default::default(),
+            thumbnails: Default::default(),
+            loading_thumbnails: Default::default(),
+            thumbnail_queue: LoadQueue::new(MAX_CONCURRENT_THUMBNAILS),
+            image_load_queue: LoadQueue::new(MAX_CONCURRENT_IMAGE_LOADS),
+            reload_debounce: DEFAULT_RELOAD_DEBOUNCE,
+            downstream_client: None,
+            next_image_transfer_id: 0,
             worktree_store,
         }
     }
 
+    pub fn shared(
+        &mut self,
+        remote_id: u64,
+        downstream_client: AnyProtoClient,
+        _cx: &mut AppContext,
+    ) {
+        self.downstream_client = Some((downstream_client, remote_id));
+    }
+
+    pub fn unshared(&mut self, _cx: &mut ModelContext<Self>) {
+        self.downstream_client.take();
+    }
+
+    /// Drops the images the host was still sending, which will never finish arriving.
+    pub fn disconnected_from_host(&mut self, cx: &mut AppContext) {
+        if let Some(remote) = self.state.as_remote() {
+            remote.update(cx, |remote, _| {
+                remote.loading_remote_images_by_id.clear();
+                remote.received_images_by_id.clear();
+                // Wake up all futures waiting on an image, to give them a chance to fail now
+                // that we've disconnected.
+                remote.remote_image_listeners.clear();
+            });
+        }
+    }
+
     pub fn images(&self) -> impl '_ + Iterator<Item = Model<ImageItem>> {
         self.opened_images
             .values()
@@ -244,107 +887,615 @@ impl ImageStore {
     }
 
     pub fn get_by_path(&self, path: &ProjectPath, cx: &AppContext) -> Option<Model<ImageItem>> {
-        self.images()
-            .find(|image| &image.read(cx).project_path(cx) == path)
+        self.images().find(|image| {
+            let image = image.read(cx);
+            !image.is_untitled() && &image.project_path(cx) == path
+        })
     }
 
-    pub fn open_image(
+    /// Creates an image from contents that aren't backed by a file, such as an image pasted
+    /// from the clipboard. It is untitled until saved with [`Self::save_image_as`].
+    pub fn create_image(
         &mut self,
-        project_path: ProjectPath,
+        content: Vec<u8>,
         cx: &mut ModelContext<Self>,
     ) -> Task<Result<Model<ImageItem>>> {
-        let existing_image = self.get_by_path(&project_path, cx);
-        if let Some(existing_image) = existing_image {
-            return Task::ready(Ok(existing_image));
-        }
+        // Untitled images belong to the first worktree so that its settings apply to them.
+        let worktree_id = self
+            .worktree_store
+            .read(cx)
+            .visible_worktrees(cx)
+            .next()
+            .map_or(WorktreeId::from_usize(0), |worktree| worktree.read(cx).id());
+        cx.spawn(|this, mut cx| async move {
+            let image = decode_image(content, &cx).await?;
+            let file = Arc::new(UntitledImageFile {
+                path: Path::new("Untitled")
+                    .with_extension(image.format.extension())
+                    .into(),
+                worktree_id,
+            });
+            let model = cx.new_model(|cx| ImageItem::new(file, image, None, cx))?;
+            this.update(&mut cx, |this, cx| this.add_image(model.clone(), cx))??;
+            Ok(model)
+        })
+    }
 
+    /// Lists the images inside an archive in the project, by their paths inside it.
+    pub fn archive_images(
+        &self,
+        archive: ProjectPath,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<Arc<Path>>>> {
         let Some(worktree) = self
             .worktree_store
             .read(cx)
-            .worktree_for_id(project_path.worktree_id, cx)
+            .worktree_for_id(archive.worktree_id, cx)
         else {
             return Task::ready(Err(anyhow::anyhow!("no such worktree")));
         };
-
-        let loading_watch = match self.loading_images_by_path.entry(project_path.clone()) {
-            // If the given path is already being loaded, then wait for that existing
-            // task to complete and return the same image.
-            hash_map::Entry::Occupied(e) => e.get().clone(),
-
-            // Otherwise, record the fact that this path is now being loaded.
-            hash_map::Entry::Vacant(entry) => {
-                let (mut tx, rx) = postage::watch::channel();
-                entry.insert(rx.clone());
-
-                let project_path = project_path.clone();
-                let load_image = self
-                    .state
-                    .open_image(project_path.path.clone(), worktree, cx);
-
-                cx.spawn(move |this, mut cx| async move {
-                    let load_result = load_image.await;
-                    *tx.borrow_mut() = Some(this.update(&mut cx, |this, _cx| {
-                        // Record the fact that the image is no longer loading.
-                        this.loading_images_by_path.remove(&project_path);
-                        let image = load_result.map_err(Arc::new)?;
-                        Ok(image)
-                    })?);
-                    anyhow::Ok(())
-                })
-                .detach();
-                rx
-            }
-        };
-
+        let load = worktree.update(cx, |worktree, cx| {
+            worktree.load_binary_file(&archive.path, cx)
+        });
         cx.background_executor().spawn(async move {
-            Self::wait_for_loading_image(loading_watch)
-                .await
-                .map_err(|e| e.cloned())
+            let LoadedBinaryFile { content, .. } = load.await?;
+            image_archives::list_images(content).await
         })
     }
 
-    pub async fn wait_for_loading_image(
-        mut receiver: postage::watch::Receiver<
-            Option<Result<Model<ImageItem>, Arc<anyhow::Error>>>,
-        >,
-    ) -> Result<Model<ImageItem>, Arc<anyhow::Error>> {
-        loop {
-            if let Some(result) = receiver.borrow().as_ref() {
-                match result {
-                    Ok(image) => return Ok(image.to_owned()),
-                    Err(e) => return Err(e.to_owned()),
-                }
-            }
-            receiver.next().await;
+    /// Opens an image stored inside an archive in the project, under the archive's path
+    /// followed by the image's path inside it. The image can't be saved back into the archive,
+    /// only saved as a new file.
+    pub fn open_image_in_archive(
+        &mut self,
+        archive: ProjectPath,
+        entry_path: Arc<Path>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Model<ImageItem>>> {
+        let virtual_path = ProjectPath {
+            worktree_id: archive.worktree_id,
+            path: image_archives::virtual_path(&archive.path, &entry_path).into(),
+        };
+        if let Some(existing_image) = self.get_by_path(&virtual_path, cx) {
+            return Task::ready(Ok(existing_image));
         }
+        let Some(worktree) = self
+            .worktree_store
+            .read(cx)
+            .worktree_for_id(archive.worktree_id, cx)
+        else {
+            return Task::ready(Err(anyhow::anyhow!("no such worktree")));
+        };
+        let load = worktree.update(cx, |worktree, cx| {
+            worktree.load_binary_file(&archive.path, cx)
+        });
+        cx.spawn(|this, mut cx| async move {
+            let LoadedBinaryFile { file, content } = load.await?;
+            let content = cx
+                .background_executor()
+                .spawn(async move { image_archives::read_entry(content, &entry_path).await })
+                .await?;
+            let image = decode_image(content, &cx).await?;
+            let file = Arc::new(ArchiveEntryFile {
+                path: virtual_path.path,
+                archive: file,
+            });
+            let model = cx.new_model(|cx| ImageItem::new(file, image, None, cx))?;
+            this.update(&mut cx, |this, cx| this.add_image(model.clone(), cx))??;
+            Ok(model)
+        })
     }
 
-    pub fn reload_images(
-        &self,
-        images: HashSet<Model<ImageItem>>,
-        cx: &mut ModelContext<ImageStore>,
+    /// Writes an image with its edits applied to its file.
+    pub fn save_image(
+        &mut self,
+        image: Model<ImageItem>,
+        cx: &mut ModelContext<Self>,
     ) -> Task<Result<()>> {
-        if images.is_empty() {
-            return Task::ready(Ok(()));
+        if !image.read(cx).has_project_file() {
+            return Task::ready(Err(anyhow::anyhow!(
+                "untitled images and images inside archives must be saved as a file"
+            )));
         }
-
-        self.state.reload_images(images, cx)
+        let project_path = image.read(cx).project_path(cx);
+        self.save_image_as(image, project_path, cx)
     }
 
-    fn add_image(
+    /// Writes an image with its edits applied to the given path and makes it the image's file,
+    /// after which the image is no longer untitled.
+    ///
+    /// Edited images are encoded again in their format, and show the saved contents afterwards.
+    pub fn save_image_as(
         &mut self,
         image: Model<ImageItem>,
-        cx: &mut ModelContext<ImageStore>,
-    ) -> Result<()> {
-        let image_id = image.read(cx).id;
-
-        self.opened_images.insert(image_id, image.downgrade());
+        project_path: ProjectPath,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let Some(worktree) = self
+            .worktree_store
+            .read(cx)
+            .worktree_for_id(project_path.worktree_id, cx)
+        else {
+            return Task::ready(Err(anyhow::anyhow!("no such worktree")));
+        };
+        let (content, edits) = {
+            let image = image.read(cx);
+            (image.image.clone(), image.edits.clone())
+        };
+        cx.spawn(|_, mut cx| async move {
+            let edited = if edits.is_empty() {
+                None
+            } else {
+                let original = content.clone();
+                let content = cx
+                    .background_executor()
+                    .spawn(async move { image_edits::apply_edits(&original, &edits) })
+                    .await?;
+                Some((decode_image(content, &cx).await?, edits.len()))
+            };
+            let write = worktree.update(&mut cx, |worktree, cx| {
+                let content = match &edited {
+                    Some((edited, _)) => edited.bytes.clone(),
+                    None => content.bytes.clone(),
+                };
+                worktree.write_binary_file(&project_path.path, content, cx)
+            })?;
+            let file = write.await?;
+            image.update(&mut cx, |image, cx| {
+                // Edits made while saving are kept, and apply to the saved contents.
+                if let Some((edited, saved_edit_count)) = edited {
+                    image.edits.drain(..saved_edit_count.min(image.edits.len()));
+                    image.image = edited;
+                    image.metadata = None;
+                    image.compute_metadata(cx);
+                    cx.emit(ImageItemEvent::Reloaded);
+                }
+                image.has_conflict = false;
+                image.saved_mtime = file.disk_state().mtime();
+                image.file_updated(file, cx);
+                cx.emit(ImageItemEvent::Saved);
+                cx.notify();
+            })?;
+            Ok(())
+        })
+    }
+
+    /// Returns how far the image at the given path has been loaded, while it is loading.
+    pub fn load_progress(&self, project_path: &ProjectPath) -> Option<Arc<ImageLoadProgress>> {
+        self.loading_images_by_path.get(project_path)?.upgrade()?;
+        self.load_progress_by_path.get(project_path).cloned()
+    }
+
+    pub fn open_image(
+        &mut self,
+        project_path: ProjectPath,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Model<ImageItem>>> {
+        let existing_image = self.get_by_path(&project_path, cx);
+        if let Some(existing_image) = existing_image {
+            return Task::ready(Ok(existing_image));
+        }
+
+        let Some(worktree) = self
+            .worktree_store
+            .read(cx)
+            .worktree_for_id(project_path.worktree_id, cx)
+        else {
+            return Task::ready(Err(anyhow::anyhow!("no such worktree")));
+        };
+
+        let loading_image = match self
+            .loading_images_by_path
+            .get(&project_path)
+            .and_then(|loading_image| loading_image.upgrade())
+        {
+            // If the given path is already being loaded, then wait for that existing
+            // task to complete and return the same image.
+            Some(loading_image) => loading_image,
+
+            // Otherwise, record the fact that this path is now being loaded. The load is
+            // cancelled when every task waiting for it is dropped, such as when the tab that
+            // requested it is closed.
+            None => {
+                let wait_for_turn = self
+                    .image_load_queue
+                    .wait_for_turn(Arc::new(AtomicBool::new(false)));
+                let total_bytes = worktree
+                    .read(cx)
+                    .entry_for_path(&project_path.path)
+                    .map(|entry| entry.size);
+                let progress = Arc::new(ImageLoadProgress::new(total_bytes));
+                self.load_progress_by_path.retain(|path, _| {
+                    self.loading_images_by_path
+                        .get(path)
+                        .is_some_and(|loading_image| loading_image.upgrade().is_some())
+                });
+                self.load_progress_by_path
+                    .insert(project_path.clone(), progress.clone());
+                let loading_image = cx
+                    .spawn({
+                        let project_path = project_path.clone();
+                        move |this, mut cx| async move {
+                            let load_result = async {
+                                let _turn = wait_for_turn
+                                    .await
+                                    .context("image load queue was dropped")?;
+                                this.update(&mut cx, |this, cx| {
+                                    this.state.open_image(
+                                        project_path.path.clone(),
+                                        worktree,
+                                        progress,
+                                        cx,
+                                    )
+                                })?
+                                .await
+                            }
+                            .await;
+                            this.update(&mut cx, |this, _cx| {
+                                // Record the fact that the image is no longer loading.
+                                this.loading_images_by_path.remove(&project_path);
+                                this.load_progress_by_path.remove(&project_path);
+                            })
+                            .ok();
+                            load_result.map_err(Arc::new)
+                        }
+                    })
+                    .shared();
+                if let Some(weak_loading_image) = loading_image.downgrade() {
+                    self.loading_images_by_path
+                        .insert(project_path, weak_loading_image);
+                }
+                loading_image
+            }
+        };
+
+        cx.background_executor()
+            .spawn(async move { loading_image.await.map_err(|e| e.cloned()) })
+    }
+
+    /// Loads a small copy of the image at the given path that fits in a square of `size`
+    /// pixels, generating it in the background the first time it is asked for.
+    ///
+    /// Only a few thumbnails are generated at once, visible ones first. Generation stops if
+    /// every returned task is dropped before it is done.
+    pub fn load_thumbnail(
+        &mut self,
+        project_path: ProjectPath,
+        size: u32,
+        priority: ThumbnailPriority,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Arc<Thumbnail>>> {
+        let key = (project_path, size);
+        if let Some(thumbnail) = self.thumbnails.get(&key) {
+            return Task::ready(Ok(thumbnail.clone()));
+        }
+
+        let visible = priority == ThumbnailPriority::Visible;
+        let loading_thumbnail = match self.loading_thumbnails.get(&key).and_then(|loading| {
+            let task = loading.task.upgrade()?;
+            Some((task, loading.visible.clone()))
+        }) {
+            // Share the thumbnail that is already being generated, moving it up the queue if it
+            // is now visible.
+            Some((task, is_visible)) => {
+                if visible {
+                    is_visible.store(true, Ordering::SeqCst);
+                }
+                task
+            }
+            None => {
+                let is_visible = Arc::new(AtomicBool::new(visible));
+                let task = self.generate_thumbnail(key.clone(), is_visible.clone(), cx);
+                if let Some(weak_task) = task.downgrade() {
+                    self.loading_thumbnails.insert(
+                        key,
+                        LoadingThumbnail {
+                            task: weak_task,
+                            visible: is_visible,
+                        },
+                    );
+                }
+                task
+            }
+        };
+        cx.background_executor()
+            .spawn(async move { loading_thumbnail.await.map_err(|e| e.cloned()) })
+    }
+
+    fn generate_thumbnail(
+        &mut self,
+        key: (ProjectPath, u32),
+        visible: Arc<AtomicBool>,
+        cx: &mut ModelContext<Self>,
+    ) -> Shared<Task<Result<Arc<Thumbnail>, Arc<anyhow::Error>>>> {
+        let wait_for_turn = self.thumbnail_queue.wait_for_turn(visible);
+        cx.spawn(|this, mut cx| async move {
+            let _turn = wait_for_turn
+                .await
+                .ok_or_else(|| Arc::new(anyhow::anyhow!("thumbnail queue was dropped")))?;
+            let (project_path, size) = key.clone();
+            let load_content = this
+                .update(&mut cx, |this, cx| {
+                    this.load_thumbnail_content(project_path, cx)
+                })
+                .map_err(Arc::new)?;
+            let (abs_path, content) = load_content.await.map_err(Arc::new)?;
+            let thumbnail = cx
+                .background_executor()
+                .spawn(async move {
+                    thumbnails::load_or_generate(abs_path.as_deref(), content, size).await
+                })
+                .await
+                .map_err(Arc::new)?;
+            let thumbnail = Arc::new(thumbnail);
+            this.update(&mut cx, |this, _| {
+                this.loading_thumbnails.remove(&key);
+                this.thumbnails.insert(key, thumbnail.clone());
+            })
+            .ok();
+            Ok(thumbnail)
+        })
+        .shared()
+    }
+
+    /// Reads the contents of an image to make a thumbnail of, along with its absolute path if
+    /// it is on the local file system.
+    fn load_thumbnail_content(
+        &mut self,
+        project_path: ProjectPath,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<(Option<PathBuf>, MappedFile)>> {
+        let local_file = self
+            .worktree_store
+            .read(cx)
+            .worktree_for_id(project_path.worktree_id, cx)
+            .and_then(|worktree| {
+                let worktree = worktree.read(cx).as_local()?;
+                Some((
+                    worktree.fs().clone(),
+                    worktree.absolutize(&project_path.path),
+                ))
+            });
+        // Remote worktrees can't load binary files, so their images are opened instead.
+        match local_file {
+            Some((fs, abs_path)) => cx.background_executor().spawn(async move {
+                let abs_path = abs_path?;
+                // Large images are mapped rather than read, as only their header is needed
+                // when their thumbnail is already stored.
+                let content = fs.load_mapped(&abs_path).await?;
+                anyhow::Ok((Some(abs_path), content))
+            }),
+            None => {
+                let open_image = self.open_image(project_path, cx);
+                cx.spawn(|_, cx| async move {
+                    let image = open_image.await?;
+                    let content = image.read_with(&cx, |image, _| image.image.bytes.clone())?;
+                    anyhow::Ok((None, MappedFile::Loaded(content)))
+                })
+            }
+        }
+    }
+
+    /// Sets how long to wait between reloads of an image that keeps changing on disk.
+    pub fn set_reload_debounce(&mut self, debounce: Duration, cx: &mut ModelContext<Self>) {
+        self.reload_debounce = debounce;
+        for image in self.images().collect::<Vec<_>>() {
+            image.update(cx, |image, _| image.reload_debounce = debounce);
+        }
+    }
+
+    /// How the cache of recently opened images is used, for local projects.
+    pub fn image_cache_stats(&self, cx: &AppContext) -> Option<ImageCacheStats> {
+        let local = self.state.as_local()?;
+        Some(local.read(cx).image_cache.stats())
+    }
+
+    /// Sets how many bytes of recently opened images are kept in memory after they are closed.
+    pub fn set_image_cache_size(&self, size: usize, cx: &mut ModelContext<Self>) {
+        if let Some(local) = self.state.as_local() {
+            local.update(cx, |local, _| local.image_cache.set_budget(size));
+        }
+    }
+
+    pub fn reload_images(
+        &self,
+        images: HashSet<Model<ImageItem>>,
+        cx: &mut ModelContext<ImageStore>,
+    ) -> Task<Result<()>> {
+        if images.is_empty() {
+            return Task::ready(Ok(()));
+        }
+
+        self.state.reload_images(images, cx)
+    }
+
+    /// Loads the version of the given image that is staged in its git repository's index.
+    ///
+    /// Resolves to `None` when the image is not inside a repository or is not tracked.
+    pub fn load_index_image(
+        &self,
+        image: &Model<ImageItem>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Option<Arc<gpui::Image>>>> {
+        let repository = match image_repository(image, cx) {
+            Ok(Some(repository)) => repository,
+            Ok(None) => return Task::ready(Ok(None)),
+            Err(error) => return Task::ready(Err(error)),
+        };
+
+        cx.background_executor().spawn(async move {
+            let (repo, relative_path) = repository;
+            repo.load_index_bytes(&relative_path)
+                .map(create_gpui_image)
+                .transpose()
+        })
+    }
+
+    /// Lists the commits that changed the given image, newest first.
+    ///
+    /// Resolves to an empty list when the image is not inside a repository.
+    pub fn load_image_history(
+        &self,
+        image: &Model<ImageItem>,
+        limit: usize,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<FileHistoryEntry>>> {
+        let repository = match image_repository(image, cx) {
+            Ok(Some(repository)) => repository,
+            Ok(None) => return Task::ready(Ok(Vec::new())),
+            Err(error) => return Task::ready(Err(error)),
+        };
+
+        cx.background_executor().spawn(async move {
+            let (repo, relative_path) = repository;
+            repo.file_history(&relative_path, limit)
+        })
+    }
+
+    /// Loads the version of the given image that was committed in `sha`.
+    ///
+    /// Resolves to `None` when the image did not exist in that commit.
+    pub fn load_committed_image(
+        &self,
+        image: &Model<ImageItem>,
+        sha: Oid,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Option<Arc<gpui::Image>>>> {
+        let repository = match image_repository(image, cx) {
+            Ok(Some(repository)) => repository,
+            Ok(None) => return Task::ready(Ok(None)),
+            Err(error) => return Task::ready(Err(error)),
+        };
+
+        cx.background_executor().spawn(async move {
+            let (repo, relative_path) = repository;
+            repo.load_committed_bytes(sha, &relative_path)?
+                .map(create_gpui_image)
+                .transpose()
+        })
+    }
+
+    pub async fn handle_open_image_by_path(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::OpenImageByPath>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::OpenImageResponse> {
+        let peer_id = envelope.original_sender_id().unwrap_or_default();
+        let project_path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(envelope.payload.worktree_id),
+            path: PathBuf::from(envelope.payload.path).into(),
+        };
+        let image = this
+            .update(&mut cx, |this, cx| this.open_image(project_path, cx))?
+            .await?;
+        let image_id = this.update(&mut cx, |this, cx| {
+            this.send_image_to_peer(&image, peer_id, cx)
+        })??;
+        Ok(proto::OpenImageResponse { image_id })
+    }
+
+    /// Sends the file and content of an image to a peer, returning the id it is sent under.
+    ///
+    /// The content is sent in chunks in the background, after the file and the size of the
+    /// content, since images can be larger than the maximum message size.
+    fn send_image_to_peer(
+        &mut self,
+        image: &Model<ImageItem>,
+        peer_id: proto::PeerId,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<u64> {
+        let (client, project_id) = self
+            .downstream_client
+            .clone()
+            .context("project is not shared")?;
+        let image_id = post_inc(&mut self.next_image_transfer_id);
+        let (file, content) = {
+            let image = image.read(cx);
+            (image.file.to_proto(cx), image.image.clone())
+        };
+
+        client.send(proto::CreateImageForPeer {
+            project_id,
+            peer_id: Some(peer_id),
+            variant: Some(proto::create_image_for_peer::Variant::State(
+                proto::ImageState {
+                    id: image_id,
+                    file: Some(file),
+                    content_size: content.bytes.len() as u64,
+                },
+            )),
+        })?;
+        cx.background_executor()
+            .spawn(async move {
+                // Empty images are still sent as a single, empty chunk.
+                let chunk_count = content.bytes.len().div_ceil(MAX_IMAGE_CHUNK_SIZE).max(1);
+                for (ix, start) in (0..chunk_count)
+                    .map(|ix| ix * MAX_IMAGE_CHUNK_SIZE)
+                    .enumerate()
+                {
+                    let end = (start + MAX_IMAGE_CHUNK_SIZE).min(content.bytes.len());
+                    client.send(proto::CreateImageForPeer {
+                        project_id,
+                        peer_id: Some(peer_id),
+                        variant: Some(proto::create_image_for_peer::Variant::Chunk(
+                            proto::ImageChunk {
+                                image_id,
+                                data: content.bytes[start..end].to_vec(),
+                                is_last: ix + 1 == chunk_count,
+                            },
+                        )),
+                    })?;
+                }
+                anyhow::Ok(())
+            })
+            .detach_and_log_err(cx);
+        Ok(image_id)
+    }
+
+    pub fn handle_create_image_for_peer(
+        &mut self,
+        envelope: TypedEnvelope<proto::CreateImageForPeer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        let remote = self
+            .state
+            .as_remote()
+            .context("received an image while hosting the project")?;
+        remote.update(cx, |remote, _| {
+            remote.handle_create_image_for_peer(envelope)
+        })
+    }
+
+    fn add_image(
+        &mut self,
+        image: Model<ImageItem>,
+        cx: &mut ModelContext<ImageStore>,
+    ) -> Result<()> {
+        let image_id = image.read(cx).id;
+        let reload_debounce = self.reload_debounce;
+        image.update(cx, |image, cx| {
+            image.reload_debounce = reload_debounce;
+            image.compute_metadata(cx);
+        });
+
+        self.opened_images.insert(image_id, image.downgrade());
 
         cx.subscribe(&image, Self::on_image_event).detach();
+        cx.observe_release(&image, move |this, _, cx| this.image_released(image_id, cx))
+            .detach();
         cx.emit(ImageStoreEvent::ImageAdded(image));
         Ok(())
     }
 
+    /// Forgets an image once nothing refers to it anymore, such as after the last view showing
+    /// it was closed, so that the store doesn't accumulate entries for closed images.
+    fn image_released(&mut self, image_id: ImageId, cx: &mut ModelContext<Self>) {
+        self.opened_images.remove(&image_id);
+        if let Some(local) = self.state.as_local() {
+            local.update(cx, |local, _| local.forget_image(image_id));
+        }
+    }
+
     fn on_image_event(
         &mut self,
         image: Model<ImageItem>,
@@ -369,22 +1520,59 @@ impl ImageStoreImpl for Model<LocalImageStore> {
         &self,
         path: Arc<Path>,
         worktree: Model<Worktree>,
+        progress: Arc<ImageLoadProgress>,
         cx: &mut ModelContext<ImageStore>,
     ) -> Task<Result<Model<ImageItem>>> {
         let this = self.clone();
 
-        let load_file = worktree.update(cx, |worktree, cx| {
-            worktree.load_binary_file(path.as_ref(), cx)
+        let cached_image = self.update(cx, |this, cx| this.cached_image(&worktree, &path, cx));
+        let canonicalize = worktree.read(cx).as_local().and_then(|local_worktree| {
+            let fs = local_worktree.fs().clone();
+            let abs_path = local_worktree.absolutize(&path).ok()?;
+            Some(async move { fs.canonicalize(&abs_path).await.ok() })
         });
         cx.spawn(move |image_store, mut cx| async move {
-            let LoadedBinaryFile { file, content } = load_file.await?;
-            let image = create_gpui_image(content)?;
-
-            let model = cx.new_model(|cx| ImageItem {
-                id: cx.entity_id().as_non_zero_u64().into(),
-                file: file.clone(),
-                image,
-                reload_task: None,
+            let canonical_path = match canonicalize {
+                Some(canonicalize) => canonicalize.await,
+                None => None,
+            };
+            if let Some(canonical_path) = &canonical_path {
+                let existing_image = this.update(&mut cx, |this, cx| {
+                    this.image_for_canonical_path(canonical_path, cx)
+                })?;
+                if let Some(existing_image) = existing_image {
+                    return Ok(existing_image);
+                }
+            }
+            let decoded_image = match (&cached_image, &canonical_path) {
+                (None, Some(canonical_path)) => this.update(&mut cx, |this, cx| {
+                    this.decoded_image(canonical_path, &worktree, &path, cx)
+                })?,
+                _ => None,
+            };
+            let (file, image) = match cached_image.or(decoded_image) {
+                Some(loaded_image) => loaded_image,
+                None => {
+                    let LoadedBinaryFile { file, content } = worktree
+                        .update(&mut cx, |worktree, cx| {
+                            let progress = progress.clone();
+                            worktree.load_binary_file_with_progress(
+                                path.as_ref(),
+                                move |bytes_read| progress.set_bytes_read(bytes_read),
+                                cx,
+                            )
+                        })?
+                        .await?;
+                    progress.set_decoding();
+                    (file, decode_image(content, &cx).await?)
+                }
+            };
+
+            let model = cx.new_model(|cx| {
+                let mut image_item =
+                    ImageItem::new(file.clone(), image, file.disk_state().mtime(), cx);
+                image_item.canonical_path = canonical_path.clone().map(Arc::from);
+                image_item
             })?;
 
             let image_id = cx.read_model(&model, |model, _| model.id)?;
@@ -404,6 +1592,12 @@ impl ImageStoreImpl for Model<LocalImageStore> {
                 if let Some(entry_id) = file.entry_id {
                     this.local_image_ids_by_entry_id.insert(entry_id, image_id);
                 }
+                this.cache_image(&model, cx);
+                if let Some(canonical_path) = canonical_path {
+                    this.local_image_ids_by_canonical_path
+                        .insert(canonical_path.clone(), image_id);
+                    this.record_decoded_image(canonical_path, &model, cx);
+                }
 
                 anyhow::Ok(())
             })??;
@@ -417,10 +1611,12 @@ impl ImageStoreImpl for Model<LocalImageStore> {
         images: HashSet<Model<ImageItem>>,
         cx: &mut ModelContext<ImageStore>,
     ) -> Task<Result<()>> {
+        let this = self.clone();
         cx.spawn(move |_, mut cx| async move {
             for image in images {
                 if let Some(rec) = image.update(&mut cx, |image, cx| image.reload(cx))? {
-                    rec.await?
+                    rec.await?;
+                    this.update(&mut cx, |this, cx| this.cache_image(&image, cx))?;
                 }
             }
             Ok(())
@@ -430,9 +1626,114 @@ impl ImageStoreImpl for Model<LocalImageStore> {
     fn as_local(&self) -> Option<Model<LocalImageStore>> {
         Some(self.clone())
     }
+
+    fn as_remote(&self) -> Option<Model<RemoteImageStore>> {
+        None
+    }
 }
 
 impl LocalImageStore {
+    /// The cached contents of the image at the given path, if it hasn't changed on disk since
+    /// they were cached.
+    fn cached_image(
+        &mut self,
+        worktree: &Model<Worktree>,
+        path: &Path,
+        cx: &AppContext,
+    ) -> Option<(Arc<worktree::File>, Arc<gpui::Image>)> {
+        let entry = worktree.read(cx).entry_for_path(path)?;
+        let project_path = ProjectPath {
+            worktree_id: worktree.read(cx).id(),
+            path: entry.path.clone(),
+        };
+        let image = self.image_cache.get(&project_path, entry.mtime?)?;
+        Some((
+            worktree::File::for_entry(entry.clone(), worktree.clone()),
+            image,
+        ))
+    }
+
+    fn forget_image(&mut self, image_id: ImageId) {
+        self.local_image_ids_by_path.retain(|_, id| *id != image_id);
+        self.local_image_ids_by_entry_id
+            .retain(|_, id| *id != image_id);
+        self.local_image_ids_by_canonical_path
+            .retain(|_, id| *id != image_id);
+        self.decoded_images_by_canonical_path
+            .retain(|_, decoded_image| decoded_image.image.strong_count() > 0);
+    }
+
+    /// The open image whose file has the given canonical path, if it still resolves to it.
+    fn image_for_canonical_path(
+        &mut self,
+        canonical_path: &Path,
+        cx: &AppContext,
+    ) -> Option<Model<ImageItem>> {
+        let image_id = *self.local_image_ids_by_canonical_path.get(canonical_path)?;
+        let image = self
+            .image_store
+            .upgrade()
+            .and_then(|image_store| image_store.read(cx).get(image_id))
+            .filter(|image| {
+                image.read(cx).canonical_path.as_deref() == Some(canonical_path)
+                    && image.read(cx).file.disk_state() != DiskState::Deleted
+            });
+        if image.is_none() {
+            self.local_image_ids_by_canonical_path
+                .remove(canonical_path);
+        }
+        image
+    }
+
+    /// The contents of an open image whose file has the given canonical path, if it hasn't
+    /// changed on disk since they were decoded.
+    fn decoded_image(
+        &self,
+        canonical_path: &Path,
+        worktree: &Model<Worktree>,
+        path: &Path,
+        cx: &AppContext,
+    ) -> Option<(Arc<worktree::File>, Arc<gpui::Image>)> {
+        let decoded_image = self.decoded_images_by_canonical_path.get(canonical_path)?;
+        let entry = worktree.read(cx).entry_for_path(path)?;
+        if entry.mtime? != decoded_image.mtime {
+            return None;
+        }
+        Some((
+            worktree::File::for_entry(entry.clone(), worktree.clone()),
+            decoded_image.image.upgrade()?,
+        ))
+    }
+
+    fn record_decoded_image(
+        &mut self,
+        canonical_path: PathBuf,
+        image: &Model<ImageItem>,
+        cx: &AppContext,
+    ) {
+        let image = image.read(cx);
+        let Some(mtime) = image.file.disk_state().mtime() else {
+            return;
+        };
+        self.decoded_images_by_canonical_path
+            .retain(|_, decoded_image| decoded_image.image.strong_count() > 0);
+        self.decoded_images_by_canonical_path.insert(
+            canonical_path,
+            DecodedImage {
+                mtime,
+                image: Arc::downgrade(&image.image),
+            },
+        );
+    }
+
+    fn cache_image(&mut self, image: &Model<ImageItem>, cx: &AppContext) {
+        let image = image.read(cx);
+        if let Some(mtime) = image.file.disk_state().mtime() {
+            self.image_cache
+                .insert(image.project_path(cx), mtime, image.image.clone());
+        }
+    }
+
     fn subscribe_to_worktree(&mut self, worktree: &Model<Worktree>, cx: &mut ModelContext<Self>) {
         cx.subscribe(worktree, |this, worktree, event, cx| {
             if worktree.read(cx).is_local() {
@@ -454,6 +1755,20 @@ impl LocalImageStore {
         cx: &mut ModelContext<Self>,
     ) {
         let snapshot = worktree_handle.read(cx).snapshot();
+        self.image_store
+            .update(cx, |image_store, _| {
+                let unchanged = |(project_path, _): &(ProjectPath, u32)| {
+                    project_path.worktree_id != snapshot.id()
+                        || !changes
+                            .iter()
+                            .any(|(path, _, _)| *path == project_path.path)
+                };
+                image_store.thumbnails.retain(|key, _| unchanged(key));
+                image_store
+                    .loading_thumbnails
+                    .retain(|key, _| unchanged(key));
+            })
+            .ok();
         for (path, entry_id, _) in changes {
             self.local_worktree_entry_changed(*entry_id, path, worktree_handle, &snapshot, cx);
         }
@@ -590,91 +1905,537 @@ impl LocalImageStore {
     }
 }
 
-fn create_gpui_image(content: Vec<u8>) -> anyhow::Result<Arc<gpui::Image>> {
-    let format = image::guess_format(&content)?;
+/// Whether the file at `path` can be opened as an [`ImageItem`].
+///
+/// Only binary images are supported (no SVGs, etc.), since we do not have a
+/// way to toggle those to an editor.
+pub fn is_image_path(path: &Path) -> bool {
+    let ext = path
+        .extension()
+        .and_then(OsStr::to_str)
+        .map(str::to_lowercase)
+        .unwrap_or_default();
+    let ext = ext.as_str();
+
+    Img::extensions().contains(&ext) && !ext.contains("svg")
+}
 
-    Ok(Arc::new(gpui::Image {
-        id: hash(&content),
-        format: match format {
-            image::ImageFormat::Png => gpui::ImageFormat::Png,
-            image::ImageFormat::Jpeg => gpui::ImageFormat::Jpeg,
-            image::ImageFormat::WebP => gpui::ImageFormat::Webp,
-            image::ImageFormat::Gif => gpui::ImageFormat::Gif,
-            image::ImageFormat::Bmp => gpui::ImageFormat::Bmp,
-            image::ImageFormat::Tiff => gpui::ImageFormat::Tiff,
-            _ => Err(anyhow::anyhow!("Image format not supported"))?,
-        },
-        bytes: content,
-    }))
+/// Returns the pixel dimensions of an image, reading only its header.
+///
+/// SVGs are measured at their intrinsic size, which is what they are rasterized at.
+pub fn image_dimensions(image: &gpui::Image) -> Option<(u32, u32)> {
+    content_dimensions(image.format, &image.bytes)
 }
 
-impl ImageStoreImpl for Model<RemoteImageStore> {
-    fn open_image(
-        &self,
-        _path: Arc<Path>,
-        _worktree: Model<Worktree>,
-        _cx: &mut ModelContext<ImageStore>,
-    ) -> Task<Result<Model<ImageItem>>> {
-        Task::ready(Err(anyhow::anyhow!(
-            "Opening images from remote is not supported"
-        )))
-    }
+/// Reads the metadata of an image. Counting the frames of animations decodes them, so this is
+/// best done in the background.
+pub fn image_metadata(image: &gpui::Image) -> Option<ImageMetadata> {
+    read_image_metadata(image).ok().flatten()
+}
 
-    fn reload_images(
-        &self,
-        _images: HashSet<Model<ImageItem>>,
-        _cx: &mut ModelContext<ImageStore>,
-    ) -> Task<Result<()>> {
-        Task::ready(Err(anyhow::anyhow!(
-            "Reloading images from remote is not supported"
-        )))
-    }
+/// Reads the metadata of an image, failing with the decoder's error when its contents are
+/// invalid. HEIC images are decoded by the platform, so their metadata can't be read.
+pub fn read_image_metadata(image: &gpui::Image) -> Result<Option<ImageMetadata>> {
+    use image::ImageDecoder as _;
 
-    fn as_local(&self) -> Option<Model<LocalImageStore>> {
-        None
+    if image.format == gpui::ImageFormat::Heic {
+        return Ok(None);
     }
+    if image.format == gpui::ImageFormat::Svg {
+        let tree = usvg::Tree::from_data(&image.bytes, &usvg::Options::default())
+            .context("Failed to parse SVG")?;
+        return Ok(Some(ImageMetadata {
+            width: tree.size().width().round() as u32,
+            height: tree.size().height().round() as u32,
+            frame_count: 1,
+            color_type: None,
+        }));
+    }
+
+    let content = image.bytes.as_slice();
+    let decoder = image::ImageReader::new(std::io::Cursor::new(content))
+        .with_guessed_format()?
+        .into_decoder()
+        .context("Failed to read image header")?;
+    let (width, height) = decoder.dimensions();
+    let color_type = decoder.color_type();
+    Ok(Some(ImageMetadata {
+        width,
+        height,
+        frame_count: frame_count(image.format, content).context("Failed to decode frames")?,
+        color_type: Some(color_type),
+    }))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use fs::FakeFs;
-    use gpui::TestAppContext;
-    use serde_json::json;
-    use settings::SettingsStore;
-    use std::path::PathBuf;
+pub(crate) fn frame_count(format: gpui::ImageFormat, content: &[u8]) -> Result<usize> {
+    Ok(animation_frames(format, content)?.map_or(1, |frames| frames.count()))
+}
 
-    pub fn init_test(cx: &mut TestAppContext) {
-        if std::env::var("RUST_LOG").is_ok() {
-            env_logger::try_init().ok();
+/// One frame of an image, as shown when it is played.
+#[derive(Clone, Debug)]
+pub struct ImageFrame {
+    pub index: usize,
+    /// How long the frame is shown before the next one, which is zero for still images.
+    pub delay: Duration,
+    /// The frame's pixels, composited over the frames before it.
+    pub bitmap: Arc<image::RgbaImage>,
+}
+
+/// Decodes the frames of an image one at a time, in the order they are played, so that
+/// animations can be stepped through or exported without decoding them in full first.
+///
+/// Still images have a single frame. SVG images, which have no pixels until they are rendered
+/// at some size, have none.
+pub fn image_frames(image: &gpui::Image) -> Result<impl '_ + Iterator<Item = Result<ImageFrame>>> {
+    let frames = match animation_frames(image.format, &image.bytes)? {
+        Some(frames) => frames,
+        None if image.format == gpui::ImageFormat::Svg => {
+            return Err(anyhow::anyhow!("SVG images have no frames"));
+        }
+        None => image::Frames::new(Box::new(std::iter::once_with(|| {
+            image::load_from_memory(&image.bytes).map(|image| image::Frame::new(image.into_rgba8()))
+        }))),
+    };
+    Ok(frames.enumerate().map(|(index, frame)| {
+        let frame = frame?;
+        Ok(ImageFrame {
+            index,
+            delay: frame.delay().into(),
+            bitmap: Arc::new(frame.into_buffer()),
+        })
+    }))
+}
+
+/// Returns the frames of animated image contents, or `None` for still images.
+fn animation_frames(
+    format: gpui::ImageFormat,
+    content: &[u8],
+) -> Result<Option<image::Frames<'_>>> {
+    use image::codecs::{gif::GifDecoder, png::PngDecoder, webp::WebPDecoder};
+    use image::AnimationDecoder as _;
+
+    let content = std::io::Cursor::new(content);
+    Ok(match format {
+        gpui::ImageFormat::Gif => Some(GifDecoder::new(content)?.into_frames()),
+        gpui::ImageFormat::Png => {
+            let decoder = PngDecoder::new(content)?;
+            if decoder.is_apng()? {
+                Some(decoder.apng()?.into_frames())
+            } else {
+                None
+            }
         }
+        gpui::ImageFormat::Webp => {
+            let decoder = WebPDecoder::new(content)?;
+            if decoder.has_animation() {
+                Some(decoder.into_frames())
+            } else {
+                None
+            }
+        }
+        _ => None,
+    })
+}
 
-        cx.update(|cx| {
-            let settings_store = SettingsStore::test(cx);
-            cx.set_global(settings_store);
-            language::init(cx);
-            Project::init_settings(cx);
-        });
+/// Returns the pixel dimensions of image contents in the given format, reading only their
+/// header.
+pub(crate) fn content_dimensions(format: gpui::ImageFormat, content: &[u8]) -> Option<(u32, u32)> {
+    if format == gpui::ImageFormat::Svg {
+        let tree = usvg::Tree::from_data(content, &usvg::Options::default()).ok()?;
+        return Some((
+            tree.size().width().round() as u32,
+            tree.size().height().round() as u32,
+        ));
     }
 
-    #[gpui::test]
-    async fn test_image_not_loaded_twice(cx: &mut TestAppContext) {
+    image::ImageReader::new(std::io::Cursor::new(content))
+        .with_guessed_format()
+        .ok()?
+        .into_dimensions()
+        .ok()
+}
+
+/// Finds the git repository of a local image, along with the image's path inside of it.
+fn image_repository(
+    image: &Model<ImageItem>,
+    cx: &AppContext,
+) -> Result<Option<(Arc<dyn GitRepository>, RepoPath)>> {
+    let Some(file) = worktree::File::from_dyn(Some(&image.read(cx).file)) else {
+        return Ok(None);
+    };
+    let Some(worktree) = file.worktree.read(cx).as_local() else {
+        return Err(anyhow::anyhow!(
+            "Loading git images from remote is not supported"
+        ));
+    };
+    let Some((repo_entry, local_repo_entry)) = worktree.repo_for_path(&file.path) else {
+        return Ok(None);
+    };
+    let relative_path = repo_entry.relativize(worktree, &file.path)?;
+    Ok(Some((local_repo_entry.repo().clone(), relative_path)))
+}
+
+/// How many bytes of images [`ImageCache`] holds unless configured otherwise.
+pub const DEFAULT_IMAGE_CACHE_SIZE: usize = 256 * 1024 * 1024;
+
+/// Recently opened images, kept after they are closed so that switching back to them doesn't
+/// read and decode them again. Images are identified by their path and modification time, and
+/// the least recently used ones are evicted once the cache outgrows its budget.
+struct ImageCache {
+    /// The cached images, least recently used first.
+    entries: VecDeque<CachedImage>,
+    size: usize,
+    budget: usize,
+    hits: usize,
+    misses: usize,
+}
+
+struct CachedImage {
+    path: ProjectPath,
+    mtime: MTime,
+    image: Arc<gpui::Image>,
+    /// How many times the image was opened from the cache.
+    hits: usize,
+}
+
+/// How the cache of recently opened images is used, to help diagnose memory use.
+#[derive(Clone, Debug, Default)]
+pub struct ImageCacheStats {
+    /// How many times an image was opened from the cache rather than read from disk.
+    pub hits: usize,
+    pub misses: usize,
+    /// How many bytes of image contents the cache holds.
+    pub size: usize,
+    /// How many bytes of image contents the cache can hold before evicting images.
+    pub budget: usize,
+    /// The cached images, least recently used first, with their size in bytes and how many
+    /// times each was opened from the cache.
+    pub entries: Vec<(ProjectPath, usize, usize)>,
+}
+
+impl ImageCache {
+    fn new(budget: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            size: 0,
+            budget,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    fn get(&mut self, path: &ProjectPath, mtime: MTime) -> Option<Arc<gpui::Image>> {
+        let Some(ix) = self
+            .entries
+            .iter()
+            .position(|entry| entry.path == *path && entry.mtime == mtime)
+        else {
+            self.misses += 1;
+            return None;
+        };
+        let mut entry = self.entries.remove(ix)?;
+        entry.hits += 1;
+        self.hits += 1;
+        let image = entry.image.clone();
+        self.entries.push_back(entry);
+        Some(image)
+    }
+
+    fn stats(&self) -> ImageCacheStats {
+        ImageCacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            size: self.size,
+            budget: self.budget,
+            entries: self
+                .entries
+                .iter()
+                .map(|entry| (entry.path.clone(), entry.image.bytes.len(), entry.hits))
+                .collect(),
+        }
+    }
+
+    fn insert(&mut self, path: ProjectPath, mtime: MTime, image: Arc<gpui::Image>) {
+        // Older versions of the image won't be asked for again.
+        self.entries.retain(|entry| {
+            if entry.path == path {
+                self.size -= entry.image.bytes.len();
+                false
+            } else {
+                true
+            }
+        });
+        self.size += image.bytes.len();
+        self.entries.push_back(CachedImage {
+            path,
+            mtime,
+            image,
+            hits: 0,
+        });
+        self.evict();
+    }
+
+    fn set_budget(&mut self, budget: usize) {
+        self.budget = budget;
+        self.evict();
+    }
+
+    fn evict(&mut self) {
+        while self.size > self.budget {
+            let Some(entry) = self.entries.pop_front() else {
+                break;
+            };
+            self.size -= entry.image.bytes.len();
+        }
+    }
+}
+
+/// Identifies the format of an image and hashes its contents on a background thread, so that
+/// large images don't block the UI while they open.
+async fn decode_image(content: Vec<u8>, cx: &AsyncAppContext) -> Result<Arc<gpui::Image>> {
+    cx.background_executor()
+        .spawn(async move { create_gpui_image(content) })
+        .await
+}
+
+pub(crate) fn create_gpui_image(content: Vec<u8>) -> anyhow::Result<Arc<gpui::Image>> {
+    Ok(Arc::new(gpui::Image {
+        id: content_hash(&content),
+        format: content_format(&content)?,
+        bytes: content,
+    }))
+}
+
+/// The hash identifying image contents, which is the id of the [`gpui::Image`] made from them.
+pub(crate) fn content_hash(content: &[u8]) -> u64 {
+    hash(&content)
+}
+
+/// Detects the format of image contents.
+pub(crate) fn content_format(content: &[u8]) -> Result<gpui::ImageFormat> {
+    // SVGs have no magic number, so they are recognized by their root element instead.
+    if is_svg_content(content) {
+        return Ok(gpui::ImageFormat::Svg);
+    }
+    if gpui::ImageFormat::is_heic(content) {
+        return Ok(gpui::ImageFormat::Heic);
+    }
+
+    Ok(match image::guess_format(content)? {
+        image::ImageFormat::Png => gpui::ImageFormat::Png,
+        image::ImageFormat::Jpeg => gpui::ImageFormat::Jpeg,
+        image::ImageFormat::WebP => gpui::ImageFormat::Webp,
+        image::ImageFormat::Gif => gpui::ImageFormat::Gif,
+        image::ImageFormat::Bmp => gpui::ImageFormat::Bmp,
+        image::ImageFormat::Tiff => gpui::ImageFormat::Tiff,
+        _ => Err(anyhow::anyhow!("Image format not supported"))?,
+    })
+}
+
+fn is_svg_content(content: &[u8]) -> bool {
+    let prefix = &content[..content.len().min(1024)];
+    String::from_utf8_lossy(prefix).contains("<svg")
+}
+
+impl RemoteImageStore {
+    /// Asks the host for an image, which it responds to by sending the image in chunks.
+    fn request_image(
+        this: Model<Self>,
+        worktree_id: WorktreeId,
+        path: &Path,
+        cx: &mut AppContext,
+    ) -> Task<Result<ReceivedImage>> {
+        let store = this.read(cx);
+        let request = store.upstream_client.request(proto::OpenImageByPath {
+            project_id: store.project_id,
+            worktree_id: worktree_id.to_proto(),
+            path: path.to_string_lossy().to_string(),
+        });
+        cx.spawn(move |mut cx| async move {
+            let response = request.await?;
+            this.update(&mut cx, |this, _| this.wait_for_image(response.image_id))?
+                .await
+                .context("stopped receiving the image")?
+        })
+    }
+
+    fn wait_for_image(&mut self, image_id: u64) -> oneshot::Receiver<Result<ReceivedImage>> {
+        let (tx, rx) = oneshot::channel();
+        match self.received_images_by_id.remove(&image_id) {
+            Some(image) => {
+                tx.send(image).ok();
+            }
+            None => {
+                self.remote_image_listeners.insert(image_id, tx);
+            }
+        }
+        rx
+    }
+
+    fn handle_create_image_for_peer(
+        &mut self,
+        envelope: TypedEnvelope<proto::CreateImageForPeer>,
+    ) -> Result<()> {
+        match envelope.payload.variant.context("missing variant")? {
+            proto::create_image_for_peer::Variant::State(state) => {
+                let file = state.file.context("image state is missing a file")?;
+                let content_size = state.content_size as usize;
+                self.loading_remote_images_by_id.insert(
+                    state.id,
+                    ReceivingImage {
+                        file,
+                        content_size,
+                        content: Vec::with_capacity(content_size.min(MAX_IMAGE_CHUNK_SIZE)),
+                    },
+                );
+            }
+            proto::create_image_for_peer::Variant::Chunk(chunk) => {
+                let image_id = chunk.image_id;
+                let image = self
+                    .loading_remote_images_by_id
+                    .get_mut(&image_id)
+                    .with_context(|| {
+                        format!("received chunk for image {image_id} without initial state")
+                    })?;
+                image.content.extend_from_slice(&chunk.data);
+                if !chunk.is_last && image.content.len() <= image.content_size {
+                    return Ok(());
+                }
+
+                let image = self.loading_remote_images_by_id.remove(&image_id).unwrap();
+                let result = if image.content.len() == image.content_size {
+                    Ok((image.file, image.content))
+                } else {
+                    Err(anyhow::anyhow!(
+                        "received {} bytes for image {image_id} instead of {}",
+                        image.content.len(),
+                        image.content_size
+                    ))
+                };
+                match self.remote_image_listeners.remove(&image_id) {
+                    Some(listener) => {
+                        listener.send(result).ok();
+                    }
+                    None => {
+                        self.received_images_by_id.insert(image_id, result);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ImageStoreImpl for Model<RemoteImageStore> {
+    fn open_image(
+        &self,
+        path: Arc<Path>,
+        worktree: Model<Worktree>,
+        progress: Arc<ImageLoadProgress>,
+        cx: &mut ModelContext<ImageStore>,
+    ) -> Task<Result<Model<ImageItem>>> {
+        let request =
+            RemoteImageStore::request_image(self.clone(), worktree.read(cx).id(), &path, cx);
+        cx.spawn(move |image_store, mut cx| async move {
+            let (file, content) = request.await?;
+            let file = cx.update(|cx| worktree::File::from_proto(file, worktree, cx))??;
+            let saved_mtime = file.disk_state().mtime();
+            progress.set_bytes_read(content.len() as u64);
+            progress.set_decoding();
+            let image = decode_image(content, &cx).await?;
+
+            let model =
+                cx.new_model(|cx| ImageItem::new(Arc::new(file), image, saved_mtime, cx))?;
+            image_store.update(&mut cx, |image_store, cx| {
+                image_store.add_image(model.clone(), cx)
+            })??;
+
+            Ok(model)
+        })
+    }
+
+    fn reload_images(
+        &self,
+        images: HashSet<Model<ImageItem>>,
+        cx: &mut ModelContext<ImageStore>,
+    ) -> Task<Result<()>> {
+        let requests = images
+            .into_iter()
+            .map(|image| {
+                let project_path = image.read(cx).project_path(cx);
+                let request = RemoteImageStore::request_image(
+                    self.clone(),
+                    project_path.worktree_id,
+                    &project_path.path,
+                    cx,
+                );
+                (image, request)
+            })
+            .collect::<Vec<_>>();
+        cx.spawn(move |_, mut cx| async move {
+            for (image, request) in requests {
+                let (_, content) = request.await?;
+                let image_id = image.update(&mut cx, |image, _| image.image.id)?;
+                if content_hash(&content) == image_id {
+                    continue;
+                }
+                let content = decode_image(content, &cx).await?;
+                image.update(&mut cx, |image, cx| image.set_image(content, cx))?;
+            }
+            Ok(())
+        })
+    }
+
+    fn as_local(&self) -> Option<Model<LocalImageStore>> {
+        None
+    }
+
+    fn as_remote(&self) -> Option<Model<RemoteImageStore>> {
+        Some(self.clone())
+    }
+}
+
+/// A PNG of a single white pixel, for tests that need an image file.
+#[cfg(any(test, feature = "test-support"))]
+pub const TEST_PNG: &[u8] = &[
+    0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0x00, 0x00, 0x00, 0x0D, 0x49, 0x48, 0x44, 0x52,
+    0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x06, 0x00, 0x00, 0x00, 0x1F, 0x15, 0xC4,
+    0x89, 0x00, 0x00, 0x00, 0x0A, 0x49, 0x44, 0x41, 0x54, 0x78, 0x9C, 0x63, 0x00, 0x01, 0x00, 0x00,
+    0x05, 0x00, 0x01, 0x0D, 0x0A, 0x2D, 0xB4, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45, 0x4E, 0x44, 0xAE,
+    0x42, 0x60, 0x82,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::{FakeFs, Fs as _};
+    use futures::StreamExt as _;
+    use gpui::TestAppContext;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::PathBuf;
+
+    pub fn init_test(cx: &mut TestAppContext) {
+        if std::env::var("RUST_LOG").is_ok() {
+            env_logger::try_init().ok();
+        }
+
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            Project::init_settings(cx);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_image_not_loaded_twice(cx: &mut TestAppContext) {
         init_test(cx);
         let fs = FakeFs::new(cx.executor());
 
         fs.insert_tree("/root", json!({})).await;
         // Create a png file that consists of a single white pixel
-        fs.insert_file(
-            "/root/image_1.png",
-            vec![
-                0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0x00, 0x00, 0x00, 0x0D, 0x49, 0x48,
-                0x44, 0x52, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x06, 0x00, 0x00,
-                0x00, 0x1F, 0x15, 0xC4, 0x89, 0x00, 0x00, 0x00, 0x0A, 0x49, 0x44, 0x41, 0x54, 0x78,
-                0x9C, 0x63, 0x00, 0x01, 0x00, 0x00, 0x05, 0x00, 0x01, 0x0D, 0x0A, 0x2D, 0xB4, 0x00,
-                0x00, 0x00, 0x00, 0x49, 0x45, 0x4E, 0x44, 0xAE, 0x42, 0x60, 0x82,
-            ],
-        )
-        .await;
+        fs.insert_file("/root/image_1.png", TEST_PNG.to_vec()).await;
 
         let project = Project::test(fs, ["/root".as_ref()], cx).await;
 
@@ -698,4 +2459,770 @@ mod tests {
 
         assert_eq!(image1, image2);
     }
+
+    #[gpui::test]
+    async fn test_image_metadata(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+
+        fs.insert_tree("/root", json!({})).await;
+        fs.insert_file("/root/image_1.png", TEST_PNG.to_vec()).await;
+
+        let project = Project::test(fs, ["/root".as_ref()], cx).await;
+        let worktree_id =
+            cx.update(|cx| project.read(cx).worktrees(cx).next().unwrap().read(cx).id());
+        let project_path = ProjectPath {
+            worktree_id,
+            path: PathBuf::from("image_1.png").into(),
+        };
+
+        let image = project
+            .update(cx, |project, cx| project.open_image(project_path, cx))
+            .await
+            .unwrap();
+        assert_eq!(
+            image.read_with(cx, |image, _| image.dimensions()),
+            Some((1, 1))
+        );
+
+        cx.run_until_parked();
+        image.read_with(cx, |image, _| {
+            assert_eq!(
+                image.metadata(),
+                Some(&ImageMetadata {
+                    width: 1,
+                    height: 1,
+                    frame_count: 1,
+                    color_type: Some(image::ColorType::Rgba8),
+                })
+            );
+            assert_eq!(image.format(), gpui::ImageFormat::Png);
+            assert_eq!(image.file_size(), 67);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_image_decoded_once_across_worktrees(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+
+        fs.insert_tree("/root", json!({ "dir": {} })).await;
+        fs.insert_file("/root/dir/image_1.png", TEST_PNG.to_vec())
+            .await;
+
+        // The image is in both worktrees, since one is nested in the other.
+        let project = Project::test(fs, ["/root".as_ref(), "/root/dir".as_ref()], cx).await;
+        let worktree_id = |abs_path: &str| {
+            cx.update(|cx| {
+                project
+                    .read(cx)
+                    .worktrees(cx)
+                    .find(|worktree| worktree.read(cx).abs_path().as_ref() == Path::new(abs_path))
+                    .unwrap()
+                    .read(cx)
+                    .id()
+            })
+        };
+        let outer_path = ProjectPath {
+            worktree_id: worktree_id("/root"),
+            path: PathBuf::from("dir/image_1.png").into(),
+        };
+        let inner_path = ProjectPath {
+            worktree_id: worktree_id("/root/dir"),
+            path: PathBuf::from("image_1.png").into(),
+        };
+
+        let outer_image = project
+            .update(cx, |project, cx| project.open_image(outer_path, cx))
+            .await
+            .unwrap();
+        let inner_image = project
+            .update(cx, |project, cx| project.open_image(inner_path.clone(), cx))
+            .await
+            .unwrap();
+
+        assert_ne!(outer_image, inner_image);
+        cx.update(|cx| {
+            assert_eq!(inner_image.read(cx).project_path(cx), inner_path);
+            assert!(Arc::ptr_eq(
+                &outer_image.read(cx).image,
+                &inner_image.read(cx).image
+            ));
+        });
+    }
+
+    #[gpui::test]
+    async fn test_image_load_cancelled_when_dropped(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+
+        fs.insert_tree("/root", json!({})).await;
+        fs.insert_file("/root/image_1.png", TEST_PNG.to_vec()).await;
+
+        let project = Project::test(fs, ["/root".as_ref()], cx).await;
+
+        let worktree_id =
+            cx.update(|cx| project.read(cx).worktrees(cx).next().unwrap().read(cx).id());
+
+        let project_path = ProjectPath {
+            worktree_id,
+            path: PathBuf::from("image_1.png").into(),
+        };
+
+        let task = project.update(cx, |project, cx| {
+            project.open_image(project_path.clone(), cx)
+        });
+        drop(task);
+        cx.run_until_parked();
+
+        let image_count = project.read_with(cx, |project, cx| {
+            project.image_store().read(cx).images().count()
+        });
+        assert_eq!(image_count, 0);
+
+        let image = project
+            .update(cx, |project, cx| project.open_image(project_path, cx))
+            .await
+            .unwrap();
+        assert_eq!(
+            image.read_with(cx, |image, _| image.dimensions()),
+            Some((1, 1))
+        );
+    }
+
+    #[gpui::test]
+    async fn test_save_untitled_image(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({})).await;
+        let content = TEST_PNG.to_vec();
+
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let worktree_id =
+            cx.update(|cx| project.read(cx).worktrees(cx).next().unwrap().read(cx).id());
+        let image_store = project.read_with(cx, |project, _| project.image_store().clone());
+
+        let image = image_store
+            .update(cx, |image_store, cx| {
+                image_store.create_image(content.clone(), cx)
+            })
+            .await
+            .unwrap();
+        image.read_with(cx, |image, cx| {
+            assert!(image.is_untitled());
+            assert_eq!(image.path().as_ref(), Path::new("Untitled.png"));
+            assert_eq!(crate::Item::project_path(image, cx), None);
+        });
+
+        let project_path = ProjectPath {
+            worktree_id,
+            path: PathBuf::from("pasted.png").into(),
+        };
+        image_store
+            .update(cx, |image_store, cx| {
+                image_store.save_image_as(image.clone(), project_path.clone(), cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        assert_eq!(
+            fs.load_bytes("/root/pasted.png".as_ref()).await.unwrap(),
+            content
+        );
+        image.read_with(cx, |image, cx| {
+            assert!(!image.is_untitled());
+            assert_eq!(image.project_path(cx), project_path);
+        });
+        let opened = project
+            .update(cx, |project, cx| project.open_image(project_path, cx))
+            .await
+            .unwrap();
+        assert_eq!(opened, image);
+    }
+
+    #[gpui::test]
+    async fn test_save_edited_image(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        let mut content = Vec::new();
+        image::RgbaImage::new(40, 20)
+            .write_to(
+                &mut std::io::Cursor::new(&mut content),
+                image::ImageFormat::Png,
+            )
+            .unwrap();
+        fs.insert_tree("/root", json!({})).await;
+        fs.insert_file("/root/image.png", content).await;
+
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let worktree_id =
+            cx.update(|cx| project.read(cx).worktrees(cx).next().unwrap().read(cx).id());
+        let image = project
+            .update(cx, |project, cx| {
+                project.open_image((worktree_id, PathBuf::from("image.png")), cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        image.update(cx, |image, cx| {
+            assert!(!image.is_dirty());
+            image.edit(ImageEdit::Rotate { quarter_turns: 1 }, cx);
+            image.edit(
+                ImageEdit::Crop {
+                    x: 0,
+                    y: 0,
+                    width: 20,
+                    height: 30,
+                },
+                cx,
+            );
+            assert!(image.is_dirty());
+            assert_eq!(image.dimensions(), Some((40, 20)));
+            assert_eq!(image.edited_dimensions(), Some((20, 30)));
+        });
+
+        let image_store = project.read_with(cx, |project, _| project.image_store().clone());
+        image_store
+            .update(cx, |image_store, cx| {
+                image_store.save_image(image.clone(), cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        image.read_with(cx, |image, _| {
+            assert!(!image.is_dirty());
+            assert_eq!(image.dimensions(), Some((20, 30)));
+        });
+        let saved = fs.load_bytes("/root/image.png".as_ref()).await.unwrap();
+        let saved = image::load_from_memory(&saved).unwrap();
+        assert_eq!((saved.width(), saved.height()), (20, 30));
+    }
+
+    #[gpui::test]
+    async fn test_open_image_in_archive(cx: &mut TestAppContext) {
+        use async_zip::{base::write::ZipFileWriter, Compression, ZipEntryBuilder};
+
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        let mut image_content = Vec::new();
+        image::RgbaImage::new(8, 4)
+            .write_to(
+                &mut std::io::Cursor::new(&mut image_content),
+                image::ImageFormat::Png,
+            )
+            .unwrap();
+        let mut archive = Vec::new();
+        let mut writer = ZipFileWriter::new(&mut archive);
+        for (path, content) in [
+            ("res/icon.png", image_content.as_slice()),
+            ("classes.dex", b"code".as_slice()),
+        ] {
+            let entry = ZipEntryBuilder::new(path.into(), Compression::Deflate);
+            writer.write_entry_whole(entry, content).await.unwrap();
+        }
+        writer.close().await.unwrap();
+        fs.insert_tree("/root", json!({})).await;
+        fs.insert_file("/root/app.apk", archive).await;
+
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let worktree_id =
+            cx.update(|cx| project.read(cx).worktrees(cx).next().unwrap().read(cx).id());
+        let archive = ProjectPath {
+            worktree_id,
+            path: Path::new("app.apk").into(),
+        };
+        let image_store = project.read_with(cx, |project, _| project.image_store().clone());
+
+        let images = image_store
+            .update(cx, |image_store, cx| {
+                image_store.archive_images(archive.clone(), cx)
+            })
+            .await
+            .unwrap();
+        assert_eq!(images, vec![Arc::from(Path::new("res/icon.png"))]);
+
+        let image = image_store
+            .update(cx, |image_store, cx| {
+                image_store.open_image_in_archive(archive.clone(), images[0].clone(), cx)
+            })
+            .await
+            .unwrap();
+        image.read_with(cx, |image, cx| {
+            assert!(image.is_in_archive());
+            assert!(!image.has_project_file());
+            assert!(!image.is_dirty());
+            assert_eq!(image.dimensions(), Some((8, 4)));
+            assert_eq!(
+                image.file.path().as_ref(),
+                Path::new("app.apk/res/icon.png")
+            );
+            assert_eq!(crate::Item::project_path(image, cx), None);
+        });
+
+        let reopened = image_store
+            .update(cx, |image_store, cx| {
+                image_store.open_image_in_archive(archive, images[0].clone(), cx)
+            })
+            .await
+            .unwrap();
+        assert_eq!(reopened.entity_id(), image.entity_id());
+        assert!(image_store
+            .update(cx, |image_store, cx| image_store.save_image(image, cx))
+            .await
+            .is_err());
+    }
+
+    #[gpui::test]
+    async fn test_conflict_when_edited_image_changes_on_disk(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        let mut content = Vec::new();
+        image::RgbaImage::new(40, 20)
+            .write_to(
+                &mut std::io::Cursor::new(&mut content),
+                image::ImageFormat::Png,
+            )
+            .unwrap();
+        fs.insert_tree("/root", json!({})).await;
+        fs.insert_file("/root/image.png", content.clone()).await;
+
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let worktree_id =
+            cx.update(|cx| project.read(cx).worktrees(cx).next().unwrap().read(cx).id());
+        let image = project
+            .update(cx, |project, cx| {
+                project.open_image((worktree_id, PathBuf::from("image.png")), cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        let conflicts = Arc::new(parking_lot::Mutex::new(0));
+        cx.update(|cx| {
+            let conflicts = conflicts.clone();
+            cx.subscribe(&image, move |_, event, _| {
+                if let ImageItemEvent::Conflicted = event {
+                    *conflicts.lock() += 1;
+                }
+            })
+            .detach();
+        });
+        image.update(cx, |image, cx| {
+            image.edit(ImageEdit::Rotate { quarter_turns: 2 }, cx)
+        });
+
+        // Rewriting the same contents isn't a conflict.
+        fs.insert_file("/root/image.png", content.clone()).await;
+        cx.run_until_parked();
+        assert_eq!(*conflicts.lock(), 0);
+        image.read_with(cx, |image, _| assert!(!image.has_conflict()));
+
+        // Changing them keeps the edits and the loaded contents until the conflict is resolved.
+        let mut changed_content = content.clone();
+        changed_content.push(0);
+        fs.insert_file("/root/image.png", changed_content).await;
+        cx.executor().advance_clock(DEFAULT_RELOAD_DEBOUNCE);
+        cx.run_until_parked();
+        assert_eq!(*conflicts.lock(), 1);
+        image.read_with(cx, |image, _| {
+            assert!(image.has_conflict());
+            assert_eq!(image.edits().len(), 1);
+            assert_eq!(image.image.bytes, content);
+        });
+
+        image.update(cx, |image, cx| image.keep_edits(cx));
+        image.read_with(cx, |image, _| {
+            assert!(!image.has_conflict());
+            assert!(image.is_dirty());
+        });
+    }
+
+    #[gpui::test]
+    async fn test_rename_open_image(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        let mut content = Vec::new();
+        image::RgbaImage::new(4, 4)
+            .write_to(
+                &mut std::io::Cursor::new(&mut content),
+                image::ImageFormat::Png,
+            )
+            .unwrap();
+        fs.insert_tree("/root", json!({ "assets": {} })).await;
+        fs.insert_file("/root/image.png", content).await;
+
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let worktree_id =
+            cx.update(|cx| project.read(cx).worktrees(cx).next().unwrap().read(cx).id());
+        let image = project
+            .update(cx, |project, cx| {
+                project.open_image((worktree_id, PathBuf::from("image.png")), cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        let renames = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        cx.update(|cx| {
+            let renames = renames.clone();
+            cx.subscribe(&image, move |_, event, _| {
+                if let ImageItemEvent::Renamed { old_path } = event {
+                    renames.lock().push(old_path.clone());
+                }
+            })
+            .detach();
+        });
+
+        fs.rename(
+            "/root/image.png".as_ref(),
+            "/root/assets/logo.png".as_ref(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+        cx.run_until_parked();
+
+        assert_eq!(*renames.lock(), vec![Arc::from(Path::new("image.png"))]);
+        image.read_with(cx, |image, _| {
+            assert_eq!(image.path().as_ref(), Path::new("assets/logo.png"));
+            assert!(!image.is_dirty());
+        });
+        let image_store = project.read_with(cx, |project, _| project.image_store().clone());
+        image_store.read_with(cx, |image_store, cx| {
+            let renamed = ProjectPath {
+                worktree_id,
+                path: Path::new("assets/logo.png").into(),
+            };
+            assert_eq!(
+                image_store
+                    .get_by_path(&renamed, cx)
+                    .map(|image| image.entity_id()),
+                Some(image.entity_id())
+            );
+        });
+        let reopened = project
+            .update(cx, |project, cx| {
+                project.open_image((worktree_id, PathBuf::from("assets/logo.png")), cx)
+            })
+            .await
+            .unwrap();
+        assert_eq!(reopened.entity_id(), image.entity_id());
+    }
+
+    #[gpui::test]
+    async fn test_delete_open_image(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        let mut content = Vec::new();
+        image::RgbaImage::new(4, 4)
+            .write_to(
+                &mut std::io::Cursor::new(&mut content),
+                image::ImageFormat::Png,
+            )
+            .unwrap();
+        fs.insert_tree("/root", json!({})).await;
+        fs.insert_file("/root/image.png", content.clone()).await;
+
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let worktree_id =
+            cx.update(|cx| project.read(cx).worktrees(cx).next().unwrap().read(cx).id());
+        let image = project
+            .update(cx, |project, cx| {
+                project.open_image((worktree_id, PathBuf::from("image.png")), cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        image.read_with(cx, |image, _| assert!(!image.is_deleted()));
+
+        fs.remove_file("/root/image.png".as_ref(), Default::default())
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        image.read_with(cx, |image, _| {
+            assert!(image.is_deleted());
+            assert_eq!(image.image.bytes, content);
+        });
+
+        fs.insert_file("/root/image.png", content).await;
+        cx.run_until_parked();
+        image.read_with(cx, |image, _| assert!(!image.is_deleted()));
+    }
+
+    #[gpui::test]
+    async fn test_open_image_through_symlink(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        let mut content = Vec::new();
+        image::RgbaImage::new(4, 4)
+            .write_to(
+                &mut std::io::Cursor::new(&mut content),
+                image::ImageFormat::Png,
+            )
+            .unwrap();
+        fs.insert_tree("/root", json!({ "shared": {} })).await;
+        fs.insert_file("/root/shared/logo.png", content).await;
+        fs.create_symlink("/root/logo.png".as_ref(), "/root/shared/logo.png".into())
+            .await
+            .unwrap();
+
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let worktree_id =
+            cx.update(|cx| project.read(cx).worktrees(cx).next().unwrap().read(cx).id());
+        let link = project
+            .update(cx, |project, cx| {
+                project.open_image((worktree_id, PathBuf::from("logo.png")), cx)
+            })
+            .await
+            .unwrap();
+        let target = project
+            .update(cx, |project, cx| {
+                project.open_image((worktree_id, PathBuf::from("shared/logo.png")), cx)
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(link.entity_id(), target.entity_id());
+        link.read_with(cx, |image, _| {
+            assert_eq!(image.path().as_ref(), Path::new("logo.png"));
+            assert_eq!(
+                image.canonical_path().map(|path| path.as_ref()),
+                Some(Path::new("/root/shared/logo.png"))
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_forget_released_images(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        let mut content = Vec::new();
+        image::RgbaImage::new(4, 4)
+            .write_to(
+                &mut std::io::Cursor::new(&mut content),
+                image::ImageFormat::Png,
+            )
+            .unwrap();
+        fs.insert_tree("/root", json!({})).await;
+        fs.insert_file("/root/image.png", content).await;
+
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let worktree_id =
+            cx.update(|cx| project.read(cx).worktrees(cx).next().unwrap().read(cx).id());
+        let image = project
+            .update(cx, |project, cx| {
+                project.open_image((worktree_id, PathBuf::from("image.png")), cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        let image_store = project.read_with(cx, |project, _| project.image_store().clone());
+        image_store.read_with(cx, |image_store, _| {
+            assert_eq!(image_store.opened_images.len(), 1)
+        });
+
+        drop(image);
+        cx.run_until_parked();
+        image_store.read_with(cx, |image_store, cx| {
+            assert!(image_store.opened_images.is_empty());
+            let local = image_store.state.as_local().unwrap();
+            let local = local.read(cx);
+            assert!(local.local_image_ids_by_path.is_empty());
+            assert!(local.local_image_ids_by_entry_id.is_empty());
+            assert!(local.local_image_ids_by_canonical_path.is_empty());
+        });
+    }
+
+    #[gpui::test]
+    async fn test_decode_error(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({})).await;
+        // A PNG signature followed by contents that aren't a valid PNG.
+        let mut content = b"\x89PNG\r\n\x1a\n".to_vec();
+        content.extend_from_slice(b"truncated");
+        fs.insert_file("/root/broken.png", content).await;
+
+        let project = Project::test(fs, ["/root".as_ref()], cx).await;
+        let worktree_id =
+            cx.update(|cx| project.read(cx).worktrees(cx).next().unwrap().read(cx).id());
+        let image = project
+            .update(cx, |project, cx| {
+                project.open_image((worktree_id, PathBuf::from("broken.png")), cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        image.read_with(cx, |image, _| {
+            assert!(image.metadata().is_none());
+            assert!(image.decode_error().is_some());
+        });
+    }
+
+    #[gpui::test]
+    async fn test_stream_frames(cx: &mut TestAppContext) {
+        init_test(cx);
+        let mut content = Vec::new();
+        {
+            let mut encoder = image::codecs::gif::GifEncoder::new(&mut content);
+            encoder
+                .encode_frames((0..3).map(|ix| {
+                    image::Frame::from_parts(
+                        image::RgbaImage::from_pixel(4, 2, image::Rgba([ix * 100, 0, 0, 255])),
+                        0,
+                        0,
+                        image::Delay::from_numer_denom_ms(50 * (ix as u32 + 1), 1),
+                    )
+                }))
+                .unwrap();
+        }
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({})).await;
+        fs.insert_file("/root/animation.gif", content).await;
+
+        let project = Project::test(fs, ["/root".as_ref()], cx).await;
+        let worktree_id =
+            cx.update(|cx| project.read(cx).worktrees(cx).next().unwrap().read(cx).id());
+        let image = project
+            .update(cx, |project, cx| {
+                project.open_image((worktree_id, PathBuf::from("animation.gif")), cx)
+            })
+            .await
+            .unwrap();
+
+        let frames = image
+            .read_with(cx, |image, cx| image.stream_frames(cx))
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(
+            frames
+                .iter()
+                .map(|frame| (frame.index, frame.delay, frame.bitmap.dimensions()))
+                .collect::<Vec<_>>(),
+            vec![
+                (0, Duration::from_millis(50), (4, 2)),
+                (1, Duration::from_millis(100), (4, 2)),
+                (2, Duration::from_millis(150), (4, 2)),
+            ]
+        );
+        assert_eq!(
+            frames[1].bitmap.get_pixel(0, 0),
+            &image::Rgba([100, 0, 0, 255])
+        );
+    }
+
+    #[gpui::test]
+    async fn test_reload_skipped_when_contents_unchanged(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+
+        let content = TEST_PNG.to_vec();
+        fs.insert_tree("/root", json!({})).await;
+        fs.insert_file("/root/image_1.png", content.clone()).await;
+
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+
+        let worktree_id =
+            cx.update(|cx| project.read(cx).worktrees(cx).next().unwrap().read(cx).id());
+
+        let project_path = ProjectPath {
+            worktree_id,
+            path: PathBuf::from("image_1.png").into(),
+        };
+
+        let image = project
+            .update(cx, |project, cx| project.open_image(project_path, cx))
+            .await
+            .unwrap();
+        let reloads_needed = Arc::new(parking_lot::Mutex::new(0));
+        cx.update(|cx| {
+            let reloads_needed = reloads_needed.clone();
+            cx.subscribe(&image, move |_, event, _| {
+                if let ImageItemEvent::ReloadNeeded = event {
+                    *reloads_needed.lock() += 1;
+                }
+            })
+            .detach();
+        });
+
+        // Writing the same contents again only changes the file's modification time.
+        fs.insert_file("/root/image_1.png", content.clone()).await;
+        cx.run_until_parked();
+        assert_eq!(*reloads_needed.lock(), 0);
+
+        let mut changed_content = content;
+        changed_content.push(0);
+        fs.insert_file("/root/image_1.png", changed_content.clone())
+            .await;
+        cx.executor().advance_clock(DEFAULT_RELOAD_DEBOUNCE);
+        cx.run_until_parked();
+        assert_eq!(*reloads_needed.lock(), 1);
+
+        // Changes made in quick succession are checked together, once they have all been made.
+        for _ in 0..5 {
+            changed_content.push(0);
+            fs.insert_file("/root/image_1.png", changed_content.clone())
+                .await;
+            cx.run_until_parked();
+        }
+        assert_eq!(*reloads_needed.lock(), 1);
+        cx.executor().advance_clock(DEFAULT_RELOAD_DEBOUNCE);
+        cx.run_until_parked();
+        assert_eq!(*reloads_needed.lock(), 2);
+    }
+
+    #[test]
+    fn test_image_cache_eviction() {
+        let path = |name: &str| ProjectPath {
+            worktree_id: WorktreeId::from_usize(1),
+            path: Path::new(name).into(),
+        };
+        let image = |size: usize| {
+            Arc::new(gpui::Image {
+                format: gpui::ImageFormat::Png,
+                bytes: vec![0; size],
+                id: size as u64,
+            })
+        };
+        let mtime = MTime::from_seconds_and_nanos(1, 0);
+        let mut cache = ImageCache::new(100);
+
+        cache.insert(path("a.png"), mtime, image(40));
+        cache.insert(path("b.png"), mtime, image(40));
+        assert!(cache.get(&path("a.png"), mtime).is_some());
+
+        // "b.png" is now the least recently used image.
+        cache.insert(path("c.png"), mtime, image(40));
+        assert!(cache.get(&path("b.png"), mtime).is_none());
+        assert!(cache.get(&path("a.png"), mtime).is_some());
+        assert!(cache.get(&path("c.png"), mtime).is_some());
+
+        // Images that changed on disk are not returned.
+        assert!(cache
+            .get(&path("a.png"), MTime::from_seconds_and_nanos(2, 0))
+            .is_none());
+
+        cache.set_budget(50);
+        assert!(cache.get(&path("a.png"), mtime).is_none());
+        assert!(cache.get(&path("c.png"), mtime).is_some());
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (4, 3));
+        assert_eq!((stats.size, stats.budget), (40, 50));
+        assert_eq!(stats.entries, vec![(path("c.png"), 40, 2)]);
+    }
+
+    #[test]
+    fn test_is_image_path() {
+        assert!(is_image_path(Path::new("assets/logo.png")));
+        assert!(is_image_path(Path::new("photos/IMG_0001.JPG")));
+        assert!(!is_image_path(Path::new("icons/arrow.svg")));
+        assert!(!is_image_path(Path::new("src/main.rs")));
+        assert!(!is_image_path(Path::new("Makefile")));
+    }
 }
@@ -0,0 +1,140 @@
+//! Limits how much loading work, such as opening images or generating thumbnails, runs at once.
+
+use std::{
+    collections::VecDeque,
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use futures::channel::oneshot;
+use parking_lot::Mutex;
+
+/// Hands out a limited number of turns to run loads, to urgent loads before the others and in
+/// the order they were asked for otherwise.
+pub(crate) struct LoadQueue {
+    state: Arc<Mutex<LoadQueueState>>,
+}
+
+struct LoadQueueState {
+    running: usize,
+    max_running: usize,
+    waiting: VecDeque<LoadWaiter>,
+}
+
+struct LoadWaiter {
+    /// Whether the load is urgent. It can become so while it waits for its turn.
+    urgent: Arc<AtomicBool>,
+    turn: oneshot::Sender<LoadTurn>,
+}
+
+/// The right to run a load, handed on to the next one waiting when dropped.
+pub(crate) struct LoadTurn {
+    queue: Arc<Mutex<LoadQueueState>>,
+}
+
+impl LoadQueue {
+    pub(crate) fn new(max_running: usize) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(LoadQueueState {
+                running: 0,
+                max_running,
+                waiting: VecDeque::new(),
+            })),
+        }
+    }
+
+    /// Waits until a load can run. Dropping the returned future gives up the place in the
+    /// queue.
+    pub(crate) fn wait_for_turn(
+        &self,
+        urgent: Arc<AtomicBool>,
+    ) -> impl Future<Output = Option<LoadTurn>> {
+        let mut state = self.state.lock();
+        let turn = if state.running < state.max_running {
+            state.running += 1;
+            Ok(LoadTurn {
+                queue: self.state.clone(),
+            })
+        } else {
+            let (tx, rx) = oneshot::channel();
+            state.waiting.push_back(LoadWaiter { urgent, turn: tx });
+            Err(rx)
+        };
+        drop(state);
+        async move {
+            match turn {
+                Ok(turn) => Some(turn),
+                Err(rx) => rx.await.ok(),
+            }
+        }
+    }
+}
+
+impl Drop for LoadTurn {
+    fn drop(&mut self) {
+        let next = {
+            let mut state = self.queue.lock();
+            state.waiting.retain(|waiter| !waiter.turn.is_canceled());
+            let next_ix = state
+                .waiting
+                .iter()
+                .position(|waiter| waiter.urgent.load(Ordering::SeqCst))
+                .or_else(|| (!state.waiting.is_empty()).then_some(0));
+            match next_ix {
+                Some(ix) => state.waiting.remove(ix),
+                None => {
+                    state.running -= 1;
+                    None
+                }
+            }
+        };
+        // If the waiter stopped waiting in the meantime, the turn is dropped again and handed
+        // on to the next one.
+        if let Some(next) = next {
+            next.turn
+                .send(LoadTurn {
+                    queue: self.queue.clone(),
+                })
+                .ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::FutureExt as _;
+
+    #[test]
+    fn test_load_queue() {
+        let queue = LoadQueue::new(1);
+        let first = queue
+            .wait_for_turn(Arc::new(AtomicBool::new(false)))
+            .now_or_never()
+            .flatten()
+            .unwrap();
+        let mut background = queue
+            .wait_for_turn(Arc::new(AtomicBool::new(false)))
+            .boxed();
+        let promoted = Arc::new(AtomicBool::new(false));
+        let mut urgent = queue.wait_for_turn(promoted.clone()).boxed();
+        let cancelled = queue.wait_for_turn(Arc::new(AtomicBool::new(true)));
+        assert!((&mut background).now_or_never().is_none());
+        assert!((&mut urgent).now_or_never().is_none());
+
+        // Loads that became urgent go first, and those that stopped waiting are skipped.
+        drop(cancelled);
+        promoted.store(true, Ordering::SeqCst);
+        drop(first);
+        assert!((&mut background).now_or_never().is_none());
+        let second = urgent.now_or_never().flatten().unwrap();
+
+        drop(second);
+        let third = background.now_or_never().flatten().unwrap();
+        drop(third);
+        assert_eq!(queue.state.lock().running, 0);
+    }
+}
@@ -2,7 +2,10 @@ pub mod buffer_store;
 mod color_extractor;
 pub mod connection_manager;
 pub mod debounced_delay;
+pub mod image_archives;
+pub mod image_edits;
 pub mod image_store;
+mod load_queue;
 pub mod lsp_command;
 pub mod lsp_ext_command;
 pub mod lsp_store;
@@ -12,6 +15,7 @@ pub mod search;
 mod task_inventory;
 pub mod task_store;
 pub mod terminals;
+pub mod thumbnails;
 pub mod toolchain_store;
 pub mod worktree_store;
 
@@ -36,8 +40,10 @@ use futures::{
     future::try_join_all,
     StreamExt,
 };
-pub use image_store::{ImageItem, ImageStore};
+pub use image_edits::ImageEdit;
+pub use image_store::{ImageFrame, ImageItem, ImageMetadata, ImageStore};
 use image_store::{ImageItemEvent, ImageStoreEvent};
+pub use thumbnails::{Thumbnail, ThumbnailPriority};
 
 use git::{blame::Blame, repository::GitRepository};
 use gpui::{
@@ -271,6 +277,10 @@ pub enum Event {
     RefreshInlayHints,
     RevealInProjectPanel(ProjectEntryId),
     SnippetEdit(BufferId, Vec<(lsp::Range, Snippet)>),
+    ImageAnnotationsUpdated {
+        peer_id: proto::PeerId,
+        annotations: proto::UpdateImageAnnotations,
+    },
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
@@ -581,9 +591,12 @@ impl Project {
         client.add_model_request_handler(Self::handle_open_buffer_by_path);
         client.add_model_request_handler(Self::handle_open_new_buffer);
         client.add_model_message_handler(Self::handle_create_buffer_for_peer);
+        client.add_model_message_handler(Self::handle_create_image_for_peer);
+        client.add_model_message_handler(Self::handle_update_image_annotations);
 
         WorktreeStore::init(&client);
         BufferStore::init(&client);
+        ImageStore::init(&client);
         LspStore::init(&client);
         SettingsObserver::init(&client);
         TaskStore::init(Some(&client));
@@ -849,6 +862,7 @@ impl Project {
             ssh.subscribe_to_entity(SSH_PROJECT_ID, &this.settings_observer);
 
             ssh_proto.add_model_message_handler(Self::handle_create_buffer_for_peer);
+            ssh_proto.add_model_message_handler(Self::handle_create_image_for_peer);
             ssh_proto.add_model_message_handler(Self::handle_update_worktree);
             ssh_proto.add_model_message_handler(Self::handle_update_project);
             ssh_proto.add_model_message_handler(Self::handle_toast);
@@ -1223,6 +1237,10 @@ impl Project {
         self.worktree_store.clone()
     }
 
+    pub fn image_store(&self) -> Model<ImageStore> {
+        self.image_store.clone()
+    }
+
     pub fn buffer_for_id(&self, remote_id: BufferId, cx: &AppContext) -> Option<Model<Buffer>> {
         self.buffer_store.read(cx).get(remote_id)
     }
@@ -1533,6 +1551,9 @@ impl Project {
             self.client
                 .subscribe_to_entity(project_id)?
                 .set_model(&self.buffer_store, &mut cx.to_async()),
+            self.client
+                .subscribe_to_entity(project_id)?
+                .set_model(&self.image_store, &mut cx.to_async()),
             self.client
                 .subscribe_to_entity(project_id)?
                 .set_model(&self.lsp_store, &mut cx.to_async()),
@@ -1544,6 +1565,9 @@ impl Project {
         self.buffer_store.update(cx, |buffer_store, cx| {
             buffer_store.shared(project_id, self.client.clone().into(), cx)
         });
+        self.image_store.update(cx, |image_store, cx| {
+            image_store.shared(project_id, self.client.clone().into(), cx)
+        });
         self.worktree_store.update(cx, |worktree_store, cx| {
             worktree_store.shared(project_id, self.client.clone().into(), cx);
         });
@@ -1634,6 +1658,9 @@ impl Project {
                 buffer_store.forget_shared_buffers();
                 buffer_store.unshared(cx)
             });
+            self.image_store.update(cx, |image_store, cx| {
+                image_store.unshared(cx);
+            });
             self.task_store.update(cx, |task_store, cx| {
                 task_store.unshared(cx);
             });
@@ -1694,6 +1721,8 @@ impl Project {
             self.buffer_store.update(cx, |buffer_store, cx| {
                 buffer_store.disconnected_from_host(cx)
             });
+            self.image_store
+                .update(cx, |image_store, cx| image_store.disconnected_from_host(cx));
             self.lsp_store
                 .update(cx, |lsp_store, _cx| lsp_store.disconnected_from_host());
         }
@@ -1920,6 +1949,23 @@ impl Project {
         })
     }
 
+    /// Loads a small copy of an image that fits in a square of `size` pixels.
+    pub fn load_thumbnail(
+        &mut self,
+        path: impl Into<ProjectPath>,
+        size: u32,
+        priority: ThumbnailPriority,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Arc<Thumbnail>>> {
+        if self.is_disconnected(cx) {
+            return Task::ready(Err(anyhow!(ErrorCode::Disconnected)));
+        }
+
+        self.image_store.update(cx, |image_store, cx| {
+            image_store.load_thumbnail(path.into(), size, priority, cx)
+        })
+    }
+
     async fn send_buffer_ordered_messages(
         this: WeakModel<Self>,
         rx: UnboundedReceiver<BufferOrderedMessage>,
@@ -2162,6 +2208,8 @@ impl Project {
                 self.buffer_store.update(cx, |buffer_store, cx| {
                     buffer_store.disconnected_from_host(cx)
                 });
+                self.image_store
+                    .update(cx, |image_store, cx| image_store.disconnected_from_host(cx));
                 self.lsp_store.update(cx, |lsp_store, _cx| {
                     lsp_store.disconnected_from_ssh_remote()
                 });
@@ -3737,6 +3785,41 @@ impl Project {
         })
     }
 
+    async fn handle_update_image_annotations(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::UpdateImageAnnotations>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        let peer_id = envelope.original_sender_id()?;
+        this.update(&mut cx, |_, cx| {
+            cx.emit(Event::ImageAnnotationsUpdated {
+                peer_id,
+                annotations: envelope.payload,
+            });
+        })
+    }
+
+    /// Shares the local pointer and annotations over an image with the project's collaborators.
+    pub fn update_image_annotations(
+        &self,
+        project_path: &ProjectPath,
+        pointer: Option<proto::ImageAnnotationPoint>,
+        strokes: Vec<proto::ImageAnnotationStroke>,
+    ) {
+        let Some(project_id) = self.remote_id() else {
+            return;
+        };
+        self.client
+            .send(proto::UpdateImageAnnotations {
+                project_id,
+                worktree_id: project_path.worktree_id.to_proto(),
+                path: project_path.path.to_string_lossy().to_string(),
+                pointer,
+                strokes,
+            })
+            .log_err();
+    }
+
     async fn handle_hide_toast(
         this: Model<Self>,
         envelope: TypedEnvelope<proto::HideToast>,
@@ -3839,6 +3922,18 @@ impl Project {
         })?
     }
 
+    async fn handle_create_image_for_peer(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::CreateImageForPeer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        this.update(&mut cx, |this, cx| {
+            this.image_store.update(cx, |image_store, cx| {
+                image_store.handle_create_image_for_peer(envelope, cx)
+            })
+        })?
+    }
+
     async fn handle_synchronize_buffers(
         this: Model<Self>,
         envelope: TypedEnvelope<proto::SynchronizeBuffers>,
@@ -0,0 +1,170 @@
+//! Small copies of images for lists and previews.
+//!
+//! Thumbnails are generated on a background thread and stored in the support directory, named
+//! after the image's path and contents, so that each version of an image is only shrunk once.
+//! Only a few are generated at a time, and the ones that are visible are generated first.
+
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    io::Cursor,
+    path::Path,
+    sync::Arc,
+};
+
+use anyhow::Result;
+use gpui::{hash, Image, ImageFormat};
+use util::ResultExt;
+
+use fs::MappedFile;
+
+use crate::image_store::{content_dimensions, content_format, content_hash, create_gpui_image};
+
+/// A small copy of an image.
+#[derive(Debug)]
+pub struct Thumbnail {
+    pub image: Arc<Image>,
+    /// The dimensions of the full image.
+    pub dimensions: Option<(u32, u32)>,
+}
+
+impl Thumbnail {
+    /// A 64-bit perceptual hash of the image, which differs in only a few bits between images
+    /// that look alike, such as copies of an image at another size, in another format, or with
+    /// small edits. Returns `None` for images that can't be rasterized here, such as SVGs.
+    pub fn perceptual_hash(&self) -> Option<u64> {
+        difference_hash(&self.image.bytes)
+    }
+}
+
+/// How urgently a thumbnail is needed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ThumbnailPriority {
+    /// The thumbnail is expected to come into view soon.
+    Background,
+    /// The thumbnail is in view.
+    Visible,
+}
+
+/// How many thumbnails are generated at once.
+pub(crate) const MAX_CONCURRENT_THUMBNAILS: usize = 4;
+
+/// Creates a thumbnail of the given image contents that fits in a square of `size` pixels,
+/// reusing the one stored for the same path and contents if there is one.
+///
+/// `path` identifies where the image comes from. Thumbnails are only stored on disk for images
+/// that have one.
+pub(crate) async fn load_or_generate(
+    path: Option<&Path>,
+    content: MappedFile,
+    size: u32,
+) -> Result<Thumbnail> {
+    let format = content_format(&content)?;
+    let dimensions = content_dimensions(format, &content);
+    // SVGs are rasterized at whatever size they are shown, and images that are already small
+    // enough don't need shrinking.
+    let fits = dimensions.map_or(false, |(width, height)| width <= size && height <= size);
+    if format == ImageFormat::Svg || fits {
+        return Ok(Thumbnail {
+            image: create_gpui_image(content.into_vec())?,
+            dimensions,
+        });
+    }
+
+    let stored_path = path.map(|path| {
+        let mut hasher = DefaultHasher::new();
+        path.hash(&mut hasher);
+        content_hash(&content).hash(&mut hasher);
+        size.hash(&mut hasher);
+        paths::thumbnails_dir().join(format!("{:016x}.png", hasher.finish()))
+    });
+    if let Some(stored_path) = stored_path.as_ref() {
+        if let Ok(bytes) = smol::fs::read(stored_path).await {
+            return Ok(Thumbnail {
+                image: Arc::new(Image {
+                    format: ImageFormat::Png,
+                    id: hash(&bytes),
+                    bytes,
+                }),
+                dimensions,
+            });
+        }
+    }
+
+    let thumbnail = shrink(&content, size)?;
+    if let Some(stored_path) = stored_path {
+        smol::fs::create_dir_all(paths::thumbnails_dir())
+            .await
+            .log_err();
+        smol::fs::write(&stored_path, &thumbnail.bytes)
+            .await
+            .log_err();
+    }
+    Ok(Thumbnail {
+        image: Arc::new(thumbnail),
+        dimensions,
+    })
+}
+
+/// Scales an image down to fit in a square of `size` pixels, keeping its aspect ratio.
+fn shrink(content: &[u8], size: u32) -> Result<Image> {
+    let thumbnail = image::load_from_memory(content)?.thumbnail(size, size);
+    let mut bytes = Vec::new();
+    thumbnail.write_to(&mut Cursor::new(&mut bytes), image::ImageFormat::Png)?;
+    Ok(Image {
+        format: ImageFormat::Png,
+        id: hash(&bytes),
+        bytes,
+    })
+}
+
+/// Hashes an image by whether the brightness increases between each pair of horizontally
+/// adjacent pixels of a 9×8 grayscale copy of it.
+fn difference_hash(content: &[u8]) -> Option<u64> {
+    let grayscale = image::load_from_memory(content)
+        .ok()?
+        .resize_exact(9, 8, image::imageops::FilterType::Triangle)
+        .into_luma8();
+    let mut hash = 0;
+    for y in 0..8 {
+        for x in 0..8 {
+            let brighter = grayscale.get_pixel(x + 1, y)[0] > grayscale.get_pixel(x, y)[0];
+            hash = (hash << 1) | u64::from(brighter);
+        }
+    }
+    Some(hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::image_store::image_dimensions;
+
+    #[test]
+    fn test_shrink() {
+        let mut bytes = Vec::new();
+        image::RgbaImage::new(400, 200)
+            .write_to(&mut Cursor::new(&mut bytes), image::ImageFormat::Png)
+            .unwrap();
+        let thumbnail = shrink(&bytes, 100).unwrap();
+        assert_eq!(image_dimensions(&thumbnail), Some((100, 50)));
+    }
+
+    #[test]
+    fn test_difference_hash() {
+        let gradient = |width: u32, height: u32, rising: bool| {
+            let image = image::GrayImage::from_fn(width, height, |x, _| {
+                let brightness = (x * 255 / (width - 1)) as u8;
+                image::Luma([if rising { brightness } else { 255 - brightness }])
+            });
+            let mut bytes = Vec::new();
+            image
+                .write_to(&mut Cursor::new(&mut bytes), image::ImageFormat::Png)
+                .unwrap();
+            bytes
+        };
+        assert_eq!(difference_hash(&gradient(64, 64, true)), Some(u64::MAX));
+        assert_eq!(difference_hash(&gradient(300, 100, true)), Some(u64::MAX));
+        assert_eq!(difference_hash(&gradient(64, 64, false)), Some(0));
+        assert_eq!(difference_hash(b"<svg></svg>"), None);
+    }
+}
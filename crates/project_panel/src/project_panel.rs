@@ -31,8 +31,8 @@ use gpui::{
 use indexmap::IndexMap;
 use menu::{Confirm, SelectFirst, SelectLast, SelectNext, SelectPrev};
 use project::{
-    relativize_path, Entry, EntryKind, Fs, Project, ProjectEntryId, ProjectPath, Worktree,
-    WorktreeId,
+    image_store::is_image_path, relativize_path, Entry, EntryKind, Fs, Project, ProjectEntryId,
+    ProjectPath, Worktree, WorktreeId,
 };
 use project_panel_settings::{
     ProjectPanelDockPosition, ProjectPanelSettings, ShowDiagnostics, ShowIndentGuides,
@@ -189,6 +189,10 @@ actions!(
         OpenPermanent,
         ToggleFocus,
         NewSearchInDirectory,
+        OpenAsGallery,
+        OpenInSplits,
+        CompareMarkedImages,
+        BrowseArchiveImages,
         UnfoldDirectory,
         FoldDirectory,
         SelectParent,
@@ -613,11 +617,13 @@ impl ProjectPanel {
             let worktree = worktree.read(cx);
             let is_root = Some(entry) == worktree.root_entry();
             let is_dir = entry.is_dir();
+            let is_archive = !is_dir && project::image_archives::is_archive_path(&entry.path);
             let is_foldable = auto_fold_dirs && self.is_foldable(entry, worktree);
             let is_unfoldable = auto_fold_dirs && self.is_unfoldable(entry, worktree);
             let is_read_only = project.is_read_only(cx);
             let is_remote = project.is_via_collab();
             let is_local = project.is_local();
+            let marked_images = self.marked_image_paths(cx).map_or(0, |paths| paths.len());
 
             let context_menu = ContextMenu::build(cx, |menu, _| {
                 menu.context(self.focus_handle.clone()).map(|menu| {
@@ -639,9 +645,24 @@ impl ProjectPanel {
                                 menu.action("Open in Default App", Box::new(OpenWithSystem))
                             })
                             .action("Open in Terminal", Box::new(OpenInTerminal))
+                            .when(marked_images > 1, |menu| {
+                                menu.separator()
+                                    .action("Open as Gallery", Box::new(OpenAsGallery))
+                                    .action("Open in Splits", Box::new(OpenInSplits))
+                                    .when(marked_images == 2, |menu| {
+                                        menu.action("Compare", Box::new(CompareMarkedImages))
+                                    })
+                            })
                             .when(is_dir, |menu| {
                                 menu.separator()
                                     .action("Find in Folder…", Box::new(NewSearchInDirectory))
+                                    .action("Open as Gallery", Box::new(OpenAsGallery))
+                            })
+                            .when(is_local && is_archive, |menu| {
+                                menu.separator().action(
+                                    "Browse Images in Archive",
+                                    Box::new(BrowseArchiveImages),
+                                )
                             })
                             .when(is_unfoldable, |menu| {
                                 menu.action("Unfold Directory", Box::new(UnfoldDirectory))
@@ -912,6 +933,9 @@ impl ProjectPanel {
     ) {
         if let Some((_, entry)) = self.selected_entry(cx) {
             if entry.is_file() {
+                if self.open_marked_images(cx) {
+                    return;
+                }
                 self.open_entry(entry.id, focus_opened_item, allow_preview, cx);
             } else {
                 self.toggle_expanded(entry.id, cx);
@@ -919,6 +943,37 @@ impl ProjectPanel {
         }
     }
 
+    /// Opens all of the marked entries together when they are all images, so that they are
+    /// loaded a few at a time while each already has a tab. Returns whether they were opened.
+    fn open_marked_images(&mut self, cx: &mut ViewContext<Self>) -> bool {
+        let Some(paths) = self.marked_image_paths(cx) else {
+            return false;
+        };
+        cx.dispatch_action(workspace::OpenImages { paths }.boxed_clone());
+        true
+    }
+
+    /// The sorted absolute paths of the marked entries, when there are several of them and they
+    /// are all images.
+    fn marked_image_paths(&self, cx: &AppContext) -> Option<Vec<PathBuf>> {
+        let marked_entries = self.marked_entries();
+        if marked_entries.len() < 2 {
+            return None;
+        }
+        let project = self.project.read(cx);
+        let mut paths = Vec::new();
+        for selection in marked_entries {
+            let worktree = project.worktree_for_id(selection.worktree_id, cx)?;
+            let worktree = worktree.read(cx);
+            let entry = worktree
+                .entry_for_id(selection.entry_id)
+                .filter(|entry| entry.is_file() && is_image_path(&entry.path))?;
+            paths.push(worktree.absolutize(&entry.path).log_err()?);
+        }
+        paths.sort();
+        Some(paths)
+    }
+
     fn confirm_edit(&mut self, cx: &mut ViewContext<Self>) -> Option<Task<Result<()>>> {
         let edit_state = self.edit_state.as_mut()?;
         cx.focus(&self.focus_handle);
@@ -1815,6 +1870,44 @@ impl ProjectPanel {
         }
     }
 
+    /// Opens a gallery of the marked images, or of the images in the selected folder.
+    fn open_as_gallery(&mut self, _: &OpenAsGallery, cx: &mut ViewContext<Self>) {
+        if let Some(paths) = self.marked_image_paths(cx) {
+            cx.dispatch_action(workspace::OpenImagesAsGallery { paths }.boxed_clone());
+        } else if let Some((worktree, entry)) = self.selected_sub_entry(cx) {
+            if entry.is_dir() {
+                if let Some(directory) = worktree.read(cx).absolutize(&entry.path).log_err() {
+                    cx.dispatch_action(workspace::OpenFolderAsGallery { directory }.boxed_clone())
+                }
+            }
+        }
+    }
+
+    fn open_in_splits(&mut self, _: &OpenInSplits, cx: &mut ViewContext<Self>) {
+        if let Some(paths) = self.marked_image_paths(cx) {
+            cx.dispatch_action(workspace::OpenImagesInSplits { paths }.boxed_clone());
+        }
+    }
+
+    fn compare_marked_images(&mut self, _: &CompareMarkedImages, cx: &mut ViewContext<Self>) {
+        if let Some([left, right]) = self
+            .marked_image_paths(cx)
+            .and_then(|paths| <[PathBuf; 2]>::try_from(paths).ok())
+        {
+            cx.dispatch_action(workspace::CompareImages { left, right }.boxed_clone());
+        }
+    }
+
+    fn browse_archive_images(&mut self, _: &BrowseArchiveImages, cx: &mut ViewContext<Self>) {
+        if let Some((worktree, entry)) = self.selected_sub_entry(cx) {
+            if entry.is_file() {
+                if let Some(path) = worktree.read(cx).absolutize(&entry.path).log_err() {
+                    cx.dispatch_action(workspace::OpenArchiveImages { path }.boxed_clone())
+                }
+            }
+        }
+    }
+
     pub fn new_search_in_directory(
         &mut self,
         _: &NewSearchInDirectory,
@@ -3528,6 +3621,10 @@ impl Render for ProjectPanel {
                             }
                         }))
                 })
+                .on_action(cx.listener(Self::open_as_gallery))
+                .on_action(cx.listener(Self::open_in_splits))
+                .on_action(cx.listener(Self::compare_marked_images))
+                .on_action(cx.listener(Self::browse_archive_images))
                 .when(project.is_local(), |el| {
                     el.on_action(cx.listener(Self::reveal_in_finder))
                         .on_action(cx.listener(Self::open_system))
@@ -371,6 +371,10 @@ messages!(
     (SyncExtensions, Background),
     (SyncExtensionsResponse, Background),
     (InstallExtension, Background),
+    (OpenImageByPath, Background),
+    (OpenImageResponse, Background),
+    (UpdateImageAnnotations, Foreground),
+    (CreateImageForPeer, Background),
 );
 
 request_messages!(
@@ -496,6 +500,7 @@ request_messages!(
     (CancelLanguageServerWork, Ack),
     (SyncExtensions, SyncExtensionsResponse),
     (InstallExtension, Ack),
+    (OpenImageByPath, OpenImageResponse),
 );
 
 entity_messages!(
@@ -510,6 +515,7 @@ entity_messages!(
     CloseBuffer,
     CopyProjectEntry,
     CreateBufferForPeer,
+    CreateImageForPeer,
     CreateProjectEntry,
     DeleteProjectEntry,
     ExpandProjectEntry,
@@ -537,6 +543,7 @@ entity_messages!(
     OpenBufferById,
     OpenBufferByPath,
     OpenBufferForSymbol,
+    OpenImageByPath,
     PerformRename,
     PrepareRename,
     RefreshInlayHints,
@@ -565,6 +572,7 @@ entity_messages!(
     OpenContext,
     CreateContext,
     UpdateContext,
+    UpdateImageAnnotations,
     SynchronizeContexts,
     LspExtSwitchSourceHeader,
     LanguageServerLog,
@@ -12,7 +12,7 @@ use project::{
     search::SearchQuery,
     task_store::TaskStore,
     worktree_store::WorktreeStore,
-    LspStore, LspStoreEvent, PrettierStore, ProjectPath, ToolchainStore, WorktreeId,
+    ImageStore, LspStore, LspStoreEvent, PrettierStore, ProjectPath, ToolchainStore, WorktreeId,
 };
 use remote::ssh_session::ChannelClient;
 use rpc::{
@@ -34,6 +34,7 @@ pub struct HeadlessProject {
     pub session: AnyProtoClient,
     pub worktree_store: Model<WorktreeStore>,
     pub buffer_store: Model<BufferStore>,
+    pub image_store: Model<ImageStore>,
     pub lsp_store: Model<LspStore>,
     pub task_store: Model<TaskStore>,
     pub settings_observer: Model<SettingsObserver>,
@@ -82,6 +83,11 @@ impl HeadlessProject {
             buffer_store.shared(SSH_PROJECT_ID, session.clone().into(), cx);
             buffer_store
         });
+        let image_store = cx.new_model(|cx| {
+            let mut image_store = ImageStore::local(worktree_store.clone(), cx);
+            image_store.shared(SSH_PROJECT_ID, session.clone().into(), cx);
+            image_store
+        });
         let prettier_store = cx.new_model(|cx| {
             PrettierStore::new(
                 node_runtime.clone(),
@@ -166,6 +172,7 @@ impl HeadlessProject {
 
         session.subscribe_to_entity(SSH_PROJECT_ID, &worktree_store);
         session.subscribe_to_entity(SSH_PROJECT_ID, &buffer_store);
+        session.subscribe_to_entity(SSH_PROJECT_ID, &image_store);
         session.subscribe_to_entity(SSH_PROJECT_ID, &cx.handle());
         session.subscribe_to_entity(SSH_PROJECT_ID, &lsp_store);
         session.subscribe_to_entity(SSH_PROJECT_ID, &task_store);
@@ -198,6 +205,7 @@ impl HeadlessProject {
         );
 
         BufferStore::init(&client);
+        ImageStore::init(&client);
         WorktreeStore::init(&client);
         SettingsObserver::init(&client);
         LspStore::init(&client);
@@ -210,6 +218,7 @@ impl HeadlessProject {
             fs,
             worktree_store,
             buffer_store,
+            image_store,
             lsp_store,
             task_store,
             next_entry_id: Default::default(),
@@ -12,6 +12,7 @@ use language::{
 use lsp::{CompletionContext, CompletionResponse, CompletionTriggerKind, LanguageServerName};
 use node_runtime::NodeRuntime;
 use project::{
+    image_store::TEST_PNG,
     search::{SearchQuery, SearchResult},
     Project, ProjectPath,
 };
@@ -1030,6 +1031,38 @@ async fn test_open_server_settings(cx: &mut TestAppContext, server_cx: &mut Test
     })
 }
 
+#[gpui::test]
+async fn test_remote_open_image(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
+    // A PNG followed by enough padding for the image to be sent in several chunks.
+    let mut content = TEST_PNG.to_vec();
+    content.extend((0..600_000).map(|ix| ix as u8));
+
+    let fs = FakeFs::new(server_cx.executor());
+    fs.insert_tree("/code", json!({ "project1": {} })).await;
+    fs.insert_file("/code/project1/image.png", content.clone())
+        .await;
+
+    let (project, _headless) = init_test(&fs, cx, server_cx).await;
+    let (worktree, _) = project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree("/code/project1", true, cx)
+        })
+        .await
+        .unwrap();
+    let worktree_id = cx.update(|cx| worktree.read(cx).id());
+
+    let image = project
+        .update(cx, |project, cx| {
+            project.open_image((worktree_id, Path::new("image.png")), cx)
+        })
+        .await
+        .unwrap();
+    image.read_with(cx, |image, _| {
+        assert_eq!(image.image.format, gpui::ImageFormat::Png);
+        assert_eq!(image.image.bytes, content);
+    });
+}
+
 #[gpui::test(iterations = 20)]
 async fn test_reconnect(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
     let fs = FakeFs::new(server_cx.executor());
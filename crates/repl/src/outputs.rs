@@ -14,7 +14,7 @@
 //! The module supports several output types, including:
 //! - Plain text
 //! - Markdown
-//! - Images (PNG and JPEG)
+//! - Images (PNG, JPEG and SVG)
 //! - Tables
 //! - Error messages
 //!
@@ -33,12 +33,12 @@
 //! This module is designed to work with Jupyter message protocols,
 //! interpreting and displaying various types of Jupyter output.
 
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
 use editor::{Editor, MultiBuffer};
 use gpui::{
-    percentage, Animation, AnimationExt, AnyElement, ClipboardItem, Model, Render, Transformation,
-    View, WeakView,
+    percentage, Animation, AnimationExt, AnyElement, ClipboardItem, Image, Model, Render,
+    Transformation, View, WeakView,
 };
 use language::Buffer;
 use runtimelib::{ExecutionState, JupyterMessageContent, MimeBundle, MimeType};
@@ -63,9 +63,10 @@ use workspace::Workspace;
 /// When deciding what to render from a collection of mediatypes, we need to rank them in order of importance
 fn rank_mime_type(mimetype: &MimeType) -> usize {
     match mimetype {
-        MimeType::DataTable(_) => 6,
-        MimeType::Png(_) => 4,
-        MimeType::Jpeg(_) => 3,
+        MimeType::DataTable(_) => 7,
+        MimeType::Png(_) => 5,
+        MimeType::Jpeg(_) => 4,
+        MimeType::Svg(_) => 3,
         MimeType::Markdown(_) => 2,
         MimeType::Plain(_) => 1,
         // All other media types are not supported in Zed at this time
@@ -84,6 +85,9 @@ pub(crate) trait OutputContent {
     fn buffer_content(&mut self, _cx: &mut WindowContext) -> Option<Model<Buffer>> {
         None
     }
+    fn image_content(&self, _cx: &WindowContext) -> Option<Arc<Image>> {
+        None
+    }
 }
 
 impl<V: OutputContent + 'static> OutputContent for View<V> {
@@ -102,6 +106,10 @@ impl<V: OutputContent + 'static> OutputContent for View<V> {
     fn buffer_content(&mut self, cx: &mut WindowContext) -> Option<Model<Buffer>> {
         self.update(cx, |item, cx| item.buffer_content(cx))
     }
+
+    fn image_content(&self, cx: &WindowContext) -> Option<Arc<Image>> {
+        self.read(cx).image_content(cx)
+    }
 }
 
 pub enum Output {
@@ -135,7 +143,8 @@ impl Output {
         workspace: WeakView<Workspace>,
         cx: &mut ViewContext<ExecutionView>,
     ) -> Option<AnyElement> {
-        if !v.has_clipboard_content(cx) && !v.has_buffer_content(cx) {
+        let image_content = v.image_content(cx);
+        if !v.has_clipboard_content(cx) && !v.has_buffer_content(cx) && image_content.is_none() {
             return None;
         }
 
@@ -197,6 +206,31 @@ impl Output {
                         })),
                     )
                 })
+                .when_some(image_content, |el, image| {
+                    el.child(
+                        IconButton::new(
+                            ElementId::Name("open-in-image-viewer".into()),
+                            IconName::Maximize,
+                        )
+                        .style(ButtonStyle::Transparent)
+                        .tooltip(move |cx| Tooltip::text("Open in Image Viewer", cx))
+                        .on_click(cx.listener({
+                            let workspace = workspace.clone();
+                            // Outputs only live in memory, so the image opens untitled.
+                            move |_, _, cx| {
+                                workspace
+                                    .update(cx, |workspace, cx| {
+                                        image_viewer::open_untitled_image(
+                                            workspace,
+                                            image.bytes.clone(),
+                                            cx,
+                                        )
+                                    })
+                                    .ok();
+                            }
+                        })),
+                    )
+                })
                 .into_any_element(),
         )
     }
@@ -279,6 +313,13 @@ impl Output {
                 },
                 Err(error) => Output::Message(format!("Failed to load image: {}", error)),
             },
+            Some(MimeType::Svg(svg)) => match ImageView::from_svg(svg, cx) {
+                Ok(view) => Output::Image {
+                    content: cx.new_view(|_| view),
+                    display_id,
+                },
+                Err(error) => Output::Message(format!("Failed to load image: {}", error)),
+            },
             Some(MimeType::DataTable(data)) => Output::Table {
                 content: cx.new_view(|cx| TableView::new(data, cx)),
                 display_id,
@@ -71,6 +71,24 @@ impl ImageView {
             image: Arc::new(gpui_image_data),
         })
     }
+
+    pub fn from_svg(svg: &str, cx: &WindowContext) -> Result<Self> {
+        let bytes = svg.as_bytes().to_vec();
+        let clipboard_image = Arc::new(Image {
+            format: ImageFormat::Svg,
+            id: gpui::hash(&bytes),
+            bytes,
+        });
+        let image = clipboard_image.to_image_data(cx.svg_renderer())?;
+        let size = image.size(0);
+
+        Ok(ImageView {
+            clipboard_image,
+            height: size.height.0 as u32,
+            width: size.width.0 as u32,
+            image,
+        })
+    }
 }
 
 impl Render for ImageView {
@@ -99,4 +117,8 @@ impl OutputContent for ImageView {
     fn has_clipboard_content(&self, _cx: &WindowContext) -> bool {
         true
     }
+
+    fn image_content(&self, _cx: &WindowContext) -> Option<Arc<Image>> {
+        Some(self.clipboard_image.clone())
+    }
 }
@@ -6,6 +6,7 @@ use command_palette_hooks::CommandPaletteFilter;
 use editor::EditorSettingsControls;
 use feature_flags::{FeatureFlag, FeatureFlagViewExt};
 use gpui::{actions, AppContext, EventEmitter, FocusHandle, FocusableView, View};
+use image_viewer::ImageViewerSettingsControls;
 use ui::prelude::*;
 use workspace::item::{Item, ItemEvent};
 use workspace::Workspace;
@@ -120,5 +121,12 @@ impl Render for SettingsPage {
                         .child(EditorSettingsControls::new()),
                 ),
             )
+            .child(
+                v_flex().gap_1().child(Label::new("Image Viewer")).child(
+                    v_flex()
+                        .elevation_2(cx)
+                        .child(ImageViewerSettingsControls::new()),
+                ),
+            )
     }
 }
@@ -0,0 +1,826 @@
+//! Support for the inline image protocols of iTerm2, kitty and sixel-capable terminals.
+//!
+//! Alacritty ignores these escape sequences, so the bytes coming from the pty are scanned before
+//! they reach it. Each image sequence is replaced by a placeholder that Alacritty does understand:
+//! a hyperlink with a unique URI over a single cell, marking where the image starts, followed by
+//! enough line feeds to reserve the rows the image covers. The decoded image is sent to the
+//! [`crate::Terminal`] separately, which finds the placeholder in the grid to anchor it.
+
+use std::{
+    io::{self, Cursor, Read, Write},
+    sync::Arc,
+};
+
+use alacritty_terminal::{
+    event::{OnResize, WindowSize},
+    tty::{ChildEvent, EventedPty, EventedReadWrite},
+};
+use base64::{
+    alphabet,
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+    Engine as _,
+};
+use futures::channel::mpsc::UnboundedSender;
+use polling::{Event, PollMode, Poller};
+
+/// The tallest an inline image may be, in terminal rows.
+pub const MAX_INLINE_IMAGE_ROWS: usize = 40;
+
+/// Image sequences longer than this are dropped without being decoded.
+const MAX_SEQUENCE_LEN: usize = 64 * 1024 * 1024;
+
+/// The largest sixel image that will be decoded, in pixels along either side.
+const MAX_SIXEL_SIZE: usize = 8192;
+
+const INLINE_IMAGE_URI_PREFIX: &str = "zed-inline-image:";
+
+const ESC: u8 = 0x1b;
+const BEL: u8 = 0x07;
+
+const BASE64_INDIFFERENT: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// An image printed by a program running in the terminal.
+#[derive(Clone, Debug)]
+pub struct InlineImage {
+    /// Identifies the placeholder hyperlink written into the grid in place of the image.
+    pub id: u64,
+    pub image: Arc<gpui::Image>,
+    /// How many terminal rows were reserved for the image.
+    pub rows: usize,
+}
+
+/// The hyperlink URI that marks the cell where the image with the given id starts.
+pub fn inline_image_uri(id: u64) -> String {
+    format!("{INLINE_IMAGE_URI_PREFIX}{id}")
+}
+
+pub fn is_inline_image_uri(uri: &str) -> bool {
+    uri.starts_with(INLINE_IMAGE_URI_PREFIX)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SequenceKind {
+    /// `ESC ]`, used by iTerm2.
+    Osc,
+    /// `ESC _`, used by kitty.
+    Apc,
+    /// `ESC P`, used by sixel.
+    Dcs,
+}
+
+impl SequenceKind {
+    fn introducer(self) -> u8 {
+        match self {
+            SequenceKind::Osc => b']',
+            SequenceKind::Apc => b'_',
+            SequenceKind::Dcs => b'P',
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ScanState {
+    Ground,
+    /// An `ESC` was seen and hasn't been forwarded yet.
+    Escape,
+    /// Buffering a sequence that may carry an image.
+    Sequence(SequenceKind),
+    /// An `ESC` was seen inside a buffered sequence, possibly starting its terminator.
+    SequenceEscape(SequenceKind),
+    /// Skipping to the end of a sequence that doesn't carry an image, forwarding it when
+    /// `forward` is set and dropping it otherwise.
+    Passthrough {
+        kind: SequenceKind,
+        forward: bool,
+    },
+    PassthroughEscape {
+        kind: SequenceKind,
+        forward: bool,
+    },
+}
+
+/// The chunks of a kitty image transmission received so far.
+struct KittyTransmission {
+    keys: KittyKeys,
+    payload: Vec<u8>,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct KittyKeys {
+    action: Option<u8>,
+    format: Option<u32>,
+    medium: Option<u8>,
+    compressed: bool,
+    width: u32,
+    height: u32,
+    more: bool,
+}
+
+/// Finds inline image sequences in the output of a pty, forwarding everything else unchanged.
+pub struct InlineImageScanner {
+    state: ScanState,
+    sequence: Vec<u8>,
+    kitty_transmission: Option<KittyTransmission>,
+    next_image_id: u64,
+}
+
+impl Default for InlineImageScanner {
+    fn default() -> Self {
+        Self {
+            state: ScanState::Ground,
+            sequence: Vec::new(),
+            kitty_transmission: None,
+            next_image_id: 0,
+        }
+    }
+}
+
+impl InlineImageScanner {
+    /// Scans the next bytes read from the pty, appending the bytes meant for the terminal to
+    /// `output` and returning the images that were completed.
+    pub fn scan(
+        &mut self,
+        input: &[u8],
+        window_size: &WindowSize,
+        output: &mut Vec<u8>,
+    ) -> Vec<InlineImage> {
+        let mut images = Vec::new();
+        for &byte in input {
+            self.advance(byte, window_size, output, &mut images);
+        }
+        images
+    }
+
+    fn advance(
+        &mut self,
+        byte: u8,
+        window_size: &WindowSize,
+        output: &mut Vec<u8>,
+        images: &mut Vec<InlineImage>,
+    ) {
+        match self.state {
+            ScanState::Ground => {
+                if byte == ESC {
+                    self.state = ScanState::Escape;
+                } else {
+                    output.push(byte);
+                }
+            }
+            ScanState::Escape => {
+                let kind = match byte {
+                    b']' => Some(SequenceKind::Osc),
+                    b'_' => Some(SequenceKind::Apc),
+                    b'P' => Some(SequenceKind::Dcs),
+                    _ => None,
+                };
+                if let Some(kind) = kind {
+                    self.sequence.clear();
+                    self.state = ScanState::Sequence(kind);
+                } else {
+                    output.push(ESC);
+                    if byte != ESC {
+                        output.push(byte);
+                        self.state = ScanState::Ground;
+                    }
+                }
+            }
+            ScanState::Sequence(kind) => {
+                if kind == SequenceKind::Osc && byte == BEL {
+                    self.state = ScanState::Ground;
+                    self.finish_sequence(kind, &[BEL], window_size, output, images);
+                } else if byte == ESC {
+                    self.state = ScanState::SequenceEscape(kind);
+                } else {
+                    self.sequence.push(byte);
+                    if !may_carry_image(kind, &self.sequence) {
+                        output.extend_from_slice(&[ESC, kind.introducer()]);
+                        output.append(&mut self.sequence);
+                        self.state = ScanState::Passthrough {
+                            kind,
+                            forward: true,
+                        };
+                    } else if self.sequence.len() > MAX_SEQUENCE_LEN {
+                        self.sequence = Vec::new();
+                        self.kitty_transmission = None;
+                        self.state = ScanState::Passthrough {
+                            kind,
+                            forward: false,
+                        };
+                    }
+                }
+            }
+            ScanState::SequenceEscape(kind) => {
+                if byte == b'\\' {
+                    self.state = ScanState::Ground;
+                    self.finish_sequence(kind, &[ESC, b'\\'], window_size, output, images);
+                } else {
+                    // The sequence was cancelled by the start of another one.
+                    output.extend_from_slice(&[ESC, kind.introducer()]);
+                    output.append(&mut self.sequence);
+                    self.state = ScanState::Escape;
+                    self.advance(byte, window_size, output, images);
+                }
+            }
+            ScanState::Passthrough { kind, forward } => {
+                if byte == ESC {
+                    self.state = ScanState::PassthroughEscape { kind, forward };
+                    return;
+                }
+                if forward {
+                    output.push(byte);
+                }
+                if kind == SequenceKind::Osc && byte == BEL {
+                    self.state = ScanState::Ground;
+                }
+            }
+            ScanState::PassthroughEscape { forward, .. } => {
+                if byte == b'\\' {
+                    if forward {
+                        output.extend_from_slice(&[ESC, b'\\']);
+                    }
+                    self.state = ScanState::Ground;
+                } else {
+                    self.state = ScanState::Escape;
+                    self.advance(byte, window_size, output, images);
+                }
+            }
+        }
+    }
+
+    fn finish_sequence(
+        &mut self,
+        kind: SequenceKind,
+        terminator: &[u8],
+        window_size: &WindowSize,
+        output: &mut Vec<u8>,
+        images: &mut Vec<InlineImage>,
+    ) {
+        let sequence = std::mem::take(&mut self.sequence);
+        if !is_image_sequence(kind, &sequence) {
+            output.extend_from_slice(&[ESC, kind.introducer()]);
+            output.extend_from_slice(&sequence);
+            output.extend_from_slice(terminator);
+            return;
+        }
+
+        let image = match kind {
+            SequenceKind::Osc => decode_iterm2_image(&sequence),
+            SequenceKind::Apc => self.decode_kitty_chunk(&sequence),
+            SequenceKind::Dcs => decode_sixel_image(&sequence),
+        };
+        let Some((image, (width, height))) = image else {
+            return;
+        };
+
+        let id = self.next_image_id;
+        self.next_image_id += 1;
+        let rows = image_rows(width, height, window_size);
+        write_placeholder(id, rows, output);
+        images.push(InlineImage { id, image, rows });
+    }
+
+    fn decode_kitty_chunk(&mut self, sequence: &[u8]) -> Option<(Arc<gpui::Image>, (u32, u32))> {
+        let (control, payload) = match sequence[1..].iter().position(|byte| *byte == b';') {
+            Some(ix) => (&sequence[1..ix + 1], &sequence[ix + 2..]),
+            None => (&sequence[1..], &[][..]),
+        };
+        let keys = parse_kitty_keys(control);
+
+        let mut transmission = match self.kitty_transmission.take() {
+            // Continuation chunks only carry the `m` key.
+            Some(mut transmission) => {
+                transmission.keys.more = keys.more;
+                transmission
+            }
+            None => KittyTransmission {
+                keys,
+                payload: Vec::new(),
+            },
+        };
+        transmission.payload.extend_from_slice(payload);
+        if transmission.keys.more {
+            self.kitty_transmission = Some(transmission);
+            return None;
+        }
+
+        let keys = transmission.keys;
+        // Only images transmitted directly and displayed straight away are supported.
+        if keys.action != Some(b'T')
+            || keys.medium.map_or(false, |medium| medium != b'd')
+            || keys.compressed
+        {
+            return None;
+        }
+        let data = BASE64_INDIFFERENT.decode(&transmission.payload).ok()?;
+        match keys.format.unwrap_or(32) {
+            100 => encoded_image(data),
+            24 => rgb_image(keys.width, keys.height, data),
+            32 => rgba_image(image::RgbaImage::from_raw(keys.width, keys.height, data)?),
+            _ => None,
+        }
+    }
+}
+
+/// Whether a partially buffered sequence could still turn out to carry an image.
+fn may_carry_image(kind: SequenceKind, sequence: &[u8]) -> bool {
+    match kind {
+        SequenceKind::Osc => {
+            const PREFIX: &[u8] = b"1337;File=";
+            let len = sequence.len().min(PREFIX.len());
+            sequence[..len] == PREFIX[..len]
+        }
+        SequenceKind::Apc => sequence[0] == b'G',
+        SequenceKind::Dcs => match sequence
+            .iter()
+            .position(|byte| !byte.is_ascii_digit() && *byte != b';')
+        {
+            Some(ix) => sequence[ix] == b'q',
+            None => true,
+        },
+    }
+}
+
+fn is_image_sequence(kind: SequenceKind, sequence: &[u8]) -> bool {
+    match kind {
+        SequenceKind::Osc => sequence.starts_with(b"1337;File="),
+        SequenceKind::Apc => sequence.first() == Some(&b'G'),
+        SequenceKind::Dcs => sequence.contains(&b'q') && may_carry_image(kind, sequence),
+    }
+}
+
+/// How many rows an image takes up once scaled down to fit the width of the terminal.
+fn image_rows(width: u32, height: u32, window_size: &WindowSize) -> usize {
+    if window_size.cell_height == 0 || width == 0 {
+        return 1;
+    }
+    let available_width = window_size.num_cols as f32 * window_size.cell_width as f32;
+    let scale = if available_width > 0. {
+        (available_width / width as f32).min(1.)
+    } else {
+        1.
+    };
+    let rows = (height as f32 * scale / window_size.cell_height as f32).ceil() as usize;
+    rows.clamp(1, MAX_INLINE_IMAGE_ROWS)
+}
+
+/// Marks the current cell with the image's hyperlink and moves the cursor down to the last row
+/// covered by the image, scrolling the terminal as needed.
+fn write_placeholder(id: u64, rows: usize, output: &mut Vec<u8>) {
+    write!(
+        output,
+        "\x1b]8;;{}\x1b\\ \x1b]8;;\x1b\\",
+        inline_image_uri(id)
+    )
+    .ok();
+    output.extend(std::iter::repeat(b'\n').take(rows.saturating_sub(1)));
+}
+
+/// Decodes `1337;File=[arguments]:<base64 data>`.
+fn decode_iterm2_image(sequence: &[u8]) -> Option<(Arc<gpui::Image>, (u32, u32))> {
+    let sequence = &sequence[b"1337;File=".len()..];
+    let separator = sequence.iter().position(|byte| *byte == b':')?;
+    let arguments = std::str::from_utf8(&sequence[..separator]).ok()?;
+    let inline = arguments
+        .split(';')
+        .filter_map(|argument| argument.split_once('='))
+        .any(|(key, value)| key == "inline" && value == "1");
+    // Images that aren't inline are file downloads, which aren't supported.
+    if !inline {
+        return None;
+    }
+    let data = BASE64_INDIFFERENT.decode(&sequence[separator + 1..]).ok()?;
+    encoded_image(data)
+}
+
+fn parse_kitty_keys(control: &[u8]) -> KittyKeys {
+    let mut keys = KittyKeys::default();
+    for pair in control.split(|byte| *byte == b',') {
+        let (key, value) = match pair {
+            [key, b'=', value @ ..] => (*key, value),
+            _ => continue,
+        };
+        let number = || std::str::from_utf8(value).ok()?.parse::<u32>().ok();
+        match key {
+            b'a' => keys.action = value.first().copied(),
+            b'f' => keys.format = number(),
+            b't' => keys.medium = value.first().copied(),
+            b'o' => keys.compressed = !value.is_empty(),
+            b's' => keys.width = number().unwrap_or(0),
+            b'v' => keys.height = number().unwrap_or(0),
+            b'm' => keys.more = value == b"1",
+            _ => {}
+        }
+    }
+    keys
+}
+
+fn encoded_image(data: Vec<u8>) -> Option<(Arc<gpui::Image>, (u32, u32))> {
+    let format = match image::guess_format(&data).ok()? {
+        image::ImageFormat::Png => gpui::ImageFormat::Png,
+        image::ImageFormat::Jpeg => gpui::ImageFormat::Jpeg,
+        image::ImageFormat::WebP => gpui::ImageFormat::Webp,
+        image::ImageFormat::Gif => gpui::ImageFormat::Gif,
+        image::ImageFormat::Bmp => gpui::ImageFormat::Bmp,
+        image::ImageFormat::Tiff => gpui::ImageFormat::Tiff,
+        _ => return None,
+    };
+    let dimensions = image::ImageReader::new(Cursor::new(&data))
+        .with_guessed_format()
+        .ok()?
+        .into_dimensions()
+        .ok()?;
+    let image = gpui::Image {
+        id: gpui::hash(&data),
+        format,
+        bytes: data,
+    };
+    Some((Arc::new(image), dimensions))
+}
+
+fn rgb_image(width: u32, height: u32, data: Vec<u8>) -> Option<(Arc<gpui::Image>, (u32, u32))> {
+    let image = image::RgbImage::from_raw(width, height, data)?;
+    rgba_image(image::DynamicImage::ImageRgb8(image).into_rgba8())
+}
+
+fn rgba_image(image: image::RgbaImage) -> Option<(Arc<gpui::Image>, (u32, u32))> {
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+        .ok()?;
+    encoded_image(png)
+}
+
+/// Decodes `[parameters]q<sixel data>` into an image.
+fn decode_sixel_image(sequence: &[u8]) -> Option<(Arc<gpui::Image>, (u32, u32))> {
+    let data_start = sequence.iter().position(|byte| *byte == b'q')? + 1;
+    rgba_image(SixelCanvas::decode(&sequence[data_start..])?)
+}
+
+/// The default sixel palette of the VT340, as RGB percentages.
+const SIXEL_DEFAULT_PALETTE: [[u8; 3]; 16] = [
+    [0, 0, 0],
+    [20, 20, 80],
+    [80, 13, 13],
+    [20, 80, 20],
+    [80, 20, 80],
+    [20, 80, 80],
+    [80, 80, 20],
+    [53, 53, 53],
+    [26, 26, 26],
+    [33, 33, 60],
+    [60, 26, 26],
+    [33, 60, 33],
+    [60, 33, 60],
+    [33, 60, 60],
+    [60, 60, 33],
+    [80, 80, 80],
+];
+
+#[derive(Default)]
+struct SixelCanvas {
+    width: usize,
+    height: usize,
+    pixels: Vec<[u8; 4]>,
+}
+
+impl SixelCanvas {
+    fn decode(data: &[u8]) -> Option<image::RgbaImage> {
+        let mut canvas = SixelCanvas::default();
+        let mut palette = SIXEL_DEFAULT_PALETTE
+            .iter()
+            .map(|rgb| rgb_from_percentages(rgb[0] as u32, rgb[1] as u32, rgb[2] as u32))
+            .collect::<Vec<_>>();
+        palette.resize(256, [0, 0, 0, 255]);
+        let mut color = palette[0];
+        let (mut x, mut y) = (0, 0);
+
+        let mut ix = 0;
+        while ix < data.len() {
+            let byte = data[ix];
+            ix += 1;
+            match byte {
+                b'"' => {
+                    let (parameters, len) = parse_sixel_parameters(&data[ix..]);
+                    ix += len;
+                    if let [_, _, width, height, ..] = parameters[..] {
+                        canvas.grow(width as usize, height as usize)?;
+                    }
+                }
+                b'#' => {
+                    let (parameters, len) = parse_sixel_parameters(&data[ix..]);
+                    ix += len;
+                    let register = *parameters.first()? as usize % palette.len();
+                    if let [_, space, a, b, c, ..] = parameters[..] {
+                        palette[register] = match space {
+                            1 => rgb_from_hls(a, b, c),
+                            _ => rgb_from_percentages(a, b, c),
+                        };
+                    }
+                    color = palette[register];
+                }
+                b'!' => {
+                    let (parameters, len) = parse_sixel_parameters(&data[ix..]);
+                    ix += len;
+                    let count = *parameters.first()? as usize;
+                    let sixel = *data.get(ix)?;
+                    ix += 1;
+                    canvas.paint(x, y, count, sixel, color)?;
+                    x += count;
+                }
+                b'$' => x = 0,
+                b'-' => {
+                    x = 0;
+                    y += 6;
+                }
+                0x3f..=0x7e => {
+                    canvas.paint(x, y, 1, byte, color)?;
+                    x += 1;
+                }
+                _ => {}
+            }
+        }
+
+        if canvas.width == 0 || canvas.height == 0 {
+            return None;
+        }
+        let data = canvas.pixels.into_iter().flatten().collect();
+        image::RgbaImage::from_raw(canvas.width as u32, canvas.height as u32, data)
+    }
+
+    fn grow(&mut self, width: usize, height: usize) -> Option<()> {
+        if width > MAX_SIXEL_SIZE || height > MAX_SIXEL_SIZE {
+            return None;
+        }
+        if width <= self.width && height <= self.height {
+            return Some(());
+        }
+        let (new_width, new_height) = (width.max(self.width), height.max(self.height));
+        let mut pixels = vec![[0; 4]; new_width * new_height];
+        for row in 0..self.height {
+            let source = &self.pixels[row * self.width..(row + 1) * self.width];
+            pixels[row * new_width..row * new_width + self.width].copy_from_slice(source);
+        }
+        self.width = new_width;
+        self.height = new_height;
+        self.pixels = pixels;
+        Some(())
+    }
+
+    /// Paints `count` copies of a sixel, a column of six pixels, starting at `x`.
+    fn paint(&mut self, x: usize, y: usize, count: usize, sixel: u8, color: [u8; 4]) -> Option<()> {
+        let bits = sixel.checked_sub(0x3f)?;
+        if bits == 0 {
+            return Some(());
+        }
+        self.grow(x + count, y + 6)?;
+        for bit in 0..6 {
+            if bits & (1 << bit) != 0 {
+                let row = (y + bit) * self.width;
+                self.pixels[row + x..row + x + count].fill(color);
+            }
+        }
+        Some(())
+    }
+}
+
+/// Parses `Pn;Pn;...`, returning the numbers and how many bytes they took up.
+fn parse_sixel_parameters(data: &[u8]) -> (Vec<u32>, usize) {
+    let len = data
+        .iter()
+        .position(|byte| !byte.is_ascii_digit() && *byte != b';')
+        .unwrap_or(data.len());
+    let parameters = data[..len]
+        .split(|byte| *byte == b';')
+        .map(|parameter| {
+            std::str::from_utf8(parameter)
+                .ok()
+                .and_then(|parameter| parameter.parse().ok())
+                .unwrap_or(0)
+        })
+        .collect();
+    (parameters, len)
+}
+
+fn rgb_from_percentages(r: u32, g: u32, b: u32) -> [u8; 4] {
+    let channel = |value: u32| (value.min(100) * 255 / 100) as u8;
+    [channel(r), channel(g), channel(b), 255]
+}
+
+/// Converts a sixel HLS color, whose hue angle starts at blue rather than red.
+fn rgb_from_hls(hue: u32, lightness: u32, saturation: u32) -> [u8; 4] {
+    let hue = ((hue + 240) % 360) as f32;
+    let rgba = gpui::hsla(
+        hue / 360.,
+        saturation.min(100) as f32 / 100.,
+        lightness.min(100) as f32 / 100.,
+        1.,
+    )
+    .to_rgb();
+    let channel = |value: f32| (value * 255.).round() as u8;
+    [channel(rgba.r), channel(rgba.g), channel(rgba.b), 255]
+}
+
+/// Wraps a pty so that the inline images in its output are sent to the terminal instead of
+/// being handed to Alacritty.
+pub struct InlineImagePty<T: EventedPty> {
+    pty: T,
+    scanner: InlineImageScanner,
+    window_size: WindowSize,
+    read_buffer: Vec<u8>,
+    /// Scanned output that didn't fit in the last read.
+    pending_output: Vec<u8>,
+    images_tx: UnboundedSender<InlineImage>,
+}
+
+impl<T: EventedPty> InlineImagePty<T> {
+    pub fn new(pty: T, window_size: WindowSize, images_tx: UnboundedSender<InlineImage>) -> Self {
+        Self {
+            pty,
+            scanner: InlineImageScanner::default(),
+            window_size,
+            read_buffer: Vec::new(),
+            pending_output: Vec::new(),
+            images_tx,
+        }
+    }
+}
+
+impl<T: EventedPty> Read for InlineImagePty<T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pending_output.is_empty() {
+            self.read_buffer.resize(buf.len(), 0);
+            loop {
+                let len = self.pty.reader().read(&mut self.read_buffer)?;
+                if len == 0 {
+                    return Ok(0);
+                }
+                let images = self.scanner.scan(
+                    &self.read_buffer[..len],
+                    &self.window_size,
+                    &mut self.pending_output,
+                );
+                for image in images {
+                    self.images_tx.unbounded_send(image).ok();
+                }
+                // Returning no bytes would signal the end of the output, so keep reading while
+                // everything read so far belongs to an unfinished image sequence.
+                if !self.pending_output.is_empty() {
+                    break;
+                }
+            }
+        }
+
+        let len = buf.len().min(self.pending_output.len());
+        buf[..len].copy_from_slice(&self.pending_output[..len]);
+        self.pending_output.drain(..len);
+        Ok(len)
+    }
+}
+
+impl<T: EventedPty> EventedReadWrite for InlineImagePty<T> {
+    type Reader = Self;
+    type Writer = T::Writer;
+
+    unsafe fn register(
+        &mut self,
+        poll: &Arc<Poller>,
+        event: Event,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        self.pty.register(poll, event, mode)
+    }
+
+    fn reregister(&mut self, poll: &Arc<Poller>, event: Event, mode: PollMode) -> io::Result<()> {
+        self.pty.reregister(poll, event, mode)
+    }
+
+    fn deregister(&mut self, poll: &Arc<Poller>) -> io::Result<()> {
+        self.pty.deregister(poll)
+    }
+
+    fn reader(&mut self) -> &mut Self::Reader {
+        self
+    }
+
+    fn writer(&mut self) -> &mut Self::Writer {
+        self.pty.writer()
+    }
+}
+
+impl<T: EventedPty> EventedPty for InlineImagePty<T> {
+    fn next_child_event(&mut self) -> Option<ChildEvent> {
+        self.pty.next_child_event()
+    }
+}
+
+impl<T: EventedPty + OnResize> OnResize for InlineImagePty<T> {
+    fn on_resize(&mut self, window_size: WindowSize) {
+        self.window_size = window_size;
+        self.pty.on_resize(window_size);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window_size() -> WindowSize {
+        WindowSize {
+            num_lines: 24,
+            num_cols: 80,
+            cell_width: 8,
+            cell_height: 16,
+        }
+    }
+
+    fn png_base64(width: u32, height: u32) -> String {
+        let mut png = Vec::new();
+        image::RgbaImage::new(width, height)
+            .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        base64::engine::general_purpose::STANDARD.encode(png)
+    }
+
+    fn scan_chunks(chunks: &[&[u8]]) -> (Vec<u8>, Vec<InlineImage>) {
+        let mut scanner = InlineImageScanner::default();
+        let mut output = Vec::new();
+        let mut images = Vec::new();
+        for chunk in chunks {
+            images.extend(scanner.scan(chunk, &window_size(), &mut output));
+        }
+        (output, images)
+    }
+
+    #[test]
+    fn test_other_sequences_pass_through() {
+        let input = b"a\x1b[31mb\x1b]0;title\x07c\x1b]8;;https://zed.dev\x1b\\d";
+        let (output, images) = scan_chunks(&[input]);
+        assert_eq!(output, input);
+        assert!(images.is_empty());
+
+        let input = b"\x1b]1337;SetMark\x07\x1bP1$r\x1b\\\x1b_Xabc\x1b\\";
+        let (output, images) = scan_chunks(&[&input[..5], &input[5..]]);
+        assert_eq!(output, input);
+        assert!(images.is_empty());
+    }
+
+    #[test]
+    fn test_iterm2_image() {
+        let sequence = format!(
+            "before\x1b]1337;File=name=eA==;inline=1:{}\x07after",
+            png_base64(16, 40)
+        );
+        let (output, images) =
+            scan_chunks(&[&sequence.as_bytes()[..20], &sequence.as_bytes()[20..]]);
+        assert_eq!(images.len(), 1);
+        assert_eq!(images[0].rows, 3);
+        assert_eq!(images[0].image.format, gpui::ImageFormat::Png);
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            format!(
+                "before\x1b]8;;{}\x1b\\ \x1b]8;;\x1b\\\n\nafter",
+                inline_image_uri(images[0].id)
+            )
+        );
+
+        // Downloads are dropped rather than displayed.
+        let sequence = format!("\x1b]1337;File=name=eA==:{}\x1b\\", png_base64(1, 1));
+        let (output, images) = scan_chunks(&[sequence.as_bytes()]);
+        assert!(output.is_empty());
+        assert!(images.is_empty());
+    }
+
+    #[test]
+    fn test_kitty_image() {
+        let data = base64::engine::general_purpose::STANDARD.encode([255u8; 2 * 3 * 4]);
+        let (first, second) = data.split_at(8);
+        let input = format!("\x1b_Ga=T,f=32,s=2,v=3,m=1;{first}\x1b\\\x1b_Gm=0;{second}\x1b\\");
+        let (output, images) = scan_chunks(&[input.as_bytes()]);
+        assert_eq!(images.len(), 1);
+        assert_eq!(images[0].rows, 1);
+        assert!(!output.is_empty());
+
+        // Images that are only stored for later aren't displayed.
+        let input = format!("\x1b_Ga=t,f=100;{}\x1b\\", png_base64(1, 1));
+        let (_, images) = scan_chunks(&[input.as_bytes()]);
+        assert!(images.is_empty());
+    }
+
+    #[test]
+    fn test_sixel_image() {
+        // Two columns of red pixels, six tall, followed by a third column of green.
+        let input = b"\x1bPq\"1;1;3;6#1;2;100;0;0#1!2~#2;2;0;100;0~\x1b\\";
+        let (_, images) = scan_chunks(&[input]);
+        assert_eq!(images.len(), 1);
+
+        let image = image::load_from_memory(&images[0].image.bytes)
+            .unwrap()
+            .into_rgba8();
+        assert_eq!(image.dimensions(), (3, 6));
+        assert_eq!(image.get_pixel(1, 5).0, [255, 0, 0, 255]);
+        assert_eq!(image.get_pixel(2, 0).0, [0, 255, 0, 255]);
+    }
+}
@@ -1,3 +1,4 @@
+pub mod inline_images;
 pub mod mappings;
 
 pub use alacritty_terminal;
@@ -8,7 +9,7 @@ pub mod terminal_settings;
 use alacritty_terminal::{
     event::{Event as AlacTermEvent, EventListener, Notify, WindowSize},
     event_loop::{EventLoop, Msg, Notifier},
-    grid::{Dimensions, Scroll as AlacScroll},
+    grid::{Dimensions, Grid, Scroll as AlacScroll},
     index::{Boundary, Column, Direction as AlacDirection, Line, Point as AlacPoint},
     selection::{Selection, SelectionRange, SelectionType},
     sync::FairMutex,
@@ -38,6 +39,7 @@ use mappings::mouse::{
 
 use collections::{HashMap, VecDeque};
 use futures::StreamExt;
+use inline_images::{inline_image_uri, is_inline_image_uri, InlineImage, InlineImagePty};
 use pty_info::PtyProcessInfo;
 use serde::{Deserialize, Serialize};
 use settings::Settings;
@@ -309,6 +311,10 @@ impl Display for TerminalError {
 // https://github.com/alacritty/alacritty/blob/cb3a79dbf6472740daca8440d5166c1d4af5029e/extra/man/alacritty.5.scd?plain=1#L207-L213
 const DEFAULT_SCROLL_HISTORY_LINES: usize = 10_000;
 const MAX_SCROLL_HISTORY_LINES: usize = 100_000;
+const MAX_INLINE_IMAGES: usize = 64;
+const MAX_PENDING_INLINE_IMAGES: usize = 16;
+/// How many lines above its last known position an inline image's placeholder is looked for.
+const MAX_INLINE_IMAGE_SEARCH_LINES: i32 = 1000;
 const URL_REGEX: &str = r#"(ipfs:|ipns:|magnet:|mailto:|gemini://|gopher://|https://|http://|news:|file://|git://|ssh:|ftp://)[^\u{0000}-\u{001F}\u{007F}-\u{009F}<>"\s{-}\^⟨⟩`]+"#;
 // Optional suffix matches MSBuild diagnostic suffixes for path parsing in PathLikeWithPosition
 // https://learn.microsoft.com/en-us/visualstudio/msbuild/msbuild-diagnostic-format-for-tasks
@@ -318,6 +324,7 @@ const WORD_REGEX: &str =
 pub struct TerminalBuilder {
     terminal: Terminal,
     events_rx: UnboundedReceiver<AlacTermEvent>,
+    inline_images_rx: UnboundedReceiver<InlineImage>,
 }
 
 impl TerminalBuilder {
@@ -434,6 +441,10 @@ impl TerminalBuilder {
 
         let pty_info = PtyProcessInfo::new(&pty);
 
+        //Pull the inline images out of the pty's output before Alacritty drops them
+        let (inline_images_tx, inline_images_rx) = unbounded();
+        let pty = InlineImagePty::new(pty, TerminalSize::default().into(), inline_images_tx);
+
         //And connect them together
         let event_loop = EventLoop::new(
             term.clone(),
@@ -471,11 +482,14 @@ impl TerminalBuilder {
             word_regex: RegexSearch::new(WORD_REGEX).unwrap(),
             vi_mode_enabled: false,
             is_ssh_terminal,
+            pending_inline_images: Vec::new(),
+            inline_images: Vec::new(),
         };
 
         Ok(TerminalBuilder {
             terminal,
             events_rx,
+            inline_images_rx,
         })
     }
 
@@ -538,6 +552,17 @@ impl TerminalBuilder {
         })
         .detach();
 
+        cx.spawn(|terminal, mut cx| async move {
+            while let Some(image) = self.inline_images_rx.next().await {
+                terminal.update(&mut cx, |terminal, cx| {
+                    terminal.add_inline_image(image, cx);
+                })?;
+            }
+
+            anyhow::Ok(())
+        })
+        .detach();
+
         self.terminal
     }
 }
@@ -569,6 +594,15 @@ pub struct TerminalContent {
     pub cursor_char: char,
     pub size: TerminalSize,
     pub last_hovered_word: Option<HoveredWord>,
+    pub inline_images: Vec<TerminalInlineImage>,
+}
+
+/// An image printed by the running program, anchored to the grid cell where it starts.
+#[derive(Clone, Debug)]
+pub struct TerminalInlineImage {
+    pub point: AlacPoint,
+    pub rows: usize,
+    pub image: Arc<gpui::Image>,
 }
 
 #[derive(Clone)]
@@ -593,6 +627,7 @@ impl Default for TerminalContent {
             cursor_char: Default::default(),
             size: Default::default(),
             last_hovered_word: None,
+            inline_images: Vec::new(),
         }
     }
 }
@@ -629,6 +664,16 @@ pub struct Terminal {
     task: Option<TaskState>,
     vi_mode_enabled: bool,
     is_ssh_terminal: bool,
+    /// Images received from the pty whose placeholder hasn't been found in the grid yet.
+    pending_inline_images: Vec<InlineImage>,
+    inline_images: Vec<AnchoredInlineImage>,
+}
+
+struct AnchoredInlineImage {
+    image: InlineImage,
+    point: AlacPoint,
+    /// The size of the scrollback when the image was last found, to tell how far it has moved.
+    history_size: usize,
 }
 
 pub struct TaskState {
@@ -1305,7 +1350,63 @@ impl Terminal {
             self.process_terminal_event(&e, &mut terminal, cx)
         }
 
+        self.update_inline_images(&terminal);
         self.last_content = Self::make_content(&terminal, &self.last_content);
+        self.last_content.inline_images = self
+            .inline_images
+            .iter()
+            .map(|anchored| TerminalInlineImage {
+                point: anchored.point,
+                rows: anchored.image.rows,
+                image: anchored.image.image.clone(),
+            })
+            .collect();
+    }
+
+    fn add_inline_image(&mut self, image: InlineImage, cx: &mut ModelContext<Self>) {
+        if self.pending_inline_images.len() >= MAX_PENDING_INLINE_IMAGES {
+            self.pending_inline_images.remove(0);
+        }
+        self.pending_inline_images.push(image);
+        cx.notify();
+    }
+
+    /// Follows the inline images as the output scrolls, dropping those whose placeholder is no
+    /// longer in the grid, and anchors the images whose placeholder was written since.
+    fn update_inline_images(&mut self, term: &Term<ZedListener>) {
+        let grid = term.grid();
+        let history_size = grid.history_size();
+        let bottommost_line = grid.bottommost_line();
+        self.inline_images.retain_mut(|anchored| {
+            // Lines only move up, by as much as the scrollback grew unless it is already full.
+            let scrolled = history_size.saturating_sub(anchored.history_size) as i32;
+            let expected_line = Line(anchored.point.line.0 - scrolled).min(bottommost_line);
+            let Some(point) = find_inline_image_placeholder(grid, anchored.image.id, expected_line)
+            else {
+                return false;
+            };
+            anchored.point = point;
+            anchored.history_size = history_size;
+            true
+        });
+
+        let mut ix = 0;
+        while ix < self.pending_inline_images.len() {
+            let id = self.pending_inline_images[ix].id;
+            if let Some(point) = find_inline_image_placeholder(grid, id, bottommost_line) {
+                let image = self.pending_inline_images.remove(ix);
+                self.inline_images.push(AnchoredInlineImage {
+                    image,
+                    point,
+                    history_size,
+                });
+                if self.inline_images.len() > MAX_INLINE_IMAGES {
+                    self.inline_images.remove(0);
+                }
+            } else {
+                ix += 1;
+            }
+        }
     }
 
     fn make_content(term: &Term<ZedListener>, last_content: &TerminalContent) -> TerminalContent {
@@ -1333,6 +1434,7 @@ impl Terminal {
             cursor_char: term.grid()[content.cursor.point].c,
             size: last_content.size,
             last_hovered_word: last_content.last_hovered_word.clone(),
+            inline_images: Vec::new(),
         }
     }
 
@@ -1550,7 +1652,10 @@ impl Terminal {
             //Hyperlinks
             if self.selection_phase == SelectionPhase::Ended {
                 let mouse_cell_index = content_index_for_mouse(position, &self.last_content.size);
-                if let Some(link) = self.last_content.cells[mouse_cell_index].hyperlink() {
+                if let Some(link) = self.last_content.cells[mouse_cell_index]
+                    .hyperlink()
+                    .filter(|link| !is_inline_image_uri(link.uri()))
+                {
                     cx.open_url(link.uri());
                 } else if self.secondary_pressed {
                     self.events
@@ -1880,6 +1985,30 @@ pub fn visible_regex_match_iter<'a, T>(
         .take_while(move |rm| rm.start().line <= viewport_end)
 }
 
+/// Finds the cell marked with the placeholder hyperlink of an inline image, searching up from
+/// `from_line`.
+fn find_inline_image_placeholder(grid: &Grid<Cell>, id: u64, from_line: Line) -> Option<AlacPoint> {
+    let uri = inline_image_uri(id);
+    let to_line = grid
+        .topmost_line()
+        .max(Line(from_line.0 - MAX_INLINE_IMAGE_SEARCH_LINES));
+    let mut line = from_line;
+    while line >= to_line {
+        let row = &grid[line];
+        for column in 0..grid.columns() {
+            let column = Column(column);
+            if row[column]
+                .hyperlink()
+                .map_or(false, |link| link.uri() == uri)
+            {
+                return Some(AlacPoint::new(line, column));
+            }
+        }
+        line = Line(line.0 - 1);
+    }
+    None
+}
+
 fn make_selection(range: &RangeInclusive<AlacPoint>) -> Selection {
     let mut selection = Selection::new(SelectionType::Simple, *range.start(), AlacDirection::Left);
     selection.update(*range.end(), AlacDirection::Right);
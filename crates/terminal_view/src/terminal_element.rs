@@ -1,11 +1,12 @@
 use editor::{CursorLayout, HighlightedRange, HighlightedRangeLine};
 use gpui::{
-    div, fill, point, px, relative, size, AnyElement, AvailableSpace, Bounds, ContentMask,
+    div, fill, img, point, px, relative, size, AnyElement, AvailableSpace, Bounds, ContentMask,
     DispatchPhase, Element, ElementId, FocusHandle, Font, FontStyle, FontWeight, GlobalElementId,
     HighlightStyle, Hitbox, Hsla, InputHandler, InteractiveElement, Interactivity, IntoElement,
-    LayoutId, Model, ModelContext, ModifiersChangedEvent, MouseButton, MouseMoveEvent, Pixels,
-    Point, ShapedLine, StatefulInteractiveElement, StrikethroughStyle, Styled, TextRun, TextStyle,
-    UTF16Selection, UnderlineStyle, View, WeakView, WhiteSpace, WindowContext, WindowTextSystem,
+    LayoutId, Model, ModelContext, ModifiersChangedEvent, MouseButton, MouseMoveEvent, ObjectFit,
+    Pixels, Point, ShapedLine, StatefulInteractiveElement, StrikethroughStyle, Styled, TextRun,
+    TextStyle, UTF16Selection, UnderlineStyle, View, WeakView, WhiteSpace, WindowContext,
+    WindowTextSystem,
 };
 use itertools::Itertools;
 use language::CursorShape;
@@ -47,6 +48,7 @@ pub struct LayoutState {
     gutter: Pixels,
     last_hovered_word: Option<HoveredWord>,
     block_below_cursor_element: Option<AnyElement>,
+    inline_image_elements: Vec<AnyElement>,
 }
 
 /// Helper struct for converting data between Alacritty's cursor points, and displayed cursor points.
@@ -843,6 +845,55 @@ impl Element for TerminalElement {
                     None
                 };
 
+                let inline_images = self.terminal.read(cx).last_content.inline_images.clone();
+                let inline_image_elements = inline_images
+                    .into_iter()
+                    .enumerate()
+                    .filter_map(|(ix, inline_image)| {
+                        let line = inline_image.point.line.0 + display_offset as i32;
+                        if line + inline_image.rows as i32 <= 0
+                            || line >= dimensions.num_lines() as i32
+                        {
+                            return None;
+                        }
+                        let max_size = size(
+                            dimensions.width()
+                                - inline_image.point.column.0 as f32 * dimensions.cell_width(),
+                            inline_image.rows as f32 * dimensions.line_height(),
+                        );
+                        let terminal_view = self.terminal_view.clone();
+                        let image = inline_image.image.clone();
+                        let mut element = div()
+                            .id(("terminal-inline-image", ix))
+                            .occlude()
+                            .cursor_pointer()
+                            .tooltip(|cx| Tooltip::text("Open in Image Viewer", cx))
+                            .on_click(move |_, cx| {
+                                terminal_view.update(cx, |terminal_view, cx| {
+                                    terminal_view.open_inline_image(image.clone(), cx)
+                                })
+                            })
+                            .child(
+                                img(inline_image.image)
+                                    .max_w(max_size.width)
+                                    .max_h(max_size.height)
+                                    .object_fit(ObjectFit::ScaleDown),
+                            )
+                            .into_any_element();
+                        let origin = bounds.origin
+                            + point(
+                                gutter
+                                    + inline_image.point.column.0 as f32 * dimensions.cell_width(),
+                                line as f32 * dimensions.line_height(),
+                            )
+                            - point(px(0.), scroll_top);
+                        cx.with_rem_size(rem_size, |cx| {
+                            element.prepaint_as_root(origin, max_size.into(), cx);
+                        });
+                        Some(element)
+                    })
+                    .collect();
+
                 LayoutState {
                     hitbox,
                     cells,
@@ -857,6 +908,7 @@ impl Element for TerminalElement {
                     gutter,
                     last_hovered_word,
                     block_below_cursor_element,
+                    inline_image_elements,
                 }
             })
     }
@@ -895,6 +947,7 @@ impl Element for TerminalElement {
             let cursor = layout.cursor.take();
             let hyperlink_tooltip = layout.hyperlink_tooltip.take();
             let block_below_cursor_element = layout.block_below_cursor_element.take();
+            let inline_image_elements = mem::take(&mut layout.inline_image_elements);
             self.interactivity
                 .paint(global_id, bounds, Some(&layout.hitbox), cx, |_, cx| {
                     cx.handle_input(&self.focus, terminal_input_handler);
@@ -940,6 +993,10 @@ impl Element for TerminalElement {
                         cell.paint(origin, &layout.dimensions, bounds, cx);
                     }
 
+                    for mut element in inline_image_elements {
+                        element.paint(cx);
+                    }
+
                     if self.cursor_visible {
                         if let Some(mut cursor) = cursor {
                             cursor.paint(origin, cx);
@@ -505,6 +505,16 @@ impl TerminalView {
         cx.notify();
     }
 
+    /// Opens an image printed in the terminal in the image viewer, as an untitled image since
+    /// it doesn't exist on disk.
+    pub fn open_inline_image(&mut self, image: Arc<gpui::Image>, cx: &mut ViewContext<Self>) {
+        self.workspace
+            .update(cx, |workspace, cx| {
+                image_viewer::open_untitled_image(workspace, image.bytes.clone(), cx)
+            })
+            .ok();
+    }
+
     fn next_blink_epoch(&mut self) -> usize {
         self.blink_epoch += 1;
         self.blink_epoch
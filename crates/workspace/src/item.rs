@@ -51,6 +51,12 @@ pub struct PreviewTabsSettings {
     pub enable_preview_from_code_navigation: bool,
 }
 
+/// The contents of the tooltip shown when hovering an item's tab.
+pub enum TabTooltipContent {
+    Text(SharedString),
+    Custom(Box<dyn Fn(&mut WindowContext) -> AnyView>),
+}
+
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum ClosePosition {
@@ -188,14 +194,31 @@ pub trait Item: FocusableView + EventEmitter<Self::Event> {
     fn to_item_events(_event: &Self::Event, _f: impl FnMut(ItemEvent)) {}
 
     fn deactivated(&mut self, _: &mut ViewContext<Self>) {}
+    /// Called when the item is removed from a pane, such as when its tab is closed or moved to
+    /// another pane.
+    fn removed_from_pane(&mut self, _: &mut ViewContext<Self>) {}
     fn discarded(&self, _project: Model<Project>, _cx: &mut ViewContext<Self>) {}
     fn workspace_deactivated(&mut self, _: &mut ViewContext<Self>) {}
     fn navigate(&mut self, _: Box<dyn Any>, _: &mut ViewContext<Self>) -> bool {
         false
     }
+    /// Handles something dropped onto the item's content, such as a [`pane::DraggedSelection`]
+    /// or [`gpui::ExternalPaths`].
+    ///
+    /// Returns `true` if the drop was consumed, in which case the pane won't open the dropped
+    /// entries itself.
+    fn handle_drop(&mut self, _dropped: &dyn Any, _cx: &mut ViewContext<Self>) -> bool {
+        false
+    }
     fn tab_tooltip_text(&self, _: &AppContext) -> Option<SharedString> {
         None
     }
+    /// Returns the tooltip shown when hovering the item's tab.
+    ///
+    /// Defaults to a plain text tooltip built from [`Item::tab_tooltip_text`].
+    fn tab_tooltip_content(&self, cx: &AppContext) -> Option<TabTooltipContent> {
+        self.tab_tooltip_text(cx).map(TabTooltipContent::Text)
+    }
     fn tab_description(&self, _: usize, _: &AppContext) -> Option<SharedString> {
         None
     }
@@ -374,6 +397,8 @@ pub trait ItemHandle: 'static + Send {
     ) -> gpui::Subscription;
     fn focus_handle(&self, cx: &WindowContext) -> FocusHandle;
     fn tab_tooltip_text(&self, cx: &AppContext) -> Option<SharedString>;
+    fn tab_tooltip_content(&self, cx: &AppContext) -> Option<TabTooltipContent>;
+    fn handle_drop(&self, dropped: &dyn Any, cx: &mut WindowContext) -> bool;
     fn tab_description(&self, detail: usize, cx: &AppContext) -> Option<SharedString>;
     fn tab_content(&self, params: TabContentParams, cx: &WindowContext) -> AnyElement;
     fn tab_icon(&self, cx: &WindowContext) -> Option<Icon>;
@@ -402,6 +427,7 @@ pub trait ItemHandle: 'static + Send {
         cx: &mut ViewContext<Workspace>,
     );
     fn deactivated(&self, cx: &mut WindowContext);
+    fn removed_from_pane(&self, cx: &mut WindowContext);
     fn discarded(&self, project: Model<Project>, cx: &mut WindowContext);
     fn workspace_deactivated(&self, cx: &mut WindowContext);
     fn navigate(&self, data: Box<dyn Any>, cx: &mut WindowContext) -> bool;
@@ -481,6 +507,14 @@ impl<T: Item> ItemHandle for View<T> {
         self.read(cx).tab_tooltip_text(cx)
     }
 
+    fn tab_tooltip_content(&self, cx: &AppContext) -> Option<TabTooltipContent> {
+        self.read(cx).tab_tooltip_content(cx)
+    }
+
+    fn handle_drop(&self, dropped: &dyn Any, cx: &mut WindowContext) -> bool {
+        self.update(cx, |item, cx| item.handle_drop(dropped, cx))
+    }
+
     fn telemetry_event_text(&self, cx: &WindowContext) -> Option<&'static str> {
         self.read(cx).telemetry_event_text()
     }
@@ -752,6 +786,10 @@ impl<T: Item> ItemHandle for View<T> {
         self.update(cx, |this, cx| this.deactivated(cx));
     }
 
+    fn removed_from_pane(&self, cx: &mut WindowContext) {
+        self.update(cx, |this, cx| this.removed_from_pane(cx));
+    }
+
     fn workspace_deactivated(&self, cx: &mut WindowContext) {
         self.update(cx, |this, cx| this.workspace_deactivated(cx));
     }
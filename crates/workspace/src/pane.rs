@@ -1,7 +1,7 @@
 use crate::{
     item::{
         ActivateOnClose, ClosePosition, Item, ItemHandle, ItemSettings, PreviewTabsSettings,
-        TabContentParams, WeakItemHandle,
+        TabContentParams, TabTooltipContent, WeakItemHandle,
     },
     move_item,
     notifications::NotifyResultExt,
@@ -1500,6 +1500,7 @@ impl Pane {
         self.nav_history.set_mode(NavigationMode::ClosingItem);
         item.deactivated(cx);
         self.nav_history.set_mode(mode);
+        item.removed_from_pane(cx);
 
         if self.is_active_preview_item(item.item_id()) {
             self.set_preview_item_id(None, cx);
@@ -2008,8 +2009,11 @@ impl Pane {
                 this.drag_split_direction = None;
                 this.handle_external_paths_drop(paths, cx)
             }))
-            .when_some(item.tab_tooltip_text(cx), |tab, text| {
-                tab.tooltip(move |cx| Tooltip::text(text.clone(), cx))
+            .when_some(item.tab_tooltip_content(cx), |tab, content| match content {
+                TabTooltipContent::Text(text) => {
+                    tab.tooltip(move |cx| Tooltip::text(text.clone(), cx))
+                }
+                TabTooltipContent::Custom(build_tooltip) => tab.tooltip(build_tooltip),
             })
             .start_slot::<Indicator>(indicator)
             .map(|this| {
@@ -2886,9 +2890,29 @@ impl Render for Pane {
                                 this.handle_tab_drop(dragged_tab, this.active_item_index(), cx)
                             }))
                             .on_drop(cx.listener(move |this, selection: &DraggedSelection, cx| {
+                                // Let the active item consume drops onto its content, unless
+                                // the secondary modifier is held to force opening the entries.
+                                if this.drag_split_direction.is_none()
+                                    && !cx.modifiers().secondary()
+                                {
+                                    if let Some(item) = this.active_item() {
+                                        if item.handle_drop(selection, cx) {
+                                            return;
+                                        }
+                                    }
+                                }
                                 this.handle_dragged_selection_drop(selection, None, cx)
                             }))
-                            .on_drop(cx.listener(move |this, paths, cx| {
+                            .on_drop(cx.listener(move |this, paths: &ExternalPaths, cx| {
+                                if this.drag_split_direction.is_none()
+                                    && !cx.modifiers().secondary()
+                                {
+                                    if let Some(item) = this.active_item() {
+                                        if item.handle_drop(paths, cx) {
+                                            return;
+                                        }
+                                    }
+                                }
                                 this.handle_external_paths_drop(paths, cx)
                             }))
                             .map(|div| {
@@ -226,6 +226,12 @@ impl_actions!(
         ActivatePaneInDirection,
         CloseAllItemsAndPanes,
         CloseInactiveTabsAndPanes,
+        CompareImages,
+        OpenArchiveImages,
+        OpenFolderAsGallery,
+        OpenImages,
+        OpenImagesAsGallery,
+        OpenImagesInSplits,
         OpenTerminal,
         Reload,
         Save,
@@ -291,6 +297,42 @@ pub struct OpenTerminal {
     pub working_directory: PathBuf,
 }
 
+/// Lists the images inside a zip-based archive, to open them without extracting it.
+#[derive(Debug, Default, Clone, Deserialize, PartialEq)]
+pub struct OpenArchiveImages {
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, PartialEq)]
+pub struct OpenFolderAsGallery {
+    pub directory: PathBuf,
+}
+
+/// Opens several images at once, giving each a tab while they load.
+#[derive(Debug, Default, Clone, Deserialize, PartialEq)]
+pub struct OpenImages {
+    pub paths: Vec<PathBuf>,
+}
+
+/// Opens a gallery of the given images, rather than of every image in a folder.
+#[derive(Debug, Default, Clone, Deserialize, PartialEq)]
+pub struct OpenImagesAsGallery {
+    pub paths: Vec<PathBuf>,
+}
+
+/// Opens each of the given images in its own pane, side by side.
+#[derive(Debug, Default, Clone, Deserialize, PartialEq)]
+pub struct OpenImagesInSplits {
+    pub paths: Vec<PathBuf>,
+}
+
+/// Opens a compare view between two images.
+#[derive(Debug, Default, Clone, Deserialize, PartialEq)]
+pub struct CompareImages {
+    pub left: PathBuf,
+    pub right: PathBuf,
+}
+
 #[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct WorkspaceId(i64);
 
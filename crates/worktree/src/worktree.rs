@@ -53,7 +53,7 @@ use std::{
     ffi::OsStr,
     fmt,
     future::Future,
-    mem,
+    io, mem,
     ops::{AddAssign, Deref, DerefMut, Sub},
     path::{Path, PathBuf},
     pin::Pin,
@@ -713,7 +713,23 @@ impl Worktree {
         cx: &ModelContext<Worktree>,
     ) -> Task<Result<LoadedBinaryFile>> {
         match self {
-            Worktree::Local(this) => this.load_binary_file(path, cx),
+            Worktree::Local(this) => this.load_binary_file(path, None, cx),
+            Worktree::Remote(_) => {
+                Task::ready(Err(anyhow!("remote worktrees can't yet load binary files")))
+            }
+        }
+    }
+
+    /// Loads a binary file like [`Self::load_binary_file`], reading it in chunks and calling
+    /// `on_progress` with how many bytes were read after each of them.
+    pub fn load_binary_file_with_progress(
+        &self,
+        path: &Path,
+        on_progress: impl Fn(u64) + Send + 'static,
+        cx: &ModelContext<Worktree>,
+    ) -> Task<Result<LoadedBinaryFile>> {
+        match self {
+            Worktree::Local(this) => this.load_binary_file(path, Some(Box::new(on_progress)), cx),
             Worktree::Remote(_) => {
                 Task::ready(Err(anyhow!("remote worktrees can't yet load binary files")))
             }
@@ -735,6 +751,20 @@ impl Worktree {
         }
     }
 
+    pub fn write_binary_file(
+        &self,
+        path: &Path,
+        content: Vec<u8>,
+        cx: &ModelContext<Worktree>,
+    ) -> Task<Result<Arc<File>>> {
+        match self {
+            Worktree::Local(this) => this.write_binary_file(path, content, cx),
+            Worktree::Remote(_) => {
+                Task::ready(Err(anyhow!("remote worktree can't yet write binary files")))
+            }
+        }
+    }
+
     pub fn create_entry(
         &mut self,
         path: impl Into<Arc<Path>>,
@@ -1310,6 +1340,7 @@ impl LocalWorktree {
     fn load_binary_file(
         &self,
         path: &Path,
+        on_progress: Option<Box<dyn Fn(u64) + Send>>,
         cx: &ModelContext<Worktree>,
     ) -> Task<Result<LoadedBinaryFile>> {
         let path = Arc::from(path);
@@ -1321,7 +1352,12 @@ impl LocalWorktree {
         let worktree = cx.weak_model();
         cx.background_executor().spawn(async move {
             let abs_path = abs_path?;
-            let content = fs.load_bytes(&abs_path).await?;
+            let content = match on_progress {
+                Some(on_progress) => {
+                    read_with_progress(fs.open_sync(&abs_path).await?, on_progress)?
+                }
+                None => fs.load_bytes(&abs_path).await?,
+            };
 
             let worktree = worktree
                 .upgrade()
@@ -1506,17 +1542,51 @@ impl LocalWorktree {
         cx: &ModelContext<Worktree>,
     ) -> Task<Result<Arc<File>>> {
         let path = path.into();
-        let fs = self.fs.clone();
-        let is_private = self.is_path_private(&path);
         let Ok(abs_path) = self.absolutize(&path) else {
             return Task::ready(Err(anyhow!("invalid path {path:?}")));
         };
 
         let write = cx.background_executor().spawn({
-            let fs = fs.clone();
-            let abs_path = abs_path.clone();
+            let fs = self.fs.clone();
             async move { fs.save(&abs_path, &text, line_ending).await }
         });
+        self.file_after_write(path, write, cx)
+    }
+
+    fn write_binary_file(
+        &self,
+        path: impl Into<Arc<Path>>,
+        content: Vec<u8>,
+        cx: &ModelContext<Worktree>,
+    ) -> Task<Result<Arc<File>>> {
+        let path = path.into();
+        let Ok(abs_path) = self.absolutize(&path) else {
+            return Task::ready(Err(anyhow!("invalid path {path:?}")));
+        };
+
+        let write = cx.background_executor().spawn({
+            let fs = self.fs.clone();
+            async move {
+                let content = content.as_slice();
+                futures::pin_mut!(content);
+                fs.create_file_with(&abs_path, content).await
+            }
+        });
+        self.file_after_write(path, write, cx)
+    }
+
+    /// Refreshes the entry of a file once `write` has written it, returning the file.
+    fn file_after_write(
+        &self,
+        path: Arc<Path>,
+        write: Task<Result<()>>,
+        cx: &ModelContext<Worktree>,
+    ) -> Task<Result<Arc<File>>> {
+        let fs = self.fs.clone();
+        let is_private = self.is_path_private(&path);
+        let Ok(abs_path) = self.absolutize(&path) else {
+            return Task::ready(Err(anyhow!("invalid path {path:?}")));
+        };
 
         cx.spawn(move |this, mut cx| async move {
             write.await?;
@@ -5060,6 +5130,26 @@ impl BackgroundScanner {
     }
 }
 
+/// Reads a file to its end in chunks, calling `on_progress` with how many bytes were read after
+/// each of them.
+fn read_with_progress(mut reader: Box<dyn io::Read>, on_progress: impl Fn(u64)) -> Result<Vec<u8>> {
+    const CHUNK_SIZE: usize = 1024 * 1024;
+
+    let mut content = Vec::new();
+    let mut chunk = vec![0; CHUNK_SIZE];
+    loop {
+        let len = match reader.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(len) => len,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error.into()),
+        };
+        content.extend_from_slice(&chunk[..len]);
+        on_progress(content.len() as u64);
+    }
+    Ok(content)
+}
+
 fn swap_to_front(child_paths: &mut Vec<PathBuf>, file: &OsStr) {
     let position = child_paths
         .iter()
@@ -26,6 +26,7 @@ use gpui::{
     PathPromptOptions, PromptLevel, ReadGlobal, Task, TitlebarOptions, View, ViewContext,
     VisualContext, WindowKind, WindowOptions,
 };
+use image_viewer::{assets_panel::AssetsPanel, golden_images_panel::GoldenImagesPanel};
 pub use open_listener::*;
 use outline_panel::OutlinePanel;
 use paths::{local_settings_file_relative_path, local_tasks_file_relative_path};
@@ -242,6 +243,9 @@ pub fn initialize_workspace(
         cx.spawn(|workspace_handle, mut cx| async move {
             let project_panel = ProjectPanel::load(workspace_handle.clone(), cx.clone());
             let outline_panel = OutlinePanel::load(workspace_handle.clone(), cx.clone());
+            let assets_panel = AssetsPanel::load(workspace_handle.clone(), cx.clone());
+            let golden_images_panel =
+                GoldenImagesPanel::load(workspace_handle.clone(), cx.clone());
             let terminal_panel = TerminalPanel::load(workspace_handle.clone(), cx.clone());
             let channels_panel =
                 collab_ui::collab_panel::CollabPanel::load(workspace_handle.clone(), cx.clone());
@@ -255,6 +259,8 @@ pub fn initialize_workspace(
             let (
                 project_panel,
                 outline_panel,
+                assets_panel,
+                golden_images_panel,
                 terminal_panel,
                 channels_panel,
                 chat_panel,
@@ -262,6 +268,8 @@ pub fn initialize_workspace(
             ) = futures::try_join!(
                 project_panel,
                 outline_panel,
+                assets_panel,
+                golden_images_panel,
                 terminal_panel,
                 channels_panel,
                 chat_panel,
@@ -271,6 +279,8 @@ pub fn initialize_workspace(
             workspace_handle.update(&mut cx, |workspace, cx| {
                 workspace.add_panel(project_panel, cx);
                 workspace.add_panel(outline_panel, cx);
+                workspace.add_panel(assets_panel, cx);
+                workspace.add_panel(golden_images_panel, cx);
                 workspace.add_panel(terminal_panel, cx);
                 workspace.add_panel(channels_panel, cx);
                 workspace.add_panel(chat_panel, cx);
@@ -535,6 +545,20 @@ pub fn initialize_workspace(
                     workspace.toggle_panel_focus::<OutlinePanel>(cx);
                 },
             )
+            .register_action(
+                |workspace: &mut Workspace,
+                 _: &image_viewer::assets_panel::ToggleFocus,
+                 cx: &mut ViewContext<Workspace>| {
+                    workspace.toggle_panel_focus::<AssetsPanel>(cx);
+                },
+            )
+            .register_action(
+                |workspace: &mut Workspace,
+                 _: &image_viewer::golden_images_panel::ToggleFocus,
+                 cx: &mut ViewContext<Workspace>| {
+                    workspace.toggle_panel_focus::<GoldenImagesPanel>(cx);
+                },
+            )
             .register_action(
                 |workspace: &mut Workspace,
                  _: &collab_ui::collab_panel::ToggleFocus,
@@ -658,6 +682,10 @@ fn initialize_pane(workspace: &Workspace, pane: &View<Pane>, cx: &mut ViewContex
             let syntax_tree_item =
                 cx.new_view(|_| language_tools::SyntaxTreeToolbarItemView::new());
             toolbar.add_item(syntax_tree_item, cx);
+            let image_viewer_toolbar = cx.new_view(|_| image_viewer::ImageViewerToolbar::new());
+            toolbar.add_item(image_viewer_toolbar, cx);
+            let image_viewer_controls = cx.new_view(|_| image_viewer::ImageViewerControls::new());
+            toolbar.add_item(image_viewer_controls, cx);
         })
     });
 }